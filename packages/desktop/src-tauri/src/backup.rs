@@ -0,0 +1,970 @@
+//! Encrypted Backups
+//!
+//! Snapshots of the active profile's app data (sessions, config, the
+//! embedded vector store and document indexes) plus the Chroma sidecar's
+//! persist dir, uploaded to an S3-compatible bucket (AWS, R2, B2, MinIO).
+//! Archives are encrypted on this machine with AES-256-GCM before upload;
+//! the key lives in `<app data>/backup.key`, is never uploaded, and must be
+//! kept somewhere safe, since backups can't be restored without it.
+//!
+//! Nothing is held in memory whole: the archive is packed and encrypted in
+//! 1 MiB chunks into a temp file, uploaded in one PUT when small and as a
+//! multipart upload otherwise (S3 caps single PUTs at 5 GB), and restores
+//! download to a temp file and unpack while decrypting.
+//!
+//! Objects are named `<prefix>/<profile>/backup-<version>.dlcb`, where the
+//! version is the UTC timestamp of the snapshot. After each upload only the
+//! newest `keep` backups are retained. With `intervalHours` set, a
+//! background job takes a backup once the last one is that old.
+//!
+//! Requests are signed with AWS Signature Version 4 using path-style URLs,
+//! which every S3-compatible service accepts.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::{digest, hmac};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use tauri::AppHandle;
+use thiserror::Error;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tracing::{debug, info, warn};
+
+use crate::chroma::sidecar::default_persist_dir;
+use crate::config::preferences::load_preferences;
+use crate::config::profile::current_profile;
+use crate::session::{get_app_data_dir_cli, SessionError};
+
+pub const KEY_FILE: &str = "backup.key";
+const STATE_FILE: &str = "backup-state.json";
+const OBJECT_PREFIX: &str = "backup-";
+const OBJECT_SUFFIX: &str = ".dlcb";
+const VERSION_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+/// Archive entries under this prefix belong in the Chroma persist dir
+const CHROMA_PREFIX: &str = "chroma/";
+/// Leading bytes of a decrypted archive
+const ARCHIVE_MAGIC: &[u8] = b"DLCARCH1";
+/// Leading bytes of an encrypted backup object, also the start of each
+/// chunk's AEAD associated data
+const OBJECT_MAGIC: &[u8] = b"DLCBAK02";
+/// Plaintext bytes per encrypted chunk
+const CHUNK_SIZE: usize = 1 << 20;
+const TAG_LEN: usize = 16;
+/// Random bytes starting every chunk nonce; the rest is the chunk index
+const NONCE_PREFIX_LEN: usize = NONCE_LEN - 4;
+/// Objects larger than this are uploaded in parts
+const MULTIPART_THRESHOLD: u64 = 64 << 20;
+/// Bytes per multipart part (S3 requires at least 5 MiB, at most 10,000 parts)
+const PART_SIZE: u64 = 16 << 20;
+/// Not backed up: the key itself, machine-local state, other profiles
+const EXCLUDED: &[&str] = &[KEY_FILE, STATE_FILE, crate::sync::SYNC_STATE_FILE, "profiles", "chroma.log"];
+/// Delay before the first scheduled check so startup isn't slowed
+const INITIAL_DELAY: Duration = Duration::from_secs(5 * 60);
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Error, Debug)]
+pub enum BackupError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Storage error ({status}): {body}")]
+    Storage { status: u16, body: String },
+    #[error("Backups are not configured")]
+    NotConfigured,
+    #[error("Backup not found: {0}")]
+    NotFound(String),
+    #[error("Backup could not be decrypted; wrong key or corrupted object")]
+    Decrypt,
+    #[error("Invalid backup archive: {0}")]
+    InvalidArchive(String),
+    #[error("Invalid backup key: {0}")]
+    InvalidKey(String),
+}
+
+impl Serialize for BackupError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+fn default_region() -> String {
+    "us-east-1".to_string()
+}
+
+fn default_keep() -> usize {
+    7
+}
+
+/// `backup` in preferences
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupConfig {
+    /// e.g. `https://s3.eu-west-1.amazonaws.com` or a MinIO URL
+    pub endpoint: String,
+    pub bucket: String,
+    #[serde(default = "default_region")]
+    pub region: String,
+    /// Key prefix inside the bucket
+    #[serde(default)]
+    pub prefix: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Hours between scheduled backups; 0 backs up only on request
+    #[serde(default)]
+    pub interval_hours: u32,
+    /// Backups retained per profile
+    #[serde(default = "default_keep")]
+    pub keep: usize,
+}
+
+impl BackupConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        let url = reqwest::Url::parse(&self.endpoint).map_err(|e| format!("backup.endpoint is not a valid URL: {}", e))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err("backup.endpoint must use http or https".to_string());
+        }
+        if self.bucket.trim().is_empty() || self.access_key_id.trim().is_empty() || self.secret_access_key.is_empty() {
+            return Err("backup.bucket, backup.accessKeyId and backup.secretAccessKey are required".to_string());
+        }
+        if self.keep == 0 {
+            return Err("backup.keep must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Key prefix of this profile's backups, ending in `/`
+    fn profile_prefix(&self) -> String {
+        let prefix = self.prefix.trim_matches('/');
+        if prefix.is_empty() {
+            format!("{}/", current_profile())
+        } else {
+            format!("{}/{}/", prefix, current_profile())
+        }
+    }
+}
+
+/// A backup stored in the bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub version: String,
+    pub key: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupResult {
+    pub backup: BackupInfo,
+    pub files: usize,
+    /// Older backups deleted by the retention policy
+    pub pruned: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupState {
+    last_backup: Option<DateTime<Utc>>,
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex(digest::digest(&digest::SHA256, data).as_ref())
+}
+
+// ============ ARCHIVE ============
+
+/// Paths must stay inside the directory they're restored to
+fn safe_relative(path: &str) -> bool {
+    !path.is_empty() && Path::new(path).components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+fn collect(root: &Path, dir: &Path, prefix: &str, entries: &mut Vec<(String, PathBuf)>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Dot entries are trash, caches and in-flight writes
+        if name.starts_with('.') || name.ends_with(".tmp") || EXCLUDED.contains(&name.as_str()) {
+            continue;
+        }
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect(root, &path, prefix, entries)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let relative = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+            entries.push((format!("{}{}", prefix, relative), path));
+        }
+    }
+    Ok(())
+}
+
+/// Files to back up as (archive path, file)
+fn backup_entries(app_data: &Path, chroma_dir: Option<&Path>) -> std::io::Result<Vec<(String, PathBuf)>> {
+    let mut entries = Vec::new();
+    collect(app_data, app_data, "", &mut entries)?;
+    // `chroma/` in the archive is reserved for the sidecar's persist dir
+    entries.retain(|(name, _)| !name.starts_with(CHROMA_PREFIX));
+    if let Some(chroma) = chroma_dir.filter(|d| d.is_dir()) {
+        collect(chroma, chroma, CHROMA_PREFIX, &mut entries)?;
+    }
+    Ok(entries)
+}
+
+/// Fill `buf` from `reader`; short only at the end of the input
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+fn write_entry(out: &mut impl Write, path: &str, len: u64, data: &mut impl Read) -> io::Result<()> {
+    out.write_all(&(path.len() as u32).to_le_bytes())?;
+    out.write_all(path.as_bytes())?;
+    out.write_all(&len.to_le_bytes())?;
+    if io::copy(&mut data.take(len), out)? != len {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, format!("{} shrank while being backed up", path)));
+    }
+    Ok(())
+}
+
+/// Pack files into `out`: the magic, then per file its path and contents,
+/// each length-prefixed (little-endian u32 path length, u64 data length).
+/// Returns the number of files packed.
+pub fn write_archive(entries: &[(String, PathBuf)], out: &mut impl Write) -> io::Result<usize> {
+    out.write_all(ARCHIVE_MAGIC)?;
+    let mut files = 0;
+    for (name, path) in entries {
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            // Deleted since the directory walk
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        let len = file.metadata()?.len();
+        write_entry(out, name, len, &mut file)?;
+        files += 1;
+    }
+    Ok(files)
+}
+
+/// Unpack an archive, writing each file to `destination(path)`. Returns the
+/// number of files.
+fn read_archive(archive: &mut impl Read, destination: impl Fn(&str) -> PathBuf) -> Result<usize, BackupError> {
+    let invalid = |msg: &str| BackupError::InvalidArchive(msg.to_string());
+    let mut magic = vec![0u8; ARCHIVE_MAGIC.len()];
+    if read_full(archive, &mut magic)? != magic.len() || magic != ARCHIVE_MAGIC {
+        return Err(invalid("unknown format"));
+    }
+    let mut files = 0;
+    loop {
+        let mut len = [0u8; 4];
+        match read_full(archive, &mut len)? {
+            0 => return Ok(files),
+            4 => {}
+            _ => return Err(invalid("truncated")),
+        }
+        let mut path = vec![0u8; u32::from_le_bytes(len) as usize];
+        archive.read_exact(&mut path).map_err(|_| invalid("truncated"))?;
+        let path = String::from_utf8(path).map_err(|_| invalid("path is not UTF-8"))?;
+        if !safe_relative(&path) {
+            return Err(BackupError::InvalidArchive(format!("unsafe path {}", path)));
+        }
+        let mut data_len = [0u8; 8];
+        archive.read_exact(&mut data_len).map_err(|_| invalid("truncated"))?;
+        let data_len = u64::from_le_bytes(data_len);
+
+        let target = destination(&path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if io::copy(&mut archive.take(data_len), &mut File::create(&target)?)? != data_len {
+            return Err(invalid("truncated"));
+        }
+        files += 1;
+    }
+}
+
+// ============ ENCRYPTION ============
+
+fn aead_key(key: &[u8]) -> Result<LessSafeKey, BackupError> {
+    UnboundKey::new(&AES_256_GCM, key)
+        .map(LessSafeKey::new)
+        .map_err(|_| BackupError::InvalidKey("expected 32 bytes".to_string()))
+}
+
+fn chunk_nonce(prefix: &[u8; NONCE_PREFIX_LEN], index: u32) -> Nonce {
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LEN..].copy_from_slice(&index.to_be_bytes());
+    Nonce::assume_unique_for_key(nonce)
+}
+
+/// The magic and whether this is the last chunk, so chunks can't be
+/// dropped from the end unnoticed
+fn chunk_aad(last: bool) -> Aad<Vec<u8>> {
+    Aad::from([OBJECT_MAGIC, &[last as u8]].concat())
+}
+
+/// Writes an encrypted object: the magic, a random nonce prefix, then the
+/// plaintext sealed in `CHUNK_SIZE` chunks, each nonce ending in the chunk's
+/// index. The last chunk is always shorter than `CHUNK_SIZE`, possibly
+/// empty, which is how readers find it.
+pub struct Encryptor<W: Write> {
+    key: LessSafeKey,
+    prefix: [u8; NONCE_PREFIX_LEN],
+    index: u32,
+    buf: Vec<u8>,
+    out: W,
+}
+
+impl<W: Write> Encryptor<W> {
+    pub fn new(key: &[u8], mut out: W) -> Result<Self, BackupError> {
+        let key = aead_key(key)?;
+        let mut prefix = [0u8; NONCE_PREFIX_LEN];
+        SystemRandom::new().fill(&mut prefix).map_err(|_| BackupError::InvalidKey("no randomness".to_string()))?;
+        out.write_all(OBJECT_MAGIC)?;
+        out.write_all(&prefix)?;
+        Ok(Self { key, prefix, index: 0, buf: Vec::with_capacity(CHUNK_SIZE + TAG_LEN), out })
+    }
+
+    fn seal(&mut self, last: bool) -> io::Result<()> {
+        self.key
+            .seal_in_place_append_tag(chunk_nonce(&self.prefix, self.index), chunk_aad(last), &mut self.buf)
+            .map_err(|_| io::Error::other("encryption failed"))?;
+        self.out.write_all(&self.buf)?;
+        self.buf.clear();
+        self.index = self.index.checked_add(1).ok_or_else(|| io::Error::other("backup too large"))?;
+        Ok(())
+    }
+
+    /// Seal the last chunk and hand back the writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.seal(true)?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+impl<W: Write> Write for Encryptor<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let n = data.len().min(CHUNK_SIZE - self.buf.len());
+        self.buf.extend_from_slice(&data[..n]);
+        if self.buf.len() == CHUNK_SIZE {
+            self.seal(false)?;
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Reads the plaintext of an object written by `Encryptor`
+pub struct Decryptor<R: Read> {
+    key: LessSafeKey,
+    prefix: [u8; NONCE_PREFIX_LEN],
+    index: u32,
+    input: R,
+    /// The current chunk, decrypted in place
+    buf: Vec<u8>,
+    pos: usize,
+    len: usize,
+    last: bool,
+}
+
+impl<R: Read> Decryptor<R> {
+    /// Fails with `Decrypt` unless the first chunk opens, so a wrong key is
+    /// caught before anything is restored
+    pub fn new(key: &[u8], mut input: R) -> Result<Self, BackupError> {
+        let mut header = vec![0u8; OBJECT_MAGIC.len() + NONCE_PREFIX_LEN];
+        if read_full(&mut input, &mut header)? != header.len() || !header.starts_with(OBJECT_MAGIC) {
+            return Err(BackupError::Decrypt);
+        }
+        let prefix = header[OBJECT_MAGIC.len()..].try_into().map_err(|_| BackupError::Decrypt)?;
+        let mut decryptor = Self {
+            key: aead_key(key)?,
+            prefix,
+            index: 0,
+            input,
+            buf: vec![0u8; CHUNK_SIZE + TAG_LEN],
+            pos: 0,
+            len: 0,
+            last: false,
+        };
+        decryptor.next_chunk().map_err(|_| BackupError::Decrypt)?;
+        Ok(decryptor)
+    }
+
+    fn next_chunk(&mut self) -> io::Result<()> {
+        let n = read_full(&mut self.input, &mut self.buf)?;
+        let last = n < self.buf.len();
+        let plaintext = self
+            .key
+            .open_in_place(chunk_nonce(&self.prefix, self.index), chunk_aad(last), &mut self.buf[..n])
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, BackupError::Decrypt.to_string()))?;
+        self.len = plaintext.len();
+        self.pos = 0;
+        self.last = last;
+        self.index = self.index.checked_add(1).ok_or_else(|| io::Error::other("backup too large"))?;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for Decryptor<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.len {
+            if self.last {
+                return Ok(0);
+            }
+            self.next_chunk()?;
+        }
+        let n = out.len().min(self.len - self.pos);
+        out[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// The backup key; with `create`, one is generated (hex, owner-only) on
+/// first use. Restores never create one, since it couldn't decrypt anything.
+fn load_key(app_data: &Path, create: bool) -> Result<Vec<u8>, BackupError> {
+    let path = app_data.join(KEY_FILE);
+    if let Ok(content) = fs::read_to_string(&path) {
+        let content = content.trim();
+        if content.len() != 64 || !content.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(BackupError::InvalidKey(format!("{} must hold 64 hex characters", path.display())));
+        }
+        return (0..64)
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&content[i..i + 2], 16).map_err(|e| BackupError::InvalidKey(e.to_string())))
+            .collect();
+    }
+    if !create {
+        return Err(BackupError::InvalidKey(format!("no key at {}; copy the key the backups were made with there", path.display())));
+    }
+    let mut key = vec![0u8; 32];
+    SystemRandom::new().fill(&mut key).map_err(|_| BackupError::InvalidKey("no randomness".to_string()))?;
+    fs::write(&path, hex(&key))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+    }
+    info!(path = %path.display(), "Created backup key; keep a copy, backups can't be restored without it");
+    Ok(key)
+}
+
+// ============ S3 ============
+
+/// RFC 3986 encoding as SigV4 expects; `/` kept in paths only
+fn uri_encode(value: &str, keep_slash: bool) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            b'/' if keep_slash => "/".to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), data.as_bytes()).as_ref().to_vec()
+}
+
+/// SigV4 signing key for a date (`YYYYMMDD`), region and service
+pub fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let k_date = hmac_sha256(format!("AWS4{}", secret).as_bytes(), date);
+    let k_region = hmac_sha256(&k_date, region);
+    let k_service = hmac_sha256(&k_region, service);
+    hmac_sha256(&k_service, "aws4_request")
+}
+
+struct SignedRequest {
+    url: String,
+    headers: Vec<(&'static str, String)>,
+}
+
+/// Sign a path-style request for `key` (empty for the bucket itself) with
+/// already-encoded, sorted `query` pairs
+fn sign(config: &BackupConfig, method: &str, key: &str, query: &[(&str, String)], payload: &[u8], now: DateTime<Utc>) -> Result<SignedRequest, BackupError> {
+    let endpoint = reqwest::Url::parse(config.endpoint.trim_end_matches('/'))
+        .map_err(|e| BackupError::Storage { status: 0, body: e.to_string() })?;
+    let host = match endpoint.port() {
+        Some(port) => format!("{}:{}", endpoint.host_str().unwrap_or_default(), port),
+        None => endpoint.host_str().unwrap_or_default().to_string(),
+    };
+    let path = uri_encode(&format!("/{}/{}", config.bucket, key), true);
+    let canonical_query = query
+        .iter()
+        .map(|(k, v)| format!("{}={}", uri_encode(k, false), uri_encode(v, false)))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let payload_hash = sha256_hex(payload);
+    let canonical_request = format!(
+        "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+        method, path, canonical_query, host, payload_hash, amz_date, payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, config.region);
+    let string_to_sign = format!("AWS4-HMAC-SHA256\n{}\n{}\n{}", amz_date, scope, sha256_hex(canonical_request.as_bytes()));
+    let signature = hex(&hmac_sha256(&signing_key(&config.secret_access_key, &date, &config.region, "s3"), &string_to_sign));
+
+    let mut url = format!("{}://{}{}", endpoint.scheme(), host, path);
+    if !canonical_query.is_empty() {
+        url = format!("{}?{}", url, canonical_query);
+    }
+    Ok(SignedRequest {
+        url,
+        headers: vec![
+            ("x-amz-content-sha256", payload_hash),
+            ("x-amz-date", amz_date),
+            (
+                "authorization",
+                format!(
+                    "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+                    config.access_key_id, scope, signature
+                ),
+            ),
+        ],
+    })
+}
+
+/// Send a signed request, failing on error statuses. Requests with a
+/// `streamed` response body may run as long as data keeps arriving.
+async fn request(
+    config: &BackupConfig,
+    method: reqwest::Method,
+    key: &str,
+    query: &[(&str, String)],
+    body: Vec<u8>,
+    streamed: bool,
+) -> Result<reqwest::Response, BackupError> {
+    let signed = sign(config, method.as_str(), key, query, &body, Utc::now())?;
+    let client = if streamed {
+        reqwest::Client::builder().read_timeout(REQUEST_TIMEOUT).build()?
+    } else {
+        reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build()?
+    };
+    let mut request = client.request(method, &signed.url).body(body);
+    for (name, value) in signed.headers {
+        request = request.header(name, value);
+    }
+    let response = request.send().await?;
+    let status = response.status();
+    if status.as_u16() == 404 {
+        return Err(BackupError::NotFound(key.to_string()));
+    }
+    if !status.is_success() {
+        let bytes = response.bytes().await?;
+        return Err(BackupError::Storage { status: status.as_u16(), body: String::from_utf8_lossy(&bytes).chars().take(500).collect() });
+    }
+    Ok(response)
+}
+
+async fn send(config: &BackupConfig, method: reqwest::Method, key: &str, query: &[(&str, String)], body: Vec<u8>) -> Result<Vec<u8>, BackupError> {
+    Ok(request(config, method, key, query, body, false).await?.bytes().await?.to_vec())
+}
+
+/// Text of every `<tag>` element in an S3 XML response
+fn xml_values<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let (open, close) = (format!("<{}>", tag), format!("</{}>", tag));
+    xml.split(open.as_str()).skip(1).filter_map(|part| part.split(close.as_str()).next()).collect()
+}
+
+fn version_of(key: &str) -> Option<(String, DateTime<Utc>)> {
+    let name = key.rsplit('/').next()?;
+    let version = name.strip_prefix(OBJECT_PREFIX)?.strip_suffix(OBJECT_SUFFIX)?;
+    let created = NaiveDateTime::parse_from_str(version, VERSION_FORMAT).ok()?.and_utc();
+    Some((version.to_string(), created))
+}
+
+/// This profile's backups, newest first
+pub async fn list_backups_in(config: &BackupConfig) -> Result<Vec<BackupInfo>, BackupError> {
+    let prefix = config.profile_prefix();
+    let mut backups = Vec::new();
+    let mut token: Option<String> = None;
+    loop {
+        let mut query = Vec::new();
+        if let Some(token) = &token {
+            query.push(("continuation-token", token.clone()));
+        }
+        query.push(("list-type", "2".to_string()));
+        query.push(("prefix", prefix.clone()));
+        let body = send(config, reqwest::Method::GET, "", &query, Vec::new()).await?;
+        let xml = String::from_utf8_lossy(&body);
+        for contents in xml_values(&xml, "Contents") {
+            let Some(key) = xml_values(contents, "Key").first().map(|k| k.to_string()) else { continue };
+            if let Some((version, created_at)) = version_of(&key) {
+                let size = xml_values(contents, "Size").first().and_then(|s| s.parse().ok()).unwrap_or(0);
+                backups.push(BackupInfo { version, key, created_at, size });
+            }
+        }
+        token = xml_values(&xml, "NextContinuationToken").first().map(|t| t.to_string());
+        if token.is_none() || xml_values(&xml, "IsTruncated").first() != Some(&"true") {
+            break;
+        }
+    }
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+    Ok(backups)
+}
+
+/// Backups beyond the newest `keep` (input newest first)
+pub fn expired(backups: &[BackupInfo], keep: usize) -> Vec<&BackupInfo> {
+    backups.iter().skip(keep.max(1)).collect()
+}
+
+/// Upload the file at `path` as `key`: in one PUT up to
+/// `MULTIPART_THRESHOLD`, otherwise part by part
+async fn upload(config: &BackupConfig, key: &str, path: &Path, size: u64) -> Result<(), BackupError> {
+    if size <= MULTIPART_THRESHOLD {
+        send(config, reqwest::Method::PUT, key, &[], tokio::fs::read(path).await?).await?;
+        return Ok(());
+    }
+    let body = send(config, reqwest::Method::POST, key, &[("uploads", String::new())], Vec::new()).await?;
+    let upload_id = xml_values(&String::from_utf8_lossy(&body), "UploadId")
+        .first()
+        .map(|id| id.to_string())
+        .ok_or_else(|| BackupError::Storage { status: 200, body: "no UploadId in response".to_string() })?;
+    let uploaded = upload_parts(config, key, path, &upload_id).await;
+    if uploaded.is_err() {
+        // Abandoned parts are stored (and billed) until aborted
+        if let Err(e) = send(config, reqwest::Method::DELETE, key, &[("uploadId", upload_id)], Vec::new()).await {
+            warn!(key = %key, error = %e, "Failed to abort multipart upload");
+        }
+    }
+    uploaded
+}
+
+async fn upload_parts(config: &BackupConfig, key: &str, path: &Path, upload_id: &str) -> Result<(), BackupError> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut etags = Vec::new();
+    loop {
+        let mut part = Vec::with_capacity(PART_SIZE as usize);
+        (&mut file).take(PART_SIZE).read_to_end(&mut part).await?;
+        if part.is_empty() {
+            break;
+        }
+        let query = [("partNumber", (etags.len() + 1).to_string()), ("uploadId", upload_id.to_string())];
+        let response = request(config, reqwest::Method::PUT, key, &query, part, false).await?;
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| BackupError::Storage { status: 200, body: "no ETag for uploaded part".to_string() })?;
+        etags.push(etag.to_string());
+        debug!(key = %key, part = etags.len(), "Uploaded backup part");
+    }
+    let parts: String = etags
+        .iter()
+        .enumerate()
+        .map(|(i, etag)| format!("<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>", i + 1, etag))
+        .collect();
+    let complete = format!("<CompleteMultipartUpload>{}</CompleteMultipartUpload>", parts);
+    let body = send(config, reqwest::Method::POST, key, &[("uploadId", upload_id.to_string())], complete.into_bytes()).await?;
+    // Completion can fail after a 200, with the error in the body
+    let body = String::from_utf8_lossy(&body);
+    if body.contains("<Error>") {
+        return Err(BackupError::Storage { status: 200, body: body.chars().take(500).collect() });
+    }
+    Ok(())
+}
+
+/// Stream object `key` into the file at `path`
+async fn download(config: &BackupConfig, key: &str, path: &Path) -> Result<(), BackupError> {
+    let mut response = request(config, reqwest::Method::GET, key, &[], Vec::new(), true).await?;
+    let mut file = tokio::fs::File::create(path).await?;
+    while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk).await?;
+    }
+    file.flush().await?;
+    Ok(())
+}
+
+// ============ BACKUP / RESTORE ============
+
+fn config() -> Result<BackupConfig, BackupError> {
+    load_preferences().backup.ok_or(BackupError::NotConfigured)
+}
+
+fn save_state(app_data: &Path, state: &BackupState) -> Result<(), BackupError> {
+    fs::write(app_data.join(STATE_FILE), serde_json::to_string_pretty(state)?)?;
+    Ok(())
+}
+
+/// Snapshot, encrypt and upload, then apply the retention policy
+pub async fn backup() -> Result<BackupResult, BackupError> {
+    let config = config()?;
+    let app_data = get_app_data_dir_cli()?;
+    let now = Utc::now();
+    let version = now.format(VERSION_FORMAT).to_string();
+    let key = format!("{}{}{}{}", config.profile_prefix(), OBJECT_PREFIX, version, OBJECT_SUFFIX);
+
+    // Dot files in app data are never backed up
+    let archive = app_data.join(format!(".backup-{}.tmp", version));
+    let uploaded = async {
+        let files = {
+            let (app_data, archive) = (app_data.clone(), archive.clone());
+            tauri::async_runtime::spawn_blocking(move || -> Result<usize, BackupError> {
+                let encryption_key = load_key(&app_data, true)?;
+                let entries = backup_entries(&app_data, Some(&default_persist_dir()))?;
+                let mut encryptor = Encryptor::new(&encryption_key, File::create(&archive)?)?;
+                let files = write_archive(&entries, &mut encryptor)?;
+                encryptor.finish()?;
+                Ok(files)
+            })
+            .await
+            .map_err(|e| BackupError::Io(std::io::Error::other(e.to_string())))??
+        };
+        let size = fs::metadata(&archive)?.len();
+        upload(&config, &key, &archive, size).await?;
+        Ok::<_, BackupError>((files, size))
+    }
+    .await;
+    fs::remove_file(&archive).ok();
+    let (files, size) = uploaded?;
+    save_state(&app_data, &BackupState { last_backup: Some(now) })?;
+    info!(version = %version, files, bytes = size, "Uploaded backup");
+
+    let mut pruned = Vec::new();
+    match list_backups_in(&config).await {
+        Ok(backups) => {
+            for old in expired(&backups, config.keep) {
+                match send(&config, reqwest::Method::DELETE, &old.key, &[], Vec::new()).await {
+                    Ok(_) => pruned.push(old.version.clone()),
+                    Err(e) => warn!(version = %old.version, error = %e, "Failed to prune backup"),
+                }
+            }
+        }
+        Err(e) => warn!(error = %e, "Failed to list backups for retention"),
+    }
+
+    Ok(BackupResult { backup: BackupInfo { version, key, created_at: now, size }, files, pruned })
+}
+
+/// Put `staged/<name>` in place of `target/<name>` for each staged entry,
+/// moving what was there into `aside`
+fn swap_in(staged: &Path, target: &Path, aside: &Path) -> std::io::Result<()> {
+    for entry in fs::read_dir(staged)? {
+        let entry = entry?;
+        let destination = target.join(entry.file_name());
+        if destination.exists() {
+            fs::create_dir_all(aside)?;
+            fs::rename(&destination, aside.join(entry.file_name()))?;
+        }
+        fs::rename(entry.path(), &destination)?;
+    }
+    Ok(())
+}
+
+/// Restore an archive over `app_data` and `chroma_dir`. Replaced files and
+/// directories are kept under `<app data>/.pre-restore-<stamp>`. Returns
+/// that directory and the number of files restored.
+pub fn restore_archive(mut archive: impl Read, app_data: &Path, chroma_dir: &Path) -> Result<(PathBuf, usize), BackupError> {
+    let stamp = Utc::now().format(VERSION_FORMAT);
+    let staging = app_data.join(format!(".restore-{}", stamp));
+    let aside = app_data.join(format!(".pre-restore-{}", stamp));
+    let (staged_data, staged_chroma) = (staging.join("data"), staging.join("chroma"));
+    fs::create_dir_all(&staged_data)?;
+
+    let unpacked = read_archive(&mut archive, |name| match name.strip_prefix(CHROMA_PREFIX) {
+        Some(rest) => staged_chroma.join(rest),
+        None => staged_data.join(name),
+    });
+    let files = match unpacked {
+        Ok(files) => files,
+        Err(e) => {
+            // Nothing has been replaced yet
+            fs::remove_dir_all(&staging).ok();
+            return Err(e);
+        }
+    };
+
+    swap_in(&staged_data, app_data, &aside)?;
+    if staged_chroma.is_dir() {
+        if chroma_dir.exists() {
+            fs::create_dir_all(&aside)?;
+            fs::rename(chroma_dir, aside.join(CHROMA_PREFIX.trim_end_matches('/')))?;
+        }
+        if let Some(parent) = chroma_dir.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(&staged_chroma, chroma_dir)?;
+    }
+    fs::remove_dir_all(&staging).ok();
+    Ok((aside, files))
+}
+
+/// Download, decrypt and restore backup `version`
+pub async fn restore(version: &str) -> Result<PathBuf, BackupError> {
+    let config = config()?;
+    let key = format!("{}{}{}{}", config.profile_prefix(), OBJECT_PREFIX, version, OBJECT_SUFFIX);
+    if version_of(&key).is_none() {
+        return Err(BackupError::NotFound(version.to_string()));
+    }
+    let app_data = get_app_data_dir_cli()?;
+    let object = app_data.join(format!(".download-{}.tmp", version));
+    let restored = async {
+        download(&config, &key, &object).await?;
+        let (app_data, object) = (app_data.clone(), object.clone());
+        tauri::async_runtime::spawn_blocking(move || {
+            let encryption_key = load_key(&app_data, false)?;
+            let archive = Decryptor::new(&encryption_key, BufReader::new(File::open(&object)?))?;
+            let (aside, files) = restore_archive(archive, &app_data, &default_persist_dir())?;
+            info!(files, previous = %aside.display(), "Restored backup");
+            Ok(aside)
+        })
+        .await
+        .map_err(|e| BackupError::Io(std::io::Error::other(e.to_string())))?
+    }
+    .await;
+    fs::remove_file(&object).ok();
+    restored
+}
+
+/// Spawn the scheduled backup job; it does nothing until `intervalHours` is set
+pub fn start_backup_scheduler() {
+    tauri::async_runtime::spawn(async {
+        tokio::time::sleep(INITIAL_DELAY).await;
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let Some(config) = load_preferences().backup.filter(|c| c.interval_hours > 0) else { continue };
+            let last = get_app_data_dir_cli()
+                .ok()
+                .and_then(|dir| fs::read_to_string(dir.join(STATE_FILE)).ok())
+                .and_then(|c| serde_json::from_str::<BackupState>(&c).ok())
+                .and_then(|s| s.last_backup);
+            if last.is_some_and(|t| Utc::now() - t < chrono::Duration::hours(config.interval_hours as i64)) {
+                continue;
+            }
+            debug!("Running scheduled backup");
+            if let Err(e) = backup().await {
+                warn!(error = %e, "Scheduled backup failed");
+            }
+        }
+    });
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub async fn backup_now() -> Result<BackupResult, BackupError> {
+    backup().await
+}
+
+#[tauri::command]
+pub async fn list_backups() -> Result<Vec<BackupInfo>, BackupError> {
+    list_backups_in(&config()?).await
+}
+
+/// Restore a backup over this profile's data and restart, so every store
+/// reopens on the restored files
+#[tauri::command]
+pub async fn restore_backup(app: AppHandle, version: String) -> Result<(), BackupError> {
+    restore(&version).await?;
+    app.restart()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sigv4_signing_key() {
+        // Example from the AWS Signature Version 4 documentation
+        let key = signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam");
+        assert_eq!(hex(&key), "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d");
+        assert_eq!(uri_encode("/b/a b+c", true), "/b/a%20b%2Bc");
+        assert_eq!(uri_encode("p/q", false), "p%2Fq");
+    }
+
+    #[test]
+    fn test_backup_round_trip() {
+        let base = std::env::temp_dir().join(format!("dialectic_backup_{}", ulid::Ulid::new()));
+        let (app_data, chroma) = (base.join("data"), base.join("chroma"));
+        fs::create_dir_all(app_data.join("sessions/sess_a")).unwrap();
+        fs::create_dir_all(app_data.join(".trash/sess_b")).unwrap();
+        fs::create_dir_all(app_data.join("profiles/work")).unwrap();
+        fs::create_dir_all(&chroma).unwrap();
+        fs::write(app_data.join("sessions/sess_a/session.json"), "{\"v\":1}").unwrap();
+        fs::write(app_data.join("profiles/work/x"), "other profile").unwrap();
+        fs::write(app_data.join(KEY_FILE), "secret").unwrap();
+        fs::write(chroma.join("chroma.sqlite3"), "vectors").unwrap();
+
+        let mut names: Vec<String> = backup_entries(&app_data, Some(&chroma)).unwrap().into_iter().map(|(n, _)| n).collect();
+        names.sort();
+        assert_eq!(names, vec!["chroma/chroma.sqlite3", "sessions/sess_a/session.json"]);
+
+        // An archive spanning several chunks, packed from files
+        let source = base.join("source");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("session.json"), "{\"v\":2}").unwrap();
+        let large: Vec<u8> = (0..CHUNK_SIZE * 2 + 17).map(|i| (i % 251) as u8).collect();
+        fs::write(source.join("large.bin"), &large).unwrap();
+        let entries = vec![
+            ("sessions/sess_a/session.json".to_string(), source.join("session.json")),
+            ("chroma/chroma.sqlite3".to_string(), source.join("large.bin")),
+            ("sessions/gone.json".to_string(), source.join("gone.json")),
+        ];
+        let key = [7u8; 32];
+        let mut encryptor = Encryptor::new(&key, Vec::new()).unwrap();
+        assert_eq!(write_archive(&entries, &mut encryptor).unwrap(), 2);
+        let object = encryptor.finish().unwrap();
+        assert!(matches!(Decryptor::new(&[8u8; 32], &object[..]), Err(BackupError::Decrypt)));
+        // Dropping the last chunk is detected
+        let truncated = &object[..OBJECT_MAGIC.len() + NONCE_PREFIX_LEN + 2 * (CHUNK_SIZE + TAG_LEN)];
+        let mut plaintext = Vec::new();
+        assert!(Decryptor::new(&key, truncated).unwrap().read_to_end(&mut plaintext).is_err());
+
+        let mut unsafe_archive = Vec::new();
+        unsafe_archive.extend_from_slice(ARCHIVE_MAGIC);
+        write_entry(&mut unsafe_archive, "../escape", 0, &mut io::empty()).unwrap();
+        assert!(restore_archive(&unsafe_archive[..], &app_data, &chroma).is_err());
+
+        let archive = Decryptor::new(&key, &object[..]).unwrap();
+        let (aside, files) = restore_archive(archive, &app_data, &chroma).unwrap();
+        assert_eq!(files, 2);
+        assert_eq!(fs::read_to_string(app_data.join("sessions/sess_a/session.json")).unwrap(), "{\"v\":2}");
+        assert_eq!(fs::read(chroma.join("chroma.sqlite3")).unwrap(), large);
+        assert_eq!(fs::read_to_string(aside.join("sessions/sess_a/session.json")).unwrap(), "{\"v\":1}");
+        assert_eq!(fs::read_to_string(aside.join("chroma/chroma.sqlite3")).unwrap(), "vectors");
+        // Untouched by the restore
+        assert!(app_data.join(KEY_FILE).exists());
+
+        let backup = |v: &str| BackupInfo { version: v.to_string(), key: v.to_string(), created_at: Utc::now(), size: 0 };
+        let backups = vec![backup("3"), backup("2"), backup("1")];
+        assert_eq!(expired(&backups, 2).iter().map(|b| b.version.as_str()).collect::<Vec<_>>(), vec!["1"]);
+        assert_eq!(version_of("p/default/backup-20261015T093000Z.dlcb").map(|(v, _)| v).as_deref(), Some("20261015T093000Z"));
+
+        fs::remove_dir_all(&base).ok();
+    }
+}
@@ -12,21 +12,45 @@ const ESTIMATED_SESSION_SUMMARY_SAVINGS: u32 = 500;
 const ESTIMATED_ARCHIVE_SAVINGS_PER_SESSION: u32 = 300;
 /// Target budget percentage after compression (below auto_compress threshold)
 const COMPRESSION_TARGET_PCT: f64 = 0.65;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use chrono::Utc;
 use dialectic_lib::{
     // Session
-    SessionStatus, load_session_cli, list_sessions_cli, save_session_cli,
+    SessionStatus, get_app_data_dir_cli, load_session_cli, list_sessions_cli, save_session_cli, get_session_dir_cli,
+    repair_session, claim_source, export_decision_record, DecisionRecordFormat,
+    ScratchpadSection, session::scratchpad, session::statusline, session::ingest::ingest_turn, session::lock::update_session_file, session::tags::retain_tagged,
+    views::list_sessions_filtered,
+    // Distill
+    distill::distill_session,
+    // Compaction
+    context::compaction::compact_context,
+    // Git
+    git::git_context,
+    // MCP
+    mcp,
+    // Headless runs
+    SessionMode, headless::{run_headless, RunOptions, DEFAULT_TIMEOUT},
+    // Logs
+    logging::read_recent_logs,
+    // Audit
+    AuditActor, read_audit_log, set_audit_actor,
+    // Timeline
+    session::timeline::session_timeline,
     // Context
-    BudgetStatus, ThresholdStatus, WORKING_BUDGET,
+    BudgetStatus, ThresholdStatus, FitCheck, WORKING_BUDGET,
     check_compression_triggers, CompressionTrigger,
     // Tokens
     count_tokens,
+    // Search
+    unified_search,
+    // Doctor
+    doctor,
     // Obsidian
     configure_vault, index_vault, query_notes, get_note_content,
     // CDG
     EdgeType, ResolutionStatus, CdgEdge, CdgSnapshot,
     compute_strata, compute_metrics, find_orphans, compute_pass_diff,
+    score_evidence, apply_scores,
 };
 
 #[derive(Parser)]
@@ -64,6 +88,98 @@ enum Commands {
         #[command(subcommand)]
         action: CdgAction,
     },
+    /// Session scratchpad commands (shared with the app)
+    Scratchpad {
+        #[command(subcommand)]
+        action: ScratchpadAction,
+    },
+    /// Write memo-final.md, spine.yaml and thesis-history.md for a session
+    Distill {
+        /// Session ID (without sess_ prefix)
+        session_id: String,
+        /// Have the configured CLI tool write the memo's executive summary
+        #[arg(long)]
+        summarize: bool,
+    },
+    /// Run a new session headlessly: create it, run the CLI tool on a prompt and extract claims
+    Run {
+        /// Session title
+        #[arg(long)]
+        title: String,
+        /// File holding the prompt sent to the CLI tool
+        #[arg(long)]
+        prompt_file: String,
+        /// Working directory (defaults to the session's own directory)
+        #[arg(long)]
+        working_dir: Option<String>,
+        /// Session mode: idea or decision
+        #[arg(long)]
+        mode: Option<String>,
+        /// Seconds before the run is killed (default: 1800)
+        #[arg(long)]
+        timeout: Option<u64>,
+    },
+    /// Serve Dialectic tools over MCP (stdio) for Claude Code
+    Mcp,
+    /// Serve the local REST API (requires the rest-api feature)
+    #[cfg(feature = "rest-api")]
+    Serve {
+        /// Port on 127.0.0.1
+        #[arg(long, default_value = "7777")]
+        port: u16,
+        /// Bearer token clients must send (default: $DIALECTIC_API_TOKEN, else generated)
+        #[arg(long)]
+        token: Option<String>,
+    },
+    /// Application log commands
+    Logs {
+        #[command(subcommand)]
+        action: LogsAction,
+    },
+    /// Search documents, vault, web sources, memories and other sessions at once
+    Search {
+        /// Session ID (without sess_ prefix)
+        session_id: String,
+        /// Search query
+        query: String,
+        /// Token budget split across sources (default: working budget)
+        #[arg(short, long)]
+        budget: Option<u32>,
+    },
+    /// Check app data, sessions, Chroma and vault config for problems
+    Doctor {
+        /// Apply safe fixes (missing dirs, stale temp files, orphaned Chroma records)
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Print a one-line session summary for Claude Code's statusline (plain text)
+    Statusline {
+        /// Session ID (default: $DIALECTIC_SESSION_ID)
+        session_id: Option<String>,
+    },
+    /// Print what to preserve through a context compaction as one Markdown block
+    /// (HEAD, key evidence, unresolved tensions, pinned chunks)
+    CompactContext {
+        /// Session ID (default: $DIALECTIC_SESSION_ID)
+        session_id: Option<String>,
+        /// Token budget for the block (default: 2000)
+        #[arg(short, long)]
+        tokens: Option<u32>,
+        /// Output the payload with its accounting as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Record markers and @@ directives from a chunk of assistant output (hook use)
+    IngestTurn {
+        /// Session ID (default: $DIALECTIC_SESSION_ID)
+        #[arg(long)]
+        session: Option<String>,
+        /// Read the output from stdin
+        #[arg(long)]
+        stdin: bool,
+        /// The output, when not read from stdin
+        text: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -74,12 +190,84 @@ enum SessionAction {
         session_id: String,
     },
     /// List all sessions
-    List,
+    List {
+        /// Only sessions with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+        /// Only sessions shown by this saved view (ID or name)
+        #[arg(short, long)]
+        view: Option<String>,
+    },
     /// Get resume context for a session
     Resume {
         /// Session ID (without sess_ prefix)
         session_id: String,
     },
+    /// Check whether more material fits alongside the reserved output
+    CanFit {
+        /// Session ID (without sess_ prefix)
+        session_id: String,
+        /// Tokens about to be loaded
+        planned_tokens: u32,
+    },
+    /// Record the output size of a completed pass
+    RecordOutput {
+        /// Session ID (without sess_ prefix)
+        session_id: String,
+        /// Output tokens produced by the pass
+        tokens: u32,
+    },
+    /// Show the session's audit log (newest last)
+    Audit {
+        /// Session ID (without sess_ prefix)
+        session_id: String,
+        /// Only show the most recent N entries
+        #[arg(short, long)]
+        limit: Option<usize>,
+        /// Only show entries with this action (e.g. claim_added, status_changed)
+        #[arg(short, long)]
+        action: Option<String>,
+    },
+    /// Chronological session activity: passes, claims, tensions, status
+    /// changes, compressions, launches and artifact writes
+    Timeline {
+        /// Session ID (without sess_ prefix)
+        session_id: String,
+        /// Only show the most recent N events
+        #[arg(short, long)]
+        limit: Option<usize>,
+    },
+    /// Recover a corrupted session.json (original is moved to quarantine)
+    Repair {
+        /// Session ID (without sess_ prefix)
+        session_id: String,
+        /// Report what would be recovered without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Repo commits per pass, changes between passes and claims by commit (project-local sessions)
+    GitContext {
+        /// Session ID (without sess_ prefix)
+        session_id: String,
+    },
+    /// Print the original source text a claim was drawn from
+    ClaimSource {
+        /// Session ID (without sess_ prefix)
+        session_id: String,
+        /// Claim ID
+        claim_id: String,
+    },
+    /// Export a decision-mode session as a decision journal record
+    DecisionRecord {
+        /// Session ID (without sess_ prefix)
+        session_id: String,
+        /// Output as JSON instead of Markdown
+        #[arg(long)]
+        json: bool,
+    },
+    /// Bind the running conversation to the session in DIALECTIC_SESSION_ID
+    /// (Claude Code SessionStart hook; reads the hook payload on stdin)
+    Bind,
 }
 
 #[derive(Subcommand)]
@@ -109,6 +297,46 @@ enum VaultAction {
     Index,
 }
 
+#[derive(Subcommand)]
+enum ScratchpadAction {
+    /// Print the scratchpad sections as JSON
+    Get {
+        /// Session ID (without sess_ prefix)
+        session_id: String,
+    },
+    /// Append text to a section
+    Append {
+        /// Session ID (without sess_ prefix)
+        session_id: String,
+        /// Section: core_claim, open_questions, next_actions
+        section: String,
+        /// Text to append (list sections get one bullet per call)
+        text: String,
+    },
+    /// Replace a section's content
+    Replace {
+        /// Session ID (without sess_ prefix)
+        session_id: String,
+        /// Section: core_claim, open_questions, next_actions
+        section: String,
+        /// New section content
+        content: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum LogsAction {
+    /// Show the most recent log records
+    Tail {
+        /// Number of records to show (default: 50)
+        #[arg(short = 'n', long, default_value = "50")]
+        lines: usize,
+        /// Minimum level: error, warn, info, debug, trace
+        #[arg(short, long)]
+        level: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 enum TokensAction {
     /// Count tokens in text
@@ -180,6 +408,11 @@ enum CdgAction {
         /// Session ID
         session_id: String,
     },
+    /// Score each claim's evidential support and store it on the claims
+    Evidence {
+        /// Session ID
+        session_id: String,
+    },
     /// Take a snapshot of current metrics (for later diff)
     Snapshot {
         /// Session ID
@@ -212,6 +445,8 @@ struct SessionListItem {
     title: String,
     status: String,
     updated: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -264,6 +499,23 @@ struct CompressSuggestOutput {
     budget_status: String,
 }
 
+/// Fields of the SessionStart hook payload that `session bind` uses
+#[derive(Deserialize)]
+struct HookInput {
+    session_id: String,
+    transcript_path: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BindOutput {
+    bound: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    conversation_id: Option<String>,
+}
+
 #[derive(Serialize)]
 struct ErrorOutput {
     error: String,
@@ -273,6 +525,59 @@ struct ErrorOutput {
 
 fn main() {
     let cli = Cli::parse();
+    set_audit_actor(AuditActor::Cli);
+
+    // The MCP server owns stdout for protocol messages
+    if let Commands::Mcp = cli.command {
+        if let Err(e) = tokio::runtime::Runtime::new().and_then(|runtime| runtime.block_on(mcp::serve())) {
+            eprintln!("MCP server error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // The statusline hook shows stdout verbatim and must never fail
+    if let Commands::Statusline { session_id } = &cli.command {
+        let session_dir = session_id
+            .clone()
+            .or_else(|| std::env::var("DIALECTIC_SESSION_ID").ok())
+            .and_then(|id| get_session_dir_cli(&id).ok());
+        match session_dir {
+            Some(dir) => println!("{}", statusline::status_line(&dir, statusline::LATENCY_BUDGET)),
+            None => println!("dialectic"),
+        }
+        return;
+    }
+
+    #[cfg(feature = "rest-api")]
+    if let Commands::Serve { port, token } = &cli.command {
+        let supplied = token.clone().or_else(|| std::env::var(dialectic_lib::rest::TOKEN_ENV).ok());
+        let generated = supplied.is_none();
+        let token = match supplied {
+            Some(token) => dialectic_lib::rest::check_token(&token).map(|_| token),
+            None => dialectic_lib::rest::generate_token(),
+        };
+        let token = match token {
+            Ok(token) => token,
+            Err(e) => {
+                eprintln!("REST API error: {}", e);
+                std::process::exit(1);
+            }
+        };
+        // A token the user supplied is a secret they already have; don't echo it
+        if generated {
+            eprintln!("Dialectic REST API on http://127.0.0.1:{} (token: {})", port, token);
+        } else {
+            eprintln!("Dialectic REST API on http://127.0.0.1:{}", port);
+        }
+        let served = tokio::runtime::Runtime::new()
+            .and_then(|runtime| runtime.block_on(dialectic_lib::rest::serve(*port, token)));
+        if let Err(e) = served {
+            eprintln!("REST API error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
 
     let result = match cli.command {
         Commands::Session { action } => handle_session(action),
@@ -280,6 +585,19 @@ fn main() {
         Commands::Tokens { action } => handle_tokens(action),
         Commands::Compress { action } => handle_compress(action),
         Commands::Cdg { action } => handle_cdg(action),
+        Commands::Scratchpad { action } => handle_scratchpad(action),
+        Commands::Distill { session_id, summarize } => handle_distill(&session_id, summarize),
+        Commands::Run { title, prompt_file, working_dir, mode, timeout } => {
+            handle_run(title, &prompt_file, working_dir, mode.as_deref(), timeout)
+        }
+        Commands::Mcp | Commands::Statusline { .. } => unreachable!("handled above"),
+        #[cfg(feature = "rest-api")]
+        Commands::Serve { .. } => unreachable!("handled above"),
+        Commands::Logs { action } => handle_logs(action),
+        Commands::Search { session_id, query, budget } => handle_search(&session_id, &query, budget),
+        Commands::Doctor { fix } => handle_doctor(fix),
+        Commands::CompactContext { session_id, tokens, json } => handle_compact_context(session_id, tokens, json),
+        Commands::IngestTurn { session, stdin, text } => handle_ingest_turn(session, stdin, text),
     };
 
     match result {
@@ -318,14 +636,21 @@ fn handle_session(action: SessionAction) -> Result<String, Box<dyn std::error::E
             Ok(serde_json::to_string(&output)?)
         }
 
-        SessionAction::List => {
-            let sessions = list_sessions_cli()?;
+        SessionAction::List { tag, view } => {
+            let mut sessions = match view {
+                Some(view) => list_sessions_filtered(Some(view), None)?,
+                None => list_sessions_cli()?,
+            };
+            if let Some(tag) = tag {
+                retain_tagged(&mut sessions, &tag);
+            }
 
             let items: Vec<SessionListItem> = sessions.iter().map(|s| SessionListItem {
                 id: s.id.clone(),
                 title: s.title.clone(),
                 status: format!("{:?}", s.status).to_lowercase(),
                 updated: s.updated.to_rfc3339(),
+                tags: s.tags.clone(),
             }).collect();
 
             Ok(serde_json::to_string(&items)?)
@@ -362,9 +687,184 @@ fn handle_session(action: SessionAction) -> Result<String, Box<dyn std::error::E
 
             Ok(serde_json::to_string(&output)?)
         }
+
+        SessionAction::CanFit { session_id, planned_tokens } => {
+            let session = load_session_cli(&session_id)?;
+            let check: FitCheck = session.context_budget.unwrap_or_default().check_fit(planned_tokens);
+            Ok(serde_json::to_string(&check)?)
+        }
+
+        SessionAction::RecordOutput { session_id, tokens } => {
+            let mut session = load_session_cli(&session_id)?;
+            let budget = session.context_budget.get_or_insert_with(Default::default);
+            budget.record_pass_output(tokens);
+            let check = budget.check_fit(0);
+            save_session_cli(&session)?;
+            Ok(serde_json::to_string(&check)?)
+        }
+
+        SessionAction::Audit { session_id, limit, action } => {
+            let mut entries = read_audit_log(&get_session_dir_cli(&session_id)?)?;
+            if let Some(action) = action {
+                let wanted = action.to_lowercase();
+                entries.retain(|e| {
+                    serde_json::to_value(e.action).ok().and_then(|v| v.as_str().map(|s| s == wanted)) == Some(true)
+                });
+            }
+            if let Some(limit) = limit {
+                entries.drain(..entries.len().saturating_sub(limit));
+            }
+            Ok(serde_json::to_string(&entries)?)
+        }
+
+        SessionAction::Timeline { session_id, limit } => {
+            let mut events = session_timeline(&get_session_dir_cli(&session_id)?)?;
+            if let Some(limit) = limit {
+                events.drain(..events.len().saturating_sub(limit));
+            }
+            Ok(serde_json::to_string(&events)?)
+        }
+
+        SessionAction::Repair { session_id, dry_run } => {
+            let report = repair_session(&session_id, dry_run)?;
+            Ok(serde_json::to_string(&report)?)
+        }
+
+        SessionAction::GitContext { session_id } => {
+            let session = load_session_cli(&session_id)?;
+            Ok(serde_json::to_string(&git_context(&session)?)?)
+        }
+
+        SessionAction::ClaimSource { session_id, claim_id } => {
+            let session = load_session_cli(&session_id)?;
+            let source = claim_source(&session, &claim_id)?;
+            Ok(serde_json::to_string(&source)?)
+        }
+
+        SessionAction::DecisionRecord { session_id, json } => {
+            let session = load_session_cli(&session_id)?;
+            let format = if json { DecisionRecordFormat::Json } else { DecisionRecordFormat::Markdown };
+            let runtime = tokio::runtime::Runtime::new()?;
+            Ok(runtime.block_on(export_decision_record(&session, format))?)
+        }
+
+        SessionAction::Bind => {
+            // Outside a Dialectic launch there's nothing to bind; a hook must not fail the CLI
+            let Ok(session_id) = std::env::var("DIALECTIC_SESSION_ID") else {
+                return Ok(serde_json::to_string(&BindOutput { bound: false, session_id: None, conversation_id: None })?);
+            };
+            let mut input = String::new();
+            std::io::Read::read_to_string(&mut std::io::stdin(), &mut input)?;
+            let hook: HookInput = serde_json::from_str(&input)?;
+            bind_conversation(&get_session_dir_cli(&session_id)?.join("session.json"), &hook)?;
+            Ok(serde_json::to_string(&BindOutput {
+                bound: true,
+                session_id: Some(session_id),
+                conversation_id: Some(hook.session_id),
+            })?)
+        }
+    }
+}
+
+/// Record the hook's conversation on the session at `session_path`
+fn bind_conversation(session_path: &std::path::Path, hook: &HookInput) -> Result<(), Box<dyn std::error::Error>> {
+    if hook.session_id.is_empty() || !hook.session_id.chars().all(|c| c.is_alphanumeric() || c == '-') {
+        return Err(format!("Invalid conversation ID: {}", hook.session_id).into());
+    }
+    update_session_file(session_path, |session| {
+        session.add_conversation(&hook.session_id, hook.transcript_path.clone());
+        Ok(())
+    })?;
+    Ok(())
+}
+
+fn handle_distill(session_id: &str, summarize: bool) -> Result<String, Box<dyn std::error::Error>> {
+    let session = load_session_cli(session_id)?;
+    let runtime = tokio::runtime::Runtime::new()?;
+    let output = runtime.block_on(distill_session(&session, summarize))?;
+    Ok(serde_json::to_string(&output)?)
+}
+
+fn handle_compact_context(session_id: Option<String>, tokens: Option<u32>, json: bool) -> Result<String, Box<dyn std::error::Error>> {
+    let session_id = session_id
+        .or_else(|| std::env::var("DIALECTIC_SESSION_ID").ok())
+        .ok_or("No session ID given and DIALECTIC_SESSION_ID is not set")?;
+    let payload = compact_context(&session_id, tokens)?;
+    if json {
+        return Ok(serde_json::to_string(&payload)?);
+    }
+    // The hook injects stdout as-is
+    Ok(payload.markdown.trim_end().to_string())
+}
+
+fn handle_ingest_turn(session_id: Option<String>, stdin: bool, text: Option<String>) -> Result<String, Box<dyn std::error::Error>> {
+    let session_id = session_id
+        .or_else(|| std::env::var("DIALECTIC_SESSION_ID").ok())
+        .ok_or("No session given and DIALECTIC_SESSION_ID is not set")?;
+    let text = match (stdin, text) {
+        (true, _) => {
+            let mut input = String::new();
+            std::io::Read::read_to_string(&mut std::io::stdin(), &mut input)?;
+            input
+        }
+        (false, Some(text)) => text,
+        (false, None) => return Err("Pass the output as an argument or use --stdin".into()),
+    };
+    let result = ingest_turn(&session_id, &text)?;
+    Ok(serde_json::to_string(&result)?)
+}
+
+fn handle_run(
+    title: String,
+    prompt_file: &str,
+    working_dir: Option<String>,
+    mode: Option<&str>,
+    timeout: Option<u64>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let prompt = std::fs::read_to_string(prompt_file)
+        .map_err(|e| format!("Cannot read prompt file '{}': {}", prompt_file, e))?;
+    let mode = match mode.map(|m| m.to_lowercase()).as_deref() {
+        None => None,
+        Some("idea") => Some(SessionMode::Idea),
+        Some("decision") => Some(SessionMode::Decision),
+        Some(other) => return Err(format!("Unknown mode: '{}'. Use: idea, decision", other).into()),
+    };
+    let options = RunOptions {
+        title,
+        prompt,
+        mode,
+        working_dir,
+        timeout: timeout.map(std::time::Duration::from_secs).unwrap_or(DEFAULT_TIMEOUT),
+    };
+    let runtime = tokio::runtime::Runtime::new()?;
+    let result = runtime.block_on(run_headless(options))?;
+    Ok(serde_json::to_string(&result)?)
+}
+
+fn parse_scratchpad_section(section: &str) -> Result<ScratchpadSection, Box<dyn std::error::Error>> {
+    match section.to_lowercase().replace('-', "_").as_str() {
+        "core_claim" => Ok(ScratchpadSection::CoreClaim),
+        "open_questions" => Ok(ScratchpadSection::OpenQuestions),
+        "next_actions" => Ok(ScratchpadSection::NextActions),
+        other => Err(format!("Unknown section: '{}'. Use: core_claim, open_questions, next_actions", other).into()),
     }
 }
 
+fn handle_scratchpad(action: ScratchpadAction) -> Result<String, Box<dyn std::error::Error>> {
+    let pad = match action {
+        ScratchpadAction::Get { session_id } => scratchpad::read(&load_session_cli(&session_id)?)?,
+        ScratchpadAction::Append { session_id, section, text } => {
+            let section = parse_scratchpad_section(&section)?;
+            scratchpad::modify(&load_session_cli(&session_id)?, |pad| pad.append(section, &text))?
+        }
+        ScratchpadAction::Replace { session_id, section, content } => {
+            let section = parse_scratchpad_section(&section)?;
+            scratchpad::modify(&load_session_cli(&session_id)?, |pad| pad.replace(section, &content))?
+        }
+    };
+    Ok(serde_json::to_string(&pad)?)
+}
+
 fn handle_vault(action: VaultAction) -> Result<String, Box<dyn std::error::Error>> {
     match action {
         VaultAction::Search { query, budget } => {
@@ -407,6 +907,27 @@ fn handle_vault(action: VaultAction) -> Result<String, Box<dyn std::error::Error
     }
 }
 
+fn handle_search(session_id: &str, query: &str, budget: Option<u32>) -> Result<String, Box<dyn std::error::Error>> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    let results = runtime.block_on(unified_search(session_id, query, budget.unwrap_or(WORKING_BUDGET)))?;
+    Ok(serde_json::to_string(&results)?)
+}
+
+fn handle_doctor(fix: bool) -> Result<String, Box<dyn std::error::Error>> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    let report = runtime.block_on(doctor::run(&get_app_data_dir_cli()?, fix));
+    Ok(serde_json::to_string(&report)?)
+}
+
+fn handle_logs(action: LogsAction) -> Result<String, Box<dyn std::error::Error>> {
+    match action {
+        LogsAction::Tail { lines, level } => {
+            let records = read_recent_logs(level.as_deref(), Some(lines))?;
+            Ok(serde_json::to_string(&records)?)
+        }
+    }
+}
+
 fn handle_tokens(action: TokensAction) -> Result<String, Box<dyn std::error::Error>> {
     match action {
         TokensAction::Count { text } => {
@@ -633,6 +1154,16 @@ fn handle_cdg(action: CdgAction) -> Result<String, Box<dyn std::error::Error>> {
             }
         }
 
+        CdgAction::Evidence { session_id } => {
+            let mut session = load_session_cli(&session_id)?;
+            let report = score_evidence(&session.claims, &session.cdg_edges);
+            if apply_scores(&mut session.claims, &report) {
+                session.updated = Utc::now();
+                save_session_cli(&session)?;
+            }
+            Ok(serde_json::to_string(&report)?)
+        }
+
         CdgAction::Snapshot {
             session_id,
             pass_id,
@@ -659,3 +1190,39 @@ fn handle_cdg(action: CdgAction) -> Result<String, Box<dyn std::error::Error>> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bind_records_conversation() {
+        let dir = std::env::temp_dir().join(format!("dialectic_bind_{}", ulid::Ulid::new()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session.json");
+        std::fs::write(&path, serde_json::json!({
+            "id": "bind", "title": "Bind", "status": "exploring", "mode": "idea",
+            "workingDir": "/tmp", "isProjectLocal": false,
+            "created": "2026-01-01T00:00:00Z", "updated": "2026-01-01T00:00:00Z",
+        }).to_string()).unwrap();
+
+        let hook = |id: &str, transcript: Option<&str>| HookInput {
+            session_id: id.to_string(),
+            transcript_path: transcript.map(str::to_string),
+        };
+        bind_conversation(&path, &hook("conv-1", None)).unwrap();
+        // A rebind fills in the transcript without duplicating the conversation
+        bind_conversation(&path, &hook("conv-1", Some("/tmp/conv-1.jsonl"))).unwrap();
+        bind_conversation(&path, &hook("conv-2", None)).unwrap();
+        assert!(bind_conversation(&path, &hook("../conv", None)).is_err());
+
+        let session: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let conversations = session["conversationIds"].as_array().unwrap();
+        assert_eq!(conversations.len(), 2);
+        assert_eq!(conversations[0]["id"], "conv-1");
+        assert_eq!(conversations[0]["jsonlPath"], "/tmp/conv-1.jsonl");
+        assert_eq!(conversations[1]["id"], "conv-2");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,99 @@
+//! Search Cancellation
+//!
+//! Search commands accept an optional `request_id`. While a search runs it
+//! is registered here, and `cancel_search(request_id)` aborts it, dropping
+//! its in-flight Chroma queries. Starting a search under an id that is
+//! still running cancels the earlier one, so a search box can reuse one id
+//! per keystroke.
+
+use futures::future::{AbortHandle, Abortable};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::LazyLock;
+use tracing::debug;
+
+/// Running searches by request id, with the generation that registered them
+static IN_FLIGHT: LazyLock<Mutex<HashMap<String, (u64, AbortHandle)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// The search was cancelled before it finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+/// Run `fut`, registered under `request_id` so it can be cancelled.
+/// Without a request id it simply runs to completion.
+pub async fn cancellable<F: Future>(request_id: Option<&str>, fut: F) -> Result<F::Output, Cancelled> {
+    let Some(request_id) = request_id else {
+        return Ok(fut.await);
+    };
+
+    let (handle, registration) = AbortHandle::new_pair();
+    let generation = GENERATION.fetch_add(1, Ordering::Relaxed);
+    if let Some((_, previous)) = IN_FLIGHT.lock().insert(request_id.to_string(), (generation, handle)) {
+        debug!(request_id = %request_id, "Superseding in-flight search");
+        previous.abort();
+    }
+
+    let result = Abortable::new(fut, registration).await;
+
+    // Only clear our own registration, not a newer search under the same id
+    let mut in_flight = IN_FLIGHT.lock();
+    if in_flight.get(request_id).is_some_and(|(g, _)| *g == generation) {
+        in_flight.remove(request_id);
+    }
+    result.map_err(|_| Cancelled)
+}
+
+/// Abort the search registered under `request_id`. Returns whether one was running.
+pub fn cancel(request_id: &str) -> bool {
+    match IN_FLIGHT.lock().remove(request_id) {
+        Some((_, handle)) => {
+            handle.abort();
+            debug!(request_id = %request_id, "Cancelled search");
+            true
+        }
+        None => false,
+    }
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn cancel_search(request_id: String) -> bool {
+    cancel(&request_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_cancel_aborts_search() {
+        let search = tokio::spawn(cancellable(Some("cancel-test"), async {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            1
+        }));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(cancel("cancel-test"));
+        assert_eq!(search.await.unwrap(), Err(Cancelled));
+        assert!(!cancel("cancel-test"));
+
+        assert_eq!(cancellable(None, async { 2 }).await, Ok(2));
+    }
+
+    #[tokio::test]
+    async fn test_new_search_supersedes_same_id() {
+        let first = tokio::spawn(cancellable(Some("supersede-test"), async {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            1
+        }));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(cancellable(Some("supersede-test"), async { 2 }).await, Ok(2));
+        assert_eq!(first.await.unwrap(), Err(Cancelled));
+        assert!(!IN_FLIGHT.lock().contains_key("supersede-test"));
+    }
+}
@@ -0,0 +1,149 @@
+//! Evidence Strength
+//!
+//! Scores how well each claim is backed by evidence: the sum of weights on
+//! SUPPORT and DERIVE edges arriving from EVIDENTIAL claims, minus a penalty
+//! for every unresolved TENSION edge touching the claim. Load-bearing claims
+//! (CORE and STRUCTURAL) scoring below `MIN_LOAD_BEARING_SUPPORT` are flagged
+//! as under-supported.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::{compute_strata, CdgEdge, ClaimStratum, EdgeType, ResolutionStatus};
+use crate::session::Claim;
+
+/// Penalty per unit weight of an unresolved tension
+const TENSION_PENALTY: f32 = 0.5;
+/// Minimum score for a load-bearing claim to count as supported
+const MIN_LOAD_BEARING_SUPPORT: f32 = 0.5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimEvidence {
+    pub claim_id: String,
+    pub stratum: ClaimStratum,
+    /// Summed weight of incoming evidential SUPPORT/DERIVE edges
+    pub support: f32,
+    /// Deduction for unresolved tensions
+    pub tension_penalty: f32,
+    /// `support - tension_penalty`, floored at 0
+    pub score: f32,
+    pub under_supported: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvidenceReport {
+    /// One entry per claim, in claim order
+    pub claims: Vec<ClaimEvidence>,
+    /// Load-bearing claims below the support threshold, weakest first
+    pub under_supported: Vec<String>,
+}
+
+fn is_load_bearing(stratum: &ClaimStratum) -> bool {
+    matches!(stratum, ClaimStratum::Core | ClaimStratum::Structural)
+}
+
+/// Score every claim's evidential support
+pub fn score_evidence(claims: &[Claim], edges: &[CdgEdge]) -> EvidenceReport {
+    let strata = compute_strata(claims, edges);
+    let mut support: HashMap<&str, f32> = HashMap::new();
+    let mut penalty: HashMap<&str, f32> = HashMap::new();
+
+    for edge in edges {
+        match edge.edge_type {
+            EdgeType::Support | EdgeType::Derive => {
+                if strata.get(&edge.source_claim_id) == Some(&ClaimStratum::Evidential) {
+                    *support.entry(edge.target_claim_id.as_str()).or_default() += edge.weight;
+                }
+            }
+            EdgeType::Tension => {
+                let unresolved = matches!(edge.resolution, None | Some(ResolutionStatus::Unresolved));
+                if unresolved {
+                    for id in [&edge.source_claim_id, &edge.target_claim_id] {
+                        *penalty.entry(id.as_str()).or_default() += edge.weight * TENSION_PENALTY;
+                    }
+                }
+            }
+            EdgeType::Require | EdgeType::Qualify => {}
+        }
+    }
+
+    let scored: Vec<ClaimEvidence> = claims
+        .iter()
+        .map(|claim| {
+            let id = claim.id.as_str();
+            let stratum = strata.get(id).cloned().unwrap_or(ClaimStratum::Peripheral);
+            let support = support.get(id).copied().unwrap_or(0.0);
+            let tension_penalty = penalty.get(id).copied().unwrap_or(0.0);
+            let score = (support - tension_penalty).max(0.0);
+            ClaimEvidence {
+                claim_id: claim.id.clone(),
+                under_supported: is_load_bearing(&stratum) && score < MIN_LOAD_BEARING_SUPPORT,
+                stratum,
+                support,
+                tension_penalty,
+                score,
+            }
+        })
+        .collect();
+
+    let mut weakest: Vec<&ClaimEvidence> = scored.iter().filter(|c| c.under_supported).collect();
+    weakest.sort_by(|a, b| a.score.partial_cmp(&b.score).unwrap_or(std::cmp::Ordering::Equal));
+    let under_supported = weakest.into_iter().map(|c| c.claim_id.clone()).collect();
+
+    EvidenceReport { claims: scored, under_supported }
+}
+
+/// Store each claim's score on the claim itself. Returns whether any changed.
+pub fn apply_scores(claims: &mut [Claim], report: &EvidenceReport) -> bool {
+    let scores: HashMap<&str, f32> = report
+        .claims
+        .iter()
+        .map(|c| (c.claim_id.as_str(), c.score))
+        .collect();
+    let mut changed = false;
+    for claim in claims {
+        let score = scores.get(claim.id.as_str()).copied();
+        if claim.evidence_score != score {
+            claim.evidence_score = score;
+            changed = true;
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cdg::tests::{make_claim, make_edge};
+
+    #[test]
+    fn test_score_evidence() {
+        //   A --REQUIRE--> B --REQUIRE--> C (core)
+        //   D --SUPPORT--> B, E --SUPPORT--> C, A <--TENSION--> E
+        let mut claims: Vec<Claim> = ["A", "B", "C", "D", "E"].into_iter().map(make_claim).collect();
+        let edges = vec![
+            make_edge("A", "B", EdgeType::Require, 1.0),
+            make_edge("B", "C", EdgeType::Require, 1.0),
+            make_edge("D", "B", EdgeType::Support, 0.8),
+            make_edge("E", "C", EdgeType::Support, 0.6),
+            make_edge("A", "E", EdgeType::Tension, 1.0),
+        ];
+        let report = score_evidence(&claims, &edges);
+        let by_id: HashMap<&str, &ClaimEvidence> =
+            report.claims.iter().map(|c| (c.claim_id.as_str(), c)).collect();
+
+        assert!((by_id["B"].score - 0.8).abs() < 1e-6);
+        assert!((by_id["C"].score - 0.6).abs() < 1e-6);
+        assert!((by_id["A"].tension_penalty - 0.5).abs() < 1e-6);
+        assert_eq!(by_id["A"].score, 0.0);
+        // Evidence nodes themselves aren't load-bearing
+        assert!(!by_id["D"].under_supported);
+        assert_eq!(report.under_supported, vec!["A".to_string()]);
+
+        assert!(apply_scores(&mut claims, &report));
+        assert_eq!(claims[1].evidence_score, Some(by_id["B"].score));
+        assert!(!apply_scores(&mut claims, &report));
+    }
+}
@@ -9,6 +9,8 @@ use std::collections::{HashMap, HashSet, VecDeque};
 
 use crate::session::Claim;
 
+pub mod evidence;
+
 // ============ Types ============
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -420,17 +422,20 @@ mod tests {
     use super::*;
     use chrono::Utc;
 
-    fn make_claim(id: &str) -> Claim {
+    pub(super) fn make_claim(id: &str) -> Claim {
         Claim {
             id: id.to_string(),
             content: format!("Claim {}", id),
             source_id: "src1".to_string(),
             marker: None,
             created_at: Utc::now(),
+            source_span: None,
+            evidence_score: None,
+            commit: None,
         }
     }
 
-    fn make_edge(src: &str, tgt: &str, edge_type: EdgeType, weight: f32) -> CdgEdge {
+    pub(super) fn make_edge(src: &str, tgt: &str, edge_type: EdgeType, weight: f32) -> CdgEdge {
         CdgEdge {
             source_claim_id: src.to_string(),
             target_claim_id: tgt.to_string(),
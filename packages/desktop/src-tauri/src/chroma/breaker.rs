@@ -0,0 +1,138 @@
+//! Chroma Circuit Breaker
+//!
+//! Shared by every `ChromaClient` clone. After `FAILURE_THRESHOLD`
+//! consecutive transient failures (connection errors, timeouts, gateway
+//! statuses) the circuit opens and Chroma calls fail fast with
+//! `ChromaError::CircuitOpen` until `COOLDOWN` passes. The next call is then
+//! let through as a trial while other callers keep failing fast: success
+//! closes the circuit, failure reopens it. A trial that never reports back
+//! (cancelled, or ended in an error that isn't Chroma's) is given up on
+//! after another `COOLDOWN`.
+
+use parking_lot::Mutex;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Consecutive transient failures before the circuit opens
+const FAILURE_THRESHOLD: u32 = 5;
+/// How long an open circuit short-circuits calls
+const COOLDOWN: Duration = Duration::from_secs(30);
+
+static BREAKER: LazyLock<Mutex<CircuitBreaker>> = LazyLock::new(|| Mutex::new(CircuitBreaker::default()));
+
+#[derive(Debug, Default)]
+struct CircuitBreaker {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    /// When the half-open trial call was let through
+    trial_started: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.open_until.filter(|until| *until > now).map(|until| until - now)
+    }
+
+    /// Let a call through, or return how long until one may try. Past the
+    /// cooldown only one trial call is let through at a time.
+    fn admit(&mut self, now: Instant) -> Result<(), Duration> {
+        if let Some(remaining) = self.remaining(now) {
+            return Err(remaining);
+        }
+        if self.open_until.is_none() {
+            return Ok(());
+        }
+        match self.trial_started {
+            Some(started) if now < started + COOLDOWN => Err(started + COOLDOWN - now),
+            _ => {
+                self.trial_started = Some(now);
+                Ok(())
+            }
+        }
+    }
+
+    fn record_success(&mut self) {
+        if self.open_until.is_some() {
+            info!("Chroma circuit closed");
+        }
+        self.consecutive_failures = 0;
+        self.open_until = None;
+        self.trial_started = None;
+    }
+
+    fn record_failure(&mut self, now: Instant) {
+        self.trial_started = None;
+        self.consecutive_failures += 1;
+        // A failed trial after the cooldown reopens immediately
+        if self.consecutive_failures >= FAILURE_THRESHOLD {
+            if self.remaining(now).is_none() {
+                warn!(failures = self.consecutive_failures, cooldown_secs = COOLDOWN.as_secs(), "Chroma circuit opened");
+            }
+            self.open_until = Some(now + COOLDOWN);
+        }
+    }
+}
+
+/// Time left before calls are allowed again, if the circuit is open
+pub fn open_remaining() -> Option<Duration> {
+    BREAKER.lock().remaining(Instant::now())
+}
+
+/// Admit a call, or return how long until one may try
+pub fn admit() -> Result<(), Duration> {
+    BREAKER.lock().admit(Instant::now())
+}
+
+/// Record a call that reached Chroma
+pub fn record_success() {
+    BREAKER.lock().record_success();
+}
+
+/// Record a transient failure
+pub fn record_failure() {
+    BREAKER.lock().record_failure(Instant::now());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_opens_and_admits_one_trial() {
+        let mut breaker = CircuitBreaker::default();
+        let start = Instant::now();
+        for _ in 0..FAILURE_THRESHOLD - 1 {
+            breaker.record_failure(start);
+        }
+        assert!(breaker.admit(start).is_ok());
+        // A success resets the count
+        breaker.record_success();
+        for _ in 0..FAILURE_THRESHOLD {
+            assert!(breaker.admit(start).is_ok());
+            breaker.record_failure(start);
+        }
+        assert_eq!(breaker.admit(start), Err(COOLDOWN));
+        assert!(breaker.admit(start + COOLDOWN / 2).is_err());
+
+        // Half-open: one trial, the rest keep failing fast
+        let half_open = start + COOLDOWN;
+        assert!(breaker.admit(half_open).is_ok());
+        assert!(breaker.admit(half_open).is_err());
+        // A failed trial reopens for a full cooldown
+        breaker.record_failure(half_open);
+        assert_eq!(breaker.remaining(half_open), Some(COOLDOWN));
+
+        // A trial that never reports back is given up on
+        let second = half_open + COOLDOWN;
+        assert!(breaker.admit(second).is_ok());
+        assert!(breaker.admit(second + COOLDOWN / 2).is_err());
+        assert!(breaker.admit(second + COOLDOWN).is_ok());
+
+        // A successful trial closes the circuit for everyone
+        breaker.record_success();
+        assert!(breaker.admit(second + COOLDOWN).is_ok());
+        assert!(breaker.admit(second + COOLDOWN).is_ok());
+        assert_eq!(breaker.remaining(second + COOLDOWN), None);
+    }
+}
@@ -3,18 +3,29 @@
 //! Direct HTTP client for Chroma's REST API. Uses reqwest instead of
 //! third-party wrapper crates for stability and full API control.
 //! Supports both v1 and v2 API versions with automatic detection.
+//! Requests go through the shared circuit breaker; idempotent ones are
+//! retried with backoff on transient failures (e.g. during sidecar startup).
+//! All clients share one pooled reqwest `Client` and a cap on requests in
+//! flight, so bulk upserts reuse warm connections instead of opening new ones.
 
 use parking_lot::RwLock;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::sync::OnceLock;
-use std::time::Duration;
+use std::sync::{LazyLock, OnceLock};
+use std::time::{Duration, Instant};
 use thiserror::Error;
+use tokio::sync::Semaphore;
 use tracing::{info, warn, error, debug};
 
+use super::breaker;
+use super::embedded::EmbeddedStore;
+use super::qdrant::{QdrantClient, DEFAULT_QDRANT_URL};
 use super::sidecar::CHROMA_PORT;
+use super::store::{VectorClient, VectorStore};
+use crate::config::preferences::{load_preferences, ChromaMode, Preferences, VectorBackend};
 use crate::documents::embeddings::generate_embedding;
+use crate::metrics;
 
 #[derive(Error, Debug)]
 pub enum ChromaError {
@@ -28,6 +39,8 @@ pub enum ChromaError {
     InvalidInput(String),
     #[error("Deserialization error: {0}")]
     Deserialize(String),
+    #[error("Chroma unavailable after repeated failures, retrying in {0}s")]
+    CircuitOpen(u64),
 }
 
 impl Serialize for ChromaError {
@@ -73,6 +86,89 @@ pub struct ChromaGetResult {
     pub embeddings: Option<Vec<Vec<f32>>>,
 }
 
+/// Attempts for idempotent requests (first try plus retries)
+const MAX_ATTEMPTS: u32 = 4;
+/// Delay before the first retry; doubles each attempt
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
+/// Delay between probes in `wait_until_healthy`
+const HEALTH_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Idle connections kept open per host
+const POOL_MAX_IDLE_PER_HOST: usize = 16;
+/// Idle connections older than this are closed
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+/// Requests in flight to Chroma at once, across all clients
+const MAX_CONCURRENT_REQUESTS: usize = 8;
+/// Records per request in `upsert_batched`
+pub const UPSERT_BATCH_SIZE: usize = 50;
+
+/// Long-lived HTTP client shared by every `ChromaClient`; survives
+/// `reset_client` so the connection pool stays warm
+static HTTP: LazyLock<Client> = LazyLock::new(build_http_client);
+
+/// The shared pooled HTTP client, for other vector backends
+pub(crate) fn shared_http() -> Client {
+    HTTP.clone()
+}
+
+static REQUEST_PERMITS: LazyLock<Semaphore> = LazyLock::new(|| Semaphore::new(MAX_CONCURRENT_REQUESTS));
+
+/// The sidecar speaks HTTP/1.1; HTTP/2 is used when an external server
+/// negotiates it over TLS
+fn build_http_client() -> Client {
+    Client::builder()
+        .timeout(Duration::from_secs(30))
+        .connect_timeout(Duration::from_secs(5))
+        .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .tcp_keepalive(TCP_KEEPALIVE)
+        .tcp_nodelay(true)
+        .http2_adaptive_window(true)
+        .http2_keep_alive_interval(Duration::from_secs(30))
+        .http2_keep_alive_while_idle(true)
+        .build()
+        .unwrap_or_else(|_| Client::new())
+}
+
+/// Connection failures and timeouts, as opposed to errors from Chroma itself
+fn is_transient_error(e: &reqwest::Error) -> bool {
+    e.is_connect() || e.is_timeout()
+}
+
+fn is_transient_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// `CircuitOpen` unless the breaker admits the call
+fn circuit_open() -> Option<ChromaError> {
+    breaker::admit().err().map(|remaining| ChromaError::CircuitOpen(remaining.as_secs().max(1)))
+}
+
+/// A single record to upsert
+#[derive(Debug, Clone)]
+pub struct ChromaUpsertItem {
+    pub id: String,
+    pub document: String,
+    pub metadata: Value,
+}
+
+/// Aggregate result of `upsert_batched`
+#[derive(Debug, Clone, Default)]
+pub struct BatchUpsertOutcome {
+    /// Records written
+    pub upserted: u32,
+    /// Records in failed batches
+    pub failed: u32,
+    /// One message per failed batch
+    pub errors: Vec<String>,
+}
+
 /// Detected API version prefix, shared across all client instances
 static DETECTED_API_PREFIX: OnceLock<String> = OnceLock::new();
 
@@ -86,17 +182,12 @@ pub struct ChromaClient {
 }
 
 /// Global client instance
-static CLIENT: RwLock<Option<ChromaClient>> = RwLock::new(None);
+static CLIENT: RwLock<Option<VectorClient>> = RwLock::new(None);
 
 impl ChromaClient {
     pub fn new(base_url: &str) -> Self {
-        let http = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .connect_timeout(Duration::from_secs(5))
-            .build()
-            .unwrap_or_else(|_| Client::new());
         Self {
-            http,
+            http: HTTP.clone(),
             base_url: base_url.trim_end_matches('/').to_string(),
             tenant: "default_tenant".to_string(),
             database: "default_database".to_string(),
@@ -113,10 +204,91 @@ impl ChromaClient {
     /// Ensure API version is detected, running detection if needed.
     /// Call this before any operation that needs the API prefix.
     pub async fn ensure_api_detected(&self) -> Result<(), ChromaError> {
-        if DETECTED_API_PREFIX.get().is_none() {
-            self.detect_api_version().await?;
+        if DETECTED_API_PREFIX.get().is_some() {
+            return Ok(());
+        }
+        if let Some(e) = circuit_open() {
+            return Err(e);
+        }
+        let mut delay = RETRY_BASE_DELAY;
+        let mut attempt = 1;
+        loop {
+            match self.detect_api_version().await {
+                Ok(_) => {
+                    breaker::record_success();
+                    return Ok(());
+                }
+                Err(e) => {
+                    breaker::record_failure();
+                    if attempt >= MAX_ATTEMPTS || breaker::open_remaining().is_some() {
+                        return Err(e);
+                    }
+                    debug!(attempt = attempt, "Chroma API detection failed, retrying");
+                }
+            }
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+            attempt += 1;
+        }
+    }
+
+    /// Send a request through the circuit breaker. Idempotent requests are
+    /// retried with exponential backoff on transient failures. Other error
+    /// statuses are returned for the caller to report.
+    async fn send<F>(&self, idempotent: bool, build: F) -> Result<Response, ChromaError>
+    where
+        F: Fn(&Client) -> RequestBuilder,
+    {
+        if let Some(e) = circuit_open() {
+            return Err(e);
+        }
+        let attempts = if idempotent { MAX_ATTEMPTS } else { 1 };
+        let mut delay = RETRY_BASE_DELAY;
+        let mut attempt = 1;
+        loop {
+            let result = {
+                // The semaphore is never closed
+                let _permit = REQUEST_PERMITS.acquire().await.ok();
+                build(&self.http).send().await
+            };
+            let transient = match &result {
+                Ok(resp) => is_transient_status(resp.status()),
+                Err(e) => is_transient_error(e),
+            };
+            if !transient {
+                if result.is_ok() {
+                    breaker::record_success();
+                }
+                return Ok(result?);
+            }
+
+            breaker::record_failure();
+            if attempt >= attempts || breaker::open_remaining().is_some() {
+                return Ok(result?);
+            }
+            debug!(attempt = attempt, delay_ms = delay.as_millis() as u64, "Transient Chroma failure, retrying");
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+            attempt += 1;
+        }
+    }
+
+    /// Poll the heartbeat until Chroma responds or `timeout` passes.
+    /// Returns the number of probes made, or the last probe's error.
+    pub async fn wait_until_healthy(&self, timeout: Duration) -> Result<u32, ChromaError> {
+        let deadline = Instant::now() + timeout;
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            match self.heartbeat().await {
+                Ok(_) => return Ok(attempt),
+                Err(e) if Instant::now() >= deadline => return Err(e),
+                Err(e) => {
+                    debug!(attempt = attempt, error = %e, "Chroma health probe failed");
+                    tokio::time::sleep(HEALTH_POLL_INTERVAL).await;
+                }
+            }
         }
-        Ok(())
     }
 
     /// Detect API version by probing heartbeat endpoints.
@@ -175,8 +347,19 @@ impl ChromaClient {
         format!("tenants/{}/databases/{}", self.tenant, self.database)
     }
 
-    /// Health check — returns nanosecond heartbeat if healthy
+    /// Health check — returns nanosecond heartbeat if healthy.
+    /// Bypasses the circuit breaker (it is the probe), but a healthy
+    /// response closes an open circuit.
     pub async fn heartbeat(&self) -> Result<i64, ChromaError> {
+        let result = self.probe_heartbeat().await;
+        match &result {
+            Ok(_) => breaker::record_success(),
+            Err(_) => breaker::record_failure(),
+        }
+        result
+    }
+
+    async fn probe_heartbeat(&self) -> Result<i64, ChromaError> {
         // Detect version on first heartbeat call
         let prefix = self.detect_api_version().await?;
 
@@ -214,9 +397,7 @@ impl ChromaClient {
             self.base_url, self.api_prefix(), self.td_path()
         );
 
-        let resp = self.http.post(&url)
-            .json(&body)
-            .send().await?;
+        let resp = self.send(true, |http| http.post(&url).json(&body)).await?;
 
         let status = resp.status();
         let text = resp.text().await?;
@@ -246,7 +427,7 @@ impl ChromaClient {
             self.base_url, self.api_prefix(), self.td_path(), name
         );
 
-        let resp = self.http.delete(&url).send().await?;
+        let resp = self.send(true, |http| http.delete(&url)).await?;
 
         if resp.status().as_u16() == 404 {
             warn!(name = %name, "Collection already deleted (404)");
@@ -259,6 +440,25 @@ impl ChromaClient {
         }
     }
 
+    /// Replace a collection's metadata
+    pub async fn set_collection_metadata(&self, name: &str, metadata: Value) -> Result<(), ChromaError> {
+        let collection = self.get_collection(name).await?;
+        let url = format!("{}{}/{}/collections/{}",
+            self.base_url, self.api_prefix(), self.td_path(), collection.id
+        );
+        let body = json!({ "new_metadata": metadata });
+
+        let resp = self.send(true, |http| http.put(&url).json(&body)).await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ChromaError::Http(format!("Modify collection failed ({}): {}", status, text)));
+        }
+        debug!(name = %name, "Updated collection metadata");
+        Ok(())
+    }
+
     /// List all collections
     pub async fn list_collections(&self) -> Result<Vec<CollectionInfo>, ChromaError> {
         self.ensure_api_detected().await?;
@@ -266,7 +466,7 @@ impl ChromaClient {
             self.base_url, self.api_prefix(), self.td_path()
         );
 
-        let resp = self.http.get(&url).send().await?;
+        let resp = self.send(true, |http| http.get(&url)).await?;
 
         if !resp.status().is_success() {
             return Err(ChromaError::Http(format!("List collections failed: {}", resp.status())));
@@ -305,9 +505,7 @@ impl ChromaClient {
             self.base_url, self.api_prefix(), self.td_path(), collection_id
         );
 
-        let resp = self.http.post(&url)
-            .json(&body)
-            .send().await?;
+        let resp = self.send(false, |http| http.post(&url).json(&body)).await?;
 
         if !resp.status().is_success() {
             let status = resp.status();
@@ -320,6 +518,17 @@ impl ChromaClient {
         Ok(())
     }
 
+    /// POST `body` to `url`, recording the round trip under `metric`
+    async fn post_measured(&self, metric: &str, url: &str, body: &Value) -> Result<Response, ChromaError> {
+        let start = Instant::now();
+        let result = self.send(true, |http| http.post(url).json(body)).await;
+        metrics::record_duration(metric, start.elapsed());
+        if !result.as_ref().is_ok_and(|r| r.status().is_success()) {
+            metrics::increment(metrics::CHROMA_ERRORS, 1);
+        }
+        result
+    }
+
     /// Upsert records (insert or update)
     pub async fn upsert(
         &self,
@@ -350,9 +559,7 @@ impl ChromaClient {
             self.base_url, self.api_prefix(), self.td_path(), collection_id
         );
 
-        let resp = self.http.post(&url)
-            .json(&body)
-            .send().await?;
+        let resp = self.post_measured(metrics::CHROMA_UPSERT, &url, &body).await?;
 
         if !resp.status().is_success() {
             let status = resp.status();
@@ -400,9 +607,7 @@ impl ChromaClient {
             self.base_url, self.api_prefix(), self.td_path(), collection_id
         );
 
-        let resp = self.http.post(&url)
-            .json(&body)
-            .send().await?;
+        let resp = self.post_measured(metrics::CHROMA_QUERY, &url, &body).await?;
 
         if !resp.status().is_success() {
             let status = resp.status();
@@ -450,9 +655,7 @@ impl ChromaClient {
             self.base_url, self.api_prefix(), self.td_path(), collection_id
         );
 
-        let resp = self.http.post(&url)
-            .json(&body)
-            .send().await?;
+        let resp = self.send(true, |http| http.post(&url).json(&body)).await?;
 
         if !resp.status().is_success() {
             let text = resp.text().await.unwrap_or_default();
@@ -482,9 +685,7 @@ impl ChromaClient {
             self.base_url, self.api_prefix(), self.td_path(), collection_id
         );
 
-        let resp = self.http.post(&url)
-            .json(&body)
-            .send().await?;
+        let resp = self.send(true, |http| http.post(&url).json(&body)).await?;
 
         if !resp.status().is_success() {
             let status = resp.status();
@@ -497,6 +698,36 @@ impl ChromaClient {
         Ok(())
     }
 
+    /// Update the metadata of existing records; keys not given are kept
+    pub async fn update_metadata(
+        &self,
+        collection_id: &str,
+        ids: Vec<String>,
+        metadatas: Vec<Value>,
+    ) -> Result<(), ChromaError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let body = json!({ "ids": ids, "metadatas": metadatas });
+
+        self.ensure_api_detected().await?;
+        let url = format!("{}{}/{}/collections/{}/update",
+            self.base_url, self.api_prefix(), self.td_path(), collection_id
+        );
+
+        let resp = self.send(true, |http| http.post(&url).json(&body)).await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            error!(status = %status, body = %text, "Chroma HTTP error");
+            return Err(ChromaError::Http(format!("Update failed: {}", text)));
+        }
+
+        debug!(collection = %collection_id, "Updated record metadata");
+        Ok(())
+    }
+
     /// Count records in a collection
     pub async fn count(&self, collection_id: &str) -> Result<u32, ChromaError> {
         self.ensure_api_detected().await?;
@@ -504,7 +735,7 @@ impl ChromaClient {
             self.base_url, self.api_prefix(), self.td_path(), collection_id
         );
 
-        let resp = self.http.get(&url).send().await?;
+        let resp = self.send(true, |http| http.get(&url)).await?;
 
         if !resp.status().is_success() {
             return Err(ChromaError::Http(format!("Count failed: {}", resp.status())));
@@ -516,6 +747,85 @@ impl ChromaClient {
     }
 }
 
+impl VectorStore for ChromaClient {
+    async fn heartbeat(&self) -> Result<i64, ChromaError> {
+        ChromaClient::heartbeat(self).await
+    }
+
+    async fn wait_until_healthy(&self, timeout: Duration) -> Result<u32, ChromaError> {
+        ChromaClient::wait_until_healthy(self, timeout).await
+    }
+
+    async fn get_or_create_collection(&self, name: &str, metadata: Option<Value>) -> Result<CollectionInfo, ChromaError> {
+        ChromaClient::get_or_create_collection(self, name, metadata).await
+    }
+
+    async fn get_collection(&self, name: &str) -> Result<CollectionInfo, ChromaError> {
+        ChromaClient::get_collection(self, name).await
+    }
+
+    async fn delete_collection(&self, name: &str) -> Result<(), ChromaError> {
+        ChromaClient::delete_collection(self, name).await
+    }
+
+    async fn set_collection_metadata(&self, name: &str, metadata: Value) -> Result<(), ChromaError> {
+        ChromaClient::set_collection_metadata(self, name, metadata).await
+    }
+
+    async fn list_collections(&self) -> Result<Vec<CollectionInfo>, ChromaError> {
+        ChromaClient::list_collections(self).await
+    }
+
+    async fn upsert(
+        &self,
+        collection_id: &str,
+        ids: Vec<String>,
+        documents: Option<Vec<String>>,
+        embeddings: Option<Vec<Vec<f32>>>,
+        metadatas: Option<Vec<Value>>,
+    ) -> Result<(), ChromaError> {
+        ChromaClient::upsert(self, collection_id, ids, documents, embeddings, metadatas).await
+    }
+
+    async fn query(
+        &self,
+        collection_id: &str,
+        query_embeddings: Option<Vec<Vec<f32>>>,
+        query_texts: Option<Vec<String>>,
+        n_results: u32,
+        where_filter: Option<Value>,
+        where_document: Option<Value>,
+        include: Option<Vec<String>>,
+    ) -> Result<ChromaQueryResult, ChromaError> {
+        ChromaClient::query(self, collection_id, query_embeddings, query_texts, n_results, where_filter, where_document, include).await
+    }
+
+    async fn get(
+        &self,
+        collection_id: &str,
+        ids: Option<Vec<String>>,
+        where_filter: Option<Value>,
+        where_document: Option<Value>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+        include: Option<Vec<String>>,
+    ) -> Result<ChromaGetResult, ChromaError> {
+        ChromaClient::get(self, collection_id, ids, where_filter, where_document, limit, offset, include).await
+    }
+
+    async fn delete(&self, collection_id: &str, ids: Option<Vec<String>>, where_filter: Option<Value>) -> Result<(), ChromaError> {
+        ChromaClient::delete(self, collection_id, ids, where_filter).await
+    }
+
+    async fn update_metadata(&self, collection_id: &str, ids: Vec<String>, metadatas: Vec<Value>) -> Result<(), ChromaError> {
+        ChromaClient::update_metadata(self, collection_id, ids, metadatas).await
+    }
+
+    async fn count(&self, collection_id: &str) -> Result<u32, ChromaError> {
+        ChromaClient::count(self, collection_id).await
+    }
+}
+
 // ============ EMBEDDING HELPERS ============
 
 /// Generate embeddings for a batch of document texts (for add/upsert).
@@ -531,8 +841,29 @@ pub fn embed_query(text: &str) -> Vec<Vec<f32>> {
     vec![generate_embedding(text).unwrap_or_else(|_| vec![0.0; 256])]
 }
 
-/// Get the global Chroma client (creates on first access)
-pub fn get_client() -> ChromaClient {
+/// Chroma endpoint: the configured server in external mode, else the sidecar
+fn chroma_url(prefs: &Preferences) -> String {
+    match (prefs.chroma_mode, prefs.chroma_url.as_deref()) {
+        (ChromaMode::External, Some(url)) => url.trim_end_matches('/').to_string(),
+        _ => format!("http://127.0.0.1:{}", CHROMA_PORT),
+    }
+}
+
+/// Client for the configured vector backend
+fn build_client() -> VectorClient {
+    let prefs = load_preferences();
+    match prefs.vector_backend {
+        VectorBackend::Chroma => VectorClient::Chroma(ChromaClient::new(&chroma_url(&prefs))),
+        VectorBackend::Embedded => VectorClient::Embedded(EmbeddedStore::in_app_data()),
+        VectorBackend::Qdrant => VectorClient::Qdrant(QdrantClient::new(
+            prefs.qdrant_url.as_deref().unwrap_or(DEFAULT_QDRANT_URL),
+            prefs.qdrant_api_key.clone(),
+        )),
+    }
+}
+
+/// Get the global vector store client (creates on first access)
+pub fn get_client() -> VectorClient {
     {
         let client = CLIENT.read();
         if let Some(ref c) = *client {
@@ -542,12 +873,12 @@ pub fn get_client() -> ChromaClient {
 
     let mut client = CLIENT.write();
     if client.is_none() {
-        *client = Some(ChromaClient::new(&format!("http://127.0.0.1:{}", CHROMA_PORT)));
+        *client = Some(build_client());
     }
     client.as_ref().unwrap().clone()
 }
 
-/// Reset the client (e.g., if port changes)
+/// Reset the client (e.g., if the backend or port changes)
 pub fn reset_client() {
     let mut client = CLIENT.write();
     *client = None;
@@ -570,3 +901,25 @@ pub async fn chroma_list_collections() -> Result<Vec<String>, ChromaError> {
     let collections = client.list_collections().await?;
     Ok(collections.into_iter().map(|c| c.name).collect())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_transient_classification() {
+        for status in [StatusCode::TOO_MANY_REQUESTS, StatusCode::BAD_GATEWAY, StatusCode::SERVICE_UNAVAILABLE, StatusCode::GATEWAY_TIMEOUT] {
+            assert!(is_transient_status(status), "{}", status);
+        }
+        for status in [StatusCode::OK, StatusCode::BAD_REQUEST, StatusCode::NOT_FOUND, StatusCode::INTERNAL_SERVER_ERROR] {
+            assert!(!is_transient_status(status), "{}", status);
+        }
+
+        // Nothing listens on port 1: a connection failure is transient
+        let refused = Client::new().get("http://127.0.0.1:1/api/v2/heartbeat").send().await.unwrap_err();
+        assert!(is_transient_error(&refused));
+        // A request that can't be built never reached Chroma
+        let invalid = Client::new().get("not a url").send().await.unwrap_err();
+        assert!(!is_transient_error(&invalid));
+    }
+}
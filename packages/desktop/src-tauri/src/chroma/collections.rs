@@ -2,12 +2,23 @@
 //!
 //! Defines the collection architecture for Dialectic and provides
 //! helpers for collection lifecycle management.
+//!
+//! Collections are created with versioned metadata (record schema version
+//! and embedding dimension). `ensure_all_collections` runs
+//! `migrate_collections` first. Collections from before versioning whose
+//! embeddings already fit are just stamped with the current metadata; any
+//! collection written under an older schema or a different embedding size
+//! is rebuilt: records are read back, their metadata upgraded, and they're
+//! written (re-embedded if needed) into a separate collection that replaces
+//! the original only once it is complete.
 
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use tracing::{info, debug};
+use tracing::{info, debug, warn};
 
-use super::client::{ChromaClient, ChromaError, CollectionInfo};
+use super::client::{embed_documents, ChromaError, CollectionInfo};
+use super::store::VectorStore;
+use crate::documents::embeddings::EMBEDDING_DIM;
 
 /// Well-known collection names
 pub const COLLECTION_DOCUMENTS: &str = "documents";
@@ -16,6 +27,7 @@ pub const COLLECTION_MEMORY_SEMANTIC: &str = "memory_semantic";
 pub const COLLECTION_MEMORY_PROCEDURAL: &str = "memory_procedural";
 pub const COLLECTION_MEMORY_EPISODIC: &str = "memory_episodic";
 pub const COLLECTION_WEB_SOURCES: &str = "web_sources";
+pub const COLLECTION_CODE_CONTEXT: &str = "code_context";
 
 /// All collections managed by Dialectic
 pub const ALL_COLLECTIONS: &[&str] = &[
@@ -25,8 +37,329 @@ pub const ALL_COLLECTIONS: &[&str] = &[
     COLLECTION_MEMORY_PROCEDURAL,
     COLLECTION_MEMORY_EPISODIC,
     COLLECTION_WEB_SOURCES,
+    COLLECTION_CODE_CONTEXT,
+];
+
+/// Collection metadata key: schema version the records were written with
+pub const SCHEMA_VERSION_KEY: &str = "dialectic_schema_version";
+/// Collection metadata key: dimension of the stored embeddings
+pub const EMBEDDING_DIM_KEY: &str = "embedding_dim";
+
+/// Current record schema version per collection. Bump a collection's
+/// version when the shape of its record metadata changes, and convert
+/// older records in `upgrade_record_metadata`.
+pub const SCHEMA_VERSIONS: &[(&str, u32)] = &[
+    (COLLECTION_DOCUMENTS, 1),
+    (COLLECTION_OBSIDIAN, 1),
+    (COLLECTION_MEMORY_SEMANTIC, 1),
+    (COLLECTION_MEMORY_PROCEDURAL, 1),
+    (COLLECTION_MEMORY_EPISODIC, 1),
+    (COLLECTION_WEB_SOURCES, 1),
+    (COLLECTION_CODE_CONTEXT, 1),
 ];
 
+/// Records read per request while rebuilding a collection
+const REBUILD_PAGE_SIZE: u32 = 500;
+/// Suffix of the collection a rebuild is written into before the swap
+const REBUILD_SUFFIX: &str = "_rebuild";
+
+/// Current schema version of collection `name`
+pub fn schema_version(name: &str) -> u32 {
+    SCHEMA_VERSIONS.iter().find(|(n, _)| *n == name).map(|(_, v)| *v).unwrap_or(1)
+}
+
+/// Metadata to create collection `name` with
+pub fn collection_metadata(name: &str) -> Value {
+    json!({
+        SCHEMA_VERSION_KEY: schema_version(name),
+        EMBEDDING_DIM_KEY: EMBEDDING_DIM,
+    })
+}
+
+/// Why a collection no longer matches the current schema
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SchemaMismatch {
+    /// Created before collections were versioned
+    Unversioned,
+    SchemaVersion { found: u32, expected: u32 },
+    EmbeddingDim { found: u64, expected: u64 },
+}
+
+/// Compare a collection's metadata with the current schema. Backends that
+/// don't keep collection metadata are only checked on what they report
+/// (the embedding size).
+pub fn check_schema(name: &str, metadata: Option<&Value>, persists_metadata: bool) -> Option<SchemaMismatch> {
+    let field = |key: &str| metadata.and_then(|m| m.get(key)).and_then(|v| v.as_u64());
+    if let Some(found) = field(EMBEDDING_DIM_KEY).filter(|dim| *dim != EMBEDDING_DIM as u64) {
+        return Some(SchemaMismatch::EmbeddingDim { found, expected: EMBEDDING_DIM as u64 });
+    }
+    let expected = schema_version(name);
+    match field(SCHEMA_VERSION_KEY) {
+        // A newer build's schema is left alone rather than rebuilt downwards
+        Some(found) if (found as u32) < expected => Some(SchemaMismatch::SchemaVersion { found: found as u32, expected }),
+        Some(_) => None,
+        None if persists_metadata => Some(SchemaMismatch::Unversioned),
+        None => None,
+    }
+}
+
+/// Convert one record's metadata from schema `version` to `version + 1`
+fn upgrade_record_metadata(_name: &str, _version: u32, metadata: Value) -> Value {
+    // No shape changes yet: version 1 only added collection-level metadata
+    metadata
+}
+
+/// A collection brought up to date by `migrate_collections`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionMigration {
+    pub name: String,
+    pub mismatch: SchemaMismatch,
+    /// Records in the collection afterwards
+    pub records: u32,
+    /// Whether records were rewritten; an unversioned collection whose
+    /// embeddings already fit only has its metadata stamped
+    pub rebuilt: bool,
+}
+
+/// One record read back for a rebuild
+struct StoredRecord {
+    id: String,
+    document: Option<String>,
+    metadata: Value,
+    /// `None` when the record must be re-embedded from its document
+    embedding: Option<Vec<f32>>,
+}
+
+/// Collection a rebuild writes into before it replaces `name`
+fn rebuild_name(name: &str) -> String {
+    format!("{}{}", name, REBUILD_SUFFIX)
+}
+
+/// Every record of a collection, with its embedding
+async fn read_records(client: &impl VectorStore, collection_id: &str) -> Result<Vec<StoredRecord>, ChromaError> {
+    let mut records = Vec::new();
+    let mut offset = 0u32;
+    loop {
+        let page = client.get(
+            collection_id,
+            None,
+            None,
+            None,
+            Some(REBUILD_PAGE_SIZE),
+            Some(offset),
+            Some(vec!["documents".to_string(), "metadatas".to_string(), "embeddings".to_string()]),
+        ).await?;
+        let fetched = page.ids.len() as u32;
+        let documents = page.documents.unwrap_or_default();
+        let metadatas = page.metadatas.unwrap_or_default();
+        let embeddings = page.embeddings.unwrap_or_default();
+        for (i, id) in page.ids.into_iter().enumerate() {
+            records.push(StoredRecord {
+                id,
+                document: documents.get(i).cloned().flatten(),
+                metadata: metadatas.get(i).cloned().flatten().unwrap_or_else(|| json!({})),
+                embedding: embeddings.get(i).cloned(),
+            });
+        }
+        if fetched < REBUILD_PAGE_SIZE {
+            break;
+        }
+        offset += fetched;
+    }
+    Ok(records)
+}
+
+/// Upsert `records`, embedding those without an embedding from their document
+async fn write_records(client: &impl VectorStore, collection_id: &str, records: &[StoredRecord]) -> Result<(), ChromaError> {
+    for page in records.chunks(REBUILD_PAGE_SIZE as usize) {
+        // Upserts take documents for all records of a call or none
+        let (with_text, without_text): (Vec<&StoredRecord>, Vec<&StoredRecord>) = page.iter().partition(|r| r.document.is_some());
+        for group in [with_text, without_text] {
+            if group.is_empty() {
+                continue;
+            }
+            let mut embeddings = Vec::with_capacity(group.len());
+            for record in &group {
+                let embedding = match (&record.embedding, &record.document) {
+                    (Some(embedding), _) => embedding.clone(),
+                    (None, Some(document)) => embed_documents(std::slice::from_ref(document)).remove(0),
+                    (None, None) => {
+                        return Err(ChromaError::InvalidInput(format!("record {} has neither an embedding nor document text", record.id)))
+                    }
+                };
+                embeddings.push(embedding);
+            }
+            let documents: Option<Vec<String>> = group.iter().map(|r| r.document.clone()).collect();
+            client.upsert(
+                collection_id,
+                group.iter().map(|r| r.id.clone()).collect(),
+                documents,
+                Some(embeddings),
+                Some(group.iter().map(|r| r.metadata.clone()).collect()),
+            ).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Replace `name` with the verified rebuild in `temp`. The original is only
+/// deleted once the complete copy exists, and the copy is only deleted once
+/// it has been written back in full; a run that stops in between is
+/// finished by `recover_rebuild`.
+async fn swap_in(client: &impl VectorStore, name: &str, temp: &CollectionInfo) -> Result<u32, ChromaError> {
+    let records = read_records(client, &temp.id).await?;
+    client.delete_collection(name).await?;
+    let target = client.get_or_create_collection(name, Some(collection_metadata(name))).await?;
+    write_records(client, &target.id, &records).await?;
+    let count = client.count(&target.id).await?;
+    if count as usize != records.len() {
+        return Err(ChromaError::Http(format!(
+            "{} has {} of {} records after rebuild; the copy in {} is kept",
+            name,
+            count,
+            records.len(),
+            rebuild_name(name)
+        )));
+    }
+    client.delete_collection(&rebuild_name(name)).await?;
+    Ok(count)
+}
+
+/// Finish or discard a rebuild an earlier run left behind. Returns the
+/// record count if a swap was completed.
+async fn recover_rebuild(client: &impl VectorStore, name: &str) -> Result<Option<u32>, ChromaError> {
+    let temp = match client.get_collection(&rebuild_name(name)).await {
+        Ok(temp) => temp,
+        Err(ChromaError::CollectionNotFound(_)) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    match client.get_collection(name).await {
+        // The original was never replaced, so the copy may be partial
+        Ok(original) if check_schema(name, original.metadata.as_ref(), client.persists_collection_metadata()).is_some() => {
+            client.delete_collection(&rebuild_name(name)).await?;
+            Ok(None)
+        }
+        // Stopped mid-swap: the copy is the one complete set of records
+        Ok(_) | Err(ChromaError::CollectionNotFound(_)) => {
+            let count = swap_in(client, name, &temp).await?;
+            info!(name = %name, records = count, "Finished interrupted collection rebuild");
+            Ok(Some(count))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Length of a stored embedding, if the collection has any records
+async fn sample_embedding_dim(client: &impl VectorStore, collection_id: &str) -> Result<Option<usize>, ChromaError> {
+    let sample = client
+        .get(collection_id, None, None, None, Some(1), None, Some(vec!["embeddings".to_string()]))
+        .await?;
+    Ok(sample.embeddings.and_then(|e| e.first().map(Vec::len)))
+}
+
+/// Rebuild collection `name` under the current schema: read every record,
+/// upgrade its metadata, write the result into a separate collection
+/// (re-embedding if the embedding size changed), check nothing is missing,
+/// then swap it in. Fails without touching the original if a record can't
+/// be carried over.
+pub async fn rebuild_collection(
+    client: &impl VectorStore,
+    collection: &CollectionInfo,
+    mismatch: SchemaMismatch,
+) -> Result<CollectionMigration, ChromaError> {
+    let name = collection.name.as_str();
+    let from_version = collection.metadata.as_ref()
+        .and_then(|m| m.get(SCHEMA_VERSION_KEY))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+    let to_version = schema_version(name);
+    let reembed = matches!(mismatch, SchemaMismatch::EmbeddingDim { .. });
+
+    let mut records = read_records(client, &collection.id).await?;
+    for record in records.iter_mut() {
+        for version in from_version..to_version {
+            record.metadata = upgrade_record_metadata(name, version, std::mem::take(&mut record.metadata));
+        }
+        if reembed {
+            if record.document.is_none() {
+                return Err(ChromaError::InvalidInput(format!("{}: record {} has no document text to re-embed", name, record.id)));
+            }
+            record.embedding = None;
+        }
+    }
+
+    // Anything under the rebuild name is a partial copy `recover_rebuild` discarded
+    client.delete_collection(&rebuild_name(name)).await?;
+    let temp = client.get_or_create_collection(&rebuild_name(name), Some(collection_metadata(name))).await?;
+    let written = write_records(client, &temp.id, &records).await;
+    let count = client.count(&temp.id).await;
+    match (written, count) {
+        (Ok(()), Ok(count)) if count as usize == records.len() => {}
+        (written, count) => {
+            client.delete_collection(&rebuild_name(name)).await?;
+            written?;
+            return Err(ChromaError::Http(format!(
+                "{}: rebuild wrote {} of {} records; original kept",
+                name,
+                count?,
+                records.len()
+            )));
+        }
+    }
+
+    let records = swap_in(client, name, &temp).await?;
+    info!(name = %name, mismatch = ?mismatch, records = records, "Rebuilt collection under current schema");
+    Ok(CollectionMigration { name: name.to_string(), mismatch, records, rebuilt: true })
+}
+
+/// Bring every existing Dialectic collection up to date. Collections that
+/// predate versioning but already hold current-size embeddings are only
+/// stamped; a rebuild that fails leaves its collection as it was.
+pub async fn migrate_collections(client: &impl VectorStore) -> Result<Vec<CollectionMigration>, ChromaError> {
+    let mut migrations = Vec::new();
+    for name in ALL_COLLECTIONS {
+        if let Err(e) = recover_rebuild(client, name).await {
+            warn!(name = %name, error = %e, "Failed to recover interrupted collection rebuild");
+            continue;
+        }
+        let collection = match client.get_collection(name).await {
+            Ok(c) => c,
+            Err(ChromaError::CollectionNotFound(_)) => continue,
+            Err(e) => return Err(e),
+        };
+        let Some(mut mismatch) = check_schema(name, collection.metadata.as_ref(), client.persists_collection_metadata()) else {
+            continue;
+        };
+
+        if mismatch == SchemaMismatch::Unversioned {
+            match sample_embedding_dim(client, &collection.id).await? {
+                Some(found) if found != EMBEDDING_DIM => {
+                    mismatch = SchemaMismatch::EmbeddingDim { found: found as u64, expected: EMBEDDING_DIM as u64 };
+                }
+                _ => {
+                    let mut metadata = collection.metadata.clone().filter(Value::is_object).unwrap_or_else(|| json!({}));
+                    if let (Some(map), Some(current)) = (metadata.as_object_mut(), collection_metadata(name).as_object()) {
+                        map.extend(current.clone());
+                    }
+                    client.set_collection_metadata(name, metadata).await?;
+                    let records = client.count(&collection.id).await?;
+                    info!(name = %name, records = records, "Stamped unversioned collection with current schema");
+                    migrations.push(CollectionMigration { name: name.to_string(), mismatch, records, rebuilt: false });
+                    continue;
+                }
+            }
+        }
+
+        match rebuild_collection(client, &collection, mismatch).await {
+            Ok(migration) => migrations.push(migration),
+            Err(e) => warn!(name = %name, error = %e, "Failed to rebuild collection; left unchanged"),
+        }
+    }
+    Ok(migrations)
+}
+
 /// Collection status info
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -36,11 +369,15 @@ pub struct CollectionStatus {
     pub record_count: u32,
 }
 
-/// Ensure all Dialectic collections exist
-pub async fn ensure_all_collections(client: &ChromaClient) -> Result<Vec<CollectionInfo>, ChromaError> {
+/// Ensure all Dialectic collections exist under the current schema
+pub async fn ensure_all_collections(client: &impl VectorStore) -> Result<Vec<CollectionInfo>, ChromaError> {
+    let migrations = migrate_collections(client).await?;
+    if !migrations.is_empty() {
+        info!(count = migrations.len(), "Migrated outdated collections");
+    }
     let mut collections = Vec::new();
     for name in ALL_COLLECTIONS {
-        let collection = client.get_or_create_collection(name, None).await?;
+        let collection = client.get_or_create_collection(name, Some(collection_metadata(name))).await?;
         collections.push(collection);
     }
     info!(count = collections.len(), "Ensured all Chroma collections");
@@ -48,7 +385,7 @@ pub async fn ensure_all_collections(client: &ChromaClient) -> Result<Vec<Collect
 }
 
 /// Get status of all collections
-pub async fn get_collection_status(client: &ChromaClient) -> Result<Vec<CollectionStatus>, ChromaError> {
+pub async fn get_collection_status(client: &impl VectorStore) -> Result<Vec<CollectionStatus>, ChromaError> {
     let existing = client.list_collections().await?;
     let existing_names: std::collections::HashSet<String> = existing.iter().map(|c| c.name.clone()).collect();
 
@@ -146,6 +483,69 @@ pub fn session_filter(session_id: &str) -> Value {
     json!({ "session_id": { "$eq": session_id } })
 }
 
+/// Collections whose records carry a session_id (and so session tags)
+pub const SESSION_SCOPED_COLLECTIONS: &[&str] = &[
+    COLLECTION_DOCUMENTS,
+    COLLECTION_MEMORY_SEMANTIC,
+    COLLECTION_MEMORY_PROCEDURAL,
+    COLLECTION_MEMORY_EPISODIC,
+    COLLECTION_WEB_SOURCES,
+    COLLECTION_CODE_CONTEXT,
+];
+
+/// Metadata key marking a record's session as tagged `tag`. Chroma
+/// metadata holds scalars only, so each tag is its own boolean key.
+pub fn tag_key(tag: &str) -> String {
+    format!("tag:{}", tag)
+}
+
+/// Mark a record's metadata with its session's tags
+pub fn add_tag_fields(metadata: &mut Value, tags: &[String]) {
+    if let Some(map) = metadata.as_object_mut() {
+        for tag in tags {
+            map.insert(tag_key(tag), json!(true));
+        }
+    }
+}
+
+/// Build a where filter for records of sessions tagged `tag`
+pub fn tag_filter(tag: &str) -> Value {
+    json!({ tag_key(tag): { "$eq": true } })
+}
+
+/// Metadata key marking a record as mentioning an entity (by slug)
+pub fn entity_key(slug: &str) -> String {
+    format!("entity:{}", slug)
+}
+
+/// Mark a record's metadata with the entities its text mentions
+pub fn add_entity_fields(metadata: &mut Value, text: &str) {
+    if let Some(map) = metadata.as_object_mut() {
+        for entity in crate::session::entities::extract_entities(text) {
+            if let Some(slug) = crate::session::entities::entity_slug(&entity) {
+                map.insert(entity_key(&slug), json!(true));
+            }
+        }
+    }
+}
+
+/// Build a where filter for records mentioning an entity, directly or
+/// through their session's entity tag
+pub fn entity_filter(slug: &str) -> Value {
+    json!({ "$or": [
+        { entity_key(slug): { "$eq": true } },
+        { tag_key(&format!("{}{}", crate::session::entities::ENTITY_TAG_PREFIX, slug)): { "$eq": true } },
+    ] })
+}
+
+/// Combine optional where filters with `$and`
+pub fn and_filters(a: Option<Value>, b: Option<Value>) -> Option<Value> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(json!({ "$and": [a, b] })),
+        (a, b) => a.or(b),
+    }
+}
+
 /// Build a document-scoped where filter
 pub fn document_filter(session_id: &str, doc_id: &str) -> Value {
     json!({
@@ -165,8 +565,90 @@ pub async fn chroma_ensure_collections() -> Result<Vec<String>, ChromaError> {
     Ok(collections.into_iter().map(|c| c.name).collect())
 }
 
+/// Rebuild outdated collections now; returns what was rebuilt
+#[tauri::command]
+pub async fn chroma_migrate_collections() -> Result<Vec<CollectionMigration>, ChromaError> {
+    let client = super::client::get_client();
+    migrate_collections(&client).await
+}
+
 #[tauri::command]
 pub async fn chroma_get_collection_status() -> Result<Vec<CollectionStatus>, ChromaError> {
     let client = super::client::get_client();
     get_collection_status(&client).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chroma::embedded::EmbeddedStore;
+
+    #[tokio::test]
+    async fn test_migrate_rebuilds_outdated_collections() {
+        assert_eq!(check_schema(COLLECTION_OBSIDIAN, Some(&collection_metadata(COLLECTION_OBSIDIAN)), true), None);
+        assert_eq!(check_schema(COLLECTION_OBSIDIAN, None, true), Some(SchemaMismatch::Unversioned));
+        assert_eq!(check_schema(COLLECTION_OBSIDIAN, None, false), None);
+        assert_eq!(
+            check_schema(COLLECTION_OBSIDIAN, Some(&json!({ SCHEMA_VERSION_KEY: 1, EMBEDDING_DIM_KEY: 384 })), false),
+            Some(SchemaMismatch::EmbeddingDim { found: 384, expected: EMBEDDING_DIM as u64 })
+        );
+
+        let root = std::env::temp_dir().join(format!("dialectic_migrate_{}", ulid::Ulid::new()));
+        let store = EmbeddedStore::new(root.clone());
+        let old = store.get_or_create_collection(COLLECTION_WEB_SOURCES, Some(json!({ EMBEDDING_DIM_KEY: 128 }))).await.unwrap();
+        store
+            .upsert(
+                &old.id,
+                vec!["a".to_string(), "b".to_string()],
+                Some(vec!["rates rise".to_string(), "housing supply".to_string()]),
+                Some(vec![vec![0.5; 128], vec![0.5; 128]]),
+                Some(vec![json!({ "session_id": "s1" }), json!({ "session_id": "s2" })]),
+            )
+            .await
+            .unwrap();
+        let current = store.get_or_create_collection(COLLECTION_DOCUMENTS, Some(collection_metadata(COLLECTION_DOCUMENTS))).await.unwrap();
+        // Unversioned but already the right size: stamped, not rebuilt
+        let unversioned = store.get_or_create_collection(COLLECTION_OBSIDIAN, None).await.unwrap();
+        store
+            .upsert(&unversioned.id, vec!["n".to_string()], None, Some(vec![vec![0.1; EMBEDDING_DIM]]), None)
+            .await
+            .unwrap();
+        // Outdated with a record that has no text to re-embed: left alone
+        let textless = store.get_or_create_collection(COLLECTION_CODE_CONTEXT, Some(json!({ EMBEDDING_DIM_KEY: 128 }))).await.unwrap();
+        store.upsert(&textless.id, vec!["t".to_string()], None, Some(vec![vec![0.5; 128]]), None).await.unwrap();
+        // A run that stopped mid-swap, after deleting the original
+        let interrupted = store
+            .get_or_create_collection(&rebuild_name(COLLECTION_MEMORY_EPISODIC), Some(collection_metadata(COLLECTION_MEMORY_EPISODIC)))
+            .await
+            .unwrap();
+        store
+            .upsert(&interrupted.id, vec!["e".to_string()], Some(vec!["met the team".to_string()]), None, None)
+            .await
+            .unwrap();
+
+        let mut migrations = migrate_collections(&store).await.unwrap();
+        migrations.sort_by(|a, b| a.name.cmp(&b.name));
+        let summary: Vec<(&str, u32, bool)> = migrations.iter().map(|m| (m.name.as_str(), m.records, m.rebuilt)).collect();
+        assert_eq!(summary, vec![(COLLECTION_OBSIDIAN, 1, false), (COLLECTION_WEB_SOURCES, 2, true)]);
+
+        let stamped = store.get_collection(COLLECTION_OBSIDIAN).await.unwrap();
+        assert_eq!(check_schema(COLLECTION_OBSIDIAN, stamped.metadata.as_ref(), true), None);
+        assert_eq!(store.count(&textless.id).await.unwrap(), 1);
+        assert!(store.get_collection(&rebuild_name(COLLECTION_CODE_CONTEXT)).await.is_err());
+        let recovered = store.get_collection(COLLECTION_MEMORY_EPISODIC).await.unwrap();
+        assert_eq!(store.count(&recovered.id).await.unwrap(), 1);
+        assert!(store.get_collection(&rebuild_name(COLLECTION_MEMORY_EPISODIC)).await.is_err());
+
+        let rebuilt = store.get_collection(COLLECTION_WEB_SOURCES).await.unwrap();
+        assert_eq!(check_schema(COLLECTION_WEB_SOURCES, rebuilt.metadata.as_ref(), true), None);
+        let records = store.get(&rebuilt.id, None, None, None, None, None, Some(vec!["metadatas".to_string(), "embeddings".to_string()])).await.unwrap();
+        assert_eq!(records.ids.len(), 2);
+        assert!(records.embeddings.unwrap().iter().all(|e| e.len() == EMBEDDING_DIM));
+        assert_eq!(store.get_collection(COLLECTION_DOCUMENTS).await.unwrap().id, current.id);
+
+        // Nothing to report on a second run; the textless collection stays as it was
+        assert!(migrate_collections(&store).await.unwrap().is_empty());
+        assert_eq!(store.count(&textless.id).await.unwrap(), 1);
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,460 @@
+//! Embedded Vector Store
+//!
+//! Pure-Rust `VectorStore` for running without the Chroma sidecar (and so
+//! without Python). Each collection is held in memory and persisted as
+//! `<app data>/vectors/<name>.json`, rewritten after every change. Queries
+//! are brute force over the collection with squared L2 distance, matching
+//! the distances Chroma reports by default, and `where`/`where_document`
+//! filters follow Chroma's operator syntax.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use super::client::{embed_documents, embed_query, ChromaError, ChromaGetResult, ChromaQueryResult, CollectionInfo};
+use super::store::VectorStore;
+use crate::session::get_app_data_dir_cli;
+
+/// Directory under app data holding one file per collection
+pub const VECTORS_DIR: &str = "vectors";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Record {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    document: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<Value>,
+    embedding: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Collection {
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<Value>,
+    /// Ordered by ID so `get` paging is stable
+    records: BTreeMap<String, Record>,
+}
+
+impl Collection {
+    fn info(&self) -> CollectionInfo {
+        // The name doubles as the ID, so callers can pass either
+        CollectionInfo { id: self.name.clone(), name: self.name.clone(), metadata: self.metadata.clone() }
+    }
+}
+
+/// Loaded collections per store root, shared by every clone of a store
+type Loaded = Arc<Mutex<HashMap<String, Collection>>>;
+
+static ROOTS: LazyLock<Mutex<HashMap<PathBuf, Loaded>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Clone)]
+pub struct EmbeddedStore {
+    root: PathBuf,
+    collections: Loaded,
+}
+
+impl EmbeddedStore {
+    pub fn new(root: PathBuf) -> Self {
+        let collections = ROOTS.lock().entry(root.clone()).or_default().clone();
+        Self { root, collections }
+    }
+
+    /// Store under `<app data>/vectors`
+    pub fn in_app_data() -> Self {
+        let app_data = get_app_data_dir_cli().unwrap_or_else(|e| {
+            warn!(error = %e, "No app data dir; embedded vector store will use the temp dir");
+            std::env::temp_dir().join("dialectic")
+        });
+        Self::new(app_data.join(VECTORS_DIR))
+    }
+
+    fn collection_path(&self, name: &str) -> PathBuf {
+        self.root.join(format!("{}.json", name))
+    }
+
+    /// Valid names are safe as file names
+    fn check_name(name: &str) -> Result<(), ChromaError> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')) || name.starts_with('.') {
+            return Err(ChromaError::InvalidInput(format!("Invalid collection name: {}", name)));
+        }
+        Ok(())
+    }
+
+    /// Load `name` into `loaded` from disk if it isn't there yet
+    fn load<'a>(&self, loaded: &'a mut HashMap<String, Collection>, name: &str) -> Result<Option<&'a mut Collection>, ChromaError> {
+        Self::check_name(name)?;
+        if !loaded.contains_key(name) {
+            let path = self.collection_path(name);
+            if !path.exists() {
+                return Ok(None);
+            }
+            let content = fs::read_to_string(&path).map_err(|e| ChromaError::Http(e.to_string()))?;
+            let collection: Collection =
+                serde_json::from_str(&content).map_err(|e| ChromaError::Deserialize(format!("{}: {}", path.display(), e)))?;
+            loaded.insert(name.to_string(), collection);
+        }
+        Ok(loaded.get_mut(name))
+    }
+
+    fn save(&self, collection: &Collection) -> Result<(), ChromaError> {
+        let io = |e: std::io::Error| ChromaError::Http(e.to_string());
+        fs::create_dir_all(&self.root).map_err(io)?;
+        let path = self.collection_path(&collection.name);
+        let tmp = path.with_extension("json.tmp");
+        let content = serde_json::to_string(collection).map_err(|e| ChromaError::Deserialize(e.to_string()))?;
+        fs::write(&tmp, content).map_err(io)?;
+        fs::rename(&tmp, &path).map_err(io)
+    }
+
+    /// Run `f` on a loaded collection, persisting it afterwards if `write`
+    fn with_collection<T>(
+        &self,
+        name: &str,
+        write: bool,
+        f: impl FnOnce(&mut Collection) -> Result<T, ChromaError>,
+    ) -> Result<T, ChromaError> {
+        let mut loaded = self.collections.lock();
+        let collection = self.load(&mut loaded, name)?.ok_or_else(|| ChromaError::CollectionNotFound(name.to_string()))?;
+        let result = f(collection)?;
+        if write {
+            self.save(collection)?;
+        }
+        Ok(result)
+    }
+}
+
+fn squared_l2(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+fn compare(actual: &Value, op: &str, expected: &Value) -> bool {
+    let numeric = actual.as_f64().zip(expected.as_f64());
+    match op {
+        "$eq" => numeric.map_or(actual == expected, |(a, b)| a == b),
+        "$ne" => numeric.map_or(actual != expected, |(a, b)| a != b),
+        "$gt" => numeric.is_some_and(|(a, b)| a > b),
+        "$gte" => numeric.is_some_and(|(a, b)| a >= b),
+        "$lt" => numeric.is_some_and(|(a, b)| a < b),
+        "$lte" => numeric.is_some_and(|(a, b)| a <= b),
+        "$in" => expected.as_array().is_some_and(|values| values.iter().any(|v| compare(actual, "$eq", v))),
+        "$nin" => expected.as_array().is_some_and(|values| !values.iter().any(|v| compare(actual, "$eq", v))),
+        _ => false,
+    }
+}
+
+/// Evaluate a Chroma `where` filter against record metadata
+fn matches_where(metadata: Option<&Value>, filter: &Value) -> bool {
+    let Some(conditions) = filter.as_object() else { return true };
+    let empty = Map::new();
+    let fields = metadata.and_then(Value::as_object).unwrap_or(&empty);
+    conditions.iter().all(|(key, condition)| match key.as_str() {
+        "$and" => condition.as_array().is_some_and(|all| all.iter().all(|f| matches_where(metadata, f))),
+        "$or" => condition.as_array().is_some_and(|any| any.iter().any(|f| matches_where(metadata, f))),
+        field => {
+            let actual = fields.get(field);
+            match condition.as_object().filter(|ops| ops.keys().all(|k| k.starts_with('$'))) {
+                // A missing field only satisfies negative operators
+                Some(ops) => ops.iter().all(|(op, expected)| match actual {
+                    Some(actual) => compare(actual, op, expected),
+                    None => matches!(op.as_str(), "$ne" | "$nin"),
+                }),
+                None => actual.is_some_and(|actual| compare(actual, "$eq", condition)),
+            }
+        }
+    })
+}
+
+/// Evaluate a Chroma `where_document` filter against a record's text
+fn matches_document(document: Option<&str>, filter: &Value) -> bool {
+    let Some(conditions) = filter.as_object() else { return true };
+    let text = document.unwrap_or_default();
+    conditions.iter().all(|(op, value)| match op.as_str() {
+        "$contains" => value.as_str().is_some_and(|needle| text.contains(needle)),
+        "$not_contains" => value.as_str().is_some_and(|needle| !text.contains(needle)),
+        "$and" => value.as_array().is_some_and(|all| all.iter().all(|f| matches_document(document, f))),
+        "$or" => value.as_array().is_some_and(|any| any.iter().any(|f| matches_document(document, f))),
+        _ => false,
+    })
+}
+
+fn record_matches(record: &Record, where_filter: Option<&Value>, where_document: Option<&Value>) -> bool {
+    where_filter.is_none_or(|f| matches_where(record.metadata.as_ref(), f))
+        && where_document.is_none_or(|f| matches_document(record.document.as_deref(), f))
+}
+
+impl VectorStore for EmbeddedStore {
+    async fn heartbeat(&self) -> Result<i64, ChromaError> {
+        Ok(chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default())
+    }
+
+    async fn wait_until_healthy(&self, _timeout: Duration) -> Result<u32, ChromaError> {
+        Ok(1)
+    }
+
+    async fn get_or_create_collection(&self, name: &str, metadata: Option<Value>) -> Result<CollectionInfo, ChromaError> {
+        let mut loaded = self.collections.lock();
+        if let Some(collection) = self.load(&mut loaded, name)? {
+            return Ok(collection.info());
+        }
+        let collection = Collection { name: name.to_string(), metadata, records: BTreeMap::new() };
+        self.save(&collection)?;
+        info!(name = %name, "Created embedded collection");
+        let info = collection.info();
+        loaded.insert(name.to_string(), collection);
+        Ok(info)
+    }
+
+    async fn get_collection(&self, name: &str) -> Result<CollectionInfo, ChromaError> {
+        let mut loaded = self.collections.lock();
+        self.load(&mut loaded, name)?
+            .map(|c| c.info())
+            .ok_or_else(|| ChromaError::CollectionNotFound(name.to_string()))
+    }
+
+    async fn delete_collection(&self, name: &str) -> Result<(), ChromaError> {
+        Self::check_name(name)?;
+        self.collections.lock().remove(name);
+        match fs::remove_file(self.collection_path(name)) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(ChromaError::Http(e.to_string())),
+        }
+    }
+
+    async fn set_collection_metadata(&self, name: &str, metadata: Value) -> Result<(), ChromaError> {
+        let mut loaded = self.collections.lock();
+        let collection = self.load(&mut loaded, name)?.ok_or_else(|| ChromaError::CollectionNotFound(name.to_string()))?;
+        collection.metadata = Some(metadata);
+        self.save(collection)
+    }
+
+    async fn list_collections(&self) -> Result<Vec<CollectionInfo>, ChromaError> {
+        let names: Vec<String> = fs::read_dir(&self.root)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                (path.extension()? == "json").then(|| path.file_stem()?.to_str().map(str::to_string))?
+            })
+            .collect();
+        let mut loaded = self.collections.lock();
+        let mut infos = Vec::new();
+        for name in names {
+            match self.load(&mut loaded, &name) {
+                Ok(Some(collection)) => infos.push(collection.info()),
+                Ok(None) => {}
+                Err(e) => warn!(collection = %name, error = %e, "Skipping unreadable embedded collection"),
+            }
+        }
+        Ok(infos)
+    }
+
+    async fn upsert(
+        &self,
+        collection_id: &str,
+        ids: Vec<String>,
+        documents: Option<Vec<String>>,
+        embeddings: Option<Vec<Vec<f32>>>,
+        metadatas: Option<Vec<Value>>,
+    ) -> Result<(), ChromaError> {
+        if ids.is_empty() {
+            return Err(ChromaError::InvalidInput("ids cannot be empty".to_string()));
+        }
+        let embeddings = match (embeddings, &documents) {
+            (Some(embeddings), _) => embeddings,
+            (None, Some(documents)) => embed_documents(documents),
+            (None, None) => return Err(ChromaError::InvalidInput("documents or embeddings are required".to_string())),
+        };
+        if embeddings.len() != ids.len() {
+            return Err(ChromaError::InvalidInput("embeddings and ids differ in length".to_string()));
+        }
+        let count = ids.len();
+        self.with_collection(collection_id, true, |collection| {
+            for (i, (id, embedding)) in ids.into_iter().zip(embeddings).enumerate() {
+                let record = Record {
+                    document: documents.as_ref().and_then(|d| d.get(i).cloned()),
+                    metadata: metadatas.as_ref().and_then(|m| m.get(i).cloned()),
+                    embedding,
+                };
+                collection.records.insert(id, record);
+            }
+            Ok(())
+        })?;
+        debug!(collection = %collection_id, count = count, "Upserted embedded records");
+        Ok(())
+    }
+
+    async fn query(
+        &self,
+        collection_id: &str,
+        query_embeddings: Option<Vec<Vec<f32>>>,
+        query_texts: Option<Vec<String>>,
+        n_results: u32,
+        where_filter: Option<Value>,
+        where_document: Option<Value>,
+        _include: Option<Vec<String>>,
+    ) -> Result<ChromaQueryResult, ChromaError> {
+        let queries = match (query_embeddings, query_texts) {
+            (Some(embeddings), _) => embeddings,
+            (None, Some(texts)) => texts.iter().flat_map(|t| embed_query(t)).collect(),
+            (None, None) => return Err(ChromaError::InvalidInput("query embeddings or texts are required".to_string())),
+        };
+        self.with_collection(collection_id, false, |collection| {
+            let candidates: Vec<(&String, &Record)> = collection
+                .records
+                .iter()
+                .filter(|(_, r)| record_matches(r, where_filter.as_ref(), where_document.as_ref()))
+                .collect();
+            let mut result = ChromaQueryResult {
+                ids: Vec::new(),
+                documents: Some(Vec::new()),
+                metadatas: Some(Vec::new()),
+                distances: Some(Vec::new()),
+            };
+            for query in &queries {
+                let mut scored: Vec<(f32, &String, &Record)> =
+                    candidates.iter().map(|(id, r)| (squared_l2(query, &r.embedding), *id, *r)).collect();
+                scored.sort_by(|a, b| a.0.total_cmp(&b.0));
+                scored.truncate(n_results as usize);
+                result.ids.push(scored.iter().map(|(_, id, _)| (*id).clone()).collect());
+                if let Some(d) = result.documents.as_mut() {
+                    d.push(scored.iter().map(|(_, _, r)| r.document.clone()).collect());
+                }
+                if let Some(m) = result.metadatas.as_mut() {
+                    m.push(scored.iter().map(|(_, _, r)| r.metadata.clone()).collect());
+                }
+                if let Some(d) = result.distances.as_mut() {
+                    d.push(scored.iter().map(|(distance, _, _)| *distance).collect());
+                }
+            }
+            Ok(result)
+        })
+    }
+
+    async fn get(
+        &self,
+        collection_id: &str,
+        ids: Option<Vec<String>>,
+        where_filter: Option<Value>,
+        where_document: Option<Value>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+        include: Option<Vec<String>>,
+    ) -> Result<ChromaGetResult, ChromaError> {
+        let with_embeddings = include.as_ref().is_some_and(|inc| inc.iter().any(|i| i == "embeddings"));
+        self.with_collection(collection_id, false, |collection| {
+            let records: Vec<(&String, &Record)> = match &ids {
+                Some(ids) => ids.iter().filter_map(|id| collection.records.get_key_value(id)).collect(),
+                None => collection.records.iter().collect(),
+            };
+            let page: Vec<(&String, &Record)> = records
+                .into_iter()
+                .filter(|(_, r)| record_matches(r, where_filter.as_ref(), where_document.as_ref()))
+                .skip(offset.unwrap_or(0) as usize)
+                .take(limit.map_or(usize::MAX, |l| l as usize))
+                .collect();
+            Ok(ChromaGetResult {
+                ids: page.iter().map(|(id, _)| (*id).clone()).collect(),
+                documents: Some(page.iter().map(|(_, r)| r.document.clone()).collect()),
+                metadatas: Some(page.iter().map(|(_, r)| r.metadata.clone()).collect()),
+                embeddings: with_embeddings.then(|| page.iter().map(|(_, r)| r.embedding.clone()).collect()),
+            })
+        })
+    }
+
+    async fn delete(&self, collection_id: &str, ids: Option<Vec<String>>, where_filter: Option<Value>) -> Result<(), ChromaError> {
+        self.with_collection(collection_id, true, |collection| {
+            collection.records.retain(|id, record| {
+                let selected = ids.as_ref().is_none_or(|ids| ids.contains(id))
+                    && where_filter.as_ref().is_none_or(|f| matches_where(record.metadata.as_ref(), f));
+                !selected
+            });
+            Ok(())
+        })
+    }
+
+    async fn update_metadata(&self, collection_id: &str, ids: Vec<String>, metadatas: Vec<Value>) -> Result<(), ChromaError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        self.with_collection(collection_id, true, |collection| {
+            for (id, patch) in ids.iter().zip(metadatas) {
+                let Some(record) = collection.records.get_mut(id) else { continue };
+                let metadata = record.metadata.get_or_insert_with(|| Value::Object(Map::new()));
+                if let (Some(fields), Value::Object(patch)) = (metadata.as_object_mut(), patch) {
+                    fields.extend(patch);
+                }
+            }
+            Ok(())
+        })
+    }
+
+    async fn count(&self, collection_id: &str) -> Result<u32, ChromaError> {
+        self.with_collection(collection_id, false, |collection| Ok(collection.records.len() as u32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_embedded_store_round_trip_and_filters() {
+        let root = std::env::temp_dir().join(format!("dialectic_vectors_{}", ulid::Ulid::new()));
+        let store = EmbeddedStore::new(root.clone());
+        let collection = store.get_or_create_collection("memory_semantic", None).await.unwrap();
+        store
+            .upsert(
+                &collection.id,
+                vec!["a".to_string(), "b".to_string(), "c".to_string()],
+                Some(vec!["rates rise on inflation".to_string(), "housing supply".to_string(), "rates and housing".to_string()]),
+                None,
+                Some(vec![
+                    json!({ "session_id": "s1", "tag:macro": true, "confidence": 0.9 }),
+                    json!({ "session_id": "s1", "confidence": 0.4 }),
+                    json!({ "session_id": "s2", "tag:macro": true, "confidence": 0.7 }),
+                ]),
+            )
+            .await
+            .unwrap();
+
+        let hits = store
+            .query(&collection.id, None, Some(vec!["rates inflation".to_string()]), 2, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(hits.ids[0][0], "a");
+        assert_eq!(hits.ids[0].len(), 2);
+
+        let filter = json!({ "$and": [{ "tag:macro": { "$eq": true } }, { "confidence": { "$gte": 0.8 } }] });
+        let got = store.get(&collection.id, None, Some(filter), None, None, None, None).await.unwrap();
+        assert_eq!(got.ids, vec!["a"]);
+        let got = store
+            .get(&collection.id, None, Some(json!({ "session_id": "s1" })), Some(json!({ "$contains": "housing" })), None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(got.ids, vec!["b"]);
+
+        store.update_metadata(&collection.id, vec!["b".to_string()], vec![json!({ "tag:macro": true })]).await.unwrap();
+        store.delete(&collection.id, None, Some(json!({ "session_id": { "$eq": "s2" } }))).await.unwrap();
+        assert_eq!(store.count(&collection.id).await.unwrap(), 2);
+
+        // A fresh store over the same files sees the persisted state
+        ROOTS.lock().remove(&root);
+        let reopened = EmbeddedStore::new(root.clone());
+        let got = reopened.get("memory_semantic", None, Some(json!({ "tag:macro": true })), None, None, None, None).await.unwrap();
+        assert_eq!(got.ids, vec!["a", "b"]);
+        assert_eq!(reopened.list_collections().await.unwrap().len(), 1);
+        assert!(matches!(reopened.count("missing").await, Err(ChromaError::CollectionNotFound(_))));
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
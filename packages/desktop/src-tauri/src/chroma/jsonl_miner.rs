@@ -2,15 +2,20 @@
 //!
 //! Parses Claude Code's JSONL conversation files to extract web search
 //! and web fetch results, then indexes them into the web_sources Chroma
-//! collection for cross-session retrieval.
+//! collection for cross-session retrieval. With `mineCodeContext` enabled,
+//! Read/Grep/Glob results also go into the session-scoped code_context
+//! collection.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use serde_json::Value;
 use tracing::{info, warn, debug};
 
-use super::client::{get_client, embed_documents};
-use super::collections::COLLECTION_WEB_SOURCES;
+use super::client::{get_client, ChromaUpsertItem};
+use super::store::VectorStore;
+use super::collections::{add_entity_fields, add_tag_fields, collection_metadata, COLLECTION_CODE_CONTEXT, COLLECTION_WEB_SOURCES};
+use crate::session::tags::session_tags;
+use crate::session::{ConversationRef, Session};
 
 /// A web source extracted from a JSONL file
 #[derive(Debug, Clone)]
@@ -22,10 +27,20 @@ pub struct WebSource {
     pub source_type: String, // "web_search" or "web_fetch"
 }
 
+/// A file read, grep or glob result extracted from a JSONL file
+#[derive(Debug, Clone)]
+pub struct CodeSource {
+    pub tool: String, // "read", "grep" or "glob"
+    pub path: Option<String>,
+    pub pattern: Option<String>,
+    pub content: String,
+}
+
 /// Result of mining a JSONL file
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct MineResult {
     pub sources: Vec<WebSource>,
+    pub code_sources: Vec<CodeSource>,
     pub tool_calls_found: usize,
 }
 
@@ -37,7 +52,7 @@ const MAX_SOURCE_CONTENT: usize = 40_000;
 
 /// Hash a URL to a short string for ID construction.
 /// Uses FNV-1a (stable across Rust versions, unlike DefaultHasher).
-fn hash_url(url: &str) -> String {
+pub(crate) fn hash_url(url: &str) -> String {
     let mut hash: u64 = 0xcbf29ce484222325; // FNV offset basis
     for byte in url.as_bytes() {
         hash ^= *byte as u64;
@@ -46,92 +61,124 @@ fn hash_url(url: &str) -> String {
     format!("{:016x}", hash)
 }
 
-/// Parse a JSONL file and extract web sources.
-pub fn parse_jsonl(jsonl_path: &Path) -> MineResult {
-    let content = match std::fs::read_to_string(jsonl_path) {
-        Ok(c) => c,
-        Err(e) => {
-            warn!(path = %jsonl_path.display(), error = %e, "Failed to read JSONL file");
-            return MineResult { sources: Vec::new(), tool_calls_found: 0 };
-        }
-    };
-
-    let parent_dir = jsonl_path.parent();
-    let mut sources = Vec::new();
-    let mut tool_calls_found = 0usize;
+/// Incremental miner: feed it transcript entries in order and it pairs each
+/// tool_use with its tool_result as the result arrives. Used for whole files
+/// by `parse_jsonl` and line by line by the session transcript tailer.
+pub struct JsonlMiner {
+    parent_dir: Option<PathBuf>,
+    /// tool_use_id → (tool name, input) awaiting a result
+    pending: HashMap<String, (String, Option<Value>)>,
+    result: MineResult,
+}
 
-    // Collect all messages
-    let messages: Vec<Value> = content.lines()
-        .filter_map(|line| serde_json::from_str(line).ok())
-        .collect();
+impl JsonlMiner {
+    /// `parent_dir` resolves "Full output saved to" references
+    pub fn new(parent_dir: Option<&Path>) -> Self {
+        Self {
+            parent_dir: parent_dir.map(Path::to_path_buf),
+            pending: HashMap::new(),
+            result: MineResult::default(),
+        }
+    }
 
-    // Build a map of tool_use_id → tool_result content
-    let mut tool_results: std::collections::HashMap<String, String> = std::collections::HashMap::new();
-    for msg in &messages {
-        if let Some(content_arr) = msg.get("message").and_then(|m| m.get("content")).and_then(|c| c.as_array()) {
-            for block in content_arr {
-                if block.get("type").and_then(|t| t.as_str()) == Some("tool_result") {
-                    if let Some(tool_use_id) = block.get("tool_use_id").and_then(|id| id.as_str()) {
-                        let result_text = extract_tool_result_text(block, parent_dir);
-                        tool_results.insert(tool_use_id.to_string(), result_text);
+    /// Process one transcript entry
+    pub fn feed(&mut self, entry: &Value) {
+        let Some(content_arr) = entry.get("message").and_then(|m| m.get("content")).and_then(|c| c.as_array()) else {
+            return;
+        };
+        for block in content_arr {
+            match block.get("type").and_then(|t| t.as_str()) {
+                Some("tool_use") => {
+                    let tool_name = block.get("name").and_then(|n| n.as_str()).unwrap_or("");
+                    let tool_use_id = block.get("id").and_then(|id| id.as_str()).unwrap_or("");
+                    if !matches!(tool_name, "WebSearch" | "WebFetch" | "Read" | "Grep" | "Glob") {
+                        continue;
+                    }
+                    if matches!(tool_name, "WebSearch" | "WebFetch") {
+                        self.result.tool_calls_found += 1;
                     }
+                    self.pending.insert(tool_use_id.to_string(), (tool_name.to_string(), block.get("input").cloned()));
+                }
+                Some("tool_result") => {
+                    let Some(tool_use_id) = block.get("tool_use_id").and_then(|id| id.as_str()) else { continue };
+                    let Some((tool_name, input)) = self.pending.remove(tool_use_id) else { continue };
+                    let result_text = extract_tool_result_text(block, self.parent_dir.as_deref());
+                    self.record(&tool_name, input.as_ref(), &result_text);
                 }
+                _ => {}
             }
         }
     }
 
-    // Find tool_use blocks for WebSearch and WebFetch
-    for msg in &messages {
-        if let Some(content_arr) = msg.get("message").and_then(|m| m.get("content")).and_then(|c| c.as_array()) {
-            for block in content_arr {
-                if block.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
-                    continue;
+    fn record(&mut self, tool_name: &str, input: Option<&Value>, result_content: &str) {
+        let field = |key: &str| input.and_then(|i| i.get(key)).and_then(|v| v.as_str()).map(|s| s.to_string());
+        match tool_name {
+            "WebSearch" => {
+                if !result_content.is_empty() {
+                    self.result.sources.push(WebSource {
+                        url: None,
+                        title: None,
+                        query: field("query"),
+                        content: truncate_content(result_content),
+                        source_type: "web_search".to_string(),
+                    });
                 }
-
-                let tool_name = block.get("name").and_then(|n| n.as_str()).unwrap_or("");
-                let tool_use_id = block.get("id").and_then(|id| id.as_str()).unwrap_or("");
-                let input = block.get("input");
-
-                match tool_name {
-                    "WebSearch" => {
-                        tool_calls_found += 1;
-                        let query = input.and_then(|i| i.get("query")).and_then(|q| q.as_str()).map(|s| s.to_string());
-                        let result_content = tool_results.get(tool_use_id).cloned().unwrap_or_default();
-
-                        if !result_content.is_empty() {
-                            sources.push(WebSource {
-                                url: None,
-                                title: None,
-                                query,
-                                content: truncate_content(&result_content),
-                                source_type: "web_search".to_string(),
-                            });
-                        }
-                    }
-                    "WebFetch" => {
-                        tool_calls_found += 1;
-                        let url = input.and_then(|i| i.get("url")).and_then(|u| u.as_str()).map(|s| s.to_string());
-                        let prompt = input.and_then(|i| i.get("prompt")).and_then(|p| p.as_str()).map(|s| s.to_string());
-                        let result_content = tool_results.get(tool_use_id).cloned().unwrap_or_default();
-
-                        if !result_content.is_empty() {
-                            sources.push(WebSource {
-                                url,
-                                title: prompt,
-                                query: None,
-                                content: truncate_content(&result_content),
-                                source_type: "web_fetch".to_string(),
-                            });
-                        }
-                    }
-                    _ => {}
+            }
+            "WebFetch" => {
+                if !result_content.is_empty() {
+                    self.result.sources.push(WebSource {
+                        url: field("url"),
+                        title: field("prompt"),
+                        query: None,
+                        content: truncate_content(result_content),
+                        source_type: "web_fetch".to_string(),
+                    });
                 }
             }
+            _ => {
+                let result_content = salient_code_content(result_content);
+                if result_content.is_empty() || result_content.starts_with("<tool_use_error>") {
+                    return;
+                }
+                let (path, pattern) = match tool_name {
+                    "Read" => (field("file_path"), None),
+                    _ => (field("path"), field("pattern")),
+                };
+                self.result.code_sources.push(CodeSource {
+                    tool: tool_name.to_lowercase(),
+                    path,
+                    pattern,
+                    content: truncate_content(&result_content),
+                });
+            }
         }
     }
 
-    debug!(path = %jsonl_path.display(), tool_calls = tool_calls_found, sources = sources.len(), "Parsed JSONL");
-    MineResult { sources, tool_calls_found }
+    /// Sources completed since the last call. Tool calls still awaiting a
+    /// result stay pending.
+    pub fn take(&mut self) -> MineResult {
+        std::mem::take(&mut self.result)
+    }
+}
+
+/// Parse a JSONL file and extract web sources.
+pub fn parse_jsonl(jsonl_path: &Path) -> MineResult {
+    let content = match std::fs::read_to_string(jsonl_path) {
+        Ok(c) => c,
+        Err(e) => {
+            warn!(path = %jsonl_path.display(), error = %e, "Failed to read JSONL file");
+            return MineResult::default();
+        }
+    };
+
+    let mut miner = JsonlMiner::new(jsonl_path.parent());
+    for entry in content.lines().filter_map(|line| serde_json::from_str::<Value>(line).ok()) {
+        miner.feed(&entry);
+    }
+    let result = miner.take();
+
+    debug!(path = %jsonl_path.display(), tool_calls = result.tool_calls_found, sources = result.sources.len(), code_sources = result.code_sources.len(), "Parsed JSONL");
+    result
 }
 
 /// Extract text content from a tool_result block, handling external file references.
@@ -197,6 +244,30 @@ fn maybe_read_external(text: &str, parent_dir: Option<&Path>) -> String {
     text.to_string()
 }
 
+/// Strip what the CLI adds around code tool output: `<system-reminder>`
+/// blocks and the `cat -n` line-number gutter on Read results.
+fn salient_code_content(text: &str) -> String {
+    let mut rest = text;
+    let mut kept = String::new();
+    while let Some(start) = rest.find("<system-reminder>") {
+        kept.push_str(&rest[..start]);
+        rest = match rest[start..].find("</system-reminder>") {
+            Some(end) => &rest[start + end + "</system-reminder>".len()..],
+            None => "",
+        };
+    }
+    kept.push_str(rest);
+
+    let lines: Vec<String> = kept
+        .lines()
+        .map(|line| match line.trim_start().split_once(['\t', '→']) {
+            Some((number, code)) if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) => code.to_string(),
+            _ => line.to_string(),
+        })
+        .collect();
+    lines.join("\n").trim().to_string()
+}
+
 /// Truncate content to MAX_SOURCE_CONTENT characters
 fn truncate_content(content: &str) -> String {
     if content.chars().count() <= MAX_SOURCE_CONTENT {
@@ -263,7 +334,7 @@ pub async fn index_sources(session_id: &str, sources: &[WebSource]) {
     }
 
     let client = get_client();
-    let collection = match client.get_or_create_collection(COLLECTION_WEB_SOURCES, None).await {
+    let collection = match client.get_or_create_collection(COLLECTION_WEB_SOURCES, Some(collection_metadata(COLLECTION_WEB_SOURCES))).await {
         Ok(c) => c,
         Err(e) => {
             warn!(error = %e, "Failed to get/create web_sources collection");
@@ -271,8 +342,23 @@ pub async fn index_sources(session_id: &str, sources: &[WebSource]) {
         }
     };
 
+    let items = web_source_items(session_id, sources);
+    let outcome = client.upsert_batched(&collection.id, &items, |_| {}).await;
+    if let Some(first) = outcome.errors.first() {
+        warn!(failed_batches = outcome.errors.len(), failed_chunks = outcome.failed, error = %first, "Failed to index web source chunks");
+    }
+    if outcome.upserted > 0 {
+        info!(session_id = %session_id, chunks_indexed = outcome.upserted, sources = sources.len(), "Indexed web sources to Chroma");
+    }
+}
+
+/// web_sources chunk records for a session's sources. Chunk IDs depend only
+/// on the session, URL and chunk index, so re-indexing a page overwrites them.
+pub fn web_source_items(session_id: &str, sources: &[WebSource]) -> Vec<ChromaUpsertItem> {
     let mut seen_urls: HashSet<String> = HashSet::new();
-    let mut total_indexed = 0u32;
+    let mut items: Vec<ChromaUpsertItem> = Vec::new();
+    let indexed_at = chrono::Utc::now().timestamp();
+    let tags = session_tags(session_id);
 
     for source in sources {
         // Dedup by URL within session
@@ -291,6 +377,7 @@ pub async fn index_sources(session_id: &str, sources: &[WebSource]) {
                 "session_id": session_id,
                 "source_type": source.source_type,
                 "chunk_index": *chunk_idx as i64,
+                "indexed_at": indexed_at,
             });
             if let Some(ref url) = source.url {
                 metadata["url"] = serde_json::json!(url);
@@ -301,36 +388,98 @@ pub async fn index_sources(session_id: &str, sources: &[WebSource]) {
             if let Some(ref query) = source.query {
                 metadata["query"] = serde_json::json!(query);
             }
+            add_tag_fields(&mut metadata, &tags);
+            add_entity_fields(&mut metadata, &format!("{}\n{}", source.title.as_deref().unwrap_or_default(), chunk_content));
 
-            let ids = vec![id];
-            let documents = vec![chunk_content.clone()];
-            let embeddings = embed_documents(&documents);
-            let metadatas = vec![metadata];
-
-            match client.upsert(
-                &collection.id,
-                ids,
-                Some(documents),
-                Some(embeddings),
-                Some(metadatas),
-            ).await {
-                Ok(_) => total_indexed += 1,
-                Err(e) => {
-                    warn!(error = %e, "Failed to index web source chunk");
-                }
+            items.push(ChromaUpsertItem {
+                id,
+                document: chunk_content.clone(),
+                metadata,
+            });
+        }
+    }
+    items
+}
+
+/// Index extracted code tool results into the code_context collection.
+/// Records are scoped to the session; repeated reads of the same file keep
+/// the latest result.
+pub async fn index_code_sources(session_id: &str, sources: &[CodeSource]) {
+    if sources.is_empty() {
+        return;
+    }
+
+    let client = get_client();
+    let collection = match client.get_or_create_collection(COLLECTION_CODE_CONTEXT, Some(collection_metadata(COLLECTION_CODE_CONTEXT))).await {
+        Ok(c) => c,
+        Err(e) => {
+            warn!(error = %e, "Failed to get/create code_context collection");
+            return;
+        }
+    };
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut items: Vec<ChromaUpsertItem> = Vec::new();
+    let indexed_at = chrono::Utc::now().timestamp();
+    let tags = session_tags(session_id);
+
+    // Newest first so the latest result for a path/pattern wins
+    for source in sources.iter().rev() {
+        let key = format!(
+            "{}:{}:{}",
+            source.tool,
+            source.path.as_deref().unwrap_or(""),
+            source.pattern.as_deref().unwrap_or("")
+        );
+        if !seen.insert(key.clone()) {
+            continue;
+        }
+
+        let key_hash = hash_url(&key);
+        for (chunk_content, chunk_idx) in chunk_content(&source.content) {
+            let mut metadata = serde_json::json!({
+                "session_id": session_id,
+                "source_type": source.tool,
+                "chunk_index": chunk_idx as i64,
+                "indexed_at": indexed_at,
+            });
+            if let Some(ref path) = source.path {
+                metadata["path"] = serde_json::json!(path);
+                metadata["title"] = serde_json::json!(path);
+            }
+            if let Some(ref pattern) = source.pattern {
+                metadata["pattern"] = serde_json::json!(pattern);
             }
+            add_tag_fields(&mut metadata, &tags);
+
+            items.push(ChromaUpsertItem {
+                id: format!("{}::code::{}::chunk_{}", session_id, key_hash, chunk_idx),
+                document: chunk_content,
+                metadata,
+            });
         }
     }
 
-    if total_indexed > 0 {
-        info!(session_id = %session_id, chunks_indexed = total_indexed, sources = sources.len(), "Indexed web sources to Chroma");
+    let outcome = client.upsert_batched(&collection.id, &items, |_| {}).await;
+    if let Some(first) = outcome.errors.first() {
+        warn!(failed_batches = outcome.errors.len(), failed_chunks = outcome.failed, error = %first, "Failed to index code context chunks");
+    }
+    if outcome.upserted > 0 {
+        info!(session_id = %session_id, chunks_indexed = outcome.upserted, sources = seen.len(), "Indexed code context to Chroma");
     }
 }
 
-/// Mine web sources from a session's JSONL file and index to Chroma.
+/// Mine web sources (and code context, if enabled in preferences) from a
+/// session's JSONL file and index to Chroma.
 /// This is the main entry point called from other modules.
 pub async fn mine_session_sources(session_id: &str, jsonl_path: &Path) {
     let result = parse_jsonl(jsonl_path);
+
+    if crate::config::preferences::load_preferences().mine_code_context && !result.code_sources.is_empty() {
+        info!(session_id = %session_id, code_sources = result.code_sources.len(), "Mining code context from JSONL");
+        index_code_sources(session_id, &result.code_sources).await;
+    }
+
     if result.sources.is_empty() {
         debug!(session_id = %session_id, "No web sources found in JSONL");
         return;
@@ -340,41 +489,124 @@ pub async fn mine_session_sources(session_id: &str, jsonl_path: &Path) {
     index_sources(session_id, &result.sources).await;
 }
 
-/// Convenience: find and mine the JSONL for a session given its conversation_id and working_dir.
-/// Tries the exact project dir first, then scans all project dirs.
-pub async fn mine_session_if_possible(session_id: &str, conversation_id: &str, working_dir: &str) {
-    let home = match dirs::home_dir() {
-        Some(h) => h,
-        None => return,
-    };
+/// Encode a working dir the way Claude Code names its project dirs: every
+/// character other than an ASCII letter or digit becomes `-`. On Unix
+/// `/Users/foo/my.app` → `-Users-foo-my-app`; on Windows
+/// `C:\Users\foo` → `C--Users-foo`. A `\\?\` verbatim prefix (from
+/// `canonicalize`) is dropped first since Claude never sees it.
+pub fn encode_project_path(working_dir: &str) -> String {
+    let path = working_dir
+        .strip_prefix(r"\\?\UNC\")
+        .map(|rest| format!(r"\\{}", rest))
+        .or_else(|| working_dir.strip_prefix(r"\\?\").map(str::to_string))
+        .unwrap_or_else(|| working_dir.to_string());
+    path.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '-' }).collect()
+}
 
-    let projects_base = home.join(".claude").join("projects");
+/// `~/.claude/projects`
+pub fn claude_projects_dir() -> Option<PathBuf> {
+    Some(dirs::home_dir()?.join(".claude").join("projects"))
+}
 
-    // Try exact encoded working-dir path first
-    let encoded = working_dir.replace('/', "-");
-    let exact_dir = projects_base.join(&encoded);
+/// Claude Code's project dir for `working_dir`
+pub fn claude_code_project_dir(working_dir: &str) -> Option<PathBuf> {
+    Some(claude_projects_dir()?.join(encode_project_path(working_dir)))
+}
+
+/// Locate the JSONL file for a conversation. Tries the exact project dir
+/// for `working_dir` first, then scans all project dirs.
+pub fn find_conversation_jsonl(conversation_id: &str, working_dir: &str) -> Option<PathBuf> {
+    let projects_base = claude_projects_dir()?;
     let jsonl_name = format!("{}.jsonl", conversation_id);
 
-    if exact_dir.exists() {
-        let jsonl_path = exact_dir.join(&jsonl_name);
-        if jsonl_path.exists() {
-            mine_session_sources(session_id, &jsonl_path).await;
-            return;
-        }
+    // Try exact encoded working-dir path first
+    let jsonl_path = projects_base.join(encode_project_path(working_dir)).join(&jsonl_name);
+    if jsonl_path.exists() {
+        return Some(jsonl_path);
     }
 
     // Scan all project dirs for the JSONL file
-    if let Ok(entries) = std::fs::read_dir(&projects_base) {
-        for entry in entries.flatten() {
-            if entry.path().is_dir() {
-                let jsonl_path = entry.path().join(&jsonl_name);
-                if jsonl_path.exists() {
-                    mine_session_sources(session_id, &jsonl_path).await;
-                    return;
-                }
-            }
+    std::fs::read_dir(&projects_base)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|dir| dir.is_dir())
+        .map(|dir| dir.join(&jsonl_name))
+        .find(|path| path.exists())
+}
+
+/// Transcript of one of a session's conversations: the recorded path if it
+/// still exists, else a lookup by ID.
+pub fn locate_transcript(conversation: &ConversationRef, working_dir: &str) -> Option<PathBuf> {
+    conversation
+        .jsonl_path
+        .as_ref()
+        .map(PathBuf::from)
+        .filter(|path| path.exists())
+        .or_else(|| find_conversation_jsonl(&conversation.id, working_dir))
+}
+
+/// Mine every conversation the session has spanned
+pub async fn mine_session_conversations(session: &Session) {
+    for conversation in session.conversations() {
+        match locate_transcript(&conversation, &session.working_dir) {
+            Some(jsonl_path) => mine_session_sources(&session.id, &jsonl_path).await,
+            None => debug!(session_id = %session.id, conversation_id = %conversation.id, "JSONL file not found for mining"),
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_code_tool_results() {
+        let lines = [
+            json!({"message": {"content": [
+                {"type": "tool_use", "id": "t1", "name": "Read", "input": {"file_path": "/repo/src/lib.rs"}},
+                {"type": "tool_use", "id": "t2", "name": "Grep", "input": {"pattern": "fn main", "path": "/repo"}},
+                {"type": "tool_use", "id": "t3", "name": "Glob", "input": {"pattern": "**/*.toml"}},
+                {"type": "tool_use", "id": "t4", "name": "Read", "input": {"file_path": "/repo/missing.rs"}}
+            ]}}),
+            json!({"message": {"content": [
+                {"type": "tool_result", "tool_use_id": "t1", "content": "     1\tpub mod a;\n     2→pub mod b;\n<system-reminder>ignore</system-reminder>"},
+                {"type": "tool_result", "tool_use_id": "t2", "content": [{"type": "text", "text": "/repo/src/main.rs:3:fn main() {"}]},
+                {"type": "tool_result", "tool_use_id": "t3", "content": "/repo/Cargo.toml"},
+                {"type": "tool_result", "tool_use_id": "t4", "content": "<tool_use_error>File does not exist.</tool_use_error>"}
+            ]}}),
+        ];
+        let dir = std::env::temp_dir().join(format!("dialectic_miner_{}", ulid::Ulid::new()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("conversation.jsonl");
+        let body: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        std::fs::write(&path, body.join("\n")).unwrap();
+
+        let result = parse_jsonl(&path);
+        assert!(result.sources.is_empty());
+        assert_eq!(result.code_sources.len(), 3);
+
+        let read = &result.code_sources[0];
+        assert_eq!(read.tool, "read");
+        assert_eq!(read.path.as_deref(), Some("/repo/src/lib.rs"));
+        assert_eq!(read.content, "pub mod a;\npub mod b;");
+
+        let grep = &result.code_sources[1];
+        assert_eq!((grep.path.as_deref(), grep.pattern.as_deref()), (Some("/repo"), Some("fn main")));
+        assert_eq!(grep.content, "/repo/src/main.rs:3:fn main() {");
+
+        assert_eq!(result.code_sources[2].pattern.as_deref(), Some("**/*.toml"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
 
-    debug!(session_id = %session_id, conversation_id = %conversation_id, "JSONL file not found for mining");
+    #[test]
+    fn test_encode_project_path() {
+        assert_eq!(encode_project_path("/Users/foo/bar"), "-Users-foo-bar");
+        assert_eq!(encode_project_path("/home/foo/my.app_v2"), "-home-foo-my-app-v2");
+        assert_eq!(encode_project_path(r"C:\Users\foo\bar"), "C--Users-foo-bar");
+        assert_eq!(encode_project_path("C:/Users/foo"), "C--Users-foo");
+        assert_eq!(encode_project_path(r"\\?\D:\work\repo"), "D--work-repo");
+        assert_eq!(encode_project_path(r"\\?\UNC\server\share\repo"), "--server-share-repo");
+    }
 }
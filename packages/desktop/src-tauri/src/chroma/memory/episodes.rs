@@ -0,0 +1,219 @@
+//! Session Episodes
+//!
+//! Episodic memories of what a session tried and what came of it, written
+//! automatically when a pass completes or the session changes status.
+//! Each records the claims and tensions the step added, tensions resolved
+//! and the thesis confidence delta, so later sessions can recall that an
+//! angle was already explored or stress-tested. IDs are deterministic
+//! (`{session}::pass::{pass}`, `{session}::status::{from}-{to}`), so the
+//! same change seen by both a CLI write and the app's watcher is written once.
+//! Episodes come from structured session data rather than extracted text,
+//! so they skip the review queue and are written approved.
+
+use serde_json::{json, Value};
+use tracing::{debug, warn};
+
+use super::{approved_review, write_memory, MemoryType};
+use crate::chroma::collections::add_tag_fields;
+use crate::jobs::{self, JobKind};
+use crate::session::{Pass, Session};
+
+/// Claims quoted in a pass summary; the rest are counted
+const QUOTED_CLAIMS: usize = 5;
+
+/// An episode ready to be written
+#[derive(Debug, Clone)]
+pub struct Episode {
+    pub id: String,
+    pub content: String,
+    pub metadata: Value,
+}
+
+fn status_name(session: &Session) -> String {
+    format!("{:?}", session.status).to_lowercase()
+}
+
+fn confidence(session: &Session) -> Option<f32> {
+    session.thesis.as_ref().map(|t| t.confidence)
+}
+
+/// "confidence 60% → 72%", or the current value when there's no change
+fn confidence_summary(before: Option<f32>, after: Option<f32>) -> Option<String> {
+    match (before, after) {
+        (Some(b), Some(a)) if (a - b).abs() >= 0.005 => Some(format!("confidence {:.0}% → {:.0}%", b * 100.0, a * 100.0)),
+        (None, Some(a)) => Some(format!("thesis formed at {:.0}% confidence", a * 100.0)),
+        (_, Some(a)) => Some(format!("confidence {:.0}%", a * 100.0)),
+        (_, None) => None,
+    }
+}
+
+fn resolved_tensions(before: &Session, after: &Session) -> usize {
+    after
+        .tensions
+        .iter()
+        .filter(|t| t.resolution.is_some())
+        .filter(|t| before.tensions.iter().any(|b| b.id == t.id && b.resolution.is_none()))
+        .count()
+}
+
+fn base_metadata(session: &Session, source_type: &str) -> Value {
+    let mut metadata = json!({
+        "session_id": session.id,
+        "session_title": session.title,
+        "source_type": source_type,
+    });
+    if let Some(confidence) = confidence(session) {
+        metadata["confidence"] = json!(confidence);
+    }
+    add_tag_fields(&mut metadata, &session.tags);
+    metadata
+}
+
+/// Summary of a completed pass: what it added within its time window
+fn pass_episode(pass: &Pass, before: &Session, after: &Session) -> Episode {
+    let completed_at = pass.completed_at.unwrap_or(after.updated);
+    let in_pass = |at| at >= pass.started_at && at <= completed_at;
+    let claims: Vec<&str> = after.claims.iter().filter(|c| in_pass(c.created_at)).map(|c| c.content.as_str()).collect();
+    let tensions = after.tensions.iter().filter(|t| in_pass(t.created_at)).count();
+    let resolved = resolved_tensions(before, after);
+
+    let mut changes = vec![
+        format!("{} claim{} added", claims.len(), if claims.len() == 1 { "" } else { "s" }),
+        format!("{} tension{} raised", tensions, if tensions == 1 { "" } else { "s" }),
+    ];
+    if resolved > 0 {
+        changes.push(format!("{} resolved", resolved));
+    }
+    changes.extend(confidence_summary(confidence(before), confidence(after)));
+
+    let mut content = format!(
+        "[PASS] {} pass on \"{}\" ({}): {}.",
+        pass.pass_type,
+        after.title,
+        status_name(after),
+        changes.join(", "),
+    );
+    if !claims.is_empty() {
+        let quoted: Vec<&str> = claims.iter().take(QUOTED_CLAIMS).copied().collect();
+        content.push_str(&format!(" Claims: {}", quoted.join("; ")));
+        if claims.len() > QUOTED_CLAIMS {
+            content.push_str(&format!(" (+{} more)", claims.len() - QUOTED_CLAIMS));
+        }
+    }
+
+    let mut metadata = base_metadata(after, "pass");
+    metadata["pass_id"] = json!(pass.id);
+    metadata["pass_type"] = json!(pass.pass_type);
+    metadata["claims_added"] = json!(claims.len());
+    metadata["tensions_added"] = json!(tensions);
+    metadata["tensions_resolved"] = json!(resolved);
+    Episode { id: format!("{}::pass::{}", after.id, pass.id), content, metadata }
+}
+
+/// Summary of a status transition: where the session stood when it moved
+fn status_episode(before: &Session, after: &Session) -> Episode {
+    let (from, to) = (status_name(before), status_name(after));
+    let open = after.tensions.iter().filter(|t| t.resolution.is_none()).count();
+    let mut changes = vec![
+        format!("{} claims", after.claims.len()),
+        format!("{} open tensions", open),
+        format!("{} passes", after.passes.len()),
+    ];
+    changes.extend(confidence_summary(confidence(before), confidence(after)));
+
+    let mut content = format!("[STATUS] \"{}\" moved from {} to {}: {}.", after.title, from, to, changes.join(", "));
+    if let Some(thesis) = &after.thesis {
+        content.push_str(&format!(" Thesis: {}", thesis.content));
+    }
+
+    let mut metadata = base_metadata(after, "status_change");
+    metadata["from_status"] = json!(from);
+    metadata["to_status"] = json!(to);
+    metadata["claim_count"] = json!(after.claims.len());
+    metadata["open_tensions"] = json!(open);
+    Episode { id: format!("{}::status::{}-{}", after.id, from, to), content, metadata }
+}
+
+/// Episodes for the passes completed and the status change between two
+/// states of a session
+pub fn episodes(before: &Session, after: &Session) -> Vec<Episode> {
+    let mut found: Vec<Episode> = after
+        .passes
+        .iter()
+        .filter(|p| p.completed_at.is_some())
+        .filter(|p| !before.passes.iter().any(|b| b.id == p.id && b.completed_at.is_some()))
+        .map(|p| pass_episode(p, before, after))
+        .collect();
+    if before.status != after.status {
+        found.push(status_episode(before, after));
+    }
+    for episode in &mut found {
+        episode.metadata = approved_review(episode.metadata.take());
+    }
+    found
+}
+
+/// Write the episodes between two states of a session in the background.
+/// Best-effort: failures are logged.
+pub fn record_outcomes(before: &Session, after: &Session) {
+    let found = episodes(before, after);
+    if found.is_empty() {
+        return;
+    }
+    let session_id = after.id.clone();
+    jobs::submit(JobKind::MemoryConsolidation, format!("{}:episodes", session_id), |_job| async move {
+        for episode in found {
+            match write_memory(MemoryType::Episodic, &episode.id, &episode.content, Some(episode.metadata)).await {
+                Ok(()) => debug!(session_id = %session_id, id = %episode.id, "Recorded session episode"),
+                Err(e) => warn!(session_id = %session_id, id = %episode.id, error = %e, "Failed to record session episode"),
+            }
+        }
+        Ok(None)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn test_pass_and_status_episodes() {
+        let start = Utc::now();
+        let during = start + Duration::seconds(5);
+        let before: Session = test_session(json!({
+            "id": "ep", "title": "Moats",
+            "created": start, "updated": start,
+            "claims": [{ "id": "c0", "content": "Old claim", "sourceId": "s", "marker": null, "createdAt": start - Duration::hours(1) }],
+            "tensions": [{ "id": "t0", "claimAId": "c0", "claimBId": "c0", "description": "d", "resolution": null, "createdAt": start }],
+            "thesis": { "content": "Moats erode", "confidence": 0.6, "updatedAt": start },
+            "passes": [{ "id": "p1", "passType": "critique", "startedAt": start, "completedAt": null, "tokenCount": null }],
+        }));
+        let mut after = before.clone();
+        after.status = crate::session::SessionStatus::Tensions;
+        after.claims.push(serde_json::from_value(json!({
+            "id": "c1", "content": "Switching costs are low", "sourceId": "s", "marker": "[COUNTER]", "createdAt": during,
+        })).unwrap());
+        after.tensions[0].resolution = Some("Scoped".to_string());
+        after.thesis.as_mut().unwrap().confidence = 0.72;
+        after.passes[0].completed_at = Some(during + Duration::seconds(1));
+
+        let found = episodes(&before, &after);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].id, "ep::pass::p1");
+        assert!(found[0].content.contains("critique pass"));
+        assert!(found[0].content.contains("1 claim added"));
+        assert!(found[0].content.contains("60% → 72%"));
+        assert!(found[0].content.contains("Switching costs are low"));
+        assert!(!found[0].content.contains("Old claim"));
+        assert_eq!(found[0].metadata["tensions_resolved"], 1);
+        assert_eq!(found[1].id, "ep::status::exploring-tensions");
+        assert_eq!(found[1].metadata["to_status"], "tensions");
+        // Recallable by default reads without review
+        assert!(found.iter().all(|e| e.metadata["review_status"] == "approved"));
+
+        // Nothing new once the pass is recorded and the status settled
+        assert!(episodes(&after, &after).is_empty());
+    }
+}
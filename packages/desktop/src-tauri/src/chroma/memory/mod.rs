@@ -0,0 +1,1029 @@
+//! Agentic Memory
+//!
+//! Three memory types stored as Chroma collections:
+//! - Semantic: facts, preferences, context (cross-session)
+//! - Procedural: learned strategies (cross-session)
+//! - Episodic: past results and plans (cross-session)
+//!
+//! Memories written by automated extraction start out pending review and
+//! are left out of reads until approved. Rejected memories are kept as
+//! tombstones so re-extraction doesn't bring them back.
+//!
+//! `provenance` traces extracted memories back to their sources, and
+//! `episodes` records the outcome of each pass and status change.
+
+pub mod episodes;
+pub mod provenance;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+use tracing::{info, warn, debug};
+
+use super::client::{ChromaError, ChromaUpsertItem, get_client, embed_documents, embed_query};
+use super::write_queue;
+use super::store::VectorStore;
+use super::collections::*;
+use crate::session::tags::session_tags;
+use crate::session::Session;
+
+#[derive(Error, Debug)]
+pub enum MemoryError {
+    #[error("Chroma error: {0}")]
+    Chroma(#[from] ChromaError),
+    #[error("Memory not found: {0}")]
+    NotFound(String),
+    #[error("Invalid memory type: {0}")]
+    InvalidType(String),
+    #[error("Memory filter needs at least one condition")]
+    EmptyFilter,
+}
+
+impl Serialize for MemoryError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Memory type enum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryType {
+    Semantic,
+    Procedural,
+    Episodic,
+}
+
+impl MemoryType {
+    pub fn collection_name(&self) -> &'static str {
+        match self {
+            MemoryType::Semantic => COLLECTION_MEMORY_SEMANTIC,
+            MemoryType::Procedural => COLLECTION_MEMORY_PROCEDURAL,
+            MemoryType::Episodic => COLLECTION_MEMORY_EPISODIC,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MemoryType::Semantic => "semantic",
+            MemoryType::Procedural => "procedural",
+            MemoryType::Episodic => "episodic",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Result<Self, MemoryError> {
+        match s {
+            "semantic" => Ok(MemoryType::Semantic),
+            "procedural" => Ok(MemoryType::Procedural),
+            "episodic" => Ok(MemoryType::Episodic),
+            _ => Err(MemoryError::InvalidType(s.to_string())),
+        }
+    }
+}
+
+/// Core metadata fields that cannot be overridden by extra_metadata
+const RESERVED_METADATA_KEYS: &[&str] = &["type", "created_at", "access_count", "last_accessed", USEFULNESS_KEY];
+
+/// Metadata key of a memory's usefulness, in [-1, 1], moved by feedback
+const USEFULNESS_KEY: &str = "usefulness";
+/// Share of the usefulness score replaced by each new piece of feedback
+const FEEDBACK_RATE: f64 = 0.3;
+/// How far usefulness scales relevance: ±1 → ×(1 ± this)
+const USEFULNESS_WEIGHT: f32 = 0.75;
+
+/// Usefulness after one more piece of feedback
+fn apply_feedback(usefulness: f64, useful: bool) -> f64 {
+    let target = if useful { 1.0 } else { -1.0 };
+    (usefulness + (target - usefulness) * FEEDBACK_RATE).clamp(-1.0, 1.0)
+}
+
+/// Query relevance weighted by the memory's usefulness
+fn ranked_relevance(relevance: f32, metadata: &Value) -> f32 {
+    let usefulness = metadata.get(USEFULNESS_KEY).and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
+    relevance * (1.0 + USEFULNESS_WEIGHT * usefulness)
+}
+
+/// Metadata key of a memory's review state; absent means approved
+const REVIEW_STATUS_KEY: &str = "review_status";
+
+/// Review state of a memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus {
+    /// Written by automated extraction, awaiting review
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl ReviewStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewStatus::Pending => "pending",
+            ReviewStatus::Approved => "approved",
+            ReviewStatus::Rejected => "rejected",
+        }
+    }
+}
+
+/// Metadata marking an automatically extracted memory as pending review
+fn pending_review(mut metadata: Value) -> Value {
+    metadata[REVIEW_STATUS_KEY] = json!(ReviewStatus::Pending.as_str());
+    metadata
+}
+
+/// Metadata marking a memory as approved without review, for memories
+/// derived from structured session data rather than extracted text
+fn approved_review(mut metadata: Value) -> Value {
+    metadata[REVIEW_STATUS_KEY] = json!(ReviewStatus::Approved.as_str());
+    metadata
+}
+
+/// Where filter excluding memories that aren't approved
+fn approved_filter() -> Value {
+    json!({ REVIEW_STATUS_KEY: { "$nin": [ReviewStatus::Pending.as_str(), ReviewStatus::Rejected.as_str()] } })
+}
+
+/// Where filter for the review queue
+fn pending_filter() -> Value {
+    json!({ REVIEW_STATUS_KEY: { "$eq": ReviewStatus::Pending.as_str() } })
+}
+
+/// A memory record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryRecord {
+    pub id: String,
+    pub memory_type: MemoryType,
+    pub content: String,
+    pub metadata: Value,
+    pub relevance: Option<f32>,
+}
+
+impl MemoryRecord {
+    pub fn review_status(&self) -> ReviewStatus {
+        match self.metadata.get(REVIEW_STATUS_KEY).and_then(|v| v.as_str()) {
+            Some("pending") => ReviewStatus::Pending,
+            Some("rejected") => ReviewStatus::Rejected,
+            _ => ReviewStatus::Approved,
+        }
+    }
+}
+
+/// The upsert that writes a memory: its collection ID and the record,
+/// with metadata merged over what the existing record keeps
+async fn memory_upsert(
+    memory_type: MemoryType,
+    id: &str,
+    content: &str,
+    extra_metadata: Option<Value>,
+) -> Result<(String, ChromaUpsertItem), MemoryError> {
+    let client = get_client();
+    let collection_name = memory_type.collection_name();
+    let collection = client.get_or_create_collection(collection_name, Some(collection_metadata(collection_name))).await?;
+
+    let now = Utc::now().to_rfc3339();
+
+    // Read existing metadata to preserve created_at and access_count across upserts
+    let existing_meta = client.get(
+        &collection.id,
+        Some(vec![id.to_string()]),
+        None,
+        None,
+        None,
+        None,
+        Some(vec!["metadatas".to_string()]),
+    ).await.ok().and_then(|r| {
+        if r.ids.is_empty() { return None; }
+        r.metadatas.and_then(|m| m.into_iter().next()).flatten()
+    });
+
+    // Merge extra metadata first, filtering out reserved keys
+    let mut metadata = json!({});
+    if let Some(extra) = extra_metadata {
+        if let Some(obj) = extra.as_object() {
+            for (k, v) in obj {
+                if !RESERVED_METADATA_KEYS.contains(&k.as_str()) {
+                    metadata[k] = v.clone();
+                }
+            }
+        }
+    }
+
+    // Set core fields after merge so they cannot be overridden
+    metadata["type"] = json!(memory_type.as_str());
+    metadata["last_accessed"] = json!(now);
+
+    // Preserve created_at and access_count from existing record, or initialize defaults
+    if let Some(ref existing) = existing_meta {
+        // A review decision outlasts re-extraction
+        if let Some(status) = existing.get(REVIEW_STATUS_KEY).filter(|s| s.as_str() != Some(ReviewStatus::Pending.as_str())) {
+            metadata[REVIEW_STATUS_KEY] = status.clone();
+        }
+        if let Some(created) = existing.get("created_at") {
+            metadata["created_at"] = created.clone();
+        } else {
+            metadata["created_at"] = json!(now);
+        }
+        if let Some(count) = existing.get("access_count") {
+            metadata["access_count"] = count.clone();
+        } else {
+            metadata["access_count"] = json!(0_i64);
+        }
+        if let Some(usefulness) = existing.get(USEFULNESS_KEY) {
+            metadata[USEFULNESS_KEY] = usefulness.clone();
+        }
+    } else {
+        metadata["created_at"] = json!(now);
+        metadata["access_count"] = json!(0_i64);
+    }
+
+    Ok((collection.id, ChromaUpsertItem { id: id.to_string(), document: content.to_string(), metadata }))
+}
+
+/// Write a memory to the appropriate collection
+pub async fn write_memory(
+    memory_type: MemoryType,
+    id: &str,
+    content: &str,
+    extra_metadata: Option<Value>,
+) -> Result<(), MemoryError> {
+    let (collection_id, item) = memory_upsert(memory_type, id, content, extra_metadata).await?;
+    let embeddings = embed_documents(std::slice::from_ref(&item.document));
+
+    get_client().upsert(
+        &collection_id,
+        vec![item.id],
+        Some(vec![item.document]),
+        Some(embeddings),
+        Some(vec![item.metadata]),
+    ).await?;
+
+    info!(memory_type = %memory_type.as_str(), id = %id, "Wrote memory");
+    Ok(())
+}
+
+/// Like `write_memory`, but the upsert goes through the write queue, so
+/// bursts from hooks and file events are batched. Errors cover preparing
+/// the write only.
+pub async fn queue_memory(
+    memory_type: MemoryType,
+    id: &str,
+    content: &str,
+    extra_metadata: Option<Value>,
+) -> Result<(), MemoryError> {
+    let (collection_id, item) = memory_upsert(memory_type, id, content, extra_metadata).await?;
+    write_queue::enqueue(&collection_id, item);
+    debug!(memory_type = %memory_type.as_str(), id = %id, "Queued memory write");
+    Ok(())
+}
+
+/// Read memories relevant to a query; pending and rejected memories are
+/// left out unless `include_unreviewed`
+pub async fn read_memories(
+    memory_type: MemoryType,
+    query: &str,
+    n_results: u32,
+    include_unreviewed: bool,
+) -> Result<Vec<MemoryRecord>, MemoryError> {
+    let truncated: String = query.chars().take(100).collect();
+    debug!(memory_type = %memory_type.as_str(), query = %truncated, n_results = n_results, "Reading memories");
+    let client = get_client();
+    let collection_name = memory_type.collection_name();
+    let collection = match client.get_collection(collection_name).await {
+        Ok(c) => c,
+        Err(ChromaError::CollectionNotFound(_)) => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let count = client.count(&collection.id).await?;
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let query_embeddings = embed_query(query);
+
+    let result = client.query(
+        &collection.id,
+        Some(query_embeddings),
+        None,
+        // Extra candidates so usefulness can reorder them; no more than exist
+        n_results.saturating_mul(2).min(count),
+        (!include_unreviewed).then(approved_filter),
+        None,
+        Some(vec!["documents".to_string(), "metadatas".to_string(), "distances".to_string()]),
+    ).await?;
+
+    let mut records = Vec::new();
+
+    for (query_idx, ids) in result.ids.iter().enumerate() {
+        for (result_idx, id) in ids.iter().enumerate() {
+            let content = result.documents.as_ref()
+                .and_then(|d| d.get(query_idx))
+                .and_then(|d| d.get(result_idx))
+                .and_then(|d| d.clone())
+                .unwrap_or_default();
+
+            let metadata = result.metadatas.as_ref()
+                .and_then(|m| m.get(query_idx))
+                .and_then(|m| m.get(result_idx))
+                .and_then(|m| m.clone())
+                .unwrap_or(Value::Null);
+
+            let distance = result.distances.as_ref()
+                .and_then(|d| d.get(query_idx))
+                .and_then(|d| d.get(result_idx))
+                .copied()
+                .unwrap_or(f32::MAX);
+
+            let relevance = ranked_relevance(1.0 / (1.0 + distance), &metadata);
+
+            records.push(MemoryRecord {
+                id: id.clone(),
+                memory_type,
+                content,
+                metadata,
+                relevance: Some(relevance),
+            });
+        }
+    }
+    records.sort_by(|a, b| b.relevance.unwrap_or(0.0).total_cmp(&a.relevance.unwrap_or(0.0)));
+    records.truncate(n_results as usize);
+
+    // Track access count updates of the memories returned
+    let now = Utc::now().to_rfc3339();
+    let mut ids_to_update = Vec::new();
+    let mut metadatas_to_update = Vec::new();
+    for record in &mut records {
+        let access_count = record.metadata.get("access_count")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0);
+        record.metadata["access_count"] = json!(access_count + 1);
+        record.metadata["last_accessed"] = json!(now);
+        ids_to_update.push(record.id.clone());
+        metadatas_to_update.push(record.metadata.clone());
+    }
+
+    // Best-effort: update access counts (don't fail the read if this errors)
+    if !ids_to_update.is_empty() {
+        let _ = client.update_metadata(&collection.id, ids_to_update, metadatas_to_update).await;
+    }
+
+    debug!(count = records.len(), "Memory read results");
+    Ok(records)
+}
+
+/// Read all memories of a given type
+pub async fn list_memories(
+    memory_type: MemoryType,
+    limit: Option<u32>,
+) -> Result<Vec<MemoryRecord>, MemoryError> {
+    get_memories(memory_type, None, limit).await
+}
+
+/// Memories awaiting review, across types unless one is given
+pub async fn list_pending(memory_type: Option<MemoryType>) -> Result<Vec<MemoryRecord>, MemoryError> {
+    let types = match memory_type {
+        Some(memory_type) => vec![memory_type],
+        None => vec![MemoryType::Semantic, MemoryType::Procedural, MemoryType::Episodic],
+    };
+    let filter = pending_filter();
+    let mut records = Vec::new();
+    for memory_type in types {
+        records.extend(get_memories(memory_type, Some(filter.clone()), None).await?);
+    }
+    Ok(records)
+}
+
+/// Record a review decision on a memory
+pub async fn set_review_status(memory_type: MemoryType, id: &str, status: ReviewStatus) -> Result<(), MemoryError> {
+    set_review_status_in(&get_client(), memory_type, id, status).await
+}
+
+async fn set_review_status_in(
+    client: &impl VectorStore,
+    memory_type: MemoryType,
+    id: &str,
+    status: ReviewStatus,
+) -> Result<(), MemoryError> {
+    update_metadata(client, memory_type, id, |metadata| {
+        metadata[REVIEW_STATUS_KEY] = json!(status.as_str());
+    }).await?;
+    info!(memory_type = %memory_type.as_str(), id = %id, status = %status.as_str(), "Reviewed memory");
+    Ok(())
+}
+
+/// Move a memory's usefulness toward useful or not; the memory is looked
+/// up across types. Returns the new score.
+pub async fn record_feedback(id: &str, useful: bool) -> Result<f64, MemoryError> {
+    record_feedback_in(&get_client(), id, useful).await
+}
+
+async fn record_feedback_in(client: &impl VectorStore, id: &str, useful: bool) -> Result<f64, MemoryError> {
+    for memory_type in [MemoryType::Semantic, MemoryType::Procedural, MemoryType::Episodic] {
+        let mut score = 0.0;
+        match update_metadata(client, memory_type, id, |metadata| {
+            let usefulness = metadata.get(USEFULNESS_KEY).and_then(|v| v.as_f64()).unwrap_or(0.0);
+            score = apply_feedback(usefulness, useful);
+            metadata[USEFULNESS_KEY] = json!(score);
+        }).await {
+            Ok(()) => {
+                debug!(memory_type = %memory_type.as_str(), id = %id, useful, usefulness = score, "Recorded memory feedback");
+                return Ok(score);
+            }
+            Err(MemoryError::NotFound(_)) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Err(MemoryError::NotFound(id.to_string()))
+}
+
+/// Read-modify-write a memory's metadata
+async fn update_metadata(
+    client: &impl VectorStore,
+    memory_type: MemoryType,
+    id: &str,
+    f: impl FnOnce(&mut Value),
+) -> Result<(), MemoryError> {
+    let collection = match client.get_collection(memory_type.collection_name()).await {
+        Ok(c) => c,
+        Err(ChromaError::CollectionNotFound(_)) => return Err(MemoryError::NotFound(id.to_string())),
+        Err(e) => return Err(e.into()),
+    };
+    let result = client.get(
+        &collection.id,
+        Some(vec![id.to_string()]),
+        None,
+        None,
+        None,
+        None,
+        Some(vec!["metadatas".to_string()]),
+    ).await?;
+    if result.ids.is_empty() {
+        return Err(MemoryError::NotFound(id.to_string()));
+    }
+    let mut metadata = result.metadatas.and_then(|m| m.into_iter().next()).flatten().unwrap_or_else(|| json!({}));
+    f(&mut metadata);
+    client.update_metadata(&collection.id, vec![id.to_string()], vec![metadata]).await?;
+    Ok(())
+}
+
+async fn get_memories(
+    memory_type: MemoryType,
+    where_filter: Option<Value>,
+    limit: Option<u32>,
+) -> Result<Vec<MemoryRecord>, MemoryError> {
+    let client = get_client();
+    let collection_name = memory_type.collection_name();
+    let collection = match client.get_collection(collection_name).await {
+        Ok(c) => c,
+        Err(ChromaError::CollectionNotFound(_)) => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let result = client.get(
+        &collection.id,
+        None,
+        where_filter,
+        None,
+        limit,
+        None,
+        Some(vec!["documents".to_string(), "metadatas".to_string()]),
+    ).await?;
+
+    let mut records = Vec::new();
+    for (idx, id) in result.ids.iter().enumerate() {
+        let content = result.documents.as_ref()
+            .and_then(|d| d.get(idx))
+            .and_then(|d| d.clone())
+            .unwrap_or_default();
+
+        let metadata = result.metadatas.as_ref()
+            .and_then(|m| m.get(idx))
+            .and_then(|m| m.clone())
+            .unwrap_or(Value::Null);
+
+        records.push(MemoryRecord {
+            id: id.clone(),
+            memory_type,
+            content,
+            metadata,
+            relevance: None,
+        });
+    }
+
+    debug!(memory_type = %memory_type.as_str(), count = records.len(), "Listed memories");
+    Ok(records)
+}
+
+/// Delete a specific memory
+pub async fn delete_memory(memory_type: MemoryType, id: &str) -> Result<(), MemoryError> {
+    let client = get_client();
+    let collection_name = memory_type.collection_name();
+    let collection = match client.get_collection(collection_name).await {
+        Ok(c) => c,
+        Err(ChromaError::CollectionNotFound(_)) => return Ok(()), // Nothing to delete
+        Err(e) => return Err(e.into()),
+    };
+
+    client.delete(
+        &collection.id,
+        Some(vec![id.to_string()]),
+        None,
+    ).await?;
+
+    provenance::forget(|trace| trace.memory_id == id);
+    info!(memory_type = %memory_type.as_str(), id = %id, "Deleted memory");
+    Ok(())
+}
+
+/// Conditions for bulk deletion; a memory must meet all that are set
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryFilter {
+    pub session_id: Option<String>,
+    /// Semantic marker such as `[INSIGHT]`
+    pub marker: Option<String>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    /// Case-insensitive substring of the content
+    pub keyword: Option<String>,
+}
+
+impl MemoryFilter {
+    fn is_empty(&self) -> bool {
+        self.session_id.is_none()
+            && self.marker.is_none()
+            && self.created_after.is_none()
+            && self.created_before.is_none()
+            && self.keyword.as_deref().is_none_or(|k| k.trim().is_empty())
+    }
+
+    /// Metadata conditions the store can apply
+    fn where_clause(&self) -> Option<Value> {
+        let mut conditions = Vec::new();
+        if let Some(session_id) = &self.session_id {
+            conditions.push(json!({ "session_id": { "$eq": session_id } }));
+        }
+        if let Some(marker) = &self.marker {
+            conditions.push(json!({ "marker": { "$eq": marker } }));
+        }
+        match conditions.len() {
+            0 => None,
+            1 => conditions.pop(),
+            _ => Some(json!({ "$and": conditions })),
+        }
+    }
+
+    /// Conditions checked after fetching: creation date and keyword
+    fn matches(&self, content: &str, metadata: &Value) -> bool {
+        let created = metadata.get("created_at")
+            .and_then(|v| v.as_str())
+            .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+            .map(|d| d.with_timezone(&Utc));
+        let in_range = match (self.created_after, self.created_before) {
+            (None, None) => true,
+            (after, before) => created.is_some_and(|c| after.is_none_or(|a| c >= a) && before.is_none_or(|b| c < b)),
+        };
+        let keyword = self.keyword.as_deref().map(str::trim).filter(|k| !k.is_empty());
+        in_range && keyword.is_none_or(|k| content.to_lowercase().contains(&k.to_lowercase()))
+    }
+}
+
+/// Delete every memory of a type matching `filter`; returns how many
+pub async fn delete_memories_where(memory_type: MemoryType, filter: &MemoryFilter) -> Result<usize, MemoryError> {
+    if filter.is_empty() {
+        return Err(MemoryError::EmptyFilter);
+    }
+    let ids: Vec<String> = get_memories(memory_type, filter.where_clause(), None).await?
+        .into_iter()
+        .filter(|record| filter.matches(&record.content, &record.metadata))
+        .map(|record| record.id)
+        .collect();
+    if ids.is_empty() {
+        return Ok(0);
+    }
+
+    let client = get_client();
+    let collection = client.get_collection(memory_type.collection_name()).await?;
+    client.delete(&collection.id, Some(ids.clone()), None).await?;
+    provenance::forget(|trace| ids.contains(&trace.memory_id));
+
+    warn!(memory_type = %memory_type.as_str(), count = ids.len(), "Deleted memories by filter");
+    Ok(ids.len())
+}
+
+/// Clear all memories of a given type
+pub async fn clear_memories(memory_type: MemoryType) -> Result<(), MemoryError> {
+    let client = get_client();
+    let collection_name = memory_type.collection_name();
+    // Delete and recreate the collection
+    client.delete_collection(collection_name).await?;
+    client.get_or_create_collection(collection_name, Some(collection_metadata(collection_name))).await?;
+    provenance::forget(|trace| trace.memory_type == Some(memory_type));
+    warn!(memory_type = %memory_type.as_str(), "Cleared all memories (destructive)");
+    Ok(())
+}
+
+/// Get memory stats
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryStats {
+    pub semantic_count: u32,
+    pub procedural_count: u32,
+    pub episodic_count: u32,
+    pub total: u32,
+}
+
+pub async fn get_memory_stats() -> Result<MemoryStats, MemoryError> {
+    debug!("Getting memory stats");
+    let client = get_client();
+
+    let mut counts = [0u32; 3];
+    for (i, name) in [
+        COLLECTION_MEMORY_SEMANTIC,
+        COLLECTION_MEMORY_PROCEDURAL,
+        COLLECTION_MEMORY_EPISODIC,
+    ].iter().enumerate() {
+        if let Ok(collection) = client.get_collection(name).await {
+            counts[i] = client.count(&collection.id).await.unwrap_or(0);
+        }
+    }
+
+    Ok(MemoryStats {
+        semantic_count: counts[0],
+        procedural_count: counts[1],
+        episodic_count: counts[2],
+        total: counts.iter().sum(),
+    })
+}
+
+// ============ ARTIFACT INDEXING ============
+
+/// Index a session artifact (state.json, scratchpad.md, distill output) to Chroma memory.
+/// Best-effort: logs warnings on failure, does not propagate errors.
+pub async fn index_session_artifact(
+    session_id: &str,
+    filename: &str,
+    content: &str,
+    memory_type: MemoryType,
+) {
+    let id = format!("{}::artifact::{}", session_id, filename);
+    let prefix = match memory_type {
+        MemoryType::Semantic => "[ARTIFACT:SEMANTIC]",
+        MemoryType::Procedural => "[ARTIFACT:PROCEDURAL]",
+        MemoryType::Episodic => "[ARTIFACT:EPISODIC]",
+    };
+
+    // Truncate content to 8000 chars to avoid oversized embeddings
+    let truncated: String = content.chars().take(8000).collect();
+    let doc = format!("{} {} -- artifact '{}' from session {}", prefix, truncated, filename, session_id);
+
+    let mut metadata = json!({
+        "session_id": session_id,
+        "source_type": "artifact",
+        "artifact_name": filename,
+    });
+    add_tag_fields(&mut metadata, &session_tags(session_id));
+
+    match queue_memory(memory_type, &id, &doc, Some(pending_review(metadata))).await {
+        Ok(()) => {
+            info!(session_id = %session_id, filename = %filename, memory_type = %memory_type.as_str(), "Queued session artifact for indexing");
+        }
+        Err(e) => {
+            warn!(session_id = %session_id, filename = %filename, error = %e, "Failed to index session artifact");
+        }
+    }
+}
+
+// ============ EXTRACTION ============
+
+/// Map a semantic marker string (e.g. "[INSIGHT]") to a MemoryType.
+/// Returns None for unknown markers.
+fn marker_to_memory_type(marker: &str) -> Option<MemoryType> {
+    let normalized = marker.trim().trim_matches(|c| c == '[' || c == ']');
+    match normalized.to_uppercase().as_str() {
+        "INSIGHT" | "EVIDENCE" | "PATTERN" | "ASSUMPTION" => Some(MemoryType::Semantic),
+        "DECISION" | "RISK" => Some(MemoryType::Procedural),
+        "COUNTER" | "TENSION" | "THREAD" | "QUESTION" => Some(MemoryType::Episodic),
+        _ => None,
+    }
+}
+
+/// Extract marked claims, unresolved tensions, and thesis from a session
+/// and upsert them into Chroma's agentic memory collections.
+/// Best-effort: individual failures are logged and skipped.
+pub async fn extract_session_markers(session: &Session) {
+    let mut extracted = Vec::new();
+    let mut errors = 0u32;
+    let session_title = &session.title;
+
+    // Marked claims
+    for claim in &session.claims {
+        if let Some(ref marker) = claim.marker {
+            let memory_type = match marker_to_memory_type(marker) {
+                Some(mt) => mt,
+                None => continue,
+            };
+            let id = format!("{}::{}", session.id, claim.id);
+            let doc = format!("{} {} -- from session \"{}\"", marker, claim.content, session_title);
+            let mut metadata = json!({
+                "session_id": session.id,
+                "session_title": session_title,
+                "claim_id": claim.id,
+                "marker": marker,
+                "source_type": "claim",
+            });
+            add_tag_fields(&mut metadata, &session.tags);
+            add_entity_fields(&mut metadata, &claim.content);
+            match queue_memory(memory_type, &id, &doc, Some(pending_review(metadata))).await {
+                Ok(()) => extracted.push((id, memory_type)),
+                Err(e) => {
+                    warn!(claim_id = %claim.id, error = %e, "Failed to extract claim to memory");
+                    errors += 1;
+                }
+            }
+        }
+    }
+
+    // Unresolved tensions
+    for tension in &session.tensions {
+        if tension.resolution.is_some() {
+            continue;
+        }
+        let id = format!("{}::tension::{}", session.id, tension.id);
+        let doc = format!("[TENSION] Unresolved: {} -- from session \"{}\"", tension.description, session_title);
+        let mut metadata = json!({
+            "session_id": session.id,
+            "session_title": session_title,
+            "tension_id": tension.id,
+            "claim_a_id": tension.claim_a_id,
+            "claim_b_id": tension.claim_b_id,
+            "source_type": "tension",
+        });
+        add_tag_fields(&mut metadata, &session.tags);
+        match queue_memory(MemoryType::Episodic, &id, &doc, Some(pending_review(metadata))).await {
+            Ok(()) => extracted.push((id, MemoryType::Episodic)),
+            Err(e) => {
+                warn!(tension_id = %tension.id, error = %e, "Failed to extract tension to memory");
+                errors += 1;
+            }
+        }
+    }
+
+    // Thesis (only if confidence >= 0.5)
+    if let Some(ref thesis) = session.thesis {
+        if thesis.confidence >= 0.5 {
+            let id = format!("{}::thesis", session.id);
+            let doc = format!(
+                "Thesis (confidence: {:.0}%): {} -- from session \"{}\"",
+                thesis.confidence * 100.0,
+                thesis.content,
+                session_title,
+            );
+            let mut metadata = json!({
+                "session_id": session.id,
+                "session_title": session_title,
+                "confidence": thesis.confidence,
+                "source_type": "thesis",
+            });
+            add_tag_fields(&mut metadata, &session.tags);
+            match queue_memory(MemoryType::Semantic, &id, &doc, Some(pending_review(metadata))).await {
+                Ok(()) => extracted.push((id, MemoryType::Semantic)),
+                Err(e) => {
+                    warn!(error = %e, "Failed to extract thesis to memory");
+                    errors += 1;
+                }
+            }
+        }
+    }
+
+    provenance::record_session(session, &extracted).await;
+    info!(
+        session_id = %session.id,
+        extracted = extracted.len(),
+        errors = errors,
+        "Session marker extraction complete"
+    );
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub async fn chroma_write_memory(
+    memory_type: String,
+    id: String,
+    content: String,
+    metadata: Option<Value>,
+) -> Result<(), MemoryError> {
+    let mt = MemoryType::from_str(&memory_type)?;
+    write_memory(mt, &id, &content, metadata).await
+}
+
+#[tauri::command]
+pub async fn chroma_read_memories(
+    memory_type: String,
+    query: String,
+    n_results: u32,
+    include_unreviewed: Option<bool>,
+) -> Result<Vec<MemoryRecord>, MemoryError> {
+    let mt = MemoryType::from_str(&memory_type)?;
+    read_memories(mt, &query, n_results, include_unreviewed.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn record_memory_feedback(id: String, useful: bool) -> Result<f64, MemoryError> {
+    record_feedback(&id, useful).await
+}
+
+#[tauri::command]
+pub async fn list_pending_memories(memory_type: Option<String>) -> Result<Vec<MemoryRecord>, MemoryError> {
+    let mt = memory_type.as_deref().map(MemoryType::from_str).transpose()?;
+    list_pending(mt).await
+}
+
+#[tauri::command]
+pub async fn approve_memory(memory_type: String, id: String) -> Result<(), MemoryError> {
+    let mt = MemoryType::from_str(&memory_type)?;
+    set_review_status(mt, &id, ReviewStatus::Approved).await
+}
+
+#[tauri::command]
+pub async fn reject_memory(memory_type: String, id: String) -> Result<(), MemoryError> {
+    let mt = MemoryType::from_str(&memory_type)?;
+    set_review_status(mt, &id, ReviewStatus::Rejected).await
+}
+
+#[tauri::command]
+pub async fn chroma_list_memories(
+    memory_type: String,
+    limit: Option<u32>,
+) -> Result<Vec<MemoryRecord>, MemoryError> {
+    let mt = MemoryType::from_str(&memory_type)?;
+    list_memories(mt, limit).await
+}
+
+#[tauri::command]
+pub async fn chroma_delete_memory(
+    memory_type: String,
+    id: String,
+) -> Result<(), MemoryError> {
+    let mt = MemoryType::from_str(&memory_type)?;
+    delete_memory(mt, &id).await
+}
+
+#[tauri::command]
+pub async fn chroma_delete_memories_where(memory_type: String, filter: MemoryFilter) -> Result<usize, MemoryError> {
+    let mt = MemoryType::from_str(&memory_type)?;
+    delete_memories_where(mt, &filter).await
+}
+
+#[tauri::command]
+pub async fn chroma_clear_memories(memory_type: String) -> Result<(), MemoryError> {
+    let mt = MemoryType::from_str(&memory_type)?;
+    clear_memories(mt).await
+}
+
+/// Provenance chain of a memory, from its session down to its sources
+#[tauri::command]
+pub async fn chroma_trace_memory(id: String) -> Result<provenance::MemoryTrace, MemoryError> {
+    provenance::trace_memory(&id).await
+}
+
+#[tauri::command]
+pub async fn chroma_get_memory_stats() -> Result<MemoryStats, MemoryError> {
+    get_memory_stats().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_filter_conditions() {
+        assert!(MemoryFilter { keyword: Some("  ".to_string()), ..Default::default() }.is_empty());
+
+        let filter = MemoryFilter {
+            session_id: Some("s1".to_string()),
+            marker: Some("[RISK]".to_string()),
+            created_after: Some("2026-01-01T00:00:00Z".parse().unwrap()),
+            keyword: Some("Tariff".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(filter.where_clause().unwrap()["$and"][1]["marker"]["$eq"], "[RISK]");
+        let meta = |created: &str| json!({ "created_at": created });
+        assert!(filter.matches("[RISK] tariffs bite", &meta("2026-03-01T12:00:00+00:00")));
+        assert!(!filter.matches("[RISK] tariffs bite", &meta("2025-12-31T23:59:59+00:00")));
+        assert!(!filter.matches("[RISK] margins", &meta("2026-03-01T12:00:00+00:00")));
+        assert!(!filter.matches("[RISK] tariffs", &json!({})));
+
+        let by_session = MemoryFilter { session_id: Some("s1".to_string()), ..Default::default() };
+        assert_eq!(by_session.where_clause().unwrap(), json!({ "session_id": { "$eq": "s1" } }));
+        assert!(by_session.matches("anything", &json!({})));
+    }
+
+    #[test]
+    fn test_feedback_moves_usefulness_and_ranking() {
+        let mut usefulness = 0.0;
+        for _ in 0..3 {
+            usefulness = apply_feedback(usefulness, false);
+        }
+        assert!((-1.0..-0.6).contains(&usefulness));
+        assert!(apply_feedback(usefulness, true) > usefulness);
+        assert!((apply_feedback(1.0, true) - 1.0).abs() < 1e-9);
+
+        // An unhelpful close match falls behind a neutral weaker one
+        let unhelpful = ranked_relevance(0.6, &json!({ "usefulness": usefulness }));
+        let neutral = ranked_relevance(0.45, &json!({}));
+        assert!(unhelpful < neutral);
+        assert_eq!(neutral, 0.45);
+    }
+
+    #[tokio::test]
+    async fn test_review_filters_and_queue() {
+        use crate::chroma::embedded::EmbeddedStore;
+
+        let root = std::env::temp_dir().join(format!("dialectic_review_{}", ulid::Ulid::new()));
+        let store = EmbeddedStore::new(root.clone());
+        let collection = store.get_or_create_collection("memory_semantic", None).await.unwrap();
+        let status = |s: &str| json!({ "type": "semantic", REVIEW_STATUS_KEY: s });
+        store
+            .upsert(
+                &collection.id,
+                ["legacy", "approved", "pending", "rejected"].map(str::to_string).to_vec(),
+                Some(["rates", "rates rise", "rates fall", "rates flat"].map(str::to_string).to_vec()),
+                None,
+                Some(vec![json!({ "type": "semantic" }), status("approved"), status("pending"), status("rejected")]),
+            )
+            .await
+            .unwrap();
+        let ids = |filter: Value| {
+            let store = store.clone();
+            let id = collection.id.clone();
+            async move { store.get(&id, None, Some(filter), None, None, None, None).await.unwrap().ids }
+        };
+
+        // Default reads keep approved memories and those written before review existed
+        assert_eq!(ids(approved_filter()).await, vec!["approved", "legacy"]);
+        let hits = store
+            .query(&collection.id, Some(embed_query("rates")), None, 4, Some(approved_filter()), None, None)
+            .await
+            .unwrap();
+        assert_eq!(hits.ids[0].len(), 2);
+        assert_eq!(ids(pending_filter()).await, vec!["pending"]);
+
+        // Approving takes a memory out of the queue and into default reads
+        set_review_status_in(&store, MemoryType::Semantic, "pending", ReviewStatus::Approved).await.unwrap();
+        assert!(ids(pending_filter()).await.is_empty());
+        assert_eq!(ids(approved_filter()).await, vec!["approved", "legacy", "pending"]);
+
+        let record = |metadata: Value| MemoryRecord {
+            id: "m".to_string(),
+            memory_type: MemoryType::Semantic,
+            content: String::new(),
+            metadata,
+            relevance: None,
+        };
+        assert_eq!(record(json!({})).review_status(), ReviewStatus::Approved);
+        assert_eq!(record(pending_review(json!({}))).review_status(), ReviewStatus::Pending);
+        assert_eq!(record(status("rejected")).review_status(), ReviewStatus::Rejected);
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[tokio::test]
+    async fn test_record_feedback_persists_usefulness() {
+        use crate::chroma::embedded::EmbeddedStore;
+
+        let root = std::env::temp_dir().join(format!("dialectic_feedback_{}", ulid::Ulid::new()));
+        let store = EmbeddedStore::new(root.clone());
+        // Looked up across types, so the memory need not be semantic
+        let collection = store.get_or_create_collection(COLLECTION_MEMORY_PROCEDURAL, None).await.unwrap();
+        store
+            .upsert(
+                &collection.id,
+                vec!["m1".to_string()],
+                Some(vec!["run the migration before deploy".to_string()]),
+                None,
+                Some(vec![json!({ "type": "procedural" })]),
+            )
+            .await
+            .unwrap();
+
+        let score = record_feedback_in(&store, "m1", false).await.unwrap();
+        assert!(score < 0.0);
+        let stored = store
+            .get(&collection.id, Some(vec!["m1".to_string()]), None, None, None, None, Some(vec!["metadatas".to_string()]))
+            .await
+            .unwrap();
+        let metadata = stored.metadatas.unwrap().remove(0).unwrap();
+        assert_eq!(metadata[USEFULNESS_KEY].as_f64(), Some(score));
+        assert_eq!(metadata["type"], "procedural");
+
+        assert!(matches!(record_feedback_in(&store, "missing", true).await, Err(MemoryError::NotFound(_))));
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,269 @@
+//! Memory Provenance
+//!
+//! Where a recalled memory came from: the session it was extracted from,
+//! the claim, tension or thesis it restates, and the sources behind those
+//! claims (reference document chunk, vault note, web source, file or
+//! transcript turn). Chains are snapshotted to
+//! `<app data>/memory_provenance.json` when session markers are extracted,
+//! so they survive later edits to the session; memories without a snapshot
+//! are traced live from the session named in their ID.
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+use super::{MemoryError, MemoryType};
+use crate::config::workspace::effective_preferences;
+use crate::session::citations::{collect_citations, CitationEntry};
+use crate::session::claim_source::SourceSpan;
+use crate::session::{get_app_data_dir_cli, load_session_cli, Claim, Session};
+
+const PROVENANCE_FILE: &str = "memory_provenance.json";
+
+/// Serializes read-modify-write cycles of the provenance file
+static PROVENANCE_LOCK: Mutex<()> = Mutex::new(());
+
+/// One link in a memory's provenance chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", rename_all_fields = "camelCase")]
+pub enum ProvenanceNode {
+    Session { session_id: String, title: String },
+    Claim { claim_id: String, content: String, marker: Option<String> },
+    Tension { tension_id: String, description: String, resolved: bool },
+    Thesis { content: String, confidence: f32 },
+    Artifact { name: String },
+    /// Where a claim was drawn from; `span` locates it exactly when known
+    Source {
+        reference: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        span: Option<SourceSpan>,
+    },
+}
+
+/// A node and the nodes it was derived from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvenanceStep {
+    pub node: ProvenanceNode,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub derived_from: Vec<ProvenanceStep>,
+}
+
+/// Provenance of one memory record, rooted at its session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryTrace {
+    pub memory_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_type: Option<MemoryType>,
+    /// When the chain was snapshotted; `None` when traced live
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recorded_at: Option<DateTime<Utc>>,
+    pub chain: ProvenanceStep,
+}
+
+fn step(node: ProvenanceNode, derived_from: Vec<ProvenanceStep>) -> ProvenanceStep {
+    ProvenanceStep { node, derived_from }
+}
+
+/// A claim and the source it cites
+fn claim_step(claim: &Claim, sources: &HashMap<&str, &CitationEntry>) -> ProvenanceStep {
+    let cited = sources.get(claim.id.as_str());
+    let source = match (cited, &claim.source_span) {
+        (Some(entry), span) => Some(ProvenanceNode::Source {
+            reference: entry.reference.clone(),
+            url: entry.url.clone(),
+            span: span.clone(),
+        }),
+        (None, Some(span @ SourceSpan::Transcript { conversation_id, turn })) => Some(ProvenanceNode::Source {
+            reference: match conversation_id {
+                Some(id) => format!("Transcript {} turn {}", id, turn),
+                None => format!("Transcript turn {}", turn),
+            },
+            url: None,
+            span: Some(span.clone()),
+        }),
+        (None, _) => None,
+    };
+    step(
+        ProvenanceNode::Claim { claim_id: claim.id.clone(), content: claim.content.clone(), marker: claim.marker.clone() },
+        source.map(|source| step(source, Vec::new())).into_iter().collect(),
+    )
+}
+
+/// Provenance chain of a memory extracted from `session`, by its ID:
+/// `{session}::{claim}`, `{session}::tension::{id}`, `{session}::thesis`
+/// or `{session}::artifact::{name}`
+fn chain_from_session(memory_id: &str, session: &Session, citations: &[CitationEntry]) -> Option<ProvenanceStep> {
+    let rest = memory_id.strip_prefix(&session.id)?.strip_prefix("::")?;
+    let sources: HashMap<&str, &CitationEntry> = citations
+        .iter()
+        .flat_map(|entry| entry.claim_ids.iter().map(move |id| (id.as_str(), entry)))
+        .collect();
+    let claim = |id: &str| session.claims.iter().find(|c| c.id == id).map(|c| claim_step(c, &sources));
+
+    let origin = if let Some(tension_id) = rest.strip_prefix("tension::") {
+        let tension = session.tensions.iter().find(|t| t.id == tension_id)?;
+        step(
+            ProvenanceNode::Tension {
+                tension_id: tension.id.clone(),
+                description: tension.description.clone(),
+                resolved: tension.resolution.is_some(),
+            },
+            [&tension.claim_a_id, &tension.claim_b_id].into_iter().filter_map(|id| claim(id)).collect(),
+        )
+    } else if let Some(name) = rest.strip_prefix("artifact::") {
+        step(ProvenanceNode::Artifact { name: name.to_string() }, Vec::new())
+    } else if rest == "thesis" {
+        let thesis = session.thesis.as_ref()?;
+        step(
+            ProvenanceNode::Thesis { content: thesis.content.clone(), confidence: thesis.confidence },
+            session.claims.iter().map(|c| claim_step(c, &sources)).collect(),
+        )
+    } else {
+        claim(rest)?
+    };
+
+    Some(step(
+        ProvenanceNode::Session { session_id: session.id.clone(), title: session.title.clone() },
+        vec![origin],
+    ))
+}
+
+async fn session_citations(session: &Session) -> Vec<CitationEntry> {
+    let prefs = effective_preferences(Path::new(&session.working_dir)).0;
+    collect_citations(session, prefs.vault_path.as_deref().map(Path::new)).await
+}
+
+fn provenance_path() -> Option<PathBuf> {
+    get_app_data_dir_cli().ok().map(|dir| dir.join(PROVENANCE_FILE))
+}
+
+fn read_traces(path: &Path) -> BTreeMap<String, MemoryTrace> {
+    match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!(error = %e, "Unreadable memory provenance file");
+            BTreeMap::new()
+        }),
+        Err(_) => BTreeMap::new(),
+    }
+}
+
+/// Apply `f` to the stored traces and write them back (best-effort)
+fn update_traces(f: impl FnOnce(&mut BTreeMap<String, MemoryTrace>)) {
+    let Some(path) = provenance_path() else { return };
+    let _guard = PROVENANCE_LOCK.lock();
+    let mut traces = read_traces(&path);
+    f(&mut traces);
+    let tmp = path.with_extension("tmp");
+    let written = serde_json::to_string(&traces)
+        .map_err(std::io::Error::from)
+        .and_then(|json| fs::write(&tmp, json))
+        .and_then(|_| fs::rename(&tmp, &path));
+    if let Err(e) = written {
+        warn!(error = %e, "Failed to write memory provenance");
+    }
+}
+
+/// Snapshot the chains of memories just extracted from `session`
+pub async fn record_session(session: &Session, memories: &[(String, MemoryType)]) {
+    if memories.is_empty() {
+        return;
+    }
+    let citations = session_citations(session).await;
+    let now = Utc::now();
+    let recorded: Vec<MemoryTrace> = memories
+        .iter()
+        .filter_map(|(id, memory_type)| {
+            Some(MemoryTrace {
+                memory_id: id.clone(),
+                memory_type: Some(*memory_type),
+                recorded_at: Some(now),
+                chain: chain_from_session(id, session, &citations)?,
+            })
+        })
+        .collect();
+    debug!(session_id = %session.id, count = recorded.len(), "Recorded memory provenance");
+    update_traces(|traces| {
+        for trace in recorded {
+            traces.insert(trace.memory_id.clone(), trace);
+        }
+    });
+}
+
+/// Drop the snapshots of deleted memories
+pub fn forget(matches: impl Fn(&MemoryTrace) -> bool) {
+    update_traces(|traces| traces.retain(|_, trace| !matches(trace)));
+}
+
+/// Where a memory came from: its snapshotted chain, else one traced live
+/// from the session its ID names
+pub async fn trace_memory(id: &str) -> Result<MemoryTrace, MemoryError> {
+    if let Some(trace) = provenance_path().and_then(|path| read_traces(&path).remove(id)) {
+        return Ok(trace);
+    }
+    let session_id = id.split("::").next().unwrap_or_default();
+    let session = load_session_cli(session_id).map_err(|_| MemoryError::NotFound(id.to_string()))?;
+    let citations = session_citations(&session).await;
+    let chain = chain_from_session(id, &session, &citations).ok_or_else(|| MemoryError::NotFound(id.to_string()))?;
+    Ok(MemoryTrace { memory_id: id.to_string(), memory_type: None, recorded_at: None, chain })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use crate::session::citations::CitationKind;
+    use serde_json::json;
+
+    #[test]
+    fn test_chains_follow_claims_to_sources() {
+        let now = Utc::now();
+        let session: Session = test_session(json!({
+            "id": "prov", "title": "Moats", "status": "formed",
+            "created": now, "updated": now,
+            "claims": [
+                { "id": "c1", "content": "Margins hold", "sourceId": "s", "marker": "[INSIGHT]", "createdAt": now,
+                  "sourceSpan": { "kind": "document", "docId": "d1", "chunkIndex": 2 } },
+                { "id": "c2", "content": "Churn rises", "sourceId": "s", "marker": null, "createdAt": now,
+                  "sourceSpan": { "kind": "transcript", "turn": 4 } },
+            ],
+            "tensions": [
+                { "id": "t1", "claimAId": "c1", "claimBId": "c2", "description": "Pricing vs churn", "resolution": null, "createdAt": now },
+            ],
+        }));
+        let citations = vec![CitationEntry {
+            number: 1,
+            kind: CitationKind::Document,
+            reference: "porter.md".to_string(),
+            url: None,
+            claim_ids: vec!["c1".to_string()],
+        }];
+
+        let chain = chain_from_session("prov::c1", &session, &citations).unwrap();
+        assert!(matches!(&chain.node, ProvenanceNode::Session { title, .. } if title == "Moats"));
+        let claim = &chain.derived_from[0];
+        assert!(matches!(&claim.node, ProvenanceNode::Claim { claim_id, .. } if claim_id == "c1"));
+        assert!(matches!(
+            &claim.derived_from[0].node,
+            ProvenanceNode::Source { reference, span: Some(SourceSpan::Document { chunk_index: 2, .. }), .. } if reference == "porter.md"
+        ));
+
+        let tension = &chain_from_session("prov::tension::t1", &session, &citations).unwrap().derived_from[0];
+        assert_eq!(tension.derived_from.len(), 2);
+        assert!(matches!(
+            &tension.derived_from[1].derived_from[0].node,
+            ProvenanceNode::Source { reference, .. } if reference == "Transcript turn 4"
+        ));
+
+        assert!(chain_from_session("prov::thesis", &session, &citations).is_none());
+        assert!(chain_from_session("other::c1", &session, &citations).is_none());
+        assert!(chain_from_session("prov::missing", &session, &citations).is_none());
+    }
+}
@@ -1,11 +1,20 @@
 //! Chroma Vector Database Integration
 //!
 //! Manages a Chroma sidecar process and provides semantic search,
-//! agentic memory, and collection management for Dialectic.
+//! agentic memory, and collection management for Dialectic. Collection
+//! operations go through the `VectorStore` trait so an embedded store
+//! or Qdrant can stand in for Chroma. Bursty best-effort writes (artifact
+//! indexing, marker extraction, agent memory writes) go through
+//! `write_queue`.
 
+pub mod breaker;
 pub mod sidecar;
 pub mod client;
+pub mod store;
+pub mod embedded;
+pub mod qdrant;
 pub mod collections;
 pub mod search;
 pub mod memory;
 pub mod jsonl_miner;
+pub mod write_queue;
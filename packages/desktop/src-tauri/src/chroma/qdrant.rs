@@ -0,0 +1,487 @@
+//! Qdrant Backend
+//!
+//! `VectorStore` over Qdrant's REST API for users who already run Qdrant.
+//! Collections map to `dialectic_<name>`, so Dialectic's data sits beside
+//! the user's own collections without clashing. Qdrant point IDs must be
+//! integers or UUIDs, so each record ID is hashed to a stable UUID and the
+//! original ID and document text are kept in the payload next to the
+//! metadata fields. Chroma `where` filters are translated to Qdrant
+//! filters, and Euclid scores are squared to match Chroma's distances.
+
+use reqwest::{Client, RequestBuilder, Response};
+use serde_json::{json, Map, Value};
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+use super::client::{
+    embed_documents, embed_query, shared_http, ChromaError, ChromaGetResult, ChromaQueryResult, CollectionInfo,
+};
+use super::collections::EMBEDDING_DIM_KEY;
+use super::store::VectorStore;
+use crate::documents::embeddings::EMBEDDING_DIM;
+
+/// Used when `qdrantUrl` is unset
+pub const DEFAULT_QDRANT_URL: &str = "http://127.0.0.1:6333";
+/// Prefix for Dialectic's collections on a shared server
+const COLLECTION_PREFIX: &str = "dialectic_";
+/// Payload key holding the record ID
+const ID_KEY: &str = "_id";
+/// Payload key holding the document text
+const DOCUMENT_KEY: &str = "_document";
+/// Delay between probes in `wait_until_healthy`
+const HEALTH_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone)]
+pub struct QdrantClient {
+    http: Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+/// FNV-1a over `bytes` starting from `seed`
+fn fnv1a(seed: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(seed, |hash, b| (hash ^ *b as u64).wrapping_mul(0x100000001b3))
+}
+
+/// Stable UUID-formatted point ID for a record ID
+pub fn point_id(id: &str) -> String {
+    let hi = fnv1a(0xcbf29ce484222325, id.as_bytes());
+    let lo = fnv1a(0x84222325cbf29ce4, id.as_bytes());
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        hi >> 32,
+        (hi >> 16) & 0xffff,
+        hi & 0xffff,
+        lo >> 48,
+        lo & 0xffff_ffff_ffff
+    )
+}
+
+fn collection_name(name: &str) -> String {
+    format!("{}{}", COLLECTION_PREFIX, name)
+}
+
+/// `{"key": .., "match": {"value": ..}}`, or an exact range for floats
+/// (Qdrant only matches keywords, integers and booleans)
+fn match_value(key: &str, value: &Value) -> Value {
+    match value.as_f64() {
+        Some(f) if !value.is_i64() && !value.is_u64() => json!({ "key": key, "range": { "gte": f, "lte": f } }),
+        _ => json!({ "key": key, "match": { "value": value } }),
+    }
+}
+
+/// Translate one field's condition into Qdrant `must`/`must_not` clauses
+fn field_condition(key: &str, condition: &Value, must: &mut Vec<Value>, must_not: &mut Vec<Value>) {
+    let Some(ops) = condition.as_object().filter(|ops| ops.keys().all(|k| k.starts_with('$'))) else {
+        must.push(match_value(key, condition));
+        return;
+    };
+    for (op, value) in ops {
+        match op.as_str() {
+            "$eq" => must.push(match_value(key, value)),
+            "$ne" => must_not.push(match_value(key, value)),
+            "$in" => must.push(json!({ "key": key, "match": { "any": value } })),
+            // `must_not` + `any` rather than `except`, so points without the
+            // key still match (as `$ne` and Chroma's `$nin` do)
+            "$nin" => must_not.push(json!({ "key": key, "match": { "any": value } })),
+            "$gt" | "$gte" | "$lt" | "$lte" => must.push(json!({ "key": key, "range": { &op[1..]: value } })),
+            _ => {}
+        }
+    }
+}
+
+/// Translate a Chroma `where` filter into a Qdrant filter
+pub fn translate_where(filter: &Value) -> Value {
+    let mut must = Vec::new();
+    let mut must_not = Vec::new();
+    for (key, condition) in filter.as_object().into_iter().flatten() {
+        match key.as_str() {
+            "$and" => must.extend(condition.as_array().into_iter().flatten().map(translate_where)),
+            "$or" => must.push(json!({ "should": condition.as_array().into_iter().flatten().map(translate_where).collect::<Vec<_>>() })),
+            field => field_condition(field, condition, &mut must, &mut must_not),
+        }
+    }
+    json!({ "must": must, "must_not": must_not })
+}
+
+/// Translate a Chroma `where_document` filter into a Qdrant filter
+pub fn translate_where_document(filter: &Value) -> Value {
+    let mut must = Vec::new();
+    let mut must_not = Vec::new();
+    for (op, value) in filter.as_object().into_iter().flatten() {
+        let text = json!({ "key": DOCUMENT_KEY, "match": { "text": value } });
+        match op.as_str() {
+            "$contains" => must.push(text),
+            "$not_contains" => must_not.push(text),
+            "$and" => must.extend(value.as_array().into_iter().flatten().map(translate_where_document)),
+            "$or" => must.push(json!({ "should": value.as_array().into_iter().flatten().map(translate_where_document).collect::<Vec<_>>() })),
+            _ => {}
+        }
+    }
+    json!({ "must": must, "must_not": must_not })
+}
+
+/// Combine optional `where` and `where_document` filters
+fn combined_filter(where_filter: Option<&Value>, where_document: Option<&Value>) -> Option<Value> {
+    let parts: Vec<Value> = where_filter
+        .map(translate_where)
+        .into_iter()
+        .chain(where_document.map(translate_where_document))
+        .collect();
+    (!parts.is_empty()).then(|| json!({ "must": parts }))
+}
+
+/// Record ID, document and metadata from a point's payload
+fn split_payload(payload: Option<&Value>) -> (Option<String>, Option<String>, Option<Value>) {
+    let mut fields = payload.and_then(Value::as_object).cloned().unwrap_or_default();
+    let id = fields.remove(ID_KEY).and_then(|v| v.as_str().map(str::to_string));
+    let document = fields.remove(DOCUMENT_KEY).and_then(|v| v.as_str().map(str::to_string));
+    (id, document, (!fields.is_empty()).then_some(Value::Object(fields)))
+}
+
+impl QdrantClient {
+    pub fn new(base_url: &str, api_key: Option<String>) -> Self {
+        Self { http: shared_http(), base_url: base_url.trim_end_matches('/').to_string(), api_key }
+    }
+
+    fn request(&self, build: impl FnOnce(&Client) -> RequestBuilder) -> RequestBuilder {
+        let builder = build(&self.http);
+        match &self.api_key {
+            Some(key) => builder.header("api-key", key),
+            None => builder,
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// The `result` field of a successful response
+    async fn result(resp: Response, what: &str) -> Result<Value, ChromaError> {
+        let status = resp.status();
+        let text = resp.text().await?;
+        if !status.is_success() {
+            return Err(ChromaError::Http(format!("{} failed ({}): {}", what, status, text)));
+        }
+        let body: Value = serde_json::from_str(&text).map_err(|e| ChromaError::Deserialize(format!("{}: {}", e, text)))?;
+        Ok(body["result"].clone())
+    }
+
+    /// POST to `/collections/<collection>/<action>`
+    async fn post(&self, collection: &str, action: &str, body: Value, what: &str) -> Result<Value, ChromaError> {
+        let path = format!("/collections/{}/{}", collection_name(collection), action);
+        let resp = self.request(|http| http.post(self.url(&path)).json(&body)).send().await?;
+        if resp.status().as_u16() == 404 {
+            return Err(ChromaError::CollectionNotFound(collection.to_string()));
+        }
+        Self::result(resp, what).await
+    }
+
+    /// Points matching `filter`, paging through `scroll` until `limit` after skipping `skip`
+    async fn scroll(
+        &self,
+        collection: &str,
+        filter: Option<Value>,
+        skip: usize,
+        limit: Option<usize>,
+        with_vector: bool,
+    ) -> Result<Vec<Value>, ChromaError> {
+        const PAGE: usize = 256;
+        let mut points = Vec::new();
+        let mut offset = Value::Null;
+        loop {
+            let mut body = json!({ "limit": PAGE, "with_payload": true, "with_vector": with_vector });
+            if let Some(filter) = &filter {
+                body["filter"] = filter.clone();
+            }
+            if !offset.is_null() {
+                body["offset"] = offset.clone();
+            }
+            let result = self.post(collection, "points/scroll", body, "Scroll").await?;
+            points.extend(result["points"].as_array().cloned().unwrap_or_default());
+            offset = result["next_page_offset"].clone();
+            let enough = limit.is_some_and(|limit| points.len() >= skip + limit);
+            if offset.is_null() || enough {
+                break;
+            }
+        }
+        Ok(points.into_iter().skip(skip).take(limit.unwrap_or(usize::MAX)).collect())
+    }
+}
+
+impl VectorStore for QdrantClient {
+    async fn heartbeat(&self) -> Result<i64, ChromaError> {
+        let resp = self
+            .request(|http| http.get(self.url("/healthz")))
+            .send()
+            .await
+            .map_err(|_| ChromaError::ServerUnavailable)?;
+        if !resp.status().is_success() {
+            return Err(ChromaError::ServerUnavailable);
+        }
+        Ok(chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default())
+    }
+
+    async fn wait_until_healthy(&self, timeout: Duration) -> Result<u32, ChromaError> {
+        let deadline = Instant::now() + timeout;
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            match self.heartbeat().await {
+                Ok(_) => return Ok(attempt),
+                Err(e) if Instant::now() >= deadline => return Err(e),
+                Err(_) => tokio::time::sleep(HEALTH_POLL_INTERVAL).await,
+            }
+        }
+    }
+
+    async fn get_or_create_collection(&self, name: &str, metadata: Option<Value>) -> Result<CollectionInfo, ChromaError> {
+        if let Ok(existing) = self.get_collection(name).await {
+            return Ok(existing);
+        }
+        let body = json!({ "vectors": { "size": EMBEDDING_DIM, "distance": "Euclid" } });
+        let path = format!("/collections/{}", collection_name(name));
+        let resp = self.request(|http| http.put(self.url(&path)).json(&body)).send().await?;
+        Self::result(resp, "Create collection").await?;
+        // Full-text index so `where_document` $contains works
+        let index = json!({ "field_name": DOCUMENT_KEY, "field_schema": "text" });
+        let resp = self.request(|http| http.put(self.url(&format!("{}/index", path))).json(&index)).send().await?;
+        Self::result(resp, "Create document index").await?;
+        info!(name = %name, "Created Qdrant collection");
+        Ok(CollectionInfo { id: name.to_string(), name: name.to_string(), metadata })
+    }
+
+    async fn get_collection(&self, name: &str) -> Result<CollectionInfo, ChromaError> {
+        let path = format!("/collections/{}", collection_name(name));
+        let resp = self.request(|http| http.get(self.url(&path))).send().await?;
+        if resp.status().as_u16() == 404 {
+            return Err(ChromaError::CollectionNotFound(name.to_string()));
+        }
+        let result = Self::result(resp, "Get collection").await?;
+        // Qdrant keeps no collection metadata; report the vector size so
+        // schema checks can still catch an embedding change
+        let metadata = result["config"]["params"]["vectors"]["size"]
+            .as_u64()
+            .map(|size| json!({ EMBEDDING_DIM_KEY: size }));
+        Ok(CollectionInfo { id: name.to_string(), name: name.to_string(), metadata })
+    }
+
+    async fn delete_collection(&self, name: &str) -> Result<(), ChromaError> {
+        let path = format!("/collections/{}", collection_name(name));
+        let resp = self.request(|http| http.delete(self.url(&path))).send().await?;
+        if resp.status().as_u16() == 404 {
+            return Ok(());
+        }
+        Self::result(resp, "Delete collection").await.map(|_| ())
+    }
+
+    /// Qdrant keeps no collection metadata
+    async fn set_collection_metadata(&self, _name: &str, _metadata: Value) -> Result<(), ChromaError> {
+        Ok(())
+    }
+
+    async fn list_collections(&self) -> Result<Vec<CollectionInfo>, ChromaError> {
+        let resp = self.request(|http| http.get(self.url("/collections"))).send().await?;
+        let result = Self::result(resp, "List collections").await?;
+        Ok(result["collections"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|c| c["name"].as_str()?.strip_prefix(COLLECTION_PREFIX).map(str::to_string))
+            .map(|name| CollectionInfo { id: name.clone(), name, metadata: None })
+            .collect())
+    }
+
+    async fn upsert(
+        &self,
+        collection_id: &str,
+        ids: Vec<String>,
+        documents: Option<Vec<String>>,
+        embeddings: Option<Vec<Vec<f32>>>,
+        metadatas: Option<Vec<Value>>,
+    ) -> Result<(), ChromaError> {
+        if ids.is_empty() {
+            return Err(ChromaError::InvalidInput("ids cannot be empty".to_string()));
+        }
+        let embeddings = match (embeddings, &documents) {
+            (Some(embeddings), _) => embeddings,
+            (None, Some(documents)) => embed_documents(documents),
+            (None, None) => return Err(ChromaError::InvalidInput("documents or embeddings are required".to_string())),
+        };
+        let points: Vec<Value> = ids
+            .iter()
+            .zip(embeddings)
+            .enumerate()
+            .map(|(i, (id, vector))| {
+                let mut payload = metadatas
+                    .as_ref()
+                    .and_then(|m| m.get(i))
+                    .and_then(Value::as_object)
+                    .cloned()
+                    .unwrap_or_else(Map::new);
+                payload.insert(ID_KEY.to_string(), json!(id));
+                if let Some(document) = documents.as_ref().and_then(|d| d.get(i)) {
+                    payload.insert(DOCUMENT_KEY.to_string(), json!(document));
+                }
+                json!({ "id": point_id(id), "vector": vector, "payload": payload })
+            })
+            .collect();
+        let path = format!("/collections/{}/points?wait=true", collection_name(collection_id));
+        let body = json!({ "points": points });
+        let resp = self.request(|http| http.put(self.url(&path)).json(&body)).send().await?;
+        Self::result(resp, "Upsert").await?;
+        debug!(collection = %collection_id, count = ids.len(), "Upserted Qdrant points");
+        Ok(())
+    }
+
+    async fn query(
+        &self,
+        collection_id: &str,
+        query_embeddings: Option<Vec<Vec<f32>>>,
+        query_texts: Option<Vec<String>>,
+        n_results: u32,
+        where_filter: Option<Value>,
+        where_document: Option<Value>,
+        _include: Option<Vec<String>>,
+    ) -> Result<ChromaQueryResult, ChromaError> {
+        let queries = match (query_embeddings, query_texts) {
+            (Some(embeddings), _) => embeddings,
+            (None, Some(texts)) => texts.iter().flat_map(|t| embed_query(t)).collect(),
+            (None, None) => return Err(ChromaError::InvalidInput("query embeddings or texts are required".to_string())),
+        };
+        let filter = combined_filter(where_filter.as_ref(), where_document.as_ref());
+
+        let mut result = ChromaQueryResult { ids: Vec::new(), documents: Some(Vec::new()), metadatas: Some(Vec::new()), distances: Some(Vec::new()) };
+        for vector in queries {
+            let mut body = json!({ "vector": vector, "limit": n_results, "with_payload": true });
+            if let Some(filter) = &filter {
+                body["filter"] = filter.clone();
+            }
+            let hits = self.post(collection_id, "points/search", body, "Search").await?;
+            let (mut ids, mut documents, mut metadatas, mut distances) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+            for hit in hits.as_array().into_iter().flatten() {
+                let (id, document, metadata) = split_payload(hit.get("payload"));
+                ids.push(id.unwrap_or_default());
+                documents.push(document);
+                metadatas.push(metadata);
+                let distance = hit["score"].as_f64().unwrap_or(f64::MAX) as f32;
+                distances.push(distance * distance);
+            }
+            result.ids.push(ids);
+            result.documents.get_or_insert_with(Vec::new).push(documents);
+            result.metadatas.get_or_insert_with(Vec::new).push(metadatas);
+            result.distances.get_or_insert_with(Vec::new).push(distances);
+        }
+        Ok(result)
+    }
+
+    async fn get(
+        &self,
+        collection_id: &str,
+        ids: Option<Vec<String>>,
+        where_filter: Option<Value>,
+        where_document: Option<Value>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+        include: Option<Vec<String>>,
+    ) -> Result<ChromaGetResult, ChromaError> {
+        let with_vector = include.as_ref().is_some_and(|inc| inc.iter().any(|i| i == "embeddings"));
+        let mut filter = combined_filter(where_filter.as_ref(), where_document.as_ref());
+        if let Some(ids) = &ids {
+            let has_id = json!({ "has_id": ids.iter().map(|id| point_id(id)).collect::<Vec<_>>() });
+            filter = Some(match filter {
+                Some(filter) => json!({ "must": [filter, has_id] }),
+                None => json!({ "must": [has_id] }),
+            });
+        }
+        let points = self
+            .scroll(collection_id, filter, offset.unwrap_or(0) as usize, limit.map(|l| l as usize), with_vector)
+            .await?;
+
+        let mut result = ChromaGetResult { ids: Vec::new(), documents: Some(Vec::new()), metadatas: Some(Vec::new()), embeddings: None };
+        for point in &points {
+            let (id, document, metadata) = split_payload(point.get("payload"));
+            result.ids.push(id.unwrap_or_default());
+            result.documents.get_or_insert_with(Vec::new).push(document);
+            result.metadatas.get_or_insert_with(Vec::new).push(metadata);
+            if with_vector {
+                let vector = serde_json::from_value(point["vector"].clone()).unwrap_or_default();
+                result.embeddings.get_or_insert_with(Vec::new).push(vector);
+            }
+        }
+        Ok(result)
+    }
+
+    async fn delete(&self, collection_id: &str, ids: Option<Vec<String>>, where_filter: Option<Value>) -> Result<(), ChromaError> {
+        let mut filter = where_filter.as_ref().map(translate_where).unwrap_or_else(|| json!({ "must": [] }));
+        if let Some(ids) = &ids {
+            let has_id = json!({ "has_id": ids.iter().map(|id| point_id(id)).collect::<Vec<_>>() });
+            filter = json!({ "must": [filter, has_id] });
+        }
+        self.post(collection_id, "points/delete?wait=true", json!({ "filter": filter }), "Delete").await?;
+        info!(collection = %collection_id, "Deleted from Qdrant collection");
+        Ok(())
+    }
+
+    async fn update_metadata(&self, collection_id: &str, ids: Vec<String>, metadatas: Vec<Value>) -> Result<(), ChromaError> {
+        // Setting payload merges keys, so each record needs its own request
+        for (id, metadata) in ids.iter().zip(metadatas) {
+            let body = json!({ "payload": metadata, "points": [point_id(id)] });
+            self.post(collection_id, "points/payload?wait=true", body, "Update payload").await?;
+        }
+        Ok(())
+    }
+
+    async fn count(&self, collection_id: &str) -> Result<u32, ChromaError> {
+        let result = self.post(collection_id, "points/count", json!({ "exact": true }), "Count").await?;
+        Ok(result["count"].as_u64().unwrap_or(0) as u32)
+    }
+
+    fn persists_collection_metadata(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_filters_and_point_ids() {
+        let filter = json!({
+            "$and": [
+                { "session_id": { "$eq": "s1" } },
+                { "tag:macro": true },
+                { "confidence": { "$gte": 0.5 } },
+                { "kind": { "$nin": ["draft"] } },
+            ]
+        });
+        assert_eq!(
+            translate_where(&filter),
+            json!({ "must": [
+                { "must": [{ "key": "session_id", "match": { "value": "s1" } }], "must_not": [] },
+                { "must": [{ "key": "tag:macro", "match": { "value": true } }], "must_not": [] },
+                { "must": [{ "key": "confidence", "range": { "gte": 0.5 } }], "must_not": [] },
+                { "must": [], "must_not": [{ "key": "kind", "match": { "any": ["draft"] } }] },
+            ], "must_not": [] })
+        );
+        assert_eq!(
+            translate_where(&json!({ "status": { "$ne": "stale" } })),
+            json!({ "must": [], "must_not": [{ "key": "status", "match": { "value": "stale" } }] })
+        );
+        assert_eq!(
+            translate_where_document(&json!({ "$contains": "rates" })),
+            json!({ "must": [{ "key": DOCUMENT_KEY, "match": { "text": "rates" } }], "must_not": [] })
+        );
+
+        let id = point_id("src_abc");
+        assert_eq!(id, point_id("src_abc"));
+        assert_ne!(id, point_id("src_abd"));
+        assert_eq!(id.len(), 36);
+        assert_eq!(id.matches('-').count(), 4);
+
+        let (id, document, metadata) = split_payload(Some(&json!({ "_id": "a", "_document": "text", "session_id": "s1" })));
+        assert_eq!((id.as_deref(), document.as_deref()), (Some("a"), Some("text")));
+        assert_eq!(metadata, Some(json!({ "session_id": "s1" })));
+    }
+}
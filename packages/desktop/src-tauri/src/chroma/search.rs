@@ -9,8 +9,10 @@ use serde_json::Value;
 use thiserror::Error;
 use tracing::{info, warn, debug};
 
-use super::client::{ChromaClient, ChromaError, get_client, embed_query};
+use super::client::{ChromaError, get_client, embed_query};
+use super::store::VectorStore;
 use super::collections::*;
+use crate::documents::snippets::{extract_snippet, Snippet};
 
 #[derive(Error, Debug)]
 pub enum SearchError {
@@ -20,6 +22,8 @@ pub enum SearchError {
     NoResults,
     #[error("Search not initialized")]
     NotInitialized,
+    #[error("Search cancelled")]
+    Cancelled,
 }
 
 impl Serialize for SearchError {
@@ -41,6 +45,9 @@ pub struct SearchHit {
     pub metadata: Value,
     pub distance: f32,
     pub relevance: f32,
+    /// Best-matching excerpt of `document` with query highlights
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<Snippet>,
 }
 
 /// Combined search results across collections
@@ -54,7 +61,7 @@ pub struct SearchResults {
 
 /// Search a single collection using embeddings generated by Chroma
 async fn search_collection(
-    client: &ChromaClient,
+    client: &impl VectorStore,
     collection_name: &str,
     query_texts: Vec<String>,
     n_results: u32,
@@ -110,6 +117,8 @@ async fn search_collection(
             // Convert distance to relevance (lower distance = higher relevance)
             // Chroma uses L2 distance by default; convert to 0-1 similarity
             let relevance = 1.0 / (1.0 + distance);
+            let snippet = query_texts.get(query_idx)
+                .and_then(|q| extract_snippet(&document, q));
 
             hits.push(SearchHit {
                 id: id.clone(),
@@ -118,6 +127,7 @@ async fn search_collection(
                 metadata,
                 distance,
                 relevance,
+                snippet,
             });
         }
     }
@@ -131,6 +141,8 @@ pub async fn search_all(
     n_results: u32,
     session_filter_value: Option<Value>,
     collections: Option<Vec<String>>,
+    tag: Option<&str>,
+    entity: Option<&str>,
 ) -> Result<SearchResults, SearchError> {
     let client = get_client();
 
@@ -143,6 +155,7 @@ pub async fn search_all(
             COLLECTION_MEMORY_PROCEDURAL.to_string(),
             COLLECTION_MEMORY_EPISODIC.to_string(),
             COLLECTION_WEB_SOURCES.to_string(),
+            COLLECTION_CODE_CONTEXT.to_string(),
         ]
     });
 
@@ -153,11 +166,16 @@ pub async fn search_all(
     let futures: Vec<_> = target_collections.iter().map(|collection_name| {
         let client = client.clone();
         let query = query.to_string();
-        let filter = if collection_name == COLLECTION_DOCUMENTS {
+        // Documents and code context are scoped to the session
+        let filter = if collection_name == COLLECTION_DOCUMENTS || collection_name == COLLECTION_CODE_CONTEXT {
             session_filter_value.clone()
         } else {
             None
         };
+        // A tag narrows every collection to records of sessions with that tag
+        let filter = and_filters(filter, tag.map(tag_filter));
+        // An entity narrows to records mentioning it (entity slug)
+        let filter = and_filters(filter, entity.map(entity_filter));
         let name = collection_name.clone();
         async move {
             let result = search_collection(
@@ -286,8 +304,8 @@ pub async fn search_related_sessions(
         COLLECTION_MEMORY_EPISODIC.to_string(),
     ];
 
-    let memory_results = search_all(&query, n_results, None, Some(memory_collections)).await;
-    let doc_results = search_all(&query, n_results, None, Some(vec![COLLECTION_DOCUMENTS.to_string()])).await;
+    let memory_results = search_all(&query, n_results, None, Some(memory_collections), None, None).await;
+    let doc_results = search_all(&query, n_results, None, Some(vec![COLLECTION_DOCUMENTS.to_string()]), None, None).await;
 
     let mut seen_sessions = HashSet::new();
     seen_sessions.insert(exclude_session_id.to_string());
@@ -363,6 +381,9 @@ pub async fn chroma_search_all(
     n_results: u32,
     session_id: Option<String>,
     collections: Option<Vec<String>>,
+    request_id: Option<String>,
+    tag: Option<String>,
+    entity: Option<String>,
 ) -> Result<SearchResults, SearchError> {
     let filter = if let Some(ref sid) = session_id {
         crate::session::validate_session_id(sid)
@@ -371,7 +392,17 @@ pub async fn chroma_search_all(
     } else {
         None
     };
-    search_all(&query, n_results, filter, collections).await
+    let tag = tag
+        .map(|t| crate::session::tags::normalize_tag(&t))
+        .transpose()
+        .map_err(|e| SearchError::Chroma(ChromaError::InvalidInput(e.to_string())))?;
+    let entity = entity
+        .map(|e| crate::session::entities::entity_slug(&e).ok_or_else(|| SearchError::Chroma(ChromaError::InvalidInput(format!("Invalid entity: {}", e)))))
+        .transpose()?;
+    let search = search_all(&query, n_results, filter, collections, tag.as_deref(), entity.as_deref());
+    crate::cancellation::cancellable(request_id.as_deref(), search)
+        .await
+        .map_err(|_| SearchError::Cancelled)?
 }
 
 #[tauri::command]
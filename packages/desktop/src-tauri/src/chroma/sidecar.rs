@@ -13,6 +13,8 @@ use std::time::{Duration, Instant};
 use thiserror::Error;
 use tracing::{info, warn, error, debug};
 
+use super::store::VectorStore;
+
 /// Sidecar state
 static SIDECAR: Mutex<Option<ChromaSidecar>> = Mutex::new(None);
 
@@ -382,27 +384,16 @@ pub async fn chroma_start_sidecar(app: tauri::AppHandle) -> Result<SidecarStatus
 
     // Wait for health check (up to 10 seconds)
     let client = super::client::get_client();
-    let deadline = Instant::now() + Duration::from_secs(10);
-    let mut last_err = String::new();
-    let mut attempt = 0u32;
-
-    while Instant::now() < deadline {
-        attempt += 1;
-        match client.heartbeat().await {
-            Ok(_) => {
-                info!("Chroma sidecar healthy after {} attempts", attempt);
-                return Ok(get_sidecar_status());
-            }
-            Err(e) => {
-                last_err = e.to_string();
-                debug!(attempt = attempt, error = %e, "Chroma health probe failed");
-                tokio::time::sleep(Duration::from_millis(500)).await;
-            }
+    match client.wait_until_healthy(Duration::from_secs(10)).await {
+        Ok(attempts) => {
+            info!("Chroma sidecar healthy after {} attempts", attempts);
+            Ok(get_sidecar_status())
+        }
+        Err(e) => {
+            error!("Chroma sidecar health check timed out");
+            Err(SidecarError::HealthCheckFailed(e.to_string()))
         }
     }
-
-    error!("Chroma sidecar health check timed out");
-    Err(SidecarError::HealthCheckFailed(last_err))
 }
 
 #[tauri::command]
@@ -0,0 +1,279 @@
+//! Vector Store Abstraction
+//!
+//! `VectorStore` is the set of collection operations memory, search, the
+//! document retriever and vault indexing rely on. `ChromaClient`, the
+//! in-process `EmbeddedStore` and `QdrantClient` implement it; `VectorClient`
+//! picks one per the `vectorBackend` preference and is what `get_client`
+//! returns. `VectorClient` also scopes collection names to the active
+//! profile, so callers always use the plain names.
+//! Filters use Chroma's `where` syntax whichever backend is behind it.
+
+use futures::StreamExt;
+use serde_json::Value;
+use std::future::Future;
+use std::time::Duration;
+
+use super::client::{
+    embed_documents, BatchUpsertOutcome, ChromaClient, ChromaError, ChromaGetResult, ChromaQueryResult,
+    ChromaUpsertItem, CollectionInfo, UPSERT_BATCH_SIZE,
+};
+use super::embedded::EmbeddedStore;
+use super::qdrant::QdrantClient;
+use crate::config::profile::{current_profile, scoped_collection_name, unscoped_collection_name};
+
+/// Batches in flight at once in `upsert_batched`
+const UPSERT_BATCHES_IN_FLIGHT: usize = 4;
+
+pub trait VectorStore: Send + Sync {
+    /// Liveness probe; returns a backend timestamp
+    fn heartbeat(&self) -> impl Future<Output = Result<i64, ChromaError>> + Send;
+
+    /// Poll until the backend responds or `timeout` passes; returns the
+    /// number of probes made
+    fn wait_until_healthy(&self, timeout: Duration) -> impl Future<Output = Result<u32, ChromaError>> + Send;
+
+    fn get_or_create_collection(
+        &self,
+        name: &str,
+        metadata: Option<Value>,
+    ) -> impl Future<Output = Result<CollectionInfo, ChromaError>> + Send;
+
+    /// Get a collection by name (read-only, does not create)
+    fn get_collection(&self, name: &str) -> impl Future<Output = Result<CollectionInfo, ChromaError>> + Send;
+
+    fn delete_collection(&self, name: &str) -> impl Future<Output = Result<(), ChromaError>> + Send;
+
+    /// Replace a collection's metadata; a no-op on backends that don't
+    /// persist it
+    fn set_collection_metadata(&self, name: &str, metadata: Value) -> impl Future<Output = Result<(), ChromaError>> + Send;
+
+    fn list_collections(&self) -> impl Future<Output = Result<Vec<CollectionInfo>, ChromaError>> + Send;
+
+    /// Insert or replace records
+    fn upsert(
+        &self,
+        collection_id: &str,
+        ids: Vec<String>,
+        documents: Option<Vec<String>>,
+        embeddings: Option<Vec<Vec<f32>>>,
+        metadatas: Option<Vec<Value>>,
+    ) -> impl Future<Output = Result<(), ChromaError>> + Send;
+
+    /// Nearest records per query; distances are squared L2 like Chroma's default space
+    #[allow(clippy::too_many_arguments)]
+    fn query(
+        &self,
+        collection_id: &str,
+        query_embeddings: Option<Vec<Vec<f32>>>,
+        query_texts: Option<Vec<String>>,
+        n_results: u32,
+        where_filter: Option<Value>,
+        where_document: Option<Value>,
+        include: Option<Vec<String>>,
+    ) -> impl Future<Output = Result<ChromaQueryResult, ChromaError>> + Send;
+
+    /// Records by IDs or filter
+    #[allow(clippy::too_many_arguments)]
+    fn get(
+        &self,
+        collection_id: &str,
+        ids: Option<Vec<String>>,
+        where_filter: Option<Value>,
+        where_document: Option<Value>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+        include: Option<Vec<String>>,
+    ) -> impl Future<Output = Result<ChromaGetResult, ChromaError>> + Send;
+
+    /// Delete records by IDs or filter
+    fn delete(
+        &self,
+        collection_id: &str,
+        ids: Option<Vec<String>>,
+        where_filter: Option<Value>,
+    ) -> impl Future<Output = Result<(), ChromaError>> + Send;
+
+    /// Update the metadata of existing records; keys not given are kept
+    fn update_metadata(
+        &self,
+        collection_id: &str,
+        ids: Vec<String>,
+        metadatas: Vec<Value>,
+    ) -> impl Future<Output = Result<(), ChromaError>> + Send;
+
+    fn count(&self, collection_id: &str) -> impl Future<Output = Result<u32, ChromaError>> + Send;
+
+    /// Whether collection metadata given at creation is stored and
+    /// returned by `get_collection`
+    fn persists_collection_metadata(&self) -> bool {
+        true
+    }
+
+    /// Upsert `items` in batches of `UPSERT_BATCH_SIZE`, embedding locally,
+    /// with up to `UPSERT_BATCHES_IN_FLIGHT` batches running at once.
+    /// A failed batch doesn't stop the others. `on_progress` receives the
+    /// number of items processed (written or failed) as batches finish.
+    fn upsert_batched(
+        &self,
+        collection_id: &str,
+        items: &[ChromaUpsertItem],
+        mut on_progress: impl FnMut(u32) + Send,
+    ) -> impl Future<Output = BatchUpsertOutcome> + Send {
+        async move {
+            // Futures are built up front (not yet polled) so the stream doesn't
+            // capture a closure over borrowed batches
+            let batches: Vec<_> = items
+                .chunks(UPSERT_BATCH_SIZE)
+                .map(|batch| async move {
+                    let ids: Vec<String> = batch.iter().map(|item| item.id.clone()).collect();
+                    let documents: Vec<String> = batch.iter().map(|item| item.document.clone()).collect();
+                    let metadatas: Vec<Value> = batch.iter().map(|item| item.metadata.clone()).collect();
+                    let embeddings = embed_documents(&documents);
+                    let result = self.upsert(collection_id, ids, Some(documents), Some(embeddings), Some(metadatas)).await;
+                    (batch.len() as u32, result)
+                })
+                .collect();
+            let mut results = futures::stream::iter(batches).buffer_unordered(UPSERT_BATCHES_IN_FLIGHT);
+
+            let mut outcome = BatchUpsertOutcome::default();
+            while let Some((count, result)) = results.next().await {
+                match result {
+                    Ok(()) => outcome.upserted += count,
+                    Err(e) => {
+                        outcome.failed += count;
+                        outcome.errors.push(e.to_string());
+                    }
+                }
+                on_progress(outcome.upserted + outcome.failed);
+            }
+            outcome
+        }
+    }
+}
+
+/// The configured backend
+#[derive(Clone)]
+pub enum VectorClient {
+    Chroma(ChromaClient),
+    Embedded(EmbeddedStore),
+    Qdrant(QdrantClient),
+}
+
+/// A collection as seen by the active profile: its unprefixed name
+fn unscoped(mut info: CollectionInfo) -> CollectionInfo {
+    if let Some(name) = unscoped_collection_name(current_profile(), &info.name) {
+        info.name = name.to_string();
+    }
+    info
+}
+
+/// Forward a `VectorStore` call to whichever backend is active
+macro_rules! dispatch {
+    ($self:ident, $method:ident ( $($arg:expr),* )) => {
+        match $self {
+            VectorClient::Chroma(c) => c.$method($($arg),*).await,
+            VectorClient::Embedded(e) => e.$method($($arg),*).await,
+            VectorClient::Qdrant(q) => q.$method($($arg),*).await,
+        }
+    };
+}
+
+impl VectorStore for VectorClient {
+    async fn heartbeat(&self) -> Result<i64, ChromaError> {
+        dispatch!(self, heartbeat())
+    }
+
+    async fn wait_until_healthy(&self, timeout: Duration) -> Result<u32, ChromaError> {
+        dispatch!(self, wait_until_healthy(timeout))
+    }
+
+    async fn get_or_create_collection(&self, name: &str, metadata: Option<Value>) -> Result<CollectionInfo, ChromaError> {
+        let stored = scoped_collection_name(current_profile(), name);
+        dispatch!(self, get_or_create_collection(&stored, metadata)).map(unscoped)
+    }
+
+    async fn get_collection(&self, name: &str) -> Result<CollectionInfo, ChromaError> {
+        let stored = scoped_collection_name(current_profile(), name);
+        match dispatch!(self, get_collection(&stored)) {
+            Err(ChromaError::CollectionNotFound(_)) => Err(ChromaError::CollectionNotFound(name.to_string())),
+            result => result.map(unscoped),
+        }
+    }
+
+    async fn delete_collection(&self, name: &str) -> Result<(), ChromaError> {
+        let stored = scoped_collection_name(current_profile(), name);
+        dispatch!(self, delete_collection(&stored))
+    }
+
+    async fn set_collection_metadata(&self, name: &str, metadata: Value) -> Result<(), ChromaError> {
+        let stored = scoped_collection_name(current_profile(), name);
+        dispatch!(self, set_collection_metadata(&stored, metadata))
+    }
+
+    /// The active profile's collections only
+    async fn list_collections(&self) -> Result<Vec<CollectionInfo>, ChromaError> {
+        let collections = dispatch!(self, list_collections())?;
+        Ok(collections
+            .into_iter()
+            .filter(|c| unscoped_collection_name(current_profile(), &c.name).is_some())
+            .map(unscoped)
+            .collect())
+    }
+
+    async fn upsert(
+        &self,
+        collection_id: &str,
+        ids: Vec<String>,
+        documents: Option<Vec<String>>,
+        embeddings: Option<Vec<Vec<f32>>>,
+        metadatas: Option<Vec<Value>>,
+    ) -> Result<(), ChromaError> {
+        dispatch!(self, upsert(collection_id, ids, documents, embeddings, metadatas))
+    }
+
+    async fn query(
+        &self,
+        collection_id: &str,
+        query_embeddings: Option<Vec<Vec<f32>>>,
+        query_texts: Option<Vec<String>>,
+        n_results: u32,
+        where_filter: Option<Value>,
+        where_document: Option<Value>,
+        include: Option<Vec<String>>,
+    ) -> Result<ChromaQueryResult, ChromaError> {
+        dispatch!(self, query(collection_id, query_embeddings, query_texts, n_results, where_filter, where_document, include))
+    }
+
+    async fn get(
+        &self,
+        collection_id: &str,
+        ids: Option<Vec<String>>,
+        where_filter: Option<Value>,
+        where_document: Option<Value>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+        include: Option<Vec<String>>,
+    ) -> Result<ChromaGetResult, ChromaError> {
+        dispatch!(self, get(collection_id, ids, where_filter, where_document, limit, offset, include))
+    }
+
+    async fn delete(&self, collection_id: &str, ids: Option<Vec<String>>, where_filter: Option<Value>) -> Result<(), ChromaError> {
+        dispatch!(self, delete(collection_id, ids, where_filter))
+    }
+
+    async fn update_metadata(&self, collection_id: &str, ids: Vec<String>, metadatas: Vec<Value>) -> Result<(), ChromaError> {
+        dispatch!(self, update_metadata(collection_id, ids, metadatas))
+    }
+
+    async fn count(&self, collection_id: &str) -> Result<u32, ChromaError> {
+        dispatch!(self, count(collection_id))
+    }
+
+    fn persists_collection_metadata(&self) -> bool {
+        match self {
+            VectorClient::Chroma(c) => c.persists_collection_metadata(),
+            VectorClient::Embedded(e) => e.persists_collection_metadata(),
+            VectorClient::Qdrant(q) => q.persists_collection_metadata(),
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//! Chroma Write Queue
+//!
+//! Hooks and file events during a busy session produce bursts of small
+//! upserts (one per claim, artifact or memory). Writes queued here are
+//! coalesced by record ID, so only the latest version of a record is sent,
+//! and flushed per collection through `upsert_batched`: once
+//! `FLUSH_SIZE` records are waiting, or `FLUSH_INTERVAL` after the first
+//! one arrived. Flushes are at least `MIN_FLUSH_GAP` apart. Short-lived
+//! processes (the CLI, the MCP server) call `flush` before exiting; it
+//! waits for a batch the background flusher is already writing.
+
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tracing::{debug, warn};
+
+use super::client::{get_client, BatchUpsertOutcome, ChromaUpsertItem, UPSERT_BATCH_SIZE};
+use super::store::VectorStore;
+use crate::metrics;
+
+/// Queued records that trigger a flush without waiting for the interval
+pub const FLUSH_SIZE: usize = UPSERT_BATCH_SIZE;
+/// Longest a queued record waits
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+/// Shortest time between two flushes
+pub const MIN_FLUSH_GAP: Duration = Duration::from_millis(500);
+
+pub const QUEUE_COALESCED: &str = "chroma.queue.coalesced";
+pub const QUEUE_FLUSHES: &str = "chroma.queue.flushes";
+
+static QUEUE: LazyLock<Mutex<WriteQueue>> = LazyLock::new(|| Mutex::new(WriteQueue::default()));
+/// Wakes the flusher early once `FLUSH_SIZE` is reached
+static FLUSH_NOW: LazyLock<Notify> = LazyLock::new(Notify::new);
+/// Held while a batch is written, so `flush` waits for one in flight
+static WRITING: LazyLock<tokio::sync::Mutex<()>> = LazyLock::new(|| tokio::sync::Mutex::new(()));
+
+type Batches = BTreeMap<String, Vec<ChromaUpsertItem>>;
+
+/// Pending upserts by collection ID, coalesced by record ID
+#[derive(Debug, Default)]
+pub struct PendingWrites {
+    collections: BTreeMap<String, Vec<ChromaUpsertItem>>,
+    len: usize,
+}
+
+impl PendingWrites {
+    /// Queue `item`, replacing a queued write of the same record. Returns
+    /// whether it replaced one.
+    pub fn push(&mut self, collection_id: &str, item: ChromaUpsertItem) -> bool {
+        let items = self.collections.entry(collection_id.to_string()).or_default();
+        match items.iter_mut().find(|queued| queued.id == item.id) {
+            Some(queued) => {
+                *queued = item;
+                true
+            }
+            None => {
+                items.push(item);
+                self.len += 1;
+                false
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Everything queued, by collection, leaving the queue empty
+    pub fn take(&mut self) -> BTreeMap<String, Vec<ChromaUpsertItem>> {
+        self.len = 0;
+        std::mem::take(&mut self.collections)
+    }
+}
+
+#[derive(Default)]
+struct WriteQueue {
+    pending: PendingWrites,
+    flusher_running: bool,
+}
+
+/// Queue an upsert into `collection_id`. The write happens in the
+/// background; failures are logged, like the best-effort writes that use it.
+pub fn enqueue(collection_id: &str, item: ChromaUpsertItem) {
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        warn!(collection_id = %collection_id, id = %item.id, "No async runtime for queued Chroma write, dropping it");
+        return;
+    };
+    let mut queue = QUEUE.lock();
+    if queue.pending.push(collection_id, item) {
+        metrics::increment(QUEUE_COALESCED, 1);
+    }
+    if !queue.flusher_running {
+        queue.flusher_running = true;
+        runtime.spawn(run_flusher());
+    }
+    if queue.pending.len() >= FLUSH_SIZE {
+        FLUSH_NOW.notify_one();
+    }
+}
+
+/// Clears `flusher_running` if the flusher task is dropped before it
+/// finishes, e.g. when the runtime that spawned it shuts down, so the next
+/// `enqueue` starts a new one
+struct FlusherRunning {
+    armed: bool,
+}
+
+impl Drop for FlusherRunning {
+    fn drop(&mut self) {
+        if self.armed {
+            QUEUE.lock().flusher_running = false;
+        }
+    }
+}
+
+/// Flush until the queue stays empty for an interval
+async fn run_flusher() {
+    run_flusher_with(write).await
+}
+
+async fn run_flusher_with<W, F>(write: W)
+where
+    W: Fn(Batches) -> F,
+    F: Future<Output = BatchUpsertOutcome>,
+{
+    let mut running = FlusherRunning { armed: true };
+    loop {
+        tokio::select! {
+            _ = FLUSH_NOW.notified() => {}
+            _ = tokio::time::sleep(FLUSH_INTERVAL) => {}
+        }
+        let writing = WRITING.lock().await;
+        let batches = {
+            let mut queue = QUEUE.lock();
+            if queue.pending.is_empty() {
+                // Cleared under the lock, so an `enqueue` after this starts a new flusher
+                queue.flusher_running = false;
+                running.armed = false;
+                return;
+            }
+            queue.pending.take()
+        };
+        write(batches).await;
+        drop(writing);
+        tokio::time::sleep(MIN_FLUSH_GAP).await;
+    }
+}
+
+async fn write(batches: Batches) -> BatchUpsertOutcome {
+    let client = get_client();
+    let mut total = BatchUpsertOutcome::default();
+    for (collection_id, items) in batches {
+        let outcome = client.upsert_batched(&collection_id, &items, |_| {}).await;
+        if outcome.failed > 0 {
+            warn!(collection_id = %collection_id, failed = outcome.failed, errors = ?outcome.errors, "Queued Chroma writes failed");
+        }
+        debug!(collection_id = %collection_id, upserted = outcome.upserted, "Flushed queued Chroma writes");
+        total.upserted += outcome.upserted;
+        total.failed += outcome.failed;
+        total.errors.extend(outcome.errors);
+    }
+    metrics::increment(QUEUE_FLUSHES, 1);
+    total
+}
+
+/// Write everything queued now, after any flush already in progress
+pub async fn flush() -> BatchUpsertOutcome {
+    flush_with(write).await
+}
+
+async fn flush_with<W, F>(write: W) -> BatchUpsertOutcome
+where
+    W: FnOnce(Batches) -> F,
+    F: Future<Output = BatchUpsertOutcome>,
+{
+    let _writing = WRITING.lock().await;
+    let batches = QUEUE.lock().pending.take();
+    if batches.is_empty() {
+        return BatchUpsertOutcome::default();
+    }
+    write(batches).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn item(id: &str, document: &str) -> ChromaUpsertItem {
+        ChromaUpsertItem { id: id.to_string(), document: document.to_string(), metadata: json!({}) }
+    }
+
+    #[test]
+    fn test_pending_writes_coalesce_by_record() {
+        let mut pending = PendingWrites::default();
+        assert!(!pending.push("memories", item("a", "first")));
+        assert!(!pending.push("memories", item("b", "other")));
+        assert!(pending.push("memories", item("a", "latest")));
+        // Same record ID in another collection is a separate write
+        assert!(!pending.push("sources", item("a", "source")));
+        assert_eq!(pending.len(), 3);
+
+        let batches = pending.take();
+        assert!(pending.is_empty());
+        let memories: Vec<(&str, &str)> = batches["memories"].iter().map(|i| (i.id.as_str(), i.document.as_str())).collect();
+        assert_eq!(memories, vec![("a", "latest"), ("b", "other")]);
+        assert_eq!(batches["sources"].len(), 1);
+    }
+
+    #[test]
+    fn test_flush_waits_for_write_in_progress() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(flush_during_write());
+        // Shutting down the runtime drops the flusher mid-loop; it must not
+        // leave the next `enqueue` thinking one is still running
+        drop(runtime);
+        assert!(!QUEUE.lock().flusher_running);
+    }
+
+    async fn flush_during_write() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        {
+            let mut queue = QUEUE.lock();
+            queue.pending.push("memories", item("a", "queued"));
+            queue.flusher_running = true;
+        }
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+        let started_tx = Mutex::new(Some(started_tx));
+        let finished = Arc::new(AtomicBool::new(false));
+        let flusher_finished = finished.clone();
+        tokio::spawn(run_flusher_with(move |batches: Batches| {
+            if let Some(tx) = started_tx.lock().take() {
+                let _ = tx.send(());
+            }
+            let finished = flusher_finished.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                finished.store(true, Ordering::SeqCst);
+                BatchUpsertOutcome { upserted: batches.values().map(|items| items.len() as u32).sum(), ..Default::default() }
+            }
+        }));
+        FLUSH_NOW.notify_one();
+        started_rx.await.unwrap();
+
+        // The queue is already drained, but the flusher's batch is still being written
+        let outcome = flush_with(|_: Batches| async { BatchUpsertOutcome { failed: 1, ..Default::default() } }).await;
+        assert!(finished.load(Ordering::SeqCst));
+        // Nothing was left for the flush itself to write
+        assert_eq!(outcome.failed, 0);
+    }
+}
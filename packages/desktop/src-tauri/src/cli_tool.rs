@@ -0,0 +1,216 @@
+//! CLI Tool Detection
+//!
+//! Locates the configured CLI binary and checks it can run a session:
+//! its version against a minimum (for `claude`) and whether `--help`
+//! lists the flags `prepare_launch` passes. GUI apps often start with a
+//! minimal PATH, so common install locations are searched too. Results
+//! are cached per binary until the file changes.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::LazyLock;
+use std::time::SystemTime;
+use tracing::{debug, warn};
+
+use crate::config::preferences::load_preferences;
+
+/// Oldest claude release known to support every flag used at launch
+pub const MIN_CLAUDE_VERSION: (u32, u32, u32) = (1, 0, 0);
+
+/// Flags `prepare_launch` may pass
+const LAUNCH_FLAGS: [&str; 3] = ["--resume", "--add-dir", "--settings"];
+
+/// Last probe per binary, with the binary's mtime at probe time
+type ProbeCache = HashMap<PathBuf, (Option<SystemTime>, CliToolDiagnostics)>;
+
+static CACHE: LazyLock<Mutex<ProbeCache>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliToolDiagnostics {
+    pub tool: String,
+    /// Resolved binary, if found
+    pub path: Option<String>,
+    /// Found outside PATH, so launches should use the absolute path
+    pub outside_path: bool,
+    pub version: Option<String>,
+    pub min_version: Option<String>,
+    pub supports_resume: bool,
+    pub supports_add_dir: bool,
+    pub supports_settings: bool,
+    /// Found and at least the minimum version
+    pub usable: bool,
+    pub problems: Vec<String>,
+}
+
+/// Find an executable named `name` in the directories of a PATH-style list
+pub fn find_in_path(name: &str, path_var: &OsStr) -> Option<PathBuf> {
+    find_in_dirs(name, std::env::split_paths(path_var))
+}
+
+fn find_in_dirs(name: &str, dirs: impl IntoIterator<Item = PathBuf>) -> Option<PathBuf> {
+    let candidates: Vec<String> = if cfg!(windows) {
+        vec![format!("{}.exe", name), format!("{}.cmd", name), name.to_string()]
+    } else {
+        vec![name.to_string()]
+    };
+    dirs.into_iter()
+        .flat_map(|dir| candidates.iter().map(move |c| dir.join(c)).collect::<Vec<_>>())
+        .find(|path| path.is_file())
+}
+
+/// Install locations a login shell has on PATH but a GUI app may not
+fn fallback_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from("/usr/local/bin"), PathBuf::from("/opt/homebrew/bin")];
+    if let Some(home) = dirs::home_dir() {
+        for rel in [".claude/local", ".local/bin", ".npm-global/bin", ".bun/bin", ".volta/bin"] {
+            dirs.push(home.join(rel));
+        }
+    }
+    dirs
+}
+
+/// Locate `tool`: an explicit path, then PATH, then common install dirs.
+/// The flag is true when the binary was found outside PATH.
+pub fn locate(tool: &str) -> Option<(PathBuf, bool)> {
+    let tool = tool.trim();
+    if tool.contains('/') || tool.contains('\\') {
+        let path = PathBuf::from(tool);
+        return path.is_file().then_some((path, false));
+    }
+    if let Some(path) = std::env::var_os("PATH").and_then(|p| find_in_path(tool, &p)) {
+        return Some((path, false));
+    }
+    find_in_dirs(tool, fallback_dirs()).map(|path| (path, true))
+}
+
+/// First `x.y[.z]` token in `text`
+pub fn parse_version(text: &str) -> Option<(u32, u32, u32)> {
+    text.split(|c: char| !(c.is_ascii_digit() || c == '.')).find_map(|token| {
+        let mut parts = token.split('.').filter(|p| !p.is_empty()).map(|p| p.parse::<u32>());
+        let major = parts.next()?.ok()?;
+        let minor = parts.next()?.ok()?;
+        let patch = parts.next().and_then(|p| p.ok()).unwrap_or(0);
+        Some((major, minor, patch))
+    })
+}
+
+fn run_capture(path: &Path, arg: &str) -> Option<String> {
+    let output = Command::new(path).arg(arg).output().ok()?;
+    let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    Some(text)
+}
+
+fn probe(tool: &str, path: &Path, outside_path: bool) -> CliToolDiagnostics {
+    let is_claude = path.file_stem().is_some_and(|stem| stem == "claude");
+    let mut diagnostics = CliToolDiagnostics {
+        tool: tool.to_string(),
+        path: Some(path.to_string_lossy().to_string()),
+        outside_path,
+        min_version: is_claude.then(|| format!("{}.{}.{}", MIN_CLAUDE_VERSION.0, MIN_CLAUDE_VERSION.1, MIN_CLAUDE_VERSION.2)),
+        ..Default::default()
+    };
+
+    let Some(version_output) = run_capture(path, "--version") else {
+        diagnostics.problems.push(format!("{} could not be executed", path.display()));
+        return diagnostics;
+    };
+    let version = parse_version(&version_output);
+    diagnostics.version = version.map(|(a, b, c)| format!("{}.{}.{}", a, b, c));
+    let version_ok = match (is_claude, version) {
+        (false, _) => true,
+        (true, Some(version)) => version >= MIN_CLAUDE_VERSION,
+        (true, None) => {
+            diagnostics.problems.push("Could not read the claude version".to_string());
+            true
+        }
+    };
+    if !version_ok {
+        diagnostics.problems.push(format!(
+            "claude {} is older than the minimum {}; update with `claude update`",
+            diagnostics.version.as_deref().unwrap_or("?"),
+            diagnostics.min_version.as_deref().unwrap_or("?"),
+        ));
+    }
+
+    let help = run_capture(path, "--help").unwrap_or_default();
+    let supported: Vec<bool> = LAUNCH_FLAGS.iter().map(|flag| help.contains(flag)).collect();
+    (diagnostics.supports_resume, diagnostics.supports_add_dir, diagnostics.supports_settings) =
+        (supported[0], supported[1], supported[2]);
+    for (flag, ok) in LAUNCH_FLAGS.iter().zip(&supported) {
+        if !ok {
+            diagnostics.problems.push(format!("{} does not list {}", tool, flag));
+        }
+    }
+
+    diagnostics.usable = version_ok;
+    diagnostics
+}
+
+/// Locate and probe `tool`, reusing the last probe while the binary is unchanged
+pub fn detect(tool: &str) -> CliToolDiagnostics {
+    let Some((path, outside_path)) = locate(tool) else {
+        return CliToolDiagnostics {
+            tool: tool.to_string(),
+            problems: vec![if tool.trim() == "claude" {
+                "claude not found on PATH. Install it with `npm install -g @anthropic-ai/claude-code`".to_string()
+            } else {
+                format!("{} not found on PATH", tool)
+            }],
+            ..Default::default()
+        };
+    };
+
+    let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+    if let Some((cached_at, cached)) = CACHE.lock().get(&path) {
+        if *cached_at == modified {
+            return cached.clone();
+        }
+    }
+    let diagnostics = probe(tool, &path, outside_path);
+    if diagnostics.problems.is_empty() {
+        debug!(tool = %tool, version = ?diagnostics.version, "Detected CLI tool");
+    } else {
+        warn!(tool = %tool, problems = ?diagnostics.problems, "CLI tool problems");
+    }
+    CACHE.lock().insert(path, (modified, diagnostics.clone()));
+    diagnostics
+}
+
+// ============ TAURI COMMANDS ============
+
+/// Diagnose `tool`, or the configured CLI tool
+#[tauri::command]
+pub async fn detect_cli_tool(tool: Option<String>) -> CliToolDiagnostics {
+    let tool = tool.unwrap_or_else(|| load_preferences().cli_tool);
+    tokio::task::spawn_blocking(move || detect(&tool))
+        .await
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version_and_find_in_path() {
+        assert_eq!(parse_version("1.0.33 (Claude Code)"), Some((1, 0, 33)));
+        assert_eq!(parse_version("aider v0.82"), Some((0, 82, 0)));
+        assert_eq!(parse_version("no version here"), None);
+        assert!(parse_version("0.9.1").unwrap() < MIN_CLAUDE_VERSION);
+
+        let dir = std::env::temp_dir().join(format!("dialectic_cli_tool_{}", ulid::Ulid::new()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let name = if cfg!(windows) { "claude.exe" } else { "claude" };
+        std::fs::write(dir.join(name), "").unwrap();
+        let path_var = std::env::join_paths([Path::new("/nonexistent"), dir.as_path()]).unwrap();
+        assert_eq!(find_in_path("claude", &path_var), Some(dir.join(name)));
+        assert_eq!(find_in_path("aider", &path_var), None);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
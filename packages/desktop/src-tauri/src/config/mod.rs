@@ -0,0 +1,31 @@
+//! Configuration Module
+//!
+//! Typed access to the app's configuration files under `config/`.
+
+pub mod preferences;
+pub mod profile;
+pub mod workspace;
+
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid configuration: {0}")]
+    Invalid(String),
+    #[error("App data directory not found")]
+    NoAppDataDir,
+}
+
+impl Serialize for ConfigError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
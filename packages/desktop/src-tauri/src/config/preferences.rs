@@ -0,0 +1,450 @@
+//! User Preferences
+//!
+//! Typed view of `config/preferences.json`. Reads migrate legacy key names
+//! and fill defaults; updates take a partial JSON patch, validate the
+//! result, write atomically and emit `preferences-changed` with the keys
+//! that changed. Unknown keys are preserved so older and newer builds can
+//! share the file.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+use super::ConfigError;
+use crate::backup::BackupConfig;
+use crate::context::classification::SessionClassification;
+use crate::obsidian::exclusions::VaultIndexConfig;
+use crate::session::retention::RetentionPolicy;
+use crate::session::{get_app_data_dir_cli, SessionMode};
+
+/// Current preferences schema version
+pub const PREFERENCES_VERSION: u32 = 1;
+
+/// Event emitted after preferences are updated
+pub const PREFERENCES_CHANGED_EVENT: &str = "preferences-changed";
+
+/// Legacy key names and their current equivalents
+const LEGACY_KEYS: &[(&str, &str)] = &[
+    ("cli_tool", "cliTool"),
+    ("default_mode", "defaultMode"),
+    ("vault_path", "vaultPath"),
+    ("obsidianVault", "vaultPath"),
+    ("obsidianVaultPath", "vaultPath"),
+    ("chroma_mode", "chromaMode"),
+    ("chroma_url", "chromaUrl"),
+    ("vector_backend", "vectorBackend"),
+    ("qdrant_url", "qdrantUrl"),
+    ("budget_profile", "budgetProfile"),
+    ("defaultClassification", "budgetProfile"),
+    ("vault_indexing", "vaultIndexing"),
+];
+
+/// UI theme
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    System,
+}
+
+/// How the app reaches Chroma
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChromaMode {
+    /// Spawn and manage a local Chroma sidecar
+    #[default]
+    Sidecar,
+    /// Connect to an already running server at `chromaUrl`
+    External,
+    /// No Chroma; local feature-hash search only
+    Disabled,
+}
+
+/// Where vector records (memory, sources, documents, vault notes) live
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VectorBackend {
+    /// Chroma, reached per `chromaMode`
+    #[default]
+    Chroma,
+    /// In-process store under `<app data>/vectors`; needs no Python or server
+    Embedded,
+    /// An existing Qdrant server at `qdrantUrl`
+    Qdrant,
+}
+
+/// How text is pulled out of image references
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OcrProvider {
+    /// Images are indexed by filename only
+    #[default]
+    Disabled,
+    /// The `tesseract` CLI on PATH
+    Tesseract,
+    /// POST the image to `ocrUrl`
+    Api,
+}
+
+/// How named entities are pulled out of sessions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntityExtraction {
+    /// Capitalized phrases, acronyms, tickers and wikilinks
+    #[default]
+    Heuristic,
+    /// Ask `cliTool`, falling back to the heuristic
+    Model,
+}
+
+/// Application preferences
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Preferences {
+    pub version: u32,
+    pub theme: Theme,
+    pub default_mode: SessionMode,
+    /// Command launched in session terminals
+    pub cli_tool: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vault_path: Option<String>,
+    pub chroma_mode: ChromaMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chroma_url: Option<String>,
+    pub vector_backend: VectorBackend,
+    /// Defaults to a local Qdrant on its standard port
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qdrant_url: Option<String>,
+    /// Sent as `api-key` when set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qdrant_api_key: Option<String>,
+    /// Budget allocation applied to new sessions
+    pub budget_profile: SessionClassification,
+    pub vault_indexing: VaultIndexConfig,
+    /// Also mine Read/Grep/Glob tool results into code_context
+    pub mine_code_context: bool,
+    /// Days deleted sessions stay in the trash before startup purges
+    /// them; 0 keeps them until restored
+    pub trash_retention_days: u32,
+    /// Stale flagging, iceboxing and archiving of idle sessions
+    pub retention: RetentionPolicy,
+    pub ocr_provider: OcrProvider,
+    /// Endpoint for the `api` OCR provider
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ocr_url: Option<String>,
+    pub entity_extraction: EntityExtraction,
+    /// Folder (Dropbox, iCloud Drive, a git checkout) sessions are mirrored
+    /// into by `sync_now`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sync_folder: Option<String>,
+    /// Encrypted backups to an S3-compatible bucket
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup: Option<BackupConfig>,
+    /// Keys this build doesn't know about, kept on write
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            version: PREFERENCES_VERSION,
+            theme: Theme::default(),
+            default_mode: SessionMode::default(),
+            cli_tool: "claude".to_string(),
+            vault_path: None,
+            chroma_mode: ChromaMode::default(),
+            chroma_url: None,
+            vector_backend: VectorBackend::default(),
+            qdrant_url: None,
+            qdrant_api_key: None,
+            budget_profile: SessionClassification::default(),
+            vault_indexing: VaultIndexConfig::default(),
+            mine_code_context: false,
+            trash_retention_days: 30,
+            retention: RetentionPolicy::default(),
+            ocr_provider: OcrProvider::default(),
+            ocr_url: None,
+            entity_extraction: EntityExtraction::default(),
+            sync_folder: None,
+            backup: None,
+            extra: Map::new(),
+        }
+    }
+}
+
+fn validate_http_url(key: &str, url: &str) -> Result<(), ConfigError> {
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| ConfigError::Invalid(format!("{} is not a valid URL: {}", key, e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(ConfigError::Invalid(format!("{} must use http or https", key)));
+    }
+    Ok(())
+}
+
+impl Preferences {
+    /// Check values that serde can't
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let tool = self.cli_tool.trim();
+        if tool.is_empty() {
+            return Err(ConfigError::Invalid("cliTool must not be empty".to_string()));
+        }
+        if !tool.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '/')) {
+            return Err(ConfigError::Invalid(format!("cliTool contains unsupported characters: {}", tool)));
+        }
+
+        if let Some(vault) = &self.vault_path {
+            if !Path::new(vault).is_dir() {
+                return Err(ConfigError::Invalid(format!("vaultPath is not a directory: {}", vault)));
+            }
+        }
+
+        if let Some(folder) = &self.sync_folder {
+            if !Path::new(folder).is_absolute() {
+                return Err(ConfigError::Invalid(format!("syncFolder must be an absolute path: {}", folder)));
+            }
+        }
+
+        if let Some(backup) = &self.backup {
+            backup.validate().map_err(ConfigError::Invalid)?;
+        }
+
+        if self.chroma_mode == ChromaMode::External {
+            let url = self.chroma_url.as_deref()
+                .ok_or_else(|| ConfigError::Invalid("chromaUrl is required when chromaMode is external".to_string()))?;
+            validate_http_url("chromaUrl", url)?;
+        }
+        if let Some(url) = self.qdrant_url.as_deref() {
+            validate_http_url("qdrantUrl", url)?;
+        }
+        if self.ocr_provider == OcrProvider::Api {
+            let url = self.ocr_url.as_deref()
+                .ok_or_else(|| ConfigError::Invalid("ocrUrl is required when ocrProvider is api".to_string()))?;
+            validate_http_url("ocrUrl", url)?;
+        }
+
+        for pattern in &self.vault_indexing.exclude_patterns {
+            glob::Pattern::new(pattern)
+                .map_err(|e| ConfigError::Invalid(format!("Invalid exclude pattern {}: {}", pattern, e)))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Rename legacy keys in place (current keys win over legacy ones).
+/// Returns true if anything changed.
+pub fn migrate(raw: &mut Map<String, Value>) -> bool {
+    let mut changed = false;
+    for (old, new) in LEGACY_KEYS {
+        if let Some(value) = raw.remove(*old) {
+            changed = true;
+            raw.entry(new.to_string()).or_insert(value);
+        }
+    }
+    let version = raw.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version < PREFERENCES_VERSION as u64 {
+        raw.insert("version".to_string(), Value::from(PREFERENCES_VERSION));
+        changed = true;
+    }
+    changed
+}
+
+/// Path to preferences.json
+pub fn preferences_path() -> Result<PathBuf, ConfigError> {
+    let base = get_app_data_dir_cli().map_err(|_| ConfigError::NoAppDataDir)?;
+    Ok(base.join("config/preferences.json"))
+}
+
+fn read_raw(path: &Path) -> Result<Map<String, Value>, ConfigError> {
+    if !path.exists() {
+        return Ok(Map::new());
+    }
+    match serde_json::from_str::<Value>(&fs::read_to_string(path)?)? {
+        Value::Object(map) => Ok(map),
+        _ => Err(ConfigError::Invalid("preferences.json is not an object".to_string())),
+    }
+}
+
+fn write_atomic(path: &Path, prefs: &Preferences) -> Result<(), ConfigError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(prefs)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Load preferences from `path`, migrating legacy keys on disk
+pub fn load_preferences_from(path: &Path) -> Result<Preferences, ConfigError> {
+    let mut raw = read_raw(path)?;
+    let migrated = !raw.is_empty() && migrate(&mut raw);
+    let prefs: Preferences = serde_json::from_value(Value::Object(raw))?;
+    if migrated {
+        info!(path = %path.display(), "Migrated legacy preference keys");
+        if let Err(e) = write_atomic(path, &prefs) {
+            warn!(error = %e, "Failed to persist migrated preferences");
+        }
+    }
+    Ok(prefs)
+}
+
+/// Load preferences, falling back to defaults if unreadable
+pub fn load_preferences() -> Preferences {
+    match preferences_path().and_then(|p| load_preferences_from(&p)) {
+        Ok(prefs) => prefs,
+        Err(e) => {
+            warn!(error = %e, "Failed to load preferences, using defaults");
+            Preferences::default()
+        }
+    }
+}
+
+/// Apply a partial update. Top-level keys in `patch` replace current values
+/// (`null` resets a key to its default). Returns the new preferences and
+/// the keys whose values changed.
+pub fn apply_patch(current: &Preferences, patch: Value) -> Result<(Preferences, Vec<String>), ConfigError> {
+    let mut patch = match patch {
+        Value::Object(map) => map,
+        _ => return Err(ConfigError::Invalid("Preferences update must be an object".to_string())),
+    };
+    migrate(&mut patch);
+    patch.remove("version");
+
+    let before = match serde_json::to_value(current)? {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    };
+    let mut merged = before.clone();
+    for (key, value) in patch {
+        if value.is_null() {
+            merged.remove(&key);
+        } else {
+            merged.insert(key, value);
+        }
+    }
+
+    let updated: Preferences = serde_json::from_value(Value::Object(merged))
+        .map_err(|e| ConfigError::Invalid(e.to_string()))?;
+    updated.validate()?;
+
+    let after = match serde_json::to_value(&updated)? {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    };
+    let mut changed: Vec<String> = after
+        .iter()
+        .filter(|(k, v)| before.get(*k) != Some(*v))
+        .map(|(k, _)| k.clone())
+        .chain(before.keys().filter(|k| !after.contains_key(*k)).cloned())
+        .collect();
+    changed.sort();
+
+    Ok((updated, changed))
+}
+
+/// Payload for `preferences-changed`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferencesChanged {
+    pub preferences: Preferences,
+    pub changed_keys: Vec<String>,
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn get_preferences() -> Result<Preferences, ConfigError> {
+    load_preferences_from(&preferences_path()?)
+}
+
+#[tauri::command]
+pub fn update_preferences(app: AppHandle, patch: Value) -> Result<Preferences, ConfigError> {
+    let path = preferences_path()?;
+    let current = load_preferences_from(&path)?;
+    let (updated, changed_keys) = apply_patch(&current, patch)?;
+    if changed_keys.is_empty() {
+        return Ok(updated);
+    }
+
+    write_atomic(&path, &updated)?;
+    info!(keys = ?changed_keys, "Preferences updated");
+    if changed_keys.iter().any(|k| matches!(k.as_str(), "vectorBackend" | "chromaMode" | "chromaUrl" | "qdrantUrl" | "qdrantApiKey")) {
+        crate::chroma::client::reset_client();
+    }
+
+    let payload = PreferencesChanged {
+        preferences: updated.clone(),
+        changed_keys,
+    };
+    if let Err(e) = app.emit(PREFERENCES_CHANGED_EVENT, payload) {
+        warn!(error = %e, "Failed to emit preferences-changed event");
+    }
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_migrate_legacy_keys() {
+        let mut raw = json!({
+            "theme": "light",
+            "cli_tool": "aider",
+            "obsidianVault": "/vault",
+            "vaultPath": "/current",
+        })
+        .as_object()
+        .cloned()
+        .unwrap();
+
+        assert!(migrate(&mut raw));
+        assert_eq!(raw["cliTool"], "aider");
+        // Current key wins over the legacy one
+        assert_eq!(raw["vaultPath"], "/current");
+        assert!(!raw.contains_key("obsidianVault"));
+        assert_eq!(raw["version"], PREFERENCES_VERSION);
+        assert!(!migrate(&mut raw));
+    }
+
+    #[test]
+    fn test_defaults_and_unknown_keys() {
+        let prefs: Preferences = serde_json::from_value(json!({
+            "theme": "dark",
+            "defaultMode": "idea",
+            "cliTool": "claude",
+            "experimentalFlag": true,
+        }))
+        .unwrap();
+        assert_eq!(prefs.chroma_mode, ChromaMode::Sidecar);
+        assert_eq!(prefs.vector_backend, VectorBackend::Chroma);
+        assert_eq!(prefs.budget_profile, SessionClassification::NetNew);
+
+        let out = serde_json::to_value(&prefs).unwrap();
+        assert_eq!(out["experimentalFlag"], true);
+    }
+
+    #[test]
+    fn test_apply_patch_validates_and_reports_changes() {
+        let current = Preferences::default();
+
+        let (updated, changed) = apply_patch(&current, json!({"theme": "light", "cli_tool": "codex"})).unwrap();
+        assert_eq!(updated.theme, Theme::Light);
+        assert_eq!(updated.cli_tool, "codex");
+        assert_eq!(changed, vec!["cliTool", "theme"]);
+
+        assert!(apply_patch(&current, json!({"cliTool": "rm -rf /"})).is_err());
+        assert!(apply_patch(&current, json!({"chromaMode": "external"})).is_err());
+        assert!(apply_patch(&current, json!({"chromaMode": "external", "chromaUrl": "http://localhost:8000"})).is_ok());
+        assert!(apply_patch(&current, json!({"theme": "neon"})).is_err());
+        assert!(apply_patch(&current, json!(["theme"])).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+//! Profiles
+//!
+//! Separate workspaces (e.g. work and personal) sharing one install. Each
+//! profile has its own app data directory, so sessions, preferences (and
+//! with them the vault config), skills and the embedded vector store never
+//! mix, and its own vector collections in a shared Chroma or Qdrant server
+//! (names prefixed with the profile). The `default` profile is the app data
+//! root itself with unprefixed collections, so existing data needs no
+//! migration.
+//!
+//! The active profile is recorded in `<app data root>/active-profile`;
+//! `DIALECTIC_PROFILE` overrides it, which is how launched sessions and the
+//! CLI they call stay on the profile they started in.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use tauri::AppHandle;
+use tracing::info;
+
+use super::ConfigError;
+
+/// Application identifier - must match tauri.conf.json
+pub const APP_IDENTIFIER: &str = "com.dialectic.dev";
+pub const DEFAULT_PROFILE: &str = "default";
+pub const PROFILE_ENV: &str = "DIALECTIC_PROFILE";
+const ACTIVE_PROFILE_FILE: &str = "active-profile";
+const PROFILES_DIR: &str = "profiles";
+const MAX_NAME_LEN: usize = 32;
+
+static CURRENT: LazyLock<String> = LazyLock::new(active_profile);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileInfo {
+    pub name: String,
+    pub active: bool,
+    pub path: String,
+}
+
+/// The shared app data root, holding the default profile
+pub fn app_data_root() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join(APP_IDENTIFIER))
+}
+
+/// Lowercase letters, digits and dashes; used in paths and collection names
+pub fn validate_profile_name(name: &str) -> Result<(), ConfigError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !name.starts_with('-');
+    if valid {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid(format!(
+            "Profile name '{}' must be 1-{} lowercase letters, digits or dashes",
+            name, MAX_NAME_LEN
+        )))
+    }
+}
+
+/// The active profile under `root`: `DIALECTIC_PROFILE`, else the recorded
+/// one, else the default. An invalid name falls back to the default.
+pub fn active_profile_in(root: &Path) -> String {
+    std::env::var(PROFILE_ENV)
+        .ok()
+        .or_else(|| fs::read_to_string(root.join(ACTIVE_PROFILE_FILE)).ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| validate_profile_name(name).is_ok())
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+pub fn active_profile() -> String {
+    app_data_root().map(|root| active_profile_in(&root)).unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+/// The profile this process runs under, fixed at first use (switching
+/// restarts the app)
+pub fn current_profile() -> &'static str {
+    &CURRENT
+}
+
+/// App data directory of profile `name`
+pub fn profile_dir(root: &Path, name: &str) -> PathBuf {
+    if name == DEFAULT_PROFILE {
+        root.to_path_buf()
+    } else {
+        root.join(PROFILES_DIR).join(name)
+    }
+}
+
+/// Vector collection `name` as stored for `profile`
+pub fn scoped_collection_name(profile: &str, name: &str) -> String {
+    if profile == DEFAULT_PROFILE {
+        name.to_string()
+    } else {
+        format!("{}__{}", profile, name)
+    }
+}
+
+/// The unscoped name of `stored` if it belongs to `profile`
+pub fn unscoped_collection_name<'a>(profile: &str, stored: &'a str) -> Option<&'a str> {
+    if profile == DEFAULT_PROFILE {
+        // Other profiles' collections aren't the default profile's
+        (!stored.contains("__")).then_some(stored)
+    } else {
+        stored.strip_prefix(profile)?.strip_prefix("__")
+    }
+}
+
+/// Profiles under `root`: the default plus every directory in `profiles/`
+pub fn list_profiles_in(root: &Path) -> Vec<ProfileInfo> {
+    let active = active_profile_in(root);
+    let mut names = vec![DEFAULT_PROFILE.to_string()];
+    if let Ok(entries) = fs::read_dir(root.join(PROFILES_DIR)) {
+        let mut others: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+            .filter_map(|e| e.file_name().to_str().map(str::to_string))
+            .filter(|name| validate_profile_name(name).is_ok() && name != DEFAULT_PROFILE)
+            .collect();
+        others.sort();
+        names.extend(others);
+    }
+    names
+        .into_iter()
+        .map(|name| ProfileInfo {
+            active: name == active,
+            path: profile_dir(root, &name).to_string_lossy().to_string(),
+            name,
+        })
+        .collect()
+}
+
+/// Record `name` as the active profile under `root`, creating its directory
+pub fn set_active_profile_in(root: &Path, name: &str) -> Result<PathBuf, ConfigError> {
+    validate_profile_name(name)?;
+    let dir = profile_dir(root, name);
+    fs::create_dir_all(&dir)?;
+    let tmp = root.join(format!("{}.tmp", ACTIVE_PROFILE_FILE));
+    fs::write(&tmp, name)?;
+    fs::rename(&tmp, root.join(ACTIVE_PROFILE_FILE))?;
+    Ok(dir)
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn list_profiles() -> Result<Vec<ProfileInfo>, ConfigError> {
+    Ok(list_profiles_in(&app_data_root().ok_or(ConfigError::NoAppDataDir)?))
+}
+
+/// Make `name` the active profile (creating it if new) and restart, so
+/// every cache, watcher and store reopens on the profile's data
+#[tauri::command]
+pub fn switch_profile(app: AppHandle, name: String) -> Result<(), ConfigError> {
+    let root = app_data_root().ok_or(ConfigError::NoAppDataDir)?;
+    if std::env::var(PROFILE_ENV).is_ok() {
+        return Err(ConfigError::Invalid(format!("{} is set; unset it to switch profiles", PROFILE_ENV)));
+    }
+    if active_profile_in(&root) == name {
+        return Ok(());
+    }
+    let dir = set_active_profile_in(&root, &name)?;
+    info!(profile = %name, path = %dir.display(), "Switched profile, restarting");
+    app.restart()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles_isolate_dirs_and_collections() {
+        let root = std::env::temp_dir().join(format!("dialectic_profiles_{}", ulid::Ulid::new()));
+        fs::create_dir_all(&root).unwrap();
+
+        assert!(validate_profile_name("work-2").is_ok());
+        assert!(validate_profile_name("Work").is_err());
+        assert!(validate_profile_name("../x").is_err());
+
+        assert_eq!(profile_dir(&root, DEFAULT_PROFILE), root);
+        assert_eq!(scoped_collection_name(DEFAULT_PROFILE, "documents"), "documents");
+        assert_eq!(scoped_collection_name("work", "documents"), "work__documents");
+        assert_eq!(unscoped_collection_name("work", "work__documents"), Some("documents"));
+        assert_eq!(unscoped_collection_name("work", "documents"), None);
+        assert_eq!(unscoped_collection_name(DEFAULT_PROFILE, "work__documents"), None);
+        assert_eq!(unscoped_collection_name(DEFAULT_PROFILE, "documents"), Some("documents"));
+
+        let dir = set_active_profile_in(&root, "work").unwrap();
+        assert_eq!(dir, root.join("profiles/work"));
+        assert!(dir.is_dir());
+        let profiles = list_profiles_in(&root);
+        let names: Vec<(&str, bool)> = profiles.iter().map(|p| (p.name.as_str(), p.active)).collect();
+        // Unless the environment pins a profile
+        if std::env::var(PROFILE_ENV).is_err() {
+            assert_eq!(names, vec![(DEFAULT_PROFILE, false), ("work", true)]);
+        }
+        assert!(set_active_profile_in(&root, "Bad Name").is_err());
+
+        fs::remove_dir_all(&root).ok();
+    }
+}
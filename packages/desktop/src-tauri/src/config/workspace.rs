@@ -0,0 +1,230 @@
+//! Workspace Settings
+//!
+//! Project-local sessions may carry a `.dialectic/config.toml` in their
+//! working directory. Its values override the global preferences when a
+//! session is created or launched from that project:
+//!
+//! ```toml
+//! default_mode = "decision"
+//! vault = "~/Notes/Work"
+//! reference_folders = ["docs", "research"]
+//! excluded_paths = ["docs/archive/**", "**/*.draft.md"]
+//! ```
+//!
+//! Relative paths resolve against the project root; `~` expands to home.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use super::preferences::Preferences;
+use super::ConfigError;
+use crate::documents::chunker::SUPPORTED_EXTENSIONS;
+use crate::session::SessionMode;
+
+/// Location of the workspace settings file, relative to the project root
+pub const WORKSPACE_CONFIG: &str = ".dialectic/config.toml";
+/// Set on a launch whose workspace uses its own vault
+pub const VAULT_PATH_VAR: &str = "DIALECTIC_VAULT_PATH";
+
+/// Reference files collected per launch, across all folders
+const MAX_REFERENCE_FILES: usize = 50;
+/// Directory depth walked under each reference folder
+const MAX_REFERENCE_DEPTH: u32 = 5;
+
+/// Settings from a project's `.dialectic/config.toml`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkspaceConfig {
+    /// Mode for sessions created in this project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_mode: Option<SessionMode>,
+    /// Glob patterns (relative to the project root) never attached as references
+    pub excluded_paths: Vec<String>,
+    /// Folders whose files are attached as reference documents on launch
+    pub reference_folders: Vec<String>,
+    /// Obsidian vault to use instead of the global one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vault: Option<String>,
+}
+
+/// Resolve `~` and project-relative paths
+fn resolve_path(root: &Path, value: &str) -> PathBuf {
+    if let Some(rest) = value.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    }
+    let path = Path::new(value);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        root.join(path)
+    }
+}
+
+impl WorkspaceConfig {
+    /// Parse and validate TOML text
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+        let config: WorkspaceConfig = toml::from_str(text)
+            .map_err(|e| ConfigError::Invalid(format!("{}: {}", WORKSPACE_CONFIG, e)))?;
+        for pattern in &config.excluded_paths {
+            glob::Pattern::new(pattern)
+                .map_err(|e| ConfigError::Invalid(format!("Invalid excluded path {}: {}", pattern, e)))?;
+        }
+        Ok(config)
+    }
+
+    /// Load the workspace settings for `root`, if the project has any
+    pub fn load(root: &Path) -> Result<Option<Self>, ConfigError> {
+        let path = root.join(WORKSPACE_CONFIG);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        Self::parse(&fs::read_to_string(&path)?).map(Some)
+    }
+
+    /// Global preferences with this workspace's overrides applied
+    pub fn apply(&self, root: &Path, prefs: &Preferences) -> Preferences {
+        let mut effective = prefs.clone();
+        if let Some(mode) = &self.default_mode {
+            effective.default_mode = mode.clone();
+        }
+        if let Some(vault) = &self.vault {
+            effective.vault_path = Some(resolve_path(root, vault).to_string_lossy().to_string());
+        }
+        effective
+    }
+
+    fn is_excluded(&self, root: &Path, path: &Path) -> bool {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+        self.excluded_paths.iter().any(|pattern| {
+            glob::Pattern::new(pattern)
+                .map(|p| p.matches_path_with(relative, options) || relative.starts_with(pattern))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Supported files under the reference folders, minus excluded paths.
+    /// Sorted, deduplicated and capped at `MAX_REFERENCE_FILES`.
+    pub fn reference_files(&self, root: &Path) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        for folder in &self.reference_folders {
+            let dir = resolve_path(root, folder);
+            if !dir.is_dir() {
+                warn!(folder = %dir.display(), "Workspace reference folder not found");
+                continue;
+            }
+            self.collect_files(root, &dir, MAX_REFERENCE_DEPTH, &mut files);
+        }
+        files.sort();
+        files.dedup();
+        files.truncate(MAX_REFERENCE_FILES);
+        files
+    }
+
+    fn collect_files(&self, root: &Path, dir: &Path, remaining_depth: u32, out: &mut Vec<PathBuf>) {
+        if remaining_depth == 0 {
+            return;
+        }
+        let Ok(entries) = fs::read_dir(dir) else { return };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let hidden = path.file_name()
+                .map(|n| n.to_string_lossy().starts_with('.'))
+                .unwrap_or(true);
+            if hidden || self.is_excluded(root, &path) {
+                continue;
+            }
+            if path.is_dir() {
+                self.collect_files(root, &path, remaining_depth - 1, out);
+            } else {
+                let ext = path.extension()
+                    .map(|e| e.to_string_lossy().to_lowercase())
+                    .unwrap_or_default();
+                if SUPPORTED_EXTENSIONS.contains(&ext.as_str()) {
+                    out.push(path);
+                }
+            }
+        }
+    }
+}
+
+/// Effective preferences for a session rooted at `working_dir`.
+///
+/// Returns the global preferences unchanged when the project has no
+/// workspace settings or they fail to parse.
+pub fn effective_preferences(working_dir: &Path) -> (Preferences, Option<WorkspaceConfig>) {
+    let prefs = super::preferences::load_preferences();
+    match WorkspaceConfig::load(working_dir) {
+        Ok(Some(workspace)) => (workspace.apply(working_dir, &prefs), Some(workspace)),
+        Ok(None) => (prefs, None),
+        Err(e) => {
+            warn!(error = %e, dir = %working_dir.display(), "Ignoring invalid workspace settings");
+            (prefs, None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_project() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("dialectic_ws_{}", ulid::Ulid::new()));
+        fs::create_dir_all(dir.join(".dialectic")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_parse_and_apply() {
+        let config = WorkspaceConfig::parse(
+            "default_mode = \"decision\"\nvault = \"notes\"\nreference_folders = [\"docs\"]\n",
+        )
+        .unwrap();
+        assert_eq!(config.default_mode, Some(SessionMode::Decision));
+
+        let root = Path::new("/projects/app");
+        let prefs = config.apply(root, &Preferences::default());
+        assert_eq!(prefs.default_mode, SessionMode::Decision);
+        assert_eq!(prefs.vault_path.as_deref(), Some("/projects/app/notes"));
+        assert_eq!(prefs.cli_tool, Preferences::default().cli_tool);
+
+        assert!(WorkspaceConfig::parse("unknown_key = 1").is_err());
+        assert!(WorkspaceConfig::parse("excluded_paths = [\"[\"]").is_err());
+        assert!(WorkspaceConfig::parse("default_mode = \"bogus\"").is_err());
+    }
+
+    #[test]
+    fn test_reference_files_respect_exclusions() {
+        let root = temp_project();
+        fs::create_dir_all(root.join("docs/archive")).unwrap();
+        fs::write(root.join("docs/spec.md"), "# Spec").unwrap();
+        fs::write(root.join("docs/notes.draft.md"), "draft").unwrap();
+        fs::write(root.join("docs/archive/old.md"), "old").unwrap();
+        fs::write(root.join("docs/logo.png"), [0u8; 4]).unwrap();
+        fs::write(
+            root.join(WORKSPACE_CONFIG),
+            "reference_folders = [\"docs\"]\nexcluded_paths = [\"docs/archive\", \"**/*.draft.md\"]\n",
+        )
+        .unwrap();
+
+        let config = WorkspaceConfig::load(&root).unwrap().unwrap();
+        let files = config.reference_files(&root);
+        assert_eq!(files, vec![root.join("docs/spec.md")]);
+
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_missing_config() {
+        let root = temp_project();
+        assert_eq!(WorkspaceConfig::load(&root).unwrap(), None);
+        fs::remove_dir_all(&root).ok();
+    }
+}
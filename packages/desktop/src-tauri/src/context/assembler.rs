@@ -0,0 +1,485 @@
+//! Context Pack Assembly
+//!
+//! Builds a ready-to-inject Markdown context pack for a session: Paper Trail
+//! tiers first (HEAD and key evidence, then recent and historical summaries),
+//! then Obsidian notes, reference material and memories fitted to the
+//! session's `TokenBudgets`. Candidates that repeat content already in the
+//! pack are dropped before budgeting, and every inclusion or drop is
+//! recorded in an accounting report.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use thiserror::Error;
+use tracing::debug;
+
+use super::budget::{ContextSource, WORKING_BUDGET};
+use super::classification::{SessionClassification, TokenBudgets};
+use super::compression::{PaperTrail, PaperTrailTier};
+use super::tokens::count_tokens;
+use super::unified_search::{allocate, gather_candidates, normalize_scores, SearchSource, UnifiedHit};
+use crate::session::{load_session_cli, validate_session_id};
+
+/// Word-set overlap above which two items count as duplicates
+const OVERLAP_THRESHOLD: f32 = 0.8;
+
+#[derive(Error, Debug)]
+pub enum AssemblerError {
+    #[error("Invalid session ID")]
+    InvalidSessionId,
+    #[error("Session error: {0}")]
+    Session(String),
+}
+
+impl Serialize for AssemblerError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// One Paper Trail entry considered for the pack
+#[derive(Debug, Clone)]
+pub(super) struct TrailItem {
+    pub(super) tier: PaperTrailTier,
+    pub(super) heading: Option<String>,
+    pub(super) content: String,
+    pub(super) token_count: u32,
+}
+
+/// Per-source token usage
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceUsage {
+    pub budget: u32,
+    pub used: u32,
+    pub items: usize,
+}
+
+/// Why a candidate was left out of the pack
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DropReason {
+    OverBudget,
+    Overlap,
+}
+
+/// A candidate left out of the pack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroppedItem {
+    pub source: ContextSource,
+    pub id: String,
+    pub token_count: u32,
+    pub reason: DropReason,
+}
+
+/// Accounting report for an assembled pack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackAccounting {
+    pub classification: SessionClassification,
+    pub budgets: TokenBudgets,
+    pub paper_trail: SourceUsage,
+    pub obsidian: SourceUsage,
+    pub reference: SourceUsage,
+    /// Sum of item token estimates
+    pub items_tokens: u32,
+    /// Exact token count of the rendered Markdown (includes headings)
+    pub pack_tokens: u32,
+    pub dropped: Vec<DroppedItem>,
+}
+
+/// Rendered context pack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextPack {
+    pub session_id: String,
+    pub query: String,
+    pub markdown: String,
+    pub accounting: PackAccounting,
+}
+
+fn context_source(source: SearchSource) -> ContextSource {
+    match source {
+        SearchSource::Document | SearchSource::WebSource => ContextSource::Reference,
+        SearchSource::Obsidian => ContextSource::Obsidian,
+        SearchSource::Memory | SearchSource::Session => ContextSource::PaperTrail,
+    }
+}
+
+fn tier_id(tier: PaperTrailTier) -> &'static str {
+    match tier {
+        PaperTrailTier::Head => "head",
+        PaperTrailTier::KeyEvidence => "key_evidence",
+        PaperTrailTier::Recent => "recent",
+        PaperTrailTier::Historical => "historical",
+        PaperTrailTier::Archived => "archived",
+    }
+}
+
+/// Flatten loadable Paper Trail tiers in priority order
+pub(super) fn trail_items(trail: &PaperTrail) -> Vec<TrailItem> {
+    let mut items = Vec::new();
+
+    let head = &trail.head;
+    if !head.core_claim.is_empty() {
+        let mut content = format!("{} (confidence {:.0}%)", head.core_claim, head.confidence * 100.0);
+        if let Some(intent) = &head.locked_intent {
+            content.push_str(&format!("\n\nLocked intent: {}", intent));
+        }
+        if !head.triggers.is_empty() {
+            content.push_str("\n\nWould change if:");
+            for trigger in &head.triggers {
+                content.push_str(&format!("\n- {}", trigger));
+            }
+        }
+        items.push(TrailItem {
+            tier: PaperTrailTier::Head,
+            heading: None,
+            token_count: head.token_count.max(count_tokens(&content)),
+            content,
+        });
+    }
+
+    for claim in &trail.key_evidence {
+        let content = format!("{} — {}", claim.content, claim.source);
+        items.push(TrailItem {
+            tier: PaperTrailTier::KeyEvidence,
+            heading: None,
+            token_count: claim.token_count.max(count_tokens(&content)),
+            content,
+        });
+    }
+
+    let mut recent: Vec<_> = trail.recent_sessions.iter().collect();
+    recent.sort_by_key(|s| std::cmp::Reverse(s.session_date));
+    for summary in recent {
+        let mut content = summary.summary.clone();
+        for outcome in &summary.key_outcomes {
+            content.push_str(&format!("\n- {}", outcome));
+        }
+        items.push(TrailItem {
+            tier: PaperTrailTier::Recent,
+            heading: Some(summary.session_date.format("%Y-%m-%d").to_string()),
+            token_count: summary.token_count.max(count_tokens(&content)),
+            content,
+        });
+    }
+
+    for historical in &trail.historical_summaries {
+        items.push(TrailItem {
+            tier: PaperTrailTier::Historical,
+            heading: Some(format!(
+                "{} – {}",
+                historical.start_date.format("%Y-%m-%d"),
+                historical.end_date.format("%Y-%m-%d")
+            )),
+            content: historical.summary.clone(),
+            token_count: historical.token_count,
+        });
+    }
+
+    items
+}
+
+fn word_set(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() > 2)
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Whether `candidate` mostly repeats `existing` (containment of its words)
+fn overlaps(candidate: &HashSet<String>, existing: &HashSet<String>) -> bool {
+    if candidate.is_empty() {
+        return true;
+    }
+    let shared = candidate.intersection(existing).count();
+    shared as f32 / candidate.len() as f32 >= OVERLAP_THRESHOLD
+}
+
+/// Drop candidates that repeat content already in the pack or a
+/// higher-scoring candidate. Hits must be sorted best first.
+fn resolve_overlaps(hits: Vec<UnifiedHit>, seen: &mut Vec<HashSet<String>>) -> (Vec<UnifiedHit>, Vec<UnifiedHit>) {
+    let mut kept = Vec::new();
+    let mut removed = Vec::new();
+    let mut ids = HashSet::new();
+
+    for hit in hits {
+        let words = word_set(&hit.content);
+        if !ids.insert((hit.source, hit.id.clone())) || seen.iter().any(|s| overlaps(&words, s)) {
+            removed.push(hit);
+            continue;
+        }
+        seen.push(words);
+        kept.push(hit);
+    }
+
+    (kept, removed)
+}
+
+fn render(title: &str, query: &str, trail: &[TrailItem], hits: &[UnifiedHit]) -> String {
+    let mut md = format!("# Context: {}\n\n", title);
+    if !query.is_empty() {
+        md.push_str(&format!("_Query: {}_\n\n", query));
+    }
+
+    let trail_sections = [
+        (PaperTrailTier::Head, "Thesis"),
+        (PaperTrailTier::KeyEvidence, "Key Evidence"),
+        (PaperTrailTier::Recent, "Recent Sessions"),
+        (PaperTrailTier::Historical, "Historical"),
+    ];
+    for (tier, heading) in trail_sections {
+        let items: Vec<&TrailItem> = trail.iter().filter(|i| i.tier == tier).collect();
+        if items.is_empty() {
+            continue;
+        }
+        md.push_str(&format!("## {}\n\n", heading));
+        for item in items {
+            match (&item.heading, tier) {
+                (Some(h), _) => md.push_str(&format!("### {}\n\n{}\n\n", h, item.content)),
+                (None, PaperTrailTier::KeyEvidence) => md.push_str(&format!("- {}\n", item.content)),
+                (None, _) => md.push_str(&format!("{}\n\n", item.content)),
+            }
+        }
+        if tier == PaperTrailTier::KeyEvidence {
+            md.push('\n');
+        }
+    }
+
+    let hit_sections = [
+        (SearchSource::Obsidian, "Obsidian Notes"),
+        (SearchSource::Document, "Reference Documents"),
+        (SearchSource::WebSource, "Web Sources"),
+        (SearchSource::Memory, "Memories"),
+        (SearchSource::Session, "Related Sessions"),
+    ];
+    for (source, heading) in hit_sections {
+        let items: Vec<&UnifiedHit> = hits.iter().filter(|h| h.source == source).collect();
+        if items.is_empty() {
+            continue;
+        }
+        md.push_str(&format!("## {}\n\n", heading));
+        for hit in items {
+            let label = match (source, &hit.title) {
+                (SearchSource::Obsidian, Some(t)) => format!("[[{}]]", t),
+                (_, Some(t)) => format!("{} ({})", t, hit.id),
+                (_, None) => hit.id.clone(),
+            };
+            md.push_str(&format!("### {}\n\n{}\n\n", label, hit.content.trim()));
+        }
+    }
+
+    md.trim_end().to_string() + "\n"
+}
+
+/// Assemble a context pack for `session_id` around `query`
+pub async fn assemble_context(
+    session_id: &str,
+    query: &str,
+    budget: Option<u32>,
+) -> Result<ContextPack, AssemblerError> {
+    validate_session_id(session_id).map_err(|_| AssemblerError::InvalidSessionId)?;
+    let session = load_session_cli(session_id)
+        .map_err(|e| AssemblerError::Session(e.to_string()))?;
+
+    let classification = session.context_budget
+        .as_ref()
+        .map(|b| b.classification)
+        .unwrap_or_default();
+    let budgets = classification.get_allocation().to_token_budgets(budget.unwrap_or(WORKING_BUDGET));
+    let mut dropped = Vec::new();
+
+    // Paper Trail tiers take the paper trail budget first
+    let mut trail = Vec::new();
+    let mut trail_used = 0u32;
+    for item in session.paper_trail.as_ref().map(trail_items).unwrap_or_default() {
+        if trail_used + item.token_count > budgets.paper_trail {
+            dropped.push(DroppedItem {
+                source: ContextSource::PaperTrail,
+                id: tier_id(item.tier).to_string(),
+                token_count: item.token_count,
+                reason: DropReason::OverBudget,
+            });
+            continue;
+        }
+        trail_used += item.token_count;
+        trail.push(item);
+    }
+
+    // Retrieved material fills what's left
+    let mut candidates = if query.trim().is_empty() {
+        Vec::new()
+    } else {
+        gather_candidates(session_id, query, &budgets).await
+    };
+    normalize_scores(&mut candidates);
+    candidates.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut seen: Vec<HashSet<String>> = trail.iter().map(|i| word_set(&i.content)).collect();
+    let (candidates, duplicates) = resolve_overlaps(candidates, &mut seen);
+    dropped.extend(duplicates.into_iter().map(|h| DroppedItem {
+        source: context_source(h.source),
+        id: h.id,
+        token_count: h.token_count,
+        reason: DropReason::Overlap,
+    }));
+
+    let remaining = TokenBudgets {
+        paper_trail: budgets.paper_trail.saturating_sub(trail_used),
+        ..budgets
+    };
+    let candidate_keys: Vec<(SearchSource, String, u32)> = candidates
+        .iter()
+        .map(|h| (h.source, h.id.clone(), h.token_count))
+        .collect();
+    let (hits, hits_used, _) = allocate(candidates, &remaining);
+    for (source, id, token_count) in candidate_keys {
+        if !hits.iter().any(|h| h.source == source && h.id == id) {
+            dropped.push(DroppedItem {
+                source: context_source(source),
+                id,
+                token_count,
+                reason: DropReason::OverBudget,
+            });
+        }
+    }
+
+    let usage = |target: ContextSource, budget: u32, base: u32, base_items: usize| {
+        let matching: Vec<&UnifiedHit> = hits.iter().filter(|h| context_source(h.source) == target).collect();
+        SourceUsage {
+            budget,
+            used: base + matching.iter().map(|h| h.token_count).sum::<u32>(),
+            items: base_items + matching.len(),
+        }
+    };
+    let paper_trail = usage(ContextSource::PaperTrail, budgets.paper_trail, trail_used, trail.len());
+    let obsidian = usage(ContextSource::Obsidian, budgets.obsidian, 0, 0);
+    let reference = usage(ContextSource::Reference, budgets.reference, 0, 0);
+
+    let markdown = render(&session.title, query, &trail, &hits);
+    let pack_tokens = count_tokens(&markdown);
+
+    debug!(
+        session_id = %session_id,
+        items = trail.len() + hits.len(),
+        dropped = dropped.len(),
+        pack_tokens = pack_tokens,
+        "Assembled context pack"
+    );
+
+    Ok(ContextPack {
+        session_id: session_id.to_string(),
+        query: query.to_string(),
+        markdown,
+        accounting: PackAccounting {
+            classification,
+            budgets,
+            paper_trail,
+            obsidian,
+            reference,
+            items_tokens: trail_used + hits_used,
+            pack_tokens,
+            dropped,
+        },
+    })
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub async fn context_assemble_pack(
+    session_id: String,
+    query: String,
+    budget: Option<u32>,
+) -> Result<ContextPack, AssemblerError> {
+    assemble_context(&session_id, &query, budget).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::compression::{KeyClaim, ThesisHead};
+    use chrono::Utc;
+    use serde_json::Value;
+
+    fn hit(source: SearchSource, id: &str, content: &str, score: f32) -> UnifiedHit {
+        UnifiedHit {
+            source,
+            id: id.to_string(),
+            title: None,
+            content: content.to_string(),
+            snippet: None,
+            score,
+            raw_score: score,
+            token_count: 10,
+            session_id: None,
+            metadata: Value::Null,
+        }
+    }
+
+    #[test]
+    fn test_trail_items_priority_order() {
+        let trail = PaperTrail {
+            head: ThesisHead {
+                core_claim: "Rates stay higher for longer".to_string(),
+                confidence: 0.7,
+                triggers: vec!["CPI below 2%".to_string()],
+                ..Default::default()
+            },
+            key_evidence: vec![KeyClaim {
+                id: "k1".to_string(),
+                content: "Core services inflation is sticky".to_string(),
+                source: "BLS".to_string(),
+                added_at: Utc::now(),
+                reason: None,
+                token_count: 0,
+            }],
+            ..Default::default()
+        };
+
+        let items = trail_items(&trail);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].tier, PaperTrailTier::Head);
+        assert!(items[0].content.contains("confidence 70%"));
+        assert!(items[0].content.contains("- CPI below 2%"));
+        assert!(items[1].token_count > 0);
+    }
+
+    #[test]
+    fn test_resolve_overlaps() {
+        let mut seen = vec![word_set("Core services inflation is sticky and persistent")];
+        let hits = vec![
+            hit(SearchSource::Memory, "m1", "Core services inflation is sticky", 1.0),
+            hit(SearchSource::Obsidian, "n1", "Housing supply constrains rents", 0.9),
+            hit(SearchSource::Document, "d1", "Housing supply constrains rents in coastal metro areas", 0.8),
+            hit(SearchSource::Obsidian, "n1", "Housing supply constrains rents", 0.7),
+        ];
+
+        let (kept, removed) = resolve_overlaps(hits, &mut seen);
+        let kept_ids: Vec<&str> = kept.iter().map(|h| h.id.as_str()).collect();
+        assert_eq!(kept_ids, vec!["n1", "d1"]);
+        assert_eq!(removed.len(), 2);
+    }
+
+    #[test]
+    fn test_render_sections() {
+        let trail = vec![TrailItem {
+            tier: PaperTrailTier::Head,
+            heading: None,
+            content: "Thesis text".to_string(),
+            token_count: 3,
+        }];
+        let mut note = hit(SearchSource::Obsidian, "Notes/Rates.md", "Note body", 1.0);
+        note.title = Some("Rates".to_string());
+
+        let md = render("Macro", "rates", &trail, &[note]);
+        assert!(md.starts_with("# Context: Macro"));
+        assert!(md.contains("## Thesis\n\nThesis text"));
+        assert!(md.contains("## Obsidian Notes\n\n### [[Rates]]\n\nNote body"));
+        assert!(!md.contains("## Memories"));
+    }
+}
@@ -4,7 +4,8 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use super::classification::{SessionClassification, TokenBudgets};
+use crate::session::{load_session_cli, save_session_cli, validate_session_id, SessionError};
+use super::classification::{ClassificationChange, ClassificationSignals, SessionClassification, TokenBudgets};
 
 /// Total context window budget
 pub const TOTAL_BUDGET: u32 = 100_000;
@@ -18,6 +19,9 @@ pub const THRESHOLD_AUTO_COMPRESS: u8 = 70;
 pub const THRESHOLD_WARN_USER: u8 = 85;
 pub const THRESHOLD_FORCE_COMPRESS: u8 = 95;
 
+/// Recent pass outputs kept for the output size estimate
+const OUTPUT_HISTORY_LEN: usize = 10;
+
 /// Context budget tracking for a session
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -42,6 +46,14 @@ pub struct ContextBudget {
 
     /// Last audit timestamp
     pub last_audit: DateTime<Utc>,
+
+    /// Classification changes made by launch-time reclassification
+    #[serde(default)]
+    pub classification_history: Vec<ClassificationChange>,
+
+    /// Output sizes of recent passes (newest last)
+    #[serde(default)]
+    pub pass_output_tokens: Vec<u32>,
 }
 
 impl Default for ContextBudget {
@@ -66,6 +78,8 @@ impl ContextBudget {
             reference_used: 0,
             reasoning_budget: budgets.reasoning,
             last_audit: Utc::now(),
+            classification_history: Vec::new(),
+            pass_output_tokens: Vec::new(),
         }
     }
 
@@ -82,6 +96,28 @@ impl ContextBudget {
         self.last_audit = Utc::now();
     }
 
+    /// Reclassify from fresh signals, recording the change.
+    ///
+    /// Returns the change if the classification moved.
+    pub fn reclassify_from_signals(
+        &mut self,
+        classification: SessionClassification,
+        signals: ClassificationSignals,
+    ) -> Option<ClassificationChange> {
+        if classification == self.classification {
+            return None;
+        }
+        let change = ClassificationChange {
+            from: self.classification,
+            to: classification,
+            changed_at: Utc::now(),
+            signals,
+        };
+        self.reclassify(classification);
+        self.classification_history.push(change.clone());
+        Some(change)
+    }
+
     /// Get total tokens used across all sources
     pub fn total_used(&self) -> u32 {
         self.paper_trail_used + self.obsidian_used + self.reference_used
@@ -161,6 +197,83 @@ impl ContextBudget {
     pub fn record_audit(&mut self) {
         self.last_audit = Utc::now();
     }
+
+    /// Record the output size of a completed pass
+    pub fn record_pass_output(&mut self, tokens: u32) {
+        self.pass_output_tokens.push(tokens);
+        if self.pass_output_tokens.len() > OUTPUT_HISTORY_LEN {
+            let excess = self.pass_output_tokens.len() - OUTPUT_HISTORY_LEN;
+            self.pass_output_tokens.drain(..excess);
+        }
+    }
+
+    /// Output to reserve for the next pass: the largest recent pass output,
+    /// never less than OUTPUT_RESERVED
+    pub fn expected_output_tokens(&self) -> u32 {
+        self.pass_output_tokens
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(0)
+            .max(OUTPUT_RESERVED)
+    }
+
+    /// Check whether `planned_tokens` more context fits alongside the
+    /// expected output within TOTAL_BUDGET
+    pub fn check_fit(&self, planned_tokens: u32) -> FitCheck {
+        let working_used = self.total_used();
+        let reserved_output = self.expected_output_tokens();
+        let projected = working_used as u64 + planned_tokens as u64 + reserved_output as u64;
+        let fits = projected <= TOTAL_BUDGET as u64;
+        let headroom = TOTAL_BUDGET.saturating_sub(working_used).saturating_sub(reserved_output);
+
+        let mut after = self.clone();
+        after.paper_trail_used = after.paper_trail_used.saturating_add(planned_tokens);
+        let projected_status = after.threshold_status();
+
+        let warning = if !fits {
+            Some(format!(
+                "Loading {} tokens would overflow the context window: {} used + {} planned + {} reserved for output > {}",
+                planned_tokens, working_used, planned_tokens, reserved_output, TOTAL_BUDGET
+            ))
+        } else if projected_status != ThresholdStatus::Normal {
+            Some(format!(
+                "Loading {} tokens fits but crosses the {:?} threshold",
+                planned_tokens, projected_status
+            ))
+        } else {
+            None
+        };
+
+        FitCheck {
+            fits,
+            planned_tokens,
+            working_used,
+            reserved_output,
+            total_budget: TOTAL_BUDGET,
+            headroom,
+            projected_status,
+            warning,
+        }
+    }
+}
+
+/// Result of checking whether more material fits in the context window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FitCheck {
+    pub fits: bool,
+    pub planned_tokens: u32,
+    pub working_used: u32,
+    /// Output tokens reserved for the next pass
+    pub reserved_output: u32,
+    pub total_budget: u32,
+    /// Context tokens that can still be loaded
+    pub headroom: u32,
+    /// Threshold status once the planned tokens are loaded
+    pub projected_status: ThresholdStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
 }
 
 /// Budget threshold status
@@ -278,6 +391,25 @@ pub fn context_get_budget_constants() -> serde_json::Value {
     })
 }
 
+#[tauri::command]
+pub fn context_can_fit(session_id: String, planned_tokens: u32) -> Result<FitCheck, SessionError> {
+    validate_session_id(&session_id)?;
+    let session = load_session_cli(&session_id)?;
+    Ok(session.context_budget.unwrap_or_default().check_fit(planned_tokens))
+}
+
+#[tauri::command]
+pub fn context_record_pass_output(session_id: String, output_tokens: u32) -> Result<FitCheck, SessionError> {
+    validate_session_id(&session_id)?;
+    let mut session = load_session_cli(&session_id)?;
+    let budget = session.context_budget.get_or_insert_with(Default::default);
+    budget.record_pass_output(output_tokens);
+    let check = budget.check_fit(0);
+    crate::metrics::record_session_pass(&session_id, output_tokens, budget.total_used());
+    save_session_cli(&session)?;
+    Ok(check)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -297,6 +429,52 @@ mod tests {
         assert_eq!(budget.paper_trail_used, 0);
     }
 
+    #[test]
+    fn test_reclassify_records_change() {
+        let mut budget = ContextBudget::new(SessionClassification::NetNew);
+        assert!(budget
+            .reclassify_from_signals(SessionClassification::NetNew, ClassificationSignals::default())
+            .is_none());
+
+        let change = budget
+            .reclassify_from_signals(SessionClassification::Fit, ClassificationSignals::default())
+            .unwrap();
+        assert_eq!(change.from, SessionClassification::NetNew);
+        assert_eq!(budget.classification, SessionClassification::Fit);
+        assert_eq!(budget.paper_trail_budget, 28800);
+        assert_eq!(budget.classification_history.len(), 1);
+    }
+
+    #[test]
+    fn test_check_fit_reserves_output() {
+        let mut budget = ContextBudget::new(SessionClassification::Fit);
+        budget.paper_trail_used = 20_000;
+
+        let check = budget.check_fit(50_000);
+        assert!(check.fits);
+        assert_eq!(check.headroom, TOTAL_BUDGET - 20_000 - OUTPUT_RESERVED);
+
+        let check = budget.check_fit(check.headroom + 1);
+        assert!(!check.fits);
+        assert!(check.warning.is_some());
+
+        // A large recent pass output raises the reservation
+        budget.record_pass_output(40_000);
+        assert_eq!(budget.expected_output_tokens(), 40_000);
+        assert!(!budget.check_fit(50_000).fits);
+    }
+
+    #[test]
+    fn test_pass_output_history_is_bounded() {
+        let mut budget = ContextBudget::default();
+        for i in 0..15 {
+            budget.record_pass_output(i);
+        }
+        assert_eq!(budget.pass_output_tokens.len(), OUTPUT_HISTORY_LEN);
+        assert_eq!(budget.pass_output_tokens[0], 5);
+        assert_eq!(budget.expected_output_tokens(), OUTPUT_RESERVED);
+    }
+
     #[test]
     fn test_add_tokens() {
         let mut budget = ContextBudget::new(SessionClassification::NetNew);
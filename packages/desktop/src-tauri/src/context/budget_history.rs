@@ -0,0 +1,167 @@
+//! Budget History
+//!
+//! Burn-down data for a session's context budget. Every session write that
+//! changes the budget's usage or allocation appends a snapshot to the
+//! session's `budget.log`, so the UI can chart usage over a long session
+//! and see which source is eating the window.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use tracing::warn;
+
+use super::budget::{ContextBudget, ContextSource};
+use super::classification::SessionClassification;
+use crate::session::{get_session_dir_cli, SessionError};
+
+pub const BUDGET_LOG: &str = "budget.log";
+
+/// Usage and allocation of each source at one moment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetSnapshot {
+    pub timestamp: DateTime<Utc>,
+    pub classification: SessionClassification,
+    pub paper_trail_used: u32,
+    pub paper_trail_budget: u32,
+    pub obsidian_used: u32,
+    pub obsidian_budget: u32,
+    pub reference_used: u32,
+    pub reference_budget: u32,
+    pub usage_percentage: u8,
+}
+
+impl BudgetSnapshot {
+    pub fn of(budget: &ContextBudget, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            timestamp,
+            classification: budget.classification,
+            paper_trail_used: budget.paper_trail_used,
+            paper_trail_budget: budget.paper_trail_budget,
+            obsidian_used: budget.obsidian_used,
+            obsidian_budget: budget.obsidian_budget,
+            reference_used: budget.reference_used,
+            reference_budget: budget.reference_budget,
+            usage_percentage: budget.usage_percentage(),
+        }
+    }
+
+    pub fn used(&self, source: ContextSource) -> u32 {
+        match source {
+            ContextSource::PaperTrail => self.paper_trail_used,
+            ContextSource::Obsidian => self.obsidian_used,
+            ContextSource::Reference => self.reference_used,
+        }
+    }
+
+    /// Same usage and allocation, whenever taken
+    fn same_as(&self, other: &BudgetSnapshot) -> bool {
+        BudgetSnapshot { timestamp: other.timestamp, ..self.clone() } == *other
+    }
+}
+
+/// Change in one source's usage across the history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceGrowth {
+    pub source: ContextSource,
+    pub tokens: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetHistory {
+    /// Oldest first
+    pub snapshots: Vec<BudgetSnapshot>,
+    /// Per source, fastest growing first
+    pub growth: Vec<SourceGrowth>,
+}
+
+/// Append a snapshot of `after` if it differs from `before`
+pub fn record_if_changed(session_dir: &Path, before: Option<&ContextBudget>, after: Option<&ContextBudget>) {
+    let Some(after) = after else { return };
+    let now = Utc::now();
+    let snapshot = BudgetSnapshot::of(after, now);
+    if before.is_some_and(|b| BudgetSnapshot::of(b, now).same_as(&snapshot)) {
+        return;
+    }
+    let result = serde_json::to_string(&snapshot).map_err(SessionError::from).and_then(|line| {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(session_dir.join(BUDGET_LOG))?;
+        file.write_all(format!("{}\n", line).as_bytes())?;
+        Ok(())
+    });
+    if let Err(e) = result {
+        warn!(error = %e, dir = %session_dir.display(), "Failed to record budget snapshot");
+    }
+}
+
+pub fn read_budget_log(session_dir: &Path) -> Result<Vec<BudgetSnapshot>, SessionError> {
+    let path = session_dir.join(BUDGET_LOG);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(fs::read_to_string(&path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Order snapshots and work out how much each source grew
+pub fn build_history(mut snapshots: Vec<BudgetSnapshot>) -> BudgetHistory {
+    snapshots.sort_by_key(|s| s.timestamp);
+    let mut growth: Vec<SourceGrowth> = match (snapshots.first(), snapshots.last()) {
+        (Some(first), Some(last)) => [ContextSource::PaperTrail, ContextSource::Obsidian, ContextSource::Reference]
+            .into_iter()
+            .map(|source| SourceGrowth { source, tokens: last.used(source) as i64 - first.used(source) as i64 })
+            .collect(),
+        _ => Vec::new(),
+    };
+    growth.sort_by_key(|g| std::cmp::Reverse(g.tokens));
+    BudgetHistory { snapshots, growth }
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn get_budget_history(session_id: String) -> Result<BudgetHistory, SessionError> {
+    Ok(build_history(read_budget_log(&get_session_dir_cli(&session_id)?)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshots_only_on_change() {
+        let dir = std::env::temp_dir().join(format!("dialectic_budget_{}", ulid::Ulid::new()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut budget = ContextBudget::new(SessionClassification::NetNew);
+        record_if_changed(&dir, None, Some(&budget));
+        let before = budget.clone();
+        budget.last_audit = Utc::now();
+        record_if_changed(&dir, Some(&before), Some(&budget));
+        assert_eq!(read_budget_log(&dir).unwrap().len(), 1);
+
+        budget.paper_trail_used = 4_000;
+        budget.reference_used = 1_000;
+        record_if_changed(&dir, Some(&before), Some(&budget));
+        let before = budget.clone();
+        budget.reference_used = 9_000;
+        record_if_changed(&dir, Some(&before), Some(&budget));
+
+        let history = build_history(read_budget_log(&dir).unwrap());
+        assert_eq!(history.snapshots.len(), 3);
+        assert_eq!(history.snapshots[2].reference_used, 9_000);
+        assert_eq!(history.growth[0].source, ContextSource::Reference);
+        assert_eq!(history.growth[0].tokens, 9_000);
+        assert_eq!(history.growth[2].tokens, 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -3,8 +3,13 @@
 //! Determines how a session relates to existing theses and allocates
 //! context budgets accordingly.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+/// Share of title keywords found in the vault above which a session counts
+/// as adjacent to existing work
+const ADJACENT_VAULT_HIT_RATE: f32 = 0.5;
+
 /// Session classification that determines context budget allocation
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -120,6 +125,26 @@ pub struct ClassificationSignals {
     pub user_marked_quick: bool,
     /// Whether session has existing paper trail
     pub has_paper_trail: bool,
+    /// Fraction of keywords with at least one vault match (0.0 - 1.0)
+    #[serde(default)]
+    pub vault_hit_rate: f32,
+    /// Reference documents attached to the session
+    #[serde(default)]
+    pub reference_doc_count: usize,
+    /// Tokens currently loaded from the paper trail
+    #[serde(default)]
+    pub paper_trail_tokens: u32,
+}
+
+/// A recorded classification change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassificationChange {
+    pub from: SessionClassification,
+    pub to: SessionClassification,
+    pub changed_at: DateTime<Utc>,
+    /// Signals that produced the new classification
+    pub signals: ClassificationSignals,
 }
 
 /// Classify a session based on its signals
@@ -134,9 +159,15 @@ pub fn classify_session(signals: &ClassificationSignals) -> SessionClassificatio
 
     if max_similarity >= 0.8 {
         SessionClassification::Fit
-    } else if max_similarity >= 0.4 || signals.has_paper_trail {
+    } else if max_similarity >= 0.4
+        || signals.has_paper_trail
+        || signals.vault_hit_rate >= ADJACENT_VAULT_HIT_RATE
+    {
         SessionClassification::Adjacent
-    } else if signals.related_thesis_ids.is_empty() && signals.keywords.is_empty() {
+    } else if signals.related_thesis_ids.is_empty()
+        && signals.keywords.is_empty()
+        && signals.reference_doc_count == 0
+    {
         SessionClassification::Quick
     } else {
         SessionClassification::NetNew
@@ -204,6 +235,16 @@ mod tests {
         assert_eq!(classify_session(&signals), SessionClassification::Quick);
     }
 
+    #[test]
+    fn test_classify_vault_coverage_is_adjacent() {
+        let signals = ClassificationSignals {
+            keywords: vec!["rates".to_string()],
+            vault_hit_rate: 0.6,
+            ..Default::default()
+        };
+        assert_eq!(classify_session(&signals), SessionClassification::Adjacent);
+    }
+
     #[test]
     fn test_token_budgets() {
         let alloc = SessionClassification::Fit.get_allocation();
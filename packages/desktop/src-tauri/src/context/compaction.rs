@@ -0,0 +1,259 @@
+//! Compaction Payload
+//!
+//! What a session must keep when Claude Code compacts its conversation:
+//! the thesis HEAD, key evidence, unresolved tensions and pinned reference
+//! chunks, in that priority order, rendered as one Markdown block that fits
+//! a token budget. `dialectic compact-context` prints it for the compaction
+//! hook, so the Paper Trail's always-loaded tiers survive compaction rather
+//! than being summarized away.
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::assembler::{trail_items, AssemblerError};
+use super::compression::PaperTrailTier;
+use super::tokens::count_tokens;
+use crate::documents::retriever::{get_chunk, list_references};
+use crate::session::{load_session_cli, validate_session_id, Session};
+
+/// Default payload size: the HEAD and key evidence tier targets
+pub const DEFAULT_COMPACTION_TOKENS: u32 = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreservedKind {
+    Head,
+    KeyEvidence,
+    Tension,
+    PinnedChunk,
+}
+
+/// One entry considered for the payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreservedItem {
+    pub kind: PreservedKind,
+    pub id: String,
+    /// Sub-heading, for pinned chunks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading: Option<String>,
+    pub content: String,
+}
+
+/// An entry left out to stay within the budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OmittedItem {
+    pub kind: PreservedKind,
+    pub id: String,
+    pub token_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactionPayload {
+    pub session_id: String,
+    pub markdown: String,
+    pub token_budget: u32,
+    pub token_count: u32,
+    pub included: usize,
+    pub omitted: Vec<OmittedItem>,
+}
+
+/// HEAD, key evidence and unresolved tensions of `session`, in priority
+/// order. HEAD falls back to the session thesis before the Paper Trail has one.
+pub fn session_items(session: &Session) -> Vec<PreservedItem> {
+    let trail = session.paper_trail.as_ref().map(trail_items).unwrap_or_default();
+    let mut items = Vec::new();
+
+    match trail.iter().find(|i| i.tier == PaperTrailTier::Head) {
+        Some(head) => items.push(PreservedItem { kind: PreservedKind::Head, id: "head".to_string(), heading: None, content: head.content.clone() }),
+        None => {
+            if let Some(thesis) = &session.thesis {
+                items.push(PreservedItem {
+                    kind: PreservedKind::Head,
+                    id: "head".to_string(),
+                    heading: None,
+                    content: format!("{} (confidence {:.0}%)", thesis.content, thesis.confidence * 100.0),
+                });
+            }
+        }
+    }
+
+    let key_evidence = session.paper_trail.iter().flat_map(|t| &t.key_evidence);
+    for (claim, item) in key_evidence.zip(trail.iter().filter(|i| i.tier == PaperTrailTier::KeyEvidence)) {
+        items.push(PreservedItem { kind: PreservedKind::KeyEvidence, id: claim.id.clone(), heading: None, content: item.content.clone() });
+    }
+
+    let claim_text = |id: &str| session.claims.iter().find(|c| c.id == id).map(|c| c.content.as_str()).unwrap_or(id).to_string();
+    for tension in session.tensions.iter().filter(|t| t.resolution.is_none()) {
+        items.push(PreservedItem {
+            kind: PreservedKind::Tension,
+            id: tension.id.clone(),
+            heading: None,
+            content: format!(
+                "{}\n  - A: {}\n  - B: {}",
+                tension.description,
+                claim_text(&tension.claim_a_id),
+                claim_text(&tension.claim_b_id)
+            ),
+        });
+    }
+
+    items
+}
+
+/// Pinned chunks of the session's reference documents, in document order
+fn pinned_items(session_id: &str) -> Vec<PreservedItem> {
+    let documents = match list_references(session_id) {
+        Ok(documents) => documents,
+        Err(e) => {
+            debug!(session_id = %session_id, error = %e, "No reference documents for compaction payload");
+            return Vec::new();
+        }
+    };
+    let mut items = Vec::new();
+    for doc in documents {
+        for &index in &doc.pinned_chunks {
+            match get_chunk(session_id, &doc.id, index) {
+                Ok(chunk) => items.push(PreservedItem {
+                    kind: PreservedKind::PinnedChunk,
+                    id: format!("{}#{}", doc.id, index),
+                    heading: Some(match chunk.section {
+                        Some(section) => format!("{} — {}", doc.filename, section),
+                        None => format!("{} (chunk {})", doc.filename, index + 1),
+                    }),
+                    content: chunk.content.trim().to_string(),
+                }),
+                Err(e) => debug!(doc_id = %doc.id, chunk = index, error = %e, "Pinned chunk unavailable"),
+            }
+        }
+    }
+    items
+}
+
+fn render(title: &str, items: &[&PreservedItem], omitted: usize) -> String {
+    let mut md = format!("# Preserve: {}\n\n", title);
+    let sections = [
+        (PreservedKind::Head, "Thesis"),
+        (PreservedKind::KeyEvidence, "Key Evidence"),
+        (PreservedKind::Tension, "Unresolved Tensions"),
+        (PreservedKind::PinnedChunk, "Pinned Excerpts"),
+    ];
+    for (kind, heading) in sections {
+        let section: Vec<&&PreservedItem> = items.iter().filter(|i| i.kind == kind).collect();
+        if section.is_empty() {
+            continue;
+        }
+        md.push_str(&format!("## {}\n\n", heading));
+        for item in section {
+            match (&item.heading, kind) {
+                (Some(h), _) => md.push_str(&format!("### {}\n\n{}\n\n", h, item.content)),
+                (None, PreservedKind::KeyEvidence | PreservedKind::Tension) => md.push_str(&format!("- {}\n", item.content)),
+                (None, _) => md.push_str(&format!("{}\n\n", item.content)),
+            }
+        }
+        if matches!(kind, PreservedKind::KeyEvidence | PreservedKind::Tension) {
+            md.push('\n');
+        }
+    }
+    if omitted > 0 {
+        md.push_str(&format!("_{} lower-priority items omitted to fit the budget._\n", omitted));
+    }
+    md.trim_end().to_string() + "\n"
+}
+
+/// Fit `items` (priority order) into `max_tokens`. HEAD is always kept;
+/// anything else that would push the rendered block over budget is skipped,
+/// so a smaller later item can still take the remaining room.
+pub fn build_payload(session: &Session, items: Vec<PreservedItem>, max_tokens: u32) -> CompactionPayload {
+    let mut kept: Vec<&PreservedItem> = Vec::new();
+    let mut omitted = Vec::new();
+    for item in &items {
+        kept.push(item);
+        if item.kind != PreservedKind::Head && count_tokens(&render(&session.title, &kept, omitted.len() + 1)) > max_tokens {
+            kept.pop();
+            omitted.push(OmittedItem { kind: item.kind, id: item.id.clone(), token_count: count_tokens(&item.content) });
+        }
+    }
+
+    let markdown = render(&session.title, &kept, omitted.len());
+    CompactionPayload {
+        session_id: session.id.clone(),
+        token_count: count_tokens(&markdown),
+        markdown,
+        token_budget: max_tokens,
+        included: kept.len(),
+        omitted,
+    }
+}
+
+/// The compaction payload for `session_id`
+pub fn compact_context(session_id: &str, max_tokens: Option<u32>) -> Result<CompactionPayload, AssemblerError> {
+    validate_session_id(session_id).map_err(|_| AssemblerError::InvalidSessionId)?;
+    let session = load_session_cli(session_id).map_err(|e| AssemblerError::Session(e.to_string()))?;
+    let mut items = session_items(&session);
+    items.extend(pinned_items(session_id));
+    let payload = build_payload(&session, items, max_tokens.unwrap_or(DEFAULT_COMPACTION_TOKENS));
+    debug!(
+        session_id = %session_id,
+        included = payload.included,
+        omitted = payload.omitted.len(),
+        tokens = payload.token_count,
+        "Built compaction payload"
+    );
+    Ok(payload)
+}
+
+// ============ TAURI COMMANDS ============
+
+/// Preview what the compaction hook would preserve
+#[tauri::command]
+pub fn context_compaction_payload(session_id: String, max_tokens: Option<u32>) -> Result<CompactionPayload, AssemblerError> {
+    compact_context(&session_id, max_tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use serde_json::json;
+
+    #[test]
+    fn test_build_payload_prioritizes_within_budget() {
+        let session: Session = test_session(json!({
+            "id": "cmp", "title": "Rates", "status": "tensions",
+            "thesis": { "content": "Cuts come late", "confidence": 0.7, "updatedAt": "2026-01-01T00:00:00Z" },
+            "claims": [
+                { "id": "a", "content": "Inflation is sticky", "sourceId": "s", "marker": null, "createdAt": "2026-01-01T00:00:00Z" },
+                { "id": "b", "content": "Labor is softening", "sourceId": "s", "marker": null, "createdAt": "2026-01-01T00:00:00Z" },
+            ],
+            "tensions": [
+                { "id": "t1", "claimAId": "a", "claimBId": "b", "description": "Which dominates", "resolution": null, "createdAt": "2026-01-01T00:00:00Z" },
+                { "id": "t2", "claimAId": "a", "claimBId": "b", "description": "Settled", "resolution": "done", "createdAt": "2026-01-01T00:00:00Z" },
+            ],
+        }));
+
+        let mut items = session_items(&session);
+        assert_eq!(items.iter().map(|i| i.kind).collect::<Vec<_>>(), vec![PreservedKind::Head, PreservedKind::Tension]);
+        items.push(PreservedItem {
+            kind: PreservedKind::PinnedChunk,
+            id: "doc#0".to_string(),
+            heading: Some("fomc.pdf (chunk 1)".to_string()),
+            content: "minutes ".repeat(400),
+        });
+
+        let payload = build_payload(&session, items.clone(), 200);
+        assert!(payload.markdown.contains("## Thesis\n\nCuts come late (confidence 70%)"));
+        assert!(payload.markdown.contains("- Which dominates\n  - A: Inflation is sticky\n  - B: Labor is softening"));
+        assert!(!payload.markdown.contains("Settled"));
+        assert!(!payload.markdown.contains("Pinned Excerpts"));
+        assert_eq!(payload.omitted.len(), 1);
+        assert_eq!(payload.omitted[0].kind, PreservedKind::PinnedChunk);
+        assert!(payload.token_count <= 200);
+
+        let roomy = build_payload(&session, items, 10_000);
+        assert!(roomy.markdown.contains("### fomc.pdf (chunk 1)"));
+        assert!(roomy.omitted.is_empty());
+    }
+}
@@ -335,6 +335,7 @@ pub fn context_create_compression_request(
     max_output_tokens: u32,
     reason: ArchiveReason,
 ) -> CompressionRequest {
+    crate::metrics::increment(crate::metrics::COMPRESSION_REQUESTS, 1);
     CompressionRequest {
         source_tier,
         target_tier,
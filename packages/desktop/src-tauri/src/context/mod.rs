@@ -3,10 +3,15 @@
 //! Handles intelligent context management that balances three competing context sources
 //! (Paper Trail, Obsidian, Reference Documents) within a ~100K token budget.
 
+pub mod assembler;
 pub mod budget;
+pub mod budget_history;
 pub mod classification;
+pub mod compaction;
 pub mod compression;
+pub mod reclassify;
 pub mod tokens;
+pub mod unified_search;
 
 // Re-export public types for external use
 pub use budget::{ContextBudget, BudgetStatus, SourceStatus, ThresholdStatus, ContextSource};
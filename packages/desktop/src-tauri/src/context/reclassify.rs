@@ -0,0 +1,133 @@
+//! Launch-Time Reclassification
+//!
+//! Sessions start as NetNew. Each launch rebuilds `ClassificationSignals`
+//! from live state (related sessions, vault coverage, attached documents,
+//! paper trail size), re-runs `classify_session` and rebalances the
+//! session's budgets if the classification moved.
+
+use super::budget::WORKING_BUDGET;
+use super::classification::{
+    classify_session, ClassificationChange, ClassificationSignals, SessionClassification,
+};
+use crate::chroma::search::RelatedSessionResults;
+use crate::obsidian::indexer::VaultIndex;
+use crate::obsidian::query::{query_notes, score_notes, GraphBoost};
+use crate::session::Session;
+
+/// Title words shorter than this are not used as keywords
+const MIN_KEYWORD_CHARS: usize = 4;
+/// Keywords checked against the vault per launch
+const MAX_KEYWORDS: usize = 8;
+
+/// Distinctive title/category words used as keywords
+fn session_keywords(session: &Session) -> Vec<String> {
+    let mut keywords: Vec<String> = Vec::new();
+    let words = session.title.split(|c: char| !c.is_alphanumeric())
+        .chain(session.category.as_deref());
+    for word in words {
+        let word = word.to_lowercase();
+        if word.chars().count() >= MIN_KEYWORD_CHARS && !keywords.contains(&word) {
+            keywords.push(word);
+        }
+    }
+    keywords.truncate(MAX_KEYWORDS);
+    keywords
+}
+
+/// Fraction of keywords with at least one match in `vault`, or in the
+/// configured vault without one (0 when no vault)
+fn vault_hit_rate(keywords: &[String], vault: Option<&VaultIndex>) -> f32 {
+    if keywords.is_empty() {
+        return 0.0;
+    }
+    let matches = |keyword: &String| match vault {
+        Some(vault) => !score_notes(vault, keyword, WORKING_BUDGET, &GraphBoost::default(), &Default::default()).is_empty(),
+        None => query_notes(keyword, WORKING_BUDGET).is_ok_and(|r| !r.is_empty()),
+    };
+    let hits = keywords.iter().filter(|k| matches(k)).count();
+    hits as f32 / keywords.len() as f32
+}
+
+/// Build classification signals from a session's live state
+pub fn launch_signals(
+    session: &Session,
+    related: Option<&RelatedSessionResults>,
+    vault_hit_rate: f32,
+) -> ClassificationSignals {
+    let hits = related.map(|r| r.hits.as_slice()).unwrap_or_default();
+    let paper_trail_tokens = session.paper_trail.as_ref().map(|pt| pt.total_tokens()).unwrap_or(0);
+    let current = session.context_budget.as_ref().map(|b| b.classification);
+
+    ClassificationSignals {
+        keywords: session_keywords(session),
+        related_thesis_ids: hits.iter().map(|h| h.session_id.clone()).collect(),
+        thesis_similarities: hits.iter().map(|h| h.relevance).collect(),
+        // Quick is only ever chosen by the user; keep it
+        user_marked_quick: current == Some(SessionClassification::Quick),
+        has_paper_trail: paper_trail_tokens > 0,
+        vault_hit_rate,
+        reference_doc_count: session.reference_docs.len(),
+        paper_trail_tokens,
+    }
+}
+
+/// Re-run classification for a launching session, updating its budget.
+/// `vault` is the launch's own vault when its workspace sets one.
+///
+/// Returns the recorded change when the classification moved.
+pub fn reclassify_for_launch(
+    session: &mut Session,
+    related: Option<&RelatedSessionResults>,
+    vault: Option<&VaultIndex>,
+) -> Option<ClassificationChange> {
+    let hit_rate = vault_hit_rate(&session_keywords(session), vault);
+    let signals = launch_signals(session, related, hit_rate);
+    let classification = classify_session(&signals);
+    session
+        .context_budget
+        .get_or_insert_with(Default::default)
+        .reclassify_from_signals(classification, signals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use crate::chroma::search::RelatedSessionHit;
+    use serde_json::json;
+
+    fn session(title: &str) -> Session {
+        test_session(json!({
+            "id": "reclass",
+            "title": title,
+        }))
+    }
+
+    #[test]
+    fn test_launch_signals() {
+        let s = session("Will the Fed cut rates in 2026?");
+        let related = RelatedSessionResults {
+            hits: vec![RelatedSessionHit {
+                session_id: "other".to_string(),
+                snippet: String::new(),
+                collection: "memory_semantic".to_string(),
+                relevance: 0.85,
+            }],
+            query_used: String::new(),
+        };
+
+        let signals = launch_signals(&s, Some(&related), 0.25);
+        assert_eq!(signals.keywords, vec!["will", "rates", "2026"]);
+        assert_eq!(signals.related_thesis_ids, vec!["other"]);
+        assert!(!signals.has_paper_trail);
+        assert_eq!(classify_session(&signals), SessionClassification::Fit);
+    }
+
+    #[test]
+    fn test_quick_is_preserved() {
+        let mut s = session("Scratch");
+        s.context_budget = Some(crate::context::ContextBudget::new(SessionClassification::Quick));
+        let signals = launch_signals(&s, None, 1.0);
+        assert_eq!(classify_session(&signals), SessionClassification::Quick);
+    }
+}
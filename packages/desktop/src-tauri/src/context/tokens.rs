@@ -1,12 +1,105 @@
 //! Token counting using tiktoken-rs for Claude-compatible token estimation.
 //!
-//! Uses cl100k_base encoding which is compatible with Claude models.
+//! Uses cl100k_base encoding which is compatible with Claude models. The
+//! quick estimators pick a chars-per-token ratio by content type, since a
+//! flat 4 chars/token undercounts CJK text (~1 token per character) and
+//! code (dense with symbols) by 2x or more.
 
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::collections::hash_map::DefaultHasher;
-use tiktoken_rs::cl100k_base;
+use std::sync::LazyLock;
+use tiktoken_rs::{cl100k_base, CoreBPE};
+
+/// Shared tokenizer (building the BPE tables is expensive; do it once)
+static TOKENIZER: LazyLock<Option<CoreBPE>> = LazyLock::new(|| cl100k_base().ok());
+
+/// Chars per token for English prose
+const PROSE_CHARS_PER_TOKEN: f64 = 4.0;
+/// Chars per token for source code (symbols and indentation tokenize densely)
+const CODE_CHARS_PER_TOKEN: f64 = 3.0;
+/// Tokens per CJK character
+const CJK_TOKENS_PER_CHAR: f64 = 1.0;
+/// Chars sampled when detecting content type
+const DETECT_SAMPLE_CHARS: usize = 4000;
+/// Share of symbol characters above which text is treated as code
+const CODE_SYMBOL_RATIO: f64 = 0.08;
+
+/// Content type used to select a token estimator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentType {
+    Prose,
+    Code,
+    Cjk,
+}
+
+/// Chinese, Japanese and Korean scripts (plus full-width punctuation)
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3000..=0x303F     // CJK punctuation
+        | 0x3040..=0x30FF   // Hiragana, Katakana
+        | 0x3400..=0x4DBF   // CJK Extension A
+        | 0x4E00..=0x9FFF   // CJK Unified Ideographs
+        | 0xAC00..=0xD7AF   // Hangul syllables
+        | 0xF900..=0xFAFF   // CJK compatibility ideographs
+        | 0xFF00..=0xFFEF   // Full-width forms
+    )
+}
+
+fn is_code_symbol(c: char) -> bool {
+    matches!(c, '{' | '}' | '(' | ')' | '[' | ']' | ';' | '=' | '<' | '>' | '_' | '|' | '&' | '$' | '\\')
+}
+
+/// Guess the dominant content type from a sample of the text
+pub fn detect_content_type(text: &str) -> ContentType {
+    let mut total = 0usize;
+    let mut cjk = 0usize;
+    let mut symbols = 0usize;
+    for c in text.chars().filter(|c| !c.is_whitespace()).take(DETECT_SAMPLE_CHARS) {
+        total += 1;
+        if is_cjk(c) {
+            cjk += 1;
+        } else if is_code_symbol(c) {
+            symbols += 1;
+        }
+    }
+    if total == 0 {
+        return ContentType::Prose;
+    }
+
+    let indented_lines = text.lines().take(200).filter(|l| l.starts_with("    ") || l.starts_with('\t')).count();
+    let lines = text.lines().take(200).count().max(1);
+
+    if cjk as f64 / total as f64 > 0.2 {
+        ContentType::Cjk
+    } else if symbols as f64 / total as f64 > CODE_SYMBOL_RATIO || indented_lines * 3 > lines {
+        ContentType::Code
+    } else {
+        ContentType::Prose
+    }
+}
+
+/// Heuristic token estimate for a given content type. CJK characters are
+/// always counted per character, so mixed-script text stays accurate.
+pub fn estimate_tokens_as(text: &str, content_type: ContentType) -> u32 {
+    let mut cjk = 0usize;
+    let mut other = 0usize;
+    for c in text.chars() {
+        if is_cjk(c) {
+            cjk += 1;
+        } else {
+            other += 1;
+        }
+    }
+    let chars_per_token = match content_type {
+        ContentType::Code => CODE_CHARS_PER_TOKEN,
+        ContentType::Prose | ContentType::Cjk => PROSE_CHARS_PER_TOKEN,
+    };
+    (cjk as f64 * CJK_TOKENS_PER_CHAR + other as f64 / chars_per_token).ceil() as u32
+}
 
 /// Global token cache to avoid recounting identical content
 static TOKEN_CACHE: RwLock<Option<TokenCache>> = RwLock::new(None);
@@ -80,9 +173,9 @@ pub fn count_tokens(text: &str) -> u32 {
     }
 
     // Count tokens using tiktoken
-    let bpe = match cl100k_base() {
-        Ok(bpe) => bpe,
-        Err(_) => return estimate_tokens_quick(text), // Fallback to estimate
+    let bpe = match TOKENIZER.as_ref() {
+        Some(bpe) => bpe,
+        None => return estimate_tokens_quick(text), // Fallback to estimate
     };
     let tokens = bpe.encode_with_special_tokens(text);
     let count = tokens.len() as u32;
@@ -105,9 +198,9 @@ pub fn count_tokens_batch(texts: &[&str]) -> Vec<u32> {
 
 /// Estimate tokens without caching (for one-off estimates)
 pub fn estimate_tokens_quick(text: &str) -> u32 {
-    // Quick estimate: ~4 chars per token on average
-    // This is less accurate but very fast
-    (text.len() as f64 / 4.0).ceil() as u32
+    // Less accurate than the tokenizer but very fast; the ratio depends on
+    // whether the text looks like prose, code or CJK
+    estimate_tokens_as(text, detect_content_type(text))
 }
 
 /// Check if text exceeds a token limit
@@ -192,6 +285,31 @@ mod tests {
         assert!(estimate >= actual / 2);
     }
 
+    #[test]
+    fn test_detect_content_type() {
+        assert_eq!(detect_content_type("The quick brown fox jumps over the lazy dog."), ContentType::Prose);
+        assert_eq!(
+            detect_content_type("fn main() {\n    let x = vec![1, 2];\n    println!(\"{:?}\", x);\n}"),
+            ContentType::Code
+        );
+        assert_eq!(detect_content_type("金利は当面高止まりする見通しです。"), ContentType::Cjk);
+        assert_eq!(detect_content_type(""), ContentType::Prose);
+    }
+
+    #[test]
+    fn test_estimators_track_tokenizer() {
+        let samples = [
+            "央行维持利率不变，市场预期年内降息两次，通胀压力仍然存在。",
+            "impl Foo {\n    fn bar(&self) -> Result<Vec<u8>, Error> {\n        self.inner.iter().map(|x| x * 2).collect()\n    }\n}",
+            "Pricing power lets firms defend margins even as input costs rise.",
+        ];
+        for text in samples {
+            let estimate = estimate_tokens_quick(text);
+            let actual = count_tokens(text);
+            assert!(estimate * 2 >= actual && estimate <= actual * 2, "{}: {} vs {}", text, estimate, actual);
+        }
+    }
+
     #[test]
     fn test_exceeds_token_limit() {
         let short_text = "Hi";
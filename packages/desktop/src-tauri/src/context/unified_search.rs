@@ -0,0 +1,358 @@
+//! Unified Cross-Source Search
+//!
+//! Fans a query out to reference documents, the Obsidian vault, web sources,
+//! memories and other sessions, normalizes each source's scores to 0-1 (so a
+//! keyword vault score and a Chroma distance are comparable), then fills the
+//! session's per-source token budgets in score order.
+//!
+//! Budget buckets follow `BudgetAllocation`: documents and web sources share
+//! the reference budget, vault notes use the obsidian budget, and memories
+//! plus other sessions' material use the paper trail budget.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use thiserror::Error;
+use tracing::{debug, warn};
+
+use super::classification::{SessionClassification, TokenBudgets};
+use super::tokens::estimate_tokens_quick;
+use crate::chroma::collections::*;
+use crate::chroma::search::search_all;
+use crate::documents::retriever::search_all_documents;
+use crate::documents::snippets::Snippet;
+use crate::obsidian::query::{query_notes, query_notes_semantic, QueryResult};
+use crate::session::{load_session_cli, validate_session_id};
+
+/// Candidates requested from each source before budgeting
+const RESULTS_PER_SOURCE: u32 = 20;
+
+#[derive(Error, Debug)]
+pub enum UnifiedSearchError {
+    #[error("Invalid session ID")]
+    InvalidSessionId,
+    #[error("Session error: {0}")]
+    Session(String),
+}
+
+impl Serialize for UnifiedSearchError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Where a hit came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSource {
+    Document,
+    Obsidian,
+    WebSource,
+    Memory,
+    /// Material belonging to another session
+    Session,
+}
+
+/// Budget bucket a source draws from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Bucket {
+    PaperTrail,
+    Obsidian,
+    Reference,
+}
+
+impl SearchSource {
+    fn bucket(&self) -> Bucket {
+        match self {
+            SearchSource::Document | SearchSource::WebSource => Bucket::Reference,
+            SearchSource::Obsidian => Bucket::Obsidian,
+            SearchSource::Memory | SearchSource::Session => Bucket::PaperTrail,
+        }
+    }
+}
+
+fn bucket_budget(budgets: &TokenBudgets, bucket: Bucket) -> u32 {
+    match bucket {
+        Bucket::PaperTrail => budgets.paper_trail,
+        Bucket::Obsidian => budgets.obsidian,
+        Bucket::Reference => budgets.reference,
+    }
+}
+
+/// A single source-tagged result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedHit {
+    pub source: SearchSource,
+    /// Source-specific id (chunk id, note path, Chroma record id)
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<Snippet>,
+    /// Score normalized within its source (0-1)
+    pub score: f32,
+    /// Score as reported by the source
+    pub raw_score: f32,
+    pub token_count: u32,
+    /// Owning session for memories and other sessions' material
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub metadata: Value,
+}
+
+/// Ranked results plus the budgets they were fitted into
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnifiedSearchResults {
+    pub hits: Vec<UnifiedHit>,
+    pub classification: SessionClassification,
+    pub budgets: TokenBudgets,
+    pub tokens_used: u32,
+    /// Candidates dropped because their source's budget was exhausted
+    pub dropped: usize,
+}
+
+/// Scale each source's scores so its best hit is 1.0
+pub fn normalize_scores(hits: &mut [UnifiedHit]) {
+    let mut max_by_source: HashMap<SearchSource, f32> = HashMap::new();
+    for hit in hits.iter() {
+        let max = max_by_source.entry(hit.source).or_insert(0.0);
+        *max = max.max(hit.raw_score);
+    }
+    for hit in hits.iter_mut() {
+        let max = max_by_source.get(&hit.source).copied().unwrap_or(0.0);
+        hit.score = if max > 0.0 { hit.raw_score / max } else { 0.0 };
+    }
+}
+
+/// Rank by normalized score and keep hits while their bucket has room.
+///
+/// Returns the kept hits (best first), tokens used and the number dropped.
+pub fn allocate(mut hits: Vec<UnifiedHit>, budgets: &TokenBudgets) -> (Vec<UnifiedHit>, u32, usize) {
+    hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut used: HashMap<Bucket, u32> = HashMap::new();
+    let mut kept = Vec::new();
+    let mut dropped = 0;
+
+    for hit in hits {
+        let bucket = hit.source.bucket();
+        let spent = used.entry(bucket).or_insert(0);
+        if *spent + hit.token_count > bucket_budget(budgets, bucket) {
+            dropped += 1;
+            continue;
+        }
+        *spent += hit.token_count;
+        kept.push(hit);
+    }
+
+    (kept, used.values().sum(), dropped)
+}
+
+fn note_hit(result: QueryResult) -> UnifiedHit {
+    UnifiedHit {
+        source: SearchSource::Obsidian,
+        id: result.note.path.clone(),
+        title: Some(result.note.title.clone()),
+        content: result.note.summary.clone(),
+        snippet: result.snippet,
+        score: 0.0,
+        raw_score: result.relevance,
+        token_count: result.note.token_count,
+        session_id: None,
+        metadata: Value::Null,
+    }
+}
+
+/// Gather candidates from every source for `session_id`
+pub(crate) async fn gather_candidates(session_id: &str, query: &str, budgets: &TokenBudgets) -> Vec<UnifiedHit> {
+    let mut hits = Vec::new();
+
+    // Reference documents attached to this session
+    match search_all_documents(session_id, query, RESULTS_PER_SOURCE as usize, budgets.reference).await {
+        Ok(results) => hits.extend(results.into_iter().map(|r| UnifiedHit {
+            source: SearchSource::Document,
+            id: format!("{}#{}", r.doc_id, r.chunk_index),
+            title: r.section.clone(),
+            token_count: r.token_count,
+            content: r.content,
+            snippet: r.snippet,
+            score: 0.0,
+            raw_score: r.score,
+            session_id: Some(session_id.to_string()),
+            metadata: Value::Null,
+        })),
+        Err(e) => debug!(error = %e, "Unified search: document search unavailable"),
+    }
+
+    // Vault: semantic first, keyword index when Chroma has nothing
+    let mut notes = query_notes_semantic(query, RESULTS_PER_SOURCE).await;
+    if notes.is_empty() {
+        notes = query_notes(query, budgets.obsidian).unwrap_or_default();
+    }
+    hits.extend(notes.into_iter().map(note_hit));
+
+    // Web sources, memories and other sessions' documents
+    let collections = vec![
+        COLLECTION_WEB_SOURCES.to_string(),
+        COLLECTION_MEMORY_SEMANTIC.to_string(),
+        COLLECTION_MEMORY_PROCEDURAL.to_string(),
+        COLLECTION_MEMORY_EPISODIC.to_string(),
+        COLLECTION_DOCUMENTS.to_string(),
+    ];
+    match search_all(query, RESULTS_PER_SOURCE * collections.len() as u32, None, Some(collections), None, None).await {
+        Ok(results) => {
+            for hit in results.hits {
+                let owner = hit.metadata.get("session_id")
+                    .and_then(|v| v.as_str())
+                    .filter(|s| !s.is_empty())
+                    .map(|s| s.to_string());
+                let is_current = owner.as_deref() == Some(session_id);
+
+                let source = match hit.collection.as_str() {
+                    COLLECTION_WEB_SOURCES => SearchSource::WebSource,
+                    // This session's documents already came from the retriever
+                    COLLECTION_DOCUMENTS if is_current => continue,
+                    COLLECTION_DOCUMENTS => SearchSource::Session,
+                    _ if owner.is_none() || is_current => SearchSource::Memory,
+                    _ => SearchSource::Session,
+                };
+                let title = hit.metadata.get("title")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string());
+
+                hits.push(UnifiedHit {
+                    source,
+                    id: hit.id,
+                    title,
+                    token_count: estimate_tokens_quick(&hit.document),
+                    content: hit.document,
+                    snippet: hit.snippet,
+                    score: 0.0,
+                    raw_score: hit.relevance,
+                    session_id: owner,
+                    metadata: hit.metadata,
+                });
+            }
+        }
+        Err(e) => warn!(error = %e, "Unified search: Chroma search failed"),
+    }
+
+    hits
+}
+
+/// Search every context source for a session and return one ranked list
+/// fitted to the session's budget allocation for a `budget`-token window
+pub async fn unified_search(
+    session_id: &str,
+    query: &str,
+    budget: u32,
+) -> Result<UnifiedSearchResults, UnifiedSearchError> {
+    validate_session_id(session_id).map_err(|_| UnifiedSearchError::InvalidSessionId)?;
+    let session = load_session_cli(session_id)
+        .map_err(|e| UnifiedSearchError::Session(e.to_string()))?;
+
+    let classification = session.context_budget
+        .map(|b| b.classification)
+        .unwrap_or_default();
+    let budgets = classification.get_allocation().to_token_budgets(budget);
+
+    let mut hits = gather_candidates(session_id, query, &budgets).await;
+    let candidates = hits.len();
+    normalize_scores(&mut hits);
+    let (hits, tokens_used, dropped) = allocate(hits, &budgets);
+
+    debug!(
+        session_id = %session_id,
+        candidates = candidates,
+        returned = hits.len(),
+        tokens_used = tokens_used,
+        "Unified search complete"
+    );
+
+    Ok(UnifiedSearchResults {
+        hits,
+        classification,
+        budgets,
+        tokens_used,
+        dropped,
+    })
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub async fn search_everything(
+    session_id: String,
+    query: String,
+    budget: u32,
+) -> Result<UnifiedSearchResults, UnifiedSearchError> {
+    unified_search(&session_id, &query, budget).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(source: SearchSource, id: &str, raw_score: f32, token_count: u32) -> UnifiedHit {
+        UnifiedHit {
+            source,
+            id: id.to_string(),
+            title: None,
+            content: String::new(),
+            snippet: None,
+            score: 0.0,
+            raw_score,
+            token_count,
+            session_id: None,
+            metadata: Value::Null,
+        }
+    }
+
+    #[test]
+    fn test_normalize_per_source() {
+        let mut hits = vec![
+            hit(SearchSource::Obsidian, "a", 2.0, 10),
+            hit(SearchSource::Obsidian, "b", 1.0, 10),
+            hit(SearchSource::WebSource, "c", 0.4, 10),
+        ];
+        normalize_scores(&mut hits);
+        assert_eq!(hits[0].score, 1.0);
+        assert_eq!(hits[1].score, 0.5);
+        // Best hit of each source is 1.0 regardless of the source's scale
+        assert_eq!(hits[2].score, 1.0);
+    }
+
+    #[test]
+    fn test_allocate_respects_buckets() {
+        let budgets = TokenBudgets {
+            paper_trail: 0,
+            obsidian: 100,
+            reference: 150,
+            reasoning: 1000,
+        };
+        let mut hits = vec![
+            hit(SearchSource::Obsidian, "note", 1.0, 80),
+            hit(SearchSource::Obsidian, "note2", 0.9, 80),
+            hit(SearchSource::Document, "doc", 0.8, 100),
+            hit(SearchSource::WebSource, "web", 0.7, 50),
+            hit(SearchSource::Memory, "mem", 1.0, 1),
+        ];
+        for h in hits.iter_mut() {
+            h.score = h.raw_score;
+        }
+
+        let (kept, used, dropped) = allocate(hits, &budgets);
+        let ids: Vec<&str> = kept.iter().map(|h| h.id.as_str()).collect();
+        // note2 overflows obsidian; memory has no paper trail budget
+        assert_eq!(ids, vec!["note", "doc", "web"]);
+        assert_eq!(used, 230);
+        assert_eq!(dropped, 2);
+    }
+}
@@ -0,0 +1,132 @@
+//! Deep Links
+//!
+//! `dialectic://session/<id>` opens a session in the app; exported notes
+//! carry such a link so a thesis published to Obsidian leads back to the
+//! session it came from. The other direction uses `obsidian://open` URIs
+//! for vault notes a session cites.
+//!
+//! Links arrive through the deep-link plugin. One that launched the app is
+//! held until the frontend asks for it with `take_pending_deep_link`; links
+//! opened while running are emitted as `DEEP_LINK_EVENT`.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+use crate::session::validate_session_id;
+
+pub const SCHEME: &str = "dialectic";
+/// Event carrying a `DeepLink` opened while the app is running
+pub const DEEP_LINK_EVENT: &str = "deep-link";
+
+/// Link the app was launched with, until the frontend takes it
+static PENDING: Mutex<Option<DeepLink>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", rename_all_fields = "camelCase")]
+pub enum DeepLink {
+    Session { session_id: String },
+}
+
+/// `dialectic://session/<id>`
+pub fn session_link(session_id: &str) -> String {
+    format!("{}://session/{}", SCHEME, session_id.trim_start_matches("sess_"))
+}
+
+/// Parse a `dialectic://` URL; `None` for other schemes, unknown targets
+/// or invalid session IDs
+pub fn parse(url: &str) -> Option<DeepLink> {
+    let rest = url.strip_prefix(SCHEME)?.strip_prefix("://")?;
+    let rest = rest.split(['?', '#']).next().unwrap_or_default().trim_end_matches('/');
+    let (target, id) = rest.split_once('/')?;
+    match target {
+        "session" => {
+            let session_id = id.trim_start_matches("sess_");
+            validate_session_id(session_id).ok()?;
+            Some(DeepLink::Session { session_id: session_id.to_string() })
+        }
+        _ => None,
+    }
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters
+fn encode_component(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// `obsidian://open` URI for a note, given as a path relative to the vault
+/// or an absolute path inside it; `None` if it's outside the vault
+pub fn obsidian_link(vault_path: &Path, note_path: &str) -> Option<String> {
+    let vault_name = vault_path.file_name()?.to_str()?;
+    let note = Path::new(note_path);
+    let relative = if note.is_absolute() { note.strip_prefix(vault_path).ok()? } else { note };
+    let file = relative.to_str()?.replace('\\', "/");
+    Some(format!(
+        "obsidian://open?vault={}&file={}",
+        encode_component(vault_name),
+        encode_component(&file)
+    ))
+}
+
+/// Hold a link the app was launched with for the frontend to take
+pub fn set_pending(url: &str) {
+    match parse(url) {
+        Some(link) => *PENDING.lock() = Some(link),
+        None => warn!(url = %url, "Ignoring unrecognized deep link"),
+    }
+}
+
+/// Forward a link opened while the app is running to the frontend
+pub fn handle_url(app: &AppHandle, url: &str) {
+    let Some(link) = parse(url) else {
+        warn!(url = %url, "Ignoring unrecognized deep link");
+        return;
+    };
+    info!(link = ?link, "Opening deep link");
+    if let Err(e) = app.emit(DEEP_LINK_EVENT, &link) {
+        warn!(error = %e, "Failed to emit deep link");
+    }
+}
+
+// ============ TAURI COMMANDS ============
+
+/// The link the app was launched with, if any (returned once)
+#[tauri::command]
+pub fn take_pending_deep_link() -> Option<DeepLink> {
+    PENDING.lock().take()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_session_links() {
+        let expected = Some(DeepLink::Session { session_id: "abc-123".to_string() });
+        assert_eq!(parse("dialectic://session/abc-123"), expected);
+        assert_eq!(parse("dialectic://session/sess_abc-123/?from=obsidian"), expected);
+        assert_eq!(parse(&session_link("sess_abc-123")), expected);
+        assert!(parse("dialectic://session/../etc").is_none());
+        assert!(parse("dialectic://note/abc").is_none());
+        assert!(parse("obsidian://session/abc").is_none());
+    }
+
+    #[test]
+    fn test_obsidian_link() {
+        let vault = Path::new("/home/me/My Vault");
+        assert_eq!(
+            obsidian_link(vault, "Theses/Pricing & Moats.md").unwrap(),
+            "obsidian://open?vault=My%20Vault&file=Theses%2FPricing%20%26%20Moats.md"
+        );
+        assert!(obsidian_link(vault, "/home/me/My Vault/a.md").unwrap().ends_with("file=a.md"));
+        assert!(obsidian_link(vault, "/elsewhere/a.md").is_none());
+    }
+}
@@ -0,0 +1,422 @@
+//! Distill Pipeline
+//!
+//! Produces the `.dialectic-output/<run>/` artifacts that launch context and
+//! the watcher consume: `memo-final.md` (conviction memo), `spine.yaml`
+//! (claims, CDG edges and tensions) and `thesis-history.md` (how the thesis
+//! got here). Everything is assembled from session data. With `summarize`
+//! the configured CLI tool is run headlessly (`<tool> -p`) to write the
+//! memo's executive summary; if that fails the memo is still written
+//! without one.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+use tracing::{info, warn};
+
+use crate::cdg::compute_strata;
+use crate::config::workspace::effective_preferences;
+use crate::deep_link::session_link;
+use crate::session::audit::{read_audit_log, AuditAction};
+use crate::session::citations::{collect_citations, render_bibliography, CitationEntry};
+use crate::session::{get_session_dir_cli, load_session_cli, Session, SessionError};
+
+pub const DISTILL_DIR: &str = ".dialectic-output";
+pub const MEMO_FILE: &str = "memo-final.md";
+pub const SPINE_FILE: &str = "spine.yaml";
+pub const THESIS_HISTORY_FILE: &str = "thesis-history.md";
+
+/// How long the headless CLI may take to summarize
+const SUMMARIZE_TIMEOUT: Duration = Duration::from_secs(180);
+const SUMMARY_PROMPT: &str = "You are distilling a Dialectic reasoning session. Read the conviction memo on stdin \
+and write a 3-5 sentence executive summary: the position, why it holds, and what would change it. \
+Output only the summary text.";
+
+#[derive(Error, Debug)]
+pub enum DistillError {
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl Serialize for DistillError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DistillOutput {
+    pub session_id: String,
+    pub run_dir: String,
+    pub artifacts: Vec<String>,
+    /// Whether the memo includes a CLI-written executive summary
+    pub summarized: bool,
+}
+
+fn lower_debug<T: std::fmt::Debug>(value: &T) -> String {
+    format!("{:?}", value).to_lowercase()
+}
+
+/// Conviction memo; `summary` becomes its executive summary section and
+/// `citations` its closing bibliography.
+pub fn render_memo(session: &Session, summary: Option<&str>, citations: &[CitationEntry]) -> String {
+    let mut md = String::with_capacity(4096);
+    let _ = writeln!(md, "# {}\n", session.title);
+    let _ = writeln!(md, "**Session:** [{}]({})  ", session.id, session_link(&session.id));
+    let _ = writeln!(md, "**Mode:** {}  ", lower_debug(&session.mode));
+    let _ = writeln!(md, "**Status:** {}\n", lower_debug(&session.status));
+
+    if let Some(summary) = summary {
+        let _ = writeln!(md, "## Executive Summary\n\n{}\n", summary.trim());
+    }
+
+    md.push_str("## Thesis\n\n");
+    match &session.thesis {
+        Some(thesis) => {
+            let _ = writeln!(md, "{}\n\n**Confidence:** {:.0}%\n", thesis.content.trim(), thesis.confidence * 100.0);
+        }
+        None => md.push_str("_No thesis formed yet._\n\n"),
+    }
+    if let Some(summary) = &session.summary {
+        let _ = writeln!(md, "## Context\n\n{}\n", summary.trim());
+    }
+
+    // Claims grouped by marker, unmarked last
+    if !session.claims.is_empty() {
+        md.push_str("## Key Claims\n\n");
+        let mut markers: Vec<&str> = session.claims.iter().filter_map(|c| c.marker.as_deref()).collect();
+        markers.sort_unstable();
+        markers.dedup();
+        for marker in markers {
+            let _ = writeln!(md, "### {}\n", marker.trim_matches(|c| c == '[' || c == ']'));
+            for claim in session.claims.iter().filter(|c| c.marker.as_deref() == Some(marker)) {
+                let _ = writeln!(md, "- {}", claim.content);
+            }
+            md.push('\n');
+        }
+        let unmarked: Vec<_> = session.claims.iter().filter(|c| c.marker.is_none()).collect();
+        if !unmarked.is_empty() {
+            md.push_str("### Other\n\n");
+            for claim in unmarked {
+                let _ = writeln!(md, "- {}", claim.content);
+            }
+            md.push('\n');
+        }
+    }
+
+    if !session.tensions.is_empty() {
+        md.push_str("## Tensions\n\n");
+        for tension in &session.tensions {
+            match &tension.resolution {
+                Some(resolution) => {
+                    let _ = writeln!(md, "- {} — *resolved:* {}", tension.description, resolution);
+                }
+                None => {
+                    let _ = writeln!(md, "- {} — *open*", tension.description);
+                }
+            }
+        }
+        md.push('\n');
+    }
+
+    if !session.review_triggers.is_empty() {
+        md.push_str("## Revision Triggers\n\n");
+        for trigger in &session.review_triggers {
+            match trigger.review_by {
+                Some(date) => {
+                    let _ = writeln!(md, "- {} (review by {})", trigger.description, date.format("%Y-%m-%d"));
+                }
+                None => {
+                    let _ = writeln!(md, "- {}", trigger.description);
+                }
+            }
+        }
+        md.push('\n');
+    }
+
+    md.push_str(&render_bibliography(citations));
+
+    md.truncate(md.trim_end().len());
+    md.push('\n');
+    md
+}
+
+/// Quote a string as a YAML scalar (JSON strings are valid YAML)
+fn yaml_str(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// Reasoning spine: claims with strata, CDG edges and tensions
+pub fn render_spine(session: &Session) -> String {
+    let strata = compute_strata(&session.claims, &session.cdg_edges);
+    let mut yaml = String::with_capacity(4096);
+    let _ = writeln!(yaml, "session: {}", yaml_str(&session.id));
+    let _ = writeln!(yaml, "title: {}", yaml_str(&session.title));
+    let _ = writeln!(yaml, "status: {}", lower_debug(&session.status));
+    match &session.thesis {
+        Some(thesis) => {
+            let _ = writeln!(yaml, "thesis:\n  content: {}\n  confidence: {:.2}", yaml_str(&thesis.content), thesis.confidence);
+        }
+        None => yaml.push_str("thesis: null\n"),
+    }
+
+    yaml.push_str(if session.claims.is_empty() { "claims: []\n" } else { "claims:\n" });
+    for claim in &session.claims {
+        let _ = writeln!(yaml, "  - id: {}", yaml_str(&claim.id));
+        let _ = writeln!(yaml, "    content: {}", yaml_str(&claim.content));
+        if let Some(marker) = &claim.marker {
+            let _ = writeln!(yaml, "    marker: {}", yaml_str(marker));
+        }
+        if let Some(stratum) = strata.get(&claim.id) {
+            let _ = writeln!(yaml, "    stratum: {}", lower_debug(stratum));
+        }
+        if let Some(score) = claim.evidence_score {
+            let _ = writeln!(yaml, "    evidence_score: {:.2}", score);
+        }
+    }
+
+    yaml.push_str(if session.cdg_edges.is_empty() { "edges: []\n" } else { "edges:\n" });
+    for edge in &session.cdg_edges {
+        let _ = writeln!(yaml, "  - from: {}", yaml_str(&edge.source_claim_id));
+        let _ = writeln!(yaml, "    to: {}", yaml_str(&edge.target_claim_id));
+        let _ = writeln!(yaml, "    type: {}", lower_debug(&edge.edge_type));
+        let _ = writeln!(yaml, "    weight: {:.2}", edge.weight);
+        if let Some(resolution) = &edge.resolution {
+            let _ = writeln!(yaml, "    resolution: {}", lower_debug(resolution));
+        }
+    }
+
+    yaml.push_str(if session.tensions.is_empty() { "tensions: []\n" } else { "tensions:\n" });
+    for tension in &session.tensions {
+        let _ = writeln!(yaml, "  - between: [{}, {}]", yaml_str(&tension.claim_a_id), yaml_str(&tension.claim_b_id));
+        let _ = writeln!(yaml, "    description: {}", yaml_str(&tension.description));
+        match &tension.resolution {
+            Some(resolution) => {
+                let _ = writeln!(yaml, "    resolution: {}", yaml_str(resolution));
+            }
+            None => yaml.push_str("    resolution: null\n"),
+        }
+    }
+    yaml
+}
+
+/// Timeline of status changes, passes and CDG snapshots ending in the
+/// current thesis. `status_changes` are (when, from, to) from the audit log.
+pub fn render_thesis_history(session: &Session, status_changes: &[(DateTime<Utc>, String, String)]) -> String {
+    let mut events: Vec<(DateTime<Utc>, String)> = vec![(session.created, "Session created".to_string())];
+    for (at, from, to) in status_changes {
+        events.push((*at, format!("Status: {} → {}", from, to)));
+    }
+    for pass in &session.passes {
+        let at = pass.completed_at.unwrap_or(pass.started_at);
+        events.push((at, format!("Pass: {}", pass.pass_type)));
+    }
+    for snapshot in &session.cdg_snapshots {
+        events.push((
+            snapshot.timestamp,
+            format!(
+                "CDG snapshot `{}`: coherence {:.2}, {} claims, {} unresolved tensions",
+                snapshot.pass_id, snapshot.metrics.coherence, snapshot.metrics.claim_count, snapshot.metrics.unresolved_count
+            ),
+        ));
+    }
+    if let Some(calibration) = &session.calibration {
+        events.push((calibration.formed_at, format!("Formed at {:.0}% confidence", calibration.confidence * 100.0)));
+        if let (Some(outcome), Some(at)) = (calibration.outcome, calibration.outcome_at) {
+            events.push((at, format!("Outcome recorded: {}", lower_debug(&outcome))));
+        }
+    }
+    if let Some(thesis) = &session.thesis {
+        events.push((thesis.updated_at, format!("Thesis updated ({:.0}% confidence)", thesis.confidence * 100.0)));
+    }
+    events.sort_by_key(|(at, _)| *at);
+
+    let mut md = format!("# Thesis History: {}\n\n## Timeline\n\n", session.title);
+    for (at, event) in &events {
+        let _ = writeln!(md, "- {} — {}", at.format("%Y-%m-%d %H:%M"), event);
+    }
+    md.push_str("\n## Current Thesis\n\n");
+    match &session.thesis {
+        Some(thesis) => {
+            let _ = writeln!(md, "{}", thesis.content.trim());
+        }
+        None => md.push_str("_No thesis formed yet._\n"),
+    }
+    md
+}
+
+/// (when, from, to) for each status change in the session's audit log
+fn status_changes(session_id: &str) -> Vec<(DateTime<Utc>, String, String)> {
+    let entries = get_session_dir_cli(session_id).and_then(|dir| read_audit_log(&dir));
+    let entries = match entries {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!(session_id = %session_id, error = %e, "Audit log unavailable for thesis history");
+            return Vec::new();
+        }
+    };
+    entries
+        .into_iter()
+        .filter(|e| e.action == AuditAction::StatusChanged)
+        .filter_map(|e| {
+            let from = e.detail.get("from")?.as_str()?.to_string();
+            let to = e.detail.get("to")?.as_str()?.to_string();
+            Some((e.timestamp, from, to))
+        })
+        .collect()
+}
+
+/// Run `<tool> -p <prompt>` with `input` on stdin; `None` on any failure
+async fn summarize_with_cli(tool: &str, input: &str) -> Option<String> {
+    let mut child = tokio::process::Command::new(tool)
+        .arg("-p")
+        .arg(SUMMARY_PROMPT)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| warn!(tool = %tool, error = %e, "Failed to start CLI tool for distill"))
+        .ok()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes()).await.ok()?;
+    }
+    let output = match tokio::time::timeout(SUMMARIZE_TIMEOUT, child.wait_with_output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => {
+            warn!(tool = %tool, error = %e, "CLI tool failed during distill");
+            return None;
+        }
+        Err(_) => {
+            warn!(tool = %tool, timeout_secs = SUMMARIZE_TIMEOUT.as_secs(), "CLI tool timed out during distill");
+            return None;
+        }
+    };
+    if !output.status.success() {
+        warn!(tool = %tool, status = %output.status, "CLI tool exited with an error during distill");
+        return None;
+    }
+    let summary = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!summary.is_empty()).then_some(summary)
+}
+
+fn write_artifact(run_dir: &Path, name: &str, content: &str) -> Result<(), std::io::Error> {
+    let tmp = run_dir.join(format!("{}.tmp", name));
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, run_dir.join(name))
+}
+
+/// Write a new distill run for `session`
+pub async fn distill_session(session: &Session, summarize: bool) -> Result<DistillOutput, DistillError> {
+    let working_dir = PathBuf::from(&session.working_dir);
+    // Run directories sort by name, newest last
+    let run_dir = working_dir
+        .join(DISTILL_DIR)
+        .join(Utc::now().format("%Y%m%d-%H%M%S").to_string());
+    fs::create_dir_all(&run_dir)?;
+
+    let prefs = effective_preferences(&working_dir).0;
+    let vault = prefs.vault_path.as_deref().map(Path::new);
+    let citations = collect_citations(session, vault).await;
+    let draft = render_memo(session, None, &citations);
+    let summary = if summarize {
+        summarize_with_cli(&prefs.cli_tool, &draft).await
+    } else {
+        None
+    };
+    let memo = match &summary {
+        Some(summary) => render_memo(session, Some(summary), &citations),
+        None => draft,
+    };
+
+    let history = render_thesis_history(session, &status_changes(&session.id));
+    write_artifact(&run_dir, MEMO_FILE, &memo)?;
+    write_artifact(&run_dir, SPINE_FILE, &render_spine(session))?;
+    write_artifact(&run_dir, THESIS_HISTORY_FILE, &history)?;
+
+    info!(session_id = %session.id, run_dir = %run_dir.display(), summarized = summary.is_some(), "Distilled session");
+    Ok(DistillOutput {
+        session_id: session.id.clone(),
+        run_dir: run_dir.to_string_lossy().to_string(),
+        artifacts: vec![MEMO_FILE.to_string(), SPINE_FILE.to_string(), THESIS_HISTORY_FILE.to_string()],
+        summarized: summary.is_some(),
+    })
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub async fn distill(session_id: String, summarize: Option<bool>) -> Result<DistillOutput, DistillError> {
+    let session = load_session_cli(&session_id)?;
+    distill_session(&session, summarize.unwrap_or(false)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use crate::session::citations::resolve_citations;
+
+    fn session() -> Session {
+        test_session(serde_json::json!({
+            "id": "distill-test",
+            "title": "Pricing \"power\"",
+            "status": "synthesizing",
+            "updated": "2026-01-03T00:00:00Z",
+            "claims": [
+                { "id": "c1", "content": "Margins: thin", "sourceId": "s", "marker": "[RISK]", "createdAt": "2026-01-01T00:00:00Z" },
+                { "id": "c2", "content": "Brand matters", "sourceId": "s", "marker": null, "createdAt": "2026-01-01T00:00:00Z",
+                  "sourceSpan": { "kind": "note", "path": "Markets/Pricing.md" } },
+            ],
+            "tensions": [
+                { "id": "t1", "claimAId": "c1", "claimBId": "c2", "description": "Cost vs brand", "resolution": null, "createdAt": "2026-01-01T00:00:00Z" },
+            ],
+            "thesis": { "content": "Raise prices", "confidence": 0.65, "updatedAt": "2026-01-03T00:00:00Z" },
+        }))
+    }
+
+    #[test]
+    fn test_render_memo_and_spine() {
+        let session = session();
+        let citations = resolve_citations(&session, &Default::default(), Some(Path::new("/vaults/Notes")));
+        let memo = render_memo(&session, Some("Prices can rise."), &citations);
+        assert!(memo.contains("**Session:** [distill-test](dialectic://session/distill-test)"));
+        assert!(memo.contains("## Executive Summary\n\nPrices can rise."));
+        assert!(memo.contains("## Bibliography\n\n1. [Markets/Pricing.md](obsidian://open?vault=Notes&file=Markets%2FPricing.md)"));
+        assert!(memo.contains("### RISK\n\n- Margins: thin"));
+        assert!(memo.contains("### Other\n\n- Brand matters"));
+        assert!(memo.contains("- Cost vs brand — *open*"));
+
+        let spine = render_spine(&session);
+        assert!(spine.contains("title: \"Pricing \\\"power\\\"\""));
+        assert!(spine.contains("    content: \"Margins: thin\""));
+        assert!(spine.contains("edges: []"));
+        assert!(spine.contains("  - between: [\"c1\", \"c2\"]"));
+    }
+
+    #[test]
+    fn test_thesis_history_is_chronological() {
+        let changes = vec![(
+            "2026-01-02T00:00:00Z".parse().unwrap(),
+            "exploring".to_string(),
+            "synthesizing".to_string(),
+        )];
+        let history = render_thesis_history(&session(), &changes);
+        let created = history.find("Session created").unwrap();
+        let status = history.find("Status: exploring → synthesizing").unwrap();
+        let thesis = history.find("Thesis updated (65% confidence)").unwrap();
+        assert!(created < status && status < thesis);
+        assert!(history.ends_with("## Current Thesis\n\nRaise prices\n"));
+    }
+}
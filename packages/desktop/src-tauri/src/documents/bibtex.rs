@@ -0,0 +1,546 @@
+//! Bibliography Import
+//!
+//! Reads a BibTeX export (including Zotero's, with its `file` attachment
+//! field) or a CSL JSON export and turns each entry into a reference
+//! document. The citation travels with the document: it is written into
+//! every chunk's Chroma metadata and returned on search results, so claims
+//! drawn from a chunk can cite author, year and DOI.
+//!
+//! Attachments are used when their path exists: PDFs via `pdftotext`
+//! (poppler), HTML snapshots via the web extractor, text as is. Entries
+//! without a readable attachment still become a document holding their
+//! metadata and abstract.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use thiserror::Error;
+use tracing::{debug, info, warn};
+
+use super::chunker::DocumentPersistence;
+use super::retriever::{add_reference_with_citation, ReferenceDocument, RetrieverError};
+use super::web::{html_to_markdown, reference_file_path};
+use crate::session::lock::update_session_file;
+use crate::session::mode_policy::document_persistence;
+use crate::session::{get_session_dir_cli, validate_session_id, SessionError, SessionReferenceDoc};
+
+/// Separator of authors in Chroma metadata (values must be scalars)
+const AUTHOR_SEPARATOR: &str = "; ";
+
+#[derive(Error, Debug)]
+pub enum BibliographyError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid CSL JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("No entries found in {0}")]
+    Empty(String),
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error(transparent)]
+    Retriever(#[from] RetrieverError),
+}
+
+impl Serialize for BibliographyError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Citation metadata of a reference document
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Citation {
+    /// BibTeX key or CSL item ID
+    pub key: String,
+    /// `article`, `book`, `inproceedings`, ...
+    pub entry_type: String,
+    pub title: Option<String>,
+    /// As given: "Family, Given" or "Given Family"
+    #[serde(default)]
+    pub authors: Vec<String>,
+    pub year: Option<String>,
+    pub doi: Option<String>,
+    pub url: Option<String>,
+    /// Journal, book or proceedings title
+    pub container: Option<String>,
+}
+
+impl Citation {
+    /// Write the citation into chunk metadata as `cite_*` keys
+    pub fn write_metadata(&self, meta: &mut Value) {
+        meta["cite_key"] = json!(self.key);
+        meta["cite_type"] = json!(self.entry_type);
+        if !self.authors.is_empty() {
+            meta["cite_authors"] = json!(self.authors.join(AUTHOR_SEPARATOR));
+        }
+        let optional = [
+            ("cite_title", &self.title),
+            ("cite_year", &self.year),
+            ("cite_doi", &self.doi),
+            ("cite_url", &self.url),
+            ("cite_container", &self.container),
+        ];
+        for (key, value) in optional {
+            if let Some(value) = value {
+                meta[key] = json!(value);
+            }
+        }
+    }
+
+    /// Read a citation back from chunk metadata written by `write_metadata`
+    pub fn from_metadata(meta: &Value) -> Option<Citation> {
+        let field = |key: &str| meta.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        Some(Citation {
+            key: field("cite_key")?,
+            entry_type: field("cite_type").unwrap_or_default(),
+            title: field("cite_title"),
+            authors: field("cite_authors")
+                .map(|a| a.split(AUTHOR_SEPARATOR).map(str::to_string).collect())
+                .unwrap_or_default(),
+            year: field("cite_year"),
+            doi: field("cite_doi"),
+            url: field("cite_url"),
+            container: field("cite_container"),
+        })
+    }
+}
+
+/// A parsed bibliography entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct BibEntry {
+    pub citation: Citation,
+    pub abstract_text: Option<String>,
+    /// Attachment paths, in export order
+    pub files: Vec<String>,
+}
+
+/// Result of importing a bibliography into a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BibliographyImport {
+    pub imported: Vec<ReferenceDocument>,
+    /// Keys of entries with an attachment that couldn't be read, imported from metadata only
+    pub metadata_only: Vec<String>,
+    /// "key: error" for entries that failed entirely
+    pub failed: Vec<String>,
+}
+
+// ============ BIBTEX ============
+
+/// Strip TeX braces and common escapes, collapsing whitespace
+fn clean_tex(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' => {}
+            '\\' => match chars.peek() {
+                Some(&next) if "&%$#_{}".contains(next) => {
+                    out.push(next);
+                    chars.next();
+                }
+                _ => {}
+            },
+            '~' => out.push(' '),
+            _ => out.push(c),
+        }
+    }
+    out.replace("---", "—").replace("--", "–").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Read one field value starting at `s` (after `=`): `{...}` with nesting,
+/// `"..."` or a bare word/number. Returns the raw value and the rest.
+fn read_value(s: &str) -> (&str, &str) {
+    let s = s.trim_start();
+    match s.chars().next() {
+        Some('{') => {
+            let mut depth = 0;
+            for (i, c) in s.char_indices() {
+                match c {
+                    '{' => depth += 1,
+                    '}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return (&s[1..i], &s[i + 1..]);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            (&s[1..], "")
+        }
+        Some('"') => {
+            let mut depth = 0;
+            for (i, c) in s.char_indices().skip(1) {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    '"' if depth == 0 => return (&s[1..i], &s[i + 1..]),
+                    _ => {}
+                }
+            }
+            (&s[1..], "")
+        }
+        _ => {
+            let end = s.find([',', '}', '\n']).unwrap_or(s.len());
+            (s[..end].trim(), &s[end..])
+        }
+    }
+}
+
+/// Zotero's `file` field: `Description:path:mime;...`, or plain `;`-separated paths
+fn parse_file_field(value: &str) -> Vec<String> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let fields: Vec<&str> = part.split(':').collect();
+            // The path itself may contain a drive letter colon
+            if fields.len() >= 3 && fields.last().is_some_and(|m| m.contains('/')) {
+                fields[1..fields.len() - 1].join(":")
+            } else {
+                part.to_string()
+            }
+        })
+        .map(|path| path.replace("\\:", ":"))
+        .collect()
+}
+
+/// Parse the entries of a BibTeX file, skipping @comment, @string and @preamble
+pub fn parse_bibtex(text: &str) -> Vec<BibEntry> {
+    let mut entries = Vec::new();
+    let mut rest = text;
+    while let Some(at) = rest.find('@') {
+        rest = &rest[at + 1..];
+        let Some(open) = rest.find(['{', '(']) else { break };
+        let entry_type = rest[..open].trim().to_lowercase();
+        rest = &rest[open + 1..];
+        if matches!(entry_type.as_str(), "comment" | "string" | "preamble")
+            || entry_type.is_empty()
+            || !entry_type.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            continue;
+        }
+        let Some(comma) = rest.find(',') else { break };
+        let key = rest[..comma].trim().to_string();
+        rest = &rest[comma + 1..];
+
+        let mut fields: Vec<(String, String)> = Vec::new();
+        loop {
+            let trimmed = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+            if trimmed.is_empty() || trimmed.starts_with(['}', ')']) {
+                rest = trimmed.get(1..).unwrap_or_default();
+                break;
+            }
+            let Some(eq) = trimmed.find('=') else {
+                rest = trimmed;
+                break;
+            };
+            let name = trimmed[..eq].trim().to_lowercase();
+            let (value, after) = read_value(&trimmed[eq + 1..]);
+            fields.push((name, value.to_string()));
+            rest = after;
+        }
+
+        let field = |name: &str| {
+            fields.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str()).filter(|v| !v.trim().is_empty())
+        };
+        let year = field("year")
+            .map(clean_tex)
+            .or_else(|| field("date").map(|d| clean_tex(d).chars().take(4).collect()));
+        let citation = Citation {
+            key,
+            entry_type,
+            title: field("title").map(clean_tex),
+            authors: field("author")
+                .or_else(|| field("editor"))
+                .map(|a| a.split(" and ").map(clean_tex).filter(|a| !a.is_empty()).collect())
+                .unwrap_or_default(),
+            year,
+            doi: field("doi").map(clean_tex),
+            url: field("url").map(|u| u.trim().to_string()),
+            container: field("journal")
+                .or_else(|| field("journaltitle"))
+                .or_else(|| field("booktitle"))
+                .map(clean_tex),
+        };
+        entries.push(BibEntry {
+            citation,
+            abstract_text: field("abstract").map(clean_tex),
+            files: field("file").map(parse_file_field).unwrap_or_default(),
+        });
+    }
+    entries
+}
+
+// ============ CSL JSON ============
+
+/// Parse a CSL JSON export (Zotero's "CSL JSON" format)
+pub fn parse_csl_json(text: &str) -> Result<Vec<BibEntry>, BibliographyError> {
+    let items: Vec<Value> = serde_json::from_str(text)?;
+    let str_field = |item: &Value, key: &str| {
+        item.get(key).and_then(|v| v.as_str()).map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+    };
+    Ok(items
+        .iter()
+        .map(|item| {
+            let authors = item
+                .get("author")
+                .and_then(|a| a.as_array())
+                .map(|authors| {
+                    authors
+                        .iter()
+                        .filter_map(|a| match (str_field(a, "family"), str_field(a, "given")) {
+                            (Some(family), Some(given)) => Some(format!("{}, {}", family, given)),
+                            (Some(family), None) => Some(family),
+                            _ => str_field(a, "literal"),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let year = item
+                .pointer("/issued/date-parts/0/0")
+                .and_then(|y| y.as_i64().map(|y| y.to_string()).or_else(|| y.as_str().map(str::to_string)));
+            BibEntry {
+                citation: Citation {
+                    key: str_field(item, "id")
+                        .or_else(|| item.get("id").and_then(|v| v.as_i64()).map(|id| id.to_string()))
+                        .unwrap_or_default(),
+                    entry_type: str_field(item, "type").unwrap_or_default(),
+                    title: str_field(item, "title"),
+                    authors,
+                    year,
+                    doi: str_field(item, "DOI"),
+                    url: str_field(item, "URL"),
+                    container: str_field(item, "container-title"),
+                },
+                abstract_text: str_field(item, "abstract"),
+                files: Vec::new(),
+            }
+        })
+        .collect())
+}
+
+/// Parse BibTeX or CSL JSON, told apart by the first character
+pub fn parse_bibliography(text: &str) -> Result<Vec<BibEntry>, BibliographyError> {
+    if text.trim_start().starts_with('[') {
+        parse_csl_json(text)
+    } else {
+        Ok(parse_bibtex(text))
+    }
+}
+
+// ============ IMPORT ============
+
+/// Text of the first readable attachment
+fn attachment_text(files: &[String]) -> Option<String> {
+    files.iter().map(Path::new).filter(|p| p.is_file()).find_map(|path| {
+        let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+        let text = match ext.as_str() {
+            "pdf" => {
+                let output = Command::new("pdftotext").arg("-layout").arg(path).arg("-").output();
+                match output {
+                    Ok(out) if out.status.success() => Some(String::from_utf8_lossy(&out.stdout).into_owned()),
+                    Ok(out) => {
+                        debug!(path = %path.display(), stderr = %String::from_utf8_lossy(&out.stderr), "pdftotext failed");
+                        None
+                    }
+                    Err(e) => {
+                        debug!(error = %e, "pdftotext unavailable");
+                        None
+                    }
+                }
+            }
+            "html" | "htm" => fs::read_to_string(path).ok().map(|html| html_to_markdown(&html).1),
+            "txt" | "md" | "markdown" => fs::read_to_string(path).ok(),
+            _ => None,
+        };
+        text.filter(|t| !t.trim().is_empty())
+    })
+}
+
+/// Markdown document for an entry: citation header, abstract, attachment text
+fn entry_markdown(entry: &BibEntry, attachment: Option<&str>) -> String {
+    let c = &entry.citation;
+    let mut md = format!("# {}\n\n", c.title.as_deref().unwrap_or(&c.key));
+    let byline: Vec<String> = [
+        (!c.authors.is_empty()).then(|| c.authors.join(AUTHOR_SEPARATOR)),
+        c.year.clone(),
+        c.container.clone(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if !byline.is_empty() {
+        md.push_str(&format!("{}\n\n", byline.join(" · ")));
+    }
+    if let Some(doi) = &c.doi {
+        md.push_str(&format!("DOI: <https://doi.org/{}>\n\n", doi));
+    }
+    if let Some(url) = &c.url {
+        md.push_str(&format!("URL: <{}>\n\n", url));
+    }
+    if let Some(abstract_text) = &entry.abstract_text {
+        md.push_str(&format!("## Abstract\n\n{}\n\n", abstract_text));
+    }
+    if let Some(text) = attachment {
+        md.push_str(&format!("## Full Text\n\n{}\n", text.trim()));
+    }
+    md
+}
+
+/// Import every entry of a bibliography file as a reference document and
+/// record them in session.json
+pub async fn import_bibliography(
+    session_id: &str,
+    path: &Path,
+    persistence: DocumentPersistence,
+) -> Result<BibliographyImport, BibliographyError> {
+    let entries = parse_bibliography(&fs::read_to_string(path)?)?;
+    if entries.is_empty() {
+        return Err(BibliographyError::Empty(path.display().to_string()));
+    }
+    let session_dir = get_session_dir_cli(session_id)?;
+    let mut import = BibliographyImport { imported: Vec::new(), metadata_only: Vec::new(), failed: Vec::new() };
+
+    for entry in entries {
+        let attachment = attachment_text(&entry.files);
+        if attachment.is_none() && !entry.files.is_empty() {
+            import.metadata_only.push(entry.citation.key.clone());
+        }
+        let title = entry.citation.title.clone().unwrap_or_else(|| entry.citation.key.clone());
+        let result = async {
+            let file = reference_file_path(&session_dir, &title)?;
+            fs::write(&file, entry_markdown(&entry, attachment.as_deref()))?;
+            let doc = add_reference_with_citation(
+                session_id,
+                &file.to_string_lossy(),
+                persistence,
+                Some(entry.citation.clone()),
+            )
+            .await?;
+            Ok::<_, BibliographyError>(doc)
+        }
+        .await;
+        match result {
+            Ok(doc) => import.imported.push(doc),
+            Err(e) => {
+                warn!(key = %entry.citation.key, error = %e, "Failed to import bibliography entry");
+                import.failed.push(format!("{}: {}", entry.citation.key, e));
+            }
+        }
+    }
+
+    update_session_file(&session_dir.join("session.json"), |s| {
+        s.reference_docs.extend(import.imported.iter().map(|doc| SessionReferenceDoc {
+            id: doc.id.clone(),
+            filename: doc.filename.clone(),
+            path: doc.path.clone(),
+            token_count: doc.loaded_tokens,
+            handling: doc.handling.as_str().to_string(),
+            persistence: doc.persistence.as_str().to_string(),
+            url: doc.citation.as_ref().and_then(|c| c.url.clone()),
+            citation: doc.citation.clone(),
+        }));
+        Ok(())
+    })?;
+    info!(
+        session_id = %session_id,
+        imported = import.imported.len(),
+        failed = import.failed.len(),
+        "Imported bibliography"
+    );
+    Ok(import)
+}
+
+// ============ TAURI COMMANDS ============
+
+/// Import a BibTeX (.bib) or CSL JSON export into a session
+#[tauri::command]
+pub async fn documents_import_bibliography(
+    session_id: String,
+    path: String,
+    persistence: Option<DocumentPersistence>,
+) -> Result<BibliographyImport, BibliographyError> {
+    validate_session_id(&session_id)?;
+    import_bibliography(&session_id, Path::new(&path), document_persistence(&session_id, persistence)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bibtex() {
+        let bib = r#"
+@comment{jabref-meta: databaseType:bibtex;}
+@article{porter1979,
+  title = {How {Competitive} Forces Shape Strategy},
+  author = {Porter, Michael E. and {Harvard Business Review}},
+  journal = "Harvard Business Review",
+  year = 1979,
+  doi = {10.1000/hbr.1979},
+  abstract = {Five forces\&more -- a framework.},
+  file = {Full Text PDF:/home/me/Zotero/storage/AB12/Porter.pdf:application/pdf;Snapshot:/home/me/Zotero/storage/AB12/snap.html:text/html},
+}
+@book{christensen, title={The Innovator's Dilemma}, date={1997-05-01}, file={C\:\\Papers\\dilemma.pdf}}
+"#;
+        let entries = parse_bibtex(bib);
+        assert_eq!(entries.len(), 2);
+
+        let porter = &entries[0];
+        assert_eq!(porter.citation.key, "porter1979");
+        assert_eq!(porter.citation.entry_type, "article");
+        assert_eq!(porter.citation.title.as_deref(), Some("How Competitive Forces Shape Strategy"));
+        assert_eq!(porter.citation.authors, vec!["Porter, Michael E.", "Harvard Business Review"]);
+        assert_eq!(porter.citation.year.as_deref(), Some("1979"));
+        assert_eq!(porter.citation.container.as_deref(), Some("Harvard Business Review"));
+        assert_eq!(porter.abstract_text.as_deref(), Some("Five forces&more – a framework."));
+        assert_eq!(porter.files, vec![
+            "/home/me/Zotero/storage/AB12/Porter.pdf",
+            "/home/me/Zotero/storage/AB12/snap.html",
+        ]);
+
+        let book = &entries[1];
+        assert_eq!(book.citation.year.as_deref(), Some("1997"));
+        assert_eq!(book.files, vec![r"C:\\Papers\\dilemma.pdf"]);
+    }
+
+    #[test]
+    fn test_parse_csl_json() {
+        let json = r#"[{"id": "smith2020", "type": "article-journal", "title": "Moats",
+            "author": [{"family": "Smith", "given": "Ann"}, {"literal": "OECD"}],
+            "issued": {"date-parts": [[2020, 3]]}, "DOI": "10.1/x", "container-title": "J. Econ."}]"#;
+        let entries = parse_bibliography(json).unwrap();
+        let citation = &entries[0].citation;
+        assert_eq!(citation.key, "smith2020");
+        assert_eq!(citation.authors, vec!["Smith, Ann", "OECD"]);
+        assert_eq!(citation.year.as_deref(), Some("2020"));
+        assert_eq!(citation.doi.as_deref(), Some("10.1/x"));
+    }
+
+    #[test]
+    fn test_citation_metadata_round_trip() {
+        let citation = Citation {
+            key: "k".into(),
+            entry_type: "book".into(),
+            title: Some("T".into()),
+            authors: vec!["A, B".into(), "C".into()],
+            year: Some("2001".into()),
+            doi: None,
+            url: None,
+            container: None,
+        };
+        let mut meta = json!({ "doc_id": "d" });
+        citation.write_metadata(&mut meta);
+        assert_eq!(Citation::from_metadata(&meta), Some(citation));
+        assert!(Citation::from_metadata(&json!({ "doc_id": "d" })).is_none());
+    }
+}
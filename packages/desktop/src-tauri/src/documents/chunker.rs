@@ -7,6 +7,10 @@ use std::fs;
 use std::path::Path;
 use thiserror::Error;
 
+use super::sentences::{find_split_boundary, sentence_boundaries};
+use super::structured::{chunk_csv, chunk_json};
+use crate::context::tokens::estimate_tokens_quick;
+
 /// Token thresholds for document handling strategies
 pub const THRESHOLD_FULL: u32 = 4_000;       // Load fully
 pub const THRESHOLD_SUMMARIZE: u32 = 20_000; // Summary + section index
@@ -51,6 +55,16 @@ pub enum DocumentHandling {
     Chunked,
 }
 
+impl DocumentHandling {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocumentHandling::Full => "full",
+            DocumentHandling::Summarized => "summarized",
+            DocumentHandling::Chunked => "chunked",
+        }
+    }
+}
+
 /// Document persistence strategy
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -63,6 +77,16 @@ pub enum DocumentPersistence {
     Permanent,
 }
 
+impl DocumentPersistence {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocumentPersistence::Ephemeral => "ephemeral",
+            DocumentPersistence::Cached => "cached",
+            DocumentPersistence::Permanent => "permanent",
+        }
+    }
+}
+
 /// A chunk of a document
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -79,6 +103,35 @@ pub struct Chunk {
     pub token_count: u32,
     /// Section heading if available
     pub section: Option<String>,
+    /// Rows or paths covered, for structured (CSV/JSON) documents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<ChunkLocation>,
+}
+
+/// Where a structured chunk's data sits in its source
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", rename_all_fields = "camelCase")]
+pub enum ChunkLocation {
+    /// Data rows `first_row..=last_row` of a table (1-based, header excluded)
+    Rows { columns: Vec<String>, first_row: u32, last_row: u32 },
+    /// Top-level JSON entries the chunk's values sit under
+    JsonPaths { paths: Vec<String> },
+}
+
+impl ChunkLocation {
+    /// Add the location to Chroma chunk metadata (scalars only, so lists are joined)
+    pub fn write_metadata(&self, meta: &mut serde_json::Value) {
+        match self {
+            ChunkLocation::Rows { columns, first_row, last_row } => {
+                meta["columns"] = serde_json::json!(columns.join(", "));
+                meta["first_row"] = serde_json::json!(first_row);
+                meta["last_row"] = serde_json::json!(last_row);
+            }
+            ChunkLocation::JsonPaths { paths } => {
+                meta["json_paths"] = serde_json::json!(paths.join(", "));
+            }
+        }
+    }
 }
 
 /// Document with metadata and optional chunks
@@ -107,6 +160,44 @@ pub struct SectionIndex {
     pub token_count: u32,
 }
 
+/// Full text of one section, including its subsections
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentSection {
+    pub heading: String,
+    pub level: u8,
+    pub content: String,
+    pub token_count: u32,
+    /// Chunks covered, end exclusive
+    pub start_chunk: u32,
+    pub end_chunk: u32,
+}
+
+impl ChunkedDocument {
+    /// Section whose heading matches `heading` (exactly, else ignoring case
+    /// and surrounding whitespace), running until the next heading of the
+    /// same or a higher level
+    pub fn section(&self, heading: &str) -> Option<DocumentSection> {
+        let wanted = heading.trim();
+        let position = self.sections.iter().position(|s| s.heading == wanted)
+            .or_else(|| self.sections.iter().position(|s| s.heading.eq_ignore_ascii_case(wanted)))?;
+        let section = &self.sections[position];
+        let end_chunk = self.sections[position + 1..].iter()
+            .find(|s| s.level <= section.level)
+            .map_or(self.chunks.len() as u32, |s| s.start_chunk);
+
+        let chunks = self.chunks.get(section.start_chunk as usize..end_chunk as usize)?;
+        Some(DocumentSection {
+            heading: section.heading.clone(),
+            level: section.level,
+            content: chunks.iter().map(|c| c.content.as_str()).collect(),
+            token_count: chunks.iter().map(|c| c.token_count).sum(),
+            start_chunk: section.start_chunk,
+            end_chunk,
+        })
+    }
+}
+
 /// Determine handling strategy for a document
 pub fn determine_handling(token_count: u32) -> DocumentHandling {
     if token_count <= THRESHOLD_FULL {
@@ -125,21 +216,26 @@ pub fn chunk_document(path: &Path, doc_id: &str) -> Result<ChunkedDocument, Chun
         return Err(ChunkerError::FileTooLarge(file_size, MAX_FILE_SIZE));
     }
     let content = fs::read_to_string(path)?;
-    let filename = path.file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_else(|| "unknown".to_string());
-
     let extension = path.extension()
         .map(|e| e.to_string_lossy().to_lowercase())
         .unwrap_or_default();
 
-    // Estimate tokens (byte-based; overestimates for non-ASCII/multi-byte text)
-    let total_tokens = (content.len() as f64 / 4.0).ceil() as u32;
+    Ok(chunk_content(&content, &extension, path, doc_id))
+}
+
+/// Chunk text that came from `path`, treating it as a file with `extension`
+pub fn chunk_content(content: &str, extension: &str, path: &Path, doc_id: &str) -> ChunkedDocument {
+    let filename = path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    // Estimate tokens (ratio depends on prose/code/CJK content)
+    let total_tokens = estimate_tokens_quick(content);
     let handling = determine_handling(total_tokens);
 
     // For full documents, just return as single chunk
     if handling == DocumentHandling::Full {
-        return Ok(ChunkedDocument {
+        return ChunkedDocument {
             id: doc_id.to_string(),
             filename,
             path: path.to_string_lossy().to_string(),
@@ -147,26 +243,29 @@ pub fn chunk_document(path: &Path, doc_id: &str) -> Result<ChunkedDocument, Chun
             handling,
             chunks: vec![Chunk {
                 index: 0,
-                content: content.clone(),
+                content: content.to_string(),
                 start_pos: 0,
                 end_pos: content.len(),
                 token_count: total_tokens,
                 section: None,
+                location: None,
             }],
             summary: None,
             sections: Vec::new(),
-        });
+        };
     }
 
     // Chunk based on content type
-    let (chunks, sections) = match extension.as_str() {
-        "md" | "markdown" => chunk_markdown(&content),
-        "txt" => chunk_plain_text(&content),
-        "py" | "rs" | "ts" | "js" | "tsx" | "jsx" => chunk_code(&content),
-        _ => chunk_plain_text(&content), // Default to plain text
+    let (chunks, sections) = match extension {
+        "md" | "markdown" => chunk_markdown(content),
+        "txt" => chunk_plain_text(content),
+        "csv" => chunk_csv(content),
+        "json" => chunk_json(content).unwrap_or_else(|| chunk_plain_text(content)),
+        "py" | "rs" | "ts" | "js" | "tsx" | "jsx" => chunk_code(content),
+        _ => chunk_plain_text(content), // Default to plain text
     };
 
-    Ok(ChunkedDocument {
+    ChunkedDocument {
         id: doc_id.to_string(),
         filename,
         path: path.to_string_lossy().to_string(),
@@ -175,7 +274,7 @@ pub fn chunk_document(path: &Path, doc_id: &str) -> Result<ChunkedDocument, Chun
         chunks,
         summary: None, // Populated by LLM later
         sections,
-    })
+    }
 }
 
 /// Chunk markdown content by headers
@@ -201,7 +300,7 @@ fn chunk_markdown(content: &str) -> (Vec<Chunk>, Vec<SectionIndex>) {
         if line.starts_with('#') {
             // Save current chunk if not empty
             if !current_chunk.trim().is_empty() {
-                let token_count = (current_chunk.len() as f64 / 4.0).ceil() as u32;
+                let token_count = estimate_tokens_quick(&current_chunk);
                 chunks.push(Chunk {
                     index: chunk_index,
                     content: current_chunk.clone(),
@@ -209,6 +308,7 @@ fn chunk_markdown(content: &str) -> (Vec<Chunk>, Vec<SectionIndex>) {
                     end_pos: line_start,
                     token_count,
                     section: current_section.clone(),
+                    location: None,
                 });
                 chunk_index += 1;
             }
@@ -233,12 +333,12 @@ fn chunk_markdown(content: &str) -> (Vec<Chunk>, Vec<SectionIndex>) {
         current_chunk.push('\n');
 
         // Check if chunk exceeds target size
-        let token_estimate = (current_chunk.len() as f64 / 4.0).ceil() as u32;
+        let token_estimate = estimate_tokens_quick(&current_chunk);
         if token_estimate >= CHUNK_SIZE_TARGET {
-            // Try to split at paragraph boundary
-            if let Some(split_pos) = find_paragraph_boundary(&current_chunk) {
+            // Split at a paragraph or sentence boundary
+            if let Some(split_pos) = find_split_boundary(&current_chunk) {
                 let (first, rest) = current_chunk.split_at(split_pos);
-                let first_tokens = (first.len() as f64 / 4.0).ceil() as u32;
+                let first_tokens = estimate_tokens_quick(first);
 
                 chunks.push(Chunk {
                     index: chunk_index,
@@ -247,6 +347,7 @@ fn chunk_markdown(content: &str) -> (Vec<Chunk>, Vec<SectionIndex>) {
                     end_pos: current_start + split_pos,
                     token_count: first_tokens,
                     section: current_section.clone(),
+                    location: None,
                 });
 
                 chunk_index += 1;
@@ -258,7 +359,7 @@ fn chunk_markdown(content: &str) -> (Vec<Chunk>, Vec<SectionIndex>) {
 
     // Save final chunk
     if !current_chunk.trim().is_empty() {
-        let token_count = (current_chunk.len() as f64 / 4.0).ceil() as u32;
+        let token_count = estimate_tokens_quick(&current_chunk);
         chunks.push(Chunk {
             index: chunk_index,
             content: current_chunk,
@@ -266,6 +367,7 @@ fn chunk_markdown(content: &str) -> (Vec<Chunk>, Vec<SectionIndex>) {
             end_pos: content.len(),
             token_count,
             section: current_section,
+            location: None,
         });
     }
 
@@ -282,64 +384,66 @@ fn chunk_markdown(content: &str) -> (Vec<Chunk>, Vec<SectionIndex>) {
     (chunks, sections)
 }
 
-/// Chunk plain text by paragraphs
+/// Chunk plain text by paragraphs, splitting paragraphs that are too
+/// large on their own at sentence boundaries
 fn chunk_plain_text(content: &str) -> (Vec<Chunk>, Vec<SectionIndex>) {
     let mut chunks = Vec::new();
-    let mut current_chunk = String::new();
-    let mut current_start = 0usize;
     let mut chunk_index = 0u32;
 
-    let paragraphs: Vec<&str> = content.split("\n\n").collect();
-    // Compute byte offset of each paragraph within content
-    let mut para_offsets: Vec<usize> = Vec::with_capacity(paragraphs.len());
+    // Byte spans of the units chunks are built from
+    let mut units: Vec<(usize, usize)> = Vec::new();
     let mut offset = 0usize;
-    for (i, para) in paragraphs.iter().enumerate() {
-        para_offsets.push(offset);
-        offset += para.len();
-        if i < paragraphs.len() - 1 {
-            offset += 2; // account for the "\n\n" separator
+    for paragraph in content.split("\n\n") {
+        let end = offset + paragraph.len();
+        if estimate_tokens_quick(paragraph) > CHUNK_SIZE_TARGET {
+            let mut start = offset;
+            for boundary in sentence_boundaries(paragraph) {
+                units.push((start, offset + boundary));
+                start = offset + boundary;
+            }
+            units.push((start, end));
+        } else {
+            units.push((offset, end));
         }
+        offset = end + 2; // account for the "\n\n" separator
     }
 
-    for (i, paragraph) in paragraphs.iter().enumerate() {
-        // Check if adding this paragraph exceeds target
-        let added_len = if current_chunk.is_empty() { paragraph.len() } else { paragraph.len() + 2 };
-        let potential_tokens = ((current_chunk.len() + added_len) as f64 / 4.0).ceil() as u32;
+    let mut current: Option<(usize, usize)> = None;
+    for (start, end) in units {
+        if let Some((current_start, current_end)) = current {
+            if estimate_tokens_quick(&content[current_start..end]) > CHUNK_SIZE_TARGET {
+                // Save current chunk — end_pos is the start of this unit
+                let chunk_content = &content[current_start..current_end];
+                chunks.push(Chunk {
+                    index: chunk_index,
+                    content: chunk_content.to_string(),
+                    start_pos: current_start,
+                    end_pos: start,
+                    token_count: estimate_tokens_quick(chunk_content),
+                    section: None,
+                    location: None,
+                });
+                chunk_index += 1;
+                current = None;
+            }
+        }
+        current = Some((current.map_or(start, |(current_start, _)| current_start), end));
+    }
 
-        if potential_tokens > CHUNK_SIZE_TARGET && !current_chunk.is_empty() {
-            // Save current chunk — end_pos is the start of this paragraph
-            let token_count = (current_chunk.len() as f64 / 4.0).ceil() as u32;
+    // Save final chunk
+    if let Some((current_start, _)) = current {
+        let chunk_content = &content[current_start..];
+        if !chunk_content.trim().is_empty() {
             chunks.push(Chunk {
                 index: chunk_index,
-                content: current_chunk.clone(),
+                content: chunk_content.to_string(),
                 start_pos: current_start,
-                end_pos: para_offsets[i],
-                token_count,
+                end_pos: content.len(),
+                token_count: estimate_tokens_quick(chunk_content),
                 section: None,
+                location: None,
             });
-
-            chunk_index += 1;
-            current_start = para_offsets[i];
-            current_chunk = String::new();
-        }
-
-        if !current_chunk.is_empty() {
-            current_chunk.push_str("\n\n");
         }
-        current_chunk.push_str(paragraph);
-    }
-
-    // Save final chunk
-    if !current_chunk.trim().is_empty() {
-        let token_count = (current_chunk.len() as f64 / 4.0).ceil() as u32;
-        chunks.push(Chunk {
-            index: chunk_index,
-            content: current_chunk,
-            start_pos: current_start,
-            end_pos: content.len(),
-            token_count,
-            section: None,
-        });
     }
 
     (chunks, Vec::new())
@@ -365,7 +469,7 @@ fn chunk_code(content: &str) -> (Vec<Chunk>, Vec<SectionIndex>) {
         } else {
             // If we hit 2+ blank lines and have content, consider splitting
             if blank_count >= 2 && !current_chunk.is_empty() {
-                let token_estimate = (current_chunk.len() as f64 / 4.0).ceil() as u32;
+                let token_estimate = estimate_tokens_quick(&current_chunk);
                 if token_estimate >= CHUNK_SIZE_TARGET / 2 {
                     chunks.push(Chunk {
                         index: chunk_index,
@@ -374,6 +478,7 @@ fn chunk_code(content: &str) -> (Vec<Chunk>, Vec<SectionIndex>) {
                         end_pos: pos,
                         token_count: token_estimate,
                         section: None,
+                        location: None,
                     });
                     chunk_index += 1;
                     current_start = pos;
@@ -388,7 +493,7 @@ fn chunk_code(content: &str) -> (Vec<Chunk>, Vec<SectionIndex>) {
         pos += line_len;
 
         // Force split at target size
-        let token_estimate = (current_chunk.len() as f64 / 4.0).ceil() as u32;
+        let token_estimate = estimate_tokens_quick(&current_chunk);
         if token_estimate >= CHUNK_SIZE_TARGET {
             chunks.push(Chunk {
                 index: chunk_index,
@@ -397,6 +502,7 @@ fn chunk_code(content: &str) -> (Vec<Chunk>, Vec<SectionIndex>) {
                 end_pos: pos,
                 token_count: token_estimate,
                 section: None,
+                location: None,
             });
             chunk_index += 1;
             current_start = pos;
@@ -406,7 +512,7 @@ fn chunk_code(content: &str) -> (Vec<Chunk>, Vec<SectionIndex>) {
 
     // Save final chunk
     if !current_chunk.trim().is_empty() {
-        let token_count = (current_chunk.len() as f64 / 4.0).ceil() as u32;
+        let token_count = estimate_tokens_quick(&current_chunk);
         chunks.push(Chunk {
             index: chunk_index,
             content: current_chunk,
@@ -414,19 +520,13 @@ fn chunk_code(content: &str) -> (Vec<Chunk>, Vec<SectionIndex>) {
             end_pos: content.len(),
             token_count,
             section: None,
+            location: None,
         });
     }
 
     (chunks, sections)
 }
 
-/// Find a good paragraph boundary for splitting
-fn find_paragraph_boundary(text: &str) -> Option<usize> {
-    // Look for \n\n in the latter half of the text
-    let mid = text.len() / 2;
-    text[mid..].find("\n\n").map(|pos| mid + pos + 2)
-}
-
 /// A file or directory entry for the file tree UI
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -438,7 +538,7 @@ pub struct FileEntry {
 }
 
 /// Supported text file extensions for the document viewer
-const SUPPORTED_EXTENSIONS: &[&str] = &[
+pub(crate) const SUPPORTED_EXTENSIONS: &[&str] = &[
     "md", "markdown", "txt", "rs", "ts", "tsx", "js", "jsx", "py", "json",
     "yaml", "yml", "toml", "css", "html", "htm", "csv", "sh", "bash", "zsh",
     "swift", "go", "java", "c", "cpp", "h", "hpp", "rb", "lua", "sql",
@@ -577,6 +677,29 @@ mod tests {
         assert_eq!(sections[1].level, 2);
     }
 
+    #[test]
+    fn test_document_section_includes_subsections() {
+        let content = "# Intro\n\nOpening.\n\n## Background\n\nHistory.\n\n### Detail\n\nFine print.\n\n## Outlook\n\nNext year.\n";
+        let (chunks, sections) = chunk_markdown(content);
+        let document = ChunkedDocument {
+            id: "doc".to_string(),
+            filename: "notes.md".to_string(),
+            path: "/tmp/notes.md".to_string(),
+            total_tokens: 0,
+            handling: DocumentHandling::Summarized,
+            chunks,
+            summary: None,
+            sections,
+        };
+
+        let background = document.section(" background ").unwrap();
+        assert_eq!(background.heading, "Background");
+        assert!(background.content.contains("History.") && background.content.contains("Fine print."));
+        assert!(!background.content.contains("Next year."));
+        assert_eq!(document.section("Outlook").unwrap().content, "## Outlook\n\nNext year.\n");
+        assert!(document.section("Missing").is_none());
+    }
+
     #[test]
     fn test_chunk_plain_text() {
         let content = "First paragraph.\n\nSecond paragraph.\n\nThird paragraph.";
@@ -599,6 +722,19 @@ mod tests {
         assert_eq!(&content[chunk.start_pos..chunk.end_pos], &chunk.content);
     }
 
+    #[test]
+    fn test_chunk_plain_text_splits_long_paragraph_at_sentences() {
+        let sentence = "The committee reviewed supply constraints across every region this quarter. ";
+        let content = format!("Intro paragraph.\n\n{}", sentence.repeat(60));
+        let (chunks, _) = chunk_plain_text(&content);
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert_eq!(&content[chunk.start_pos..chunk.start_pos + chunk.content.len()], chunk.content);
+            assert!(chunk.content.trim_end().ends_with('.'), "chunk ends mid-sentence: {:?}", chunk.content);
+        }
+    }
+
     #[test]
     fn test_chunk_markdown_no_trailing_newline() {
         let content = "# Title\n\nSome text";
@@ -13,7 +13,7 @@ use std::hash::{Hash, Hasher};
 use thiserror::Error;
 
 /// Dimensionality of the embedding vectors.
-const EMBEDDING_DIM: usize = 256;
+pub const EMBEDDING_DIM: usize = 256;
 
 #[derive(Error, Debug)]
 pub enum EmbeddingError {
@@ -125,15 +125,54 @@ pub fn get_cached_embedding(chunk_id: &str) -> Option<Embedding> {
     cache.as_ref()?.embeddings.get(chunk_id).cloned()
 }
 
+/// Independent partial sums per loop; eight f32 lanes fill an AVX register
+/// (two SSE/NEON ones), so LLVM vectorizes the loops below on stable Rust
+const LANES: usize = 8;
+
+/// Dot product of `a` and `b` with the sums of squares of each, in one pass
+fn dot_and_norms(a: &[f32], b: &[f32]) -> (f32, f32, f32) {
+    let mut dot = [0.0f32; LANES];
+    let mut sq_a = [0.0f32; LANES];
+    let mut sq_b = [0.0f32; LANES];
+    let (chunks_a, chunks_b) = (a.chunks_exact(LANES), b.chunks_exact(LANES));
+    let (rest_a, rest_b) = (chunks_a.remainder(), chunks_b.remainder());
+    for (x, y) in chunks_a.zip(chunks_b) {
+        for i in 0..LANES {
+            dot[i] += x[i] * y[i];
+            sq_a[i] += x[i] * x[i];
+            sq_b[i] += y[i] * y[i];
+        }
+    }
+    let (mut dot, mut sq_a, mut sq_b) = (dot.iter().sum::<f32>(), sq_a.iter().sum::<f32>(), sq_b.iter().sum::<f32>());
+    for (x, y) in rest_a.iter().zip(rest_b) {
+        dot += x * y;
+        sq_a += x * x;
+        sq_b += y * y;
+    }
+    (dot, sq_a, sq_b)
+}
+
+/// Dot product of two equal-length slices
+pub fn dot(a: &[f32], b: &[f32]) -> f32 {
+    let mut acc = [0.0f32; LANES];
+    let (chunks_a, chunks_b) = (a.chunks_exact(LANES), b.chunks_exact(LANES));
+    let tail: f32 = chunks_a.remainder().iter().zip(chunks_b.remainder()).map(|(x, y)| x * y).sum();
+    for (x, y) in chunks_a.zip(chunks_b) {
+        for i in 0..LANES {
+            acc[i] += x[i] * y[i];
+        }
+    }
+    acc.iter().sum::<f32>() + tail
+}
+
 /// Calculate cosine similarity between two embeddings
 pub fn cosine_similarity(a: &Embedding, b: &Embedding) -> f32 {
     if a.len() != b.len() || a.is_empty() {
         return 0.0;
     }
 
-    let dot: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
-    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let (dot, sq_a, sq_b) = dot_and_norms(a, b);
+    let (norm_a, norm_b) = (sq_a.sqrt(), sq_b.sqrt());
 
     if norm_a == 0.0 || norm_b == 0.0 {
         return 0.0;
@@ -142,6 +181,73 @@ pub fn cosine_similarity(a: &Embedding, b: &Embedding) -> f32 {
     dot / (norm_a * norm_b)
 }
 
+/// Embeddings packed row-major into one contiguous buffer, with each row's
+/// inverse norm precomputed, so scoring a query is a single linear sweep
+#[derive(Debug, Clone)]
+pub struct EmbeddingMatrix {
+    dim: usize,
+    data: Vec<f32>,
+    /// `1 / |row|`, or 0 for all-zero rows so they score 0
+    inv_norms: Vec<f32>,
+}
+
+impl EmbeddingMatrix {
+    pub fn new(dim: usize) -> Self {
+        Self { dim, data: Vec::new(), inv_norms: Vec::new() }
+    }
+
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    pub fn len(&self) -> usize {
+        self.inv_norms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inv_norms.is_empty()
+    }
+
+    /// Append a row; returns its index, or `None` if the length is wrong
+    pub fn push(&mut self, row: &[f32]) -> Option<usize> {
+        if row.len() != self.dim {
+            return None;
+        }
+        let norm = dot(row, row).sqrt();
+        self.data.extend_from_slice(row);
+        self.inv_norms.push(if norm > 0.0 { 1.0 / norm } else { 0.0 });
+        Some(self.inv_norms.len() - 1)
+    }
+
+    pub fn row(&self, index: usize) -> &[f32] {
+        &self.data[index * self.dim..(index + 1) * self.dim]
+    }
+
+    pub fn inv_norm(&self, index: usize) -> f32 {
+        self.inv_norms[index]
+    }
+
+    /// Cosine similarity between two rows
+    pub fn row_similarity(&self, a: usize, b: usize) -> f32 {
+        dot(self.row(a), self.row(b)) * self.inv_norms[a] * self.inv_norms[b]
+    }
+
+    /// Cosine similarity of `query` to each row in `rows`
+    pub fn similarities(&self, query: &[f32], rows: std::ops::Range<usize>) -> Vec<f32> {
+        let rows = rows.start.min(self.len())..rows.end.min(self.len());
+        let query_norm = dot(query, query).sqrt();
+        if query.len() != self.dim || query_norm == 0.0 {
+            return vec![0.0; rows.len()];
+        }
+        let inv_query = 1.0 / query_norm;
+        self.data[rows.start * self.dim..rows.end * self.dim]
+            .chunks_exact(self.dim)
+            .zip(&self.inv_norms[rows])
+            .map(|(row, inv_norm)| dot(row, query) * inv_norm * inv_query)
+            .collect()
+    }
+}
+
 /// Find most similar embeddings to query
 pub fn find_similar(
     query_embedding: &Embedding,
@@ -249,4 +355,29 @@ mod tests {
         assert_eq!(results.len(), 2);
         assert_eq!(results[0].0, "a"); // Most similar
     }
+
+    #[test]
+    fn test_embedding_matrix_matches_cosine_similarity() {
+        let rows = [
+            generate_embedding("market share and pricing power").unwrap(),
+            vec![0.0; EMBEDDING_DIM],
+            generate_embedding("supply chain risk in semiconductors").unwrap(),
+        ];
+        let mut matrix = EmbeddingMatrix::new(EMBEDDING_DIM);
+        for row in &rows {
+            assert!(matrix.push(row).is_some());
+        }
+        assert_eq!(matrix.push(&[1.0, 2.0]), None);
+
+        let query = generate_embedding("pricing power in the market").unwrap();
+        let scores = matrix.similarities(&query, 0..matrix.len());
+        for (row, score) in rows.iter().zip(&scores) {
+            assert!((cosine_similarity(&query, row) - score).abs() < 1e-5);
+        }
+        assert_eq!(matrix.similarities(&query, 2..3), scores[2..].to_vec());
+
+        // Odd lengths exercise the scalar tail
+        let (a, b) = (vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0], vec![1.0; 9]);
+        assert_eq!(dot(&a, &b), 45.0);
+    }
 }
@@ -0,0 +1,305 @@
+//! Fallback Index Persistence
+//!
+//! Keeps the feature-hash embeddings behind offline document search on disk,
+//! so a session's references stay searchable after a restart even when
+//! Chroma never saw them. Each session gets `<session dir>/fallback_index/`
+//! with two files:
+//!
+//! - `embeddings.f32`: a 16-byte header (magic, format version, dimension,
+//!   row count) followed by one little-endian f32 row per embedded chunk
+//! - `documents.json`: the chunked documents, in row order, with the chunk
+//!   index of each of their rows
+//!
+//! Both are rewritten whole after every change and read in one pass the
+//! first time a session is searched. Ephemeral documents are never written.
+//! Documents held in the cross-session registry are written as a
+//! `RegisteredDocument` with no rows and resolved from the registry on load.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::bibtex::Citation;
+use super::chunker::{ChunkedDocument, DocumentPersistence};
+use super::embeddings::{Embedding, EMBEDDING_DIM};
+use super::registry::RegisteredDocument;
+use crate::session::get_session_dir_cli;
+
+/// Subdirectory of the session directory holding the index
+const FALLBACK_DIR: &str = "fallback_index";
+const EMBEDDINGS_FILE: &str = "embeddings.f32";
+const DOCUMENTS_FILE: &str = "documents.json";
+const MAGIC: &[u8; 4] = b"DLFI";
+const FORMAT_VERSION: u32 = 1;
+const HEADER_LEN: usize = 16;
+
+/// Stored document with chunks and local embeddings (fallback)
+#[derive(Clone)]
+pub struct StoredDocument {
+    pub document: ChunkedDocument,
+    pub persistence: DocumentPersistence,
+    pub citation: Option<Citation>,
+    /// Fallback embeddings for when Chroma is offline
+    pub chunk_embeddings: Vec<(u32, Embedding)>,
+    /// Chunks always loaded by search assembly
+    pub pinned_chunks: BTreeSet<u32>,
+    /// Largest share of a search's token budget this document may take
+    pub max_token_share: Option<f32>,
+    /// Registry entry the chunks and embeddings are shared through
+    pub content_hash: Option<String>,
+}
+
+/// `documents.json`; `D` is borrowed when saving and owned when loading
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest<D> {
+    version: u32,
+    documents: Vec<ManifestEntry<D>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestEntry<D> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    document: Option<D>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    registered: Option<RegisteredDocument>,
+    persistence: DocumentPersistence,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    citation: Option<Citation>,
+    /// Chunk index of each of this document's rows, which follow the
+    /// previous document's rows in `embeddings.f32`
+    rows: Vec<u32>,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pinned_chunks: BTreeSet<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_token_share: Option<f32>,
+}
+
+/// Index directory of a session
+pub fn session_index_dir(session_id: &str) -> Option<PathBuf> {
+    get_session_dir_cli(session_id).ok().map(|dir| dir.join(FALLBACK_DIR))
+}
+
+/// Write the non-ephemeral `documents` to `dir`, replacing what was there.
+/// Removes the directory when nothing is left to persist.
+pub fn save<'a>(dir: &Path, documents: impl IntoIterator<Item = &'a StoredDocument>) -> io::Result<()> {
+    let documents: Vec<&StoredDocument> = documents
+        .into_iter()
+        .filter(|stored| stored.persistence != DocumentPersistence::Ephemeral)
+        .collect();
+    if documents.is_empty() {
+        return remove(dir);
+    }
+
+    let rows: usize = documents.iter().map(|stored| rows_of(stored).len()).sum();
+    let mut matrix = Vec::with_capacity(HEADER_LEN + rows * EMBEDDING_DIM * 4);
+    matrix.extend_from_slice(MAGIC);
+    matrix.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    matrix.extend_from_slice(&(EMBEDDING_DIM as u32).to_le_bytes());
+    matrix.extend_from_slice(&(rows as u32).to_le_bytes());
+
+    let mut entries = Vec::with_capacity(documents.len());
+    for stored in documents {
+        for (_, embedding) in rows_of(stored) {
+            if embedding.len() != EMBEDDING_DIM {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, "Embedding has the wrong dimension"));
+            }
+            for value in embedding {
+                matrix.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        let registered = stored.content_hash.as_ref().map(|hash| RegisteredDocument {
+            id: stored.document.id.clone(),
+            filename: stored.document.filename.clone(),
+            path: stored.document.path.clone(),
+            content_hash: hash.clone(),
+        });
+        entries.push(ManifestEntry {
+            document: registered.is_none().then_some(&stored.document),
+            registered,
+            persistence: stored.persistence,
+            citation: stored.citation.clone(),
+            rows: rows_of(stored).iter().map(|(index, _)| *index).collect(),
+            pinned_chunks: stored.pinned_chunks.clone(),
+            max_token_share: stored.max_token_share,
+        });
+    }
+    let manifest = serde_json::to_string(&Manifest { version: FORMAT_VERSION, documents: entries })?;
+
+    fs::create_dir_all(dir)?;
+    write_atomic(&dir.join(EMBEDDINGS_FILE), &matrix)?;
+    write_atomic(&dir.join(DOCUMENTS_FILE), manifest.as_bytes())
+}
+
+/// Read the documents persisted in `dir`; empty if there is no index.
+/// `resolve` looks up the chunks and embeddings of registered documents,
+/// which are skipped if it finds nothing.
+pub fn load(
+    dir: &Path,
+    mut resolve: impl FnMut(&str) -> Option<(ChunkedDocument, Vec<(u32, Embedding)>)>,
+) -> io::Result<Vec<StoredDocument>> {
+    let manifest_path = dir.join(DOCUMENTS_FILE);
+    if !manifest_path.exists() {
+        return Ok(Vec::new());
+    }
+    let manifest: Manifest<ChunkedDocument> = serde_json::from_str(&fs::read_to_string(manifest_path)?)?;
+    let matrix = fs::read(dir.join(EMBEDDINGS_FILE))?;
+
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+    if matrix.len() < HEADER_LEN || &matrix[..4] != MAGIC {
+        return Err(invalid("Not a fallback embeddings file"));
+    }
+    let header = |i: usize| u32::from_le_bytes([matrix[i], matrix[i + 1], matrix[i + 2], matrix[i + 3]]) as usize;
+    let (version, dim, rows) = (header(4) as u32, header(8), header(12));
+    if version != FORMAT_VERSION || manifest.version != FORMAT_VERSION {
+        return Err(invalid("Unsupported fallback index version"));
+    }
+    if dim != EMBEDDING_DIM {
+        return Err(invalid("Fallback embeddings have a different dimension"));
+    }
+    let manifest_rows: usize = manifest.documents.iter().map(|entry| entry.rows.len()).sum();
+    if rows != manifest_rows || matrix.len() != HEADER_LEN + rows * dim * 4 {
+        return Err(invalid("Fallback embeddings don't match the document manifest"));
+    }
+
+    let mut values = matrix[HEADER_LEN..]
+        .chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+    let mut documents = Vec::with_capacity(manifest.documents.len());
+    for entry in manifest.documents {
+        let chunk_embeddings: Vec<(u32, Embedding)> = entry
+            .rows
+            .iter()
+            .map(|index| (*index, values.by_ref().take(dim).collect()))
+            .collect();
+        let (document, chunk_embeddings, content_hash) = match (entry.document, entry.registered) {
+            (Some(document), _) => (document, chunk_embeddings, None),
+            (None, Some(registered)) => {
+                let Some((mut document, embeddings)) = resolve(&registered.content_hash) else { continue };
+                document.id = registered.id;
+                document.filename = registered.filename;
+                document.path = registered.path;
+                (document, embeddings, Some(registered.content_hash))
+            }
+            (None, None) => return Err(invalid("Manifest entry has no document")),
+        };
+        documents.push(StoredDocument {
+            document,
+            persistence: entry.persistence,
+            citation: entry.citation,
+            chunk_embeddings,
+            pinned_chunks: entry.pinned_chunks,
+            max_token_share: entry.max_token_share,
+            content_hash,
+        });
+    }
+    Ok(documents)
+}
+
+/// Delete a session's index
+pub fn remove(dir: &Path) -> io::Result<()> {
+    match fs::remove_dir_all(dir) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Embedding rows written to this index; registered documents keep theirs
+/// in the registry
+fn rows_of(stored: &StoredDocument) -> &[(u32, Embedding)] {
+    match stored.content_hash {
+        Some(_) => &[],
+        None => &stored.chunk_embeddings,
+    }
+}
+
+fn write_atomic(path: &Path, content: &[u8]) -> io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::documents::chunker::{Chunk, DocumentHandling};
+    use crate::documents::embeddings::generate_embedding;
+
+    fn stored(id: &str, persistence: DocumentPersistence, texts: &[&str]) -> StoredDocument {
+        let chunks: Vec<Chunk> = texts
+            .iter()
+            .enumerate()
+            .map(|(i, text)| Chunk {
+                index: i as u32,
+                content: text.to_string(),
+                start_pos: 0,
+                end_pos: text.len(),
+                token_count: 4,
+                section: None,
+                location: None,
+            })
+            .collect();
+        StoredDocument {
+            chunk_embeddings: chunks.iter().map(|c| (c.index, generate_embedding(&c.content).unwrap())).collect(),
+            document: ChunkedDocument {
+                id: id.to_string(),
+                filename: format!("{}.md", id),
+                path: format!("/tmp/{}.md", id),
+                total_tokens: 4 * texts.len() as u32,
+                handling: DocumentHandling::Chunked,
+                chunks,
+                summary: None,
+                sections: Vec::new(),
+            },
+            persistence,
+            citation: None,
+            pinned_chunks: BTreeSet::from([1]),
+            max_token_share: Some(0.5),
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = std::env::temp_dir().join(format!("dialectic_fallback_{}", ulid::Ulid::new()));
+        let docs = [
+            stored("a", DocumentPersistence::Cached, &["market share grows", "pricing power"]),
+            stored("b", DocumentPersistence::Ephemeral, &["scratch notes"]),
+            stored("c", DocumentPersistence::Permanent, &["supply chain risk"]),
+        ];
+        save(&dir, &docs).unwrap();
+
+        let loaded = load(&dir, |_| None).unwrap();
+        let ids: Vec<&str> = loaded.iter().map(|s| s.document.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c"]);
+        assert_eq!(loaded[0].chunk_embeddings, docs[0].chunk_embeddings);
+        assert_eq!(loaded[1].chunk_embeddings, docs[2].chunk_embeddings);
+        assert_eq!(loaded[1].persistence, DocumentPersistence::Permanent);
+        assert_eq!(loaded[0].pinned_chunks, BTreeSet::from([1]));
+        assert_eq!(loaded[0].max_token_share, Some(0.5));
+
+        // Registered documents keep only their identity here
+        let mut registered = stored("d", DocumentPersistence::Cached, &["shared notes"]);
+        registered.content_hash = Some("hash".to_string());
+        save(&dir, [&docs[0], &registered]).unwrap();
+        let shared = stored("shared", DocumentPersistence::Cached, &["shared notes"]);
+        let loaded = load(&dir, |hash| (hash == "hash").then(|| (shared.document.clone(), shared.chunk_embeddings.clone()))).unwrap();
+        assert_eq!(loaded[1].document.id, "d");
+        assert_eq!(loaded[1].document.chunks.len(), 1);
+        assert_eq!(loaded[1].chunk_embeddings, shared.chunk_embeddings);
+        assert_eq!(load(&dir, |_| None).unwrap().len(), 1);
+
+        // A truncated matrix is rejected rather than misread
+        let path = dir.join(EMBEDDINGS_FILE);
+        let bytes = fs::read(&path).unwrap();
+        fs::write(&path, &bytes[..bytes.len() - 4]).unwrap();
+        assert!(load(&dir, |_| None).is_err());
+
+        save(&dir, &docs[1..2]).unwrap();
+        assert!(!dir.exists());
+        assert!(load(&dir, |_| None).unwrap().is_empty());
+    }
+}
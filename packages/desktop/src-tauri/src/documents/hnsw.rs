@@ -0,0 +1,216 @@
+//! HNSW Index over Fallback Embeddings
+//!
+//! Hierarchical navigable small world graph (Malkov & Yashunin) for
+//! approximate nearest-neighbour search over an `EmbeddingMatrix`, so
+//! offline search stays fast once a session holds thousands of chunks.
+//! The graph only stores links; vectors stay in the matrix, and node `i` is
+//! matrix row `i`. Rows are inserted one at a time, in order, as they are
+//! appended to the matrix. Similarity is cosine, higher is closer.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+
+use super::embeddings::{dot, EmbeddingMatrix};
+
+/// Links per node on upper layers
+const M: usize = 16;
+/// Links per node on the bottom layer
+const M0: usize = 2 * M;
+/// Candidate list size while inserting
+const EF_CONSTRUCTION: usize = 100;
+/// Minimum candidate list size while searching
+const EF_SEARCH: usize = 64;
+
+/// Similarity paired with a node, ordered by similarity
+#[derive(Clone, Copy, PartialEq)]
+struct Scored(f32, u32);
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(other.1.cmp(&self.1))
+    }
+}
+
+pub struct Hnsw {
+    /// `links[node][layer]`: neighbours of `node` on `layer`
+    links: Vec<Vec<Vec<u32>>>,
+    entry: Option<u32>,
+    /// 1 / ln(M), scales the random layer draw
+    level_mult: f64,
+    /// splitmix64 state; seeded so the same rows build the same graph
+    rng: u64,
+}
+
+impl Default for Hnsw {
+    fn default() -> Self {
+        Self { links: Vec::new(), entry: None, level_mult: 1.0 / (M as f64).ln(), rng: 0x9E37_79B9_7F4A_7C15 }
+    }
+}
+
+impl Hnsw {
+    /// Index every row of `matrix`
+    pub fn build(matrix: &EmbeddingMatrix) -> Self {
+        let mut graph = Self::default();
+        graph.extend(matrix);
+        graph
+    }
+
+    /// Insert the rows of `matrix` that aren't in the graph yet
+    pub fn extend(&mut self, matrix: &EmbeddingMatrix) {
+        while self.links.len() < matrix.len() {
+            self.insert(matrix);
+        }
+    }
+
+    /// Up to `k` `(row, similarity)` pairs nearest `query`, most similar first
+    pub fn search(&self, matrix: &EmbeddingMatrix, query: &[f32], k: usize) -> Vec<(usize, f32)> {
+        let Some(entry) = self.entry else { return Vec::new() };
+        let query_norm = dot(query, query).sqrt();
+        if query_norm == 0.0 || query.len() != matrix.dim() {
+            return Vec::new();
+        }
+        let inv_query = 1.0 / query_norm;
+        let similarity = |node: u32| dot(matrix.row(node as usize), query) * matrix.inv_norm(node as usize) * inv_query;
+
+        let mut nearest = Scored(similarity(entry), entry);
+        for layer in (1..self.links[entry as usize].len()).rev() {
+            nearest = self.search_layer(&similarity, nearest, 1, layer)[0];
+        }
+        let mut found = self.search_layer(&similarity, nearest, k.max(EF_SEARCH), 0);
+        found.truncate(k);
+        found.into_iter().map(|Scored(score, node)| (node as usize, score)).collect()
+    }
+
+    fn insert(&mut self, matrix: &EmbeddingMatrix) {
+        let node = self.links.len() as u32;
+        let level = self.random_level();
+        self.links.push(vec![Vec::new(); level + 1]);
+
+        let Some(entry) = self.entry else {
+            self.entry = Some(node);
+            return;
+        };
+        let similarity = |other: u32| matrix.row_similarity(node as usize, other as usize);
+
+        let top = self.links[entry as usize].len() - 1;
+        let mut nearest = Scored(similarity(entry), entry);
+        for layer in (level + 1..=top).rev() {
+            nearest = self.search_layer(&similarity, nearest, 1, layer)[0];
+        }
+        for layer in (0..=level.min(top)).rev() {
+            let candidates = self.search_layer(&similarity, nearest, EF_CONSTRUCTION, layer);
+            nearest = candidates[0];
+            let max_links = if layer == 0 { M0 } else { M };
+            let neighbours: Vec<u32> = candidates.iter().take(max_links).map(|s| s.1).collect();
+            for &neighbour in &neighbours {
+                let links = &mut self.links[neighbour as usize][layer];
+                links.push(node);
+                if links.len() > max_links {
+                    // Keep the neighbour's closest links
+                    let mut scored: Vec<Scored> = links
+                        .iter()
+                        .map(|&other| Scored(matrix.row_similarity(neighbour as usize, other as usize), other))
+                        .collect();
+                    scored.sort_unstable_by(|a, b| b.cmp(a));
+                    *links = scored.into_iter().take(max_links).map(|s| s.1).collect();
+                }
+            }
+            self.links[node as usize][layer] = neighbours;
+        }
+        if level > top {
+            self.entry = Some(node);
+        }
+    }
+
+    /// Greedy beam search on one layer; the `ef` best nodes found, most similar first
+    fn search_layer(&self, similarity: &impl Fn(u32) -> f32, start: Scored, ef: usize, layer: usize) -> Vec<Scored> {
+        let mut visited = HashSet::from([start.1]);
+        let mut candidates = BinaryHeap::from([start]);
+        let mut best = BinaryHeap::from([Reverse(start)]);
+
+        while let Some(current) = candidates.pop() {
+            let worst = best.peek().map(|r| r.0 .0).unwrap_or(f32::MIN);
+            if current.0 < worst && best.len() >= ef {
+                break;
+            }
+            for &neighbour in self.links[current.1 as usize].get(layer).into_iter().flatten() {
+                if !visited.insert(neighbour) {
+                    continue;
+                }
+                let scored = Scored(similarity(neighbour), neighbour);
+                let worst = best.peek().map(|r| r.0 .0).unwrap_or(f32::MIN);
+                if best.len() < ef || scored.0 > worst {
+                    candidates.push(scored);
+                    best.push(Reverse(scored));
+                    if best.len() > ef {
+                        best.pop();
+                    }
+                }
+            }
+        }
+
+        let mut found: Vec<Scored> = best.into_iter().map(|r| r.0).collect();
+        found.sort_unstable_by(|a, b| b.cmp(a));
+        found
+    }
+
+    /// Layer for a new node: floor(-ln(U) / ln(M))
+    fn random_level(&mut self) -> usize {
+        self.rng = self.rng.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        let uniform = ((z >> 11) as f64 + 1.0) / (1u64 << 53) as f64;
+        (-uniform.ln() * self.level_mult) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::documents::embeddings::{generate_embedding, EMBEDDING_DIM};
+
+    #[test]
+    fn test_hnsw_recall_against_brute_force() {
+        let topics = ["pricing", "supply", "regulation", "margins", "churn", "capex", "hiring", "tariffs"];
+        let mut matrix = EmbeddingMatrix::new(EMBEDDING_DIM);
+        let mut graph = Hnsw::default();
+        for i in 0..300 {
+            let text = format!(
+                "{} {} outlook note {} quarter {}",
+                topics[i % topics.len()],
+                topics[(i / 7) % topics.len()],
+                i % 13,
+                i % 5
+            );
+            matrix.push(&generate_embedding(&text).unwrap());
+            // Extend in two steps to exercise incremental insertion
+            if i == 199 {
+                graph.extend(&matrix);
+            }
+        }
+        graph.extend(&matrix);
+
+        let query = generate_embedding("supply tariffs outlook quarter").unwrap();
+        let mut exact: Vec<(usize, f32)> = matrix.similarities(&query, 0..matrix.len()).into_iter().enumerate().collect();
+        exact.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let approx = graph.search(&matrix, &query, 10);
+        assert_eq!(approx.len(), 10);
+        assert!((approx[0].1 - exact[0].1).abs() < 1e-5);
+        assert!(approx.windows(2).all(|w| w[0].1 >= w[1].1));
+
+        // Ties make row identity ambiguous, so compare the scores reached
+        let cutoff = exact[9].1 - 1e-5;
+        let hits = approx.iter().filter(|(_, score)| *score >= cutoff).count();
+        assert!(hits >= 9, "recall too low: {hits}/10");
+    }
+}
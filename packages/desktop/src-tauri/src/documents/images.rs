@@ -0,0 +1,161 @@
+//! Image References
+//!
+//! `.png`/`.jpg` references: the image is copied into the session's
+//! `references/` directory, its text is extracted by the configured
+//! `ocrProvider` (the `tesseract` CLI or an HTTP endpoint), and that text is
+//! chunked and indexed like any other document with `source_kind: image`.
+//! Without a provider, or if extraction fails, the image is indexed by
+//! filename alone. Search results from image documents carry the stored
+//! image path so the UI can preview it.
+
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::chunker::{chunk_content, ChunkedDocument, ChunkerError};
+use super::web::new_reference_dir;
+use crate::config::preferences::{load_preferences, OcrProvider, Preferences};
+use crate::session::get_session_dir_cli;
+
+/// Extensions added as image references
+pub const IMAGE_EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+/// Larger images are rejected rather than sent to OCR
+const MAX_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+const OCR_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Whether `path` has an image extension
+pub fn is_image(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.as_str()))
+}
+
+/// Copy an image into the session, extract its text and chunk it
+pub async fn chunk_image(session_id: &str, path: &Path, doc_id: &str) -> Result<ChunkedDocument, ChunkerError> {
+    let size = fs::metadata(path)?.len();
+    if size > MAX_IMAGE_BYTES {
+        return Err(ChunkerError::FileTooLarge(size, MAX_IMAGE_BYTES));
+    }
+    let filename = path
+        .file_name()
+        .ok_or_else(|| ChunkerError::UnsupportedType(path.to_string_lossy().to_string()))?;
+    let session_dir = get_session_dir_cli(session_id).map_err(|e| ChunkerError::Io(std::io::Error::other(e.to_string())))?;
+    let stored = new_reference_dir(&session_dir)?.join(filename);
+    fs::copy(path, &stored)?;
+
+    let prefs = load_preferences();
+    let text = match extract_text(&stored, &prefs).await {
+        Ok(text) => text,
+        Err(e) => {
+            warn!(path = %stored.display(), error = %e, "OCR failed; indexing image by filename only");
+            None
+        }
+    };
+    info!(path = %stored.display(), ocr = text.is_some(), "Stored image reference");
+    Ok(image_document(&stored, doc_id, text.as_deref()))
+}
+
+/// Chunk an image's extracted text, headed by its filename so the image
+/// is findable even without OCR
+fn image_document(stored: &Path, doc_id: &str, text: Option<&str>) -> ChunkedDocument {
+    let filename = stored.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let content = match text.map(str::trim).filter(|t| !t.is_empty()) {
+        Some(text) => format!("Image: {}\n\n{}\n", filename, text),
+        None => format!("Image: {}\n", filename),
+    };
+    chunk_content(&content, "txt", stored, doc_id)
+}
+
+/// Text in the image per the configured provider; `None` when OCR is disabled
+async fn extract_text(path: &Path, prefs: &Preferences) -> Result<Option<String>, String> {
+    match prefs.ocr_provider {
+        OcrProvider::Disabled => Ok(None),
+        OcrProvider::Tesseract => tesseract(path).await.map(Some),
+        OcrProvider::Api => {
+            let url = prefs.ocr_url.as_deref().ok_or("ocrUrl is not set")?;
+            ocr_api(url, path).await.map(Some)
+        }
+    }
+}
+
+async fn tesseract(path: &Path) -> Result<String, String> {
+    let (binary, _) = crate::cli_tool::locate("tesseract").ok_or("tesseract is not installed")?;
+    let output = tokio::time::timeout(
+        OCR_TIMEOUT,
+        tokio::process::Command::new(binary).arg(path).arg("stdout").output(),
+    )
+    .await
+    .map_err(|_| "tesseract timed out".to_string())?
+    .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// POST the image bytes; the reply is `{"text": ...}` or plain text
+async fn ocr_api(url: &str, path: &Path) -> Result<String, String> {
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    let content_type = match path.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() {
+        Some("png") => "image/png",
+        _ => "image/jpeg",
+    };
+    let client = reqwest::Client::builder().timeout(OCR_TIMEOUT).build().map_err(|e| e.to_string())?;
+    let response = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, content_type)
+        .body(bytes)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("OCR endpoint returned {}", response.status()));
+    }
+    let body = response.text().await.map_err(|e| e.to_string())?;
+    Ok(text_from_response(&body))
+}
+
+fn text_from_response(body: &str) -> String {
+    serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|v| v.get("text").and_then(|t| t.as_str()).map(str::to_string))
+        .unwrap_or_else(|| body.to_string())
+}
+
+/// Stored image path for a document path, if it is an image
+pub fn image_path(document_path: &str) -> Option<String> {
+    is_image(document_path).then(|| document_path.to_string())
+}
+
+/// Stored image path recorded in Chroma chunk metadata
+pub fn image_path_from_metadata(meta: &Value) -> Option<String> {
+    (meta.get("source_kind").and_then(|v| v.as_str()) == Some("image"))
+        .then(|| meta.get("image_path").and_then(|v| v.as_str()).map(str::to_string))
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_documents_and_ocr_responses() {
+        assert!(is_image("/tmp/Diagram.PNG") && is_image("a.jpeg"));
+        assert!(!is_image("notes.md"));
+
+        let stored = Path::new("/tmp/refs/market-map.png");
+        let doc = image_document(stored, "doc1", Some("  TAM $4B\nSAM $1B  "));
+        assert_eq!(doc.chunks.len(), 1);
+        assert_eq!(doc.chunks[0].content, "Image: market-map.png\n\nTAM $4B\nSAM $1B\n");
+        assert_eq!(doc.path, "/tmp/refs/market-map.png");
+        assert_eq!(image_document(stored, "doc1", None).chunks[0].content, "Image: market-map.png\n");
+
+        assert_eq!(text_from_response(r#"{"text": "hello"}"#), "hello");
+        assert_eq!(text_from_response("plain words"), "plain words");
+        let meta = serde_json::json!({ "source_kind": "image", "image_path": "/tmp/a.png" });
+        assert_eq!(image_path_from_metadata(&meta).as_deref(), Some("/tmp/a.png"));
+    }
+}
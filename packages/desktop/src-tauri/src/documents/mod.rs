@@ -2,9 +2,19 @@
 //!
 //! Handles document chunking, embedding, and retrieval for reference materials.
 
+pub mod bibtex;
 pub mod chunker;
 pub mod embeddings;
+pub mod fallback_index;
+pub mod hnsw;
+pub mod images;
+pub mod permanent;
+pub mod registry;
 pub mod retriever;
+pub mod sentences;
+pub mod snippets;
+pub mod structured;
+pub mod web;
 
 // Re-export key public types
 pub use chunker::{
@@ -0,0 +1,237 @@
+//! Permanent Documents
+//!
+//! Documents added with `DocumentPersistence::Permanent` are linked to the
+//! session's thesis and outlive the session. Sessions forked from it search
+//! them alongside their own references, and when the session is deleted
+//! they are copied into `<app data>/permanent_documents/` (a fallback index
+//! plus `origins.json`) so its descendants keep finding them. Restoring the
+//! session from trash takes its copies back out.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use super::bibtex::Citation;
+use super::chunker::DocumentPersistence;
+use super::fallback_index::{self, StoredDocument};
+use super::registry;
+use super::retriever;
+use crate::session::{get_app_data_dir_cli, load_session_cli};
+
+const STORE_DIR: &str = "permanent_documents";
+const ORIGINS_FILE: &str = "origins.json";
+/// Document store key and registry holder of the preserved documents
+pub const PERMANENT_STORE_KEY: &str = "@permanent";
+
+/// A permanent document preserved from a deleted session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermanentDocument {
+    pub doc_id: String,
+    pub filename: String,
+    pub path: String,
+    pub chunk_count: u32,
+    pub total_tokens: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub citation: Option<Citation>,
+    pub origin_session_id: String,
+    pub origin_title: String,
+    /// Parent of the origin session, so lineage survives deleted links
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_session_id: Option<String>,
+    pub preserved_at: DateTime<Utc>,
+}
+
+/// Directory of the preserved documents' index
+pub fn store_dir() -> Option<PathBuf> {
+    get_app_data_dir_cli().ok().map(|dir| dir.join(STORE_DIR))
+}
+
+/// Preserved documents, oldest first
+pub fn list() -> Vec<PermanentDocument> {
+    store_dir().map(|dir| read_origins(&dir)).unwrap_or_default()
+}
+
+/// Ancestors of a session, nearest first. Deleted ancestors are followed
+/// through the parents recorded with their preserved documents.
+pub fn lineage(session_id: &str) -> Vec<String> {
+    let preserved = list();
+    let parent_of = |id: &str| match load_session_cli(id) {
+        Ok(session) => session.parent_session_id,
+        Err(_) => preserved.iter().find(|d| d.origin_session_id == id).and_then(|d| d.parent_session_id.clone()),
+    };
+
+    let mut ancestors = Vec::new();
+    let mut seen = HashSet::from([session_id.to_string()]);
+    let mut current = parent_of(session_id);
+    while let Some(id) = current.filter(|id| seen.insert(id.clone())) {
+        current = parent_of(&id);
+        ancestors.push(id);
+    }
+    ancestors
+}
+
+/// Copy a session's permanent documents into the preserved store ahead of
+/// deleting it; returns how many were copied
+pub fn preserve_session(session_id: &str) -> io::Result<usize> {
+    let Some(dir) = store_dir() else { return Ok(0) };
+    let session = load_session_cli(session_id).map_err(|e| io::Error::other(e.to_string()))?;
+    let Some(index_dir) = fallback_index::session_index_dir(session_id) else { return Ok(0) };
+    let mut documents: Vec<StoredDocument> = fallback_index::load(&index_dir, registry::lookup)?
+        .into_iter()
+        .filter(|stored| stored.persistence == DocumentPersistence::Permanent)
+        .collect();
+    if documents.is_empty() {
+        return Ok(0);
+    }
+    for stored in &mut documents {
+        // Hold registered content so purging the session doesn't drop it
+        if let Err(e) = registry::acquire(PERMANENT_STORE_KEY, &stored.document.id, stored) {
+            warn!(doc_id = %stored.document.id, error = %e, "Failed to register permanent document");
+            stored.content_hash = None;
+        }
+    }
+
+    let count = documents.len();
+    preserve_in(&dir, &session.id, &session.title, session.parent_session_id.as_deref(), documents, Utc::now())?;
+    retriever::forget_session(PERMANENT_STORE_KEY);
+    info!(session_id = %session_id, count, "Preserved permanent documents");
+    Ok(count)
+}
+
+/// Take a restored session's documents back out of the preserved store
+pub fn restore_session(session_id: &str) -> io::Result<()> {
+    let Some(dir) = store_dir() else { return Ok(()) };
+    let removed = remove_origin_in(&dir, session_id)?;
+    for (doc_id, hash) in &removed {
+        if let Some(hash) = hash {
+            registry::release(PERMANENT_STORE_KEY, doc_id, hash)?;
+        }
+    }
+    if !removed.is_empty() {
+        retriever::forget_session(PERMANENT_STORE_KEY);
+    }
+    retriever::forget_session(session_id);
+    Ok(())
+}
+
+fn preserve_in(
+    dir: &Path,
+    origin_session_id: &str,
+    origin_title: &str,
+    parent_session_id: Option<&str>,
+    documents: Vec<StoredDocument>,
+    now: DateTime<Utc>,
+) -> io::Result<()> {
+    let mut origins = read_origins(dir);
+    let mut stored = fallback_index::load(dir, registry::lookup)?;
+    for document in documents {
+        origins.retain(|o| o.doc_id != document.document.id);
+        stored.retain(|s| s.document.id != document.document.id);
+        origins.push(PermanentDocument {
+            doc_id: document.document.id.clone(),
+            filename: document.document.filename.clone(),
+            path: document.document.path.clone(),
+            chunk_count: document.document.chunks.len() as u32,
+            total_tokens: document.document.total_tokens,
+            citation: document.citation.clone(),
+            origin_session_id: origin_session_id.to_string(),
+            origin_title: origin_title.to_string(),
+            parent_session_id: parent_session_id.map(str::to_string),
+            preserved_at: now,
+        });
+        stored.push(document);
+    }
+    fallback_index::save(dir, &stored)?;
+    write_origins(dir, &origins)
+}
+
+/// Remove the documents preserved from `origin_session_id`; returns their
+/// ids and registry content hashes
+fn remove_origin_in(dir: &Path, origin_session_id: &str) -> io::Result<Vec<(String, Option<String>)>> {
+    let mut origins = read_origins(dir);
+    let ids: HashSet<String> = origins
+        .iter()
+        .filter(|o| o.origin_session_id == origin_session_id)
+        .map(|o| o.doc_id.clone())
+        .collect();
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (removed, kept): (Vec<StoredDocument>, Vec<StoredDocument>) =
+        fallback_index::load(dir, registry::lookup)?.into_iter().partition(|s| ids.contains(&s.document.id));
+    origins.retain(|o| !ids.contains(&o.doc_id));
+    fallback_index::save(dir, &kept)?;
+    write_origins(dir, &origins)?;
+    Ok(removed.into_iter().map(|s| (s.document.id, s.content_hash)).collect())
+}
+
+fn read_origins(dir: &Path) -> Vec<PermanentDocument> {
+    match fs::read_to_string(dir.join(ORIGINS_FILE)) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!(error = %e, "Unreadable permanent document origins");
+            Vec::new()
+        }),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn write_origins(dir: &Path, origins: &[PermanentDocument]) -> io::Result<()> {
+    if origins.is_empty() {
+        return fallback_index::remove(dir);
+    }
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join(ORIGINS_FILE), serde_json::to_string_pretty(origins)?)
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn list_permanent_documents() -> Vec<PermanentDocument> {
+    list()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::documents::chunker::chunk_content;
+    use crate::documents::embeddings::generate_embedding;
+    use std::collections::BTreeSet;
+
+    fn permanent(id: &str, text: &str) -> StoredDocument {
+        let document = chunk_content(text, "md", Path::new("/tmp/thesis.md"), id);
+        StoredDocument {
+            chunk_embeddings: document.chunks.iter().map(|c| (c.index, generate_embedding(&c.content).unwrap())).collect(),
+            document,
+            persistence: DocumentPersistence::Permanent,
+            citation: None,
+            pinned_chunks: BTreeSet::new(),
+            max_token_share: None,
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_preserve_and_restore() {
+        let dir = std::env::temp_dir().join(format!("dialectic_permanent_{}", ulid::Ulid::new()));
+        preserve_in(&dir, "s1", "Pricing", None, vec![permanent("d1", "Pricing power holds.")], Utc::now()).unwrap();
+        preserve_in(&dir, "s2", "Fork", Some("s1"), vec![permanent("d2", "Supply is tight.")], Utc::now()).unwrap();
+
+        let origins = read_origins(&dir);
+        assert_eq!(origins.len(), 2);
+        assert_eq!(origins[1].parent_session_id.as_deref(), Some("s1"));
+        assert_eq!(fallback_index::load(&dir, |_| None).unwrap().len(), 2);
+
+        let removed = remove_origin_in(&dir, "s1").unwrap();
+        assert_eq!(removed, vec![("d1".to_string(), None)]);
+        let ids: Vec<String> = fallback_index::load(&dir, |_| None).unwrap().into_iter().map(|s| s.document.id).collect();
+        assert_eq!(ids, vec!["d2"]);
+
+        remove_origin_in(&dir, "s2").unwrap();
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,192 @@
+//! Cross-Session Document Registry
+//!
+//! Reference files added to more than one session are chunked and embedded
+//! once. The registry lives in `<app data>/document_registry/`: one
+//! fallback-index directory per content hash holding the chunks and
+//! embeddings, plus `registry.json` listing which `session/doc` pairs hold
+//! each entry. Session indexes store a `RegisteredDocument` in place of the
+//! chunks, and an entry is deleted when its last holder releases it.
+//!
+//! Only persisted text documents are registered. Ephemeral documents never
+//! reach disk, and image references are copied into their session's own
+//! `references/` directory. Chroma chunks stay per session since their
+//! metadata is session-scoped.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::chunker::ChunkedDocument;
+use super::embeddings::Embedding;
+use super::fallback_index::{self, StoredDocument};
+use crate::session::get_app_data_dir_cli;
+
+const REGISTRY_DIR: &str = "document_registry";
+const REGISTRY_FILE: &str = "registry.json";
+
+/// Serializes read-modify-write cycles of `registry.json`
+static REGISTRY_LOCK: Mutex<()> = Mutex::new(());
+
+/// Session-specific identity of a registered document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredDocument {
+    pub id: String,
+    pub filename: String,
+    pub path: String,
+    pub content_hash: String,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct RegistryFile {
+    /// Content hash → `session_id/doc_id` holders
+    entries: BTreeMap<String, BTreeSet<String>>,
+}
+
+/// Hash of a file's extension and bytes; the extension is included since it
+/// decides how the content is chunked
+pub fn content_hash(path: &Path) -> io::Result<String> {
+    let bytes = fs::read(path)?;
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let hash = |seed: u64| {
+        extension
+            .as_bytes()
+            .iter()
+            .chain(&[0])
+            .chain(&bytes)
+            .fold(seed, |hash, b| (hash ^ *b as u64).wrapping_mul(0x100000001b3))
+    };
+    Ok(format!("{:016x}{:016x}{:08x}", hash(0xcbf29ce484222325), hash(0x84222325cbf29ce4), bytes.len()))
+}
+
+fn registry_dir() -> Option<PathBuf> {
+    get_app_data_dir_cli().ok().map(|dir| dir.join(REGISTRY_DIR))
+}
+
+fn holder(session_id: &str, doc_id: &str) -> String {
+    format!("{}/{}", session_id, doc_id)
+}
+
+/// Chunks and embeddings of a registered document
+pub fn lookup(hash: &str) -> Option<(ChunkedDocument, Vec<(u32, Embedding)>)> {
+    lookup_in(&registry_dir()?, hash)
+}
+
+fn lookup_in(root: &Path, hash: &str) -> Option<(ChunkedDocument, Vec<(u32, Embedding)>)> {
+    let stored = fallback_index::load(&root.join(hash), |_| None).ok()?.into_iter().next()?;
+    Some((stored.document, stored.chunk_embeddings))
+}
+
+/// Record `doc_id` in `session_id` as a holder of `stored`'s content,
+/// writing the entry if it's new
+pub fn acquire(session_id: &str, doc_id: &str, stored: &StoredDocument) -> io::Result<()> {
+    let root = registry_dir().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No app data directory"))?;
+    acquire_in(&root, &holder(session_id, doc_id), stored)
+}
+
+fn acquire_in(root: &Path, holder: &str, stored: &StoredDocument) -> io::Result<()> {
+    let Some(hash) = stored.content_hash.as_deref() else { return Ok(()) };
+    let _guard = REGISTRY_LOCK.lock();
+    let mut registry = read_registry(root)?;
+    if !root.join(hash).exists() {
+        let entry = StoredDocument { content_hash: None, ..stored.clone() };
+        fallback_index::save(&root.join(hash), [&entry])?;
+    }
+    registry.entries.entry(hash.to_string()).or_default().insert(holder.to_string());
+    write_registry(root, &registry)
+}
+
+/// Drop `doc_id`'s hold on `hash`, deleting the entry if it was the last
+pub fn release(session_id: &str, doc_id: &str, hash: &str) -> io::Result<()> {
+    let Some(root) = registry_dir() else { return Ok(()) };
+    let holder = holder(session_id, doc_id);
+    release_where(&root, |entry_hash, h| entry_hash == hash && *h == holder)
+}
+
+/// Drop every hold a session has; `app_data` is the app data directory
+pub fn release_session_in(app_data: &Path, session_id: &str) -> io::Result<()> {
+    let prefix = holder(session_id, "");
+    release_where(&app_data.join(REGISTRY_DIR), |_, h| h.starts_with(&prefix))
+}
+
+fn release_where(root: &Path, matches: impl Fn(&str, &String) -> bool) -> io::Result<()> {
+    let _guard = REGISTRY_LOCK.lock();
+    let mut registry = read_registry(root)?;
+    let mut unused = Vec::new();
+    let mut changed = false;
+    for (hash, holders) in registry.entries.iter_mut() {
+        let before = holders.len();
+        holders.retain(|h| !matches(hash, h));
+        changed |= holders.len() != before;
+        if holders.is_empty() {
+            unused.push(hash.clone());
+        }
+    }
+    for hash in unused {
+        registry.entries.remove(&hash);
+        fallback_index::remove(&root.join(&hash))?;
+    }
+    if !changed {
+        return Ok(());
+    }
+    write_registry(root, &registry)
+}
+
+fn read_registry(root: &Path) -> io::Result<RegistryFile> {
+    match fs::read_to_string(root.join(REGISTRY_FILE)) {
+        Ok(content) => Ok(serde_json::from_str(&content)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(RegistryFile::default()),
+        Err(e) => Err(e),
+    }
+}
+
+fn write_registry(root: &Path, registry: &RegistryFile) -> io::Result<()> {
+    fs::create_dir_all(root)?;
+    let tmp = root.join(REGISTRY_FILE).with_extension("tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(registry)?)?;
+    fs::rename(&tmp, root.join(REGISTRY_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::documents::chunker::{chunk_content, DocumentPersistence};
+    use crate::documents::embeddings::generate_embedding;
+
+    #[test]
+    fn test_entries_are_shared_and_released() {
+        let root = std::env::temp_dir().join(format!("dialectic_registry_{}", ulid::Ulid::new()));
+        let file = root.with_extension("md");
+        fs::write(&file, "# Notes\n\nMargins compress as supply tightens.\n").unwrap();
+        let hash = content_hash(&file).unwrap();
+        assert_ne!(hash, content_hash(&root.with_extension("txt")).unwrap_or_default());
+
+        let document = chunk_content(&fs::read_to_string(&file).unwrap(), "md", &file, "doc1");
+        let stored = StoredDocument {
+            chunk_embeddings: document.chunks.iter().map(|c| (c.index, generate_embedding(&c.content).unwrap())).collect(),
+            document,
+            persistence: DocumentPersistence::Cached,
+            citation: None,
+            pinned_chunks: BTreeSet::new(),
+            max_token_share: None,
+            content_hash: Some(hash.clone()),
+        };
+        acquire_in(&root, "s1/doc1", &stored).unwrap();
+        acquire_in(&root, "s2/doc2", &stored).unwrap();
+        let (document, embeddings) = lookup_in(&root, &hash).unwrap();
+        assert_eq!(document.chunks.len(), stored.document.chunks.len());
+        assert_eq!(embeddings, stored.chunk_embeddings);
+
+        release_where(&root, |_, h| h.starts_with("s1/")).unwrap();
+        assert!(lookup_in(&root, &hash).is_some());
+        release_where(&root, |_, h| h == "s2/doc2").unwrap();
+        assert!(lookup_in(&root, &hash).is_none());
+        assert!(read_registry(&root).unwrap().entries.is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_file(&file);
+    }
+}
@@ -2,29 +2,48 @@
 //!
 //! Retrieves relevant chunks from documents based on query.
 //! Uses Chroma for semantic search when available, falling back
-//! to in-memory feature-hash search when Chroma is offline.
+//! to feature-hash search when Chroma is offline. Fallback embeddings are
+//! persisted per session (see `fallback_index`) and loaded back on first use.
 
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 use tracing::{info, warn, debug};
 use ulid::Ulid;
 
-use super::chunker::{chunk_document, ChunkedDocument, DocumentHandling, DocumentPersistence, ChunkerError, Chunk};
-use super::embeddings::{generate_embedding, cache_embedding, cosine_similarity, Embedding};
+use super::chunker::{chunk_document, ChunkedDocument, DocumentHandling, DocumentPersistence, DocumentSection, ChunkerError, Chunk};
+use super::embeddings::{generate_embedding, cache_embedding, EmbeddingMatrix, EMBEDDING_DIM};
+use super::bibtex::Citation;
+use super::fallback_index::{self, StoredDocument};
+use super::images;
+use super::permanent::{self, PERMANENT_STORE_KEY};
+use super::registry;
+use super::hnsw::Hnsw;
+use super::snippets::{extract_snippet, Snippet};
+use crate::session::mode_policy::document_persistence;
+use crate::session::tags::session_tags;
 use crate::session::validate_session_id;
-use crate::chroma::client::{get_client, ChromaError};
+use crate::context::tokens::estimate_tokens_quick;
+use crate::chroma::client::{get_client, ChromaError, ChromaUpsertItem};
+use crate::chroma::store::VectorStore;
+use crate::cancellation::cancellable;
+use crate::events::{IndexOperation, ProgressReporter};
+use crate::jobs::{self, JobKind};
 use crate::chroma::collections::{
-    COLLECTION_DOCUMENTS, chunk_id, document_chunk_metadata, session_filter, document_filter,
+    COLLECTION_DOCUMENTS, add_tag_fields, chunk_id, collection_metadata, document_chunk_metadata, session_filter, document_filter,
 };
 
 /// Global document store (in-memory fallback + metadata tracking)
 static DOCUMENT_STORE: RwLock<Option<DocumentStore>> = RwLock::new(None);
 
+/// Chunks in a session before fallback search switches from exact brute
+/// force to the HNSW graph
+const HNSW_MIN_ROWS: usize = 2_000;
+
 #[derive(Error, Debug)]
 pub enum RetrieverError {
     #[error("Document not found: {0}")]
@@ -39,6 +58,10 @@ pub enum RetrieverError {
     EmbeddingFailed(String),
     #[error("Chroma error: {0}")]
     ChromaError(String),
+    #[error("Search cancelled")]
+    Cancelled,
+    #[error("Token share must be in (0, 1], got {0}")]
+    InvalidShare(f32),
 }
 
 impl Serialize for RetrieverError {
@@ -66,14 +89,101 @@ struct DocumentStore {
 #[derive(Default)]
 struct SessionDocuments {
     documents: HashMap<String, StoredDocument>,
+    /// Fallback embeddings packed for scoring; extended as documents are
+    /// added, rebuilt after any are removed
+    index: Option<SessionIndex>,
 }
 
-/// Stored document with chunks and optional local embeddings (fallback)
-struct StoredDocument {
-    document: ChunkedDocument,
-    persistence: DocumentPersistence,
-    /// Fallback embeddings for when Chroma is offline
-    chunk_embeddings: Vec<(u32, Embedding)>,
+/// Every chunk embedding of a session in one matrix, grouped by document
+struct SessionIndex {
+    matrix: EmbeddingMatrix,
+    /// Chunk index of each matrix row
+    chunk_indices: Vec<u32>,
+    /// Position in `doc_ids` of each matrix row's document
+    row_docs: Vec<u32>,
+    doc_ids: Vec<String>,
+    /// Rows of each document
+    rows: HashMap<String, std::ops::Range<usize>>,
+    /// ANN graph over `matrix` once it reaches `HNSW_MIN_ROWS`
+    graph: Option<Hnsw>,
+}
+
+impl SessionIndex {
+    fn build(documents: &HashMap<String, StoredDocument>) -> Self {
+        let mut index = SessionIndex {
+            matrix: EmbeddingMatrix::new(EMBEDDING_DIM),
+            chunk_indices: Vec::new(),
+            row_docs: Vec::new(),
+            doc_ids: Vec::new(),
+            rows: HashMap::new(),
+            graph: None,
+        };
+        for (doc_id, stored) in documents {
+            index.push_rows(doc_id, stored);
+        }
+        index.update_graph();
+        index
+    }
+
+    /// Add a document's rows, inserting them into the graph if there is one
+    fn append(&mut self, doc_id: &str, stored: &StoredDocument) {
+        self.push_rows(doc_id, stored);
+        self.update_graph();
+    }
+
+    fn push_rows(&mut self, doc_id: &str, stored: &StoredDocument) {
+        let start = self.matrix.len();
+        let doc = self.doc_ids.len() as u32;
+        for (chunk_index, embedding) in &stored.chunk_embeddings {
+            if self.matrix.push(embedding).is_some() {
+                self.chunk_indices.push(*chunk_index);
+                self.row_docs.push(doc);
+            }
+        }
+        self.doc_ids.push(doc_id.to_string());
+        self.rows.insert(doc_id.to_string(), start..self.matrix.len());
+    }
+
+    fn update_graph(&mut self) {
+        match self.graph.as_mut() {
+            Some(graph) => graph.extend(&self.matrix),
+            None if self.matrix.len() >= HNSW_MIN_ROWS => self.graph = Some(Hnsw::build(&self.matrix)),
+            None => {}
+        }
+    }
+
+    /// `(doc_id, chunk_index, score)` of chunks that score above zero, best
+    /// first; approximate and capped at `limit` once the graph is built
+    fn score_all(&self, query: &[f32], limit: usize) -> Vec<(&str, u32, f32)> {
+        let scored: Vec<(usize, f32)> = match &self.graph {
+            Some(graph) => graph.search(&self.matrix, query, limit),
+            None => {
+                let mut scored: Vec<(usize, f32)> = self.matrix
+                    .similarities(query, 0..self.matrix.len())
+                    .into_iter()
+                    .enumerate()
+                    .collect();
+                scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+                scored
+            }
+        };
+        scored.into_iter()
+            .filter(|(_, score)| *score > 0.0)
+            .map(|(row, score)| (self.doc_ids[self.row_docs[row] as usize].as_str(), self.chunk_indices[row], score))
+            .collect()
+    }
+
+    /// `(chunk_index, score)` of a document's chunks that score above zero
+    fn score_document(&self, doc_id: &str, query: &[f32]) -> Vec<(u32, f32)> {
+        let Some(rows) = self.rows.get(doc_id) else { return Vec::new() };
+        self.matrix
+            .similarities(query, rows.clone())
+            .into_iter()
+            .zip(&self.chunk_indices[rows.clone()])
+            .filter(|(score, _)| *score > 0.0)
+            .map(|(score, chunk_index)| (*chunk_index, score))
+            .collect()
+    }
 }
 
 /// Reference document metadata
@@ -88,6 +198,12 @@ pub struct ReferenceDocument {
     pub handling: DocumentHandling,
     pub persistence: DocumentPersistence,
     pub chunk_count: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub citation: Option<Citation>,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub pinned_chunks: BTreeSet<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_token_share: Option<f32>,
 }
 
 /// Search result
@@ -100,6 +216,18 @@ pub struct SearchResult {
     pub section: Option<String>,
     pub score: f32,
     pub token_count: u32,
+    /// Best-matching excerpt of `content` with query highlights
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<Snippet>,
+    /// Citation of the document the chunk belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub citation: Option<Citation>,
+    /// Stored image, for chunks of text extracted from an image reference
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_path: Option<String>,
+    /// Always-loaded chunk; its score is 0 unless the query also matched it
+    #[serde(default)]
+    pub pinned: bool,
 }
 
 /// Initialize the document store
@@ -110,12 +238,164 @@ fn ensure_initialized() {
     }
 }
 
+/// Load a session's persisted fallback index into the store the first time
+/// the session is touched in this process
+fn ensure_session_loaded(session_id: &str) {
+    ensure_initialized();
+    if DOCUMENT_STORE.read().as_ref().is_some_and(|s| s.sessions.contains_key(session_id)) {
+        return;
+    }
+
+    let documents = match index_dir(session_id).map(|dir| fallback_index::load(&dir, registry::lookup)) {
+        Some(Ok(documents)) => documents,
+        Some(Err(e)) => {
+            warn!(session_id = %session_id, error = %e, "Failed to load fallback document index");
+            Vec::new()
+        }
+        None => Vec::new(),
+    };
+    if !documents.is_empty() {
+        debug!(session_id = %session_id, count = documents.len(), "Loaded fallback document index");
+    }
+
+    let mut store = DOCUMENT_STORE.write();
+    if let Some(store) = store.as_mut() {
+        store.sessions.entry(session_id.to_string()).or_insert_with(|| SessionDocuments {
+            documents: documents.into_iter().map(|stored| (stored.document.id.clone(), stored)).collect(),
+            index: None,
+        });
+    }
+}
+
+/// Fallback index directory behind a store key
+fn index_dir(session_id: &str) -> Option<PathBuf> {
+    if session_id == PERMANENT_STORE_KEY {
+        permanent::store_dir()
+    } else {
+        fallback_index::session_index_dir(session_id)
+    }
+}
+
+/// Drop a session's documents from memory so the next use reloads them
+/// from disk
+pub fn forget_session(session_id: &str) {
+    if let Some(store) = DOCUMENT_STORE.write().as_mut() {
+        store.sessions.remove(session_id);
+    }
+}
+
+/// Permanent documents a session sees through its lineage, as store key
+/// and document ids: each live ancestor's own, plus those preserved from
+/// deleted ancestors
+fn lineage_documents(session_id: &str) -> Vec<(String, HashSet<String>)> {
+    let ancestors = permanent::lineage(session_id);
+    if ancestors.is_empty() {
+        return Vec::new();
+    }
+    let preserved: HashSet<String> = permanent::list()
+        .into_iter()
+        .filter(|d| ancestors.contains(&d.origin_session_id))
+        .map(|d| d.doc_id)
+        .collect();
+
+    let mut sources = Vec::new();
+    for key in ancestors.iter().map(String::as_str).chain((!preserved.is_empty()).then_some(PERMANENT_STORE_KEY)) {
+        ensure_session_index(key);
+        let store = DOCUMENT_STORE.read();
+        let Some(session) = store.as_ref().and_then(|s| s.sessions.get(key)) else { continue };
+        let ids: HashSet<String> = session.documents.iter()
+            .filter(|(doc_id, stored)| match key {
+                PERMANENT_STORE_KEY => preserved.contains(*doc_id),
+                _ => stored.persistence == DocumentPersistence::Permanent,
+            })
+            .map(|(doc_id, _)| doc_id.clone())
+            .collect();
+        if !ids.is_empty() {
+            sources.push((key.to_string(), ids));
+        }
+    }
+    sources
+}
+
+/// Local results from the permanent documents of a session's lineage,
+/// scored by cosine similarity
+fn search_lineage_local(session_id: &str, query: &str, top_k: usize) -> Vec<SearchResult> {
+    let sources = lineage_documents(session_id);
+    if sources.is_empty() {
+        return Vec::new();
+    }
+    let Ok(query_embedding) = generate_embedding(query) else { return Vec::new() };
+    let store = DOCUMENT_STORE.read();
+    let Some(store) = store.as_ref() else { return Vec::new() };
+
+    let mut results = Vec::new();
+    for (key, doc_ids) in &sources {
+        let Some(session) = store.sessions.get(key) else { continue };
+        let Some(index) = session.index.as_ref() else { continue };
+        results.extend(index.score_all(&query_embedding, top_k * 4).into_iter()
+            .filter(|(doc_id, _, _)| doc_ids.contains(*doc_id))
+            .take(top_k * 2)
+            .filter_map(|(doc_id, chunk_index, score)| {
+                let stored = session.documents.get(doc_id)?;
+                let chunk = stored.document.chunks.get(chunk_index as usize)?;
+                Some(SearchResult {
+                    doc_id: doc_id.to_string(),
+                    chunk_index,
+                    content: chunk.content.clone(),
+                    section: chunk.section.clone(),
+                    score,
+                    token_count: chunk.token_count,
+                    snippet: None,
+                    citation: stored.citation.clone(),
+                    image_path: images::image_path(&stored.document.path),
+                    pinned: false,
+                })
+            }));
+    }
+    results
+}
+
+/// Merge lineage results into a session's candidates, best first
+fn with_lineage(mut results: Vec<SearchResult>, lineage: Vec<SearchResult>) -> Vec<SearchResult> {
+    if lineage.is_empty() {
+        return results;
+    }
+    results.extend(lineage);
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    results
+}
+
+/// Load a session and pack its fallback embeddings if they changed since
+/// the last search
+fn ensure_session_index(session_id: &str) {
+    ensure_session_loaded(session_id);
+    let mut store = DOCUMENT_STORE.write();
+    if let Some(session) = store.as_mut().and_then(|s| s.sessions.get_mut(session_id)) {
+        if session.index.is_none() {
+            session.index = Some(SessionIndex::build(&session.documents));
+        }
+    }
+}
+
+/// Rewrite a session's fallback index from the store
+fn persist_session(store: &DocumentStore, session_id: &str) {
+    let Some(dir) = index_dir(session_id) else { return };
+    let documents = store.sessions.get(session_id).into_iter().flat_map(|s| s.documents.values());
+    if let Err(e) = fallback_index::save(&dir, documents) {
+        warn!(session_id = %session_id, error = %e, "Failed to persist fallback document index");
+    }
+}
+
 /// Cached Chroma availability check (5-second TTL)
 static CHROMA_AVAILABLE: AtomicBool = AtomicBool::new(false);
 static CHROMA_CHECKED_AT: AtomicU64 = AtomicU64::new(0);
 const CHROMA_CACHE_TTL_MS: u64 = 5_000;
 
 async fn chroma_available() -> bool {
+    // Don't probe while the circuit is open
+    if crate::chroma::breaker::open_remaining().is_some() {
+        return false;
+    }
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map(|d| d.as_millis() as u64)
@@ -139,10 +419,12 @@ async fn index_to_chroma(
     doc_id: &str,
     chunked: &ChunkedDocument,
     persistence: &DocumentPersistence,
+    citation: Option<&Citation>,
+    progress: &mut ProgressReporter,
 ) -> Option<String> {
     let client = get_client();
 
-    let collection = match client.get_or_create_collection(COLLECTION_DOCUMENTS, None).await {
+    let collection = match client.get_or_create_collection(COLLECTION_DOCUMENTS, Some(collection_metadata(COLLECTION_DOCUMENTS))).await {
         Ok(c) => c,
         Err(_) => return None,
     };
@@ -159,40 +441,42 @@ async fn index_to_chroma(
         .unwrap_or_else(|| "unknown".to_string());
 
     // Batch upsert chunks
-    let ids: Vec<String> = chunked.chunks.iter()
-        .map(|c| chunk_id(COLLECTION_DOCUMENTS, doc_id, c.index))
-        .collect();
-
-    let documents: Vec<String> = chunked.chunks.iter()
-        .map(|c| c.content.clone())
-        .collect();
-
-    let metadatas: Vec<serde_json::Value> = chunked.chunks.iter()
-        .map(|c| document_chunk_metadata(
-            session_id,
-            doc_id,
-            c.index,
-            c.section.as_deref(),
-            &file_type,
-            persistence_str,
-        ))
+    let tags = session_tags(session_id);
+    let items: Vec<ChromaUpsertItem> = chunked.chunks.iter()
+        .map(|c| {
+            let mut metadata = document_chunk_metadata(
+                session_id,
+                doc_id,
+                c.index,
+                c.section.as_deref(),
+                &file_type,
+                persistence_str,
+            );
+            if let Some(citation) = citation {
+                citation.write_metadata(&mut metadata);
+            }
+            if let Some(location) = &c.location {
+                location.write_metadata(&mut metadata);
+            }
+            if let Some(image_path) = images::image_path(&chunked.path) {
+                metadata["source_kind"] = serde_json::json!("image");
+                metadata["image_path"] = serde_json::json!(image_path);
+            }
+            add_tag_fields(&mut metadata, &tags);
+            ChromaUpsertItem {
+                id: chunk_id(COLLECTION_DOCUMENTS, doc_id, c.index),
+                document: c.content.clone(),
+                metadata,
+            }
+        })
         .collect();
 
-    let embeddings = crate::chroma::client::embed_documents(&documents);
-
-    match client.upsert(
-        &collection.id,
-        ids,
-        Some(documents),
-        Some(embeddings),
-        Some(metadatas),
-    ).await {
-        Ok(_) => Some(collection.id.clone()),
-        Err(e) => {
-            warn!(error = %e, "Failed to index document to Chroma, local-only");
-            None
-        }
+    let base = progress.processed();
+    let outcome = client.upsert_batched(&collection.id, &items, |processed| progress.set(base + processed)).await;
+    if let Some(first) = outcome.errors.first() {
+        warn!(failed_chunks = outcome.failed, error = %first, "Failed to index document to Chroma, local-only");
     }
+    (outcome.upserted > 0).then(|| collection.id.clone())
 }
 
 /// Add a reference document to a session
@@ -201,23 +485,65 @@ pub async fn add_reference(
     path: &str,
     persistence: DocumentPersistence,
 ) -> Result<ReferenceDocument, RetrieverError> {
-    ensure_initialized();
+    add_reference_with_citation(session_id, path, persistence, None).await
+}
+
+/// Add a reference document whose chunks carry citation metadata
+pub async fn add_reference_with_citation(
+    session_id: &str,
+    path: &str,
+    persistence: DocumentPersistence,
+    citation: Option<Citation>,
+) -> Result<ReferenceDocument, RetrieverError> {
+    ensure_session_loaded(session_id);
 
+    let start = std::time::Instant::now();
     let doc_id = Ulid::new().to_string();
-    let chunked = chunk_document(Path::new(path), &doc_id)?;
+    // Persisted text documents are shared across sessions by content
+    let content_hash = if images::is_image(path) || persistence == DocumentPersistence::Ephemeral {
+        None
+    } else {
+        registry::content_hash(Path::new(path)).ok()
+    };
+    let registered = content_hash.as_deref().and_then(registry::lookup);
+    let (chunked, registered_embeddings) = match registered {
+        Some((mut document, embeddings)) => {
+            debug!(doc_id = %doc_id, path = %path, "Reusing registered chunks and embeddings");
+            let source = Path::new(path);
+            document.id = doc_id.clone();
+            document.path = source.to_string_lossy().to_string();
+            document.filename = source.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or(document.filename);
+            (document, Some(embeddings))
+        }
+        None if images::is_image(path) => (images::chunk_image(session_id, Path::new(path), &doc_id).await?, None),
+        None => (chunk_document(Path::new(path), &doc_id)?, None),
+    };
+
+    // Progress covers the Chroma upsert and local embedding passes
+    let mut progress = ProgressReporter::start(IndexOperation::Document, path, chunked.chunks.len() * 2);
 
     // Try Chroma first (best-effort, fall back to local embeddings)
-    let _ = index_to_chroma(session_id, &doc_id, &chunked, &persistence).await;
+    let _ = index_to_chroma(session_id, &doc_id, &chunked, &persistence, citation.as_ref(), &mut progress).await;
 
-    // Generate local fallback embeddings regardless
+    // Generate local fallback embeddings regardless, unless registered
+    progress.set(chunked.chunks.len() as u32);
     let mut chunk_embeddings = Vec::new();
-    for chunk in &chunked.chunks {
-        let cache_key = format!("{}_{}", doc_id, chunk.index);
-        if let Ok(embedding) = generate_embedding(&chunk.content) {
-            cache_embedding(&cache_key, embedding.clone());
-            chunk_embeddings.push((chunk.index, embedding));
+    if let Some(embeddings) = registered_embeddings {
+        for (index, embedding) in &embeddings {
+            cache_embedding(&format!("{}_{}", doc_id, index), embedding.clone());
+        }
+        chunk_embeddings = embeddings;
+    } else {
+        for chunk in &chunked.chunks {
+            let cache_key = format!("{}_{}", doc_id, chunk.index);
+            if let Ok(embedding) = generate_embedding(&chunk.content) {
+                cache_embedding(&cache_key, embedding.clone());
+                chunk_embeddings.push((chunk.index, embedding));
+            }
+            progress.advance(1);
         }
     }
+    progress.finish();
 
     let loaded_tokens: u32 = chunked.chunks.iter().map(|c| c.token_count).sum();
     let chunk_count = chunked.chunks.len() as u32;
@@ -231,8 +557,12 @@ pub async fn add_reference(
         handling: chunked.handling,
         persistence,
         chunk_count,
+        citation: citation.clone(),
+        pinned_chunks: BTreeSet::new(),
+        max_token_share: None,
     };
 
+    crate::metrics::record_duration(crate::metrics::DOCUMENT_INDEX, start.elapsed());
     info!(doc_id = %doc_id, filename = %reference.filename, chunk_count = chunk_count, "Added reference document");
 
     // Store metadata
@@ -244,16 +574,54 @@ pub async fn add_reference(
             .entry(session_id.to_string())
             .or_insert_with(SessionDocuments::default);
 
-        session.documents.insert(doc_id, StoredDocument {
+        let mut stored = StoredDocument {
             document: chunked,
             persistence,
+            citation,
             chunk_embeddings,
-        });
+            pinned_chunks: BTreeSet::new(),
+            max_token_share: None,
+            content_hash,
+        };
+        if let Err(e) = registry::acquire(session_id, &doc_id, &stored) {
+            // Keep a private copy in the session index instead
+            warn!(doc_id = %doc_id, error = %e, "Failed to register document");
+            stored.content_hash = None;
+        }
+        if let Some(index) = session.index.as_mut() {
+            index.append(&doc_id, &stored);
+        }
+        session.documents.insert(doc_id, stored);
+        persist_session(store, session_id);
     }
 
     Ok(reference)
 }
 
+/// Filename of a loaded reference document
+#[derive(Debug, Clone)]
+pub struct DocumentName {
+    pub session_id: String,
+    pub doc_id: String,
+    pub filename: String,
+}
+
+/// Filenames of every loaded reference document, across sessions
+pub fn document_filenames() -> Vec<DocumentName> {
+    let store = DOCUMENT_STORE.read();
+    let Some(store) = store.as_ref() else { return Vec::new() };
+    store.sessions.iter()
+        .filter(|(session_id, _)| session_id.as_str() != PERMANENT_STORE_KEY)
+        .flat_map(|(session_id, docs)| {
+            docs.documents.iter().map(move |(doc_id, stored)| DocumentName {
+                session_id: session_id.clone(),
+                doc_id: doc_id.clone(),
+                filename: stored.document.filename.clone(),
+            })
+        })
+        .collect()
+}
+
 /// Remove a reference document from a session
 pub async fn remove_reference(session_id: &str, doc_id: &str) -> Result<(), RetrieverError> {
     info!(doc_id = %doc_id, "Removed reference document");
@@ -264,19 +632,92 @@ pub async fn remove_reference(session_id: &str, doc_id: &str) -> Result<(), Retr
         let _ = client.delete(&collection.id, None, Some(filter)).await;
     }
 
-    // Remove from in-memory store
+    // Remove from the store and its persisted index
+    ensure_session_loaded(session_id);
     let mut store = DOCUMENT_STORE.write();
     let store = store.as_mut().ok_or(RetrieverError::NotInitialized)?;
 
     if let Some(session) = store.sessions.get_mut(session_id) {
-        session.documents.remove(doc_id);
+        if let Some(stored) = session.documents.remove(doc_id) {
+            session.index = None;
+            persist_session(store, session_id);
+            if let Some(hash) = stored.content_hash {
+                if let Err(e) = registry::release(session_id, doc_id, &hash) {
+                    warn!(doc_id = %doc_id, error = %e, "Failed to release registered document");
+                }
+            }
+        }
     }
 
     Ok(())
 }
 
+fn reference_of(id: &str, stored: &StoredDocument) -> ReferenceDocument {
+    ReferenceDocument {
+        id: id.to_string(),
+        filename: stored.document.filename.clone(),
+        path: stored.document.path.clone(),
+        total_tokens: stored.document.total_tokens,
+        loaded_tokens: stored.document.chunks.iter().map(|c| c.token_count).sum(),
+        handling: stored.document.handling,
+        persistence: stored.persistence,
+        chunk_count: stored.document.chunks.len() as u32,
+        citation: stored.citation.clone(),
+        pinned_chunks: stored.pinned_chunks.clone(),
+        max_token_share: stored.max_token_share,
+    }
+}
+
+/// Change one document's settings and persist them
+fn update_document(
+    session_id: &str,
+    doc_id: &str,
+    f: impl FnOnce(&mut StoredDocument) -> Result<(), RetrieverError>,
+) -> Result<ReferenceDocument, RetrieverError> {
+    ensure_session_loaded(session_id);
+    let mut store = DOCUMENT_STORE.write();
+    let store = store.as_mut().ok_or(RetrieverError::NotInitialized)?;
+    let stored = store.sessions.get_mut(session_id)
+        .and_then(|s| s.documents.get_mut(doc_id))
+        .ok_or_else(|| RetrieverError::NotFound(doc_id.to_string()))?;
+    f(stored)?;
+    let reference = reference_of(doc_id, stored);
+    persist_session(store, session_id);
+    Ok(reference)
+}
+
+/// Pin or unpin a chunk so search assembly always loads it
+pub fn pin_chunk(session_id: &str, doc_id: &str, chunk_index: u32, pinned: bool) -> Result<ReferenceDocument, RetrieverError> {
+    update_document(session_id, doc_id, |stored| {
+        if chunk_index as usize >= stored.document.chunks.len() {
+            return Err(RetrieverError::NotFound(format!("Chunk {} not found", chunk_index)));
+        }
+        if pinned {
+            stored.pinned_chunks.insert(chunk_index);
+        } else {
+            stored.pinned_chunks.remove(&chunk_index);
+        }
+        Ok(())
+    })
+}
+
+/// Cap the share (0–1] of a search's token budget a document may take;
+/// `None` removes the cap
+pub fn set_document_budget(session_id: &str, doc_id: &str, max_share: Option<f32>) -> Result<ReferenceDocument, RetrieverError> {
+    if let Some(share) = max_share {
+        if !(share > 0.0 && share <= 1.0) {
+            return Err(RetrieverError::InvalidShare(share));
+        }
+    }
+    update_document(session_id, doc_id, |stored| {
+        stored.max_token_share = max_share;
+        Ok(())
+    })
+}
+
 /// Get all reference documents for a session
 pub fn list_references(session_id: &str) -> Result<Vec<ReferenceDocument>, RetrieverError> {
+    ensure_session_loaded(session_id);
     let store = DOCUMENT_STORE.read();
     let store = store.as_ref().ok_or(RetrieverError::NotInitialized)?;
 
@@ -286,22 +727,7 @@ pub fn list_references(session_id: &str) -> Result<Vec<ReferenceDocument>, Retri
     };
 
     let references = session.documents.iter()
-        .map(|(id, stored)| {
-            let loaded_tokens: u32 = stored.document.chunks.iter()
-                .map(|c| c.token_count)
-                .sum();
-
-            ReferenceDocument {
-                id: id.clone(),
-                filename: stored.document.filename.clone(),
-                path: stored.document.path.clone(),
-                total_tokens: stored.document.total_tokens,
-                loaded_tokens,
-                handling: stored.document.handling,
-                persistence: stored.persistence,
-                chunk_count: stored.document.chunks.len() as u32,
-            }
-        })
+        .map(|(id, stored)| reference_of(id, stored))
         .collect();
 
     Ok(references)
@@ -319,7 +745,7 @@ pub async fn search_document(
         debug!(doc_id = %doc_id, "Searching document via Chroma");
         if let Ok(results) = search_document_chroma(session_id, doc_id, query, top_k).await {
             if !results.is_empty() {
-                return Ok(results);
+                return Ok(with_snippets(results, query));
             }
         }
     }
@@ -327,6 +753,7 @@ pub async fn search_document(
     // Fallback to local feature-hash search
     warn!(doc_id = %doc_id, "Falling back to local search");
     search_document_local(session_id, doc_id, query, top_k)
+        .map(|results| with_snippets(results, query))
 }
 
 /// Search via Chroma
@@ -387,7 +814,7 @@ async fn search_document_chroma(
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string());
 
-            let token_count = (content.len() as f64 / 4.0).ceil() as u32;
+            let token_count = estimate_tokens_quick(&content);
             let score = 1.0 / (1.0 + distance);
 
             results.push(SearchResult {
@@ -397,6 +824,10 @@ async fn search_document_chroma(
                 section,
                 score,
                 token_count,
+                snippet: None,
+                citation: metadata.as_ref().and_then(Citation::from_metadata),
+                image_path: metadata.as_ref().and_then(images::image_path_from_metadata),
+                pinned: false,
             });
         }
     }
@@ -411,6 +842,7 @@ fn search_document_local(
     query: &str,
     top_k: usize,
 ) -> Result<Vec<SearchResult>, RetrieverError> {
+    ensure_session_index(session_id);
     let store = DOCUMENT_STORE.read();
     let store = store.as_ref().ok_or(RetrieverError::NotInitialized)?;
 
@@ -423,21 +855,24 @@ fn search_document_local(
     let query_embedding = generate_embedding(query)
         .map_err(|e| RetrieverError::EmbeddingFailed(e.to_string()))?;
 
-    let mut results: Vec<SearchResult> = stored.chunk_embeddings.iter()
-        .filter_map(|(chunk_index, embedding)| {
-            let score = cosine_similarity(&query_embedding, embedding);
-            if score <= 0.0 {
-                return None;
-            }
+    let scored = session.index.as_ref()
+        .map(|index| index.score_document(doc_id, &query_embedding))
+        .unwrap_or_default();
 
-            stored.document.chunks.get(*chunk_index as usize)
+    let mut results: Vec<SearchResult> = scored.into_iter()
+        .filter_map(|(chunk_index, score)| {
+            stored.document.chunks.get(chunk_index as usize)
                 .map(|chunk| SearchResult {
                     doc_id: doc_id.to_string(),
-                    chunk_index: *chunk_index,
+                    chunk_index,
                     content: chunk.content.clone(),
                     section: chunk.section.clone(),
                     score,
                     token_count: chunk.token_count,
+                    snippet: None,
+                    citation: stored.citation.clone(),
+                    image_path: images::image_path(&stored.document.path),
+                    pinned: false,
                 })
         })
         .collect();
@@ -458,16 +893,117 @@ pub async fn search_all_documents(
     // Try Chroma first
     if chroma_available().await {
         debug!(session_id = %session_id, query = %query, top_k = top_k, "Searching all docs via Chroma");
-        if let Ok(results) = search_all_chroma(session_id, query, top_k, token_budget).await {
+        if let Ok(results) = search_all_chroma(session_id, query, top_k).await {
             if !results.is_empty() {
-                return Ok(results);
+                // Chroma scores 1/(1+d²); for unit vectors d² = 2 - 2·cosine
+                let lineage = search_lineage_local(session_id, query, top_k).into_iter()
+                    .map(|r| SearchResult { score: 1.0 / (3.0 - 2.0 * r.score), ..r })
+                    .collect();
+                let results = with_lineage(results, lineage);
+                return Ok(with_snippets(assemble(session_id, results, top_k, token_budget), query));
             }
         }
     }
 
     // Fallback to local
     warn!(session_id = %session_id, "Falling back to local search for session");
-    search_all_local(session_id, query, top_k, token_budget)
+    search_all_local(session_id, query, top_k)
+        .map(|results| with_lineage(results, search_lineage_local(session_id, query, top_k)))
+        .map(|results| with_snippets(assemble(session_id, results, top_k, token_budget), query))
+}
+
+/// Pinned chunks first, then up to `top_k` of `candidates` in score order,
+/// all within `token_budget`. A document with a `max_token_share` gets at
+/// most that share of the budget, pins included; chunks past it are skipped.
+fn assemble(session_id: &str, candidates: Vec<SearchResult>, top_k: usize, token_budget: u32) -> Vec<SearchResult> {
+    ensure_session_loaded(session_id);
+    let store = DOCUMENT_STORE.read();
+    let session = store.as_ref().and_then(|s| s.sessions.get(session_id));
+    assemble_from(session, candidates, top_k, token_budget)
+}
+
+fn assemble_from(
+    session: Option<&SessionDocuments>,
+    candidates: Vec<SearchResult>,
+    top_k: usize,
+    token_budget: u32,
+) -> Vec<SearchResult> {
+    let mut results: Vec<SearchResult> = Vec::new();
+    let mut total_tokens = 0u32;
+    let mut doc_tokens: HashMap<String, u32> = HashMap::new();
+    let doc_cap = |doc_id: &str| {
+        session.and_then(|s| s.documents.get(doc_id))
+            .and_then(|stored| stored.max_token_share)
+            .map(|share| (token_budget as f32 * share) as u32)
+    };
+
+    // Pinned chunks are always loaded while the budget and their document's
+    // share allow
+    let mut pinned: Vec<(&String, &StoredDocument)> = session
+        .map(|s| s.documents.iter().filter(|(_, stored)| !stored.pinned_chunks.is_empty()).collect())
+        .unwrap_or_default();
+    pinned.sort_by_key(|(doc_id, _)| *doc_id);
+    for (doc_id, stored) in pinned {
+        for chunk in stored.pinned_chunks.iter().filter_map(|i| stored.document.chunks.get(*i as usize)) {
+            if total_tokens + chunk.token_count > token_budget {
+                debug!(doc_id = %doc_id, chunk_index = chunk.index, "Pinned chunk exceeds the token budget, skipped");
+                continue;
+            }
+            let used = doc_tokens.get(doc_id).copied().unwrap_or(0);
+            if doc_cap(doc_id).is_some_and(|cap| used + chunk.token_count > cap) {
+                debug!(doc_id = %doc_id, chunk_index = chunk.index, "Pinned chunk exceeds the document's token share, skipped");
+                continue;
+            }
+            total_tokens += chunk.token_count;
+            *doc_tokens.entry(doc_id.clone()).or_default() += chunk.token_count;
+            let score = candidates.iter()
+                .find(|c| &c.doc_id == doc_id && c.chunk_index == chunk.index)
+                .map_or(0.0, |c| c.score);
+            results.push(SearchResult {
+                doc_id: doc_id.clone(),
+                chunk_index: chunk.index,
+                content: chunk.content.clone(),
+                section: chunk.section.clone(),
+                score,
+                token_count: chunk.token_count,
+                snippet: None,
+                citation: stored.citation.clone(),
+                image_path: images::image_path(&stored.document.path),
+                pinned: true,
+            });
+        }
+    }
+
+    let mut found = 0;
+    for result in candidates {
+        if found >= top_k {
+            break;
+        }
+        if results.iter().any(|r| r.pinned && r.doc_id == result.doc_id && r.chunk_index == result.chunk_index) {
+            continue;
+        }
+        let used = doc_tokens.get(&result.doc_id).copied().unwrap_or(0);
+        if doc_cap(&result.doc_id).is_some_and(|cap| used + result.token_count > cap) {
+            continue;
+        }
+        if total_tokens + result.token_count > token_budget {
+            break;
+        }
+        total_tokens += result.token_count;
+        *doc_tokens.entry(result.doc_id.clone()).or_default() += result.token_count;
+        results.push(result);
+        found += 1;
+    }
+
+    results
+}
+
+/// Attach highlight snippets to the final (already truncated) results
+fn with_snippets(mut results: Vec<SearchResult>, query: &str) -> Vec<SearchResult> {
+    for result in &mut results {
+        result.snippet = extract_snippet(&result.content, query);
+    }
+    results
 }
 
 /// Search all documents via Chroma
@@ -475,7 +1011,6 @@ async fn search_all_chroma(
     session_id: &str,
     query: &str,
     top_k: usize,
-    token_budget: u32,
 ) -> Result<Vec<SearchResult>, RetrieverError> {
     let client = get_client();
     let collection = client.get_collection(COLLECTION_DOCUMENTS).await?;
@@ -534,7 +1069,7 @@ async fn search_all_chroma(
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string());
 
-            let token_count = (content.len() as f64 / 4.0).ceil() as u32;
+            let token_count = estimate_tokens_quick(&content);
             let score = 1.0 / (1.0 + distance);
 
             all_results.push(SearchResult {
@@ -544,27 +1079,15 @@ async fn search_all_chroma(
                 section,
                 score,
                 token_count,
+                snippet: None,
+                citation: metadata.as_ref().and_then(Citation::from_metadata),
+                image_path: metadata.as_ref().and_then(images::image_path_from_metadata),
+                pinned: false,
             });
         }
     }
 
-    // Apply token budget
-    let mut results = Vec::new();
-    let mut total_tokens = 0u32;
-
-    for result in all_results {
-        if total_tokens + result.token_count > token_budget {
-            break;
-        }
-        total_tokens += result.token_count;
-        results.push(result);
-
-        if results.len() >= top_k {
-            break;
-        }
-    }
-
-    Ok(results)
+    Ok(all_results)
 }
 
 /// Search all documents locally (fallback)
@@ -572,8 +1095,8 @@ fn search_all_local(
     session_id: &str,
     query: &str,
     top_k: usize,
-    token_budget: u32,
 ) -> Result<Vec<SearchResult>, RetrieverError> {
+    ensure_session_index(session_id);
     let store = DOCUMENT_STORE.read();
     let store = store.as_ref().ok_or(RetrieverError::NotInitialized)?;
 
@@ -582,65 +1105,76 @@ fn search_all_local(
         None => return Ok(Vec::new()),
     };
 
+    let Some(index) = session.index.as_ref().filter(|index| !index.matrix.is_empty()) else {
+        return Ok(Vec::new());
+    };
+
     let query_embedding = generate_embedding(query)
         .map_err(|e| RetrieverError::EmbeddingFailed(e.to_string()))?;
 
-    let mut all_results: Vec<SearchResult> = Vec::new();
-
-    for (doc_id, stored) in &session.documents {
-        for (chunk_index, embedding) in &stored.chunk_embeddings {
-            let score = cosine_similarity(&query_embedding, embedding);
-            if score <= 0.0 {
-                continue;
-            }
-
-            if let Some(chunk) = stored.document.chunks.get(*chunk_index as usize) {
-                all_results.push(SearchResult {
-                    doc_id: doc_id.to_string(),
-                    chunk_index: *chunk_index,
-                    content: chunk.content.clone(),
-                    section: chunk.section.clone(),
-                    score,
-                    token_count: chunk.token_count,
-                });
-            }
-        }
-    }
-
-    all_results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-
-    let mut results = Vec::new();
-    let mut total_tokens = 0u32;
-
-    for result in all_results {
-        if total_tokens + result.token_count > token_budget {
-            break;
-        }
-        total_tokens += result.token_count;
-        results.push(result);
-
-        if results.len() >= top_k {
-            break;
-        }
-    }
+    // Extra candidates allow for budget filtering when the graph caps them
+    let all_results = index.score_all(&query_embedding, top_k * 2).into_iter()
+        .filter_map(|(doc_id, chunk_index, score)| {
+            let stored = session.documents.get(doc_id)?;
+            let chunk = stored.document.chunks.get(chunk_index as usize)?;
+            Some(SearchResult {
+                doc_id: doc_id.to_string(),
+                chunk_index,
+                content: chunk.content.clone(),
+                section: chunk.section.clone(),
+                score,
+                token_count: chunk.token_count,
+                snippet: None,
+                citation: stored.citation.clone(),
+                image_path: images::image_path(&stored.document.path),
+                pinned: false,
+            })
+        });
 
-    Ok(results)
+    Ok(all_results.collect())
 }
 
 /// Get a specific chunk from a document
 pub fn get_chunk(session_id: &str, doc_id: &str, chunk_index: u32) -> Result<Chunk, RetrieverError> {
-    let store = DOCUMENT_STORE.read();
-    let store = store.as_ref().ok_or(RetrieverError::NotInitialized)?;
+    with_document(session_id, doc_id, |stored| {
+        stored.document.chunks.get(chunk_index as usize)
+            .cloned()
+            .ok_or_else(|| RetrieverError::NotFound(format!("Chunk {} not found", chunk_index)))
+    })
+}
 
-    let session = store.sessions.get(session_id)
-        .ok_or_else(|| RetrieverError::NotFound(session_id.to_string()))?;
+/// Get the full text of a named section, for drilling into summarized documents
+pub fn get_section(session_id: &str, doc_id: &str, heading: &str) -> Result<DocumentSection, RetrieverError> {
+    with_document(session_id, doc_id, |stored| {
+        stored.document.section(heading)
+            .ok_or_else(|| RetrieverError::NotFound(format!("Section '{}' not found", heading)))
+    })
+}
 
-    let stored = session.documents.get(doc_id)
-        .ok_or_else(|| RetrieverError::NotFound(doc_id.to_string()))?;
+/// Run `f` on a document of the session, or a permanent one from its lineage
+fn with_document<T>(
+    session_id: &str,
+    doc_id: &str,
+    f: impl FnOnce(&StoredDocument) -> Result<T, RetrieverError>,
+) -> Result<T, RetrieverError> {
+    ensure_session_loaded(session_id);
+    let in_session = DOCUMENT_STORE.read().as_ref()
+        .and_then(|s| s.sessions.get(session_id))
+        .is_some_and(|s| s.documents.contains_key(doc_id));
+    let key = match in_session {
+        true => session_id.to_string(),
+        false => lineage_documents(session_id).into_iter()
+            .find(|(_, ids)| ids.contains(doc_id))
+            .map(|(key, _)| key)
+            .ok_or_else(|| RetrieverError::NotFound(doc_id.to_string()))?,
+    };
 
-    stored.document.chunks.get(chunk_index as usize)
-        .cloned()
-        .ok_or_else(|| RetrieverError::NotFound(format!("Chunk {} not found", chunk_index)))
+    let store = DOCUMENT_STORE.read();
+    let store = store.as_ref().ok_or(RetrieverError::NotInitialized)?;
+    let stored = store.sessions.get(&key)
+        .and_then(|s| s.documents.get(doc_id))
+        .ok_or_else(|| RetrieverError::NotFound(doc_id.to_string()))?;
+    f(stored)
 }
 
 /// Clear ephemeral documents from a session
@@ -658,13 +1192,14 @@ pub async fn clear_ephemeral(session_id: &str) {
         let _ = client.delete(&collection.id, None, Some(filter)).await;
     }
 
-    // Remove from in-memory store
+    // Remove from in-memory store (ephemeral documents are never persisted)
     let mut store = DOCUMENT_STORE.write();
     if let Some(ref mut s) = *store {
         if let Some(session) = s.sessions.get_mut(session_id) {
             session.documents.retain(|_, stored| {
                 stored.persistence != DocumentPersistence::Ephemeral
             });
+            session.index = None;
         }
     }
 }
@@ -679,11 +1214,21 @@ pub async fn clear_session(session_id: &str) {
         let _ = client.delete(&collection.id, None, Some(filter)).await;
     }
 
-    // Remove from in-memory store
+    // Remove from in-memory store and the persisted index
     let mut store = DOCUMENT_STORE.write();
     if let Some(ref mut s) = *store {
         s.sessions.remove(session_id);
     }
+    if let Some(dir) = fallback_index::session_index_dir(session_id) {
+        if let Err(e) = fallback_index::remove(&dir) {
+            warn!(session_id = %session_id, error = %e, "Failed to remove fallback document index");
+        }
+    }
+    if let Ok(app_data) = crate::session::get_app_data_dir_cli() {
+        if let Err(e) = registry::release_session_in(&app_data, session_id) {
+            warn!(session_id = %session_id, error = %e, "Failed to release registered documents");
+        }
+    }
 }
 
 // ============ TAURI COMMANDS ============
@@ -692,7 +1237,7 @@ pub async fn clear_session(session_id: &str) {
 pub async fn documents_add_reference(
     session_id: String,
     path: String,
-    persistence: DocumentPersistence,
+    persistence: Option<DocumentPersistence>,
 ) -> Result<ReferenceDocument, RetrieverError> {
     validate_session_id(&session_id).map_err(|_| RetrieverError::InvalidSessionId)?;
     // Canonicalize and validate the path is under the user's home directory
@@ -705,9 +1250,29 @@ pub async fn documents_add_reference(
             )));
         }
     }
+    let persistence = document_persistence(&session_id, persistence);
     add_reference(&session_id, &canonical.to_string_lossy(), persistence).await
 }
 
+/// Chunk and embed a reference document as a background job; returns the
+/// job id. The job's result is the `ReferenceDocument`.
+#[tauri::command]
+pub fn documents_add_reference_job(
+    session_id: String,
+    path: String,
+    persistence: Option<DocumentPersistence>,
+) -> Result<String, RetrieverError> {
+    validate_session_id(&session_id).map_err(|_| RetrieverError::InvalidSessionId)?;
+    let label = format!("{}:{}", session_id, path);
+    Ok(jobs::submit_unique(JobKind::DocumentIndex, label, move |job| async move {
+        job.progress(0.0, format!("Chunking {}", path));
+        let reference = documents_add_reference(session_id, path, persistence)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(serde_json::to_value(reference).ok())
+    }))
+}
+
 #[tauri::command]
 pub async fn documents_remove_reference(
     session_id: String,
@@ -740,9 +1305,12 @@ pub async fn documents_search_all(
     query: String,
     top_k: usize,
     token_budget: u32,
+    request_id: Option<String>,
 ) -> Result<Vec<SearchResult>, RetrieverError> {
     validate_session_id(&session_id).map_err(|_| RetrieverError::InvalidSessionId)?;
-    search_all_documents(&session_id, &query, top_k, token_budget).await
+    cancellable(request_id.as_deref(), search_all_documents(&session_id, &query, top_k, token_budget))
+        .await
+        .map_err(|_| RetrieverError::Cancelled)?
 }
 
 #[tauri::command]
@@ -755,6 +1323,37 @@ pub fn documents_get_chunk(
     get_chunk(&session_id, &doc_id, chunk_index)
 }
 
+#[tauri::command]
+pub fn documents_pin_chunk(
+    session_id: String,
+    doc_id: String,
+    chunk_index: u32,
+    pinned: bool,
+) -> Result<ReferenceDocument, RetrieverError> {
+    validate_session_id(&session_id).map_err(|_| RetrieverError::InvalidSessionId)?;
+    pin_chunk(&session_id, &doc_id, chunk_index, pinned)
+}
+
+#[tauri::command]
+pub fn documents_set_budget(
+    session_id: String,
+    doc_id: String,
+    max_share: Option<f32>,
+) -> Result<ReferenceDocument, RetrieverError> {
+    validate_session_id(&session_id).map_err(|_| RetrieverError::InvalidSessionId)?;
+    set_document_budget(&session_id, &doc_id, max_share)
+}
+
+#[tauri::command]
+pub fn documents_get_section(
+    session_id: String,
+    doc_id: String,
+    heading: String,
+) -> Result<DocumentSection, RetrieverError> {
+    validate_session_id(&session_id).map_err(|_| RetrieverError::InvalidSessionId)?;
+    get_section(&session_id, &doc_id, &heading)
+}
+
 #[tauri::command]
 pub async fn documents_clear_ephemeral(session_id: String) -> Result<(), RetrieverError> {
     validate_session_id(&session_id).map_err(|_| RetrieverError::InvalidSessionId)?;
@@ -772,4 +1371,96 @@ mod tests {
         let store = DOCUMENT_STORE.read();
         assert!(store.is_some());
     }
+
+    /// A document of `chunks` chunks, `tokens` tokens each
+    fn stored(chunks: u32, tokens: u32, pinned: &[u32], max_token_share: Option<f32>) -> StoredDocument {
+        let chunks: Vec<serde_json::Value> = (0..chunks)
+            .map(|i| serde_json::json!({
+                "index": i, "content": format!("chunk {}", i), "startPos": 0, "endPos": 0,
+                "tokenCount": tokens, "section": null,
+            }))
+            .collect();
+        StoredDocument {
+            document: serde_json::from_value(serde_json::json!({
+                "id": "doc", "filename": "doc.md", "path": "/tmp/doc.md", "totalTokens": 0,
+                "handling": "chunked", "chunks": chunks, "summary": null, "sections": [],
+            }))
+            .unwrap(),
+            persistence: DocumentPersistence::Cached,
+            citation: None,
+            chunk_embeddings: Vec::new(),
+            pinned_chunks: pinned.iter().copied().collect(),
+            max_token_share,
+            content_hash: None,
+        }
+    }
+
+    fn candidate(doc_id: &str, chunk_index: u32, score: f32, token_count: u32) -> SearchResult {
+        SearchResult {
+            doc_id: doc_id.to_string(),
+            chunk_index,
+            content: format!("chunk {}", chunk_index),
+            section: None,
+            score,
+            token_count,
+            snippet: None,
+            citation: None,
+            image_path: None,
+            pinned: false,
+        }
+    }
+
+    fn session(documents: Vec<(&str, StoredDocument)>) -> SessionDocuments {
+        SessionDocuments {
+            documents: documents.into_iter().map(|(id, doc)| (id.to_string(), doc)).collect(),
+            index: None,
+        }
+    }
+
+    fn keys(results: &[SearchResult]) -> Vec<(&str, u32, bool)> {
+        results.iter().map(|r| (r.doc_id.as_str(), r.chunk_index, r.pinned)).collect()
+    }
+
+    #[test]
+    fn test_assemble_pins_first_by_document() {
+        let session = session(vec![("b", stored(3, 10, &[2, 0], None)), ("a", stored(3, 10, &[1], None))]);
+        let candidates = vec![candidate("b", 1, 0.9, 10), candidate("b", 2, 0.8, 10), candidate("a", 0, 0.7, 10)];
+
+        let results = assemble_from(Some(&session), candidates, 5, 1000);
+        assert_eq!(keys(&results), vec![
+            ("a", 1, true), ("b", 0, true), ("b", 2, true),
+            ("b", 1, false), ("a", 0, false),
+        ]);
+        // A pin the query also matched keeps its score
+        assert_eq!(results[2].score, 0.8);
+        assert_eq!(results[0].score, 0.0);
+    }
+
+    #[test]
+    fn test_assemble_share_caps_pins_and_candidates() {
+        // 25% of 100 tokens: two 10-token chunks
+        let session = session(vec![("a", stored(4, 10, &[0, 1, 2], Some(0.25))), ("b", stored(2, 10, &[], None))]);
+        let candidates = vec![candidate("a", 3, 0.9, 10), candidate("b", 0, 0.8, 10)];
+
+        let results = assemble_from(Some(&session), candidates, 5, 100);
+        assert_eq!(keys(&results), vec![("a", 0, true), ("a", 1, true), ("b", 0, false)]);
+    }
+
+    #[test]
+    fn test_assemble_budget_overflow() {
+        let session = session(vec![("a", stored(2, 40, &[0], None)), ("b", stored(1, 80, &[0], None))]);
+        let candidates = vec![
+            candidate("c", 0, 0.9, 30),
+            candidate("c", 1, 0.8, 40),
+            candidate("c", 2, 0.7, 10),
+        ];
+
+        // The pin that doesn't fit is skipped; candidates stop at the first overflow
+        let results = assemble_from(Some(&session), candidates.clone(), 5, 100);
+        assert_eq!(keys(&results), vec![("a", 0, true), ("c", 0, false)]);
+
+        // No session: candidates alone, up to top_k
+        let results = assemble_from(None, candidates, 2, 1000);
+        assert_eq!(keys(&results), vec![("c", 0, false), ("c", 1, false)]);
+    }
 }
@@ -0,0 +1,120 @@
+//! Sentence Boundaries
+//!
+//! Finds where sentences end so chunkers can split oversized text without
+//! cutting a sentence in half. Rule based: a sentence ends at `.`, `!`, `?`
+//! or `…` (plus any closing quotes or brackets) followed by whitespace,
+//! unless the period ends a known abbreviation or an initial, or the next
+//! word starts lowercase. CJK full stops end a sentence without whitespace.
+
+/// Terminal punctuation that needs whitespace after it
+const TERMINALS: [char; 4] = ['.', '!', '?', '…'];
+/// Full-width terminals used without a following space
+const CJK_TERMINALS: [char; 3] = ['。', '！', '？'];
+/// Closing characters that stay with the sentence they end
+const CLOSERS: [char; 8] = ['"', '\'', '”', '’', '»', ')', ']', '」'];
+/// Words whose trailing period doesn't end a sentence (lowercase, without
+/// the final period)
+const ABBREVIATIONS: [&str; 23] = [
+    "mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "vs", "e.g", "i.e", "cf", "al", "fig",
+    "inc", "ltd", "co", "corp", "approx", "est", "u.s", "u.k", "p",
+];
+
+/// Byte offsets where each sentence after the first starts
+pub fn sentence_boundaries(text: &str) -> Vec<usize> {
+    let mut boundaries = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((pos, c)) = chars.next() {
+        let cjk = CJK_TERMINALS.contains(&c);
+        if !cjk && !TERMINALS.contains(&c) {
+            continue;
+        }
+        // Runs like "?!" or `."` close the same sentence
+        while let Some(&(_, next)) = chars.peek() {
+            if TERMINALS.contains(&next) || CJK_TERMINALS.contains(&next) || CLOSERS.contains(&next) {
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        let mut had_space = false;
+        while let Some(&(_, next)) = chars.peek() {
+            if next.is_whitespace() {
+                had_space = true;
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        let Some(&(start, next)) = chars.peek() else { break };
+        if !had_space && !cjk {
+            continue;
+        }
+        if c == '.' && (is_abbreviation(&text[..pos]) || next.is_lowercase()) {
+            continue;
+        }
+        boundaries.push(start);
+    }
+
+    boundaries
+}
+
+/// Whether the word ending `before` (the text up to a period) is an
+/// abbreviation or a single-letter initial
+fn is_abbreviation(before: &str) -> bool {
+    let word = before
+        .rsplit(char::is_whitespace)
+        .next()
+        .unwrap_or("")
+        .trim_start_matches(|c: char| !c.is_alphanumeric());
+    let mut letters = word.chars();
+    match (letters.next(), letters.next()) {
+        (Some(first), None) => first.is_alphabetic(),
+        _ => ABBREVIATIONS.contains(&word.to_lowercase().as_str()),
+    }
+}
+
+/// Where to split a chunk that has grown past its target size: the first
+/// paragraph break in the latter half, else the last sentence boundary there
+pub fn find_split_boundary(text: &str) -> Option<usize> {
+    let mut mid = text.len() / 2;
+    while !text.is_char_boundary(mid) {
+        mid += 1;
+    }
+    if let Some(pos) = text[mid..].find("\n\n") {
+        return Some(mid + pos + 2);
+    }
+    sentence_boundaries(text).into_iter().rev().find(|&boundary| boundary >= mid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentences(text: &str) -> Vec<&str> {
+        let mut starts = vec![0];
+        starts.extend(sentence_boundaries(text));
+        starts.push(text.len());
+        starts.windows(2).map(|w| text[w[0]..w[1]].trim_end()).collect()
+    }
+
+    #[test]
+    fn test_sentence_boundaries() {
+        assert_eq!(
+            sentences("Margins fell 3.5% in Q2. Dr. Smith disagreed, e.g. on pricing! Why? \"Supply,\" she said.\nNext line."),
+            vec!["Margins fell 3.5% in Q2.", "Dr. Smith disagreed, e.g. on pricing!", "Why?", "\"Supply,\" she said.", "Next line."]
+        );
+        assert_eq!(sentences("See J. R. Tolkien and approx. five others (roughly.) Then more"), vec![
+            "See J. R. Tolkien and approx. five others (roughly.)",
+            "Then more"
+        ]);
+        assert_eq!(sentences("市场下滑。供应紧张！"), vec!["市场下滑。", "供应紧张！"]);
+        assert!(sentence_boundaries("no terminal punctuation here").is_empty());
+
+        // No paragraph break: split at the last sentence end in the latter half
+        let text = "One short sentence. Another one follows here. A third closes it out. Trailing words";
+        let split = find_split_boundary(text).unwrap();
+        assert_eq!(&text[split..], "Trailing words");
+        assert_eq!(find_split_boundary("Opening words here.\n\nSecond."), Some(21));
+    }
+}
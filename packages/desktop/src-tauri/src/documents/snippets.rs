@@ -0,0 +1,249 @@
+//! Search Result Snippets
+//!
+//! Picks the most relevant sentence window from a matched chunk so the UI
+//! can show a short excerpt with highlighted query terms. Sentences are
+//! scored by query-term hits; when no term appears (purely semantic match)
+//! the sentence closest to the query embedding wins.
+//!
+//! All offsets are char (not byte) offsets into the original text.
+
+use serde::{Deserialize, Serialize};
+
+use super::embeddings::{cosine_similarity, generate_embedding};
+
+/// Target maximum snippet length (chars)
+pub const MAX_SNIPPET_CHARS: usize = 240;
+/// Query terms shorter than this are not highlighted
+const MIN_TERM_CHARS: usize = 2;
+
+/// Highlighted range within the source text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighlightSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Excerpt of a search hit with highlight ranges
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    pub text: String,
+    /// Char offset of the snippet within the source text
+    pub start: usize,
+    pub end: usize,
+    /// Query-term matches inside the snippet (offsets into the source text)
+    pub highlights: Vec<HighlightSpan>,
+}
+
+/// Split into sentence spans `(start, end)` over `chars`, trimmed of whitespace.
+fn sentence_spans(chars: &[char]) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+
+    for i in 0..chars.len() {
+        let c = chars[i];
+        let at_break = c == '\n'
+            || (matches!(c, '.' | '!' | '?') && chars.get(i + 1).is_none_or(|n| n.is_whitespace()));
+        if at_break {
+            spans.push((start, i + 1));
+            start = i + 1;
+        }
+    }
+    if start < chars.len() {
+        spans.push((start, chars.len()));
+    }
+
+    spans
+        .into_iter()
+        .filter_map(|(mut s, mut e)| {
+            while s < e && chars[s].is_whitespace() {
+                s += 1;
+            }
+            while e > s && chars[e - 1].is_whitespace() {
+                e -= 1;
+            }
+            (s < e).then_some((s, e))
+        })
+        .collect()
+}
+
+/// Lowercased, de-duplicated query terms
+fn query_terms(query: &str) -> Vec<Vec<char>> {
+    let mut terms: Vec<Vec<char>> = Vec::new();
+    for word in query.split(|c: char| !c.is_alphanumeric()) {
+        let term: Vec<char> = word.chars().map(lower).collect();
+        if term.len() >= MIN_TERM_CHARS && !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    terms
+}
+
+/// Single-char lowercase so char offsets stay aligned with the source
+fn lower(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Occurrences of each term that start on a word boundary
+fn term_matches(lowered: &[char], terms: &[Vec<char>]) -> Vec<HighlightSpan> {
+    let mut spans = Vec::new();
+    for term in terms {
+        if term.len() > lowered.len() {
+            continue;
+        }
+        for start in 0..=(lowered.len() - term.len()) {
+            let on_boundary = start == 0 || !lowered[start - 1].is_alphanumeric();
+            if on_boundary && lowered[start..start + term.len()] == term[..] {
+                spans.push(HighlightSpan { start, end: start + term.len() });
+            }
+        }
+    }
+    spans.sort_by_key(|s| s.start);
+    spans
+}
+
+/// Extract the best snippet for `query` from `text`.
+///
+/// Returns `None` for empty text.
+pub fn extract_snippet(text: &str, query: &str) -> Option<Snippet> {
+    let chars: Vec<char> = text.chars().collect();
+    let spans = sentence_spans(&chars);
+    if spans.is_empty() {
+        return None;
+    }
+
+    let lowered: Vec<char> = chars.iter().copied().map(lower).collect();
+    let terms = query_terms(query);
+    let matches = term_matches(&lowered, &terms);
+
+    // Score sentences by distinct terms hit, then by total hits
+    let term_score = |(s, e): (usize, usize)| -> f32 {
+        let inside: Vec<&HighlightSpan> = matches.iter().filter(|m| m.start >= s && m.end <= e).collect();
+        let distinct = terms
+            .iter()
+            .filter(|t| inside.iter().any(|m| lowered[m.start..m.end] == t[..]))
+            .count();
+        distinct as f32 + 0.1 * inside.len() as f32
+    };
+    let mut scores: Vec<f32> = spans.iter().map(|&span| term_score(span)).collect();
+
+    // Purely semantic hit: fall back to embedding similarity per sentence
+    if scores.iter().all(|s| *s == 0.0) {
+        if let Ok(query_embedding) = generate_embedding(query) {
+            scores = spans
+                .iter()
+                .map(|&(s, e)| {
+                    let sentence: String = chars[s..e].iter().collect();
+                    generate_embedding(&sentence)
+                        .map(|emb| cosine_similarity(&query_embedding, &emb))
+                        .unwrap_or(0.0)
+                })
+                .collect();
+        }
+    }
+
+    let best = scores
+        .iter()
+        .enumerate()
+        .fold(0, |best, (i, s)| if *s > scores[best] { i } else { best });
+
+    // Grow the window with neighbouring sentences while it fits
+    let (mut start, mut end) = spans[best];
+    let (mut prev, mut next) = (best, best + 1);
+    loop {
+        let mut grew = false;
+        if next < spans.len() && spans[next].1 - start <= MAX_SNIPPET_CHARS {
+            end = spans[next].1;
+            next += 1;
+            grew = true;
+        }
+        if prev > 0 && end - spans[prev - 1].0 <= MAX_SNIPPET_CHARS {
+            prev -= 1;
+            start = spans[prev].0;
+            grew = true;
+        }
+        if !grew {
+            break;
+        }
+    }
+
+    // A single long sentence: clip around the first match inside it
+    if end - start > MAX_SNIPPET_CHARS {
+        let anchor = matches
+            .iter()
+            .find(|m| m.start >= start && m.end <= end)
+            .map(|m| m.start)
+            .unwrap_or(start);
+        let clipped_start = anchor.saturating_sub(MAX_SNIPPET_CHARS / 4).max(start);
+        end = (clipped_start + MAX_SNIPPET_CHARS).min(end);
+        start = clipped_start;
+    }
+
+    Some(Snippet {
+        text: chars[start..end].iter().collect(),
+        start,
+        end,
+        highlights: matches
+            .into_iter()
+            .filter(|m| m.start >= start && m.end <= end)
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet_picks_matching_sentence() {
+        let text = "Intro sentence about nothing. Pricing power drives margins here. Closing remark.";
+        let snippet = extract_snippet(text, "pricing margins").unwrap();
+        assert!(snippet.text.contains("Pricing power drives margins here."));
+
+        let chars: Vec<char> = text.chars().collect();
+        for h in &snippet.highlights {
+            let word: String = chars[h.start..h.end].iter().collect();
+            assert!(word.eq_ignore_ascii_case("pricing") || word == "margins");
+        }
+        assert_eq!(snippet.highlights.len(), 2);
+    }
+
+    #[test]
+    fn test_snippet_window_is_bounded() {
+        let sentence = "Filler words that do not match anything at all. ";
+        let text = format!("{}The key insight is here. {}", sentence.repeat(10), sentence.repeat(10));
+        let snippet = extract_snippet(&text, "insight").unwrap();
+        assert!(snippet.text.contains("The key insight is here."));
+        assert!(snippet.end - snippet.start <= MAX_SNIPPET_CHARS);
+    }
+
+    #[test]
+    fn test_long_sentence_clipped_around_match() {
+        let text = format!("{} needle {}", "a".repeat(500), "b".repeat(500));
+        let snippet = extract_snippet(&text, "needle").unwrap();
+        assert!(snippet.text.contains("needle"));
+        assert_eq!(snippet.text.chars().count(), MAX_SNIPPET_CHARS);
+        assert_eq!(snippet.highlights.len(), 1);
+    }
+
+    #[test]
+    fn test_char_offsets_with_multibyte_text() {
+        let text = "Café résumé. Naïve thesis on rates.";
+        let snippet = extract_snippet(text, "rates").unwrap();
+        let chars: Vec<char> = text.chars().collect();
+        let h = snippet.highlights[0];
+        assert_eq!(chars[h.start..h.end].iter().collect::<String>(), "rates");
+    }
+
+    fn find_term_matches(text: &str, query: &str) -> Vec<HighlightSpan> {
+        let chars: Vec<char> = text.chars().map(lower).collect();
+        term_matches(&chars, &query_terms(query))
+    }
+
+    #[test]
+    fn test_word_boundary_matching() {
+        assert!(find_term_matches("that cat", "at").is_empty());
+        assert_eq!(find_term_matches("at the cat", "at").len(), 1);
+        assert!(extract_snippet("   ", "query").is_none());
+    }
+}
@@ -0,0 +1,224 @@
+//! Structured Data Chunking
+//!
+//! Schema-aware chunking for `.csv` and `.json` references. Rather than
+//! splitting the raw text at arbitrary points, each chunk is rendered so the
+//! schema travels with the data: CSV chunks repeat the header and label
+//! every row (`Row 12: region: EMEA; revenue: 4.2`), JSON chunks list the
+//! leaf values of a group of top-level entries by path
+//! (`$.items[3].price = 12.5`). Each chunk carries a `ChunkLocation` naming
+//! the rows or paths it covers.
+
+use serde_json::Value;
+
+use super::chunker::{Chunk, ChunkLocation, SectionIndex, CHUNK_SIZE_TARGET};
+use crate::context::tokens::estimate_tokens_quick;
+
+/// A parsed CSV record and its byte span in the source
+struct Record {
+    fields: Vec<String>,
+    start: usize,
+    end: usize,
+}
+
+/// Chunk a CSV file into groups of rows under its header. Row numbers are
+/// 1-based and count data rows only, so "row 1" is the line after the header.
+pub fn chunk_csv(content: &str) -> (Vec<Chunk>, Vec<SectionIndex>) {
+    let mut records = parse_csv(content, detect_delimiter(content)).into_iter();
+    let Some(header) = records.next() else { return (Vec::new(), Vec::new()) };
+    let columns: Vec<String> = header.fields.iter().map(|c| c.trim().to_string()).collect();
+    let header_line = format!("Columns: {}\n", columns.join(", "));
+
+    let mut chunks = Vec::new();
+    let mut current = header_line.clone();
+    let mut group: Option<(u32, usize)> = None; // (first row, start byte)
+    let mut last = (0u32, 0usize); // (last row, end byte)
+
+    for (row, record) in (1u32..).zip(records) {
+        if record.fields.iter().all(|f| f.trim().is_empty()) {
+            continue;
+        }
+        let line = render_row(row, &columns, &record.fields);
+        if let Some((first_row, start)) = group {
+            if estimate_tokens_quick(&current) + estimate_tokens_quick(&line) > CHUNK_SIZE_TARGET {
+                chunks.push(rows_chunk(chunks.len() as u32, current, &columns, (first_row, last.0), (start, last.1)));
+                current = header_line.clone();
+                group = None;
+            }
+        }
+        group.get_or_insert((row, record.start));
+        current.push_str(&line);
+        last = (row, record.end);
+    }
+    if let Some((first_row, start)) = group {
+        chunks.push(rows_chunk(chunks.len() as u32, current, &columns, (first_row, last.0), (start, last.1)));
+    }
+
+    (chunks, Vec::new())
+}
+
+fn render_row(row: u32, columns: &[String], fields: &[String]) -> String {
+    let cells: Vec<String> = fields
+        .iter()
+        .enumerate()
+        .filter(|(_, value)| !value.trim().is_empty())
+        .map(|(i, value)| match columns.get(i).filter(|c| !c.is_empty()) {
+            Some(column) => format!("{}: {}", column, value.trim()),
+            None => format!("column {}: {}", i + 1, value.trim()),
+        })
+        .collect();
+    format!("Row {}: {}\n", row, cells.join("; "))
+}
+
+fn rows_chunk(index: u32, content: String, columns: &[String], rows: (u32, u32), span: (usize, usize)) -> Chunk {
+    Chunk {
+        index,
+        token_count: estimate_tokens_quick(&content),
+        content,
+        start_pos: span.0,
+        end_pos: span.1,
+        section: Some(format!("Rows {}-{}", rows.0, rows.1)),
+        location: Some(ChunkLocation::Rows { columns: columns.to_vec(), first_row: rows.0, last_row: rows.1 }),
+    }
+}
+
+/// Delimiter used by the header line: comma unless semicolons or tabs
+/// are more common there
+fn detect_delimiter(content: &str) -> char {
+    let header = content.lines().next().unwrap_or("");
+    [',', ';', '\t']
+        .into_iter()
+        .max_by_key(|d| (header.matches(*d).count(), *d == ','))
+        .unwrap_or(',')
+}
+
+/// RFC 4180 records: quoted fields may hold delimiters, newlines and `""`
+fn parse_csv(content: &str, delimiter: char) -> Vec<Record> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut start = 0usize;
+    let mut chars = content.char_indices().peekable();
+
+    while let Some((pos, c)) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek().is_some_and(|&(_, next)| next == '"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.is_empty() => in_quotes = true,
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                fields.push(std::mem::take(&mut field));
+                records.push(Record { fields: std::mem::take(&mut fields), start, end: pos });
+                start = pos + 1;
+            }
+            c if c == delimiter && !in_quotes => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push(Record { fields, start, end: content.len() });
+    }
+    records
+}
+
+/// Chunk a JSON file by top-level entries (object keys or array elements),
+/// flattening each into `path = value` lines. `None` if it doesn't parse.
+/// Positions span the whole file since flattened lines have no single
+/// source range.
+pub fn chunk_json(content: &str) -> Option<(Vec<Chunk>, Vec<SectionIndex>)> {
+    let root: Value = serde_json::from_str(content).ok()?;
+    let groups: Vec<(String, Value)> = match root {
+        Value::Object(map) => map.into_iter().map(|(key, value)| (format!("$.{}", key), value)).collect(),
+        Value::Array(items) => items.into_iter().enumerate().map(|(i, value)| (format!("$[{}]", i), value)).collect(),
+        scalar => vec![("$".to_string(), scalar)],
+    };
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut paths: Vec<String> = Vec::new();
+    let flush = |current: &mut String, paths: &mut Vec<String>, chunks: &mut Vec<Chunk>| {
+        if current.trim().is_empty() {
+            return;
+        }
+        let content_str = std::mem::take(current);
+        let paths = std::mem::take(paths);
+        chunks.push(Chunk {
+            index: chunks.len() as u32,
+            token_count: estimate_tokens_quick(&content_str),
+            content: content_str,
+            start_pos: 0,
+            end_pos: content.len(),
+            section: paths.first().cloned(),
+            location: Some(ChunkLocation::JsonPaths { paths }),
+        });
+    };
+
+    for (path, value) in groups {
+        let mut lines = Vec::new();
+        flatten_json(&path, &value, &mut lines);
+        for line in lines {
+            if estimate_tokens_quick(&current) + estimate_tokens_quick(&line) > CHUNK_SIZE_TARGET {
+                flush(&mut current, &mut paths, &mut chunks);
+            }
+            if paths.last() != Some(&path) {
+                paths.push(path.clone());
+            }
+            current.push_str(&line);
+            current.push('\n');
+        }
+    }
+    flush(&mut current, &mut paths, &mut chunks);
+
+    Some((chunks, Vec::new()))
+}
+
+/// Leaf values under `path` as `path = value` lines
+fn flatten_json(path: &str, value: &Value, lines: &mut Vec<String>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                flatten_json(&format!("{}.{}", path, key), child, lines);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for (i, child) in items.iter().enumerate() {
+                flatten_json(&format!("{}[{}]", path, i), child, lines);
+            }
+        }
+        leaf => lines.push(format!("{} = {}", path, leaf)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_structured_chunks_label_rows_and_paths() {
+        let mut csv = String::from("region,\"revenue, usd\",note\n");
+        for i in 0..400 {
+            csv.push_str(&format!("R{},{},\"line one\nline \"\"two\"\"\"\n", i, i * 10));
+        }
+        let (chunks, _) = chunk_csv(&csv);
+        assert!(chunks.len() > 1);
+        assert!(chunks[0].content.starts_with("Columns: region, revenue, usd, note\nRow 1: region: R0; revenue, usd: 0; note: line one\nline \"two\"\n"));
+        let Some(ChunkLocation::Rows { first_row, last_row, .. }) = chunks[1].location.clone() else { panic!("no rows") };
+        assert_eq!(first_row, chunks[0].content.matches("\nRow ").count() as u32 + 1);
+        assert!(chunks[1].content.contains(&format!("Row {}: region: R{};", last_row, last_row - 1)));
+        assert!(csv[chunks[1].start_pos..].starts_with(&format!("R{},", first_row - 1)));
+        assert_eq!(chunks.last().unwrap().end_pos, csv.len() - 1);
+
+        let (chunks, _) = chunk_json(r#"{"company": {"name": "Acme", "tags": ["b2b", "saas"]}, "items": [{"sku": 1}, {"sku": 2}]}"#).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].content, "$.company.name = \"Acme\"\n$.company.tags[0] = \"b2b\"\n$.company.tags[1] = \"saas\"\n$.items[0].sku = 1\n$.items[1].sku = 2\n");
+        assert_eq!(chunks[0].location, Some(ChunkLocation::JsonPaths { paths: vec!["$.company".to_string(), "$.items".to_string()] }));
+        assert!(chunk_json("{ not json").is_none());
+    }
+}
@@ -0,0 +1,480 @@
+//! Web Reference Documents
+//!
+//! Fetches a web page, keeps the readable part as Markdown and saves it in
+//! the session directory, where it is chunked and indexed like any other
+//! reference file. Claims cite the saved copy, so they stay traceable after
+//! the page changes or goes away; the URL is recorded alongside it.
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+use tracing::info;
+use ulid::Ulid;
+
+use super::chunker::DocumentPersistence;
+use super::retriever::{add_reference, ReferenceDocument, RetrieverError};
+use crate::session::lock::update_session_file;
+use crate::session::mode_policy::document_persistence;
+use crate::session::{get_session_dir_cli, validate_session_id, SessionError, SessionReferenceDoc};
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+/// Pages larger than this are rejected rather than truncated
+const MAX_PAGE_BYTES: usize = 5 * 1024 * 1024;
+/// Subdirectory of the session directory holding fetched pages
+const REFERENCES_DIR: &str = "references";
+/// Elements dropped with their contents before extraction
+const SKIPPED_ELEMENTS: [&str; 12] = [
+    "head", "script", "style", "noscript", "template", "svg", "iframe", "form", "nav", "header",
+    "footer", "aside",
+];
+/// Elements that start a new paragraph
+const BLOCK_ELEMENTS: [&str; 13] = [
+    "p", "div", "section", "article", "main", "blockquote", "pre", "table", "tr", "ul", "ol",
+    "figure", "hr",
+];
+
+#[derive(Error, Debug)]
+pub enum WebReferenceError {
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+    #[error("Fetch failed: {0}")]
+    Fetch(String),
+    #[error("Unsupported content type: {0}")]
+    UnsupportedContent(String),
+    #[error("Page is larger than {} MB", MAX_PAGE_BYTES / (1024 * 1024))]
+    TooLarge,
+    #[error("No readable content at {0}")]
+    Empty(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error(transparent)]
+    Retriever(#[from] RetrieverError),
+}
+
+impl Serialize for WebReferenceError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// A fetched page as Markdown
+#[derive(Debug, Clone)]
+pub struct WebPage {
+    pub url: String,
+    pub title: Option<String>,
+    pub markdown: String,
+}
+
+/// Only absolute http(s) URLs are fetched
+pub(crate) fn validate_url(url: &str) -> Result<reqwest::Url, WebReferenceError> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|e| WebReferenceError::InvalidUrl(e.to_string()))?;
+    match parsed.scheme() {
+        "http" | "https" if parsed.host_str().is_some() => Ok(parsed),
+        _ => Err(WebReferenceError::InvalidUrl(url.to_string())),
+    }
+}
+
+/// A fetched response body
+pub(crate) struct FetchedText {
+    /// URL after redirects
+    pub url: String,
+    pub content_type: String,
+    pub body: String,
+}
+
+/// GET an http(s) URL as text, capped at `MAX_PAGE_BYTES`
+pub(crate) async fn fetch_text(url: &str) -> Result<FetchedText, WebReferenceError> {
+    let parsed = validate_url(url)?;
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .user_agent(concat!("Dialectic/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| WebReferenceError::Fetch(e.to_string()))?;
+    let mut response = client
+        .get(parsed)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| WebReferenceError::Fetch(e.to_string()))?;
+    if response.content_length().is_some_and(|len| len as usize > MAX_PAGE_BYTES) {
+        return Err(WebReferenceError::TooLarge);
+    }
+    let final_url = response.url().to_string();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("text/html")
+        .to_ascii_lowercase();
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| WebReferenceError::Fetch(e.to_string()))? {
+        body.extend_from_slice(&chunk);
+        if body.len() > MAX_PAGE_BYTES {
+            return Err(WebReferenceError::TooLarge);
+        }
+    }
+    Ok(FetchedText {
+        url: final_url,
+        content_type,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+/// Fetch `url` and convert it to Markdown. HTML goes through
+/// `html_to_markdown`; plain text and Markdown are kept as served.
+pub async fn fetch_page(url: &str) -> Result<WebPage, WebReferenceError> {
+    let fetched = fetch_text(url).await?;
+    let (title, markdown) = if fetched.content_type.contains("html") {
+        html_to_markdown(&fetched.body)
+    } else if fetched.content_type.starts_with("text/") {
+        (None, fetched.body.trim().to_string())
+    } else {
+        return Err(WebReferenceError::UnsupportedContent(fetched.content_type));
+    };
+    if markdown.trim().is_empty() {
+        return Err(WebReferenceError::Empty(fetched.url));
+    }
+    Ok(WebPage { url: fetched.url, title, markdown })
+}
+
+/// Byte range of the first `<name ...>` ... last `</name>` in `lower`
+fn element_range(lower: &str, name: &str) -> Option<(usize, usize)> {
+    let open = format!("<{}", name);
+    let start = lower.match_indices(&open).find_map(|(i, _)| {
+        let next = lower.as_bytes().get(i + open.len())?;
+        (next.is_ascii_whitespace() || *next == b'>').then_some(i)
+    })?;
+    let body_start = start + lower[start..].find('>')? + 1;
+    let end = lower.rfind(&format!("</{}", name)).filter(|&e| e >= body_start)?;
+    Some((body_start, end))
+}
+
+/// Decode the common named entities and numeric character references
+pub(crate) fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').filter(|&semi| semi <= 10).and_then(|semi| {
+            let entity = &rest[1..semi];
+            let c = match entity {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                "nbsp" => ' ',
+                "mdash" => '—',
+                "ndash" => '–',
+                "hellip" => '…',
+                "rsquo" => '’',
+                "lsquo" => '‘',
+                "rdquo" => '”',
+                "ldquo" => '“',
+                _ => {
+                    let code = match entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => entity.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, semi))
+        });
+        match decoded {
+            Some((c, semi)) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Value of attribute `name` in a raw tag body like `a href="..." class=x`
+pub(crate) fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let lower = tag.to_ascii_lowercase();
+    let at = lower.find(&format!("{}=", name))?;
+    let value = &tag[at + name.len() + 1..];
+    match value.chars().next()? {
+        q @ ('"' | '\'') => value[1..].split(q).next(),
+        _ => value.split(|c: char| c.is_whitespace() || c == '>').next(),
+    }
+}
+
+/// Readability-style extraction: drop scripts, navigation and page chrome,
+/// keep `<article>` (else `<main>`, else `<body>`) and render headings,
+/// paragraphs, list items and absolute links as Markdown. Returns the page
+/// title (from `<title>`, else the first heading) and the Markdown.
+pub fn html_to_markdown(html: &str) -> (Option<String>, String) {
+    // ASCII lowercasing keeps byte offsets aligned with `html`
+    let lower = html.to_ascii_lowercase();
+    let title = element_range(&lower, "title")
+        .map(|(s, e)| decode_entities(html[s..e].trim()))
+        .filter(|t| !t.is_empty());
+
+    let (start, end) = ["article", "main", "body"]
+        .iter()
+        .find_map(|name| element_range(&lower, name))
+        .unwrap_or((0, html.len()));
+    let (html, lower) = (&html[start..end], &lower[start..end]);
+
+    let mut out = String::new();
+    let mut text = String::new();
+    let mut skip_depth: Option<(&str, usize)> = None;
+    let mut link: Option<(String, usize)> = None;
+    let mut first_heading: Option<String> = None;
+    let mut heading_start: Option<usize> = None;
+    let mut pos = 0;
+
+    let flush = |text: &mut String, out: &mut String| {
+        let decoded = decode_entities(text);
+        let collapsed = decoded.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !collapsed.is_empty() {
+            let spaced = decoded.starts_with(char::is_whitespace);
+            if spaced && !out.is_empty() && !out.ends_with([' ', '\n', '[']) {
+                out.push(' ');
+            }
+            out.push_str(&collapsed);
+            if decoded.ends_with(char::is_whitespace) {
+                out.push(' ');
+            }
+        }
+        text.clear();
+    };
+
+    while pos < html.len() {
+        let Some(lt) = html[pos..].find('<').map(|i| pos + i) else {
+            if skip_depth.is_none() {
+                text.push_str(&html[pos..]);
+            }
+            break;
+        };
+        if skip_depth.is_none() {
+            text.push_str(&html[pos..lt]);
+        }
+        if lower[lt..].starts_with("<!--") {
+            pos = lower[lt..].find("-->").map_or(html.len(), |i| lt + i + 3);
+            continue;
+        }
+        let Some(gt) = html[lt..].find('>').map(|i| lt + i) else {
+            break;
+        };
+        pos = gt + 1;
+        let raw = &html[lt + 1..gt];
+        let closing = raw.starts_with('/');
+        let name_lower = lower[lt + 1..gt].trim_start_matches('/');
+        let name = name_lower
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+
+        if let Some((skipped, depth)) = skip_depth {
+            if name == skipped {
+                let depth = if closing { depth - 1 } else { depth + 1 };
+                skip_depth = (depth > 0).then_some((skipped, depth));
+            }
+            continue;
+        }
+        if let Some(skipped) = SKIPPED_ELEMENTS.iter().find(|s| **s == name) {
+            if !closing && !raw.ends_with('/') {
+                skip_depth = Some((skipped, 1));
+            }
+            continue;
+        }
+
+        flush(&mut text, &mut out);
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                if closing {
+                    if let Some(start) = heading_start.take() {
+                        let heading = out[start..].trim().to_string();
+                        if first_heading.is_none() && !heading.is_empty() {
+                            first_heading = Some(heading);
+                        }
+                    }
+                    out.push_str("\n\n");
+                } else {
+                    let level = name[1..].parse().unwrap_or(1);
+                    out.push_str("\n\n");
+                    out.push_str(&"#".repeat(level));
+                    out.push(' ');
+                    heading_start = Some(out.len());
+                }
+            }
+            "li" if !closing => out.push_str("\n- "),
+            "br" => out.push('\n'),
+            "a" if !closing => {
+                link = attribute(raw, "href")
+                    .filter(|href| href.starts_with("http://") || href.starts_with("https://"))
+                    .map(|href| (decode_entities(href), out.len()));
+                if link.is_some() {
+                    out.push('[');
+                }
+            }
+            "a" => {
+                if let Some((href, start)) = link.take() {
+                    if out[start + 1..].trim().is_empty() {
+                        out.truncate(start);
+                    } else {
+                        out.truncate(out.trim_end().len());
+                        out.push_str(&format!("]({})", href));
+                    }
+                }
+            }
+            _ if BLOCK_ELEMENTS.contains(&name) => out.push_str("\n\n"),
+            _ => {}
+        }
+    }
+    flush(&mut text, &mut out);
+
+    // Trim each line and collapse runs of blank lines
+    let mut markdown = String::new();
+    let mut blank = true;
+    for line in out.lines().map(str::trim) {
+        if line.is_empty() || line == "-" || line.trim_start_matches('#').is_empty() {
+            if !blank {
+                markdown.push('\n');
+            }
+            blank = true;
+            continue;
+        }
+        markdown.push_str(line);
+        markdown.push('\n');
+        blank = false;
+    }
+    (title.or(first_heading), markdown.trim().to_string())
+}
+
+/// Lowercase, hyphenated file stem for a page title
+fn slug(title: &str) -> String {
+    let slug = title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let slug: String = slug.chars().take(60).collect();
+    match slug.trim_end_matches('-') {
+        "" => "page".to_string(),
+        s => s.to_string(),
+    }
+}
+
+/// New `references/<ulid>/` directory in a session directory for files
+/// kept with the session; creates it
+pub(crate) fn new_reference_dir(session_dir: &Path) -> std::io::Result<PathBuf> {
+    let dir = session_dir.join(REFERENCES_DIR).join(Ulid::new().to_string());
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// New `references/<ulid>/<slug>.md` path in a session directory for a
+/// document saved from outside the filesystem; creates its directory
+pub(crate) fn reference_file_path(session_dir: &Path, title: &str) -> std::io::Result<PathBuf> {
+    Ok(new_reference_dir(session_dir)?.join(format!("{}.md", slug(title))))
+}
+
+/// Fetch `url`, save it as Markdown under the session's `references/`
+/// directory, index it and record it in session.json with its URL
+pub async fn add_url_reference(
+    session_id: &str,
+    url: &str,
+    persistence: DocumentPersistence,
+) -> Result<ReferenceDocument, WebReferenceError> {
+    let page = fetch_page(url).await?;
+    let session_dir = get_session_dir_cli(session_id)?;
+    let title = page.title.clone().unwrap_or_else(|| page.url.clone());
+
+    let path = reference_file_path(&session_dir, &title)?;
+    fs::write(&path, format!("# {}\n\nSource: <{}>\n\n{}\n", title, page.url, page.markdown))?;
+
+    let doc = add_reference(session_id, &path.to_string_lossy(), persistence).await?;
+    update_session_file(&session_dir.join("session.json"), |s| {
+        s.reference_docs.push(SessionReferenceDoc {
+            id: doc.id.clone(),
+            filename: doc.filename.clone(),
+            path: doc.path.clone(),
+            token_count: doc.loaded_tokens,
+            handling: doc.handling.as_str().to_string(),
+            persistence: doc.persistence.as_str().to_string(),
+            url: Some(page.url.clone()),
+            citation: None,
+        });
+        Ok(())
+    })?;
+    info!(session_id = %session_id, url = %page.url, doc_id = %doc.id, "Added URL reference");
+    Ok(doc)
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub async fn documents_add_url_reference(
+    session_id: String,
+    url: String,
+    persistence: Option<DocumentPersistence>,
+) -> Result<ReferenceDocument, WebReferenceError> {
+    validate_session_id(&session_id)?;
+    add_url_reference(&session_id, &url, document_persistence(&session_id, persistence)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_to_markdown() {
+        let html = r#"<html><head><title>Pricing &amp; Moats</title><style>p{}</style></head>
+            <body><nav><a href="/">Home</a></nav>
+            <article><h1>Pricing power</h1>
+            <p>Firms with   <b>pricing power</b> can raise prices&nbsp;without losing share.</p>
+            <script>track()</script>
+            <ul><li>See <a href="https://example.com/buffett">Buffett</a></li><li>Local <a href="/x">link</a></li></ul>
+            <!-- <p>hidden</p> --><aside>Related posts</aside>
+            <h2>Evidence</h2><p>Q3&#8217;s retention was 94%<br>Churn fell</p></article>
+            <footer>© 2024</footer></body></html>"#;
+        let (title, markdown) = html_to_markdown(html);
+        assert_eq!(title.as_deref(), Some("Pricing & Moats"));
+        assert_eq!(
+            markdown,
+            "# Pricing power\n\n\
+             Firms with pricing power can raise prices without losing share.\n\n\
+             - See [Buffett](https://example.com/buffett)\n\
+             - Local link\n\n\
+             ## Evidence\n\n\
+             Q3’s retention was 94%\n\
+             Churn fell"
+        );
+    }
+
+    #[test]
+    fn test_title_falls_back_to_heading() {
+        let (title, markdown) = html_to_markdown("<div><h2>Only heading</h2>text</div>");
+        assert_eq!(title.as_deref(), Some("Only heading"));
+        assert_eq!(markdown, "## Only heading\n\ntext");
+    }
+
+    #[test]
+    fn test_slug_and_url_validation() {
+        assert_eq!(slug("Pricing & Moats: A Primer!"), "pricing-moats-a-primer");
+        assert_eq!(slug("???"), "page");
+        assert!(validate_url("https://example.com/a").is_ok());
+        assert!(validate_url("file:///etc/passwd").is_err());
+        assert!(validate_url("not a url").is_err());
+    }
+}
@@ -0,0 +1,159 @@
+//! App Events
+//!
+//! Process-wide app handle for emitting events from code that isn't handed
+//! one (background jobs, indexing). Until `set_app_handle` is called — the
+//! CLI, tests — events are dropped. With the `rest-api` feature, emitted
+//! events are also broadcast to in-process subscribers (the REST API's
+//! event stream).
+
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tracing::warn;
+
+/// Event carrying an `IndexProgress`
+pub const INDEX_PROGRESS_EVENT: &str = "index://progress";
+/// Minimum gap between intermediate progress events
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Events buffered per subscriber before it starts lagging
+#[cfg(feature = "rest-api")]
+const BUS_CAPACITY: usize = 256;
+
+#[cfg(feature = "rest-api")]
+static BUS: std::sync::LazyLock<tokio::sync::broadcast::Sender<BusEvent>> =
+    std::sync::LazyLock::new(|| tokio::sync::broadcast::channel(BUS_CAPACITY).0);
+
+/// An emitted event as seen by in-process subscribers
+#[cfg(feature = "rest-api")]
+#[derive(Debug, Clone, Serialize)]
+pub struct BusEvent {
+    pub event: String,
+    pub payload: serde_json::Value,
+}
+
+/// Receive every event emitted in this process from now on
+#[cfg(feature = "rest-api")]
+pub fn subscribe() -> tokio::sync::broadcast::Receiver<BusEvent> {
+    BUS.subscribe()
+}
+
+/// Register the app handle used to emit events
+pub fn set_app_handle(app: AppHandle) {
+    let _ = APP.set(app);
+}
+
+/// Emit `payload` to the frontend (no-op without an app handle)
+pub fn emit<S: Serialize + Clone>(event: &str, payload: S) {
+    #[cfg(feature = "rest-api")]
+    if BUS.receiver_count() > 0 {
+        if let Ok(payload) = serde_json::to_value(&payload) {
+            let _ = BUS.send(BusEvent { event: event.to_string(), payload });
+        }
+    }
+    if let Some(app) = APP.get() {
+        if let Err(e) = app.emit(event, payload) {
+            warn!(event = %event, error = %e, "Failed to emit event");
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexOperation {
+    /// Parsing vault notes
+    Vault,
+    /// Upserting vault notes to Chroma
+    VaultChroma,
+    /// Chunking and embedding a reference document
+    Document,
+}
+
+/// Payload of `INDEX_PROGRESS_EVENT`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexProgress {
+    pub operation: IndexOperation,
+    /// Vault path or document path
+    pub target: String,
+    pub processed: u32,
+    pub total: u32,
+    pub done: bool,
+}
+
+/// Emits throttled `INDEX_PROGRESS_EVENT`s for one operation: the first and
+/// last always go out, intermediate ones at most every `PROGRESS_INTERVAL`
+pub struct ProgressReporter {
+    progress: IndexProgress,
+    last_emit: Option<Instant>,
+}
+
+impl ProgressReporter {
+    pub fn start(operation: IndexOperation, target: impl Into<String>, total: usize) -> Self {
+        let mut reporter = Self {
+            progress: IndexProgress {
+                operation,
+                target: target.into(),
+                processed: 0,
+                total: total as u32,
+                done: false,
+            },
+            last_emit: None,
+        };
+        reporter.emit_now();
+        reporter
+    }
+
+    fn emit_now(&mut self) {
+        self.last_emit = Some(Instant::now());
+        emit(INDEX_PROGRESS_EVENT, self.progress.clone());
+    }
+
+    fn due(&self) -> bool {
+        self.last_emit.is_none_or(|t| t.elapsed() >= PROGRESS_INTERVAL)
+    }
+
+    pub fn processed(&self) -> u32 {
+        self.progress.processed
+    }
+
+    /// Set the processed count
+    pub fn set(&mut self, processed: u32) {
+        self.progress.processed = processed;
+        if self.due() {
+            self.emit_now();
+        }
+    }
+
+    /// Add to the processed count
+    pub fn advance(&mut self, by: u32) {
+        self.set(self.progress.processed + by);
+    }
+
+    pub fn finish(mut self) {
+        self.progress.done = true;
+        self.emit_now();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reporter_throttles_intermediate_events() {
+        let mut reporter = ProgressReporter::start(IndexOperation::Vault, "/vault", 3);
+        assert!(!reporter.due());
+        reporter.advance(1);
+        assert_eq!(reporter.progress.processed, 1);
+
+        reporter.last_emit = Some(Instant::now() - PROGRESS_INTERVAL);
+        assert!(reporter.due());
+        reporter.advance(2);
+        assert!(!reporter.due());
+        assert_eq!(reporter.progress.processed, 3);
+    }
+}
@@ -0,0 +1,252 @@
+//! Git Context
+//!
+//! Project-local sessions usually reason about a codebase that keeps moving.
+//! New passes and claims are stamped with the repo HEAD when a session is
+//! written, so each claim records the commit it was formed against, and
+//! `git_context` diffs the working directory between consecutive passes
+//! (and from the last pass to the current working tree).
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+use tauri::AppHandle;
+use thiserror::Error;
+use tracing::debug;
+
+use crate::session::{Session, SessionError};
+
+#[derive(Error, Debug)]
+pub enum GitError {
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error("Session is not project-local: {0}")]
+    NotProjectLocal(String),
+    #[error("Not a git repository: {0}")]
+    NotARepo(String),
+    #[error("git failed: {0}")]
+    Git(String),
+}
+
+impl Serialize for GitError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Run git in `dir`, returning trimmed stdout
+fn git(dir: &Path, args: &[&str]) -> Result<String, GitError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| GitError::Git(e.to_string()))?;
+    if !output.status.success() {
+        return Err(GitError::Git(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// HEAD commit of the repo containing `dir`, if it is one
+pub fn head_commit(dir: &Path) -> Option<String> {
+    git(dir, &["rev-parse", "HEAD"]).ok().filter(|c| !c.is_empty())
+}
+
+/// Stamp passes and claims that have no commit yet with the repo HEAD.
+/// No-op for sessions that aren't project-local or whose working dir
+/// isn't a repo. Returns whether anything was stamped.
+pub fn stamp_commits(session: &mut Session) -> bool {
+    if !session.is_project_local {
+        return false;
+    }
+    let unstamped = session.passes.iter().any(|p| p.commit.is_none())
+        || session.claims.iter().any(|c| c.commit.is_none());
+    if !unstamped {
+        return false;
+    }
+    let Some(head) = head_commit(Path::new(&session.working_dir)) else {
+        return false;
+    };
+    for pass in session.passes.iter_mut().filter(|p| p.commit.is_none()) {
+        pass.commit = Some(head.clone());
+    }
+    for claim in session.claims.iter_mut().filter(|c| c.commit.is_none()) {
+        claim.commit = Some(head.clone());
+    }
+    debug!(session_id = %session.id, commit = %head, "Stamped session with git HEAD");
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChange {
+    pub path: String,
+    /// `None` for binary files
+    pub additions: Option<u32>,
+    pub deletions: Option<u32>,
+}
+
+/// Parse `git diff --numstat` output
+fn parse_numstat(output: &str) -> Vec<FileChange> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '\t');
+            let additions = parts.next()?.parse().ok();
+            let deletions = parts.next()?.parse().ok();
+            let path = parts.next()?.to_string();
+            Some(FileChange { path, additions, deletions })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PassCommit {
+    pub pass_id: String,
+    pub pass_type: String,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub commit: Option<String>,
+}
+
+/// What changed between two passes; `to_pass` is `None` for the working tree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PassChange {
+    pub from_pass: String,
+    pub to_pass: Option<String>,
+    pub from_commit: String,
+    pub to_commit: Option<String>,
+    pub files: Vec<FileChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitContext {
+    pub session_id: String,
+    pub repo_root: String,
+    pub branch: Option<String>,
+    pub head: Option<String>,
+    /// Uncommitted changes in the working tree
+    pub dirty: bool,
+    pub passes: Vec<PassCommit>,
+    pub changes: Vec<PassChange>,
+    /// Claim IDs by the commit they were formed against
+    pub claims_by_commit: BTreeMap<String, Vec<String>>,
+}
+
+/// Git history of a project-local session's working directory
+pub fn git_context(session: &Session) -> Result<GitContext, GitError> {
+    if !session.is_project_local {
+        return Err(GitError::NotProjectLocal(session.id.clone()));
+    }
+    let dir = Path::new(&session.working_dir);
+    let repo_root = git(dir, &["rev-parse", "--show-toplevel"])
+        .map_err(|_| GitError::NotARepo(session.working_dir.clone()))?;
+    let head = head_commit(dir);
+    let branch = git(dir, &["rev-parse", "--abbrev-ref", "HEAD"]).ok().filter(|b| b != "HEAD");
+    let dirty = !git(dir, &["status", "--porcelain"])?.is_empty();
+
+    let passes: Vec<PassCommit> = session
+        .passes
+        .iter()
+        .map(|p| PassCommit {
+            pass_id: p.id.clone(),
+            pass_type: p.pass_type.clone(),
+            started_at: p.started_at,
+            commit: p.commit.clone(),
+        })
+        .collect();
+
+    // Consecutive stamped passes whose commit moved, then the last one to the working tree
+    let stamped: Vec<(&str, &str)> = passes
+        .iter()
+        .filter_map(|p| p.commit.as_deref().map(|c| (p.pass_id.as_str(), c)))
+        .collect();
+    let mut changes = Vec::new();
+    for pair in stamped.windows(2) {
+        let ((from_pass, from), (to_pass, to)) = (pair[0], pair[1]);
+        if from == to {
+            continue;
+        }
+        changes.push(PassChange {
+            from_pass: from_pass.to_string(),
+            to_pass: Some(to_pass.to_string()),
+            from_commit: from.to_string(),
+            to_commit: Some(to.to_string()),
+            files: parse_numstat(&git(dir, &["diff", "--numstat", from, to])?),
+        });
+    }
+    if let Some((from_pass, from)) = stamped.last() {
+        let files = parse_numstat(&git(dir, &["diff", "--numstat", from])?);
+        if !files.is_empty() {
+            changes.push(PassChange {
+                from_pass: from_pass.to_string(),
+                to_pass: None,
+                from_commit: from.to_string(),
+                to_commit: None,
+                files,
+            });
+        }
+    }
+
+    let mut claims_by_commit: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for claim in &session.claims {
+        if let Some(commit) = &claim.commit {
+            claims_by_commit.entry(commit.clone()).or_default().push(claim.id.clone());
+        }
+    }
+
+    Ok(GitContext {
+        session_id: session.id.clone(),
+        repo_root,
+        branch,
+        head,
+        dirty,
+        passes,
+        changes,
+        claims_by_commit,
+    })
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn get_git_context(app: AppHandle, session_id: String) -> Result<GitContext, GitError> {
+    let session = crate::session::load_session(app, session_id)?;
+    git_context(&session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+
+    #[test]
+    fn test_parse_numstat() {
+        let output = "3\t1\tsrc/lib.rs\n-\t-\tassets/logo.png\n0\t12\tdocs/a b.md";
+        assert_eq!(parse_numstat(output), vec![
+            FileChange { path: "src/lib.rs".into(), additions: Some(3), deletions: Some(1) },
+            FileChange { path: "assets/logo.png".into(), additions: None, deletions: None },
+            FileChange { path: "docs/a b.md".into(), additions: Some(0), deletions: Some(12) },
+        ]);
+    }
+
+    #[test]
+    fn test_stamp_skips_global_sessions() {
+        let mut session: Session = test_session(serde_json::json!({
+            "id": "git-test",
+            "title": "Global",
+            "created": chrono::Utc::now(),
+            "updated": chrono::Utc::now(),
+            "claims": [{ "id": "c1", "content": "x", "sourceId": "s", "marker": null, "createdAt": chrono::Utc::now() }],
+        }));
+        assert!(!stamp_commits(&mut session));
+        assert!(session.claims[0].commit.is_none());
+        assert!(matches!(git_context(&session), Err(GitError::NotProjectLocal(_))));
+    }
+}
@@ -0,0 +1,217 @@
+//! Headless Session Runner
+//!
+//! `dialectic run` drives a whole session without a terminal: create the
+//! session, prepare its launch context, run the CLI tool non-interactively
+//! (`<tool> -p` with the prompt on stdin), capture the conversation ID and
+//! pull semantic-marker lines out of the result as claims. Meant for batch
+//! and CI runs, so everything ends in a single serializable summary.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+use tracing::{info, warn};
+use ulid::Ulid;
+
+use crate::chroma::jsonl_miner::{find_conversation_jsonl, mine_session_conversations};
+use crate::session::claim_source::{transcript_messages, SourceSpan};
+use crate::session::lock::update_session_file;
+use crate::session::markers::extract_markers;
+use crate::session::{
+    capture_conversation_id_in, create_session_in, get_app_data_dir_cli, get_session_dir_cli, load_session_cli,
+    prepare_launch_in, Claim, CreateSessionInput, Pass, SessionError, SessionMode,
+};
+
+/// Default limit on a single run
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// Source ID of claims extracted by a headless run
+const SOURCE_ID: &str = "headless_run";
+/// Chars of the final output kept in the summary
+const EXCERPT_CHARS: usize = 500;
+
+#[derive(Error, Debug)]
+pub enum HeadlessError {
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to start {tool}: {error}")]
+    Spawn { tool: String, error: std::io::Error },
+    #[error("Run timed out after {0}s")]
+    Timeout(u64),
+}
+
+impl Serialize for HeadlessError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RunOptions {
+    pub title: String,
+    pub prompt: String,
+    pub mode: Option<SessionMode>,
+    pub working_dir: Option<String>,
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeadlessRunResult {
+    pub session_id: String,
+    pub conversation_id: Option<String>,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub duration_secs: f64,
+    pub claims_added: usize,
+    /// Claims found per marker, including ones already in the session
+    pub marker_counts: BTreeMap<String, usize>,
+    pub output_excerpt: String,
+}
+
+/// Claims found in the run: from assistant turns of the transcript when it
+/// can be found (with a span back to the turn), else from the CLI's stdout
+fn extract_claims(stdout: &str, conversation_id: Option<&str>, working_dir: &str) -> Vec<Claim> {
+    let claim = |(marker, content): (&str, String), span: Option<SourceSpan>| Claim {
+        id: Ulid::new().to_string(),
+        content,
+        source_id: SOURCE_ID.to_string(),
+        marker: Some(format!("[{}]", marker)),
+        created_at: Utc::now(),
+        source_span: span,
+        evidence_score: None,
+        commit: None,
+    };
+
+    let transcript = conversation_id
+        .and_then(|id| find_conversation_jsonl(id, working_dir).map(|path| (id, path)))
+        .and_then(|(id, path)| fs::read_to_string(path).ok().map(|jsonl| (id, jsonl)));
+    let Some((conversation_id, jsonl)) = transcript else {
+        return extract_markers(stdout).into_iter().map(|m| claim(m, None)).collect();
+    };
+
+    transcript_messages(&jsonl)
+        .into_iter()
+        .enumerate()
+        .filter(|(_, (role, _))| role == "assistant")
+        .flat_map(|(turn, (_, text))| {
+            extract_markers(&text).into_iter().map(move |m| {
+                let span = SourceSpan::Transcript {
+                    conversation_id: Some(conversation_id.to_string()),
+                    turn: turn as u32,
+                };
+                claim(m, Some(span))
+            })
+        })
+        .collect()
+}
+
+/// Run a new session end to end and summarize the result
+pub async fn run_headless(options: RunOptions) -> Result<HeadlessRunResult, HeadlessError> {
+    let app_data = get_app_data_dir_cli()?;
+    let session = create_session_in(&app_data, CreateSessionInput {
+        title: options.title,
+        mode: options.mode,
+        working_dir: options.working_dir,
+        category: None,
+        summary: None,
+    })?;
+    let ctx = prepare_launch_in(app_data.clone(), session.id.clone()).await?;
+    let (tool, args) = ctx
+        .claude_command
+        .split_first()
+        .ok_or_else(|| SessionError::InvalidPath("empty CLI command".to_string()))?;
+    info!(session_id = %session.id, tool = %tool, "Starting headless run");
+
+    let started = Instant::now();
+    let started_at = Utc::now();
+    let mut child = tokio::process::Command::new(tool)
+        .args(args)
+        .arg("-p")
+        .current_dir(&ctx.working_dir)
+        .envs(&ctx.env_vars)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|error| HeadlessError::Spawn { tool: tool.clone(), error })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(options.prompt.as_bytes()).await?;
+    }
+    let output = tokio::time::timeout(options.timeout, child.wait_with_output())
+        .await
+        .map_err(|_| HeadlessError::Timeout(options.timeout.as_secs()))??;
+    let duration = started.elapsed();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        warn!(session_id = %session.id, status = %output.status, "Headless run exited with an error");
+    }
+
+    let conversation_id = capture_conversation_id_in(app_data, session.id.clone()).await?;
+    if conversation_id.is_some() {
+        mine_session_conversations(&load_session_cli(&session.id)?).await;
+    }
+    let claims = extract_claims(&stdout, conversation_id.as_deref(), &ctx.working_dir);
+    let mut marker_counts = BTreeMap::new();
+    for claim in &claims {
+        let marker = claim.marker.as_deref().unwrap_or_default().trim_matches(['[', ']']);
+        *marker_counts.entry(marker.to_string()).or_insert(0) += 1;
+    }
+
+    let mut claims_added = 0;
+    let session_path = get_session_dir_cli(&session.id)?.join("session.json");
+    update_session_file(&session_path, |s| {
+        for claim in claims {
+            if !s.claims.iter().any(|c| c.content == claim.content) {
+                s.claims.push(claim);
+                claims_added += 1;
+            }
+        }
+        s.passes.push(Pass {
+            id: Ulid::new().to_string(),
+            pass_type: "headless".to_string(),
+            started_at,
+            completed_at: Some(Utc::now()),
+            token_count: None,
+            commit: None,
+        });
+        Ok(())
+    })?;
+
+    let trimmed = stdout.trim();
+    let skip = trimmed.chars().count().saturating_sub(EXCERPT_CHARS);
+    info!(session_id = %session.id, claims_added, secs = duration.as_secs(), "Finished headless run");
+    Ok(HeadlessRunResult {
+        session_id: session.id,
+        conversation_id,
+        success: output.status.success(),
+        exit_code: output.status.code(),
+        duration_secs: duration.as_secs_f64(),
+        claims_added,
+        marker_counts,
+        output_excerpt: trimmed.chars().skip(skip).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claims_from_stdout_without_transcript() {
+        let claims = extract_claims("[PATTERN] Same as 2019", None, "/tmp");
+        assert_eq!(claims.len(), 1);
+        assert_eq!(claims[0].marker.as_deref(), Some("[PATTERN]"));
+        assert_eq!(claims[0].source_id, SOURCE_ID);
+        assert!(claims[0].source_span.is_none());
+    }
+}
@@ -0,0 +1,343 @@
+//! Background Job Queue
+//!
+//! Small in-process queue for long-running work (vault indexing, document
+//! chunking/embedding, JSONL mining, memory consolidation). Each job runs
+//! as a tokio task, at most `MAX_RUNNING_JOBS` at a time; the rest wait as
+//! `Queued`. Status changes and progress are emitted as `JOB_PROGRESS_EVENT`,
+//! and finished jobs stay listed until `MAX_FINISHED_JOBS` newer ones push
+//! them out.
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::LazyLock;
+use thiserror::Error;
+use tokio::sync::Semaphore;
+use tracing::{debug, info, warn};
+use ulid::Ulid;
+
+use crate::events;
+
+/// Event carrying a `JobInfo` whenever a job changes
+pub const JOB_PROGRESS_EVENT: &str = "job-progress";
+/// Jobs running at once; the rest stay queued
+const MAX_RUNNING_JOBS: usize = 2;
+/// Finished jobs kept for `list_jobs`
+const MAX_FINISHED_JOBS: usize = 50;
+
+static JOBS: LazyLock<Mutex<HashMap<String, JobEntry>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+static RUN_PERMITS: LazyLock<Semaphore> = LazyLock::new(|| Semaphore::new(MAX_RUNNING_JOBS));
+
+#[derive(Error, Debug)]
+pub enum JobError {
+    #[error("Job not found: {0}")]
+    NotFound(String),
+    #[error("Job already finished: {0}")]
+    AlreadyFinished(String),
+}
+
+impl Serialize for JobError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    VaultIndex,
+    DocumentIndex,
+    JsonlMining,
+    MemoryConsolidation,
+    KnowledgeGraph,
+    CalendarExport,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(self, JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled)
+    }
+}
+
+/// Snapshot of a job for the UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobInfo {
+    pub id: String,
+    pub kind: JobKind,
+    /// What the job works on (vault path, file name, session id)
+    pub label: String,
+    pub status: JobStatus,
+    /// 0.0–1.0
+    pub progress: f32,
+    pub message: Option<String>,
+    pub error: Option<String>,
+    /// Job output on completion (e.g. the added reference document)
+    pub result: Option<Value>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+struct JobEntry {
+    info: JobInfo,
+    /// Aborts the job's task
+    abort: Option<Box<dyn FnOnce() + Send>>,
+}
+
+/// Passed to job work for progress reporting
+#[derive(Debug, Clone)]
+pub struct JobHandle {
+    id: String,
+}
+
+impl JobHandle {
+    /// Report progress (clamped to 0.0–1.0) with an optional stage message
+    pub fn progress(&self, fraction: f32, message: impl Into<String>) {
+        update(&self.id, |info| {
+            info.progress = fraction.clamp(0.0, 1.0);
+            info.message = Some(message.into());
+        });
+    }
+}
+
+fn emit(info: &JobInfo) {
+    events::emit(JOB_PROGRESS_EVENT, info);
+}
+
+/// Apply `f` to a job that hasn't finished, then emit it
+fn update(id: &str, f: impl FnOnce(&mut JobInfo)) {
+    let info = {
+        let mut jobs = JOBS.lock();
+        let Some(entry) = jobs.get_mut(id) else { return };
+        if entry.info.status.is_finished() {
+            return;
+        }
+        f(&mut entry.info);
+        entry.info.clone()
+    };
+    emit(&info);
+}
+
+/// Drop the oldest finished jobs beyond `MAX_FINISHED_JOBS`
+fn prune_finished(jobs: &mut HashMap<String, JobEntry>) {
+    let mut finished: Vec<(DateTime<Utc>, String)> = jobs
+        .values()
+        .filter(|e| e.info.status.is_finished())
+        .map(|e| (e.info.finished_at.unwrap_or(e.info.created_at), e.info.id.clone()))
+        .collect();
+    if finished.len() <= MAX_FINISHED_JOBS {
+        return;
+    }
+    finished.sort();
+    let excess = finished.len() - MAX_FINISHED_JOBS;
+    for (_, id) in finished.into_iter().take(excess) {
+        jobs.remove(&id);
+    }
+}
+
+fn finish(id: &str, outcome: Result<Option<Value>, String>) {
+    update(id, |info| {
+        info.finished_at = Some(Utc::now());
+        match outcome {
+            Ok(result) => {
+                info.status = JobStatus::Completed;
+                info.progress = 1.0;
+                info.result = result;
+            }
+            Err(e) => {
+                info.status = JobStatus::Failed;
+                info.error = Some(e);
+            }
+        }
+    });
+    let mut jobs = JOBS.lock();
+    if let Some(entry) = jobs.get_mut(id) {
+        entry.abort = None;
+    }
+    prune_finished(&mut jobs);
+}
+
+/// Queue `work` as a background job and return its id
+pub fn submit<F, Fut>(kind: JobKind, label: impl Into<String>, work: F) -> String
+where
+    F: FnOnce(JobHandle) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<Option<Value>, String>> + Send + 'static,
+{
+    enqueue(kind, label.into(), false, work)
+}
+
+/// Like `submit`, but if a job with the same kind and label is still queued
+/// or running, its id is returned and `work` is dropped. For work that
+/// re-reads its inputs, where a second run would repeat the first.
+pub fn submit_unique<F, Fut>(kind: JobKind, label: impl Into<String>, work: F) -> String
+where
+    F: FnOnce(JobHandle) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<Option<Value>, String>> + Send + 'static,
+{
+    enqueue(kind, label.into(), true, work)
+}
+
+fn enqueue<F, Fut>(kind: JobKind, label: String, unique: bool, work: F) -> String
+where
+    F: FnOnce(JobHandle) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<Option<Value>, String>> + Send + 'static,
+{
+    let info = {
+        let mut jobs = JOBS.lock();
+        let active = jobs
+            .values()
+            .find(|e| unique && e.info.kind == kind && e.info.label == label && !e.info.status.is_finished());
+        if let Some(active) = active {
+            debug!(job_id = %active.info.id, kind = ?kind, label = %label, "Job already active");
+            return active.info.id.clone();
+        }
+        let info = JobInfo {
+            id: Ulid::new().to_string(),
+            kind,
+            label,
+            status: JobStatus::Queued,
+            progress: 0.0,
+            message: None,
+            error: None,
+            result: None,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+        };
+        jobs.insert(info.id.clone(), JobEntry { info: info.clone(), abort: None });
+        info
+    };
+    emit(&info);
+
+    let id = info.id.clone();
+    let job = JobHandle { id: id.clone() };
+    let handle = tauri::async_runtime::spawn(async move {
+        // The semaphore is never closed
+        let _permit = RUN_PERMITS.acquire().await.ok();
+        update(&job.id, |info| {
+            info.status = JobStatus::Running;
+            info.started_at = Some(Utc::now());
+        });
+        let job_id = job.id.clone();
+        let outcome = work(job).await;
+        match &outcome {
+            Ok(_) => info!(job_id = %job_id, kind = ?kind, "Job completed"),
+            Err(e) => warn!(job_id = %job_id, kind = ?kind, error = %e, "Job failed"),
+        }
+        finish(&job_id, outcome);
+    });
+
+    if let Some(entry) = JOBS.lock().get_mut(&id) {
+        if !entry.info.status.is_finished() {
+            entry.abort = Some(Box::new(move || handle.abort()));
+        }
+    }
+    id
+}
+
+/// All known jobs, newest first
+pub fn list() -> Vec<JobInfo> {
+    let mut jobs: Vec<JobInfo> = JOBS.lock().values().map(|e| e.info.clone()).collect();
+    jobs.sort_by_key(|j| std::cmp::Reverse(j.created_at));
+    jobs
+}
+
+/// Cancel a queued or running job. The task is aborted at its next await
+/// point; blocking work already handed to a thread runs to completion but
+/// its result is discarded.
+pub fn cancel(id: &str) -> Result<JobInfo, JobError> {
+    let info = {
+        let mut jobs = JOBS.lock();
+        let entry = jobs.get_mut(id).ok_or_else(|| JobError::NotFound(id.to_string()))?;
+        if entry.info.status.is_finished() {
+            return Err(JobError::AlreadyFinished(id.to_string()));
+        }
+        if let Some(abort) = entry.abort.take() {
+            abort();
+        }
+        entry.info.status = JobStatus::Cancelled;
+        entry.info.finished_at = Some(Utc::now());
+        let info = entry.info.clone();
+        prune_finished(&mut jobs);
+        info
+    };
+    info!(job_id = %id, kind = ?info.kind, "Job cancelled");
+    emit(&info);
+    Ok(info)
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn list_jobs() -> Vec<JobInfo> {
+    list()
+}
+
+#[tauri::command]
+pub fn cancel_job(job_id: String) -> Result<JobInfo, JobError> {
+    cancel(&job_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    async fn wait_finished(id: &str) -> JobInfo {
+        for _ in 0..200 {
+            if let Some(info) = list().into_iter().find(|j| j.id == id && j.status.is_finished()) {
+                return info;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("job {} did not finish", id);
+    }
+
+    #[tokio::test]
+    async fn test_job_completes_with_result() {
+        let id = submit(JobKind::JsonlMining, "jobs-test-complete", |job| async move {
+            job.progress(0.5, "halfway");
+            Ok(Some(serde_json::json!({ "sources": 3 })))
+        });
+        let info = wait_finished(&id).await;
+        assert_eq!(info.status, JobStatus::Completed);
+        assert_eq!(info.progress, 1.0);
+        assert_eq!(info.result, Some(serde_json::json!({ "sources": 3 })));
+        assert!(matches!(cancel(&id), Err(JobError::AlreadyFinished(_))));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_submit_and_cancel() {
+        let work = |_job: JobHandle| async move {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            Ok(None)
+        };
+        let id = submit_unique(JobKind::VaultIndex, "jobs-test-cancel", work);
+        assert_eq!(submit_unique(JobKind::VaultIndex, "jobs-test-cancel", work), id);
+
+        let info = cancel(&id).unwrap();
+        assert_eq!(info.status, JobStatus::Cancelled);
+        assert!(matches!(cancel("missing"), Err(JobError::NotFound(_))));
+        // A new job can be queued once the old one is finished
+        let next = submit_unique(JobKind::VaultIndex, "jobs-test-cancel", |_job| async { Ok(None) });
+        assert_ne!(next, id);
+        wait_finished(&next).await;
+    }
+}
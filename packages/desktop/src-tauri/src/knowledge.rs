@@ -0,0 +1,564 @@
+//! Knowledge Graph
+//!
+//! A cross-session graph of claims, theses, memories, vault notes and the
+//! sources claims cite. Nodes are linked by structure (a session contains
+//! its claims, a claim cites its source, a memory derives from its claim)
+//! and by content: nodes mentioning the same entity (a content word or
+//! two-word phrase such as "pricing strategy") link to a shared entity
+//! node, and nodes with overlapping vocabulary get a `similar` edge.
+//!
+//! The graph is rebuilt on demand, persisted to
+//! `<app data>/knowledge_graph.json` and queried with `knowledge_neighbors`,
+//! which accepts a node ID or free text naming an entity.
+
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+use thiserror::Error;
+use tracing::{debug, info};
+
+use crate::chroma::memory::{list_memories, MemoryRecord, MemoryType, ReviewStatus};
+use crate::config::workspace::effective_preferences;
+use crate::jobs::{self, JobKind};
+use crate::obsidian::indexer::{with_vault_index, NoteIndex};
+use crate::session::citations::{claim_sources, CitationKind, ClaimSource};
+use crate::session::{get_app_data_dir_cli, list_sessions_cli, Session, SessionError};
+
+const GRAPH_FILE: &str = "knowledge_graph.json";
+/// Memories read per type when building
+const MEMORY_LIMIT: u32 = 2000;
+/// Minimum term overlap (Jaccard) for a `similar` edge
+const SIMILARITY_THRESHOLD: f32 = 0.35;
+/// Entities mentioned by more nodes than this are too common to link on
+const MAX_ENTITY_NODES: usize = 40;
+/// Shortest word treated as a content word
+const MIN_TERM_LEN: usize = 4;
+const DEFAULT_DEPTH: u32 = 1;
+const MAX_DEPTH: u32 = 3;
+const DEFAULT_LIMIT: usize = 100;
+
+const STOPWORDS: &[&str] = &[
+    "about", "above", "after", "again", "against", "also", "because", "been", "before", "being", "below",
+    "between", "both", "could", "does", "doing", "down", "during", "each", "even", "from", "further",
+    "have", "having", "here", "into", "just", "more", "most", "much", "must", "only", "other", "over",
+    "same", "session", "should", "some", "such", "than", "that", "their", "them", "then", "there",
+    "these", "they", "this", "those", "through", "under", "until", "very", "well", "were", "what",
+    "when", "where", "which", "while", "will", "with", "within", "without", "would", "your",
+];
+
+/// Last loaded or built graph
+static GRAPH: LazyLock<RwLock<Option<Arc<KnowledgeGraph>>>> = LazyLock::new(|| RwLock::new(None));
+
+#[derive(Error, Debug)]
+pub enum KnowledgeError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error("Knowledge node not found: {0}")]
+    NotFound(String),
+}
+
+impl Serialize for KnowledgeError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Session,
+    Claim,
+    Thesis,
+    Memory,
+    Note,
+    WebSource,
+    Document,
+    File,
+    Entity,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    /// Session → its claims and thesis
+    Contains,
+    /// Claim → the source it was drawn from
+    Cites,
+    /// Memory → the claim or session it was extracted from
+    Derived,
+    /// Node → an entity it mentions
+    Mentions,
+    /// Nodes with overlapping vocabulary
+    Similar,
+}
+
+/// A node of the graph. IDs: `session:{id}`, `claim:{session}::{claim}`,
+/// `thesis:{session}`, `memory:{id}`, `note:{path}`, `web:{url}`,
+/// `doc:{id}`, `file:{path}` and `entity:{term}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KnowledgeNode {
+    pub id: String,
+    pub kind: NodeKind,
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+}
+
+/// Undirected link between two nodes; `weight` is in (0, 1]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KnowledgeEdge {
+    pub source: String,
+    pub target: String,
+    pub kind: EdgeKind,
+    pub weight: f32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KnowledgeGraph {
+    pub built_at: Option<DateTime<Utc>>,
+    pub nodes: Vec<KnowledgeNode>,
+    pub edges: Vec<KnowledgeEdge>,
+}
+
+/// A node reached from the queried one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Neighbor {
+    pub node: KnowledgeNode,
+    /// Node it was reached from
+    pub via: String,
+    pub edge: EdgeKind,
+    pub weight: f32,
+    /// Hops from the queried node
+    pub distance: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Neighborhood {
+    pub node: KnowledgeNode,
+    pub neighbors: Vec<Neighbor>,
+}
+
+/// Graph size after a rebuild
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KnowledgeStats {
+    pub nodes: usize,
+    pub edges: usize,
+    pub entities: usize,
+}
+
+// ============ TEXT ============
+
+/// Plural to singular, roughly: "strategies" → "strategy", "margins" → "margin"
+fn stem(word: &str) -> String {
+    if let Some(stem) = word.strip_suffix("ies").filter(|s| s.len() >= 3) {
+        return format!("{}y", stem);
+    }
+    match word.strip_suffix('s') {
+        Some(stem) if word.len() > MIN_TERM_LEN && !stem.ends_with('s') => stem.to_string(),
+        _ => word.to_string(),
+    }
+}
+
+/// Content words of `text` in order; `None` marks a break (stopword,
+/// short word or punctuation) so phrases don't span it
+pub fn content_words(text: &str) -> Vec<Option<String>> {
+    let mut words = Vec::new();
+    for piece in text.split(|c: char| !c.is_alphanumeric() && c != '\'' && c != '-') {
+        let word = piece.trim_matches(['\'', '-']).to_lowercase();
+        let is_content = word.chars().count() >= MIN_TERM_LEN
+            && word.chars().any(|c| c.is_alphabetic())
+            && !STOPWORDS.contains(&word.as_str());
+        words.push(is_content.then(|| stem(&word)));
+    }
+    words
+}
+
+/// Entities mentioned in `text`: content words and adjacent pairs of them
+fn entities(text: &str) -> BTreeSet<String> {
+    let words = content_words(text);
+    let mut found: BTreeSet<String> = words.iter().flatten().cloned().collect();
+    for pair in words.windows(2) {
+        if let [Some(a), Some(b)] = pair {
+            found.insert(format!("{} {}", a, b));
+        }
+    }
+    found
+}
+
+/// Entity node ID for free text, e.g. "Pricing strategies" → `entity:pricing strategy`
+fn entity_id(text: &str) -> Option<String> {
+    let words: Vec<String> = content_words(text).into_iter().flatten().collect();
+    (!words.is_empty()).then(|| format!("entity:{}", words.join(" ")))
+}
+
+fn jaccard(a: &BTreeSet<String>, b: &BTreeSet<String>) -> f32 {
+    let shared = a.intersection(b).count();
+    if shared == 0 {
+        return 0.0;
+    }
+    shared as f32 / (a.len() + b.len() - shared) as f32
+}
+
+// ============ BUILD ============
+
+/// A session with the sources its claims cite
+pub struct SessionInput {
+    pub session: Session,
+    pub sources: Vec<ClaimSource>,
+}
+
+#[derive(Default)]
+struct Builder {
+    nodes: BTreeMap<String, KnowledgeNode>,
+    /// Text each content node is linked on
+    texts: BTreeMap<String, String>,
+    edges: Vec<KnowledgeEdge>,
+}
+
+impl Builder {
+    fn node(&mut self, id: String, kind: NodeKind, label: &str, session_id: Option<&str>, text: Option<&str>) {
+        if let Some(text) = text {
+            self.texts.entry(id.clone()).or_default().push_str(&format!(" {}", text));
+        }
+        self.nodes.entry(id.clone()).or_insert_with(|| KnowledgeNode {
+            id,
+            kind,
+            label: label.chars().take(200).collect(),
+            session_id: session_id.map(str::to_string),
+        });
+    }
+
+    fn edge(&mut self, source: &str, target: &str, kind: EdgeKind, weight: f32) {
+        self.edges.push(KnowledgeEdge { source: source.to_string(), target: target.to_string(), kind, weight });
+    }
+
+    fn session(&mut self, input: &SessionInput) {
+        let session = &input.session;
+        let session_node = format!("session:{}", session.id);
+        self.node(session_node.clone(), NodeKind::Session, &session.title, Some(&session.id), Some(&session.title));
+        for claim in &session.claims {
+            let id = format!("claim:{}::{}", session.id, claim.id);
+            self.node(id.clone(), NodeKind::Claim, &claim.content, Some(&session.id), Some(&claim.content));
+            self.edge(&session_node, &id, EdgeKind::Contains, 1.0);
+        }
+        if let Some(thesis) = &session.thesis {
+            let id = format!("thesis:{}", session.id);
+            self.node(id.clone(), NodeKind::Thesis, &thesis.content, Some(&session.id), Some(&thesis.content));
+            self.edge(&session_node, &id, EdgeKind::Contains, 1.0);
+        }
+        for source in &input.sources {
+            let kind = match source.kind {
+                CitationKind::Document => NodeKind::Document,
+                CitationKind::WebSource => NodeKind::WebSource,
+                CitationKind::Note => NodeKind::Note,
+                CitationKind::File => NodeKind::File,
+            };
+            self.node(source.key.clone(), kind, &source.reference, None, None);
+            self.edge(&format!("claim:{}::{}", session.id, source.claim_id), &source.key, EdgeKind::Cites, 1.0);
+        }
+    }
+
+    fn note(&mut self, note: &NoteIndex) {
+        let text = format!("{} {} {}", note.title, note.summary, note.tags.join(" "));
+        self.node(format!("note:{}", note.path), NodeKind::Note, &note.title, None, Some(&text));
+    }
+
+    fn memory(&mut self, memory: &MemoryRecord) {
+        let id = format!("memory:{}", memory.id);
+        let session_id = memory.metadata.get("session_id").and_then(|v| v.as_str());
+        self.node(id.clone(), NodeKind::Memory, &memory.content, session_id, Some(&memory.content));
+        let Some(session_id) = session_id else { return };
+        let origin = memory
+            .metadata
+            .get("claim_id")
+            .and_then(|v| v.as_str())
+            .map(|claim_id| format!("claim:{}::{}", session_id, claim_id))
+            .filter(|claim| self.nodes.contains_key(claim))
+            .unwrap_or_else(|| format!("session:{}", session_id));
+        if self.nodes.contains_key(&origin) {
+            self.edge(&id, &origin, EdgeKind::Derived, 1.0);
+        }
+    }
+
+    /// Entity and similarity edges between content nodes
+    fn link_content(&mut self) {
+        let mentions: BTreeMap<String, BTreeSet<String>> =
+            self.texts.iter().map(|(id, text)| (id.clone(), entities(text))).collect();
+
+        let mut by_entity: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for (id, found) in &mentions {
+            for entity in found {
+                by_entity.entry(entity.as_str()).or_default().push(id.as_str());
+            }
+        }
+        let mut pairs: BTreeSet<(&str, &str)> = BTreeSet::new();
+        let mut edges = Vec::new();
+        for (entity, ids) in &by_entity {
+            if ids.len() < 2 || ids.len() > MAX_ENTITY_NODES {
+                continue;
+            }
+            let entity_node = format!("entity:{}", entity);
+            self.nodes.insert(
+                entity_node.clone(),
+                KnowledgeNode { id: entity_node.clone(), kind: NodeKind::Entity, label: entity.to_string(), session_id: None },
+            );
+            for (i, a) in ids.iter().enumerate() {
+                edges.push(KnowledgeEdge { source: a.to_string(), target: entity_node.clone(), kind: EdgeKind::Mentions, weight: 1.0 });
+                pairs.extend(ids[i + 1..].iter().map(|b| (*a, *b)));
+            }
+        }
+
+        // Similarity over single words, so phrases don't count twice
+        let words = |id: &str| -> BTreeSet<String> { mentions[id].iter().filter(|e| !e.contains(' ')).cloned().collect() };
+        for (a, b) in pairs {
+            let weight = jaccard(&words(a), &words(b));
+            if weight >= SIMILARITY_THRESHOLD {
+                edges.push(KnowledgeEdge { source: a.to_string(), target: b.to_string(), kind: EdgeKind::Similar, weight });
+            }
+        }
+        self.edges.extend(edges);
+    }
+
+    fn finish(mut self, now: DateTime<Utc>) -> KnowledgeGraph {
+        self.link_content();
+        let nodes = &self.nodes;
+        let mut seen = HashSet::new();
+        let edges = self
+            .edges
+            .into_iter()
+            .filter(|e| nodes.contains_key(&e.source) && nodes.contains_key(&e.target) && e.source != e.target)
+            .filter(|e| seen.insert((e.source.clone(), e.target.clone(), e.kind)))
+            .collect();
+        KnowledgeGraph { built_at: Some(now), nodes: self.nodes.into_values().collect(), edges }
+    }
+}
+
+/// Build the graph from its inputs. Rejected memories are left out.
+pub fn build(sessions: &[SessionInput], notes: &[NoteIndex], memories: &[MemoryRecord], now: DateTime<Utc>) -> KnowledgeGraph {
+    let mut builder = Builder::default();
+    for note in notes {
+        builder.note(note);
+    }
+    for session in sessions {
+        builder.session(session);
+    }
+    for memory in memories.iter().filter(|m| m.review_status() != ReviewStatus::Rejected) {
+        builder.memory(memory);
+    }
+    builder.finish(now)
+}
+
+// ============ QUERY ============
+
+impl KnowledgeGraph {
+    /// Resolve a node ID, or free text naming an entity
+    fn resolve(&self, query: &str) -> Option<&KnowledgeNode> {
+        let query = query.trim();
+        self.nodes
+            .iter()
+            .find(|n| n.id == query)
+            .or_else(|| {
+                let entity = entity_id(query)?;
+                self.nodes.iter().find(|n| n.id == entity)
+            })
+    }
+
+    /// Nodes within `depth` hops of `query`, nearest and strongest first
+    pub fn neighbors(&self, query: &str, depth: u32, limit: usize) -> Result<Neighborhood, KnowledgeError> {
+        let node = self.resolve(query).ok_or_else(|| KnowledgeError::NotFound(query.to_string()))?;
+        let mut adjacency: HashMap<&str, Vec<(&str, &KnowledgeEdge)>> = HashMap::new();
+        for edge in &self.edges {
+            adjacency.entry(edge.source.as_str()).or_default().push((edge.target.as_str(), edge));
+            adjacency.entry(edge.target.as_str()).or_default().push((edge.source.as_str(), edge));
+        }
+        let by_id: HashMap<&str, &KnowledgeNode> = self.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+
+        let mut neighbors = Vec::new();
+        let mut visited = HashSet::from([node.id.as_str()]);
+        let mut queue = VecDeque::from([(node.id.as_str(), 0u32)]);
+        while let Some((id, distance)) = queue.pop_front() {
+            if distance >= depth {
+                continue;
+            }
+            let mut next = adjacency.get(id).cloned().unwrap_or_default();
+            next.sort_by(|a, b| b.1.weight.total_cmp(&a.1.weight));
+            for (other, edge) in next {
+                if !visited.insert(other) {
+                    continue;
+                }
+                neighbors.push(Neighbor {
+                    node: by_id[other].clone(),
+                    via: id.to_string(),
+                    edge: edge.kind,
+                    weight: edge.weight,
+                    distance: distance + 1,
+                });
+                queue.push_back((other, distance + 1));
+            }
+        }
+        neighbors.sort_by(|a, b| a.distance.cmp(&b.distance).then(b.weight.total_cmp(&a.weight)));
+        neighbors.truncate(limit);
+        Ok(Neighborhood { node: node.clone(), neighbors })
+    }
+
+    pub fn stats(&self) -> KnowledgeStats {
+        KnowledgeStats {
+            nodes: self.nodes.len(),
+            edges: self.edges.len(),
+            entities: self.nodes.iter().filter(|n| n.kind == NodeKind::Entity).count(),
+        }
+    }
+}
+
+// ============ STORAGE ============
+
+fn graph_path() -> Result<PathBuf, KnowledgeError> {
+    Ok(get_app_data_dir_cli()?.join(GRAPH_FILE))
+}
+
+fn save_to(path: &Path, graph: &KnowledgeGraph) -> Result<(), KnowledgeError> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, serde_json::to_string(graph)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Read every source and rebuild the persisted graph
+pub async fn rebuild() -> Result<KnowledgeStats, KnowledgeError> {
+    let mut sessions = Vec::new();
+    for session in list_sessions_cli()? {
+        let prefs = effective_preferences(Path::new(&session.working_dir)).0;
+        let sources = claim_sources(&session, prefs.vault_path.as_deref().map(Path::new)).await;
+        sessions.push(SessionInput { session, sources });
+    }
+    let notes: Vec<NoteIndex> = with_vault_index(|index| index.notes.values().cloned().collect()).unwrap_or_default();
+    let mut memories = Vec::new();
+    for memory_type in [MemoryType::Semantic, MemoryType::Procedural, MemoryType::Episodic] {
+        match list_memories(memory_type, Some(MEMORY_LIMIT)).await {
+            Ok(found) => memories.extend(found),
+            Err(e) => debug!(memory_type = %memory_type.as_str(), error = %e, "Memories unavailable for knowledge graph"),
+        }
+    }
+
+    let graph = build(&sessions, &notes, &memories, Utc::now());
+    save_to(&graph_path()?, &graph)?;
+    let stats = graph.stats();
+    *GRAPH.write() = Some(Arc::new(graph));
+    info!(nodes = stats.nodes, edges = stats.edges, entities = stats.entities, "Rebuilt knowledge graph");
+    Ok(stats)
+}
+
+/// The persisted graph, built first if there isn't one yet
+pub async fn graph() -> Result<Arc<KnowledgeGraph>, KnowledgeError> {
+    if let Some(graph) = GRAPH.read().clone() {
+        return Ok(graph);
+    }
+    let path = graph_path()?;
+    if path.exists() {
+        let graph: Arc<KnowledgeGraph> = Arc::new(serde_json::from_str(&fs::read_to_string(&path)?)?);
+        *GRAPH.write() = Some(graph.clone());
+        return Ok(graph);
+    }
+    rebuild().await?;
+    Ok(GRAPH.read().clone().unwrap_or_default())
+}
+
+// ============ TAURI COMMANDS ============
+
+/// Everything connected to a node ID or entity (e.g. "pricing strategy")
+#[tauri::command]
+pub async fn knowledge_neighbors(
+    node_id: String,
+    depth: Option<u32>,
+    limit: Option<usize>,
+) -> Result<Neighborhood, KnowledgeError> {
+    let depth = depth.unwrap_or(DEFAULT_DEPTH).clamp(1, MAX_DEPTH);
+    graph().await?.neighbors(&node_id, depth, limit.unwrap_or(DEFAULT_LIMIT))
+}
+
+/// Rebuild the knowledge graph as a background job; returns the job id
+#[tauri::command]
+pub fn rebuild_knowledge_graph() -> String {
+    jobs::submit_unique(JobKind::KnowledgeGraph, "knowledge_graph", |_job| async move {
+        let stats = rebuild().await.map_err(|e| e.to_string())?;
+        Ok(serde_json::to_value(stats).ok())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use serde_json::json;
+
+    fn session(id: &str, title: &str, claims: &[(&str, &str)]) -> SessionInput {
+        let now = Utc::now();
+        let claims: Vec<_> = claims
+            .iter()
+            .map(|(id, content)| json!({ "id": id, "content": content, "sourceId": "s", "marker": null, "createdAt": now }))
+            .collect();
+        SessionInput {
+            session: test_session(json!({
+                "id": id, "title": title,
+                "created": now, "updated": now,
+                "claims": claims,
+            })),
+            sources: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_entities_link_across_sessions() {
+        assert_eq!(entity_id("Pricing strategies").as_deref(), Some("entity:pricing strategy"));
+
+        let mut a = session("a", "Retail margins", &[("c1", "Our pricing strategy protects margins")]);
+        a.sources.push(ClaimSource {
+            claim_id: "c1".to_string(),
+            key: "web:https://example.com/pricing".to_string(),
+            kind: CitationKind::WebSource,
+            reference: "Pricing study".to_string(),
+            url: Some("https://example.com/pricing".to_string()),
+        });
+        let b = session("b", "SaaS expansion", &[("c1", "A tiered pricing strategy drives expansion revenue")]);
+        let memory = MemoryRecord {
+            id: "b::c1".to_string(),
+            memory_type: MemoryType::Semantic,
+            content: "[INSIGHT] Tiered pricing strategy drives expansion".to_string(),
+            metadata: json!({ "session_id": "b", "claim_id": "c1" }),
+            relevance: None,
+        };
+        let graph = build(&[a, b], &[], &[memory], Utc::now());
+
+        let around = graph.neighbors("pricing strategy", 1, 50).unwrap();
+        assert_eq!(around.node.kind, NodeKind::Entity);
+        let ids: BTreeSet<&str> = around.neighbors.iter().map(|n| n.node.id.as_str()).collect();
+        assert!(ids.contains("claim:a::c1"));
+        assert!(ids.contains("claim:b::c1"));
+        assert!(ids.contains("memory:b::c1"));
+
+        let claim = graph.neighbors("claim:b::c1", 1, 50).unwrap();
+        assert!(claim.neighbors.iter().any(|n| n.node.id == "memory:b::c1" && n.edge == EdgeKind::Derived));
+        let two_hops = graph.neighbors("claim:a::c1", 2, 50).unwrap();
+        assert!(two_hops.neighbors.iter().any(|n| n.node.id == "web:https://example.com/pricing" && n.distance == 1));
+        assert!(two_hops.neighbors.iter().any(|n| n.node.id == "claim:b::c1" && n.distance == 2));
+
+        assert!(matches!(graph.neighbors("unrelated topic", 1, 50), Err(KnowledgeError::NotFound(_))));
+    }
+}
@@ -1,16 +1,41 @@
 // Dialectic Library
 // Exports core modules for use by both Tauri app and CLI binary
 
+pub mod backup;
+pub mod cancellation;
 pub mod cdg;
 pub mod chroma;
+pub mod cli_tool;
+pub mod config;
 pub mod context;
+pub mod deep_link;
+pub mod distill;
+pub mod doctor;
 pub mod documents;
+pub mod events;
+pub mod git;
+pub mod headless;
+pub mod jobs;
+pub mod knowledge;
+pub mod logging;
+pub mod mcp;
+pub mod metrics;
 pub mod obsidian;
+pub mod onboarding;
+pub mod publish;
+pub mod quick_search;
+pub mod report;
+#[cfg(feature = "rest-api")]
+pub mod rest;
 pub mod session;
+pub mod skills;
+pub mod sources;
+pub mod sync;
+pub mod views;
 
 // Re-export commonly used types for CLI
 pub use context::budget::{
-    ContextBudget, BudgetStatus, SourceStatus, ThresholdStatus, ContextSource,
+    ContextBudget, BudgetStatus, SourceStatus, ThresholdStatus, ContextSource, FitCheck,
     TOTAL_BUDGET, OUTPUT_RESERVED, WORKING_BUDGET,
     THRESHOLD_AUTO_COMPRESS, THRESHOLD_WARN_USER, THRESHOLD_FORCE_COMPRESS,
 };
@@ -21,6 +46,7 @@ pub use context::compression::{
     check_compression_triggers,
 };
 pub use context::tokens::{count_tokens, count_tokens_batch, estimate_tokens_quick};
+pub use context::unified_search::{UnifiedHit, UnifiedSearchResults, SearchSource, unified_search};
 
 pub use obsidian::query::{QueryResult, MatchType, NoteContent, query_notes, get_note_content};
 pub use obsidian::indexer::{NoteIndex, VaultIndex, ObsidianError, IndexStats, configure_vault, index_vault, get_vault_index};
@@ -30,8 +56,14 @@ pub use session::{
     get_app_data_dir_cli, get_session_dir_cli, load_session_cli, list_sessions_cli,
     save_session_cli,
 };
+pub use session::repair::{RepairReport, RepairStrategy, repair_session};
+pub use session::decision_record::{DecisionRecord, DecisionRecordFormat, export as export_decision_record};
+pub use session::scratchpad::{Scratchpad, ScratchpadSection};
+pub use session::claim_source::{ClaimSource, ClaimSourceError, SourceSpan, claim_source};
+pub use session::audit::{AuditEntry, AuditAction, AuditActor, read_audit_log, set_actor as set_audit_actor};
 
 pub use cdg::{
     EdgeType, ClaimStratum, ResolutionStatus, CdgEdge, CdgMetrics, CdgSnapshot, PassDiff,
     compute_strata, compute_metrics, find_orphans, compute_pass_diff,
 };
+pub use cdg::evidence::{ClaimEvidence, EvidenceReport, score_evidence, apply_scores};
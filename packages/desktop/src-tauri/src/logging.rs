@@ -0,0 +1,293 @@
+//! Structured File Logging
+//!
+//! Alongside the stdout formatter, tracing events are written as JSON lines
+//! to `<app data>/logs/dialectic.log`. The file rotates at `MAX_LOG_BYTES`
+//! (`dialectic.log.1` is the previous file, and so on) and keeps
+//! `MAX_LOG_FILES` in total, so sidecar failures and indexing errors can be
+//! read back after the fact with `get_recent_logs` or `dialectic logs tail`.
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use thiserror::Error;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+use crate::session::get_app_data_dir_cli;
+
+/// Log directory under the app data dir
+pub const LOG_DIR: &str = "logs";
+/// Current log file name
+pub const LOG_FILE: &str = "dialectic.log";
+/// Rotate once the current file reaches this size
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// Current file plus rotated ones
+const MAX_LOG_FILES: usize = 5;
+/// Default number of records returned by `get_recent_logs`
+const DEFAULT_RECENT_LIMIT: usize = 200;
+
+#[derive(Error, Debug)]
+pub enum LogError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("App data directory not found")]
+    NoAppDataDir,
+    #[error("Invalid log level: {0}")]
+    InvalidLevel(String),
+}
+
+impl Serialize for LogError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// One log line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRecord {
+    pub timestamp: DateTime<Utc>,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub fields: Map<String, Value>,
+}
+
+/// Path to the log directory
+pub fn log_dir() -> Result<PathBuf, LogError> {
+    Ok(get_app_data_dir_cli().map_err(|_| LogError::NoAppDataDir)?.join(LOG_DIR))
+}
+
+fn rotated_path(dir: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        dir.join(LOG_FILE)
+    } else {
+        dir.join(format!("{}.{}", LOG_FILE, index))
+    }
+}
+
+/// Size-rotated append-only log file
+struct RotatingFile {
+    dir: PathBuf,
+    file: File,
+    size: u64,
+    max_bytes: u64,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    fn open(dir: &Path, max_bytes: u64, max_files: usize) -> std::io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let file = OpenOptions::new().create(true).append(true).open(rotated_path(dir, 0))?;
+        let size = file.metadata()?.len();
+        Ok(Self { dir: dir.to_path_buf(), file, size, max_bytes, max_files })
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        for index in (1..self.max_files).rev() {
+            let from = rotated_path(&self.dir, index - 1);
+            if from.exists() {
+                fs::rename(&from, rotated_path(&self.dir, index))?;
+            }
+        }
+        self.file = OpenOptions::new().create(true).append(true).open(rotated_path(&self.dir, 0))?;
+        self.size = 0;
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.size > 0 && self.size + line.len() as u64 + 1 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.file.write_all(b"\n")?;
+        self.size += line.len() as u64 + 1;
+        Ok(())
+    }
+}
+
+/// Collects an event's fields into JSON
+#[derive(Default)]
+struct JsonVisitor {
+    message: String,
+    fields: Map<String, Value>,
+}
+
+impl JsonVisitor {
+    fn insert(&mut self, field: &Field, value: Value) {
+        if field.name() == "message" {
+            self.message = match value {
+                Value::String(s) => s,
+                other => other.to_string(),
+            };
+        } else {
+            self.fields.insert(field.name().to_string(), value);
+        }
+    }
+}
+
+impl Visit for JsonVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, Value::from(value));
+    }
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, Value::from(value));
+    }
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, Value::from(value));
+    }
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, Value::from(value));
+    }
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, Value::from(value));
+    }
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.insert(field, Value::from(format!("{:?}", value)));
+    }
+}
+
+/// Layer writing each event as a JSON line to the rotating file
+struct JsonFileLayer {
+    writer: Mutex<RotatingFile>,
+}
+
+impl<S: Subscriber> Layer<S> for JsonFileLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = JsonVisitor::default();
+        event.record(&mut visitor);
+        let record = LogRecord {
+            timestamp: Utc::now(),
+            level: event.metadata().level().to_string(),
+            target: event.metadata().target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+        };
+        if let Ok(line) = serde_json::to_string(&record) {
+            // Nowhere to report a failed log write; drop the line
+            let _ = self.writer.lock().write_line(&line);
+        }
+    }
+}
+
+/// Install stdout and file logging. `default_filter` applies when
+/// `RUST_LOG` is unset. File logging is skipped if the log directory
+/// can't be created.
+pub fn init(default_filter: &str) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_filter));
+    let file_layer = log_dir()
+        .ok()
+        .and_then(|dir| RotatingFile::open(&dir, MAX_LOG_BYTES, MAX_LOG_FILES).ok())
+        .map(|file| JsonFileLayer { writer: Mutex::new(file) });
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer())
+        .with(file_layer)
+        .init();
+}
+
+fn parse_level(level: &str) -> Result<Level, LogError> {
+    Level::from_str(level).map_err(|_| LogError::InvalidLevel(level.to_string()))
+}
+
+/// The last `limit` records at `min_level` or more severe, oldest first
+pub fn read_recent_logs_from(dir: &Path, min_level: Option<Level>, limit: usize) -> Vec<LogRecord> {
+    let mut records: Vec<LogRecord> = Vec::new();
+    // Newest file first; stop once enough records are collected
+    for index in 0..MAX_LOG_FILES {
+        let Ok(content) = fs::read_to_string(rotated_path(dir, index)) else { continue };
+        let mut batch: Vec<LogRecord> = content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<LogRecord>(line).ok())
+            .filter(|r| match (min_level, parse_level(&r.level)) {
+                (Some(min), Ok(level)) => level <= min,
+                (Some(_), Err(_)) => false,
+                (None, _) => true,
+            })
+            .collect();
+        batch.append(&mut records);
+        records = batch;
+        if records.len() >= limit {
+            break;
+        }
+    }
+    let skip = records.len().saturating_sub(limit);
+    records.split_off(skip)
+}
+
+/// Recent records from the app's log directory
+pub fn read_recent_logs(level: Option<&str>, limit: Option<usize>) -> Result<Vec<LogRecord>, LogError> {
+    let min_level = level.map(parse_level).transpose()?;
+    Ok(read_recent_logs_from(&log_dir()?, min_level, limit.unwrap_or(DEFAULT_RECENT_LIMIT)))
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn get_recent_logs(level: Option<String>, limit: Option<usize>) -> Result<Vec<LogRecord>, LogError> {
+    read_recent_logs(level.as_deref(), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("dialectic_logs_{}", ulid::Ulid::new()))
+    }
+
+    fn line(level: &str, message: &str) -> String {
+        serde_json::to_string(&LogRecord {
+            timestamp: Utc::now(),
+            level: level.to_string(),
+            target: "dialectic_lib::test".to_string(),
+            message: message.to_string(),
+            fields: Map::new(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_rotation_keeps_max_files() {
+        let dir = temp_dir();
+        let mut file = RotatingFile::open(&dir, 200, 3).unwrap();
+        for i in 0..20 {
+            file.write_line(&line("INFO", &format!("message {}", i))).unwrap();
+        }
+        assert!(rotated_path(&dir, 0).exists());
+        assert!(rotated_path(&dir, 2).exists());
+        assert!(!rotated_path(&dir, 3).exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_read_recent_filters_and_orders() {
+        let dir = temp_dir();
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(rotated_path(&dir, 1), format!("{}\n{}\n", line("ERROR", "old error"), line("INFO", "old info"))).unwrap();
+        fs::write(rotated_path(&dir, 0), format!("{}\nnot json\n{}\n", line("WARN", "new warn"), line("DEBUG", "new debug"))).unwrap();
+
+        let warnings = read_recent_logs_from(&dir, Some(Level::WARN), 10);
+        let messages: Vec<&str> = warnings.iter().map(|r| r.message.as_str()).collect();
+        assert_eq!(messages, vec!["old error", "new warn"]);
+
+        let last_two = read_recent_logs_from(&dir, None, 2);
+        let messages: Vec<&str> = last_two.iter().map(|r| r.message.as_str()).collect();
+        assert_eq!(messages, vec!["new warn", "new debug"]);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,53 +1,124 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod backup;
+mod cancellation;
 mod cdg;
 mod chroma;
+mod cli_tool;
+mod config;
 mod session;
 mod terminal;
 mod watcher;
 mod context;
+mod deep_link;
+mod distill;
+mod doctor;
 mod obsidian;
+mod onboarding;
 mod documents;
+mod quick_search;
+mod publish;
+mod report;
+mod skills;
+mod sources;
+mod sync;
+mod views;
+mod events;
+mod git;
+mod jobs;
+mod knowledge;
+mod logging;
+mod metrics;
 
 fn main() {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("dialectic=info")),
-        )
-        .init();
+    logging::init("dialectic=info");
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_deep_link::init())
         .setup(|app| {
             use tauri::Manager;
+            use tauri_plugin_deep_link::DeepLinkExt;
+            use chroma::store::VectorStore;
             // Initialize app data directory structure on first run
             if let Err(e) = session::init_app_data_dir(app.handle()) {
                 tracing::error!(error = %e, "Failed to initialize app data directory");
             }
+            if let Ok(app_data) = session::get_app_data_dir_cli() {
+                doctor::heal_on_startup(&app_data);
+            }
+
+            // Background jobs and indexing emit events through the app handle
+            events::set_app_handle(app.handle().clone());
+
+            // dialectic:// links: hold the launch link for the frontend, forward later ones
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                for url in urls {
+                    deep_link::set_pending(url.as_str());
+                }
+            }
+            let link_handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                for url in event.urls() {
+                    deep_link::handle_url(&link_handle, url.as_str());
+                }
+            });
+
+            // Periodically match thesis revision triggers against new Chroma content
+            session::trigger_alerts::start_trigger_matcher(app.handle().clone());
+
+            // Poll registered RSS/Atom feeds into web_sources
+            sources::feeds::start_feed_watcher(app.handle().clone());
+            backup::start_backup_scheduler();
+
+            let prefs = config::preferences::load_preferences();
+
+            // Load session titles for quick search off the main thread
+            std::thread::spawn(quick_search::warm_session_titles);
+
+            // Restore the configured vault in the background
+            if let Some(vault_path) = prefs.vault_path.clone() {
+                std::thread::spawn(move || {
+                    if let Err(e) = obsidian::indexer::configure_vault(&vault_path) {
+                        tracing::warn!(error = %e, "Failed to restore configured vault");
+                    }
+                });
+            }
+
+            // An external Chroma (or Qdrant) may be running; ensure collections against it
+            let ensure_external = || {
+                tauri::async_runtime::spawn(async {
+                    let client = chroma::client::get_client();
+                    if client.heartbeat().await.is_ok() {
+                        tracing::info!("External vector store detected, ensuring collections...");
+                        if let Err(e) = chroma::collections::ensure_all_collections(&client).await {
+                            tracing::warn!(error = %e, "Failed to ensure collections on external Chroma");
+                        }
+                    }
+                });
+            };
 
             // Start Chroma sidecar and ensure collections exist.
             // Non-fatal: app works offline with feature-hash fallback.
-            match chroma::sidecar::start_sidecar(Some(app.handle())) {
-                Ok(()) => {
+            let sidecar = match (prefs.vector_backend, prefs.chroma_mode) {
+                (config::preferences::VectorBackend::Embedded | config::preferences::VectorBackend::Qdrant, _) => None,
+                (_, config::preferences::ChromaMode::Sidecar) => Some(chroma::sidecar::start_sidecar(Some(app.handle()))),
+                (_, config::preferences::ChromaMode::External | config::preferences::ChromaMode::Disabled) => None,
+            };
+            match sidecar {
+                Some(Ok(())) => {
                     tracing::info!("Chroma sidecar started, ensuring collections...");
                     // Health check + collection init is async; fire in background
                     tauri::async_runtime::spawn(async {
                         let client = chroma::client::get_client();
                         // Poll health for up to 10 seconds
-                        let deadline = std::time::Instant::now()
-                            + std::time::Duration::from_secs(10);
-                        let mut healthy = false;
-                        while std::time::Instant::now() < deadline {
-                            if client.heartbeat().await.is_ok() {
-                                healthy = true;
-                                break;
-                            }
-                            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-                        }
+                        let healthy = client
+                            .wait_until_healthy(std::time::Duration::from_secs(10))
+                            .await
+                            .is_ok();
                         if healthy {
                             match chroma::collections::ensure_all_collections(&client).await {
                                 Ok(cols) => tracing::info!(
@@ -64,36 +135,117 @@ fn main() {
                         }
                     });
                 }
-                Err(e) => {
+                Some(Err(e)) => {
                     tracing::warn!(error = %e, "Chroma sidecar not available (app will use offline fallback)");
-                    // Even without our sidecar, an external Chroma may be running.
-                    // Try to ensure collections against it.
+                    ensure_external();
+                }
+                None if prefs.vector_backend == config::preferences::VectorBackend::Embedded => {
                     tauri::async_runtime::spawn(async {
                         let client = chroma::client::get_client();
-                        if client.heartbeat().await.is_ok() {
-                            tracing::info!("External Chroma detected, ensuring collections...");
-                            if let Err(e) = chroma::collections::ensure_all_collections(&client).await {
-                                tracing::warn!(error = %e, "Failed to ensure collections on external Chroma");
-                            }
+                        if let Err(e) = chroma::collections::ensure_all_collections(&client).await {
+                            tracing::warn!(error = %e, "Failed to ensure embedded vector collections");
                         }
                     });
                 }
+                None if prefs.vector_backend == config::preferences::VectorBackend::Qdrant
+                    || prefs.chroma_mode == config::preferences::ChromaMode::External => ensure_external(),
+                None => tracing::info!("Chroma disabled in preferences, using offline fallback"),
             }
 
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
+            // Preferences commands
+            config::preferences::get_preferences,
+            config::preferences::update_preferences,
+            config::profile::list_profiles,
+            config::profile::switch_profile,
+            // Log commands
+            logging::get_recent_logs,
+            metrics::get_app_metrics,
+            metrics::reset_app_metrics,
+            // Search cancellation
+            cancellation::cancel_search,
+            // Autocomplete
+            quick_search::quick_search,
+            // Git context (project-local sessions)
+            git::get_git_context,
+            // Deep links
+            deep_link::take_pending_deep_link,
+            // Job commands
+            jobs::list_jobs,
+            jobs::cancel_job,
+            knowledge::knowledge_neighbors,
+            knowledge::rebuild_knowledge_graph,
             // Session commands
             session::create_session,
             session::load_session,
             session::list_sessions,
             session::update_session_status,
             session::delete_session,
+            session::trash::list_trash,
+            session::trash::restore_from_trash,
             session::get_app_data_dir,
             session::get_skills_dir,
+            skills::sync_skills,
+            skills::list_skills,
+            skills::set_session_skills,
+            views::list_views,
+            views::save_view,
+            views::delete_view,
+            views::list_sessions_filtered,
+            doctor::run_doctor,
+            cli_tool::detect_cli_tool,
+            onboarding::get_onboarding_status,
+            onboarding::run_setup_step,
+            onboarding::skip_setup_step,
             session::prepare_launch,
             session::fork_session,
+            session::tags::add_session_tag,
+            session::tags::remove_session_tag,
+            session::entities::extract_session_entities,
+            session::clusters::cluster_sessions,
+            session::calendar::export_review_calendar,
+            session::statusline::get_status_line,
+            session::timeline::get_session_timeline,
+            session::manifest::get_context_manifest,
+            session::time_tracking::record_activity,
+            session::time_tracking::get_time_report,
+            session::retention::apply_retention_policies,
+            session::ingest::ingest_agent_output,
             session::capture_conversation_id,
+            session::annotations::add_annotation,
+            session::annotations::resolve_annotation,
+            session::annotations::list_annotations,
+            session::review::add_review_trigger,
+            session::review::remove_review_trigger,
+            session::review::mark_trigger_reviewed,
+            session::review::get_due_reviews,
+            session::calibration::record_thesis_outcome,
+            session::calibration::get_calibration_report,
+            session::trigger_alerts::check_trigger_alerts,
+            session::trigger_alerts::acknowledge_trigger_alert,
+            sources::feeds::feeds_list,
+            sources::feeds::feeds_add,
+            sources::feeds::feeds_remove,
+            sources::feeds::feeds_refresh,
+            sources::freshness::refresh_web_source,
+            sources::freshness::acknowledge_source_change,
+            session::repair::repair_corrupted_session,
+            session::claim_source::get_claim_source,
+            session::decision_record::export_decision_record,
+            session::share::export_shareable,
+            session::citations::format_citations,
+            distill::distill,
+            report::render_session_report,
+            publish::publish_site,
+            sync::sync_now,
+            backup::backup_now,
+            backup::list_backups,
+            backup::restore_backup,
+            session::scratchpad::scratchpad_get,
+            session::scratchpad::scratchpad_append,
+            session::scratchpad::scratchpad_replace_section,
             // Terminal commands
             terminal::spawn_terminal,
             terminal::write_to_terminal,
@@ -110,11 +262,18 @@ fn main() {
             context::classification::context_get_allocation,
             context::classification::context_classify_session,
             context::budget::context_get_budget_constants,
+            context::budget::context_can_fit,
+            context::budget::context_record_pass_output,
+            context::budget_history::get_budget_history,
             context::compression::context_check_compression_triggers,
             context::compression::context_create_compression_request,
+            context::unified_search::search_everything,
+            context::assembler::context_assemble_pack,
+            context::compaction::context_compaction_payload,
             // Obsidian commands
             obsidian::indexer::obsidian_configure_vault,
             obsidian::indexer::obsidian_index_vault,
+            obsidian::indexer::obsidian_index_vault_job,
             obsidian::indexer::obsidian_get_stats,
             obsidian::query::obsidian_resolve_mention,
             obsidian::query::obsidian_query_notes,
@@ -134,12 +293,19 @@ fn main() {
             documents::embeddings::documents_cache_embedding,
             documents::embeddings::documents_get_cached_embedding,
             documents::retriever::documents_add_reference,
+            documents::retriever::documents_add_reference_job,
             documents::retriever::documents_remove_reference,
             documents::retriever::documents_list_references,
             documents::retriever::documents_search_document,
             documents::retriever::documents_search_all,
             documents::retriever::documents_get_chunk,
+            documents::retriever::documents_get_section,
+            documents::retriever::documents_pin_chunk,
+            documents::retriever::documents_set_budget,
+            documents::permanent::list_permanent_documents,
             documents::retriever::documents_clear_ephemeral,
+            documents::web::documents_add_url_reference,
+            documents::bibtex::documents_import_bibliography,
             // Chroma commands — sidecar
             chroma::sidecar::chroma_start_sidecar,
             chroma::sidecar::chroma_stop_sidecar,
@@ -149,6 +315,7 @@ fn main() {
             chroma::client::chroma_list_collections,
             // Chroma commands — collections
             chroma::collections::chroma_ensure_collections,
+            chroma::collections::chroma_migrate_collections,
             chroma::collections::chroma_get_collection_status,
             // Chroma commands — search
             chroma::search::chroma_search_all,
@@ -160,13 +327,20 @@ fn main() {
             chroma::memory::chroma_read_memories,
             chroma::memory::chroma_list_memories,
             chroma::memory::chroma_delete_memory,
+            chroma::memory::chroma_delete_memories_where,
             chroma::memory::chroma_clear_memories,
             chroma::memory::chroma_get_memory_stats,
+            chroma::memory::chroma_trace_memory,
+            chroma::memory::record_memory_feedback,
+            chroma::memory::list_pending_memories,
+            chroma::memory::approve_memory,
+            chroma::memory::reject_memory,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|_app_handle, event| {
             if let tauri::RunEvent::Exit = event {
+                tauri::async_runtime::block_on(chroma::write_queue::flush());
                 let _ = chroma::sidecar::stop_sidecar();
             }
         });
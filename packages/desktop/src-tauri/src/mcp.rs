@@ -0,0 +1,448 @@
+//! MCP Server
+//!
+//! `dialectic mcp` speaks the Model Context Protocol over stdio so Claude
+//! Code can call Dialectic as native tools instead of shelling out to the
+//! CLI: vault queries, document and unified search, memory read/write,
+//! recording claims and checking the context budget. Messages are
+//! newline-delimited JSON-RPC 2.0; stdout carries only protocol messages.
+
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tracing::warn;
+use ulid::Ulid;
+
+use crate::chroma::memory::{queue_memory, read_memories, MemoryType};
+use crate::chroma::write_queue;
+use crate::config::preferences::load_preferences;
+use crate::config::workspace::VAULT_PATH_VAR;
+use crate::context::budget::{BudgetStatus, WORKING_BUDGET};
+use crate::context::unified_search::unified_search;
+use crate::documents::retriever::search_all_documents;
+use crate::obsidian::indexer::{configure_vault, index_vault};
+use crate::obsidian::query::{get_note_content, query_notes};
+use crate::session::lock::update_session_file;
+use crate::session::markers::MARKERS;
+use crate::session::{get_session_dir_cli, load_session_cli, Claim, SessionError};
+
+pub const PROTOCOL_VERSION: &str = "2024-11-05";
+/// Source ID of claims recorded through MCP
+const SOURCE_ID: &str = "mcp";
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+#[derive(Error, Debug)]
+pub enum McpError {
+    #[error("Unknown tool: {0}")]
+    UnknownTool(String),
+    #[error("Invalid arguments: {0}")]
+    InvalidParams(String),
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error("{0}")]
+    Tool(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    /// Absent for notifications, which get no response
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct ToolCall {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
+#[derive(Deserialize)]
+struct VaultQueryArgs {
+    query: String,
+    budget: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct VaultNoteArgs {
+    path: String,
+    max_tokens: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct SearchArgs {
+    session_id: String,
+    query: String,
+    budget: Option<u32>,
+    top_k: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct MemoryReadArgs {
+    memory_type: String,
+    query: String,
+    n_results: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct MemoryWriteArgs {
+    memory_type: String,
+    content: String,
+    id: Option<String>,
+    metadata: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct RecordClaimArgs {
+    session_id: String,
+    content: String,
+    marker: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SessionArgs {
+    session_id: String,
+}
+
+fn tool(name: &str, description: &str, properties: Value, required: &[&str]) -> Value {
+    json!({
+        "name": name,
+        "description": description,
+        "inputSchema": { "type": "object", "properties": properties, "required": required },
+    })
+}
+
+/// Tool definitions returned by `tools/list`
+pub fn tool_definitions() -> Vec<Value> {
+    let session_id = json!({ "type": "string", "description": "Session ID (without sess_ prefix)" });
+    let memory_type = json!({ "type": "string", "enum": ["semantic", "procedural", "episodic"] });
+    vec![
+        tool(
+            "vault_query",
+            "Search the Obsidian vault for notes relevant to a query",
+            json!({ "query": { "type": "string" }, "budget": { "type": "integer", "description": "Token budget" } }),
+            &["query"],
+        ),
+        tool(
+            "vault_note",
+            "Read a vault note by path",
+            json!({ "path": { "type": "string" }, "max_tokens": { "type": "integer" } }),
+            &["path"],
+        ),
+        tool(
+            "search_documents",
+            "Search a session's reference documents",
+            json!({ "session_id": session_id, "query": { "type": "string" }, "top_k": { "type": "integer" } }),
+            &["session_id", "query"],
+        ),
+        tool(
+            "search",
+            "Search documents, vault, web sources, memories and other sessions within the session's budget",
+            json!({ "session_id": session_id, "query": { "type": "string" }, "budget": { "type": "integer" } }),
+            &["session_id", "query"],
+        ),
+        tool(
+            "memory_read",
+            "Read memories relevant to a query",
+            json!({ "memory_type": memory_type, "query": { "type": "string" }, "n_results": { "type": "integer" } }),
+            &["memory_type", "query"],
+        ),
+        tool(
+            "memory_write",
+            "Write (or overwrite, given an id) a memory",
+            json!({
+                "memory_type": memory_type,
+                "content": { "type": "string" },
+                "id": { "type": "string" },
+                "metadata": { "type": "object" },
+            }),
+            &["memory_type", "content"],
+        ),
+        tool(
+            "record_claim",
+            "Record a claim in a session, optionally tagged with a semantic marker",
+            json!({
+                "session_id": session_id,
+                "content": { "type": "string" },
+                "marker": { "type": "string", "enum": MARKERS },
+            }),
+            &["session_id", "content"],
+        ),
+        tool(
+            "check_budget",
+            "Context budget usage of a session",
+            json!({ "session_id": session_id }),
+            &["session_id"],
+        ),
+    ]
+}
+
+fn args<T: for<'de> Deserialize<'de>>(arguments: Value) -> Result<T, McpError> {
+    serde_json::from_value(arguments).map_err(|e| McpError::InvalidParams(e.to_string()))
+}
+
+fn tool_err(e: impl std::fmt::Display) -> McpError {
+    McpError::Tool(e.to_string())
+}
+
+/// `[INSIGHT]` form of a marker given as `insight`, `INSIGHT` or `[INSIGHT]`
+fn normalize_marker(marker: &str) -> Result<String, McpError> {
+    let bare = marker.trim().trim_matches(['[', ']']);
+    MARKERS
+        .into_iter()
+        .find(|m| m.eq_ignore_ascii_case(bare))
+        .map(|m| format!("[{}]", m))
+        .ok_or_else(|| McpError::InvalidParams(format!("unknown marker '{}'", marker)))
+}
+
+fn record_claim(args: RecordClaimArgs) -> Result<Value, McpError> {
+    let content = args.content.trim().to_string();
+    if content.is_empty() {
+        return Err(McpError::InvalidParams("content is empty".to_string()));
+    }
+    let marker = args.marker.as_deref().map(normalize_marker).transpose()?;
+    let session_path = get_session_dir_cli(&args.session_id)?.join("session.json");
+    if !session_path.exists() {
+        return Err(SessionError::NotFound(args.session_id).into());
+    }
+    let claim = Claim {
+        id: Ulid::new().to_string(),
+        content,
+        source_id: SOURCE_ID.to_string(),
+        marker,
+        created_at: Utc::now(),
+        source_span: None,
+        evidence_score: None,
+        commit: None,
+    };
+    let id = claim.id.clone();
+    update_session_file(&session_path, |session| {
+        session.claims.push(claim);
+        Ok(())
+    })?;
+    Ok(json!({ "claimId": id }))
+}
+
+async fn call_tool(name: &str, arguments: Value) -> Result<Value, McpError> {
+    match name {
+        "vault_query" => {
+            let a: VaultQueryArgs = args(arguments)?;
+            let results = query_notes(&a.query, a.budget.unwrap_or(5000)).map_err(tool_err)?;
+            Ok(json!(results))
+        }
+        "vault_note" => {
+            let a: VaultNoteArgs = args(arguments)?;
+            let note = get_note_content(&a.path, a.max_tokens.unwrap_or(2000)).map_err(tool_err)?;
+            Ok(json!(note))
+        }
+        "search_documents" => {
+            let a: SearchArgs = args(arguments)?;
+            let results = search_all_documents(&a.session_id, &a.query, a.top_k.unwrap_or(5), WORKING_BUDGET)
+                .await
+                .map_err(tool_err)?;
+            Ok(json!(results))
+        }
+        "search" => {
+            let a: SearchArgs = args(arguments)?;
+            let results = unified_search(&a.session_id, &a.query, a.budget.unwrap_or(WORKING_BUDGET))
+                .await
+                .map_err(tool_err)?;
+            Ok(json!(results))
+        }
+        "memory_read" => {
+            let a: MemoryReadArgs = args(arguments)?;
+            let memory_type = MemoryType::from_str(&a.memory_type).map_err(|e| McpError::InvalidParams(e.to_string()))?;
+            let memories = read_memories(memory_type, &a.query, a.n_results.unwrap_or(5), false).await.map_err(tool_err)?;
+            Ok(json!(memories))
+        }
+        "memory_write" => {
+            let a: MemoryWriteArgs = args(arguments)?;
+            let memory_type = MemoryType::from_str(&a.memory_type).map_err(|e| McpError::InvalidParams(e.to_string()))?;
+            let id = a.id.unwrap_or_else(|| Ulid::new().to_string());
+            queue_memory(memory_type, &id, &a.content, a.metadata).await.map_err(tool_err)?;
+            Ok(json!({ "id": id }))
+        }
+        "record_claim" => record_claim(args(arguments)?),
+        "check_budget" => {
+            let a: SessionArgs = args(arguments)?;
+            let session = load_session_cli(&a.session_id)?;
+            let budget = session.context_budget.unwrap_or_default();
+            Ok(json!(BudgetStatus::from(&budget)))
+        }
+        other => Err(McpError::UnknownTool(other.to_string())),
+    }
+}
+
+fn response(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error_response(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message.into() } })
+}
+
+/// Handle one JSON-RPC message; `None` for notifications
+pub async fn handle_message(line: &str) -> Option<Value> {
+    let request: Request = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, e.to_string())),
+    };
+    let id = request.id?;
+
+    let result = match request.method.as_str() {
+        "initialize" => json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "dialectic", "version": env!("CARGO_PKG_VERSION") },
+        }),
+        "ping" => json!({}),
+        "tools/list" => json!({ "tools": tool_definitions() }),
+        "tools/call" => {
+            let call: ToolCall = match serde_json::from_value(request.params) {
+                Ok(call) => call,
+                Err(e) => return Some(error_response(id, INVALID_PARAMS, e.to_string())),
+            };
+            // Tool failures are results the model can see, not protocol errors
+            match call_tool(&call.name, call.arguments).await {
+                Ok(value) => json!({
+                    "content": [{ "type": "text", "text": value.to_string() }],
+                    "isError": false,
+                }),
+                Err(McpError::UnknownTool(name)) => {
+                    return Some(error_response(id, INVALID_PARAMS, format!("Unknown tool: {}", name)));
+                }
+                Err(e) => json!({
+                    "content": [{ "type": "text", "text": e.to_string() }],
+                    "isError": true,
+                }),
+            }
+        }
+        other => return Some(error_response(id, METHOD_NOT_FOUND, format!("Method not found: {}", other))),
+    };
+    Some(response(id, result))
+}
+
+/// Index the configured vault for the vault tools; the app's index lives
+/// in another process
+fn load_vault(vault_path: Option<&str>) {
+    let Some(vault_path) = vault_path else { return };
+    if let Err(e) = configure_vault(vault_path).and_then(|_| index_vault()) {
+        warn!(error = %e, vault = %vault_path, "Failed to load vault for MCP");
+    }
+}
+
+/// Serve MCP over stdin/stdout until stdin closes
+pub async fn serve() -> std::io::Result<()> {
+    // A launch from a project with its own vault names it
+    let vault_path = std::env::var(VAULT_PATH_VAR).ok().or_else(|| load_preferences().vault_path);
+    tokio::task::spawn_blocking(move || load_vault(vault_path.as_deref()))
+        .await
+        .map_err(std::io::Error::other)?;
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(reply) = handle_message(&line).await {
+            stdout.write_all(format!("{}\n", reply).as_bytes()).await?;
+            stdout.flush().await?;
+        }
+    }
+    // Memory writes are queued; don't lose them when the client disconnects
+    write_queue::flush().await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_initialize_and_list() {
+        let init = handle_message(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#).await.unwrap();
+        assert_eq!(init["id"], 1);
+        assert_eq!(init["result"]["protocolVersion"], PROTOCOL_VERSION);
+
+        assert!(handle_message(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#).await.is_none());
+
+        let list = handle_message(r#"{"jsonrpc":"2.0","id":"a","method":"tools/list"}"#).await.unwrap();
+        let names: Vec<&str> = list["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert!(names.contains(&"record_claim"));
+        assert!(names.contains(&"check_budget"));
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let parse = handle_message("not json").await.unwrap();
+        assert_eq!(parse["error"]["code"], PARSE_ERROR);
+
+        let unknown = handle_message(r#"{"jsonrpc":"2.0","id":2,"method":"resources/list"}"#).await.unwrap();
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+
+        let bad_tool = handle_message(r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"nope"}}"#)
+            .await
+            .unwrap();
+        assert_eq!(bad_tool["error"]["code"], INVALID_PARAMS);
+
+        // Missing arguments surface as a tool error result
+        let bad_args = handle_message(
+            r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"vault_query","arguments":{}}}"#,
+        )
+        .await
+        .unwrap();
+        assert_eq!(bad_args["result"]["isError"], true);
+    }
+
+    #[tokio::test]
+    async fn test_vault_query_uses_configured_vault() {
+        use crate::obsidian::indexer::{fill_index, VaultIndex, VaultIndexOverride};
+
+        /// Removes the vault even if an assertion fails
+        struct RemoveOnDrop(std::path::PathBuf);
+        impl Drop for RemoveOnDrop {
+            fn drop(&mut self) {
+                std::fs::remove_dir_all(&self.0).ok();
+            }
+        }
+
+        let dir = std::env::temp_dir().join(format!("dialectic_mcp_vault_{}", Ulid::new()));
+        let _cleanup = RemoveOnDrop(dir.clone());
+        std::fs::create_dir_all(dir.join(".obsidian")).unwrap();
+        std::fs::write(dir.join("rates.md"), "# Rates\n\nThe Fed holds interest rates steady.").unwrap();
+        // Vault paths outside the home directory are refused, so the index
+        // is built and installed directly; the override restores the
+        // previous one
+        let mut vault = VaultIndex::new(dir.clone());
+        fill_index(&mut vault).unwrap();
+        let _vault = VaultIndexOverride::new(vault);
+
+        let reply = handle_message(
+            r#"{"jsonrpc":"2.0","id":5,"method":"tools/call","params":{"name":"vault_query","arguments":{"query":"interest rates"}}}"#,
+        )
+        .await
+        .unwrap();
+        assert_ne!(reply["result"]["isError"], true);
+        assert!(reply["result"]["content"][0]["text"].as_str().unwrap().contains("rates.md"));
+    }
+
+    #[test]
+    fn test_normalize_marker() {
+        assert_eq!(normalize_marker("insight").unwrap(), "[INSIGHT]");
+        assert_eq!(normalize_marker("[Risk]").unwrap(), "[RISK]");
+        assert!(normalize_marker("TODO").is_err());
+    }
+}
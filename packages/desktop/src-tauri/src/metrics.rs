@@ -0,0 +1,239 @@
+//! Local Operational Metrics
+//!
+//! In-process counters and timings for a diagnostics panel: Chroma query
+//! latency, indexing durations, compression events and per-session token
+//! usage. Nothing leaves the machine; values cover the current process
+//! since it started (or since the last reset).
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Samples kept per timing for percentiles
+const RECENT_SAMPLES: usize = 200;
+
+// Metric names
+pub const CHROMA_QUERY: &str = "chroma.query";
+pub const CHROMA_UPSERT: &str = "chroma.upsert";
+pub const CHROMA_ERRORS: &str = "chroma.errors";
+pub const VAULT_INDEX: &str = "obsidian.index";
+pub const VAULT_CHROMA_INDEX: &str = "obsidian.chroma_index";
+pub const DOCUMENT_INDEX: &str = "documents.index";
+pub const COMPRESSION_REQUESTS: &str = "compression.requests";
+pub const COMPRESSION_EVENTS: &str = "compression.events";
+
+static METRICS: LazyLock<Mutex<MetricsStore>> = LazyLock::new(|| Mutex::new(MetricsStore::new()));
+
+#[derive(Debug, Clone, Default)]
+struct Timing {
+    count: u64,
+    total_ms: f64,
+    max_ms: f64,
+    recent: VecDeque<f64>,
+}
+
+impl Timing {
+    fn record(&mut self, ms: f64) {
+        self.count += 1;
+        self.total_ms += ms;
+        self.max_ms = self.max_ms.max(ms);
+        if self.recent.len() == RECENT_SAMPLES {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(ms);
+    }
+
+    fn percentile(&self, p: f64) -> f64 {
+        if self.recent.is_empty() {
+            return 0.0;
+        }
+        let mut sorted: Vec<f64> = self.recent.iter().copied().collect();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let rank = ((p * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len());
+        sorted[rank - 1]
+    }
+}
+
+/// Token usage observed for one session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTokenUsage {
+    pub session_id: String,
+    /// Context budget used at the last launch or pass
+    pub context_tokens: u32,
+    /// Output tokens recorded across passes
+    pub output_tokens: u64,
+    pub passes: u32,
+    pub last_updated: Option<DateTime<Utc>>,
+}
+
+struct MetricsStore {
+    since: DateTime<Utc>,
+    timings: HashMap<String, Timing>,
+    counters: HashMap<String, u64>,
+    sessions: HashMap<String, SessionTokenUsage>,
+}
+
+impl MetricsStore {
+    fn new() -> Self {
+        Self {
+            since: Utc::now(),
+            timings: HashMap::new(),
+            counters: HashMap::new(),
+            sessions: HashMap::new(),
+        }
+    }
+
+    fn session(&mut self, session_id: &str) -> &mut SessionTokenUsage {
+        let usage = self.sessions.entry(session_id.to_string()).or_insert_with(|| SessionTokenUsage {
+            session_id: session_id.to_string(),
+            ..Default::default()
+        });
+        usage.last_updated = Some(Utc::now());
+        usage
+    }
+}
+
+/// Summary of one timing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimingSummary {
+    pub name: String,
+    pub count: u64,
+    pub avg_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Snapshot returned to the diagnostics panel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppMetrics {
+    pub since: DateTime<Utc>,
+    pub timings: Vec<TimingSummary>,
+    pub counters: BTreeMap<String, u64>,
+    /// Most recently active first
+    pub sessions: Vec<SessionTokenUsage>,
+}
+
+/// Record how long an operation took
+pub fn record_duration(name: &str, elapsed: Duration) {
+    METRICS.lock().timings.entry(name.to_string()).or_default().record(elapsed.as_secs_f64() * 1000.0);
+}
+
+/// Bump a counter
+pub fn increment(name: &str, by: u64) {
+    if by > 0 {
+        *METRICS.lock().counters.entry(name.to_string()).or_insert(0) += by;
+    }
+}
+
+/// Record a session's current context usage
+pub fn record_session_context(session_id: &str, context_tokens: u32) {
+    METRICS.lock().session(session_id).context_tokens = context_tokens;
+}
+
+/// Record a completed pass's output
+pub fn record_session_pass(session_id: &str, output_tokens: u32, context_tokens: u32) {
+    let mut store = METRICS.lock();
+    let usage = store.session(session_id);
+    usage.output_tokens += output_tokens as u64;
+    usage.passes += 1;
+    usage.context_tokens = context_tokens;
+}
+
+/// Snapshot current metrics. `prefix` limits timings and counters to
+/// names starting with it (e.g. "chroma.").
+pub fn snapshot(prefix: Option<&str>) -> AppMetrics {
+    let store = METRICS.lock();
+    let wanted = |name: &str| prefix.is_none_or(|p| name.starts_with(p));
+
+    let mut timings: Vec<TimingSummary> = store
+        .timings
+        .iter()
+        .filter(|(name, _)| wanted(name))
+        .map(|(name, t)| TimingSummary {
+            name: name.clone(),
+            count: t.count,
+            avg_ms: if t.count > 0 { t.total_ms / t.count as f64 } else { 0.0 },
+            p50_ms: t.percentile(0.5),
+            p95_ms: t.percentile(0.95),
+            max_ms: t.max_ms,
+        })
+        .collect();
+    timings.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut sessions: Vec<SessionTokenUsage> = store.sessions.values().cloned().collect();
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.last_updated));
+
+    AppMetrics {
+        since: store.since,
+        timings,
+        counters: store
+            .counters
+            .iter()
+            .filter(|(name, _)| wanted(name))
+            .map(|(k, v)| (k.clone(), *v))
+            .collect(),
+        sessions,
+    }
+}
+
+/// Clear all metrics
+pub fn reset() {
+    *METRICS.lock() = MetricsStore::new();
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn get_app_metrics(prefix: Option<String>) -> AppMetrics {
+    snapshot(prefix.as_deref())
+}
+
+#[tauri::command]
+pub fn reset_app_metrics() {
+    reset();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timing_percentiles() {
+        let mut timing = Timing::default();
+        for ms in 1..=100 {
+            timing.record(ms as f64);
+        }
+        assert_eq!(timing.percentile(0.5), 50.0);
+        assert_eq!(timing.percentile(0.95), 95.0);
+        assert_eq!(timing.max_ms, 100.0);
+        assert_eq!(Timing::default().percentile(0.5), 0.0);
+    }
+
+    #[test]
+    fn test_snapshot_filters_by_prefix() {
+        // Unique names so parallel tests sharing the store don't interfere
+        record_duration("test_metrics.query", Duration::from_millis(12));
+        increment("test_metrics.events", 2);
+        increment("other_metrics.events", 1);
+        record_session_pass("metrics-session", 300, 1_000);
+        record_session_pass("metrics-session", 200, 1_200);
+
+        let metrics = snapshot(Some("test_metrics."));
+        assert_eq!(metrics.timings.len(), 1);
+        assert_eq!(metrics.timings[0].count, 1);
+        assert_eq!(metrics.counters.get("test_metrics.events"), Some(&2));
+        assert!(!metrics.counters.contains_key("other_metrics.events"));
+
+        let usage = metrics.sessions.iter().find(|s| s.session_id == "metrics-session").unwrap();
+        assert_eq!(usage.output_tokens, 500);
+        assert_eq!(usage.passes, 2);
+        assert_eq!(usage.context_tokens, 1_200);
+    }
+}
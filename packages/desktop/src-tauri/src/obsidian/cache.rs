@@ -0,0 +1,185 @@
+//! Obsidian Query Cache
+//!
+//! Small LRU cache for keyword and semantic vault queries. The app re-runs
+//! the same queries often during a session (search as you type, launches,
+//! context refreshes), and each uncached call re-scores the whole index.
+//! The cache lives in the app process; CLI and hook calls start cold. The
+//! vault watcher and full re-indexes clear it.
+
+use parking_lot::Mutex;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::LazyLock;
+
+use super::query::QueryResult;
+
+/// Maximum cached queries
+const QUERY_CACHE_CAPACITY: usize = 128;
+
+/// Global query cache
+static QUERY_CACHE: LazyLock<Mutex<LruCache<QueryCacheKey, Vec<QueryResult>>>> =
+    LazyLock::new(|| Mutex::new(LruCache::new(QUERY_CACHE_CAPACITY)));
+
+/// Which query path produced a cached result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QueryKind {
+    Keyword,
+    Semantic,
+}
+
+/// Cache key: query kind, normalized query text, its size limit (token
+/// budget for keyword queries, result count for semantic ones) and a hash
+/// of anything else that shapes the ranking
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QueryCacheKey {
+    kind: QueryKind,
+    query: String,
+    limit: u32,
+    context: u64,
+}
+
+impl QueryCacheKey {
+    pub fn new(kind: QueryKind, query: &str, limit: u32) -> Self {
+        Self {
+            kind,
+            query: query.trim().to_lowercase(),
+            limit,
+            context: 0,
+        }
+    }
+
+    /// Key results that also depend on `context`
+    pub fn with_context(mut self, context: impl Hash) -> Self {
+        let mut hasher = DefaultHasher::new();
+        context.hash(&mut hasher);
+        self.context = hasher.finish();
+        self
+    }
+}
+
+/// Least-recently-used cache with a fixed capacity
+pub struct LruCache<K, V> {
+    map: HashMap<K, V>,
+    order: VecDeque<K>,
+    capacity: usize,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> LruCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            map: HashMap::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Get a value, marking it most recently used
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let value = self.map.get(key).cloned()?;
+        self.touch(key);
+        Some(value)
+    }
+
+    /// Insert a value, evicting the least recently used entry at capacity
+    pub fn insert(&mut self, key: K, value: V) {
+        if self.map.insert(key.clone(), value).is_some() {
+            self.touch(&key);
+            return;
+        }
+        self.order.push_back(key);
+        while self.order.len() > self.capacity {
+            if let Some(evicted) = self.order.pop_front() {
+                self.map.remove(&evicted);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.order.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    fn touch(&mut self, key: &K) {
+        if let Some(pos) = self.order.iter().position(|k| k == key) {
+            if let Some(k) = self.order.remove(pos) {
+                self.order.push_back(k);
+            }
+        }
+    }
+}
+
+/// Look up cached results for a query
+pub fn get_cached_query(key: &QueryCacheKey) -> Option<Vec<QueryResult>> {
+    QUERY_CACHE.lock().get(key)
+}
+
+/// Cache results for a query
+pub fn cache_query(key: QueryCacheKey, results: Vec<QueryResult>) {
+    QUERY_CACHE.lock().insert(key, results);
+}
+
+/// Drop all cached queries (vault contents changed)
+pub fn invalidate_query_cache() {
+    let mut cache = QUERY_CACHE.lock();
+    if !cache.is_empty() {
+        tracing::debug!(entries = cache.len(), "Invalidated obsidian query cache");
+    }
+    cache.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_eviction_order() {
+        let mut cache = LruCache::new(2);
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        // Touch "a" so "b" becomes least recently used
+        assert_eq!(cache.get(&"a"), Some(1));
+        cache.insert("c", 3);
+
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!(cache.get(&"a"), Some(1));
+        assert_eq!(cache.get(&"c"), Some(3));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_lru_overwrite_and_clear() {
+        let mut cache = LruCache::new(2);
+        cache.insert("a", 1);
+        cache.insert("a", 2);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get(&"a"), Some(2));
+
+        cache.clear();
+        assert_eq!(cache.get(&"a"), None);
+    }
+
+    #[test]
+    fn test_key_normalization() {
+        assert_eq!(
+            QueryCacheKey::new(QueryKind::Keyword, "  Pricing Power ", 500),
+            QueryCacheKey::new(QueryKind::Keyword, "pricing power", 500)
+        );
+        assert_ne!(
+            QueryCacheKey::new(QueryKind::Keyword, "pricing", 500),
+            QueryCacheKey::new(QueryKind::Semantic, "pricing", 500)
+        );
+        assert_ne!(
+            QueryCacheKey::new(QueryKind::Keyword, "pricing", 500).with_context(["a.md"]),
+            QueryCacheKey::new(QueryKind::Keyword, "pricing", 500).with_context(["b.md"])
+        );
+    }
+}
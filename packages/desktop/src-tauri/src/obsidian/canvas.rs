@@ -0,0 +1,170 @@
+//! Obsidian Canvas Parsing
+//!
+//! `.canvas` files are JSON Canvas boards: a list of nodes (text cards,
+//! embedded files, web links, groups) and edges between them. They are
+//! rendered to markdown so the rest of the indexer treats them like notes:
+//! file nodes become `[[path]]` links and edges become labelled bullets.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// File extension for Obsidian canvas boards
+pub const CANVAS_EXTENSION: &str = "canvas";
+
+/// Parsed JSON Canvas document
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Canvas {
+    #[serde(default)]
+    pub nodes: Vec<CanvasNode>,
+    #[serde(default)]
+    pub edges: Vec<CanvasEdge>,
+}
+
+/// A canvas node (`text`, `file`, `link` or `group`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct CanvasNode {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub node_type: String,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub file: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// A directed connection between two nodes
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasEdge {
+    pub from_node: String,
+    pub to_node: String,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl CanvasNode {
+    /// Short human-readable name used when rendering edges
+    fn display_name(&self) -> String {
+        match self.node_type.as_str() {
+            "file" => self.file.as_deref().map(|f| format!("[[{}]]", f)),
+            "link" => self.url.clone(),
+            "group" => self.label.clone(),
+            _ => self
+                .text
+                .as_deref()
+                .and_then(|t| t.lines().map(str::trim).find(|l| !l.is_empty()))
+                .map(|l| l.trim_start_matches('#').trim().chars().take(80).collect()),
+        }
+        .unwrap_or_else(|| self.id.clone())
+    }
+}
+
+impl Canvas {
+    /// Parse a `.canvas` file
+    pub fn parse(content: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(content)
+    }
+
+    /// Vault paths of embedded file nodes
+    pub fn file_links(&self) -> Vec<String> {
+        self.nodes.iter().filter_map(|n| n.file.clone()).collect()
+    }
+
+    /// Render the board as markdown: text cards first (so the summary comes
+    /// from real content), then linked files, web links and connections.
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+
+        for node in self.nodes.iter().filter(|n| n.node_type == "text") {
+            if let Some(text) = node.text.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+                md.push_str(text);
+                md.push_str("\n\n");
+            }
+        }
+
+        let files = self.file_links();
+        if !files.is_empty() {
+            md.push_str("## Files\n\n");
+            for file in files {
+                md.push_str(&format!("- [[{}]]\n", file));
+            }
+            md.push('\n');
+        }
+
+        let urls: Vec<&str> = self.nodes.iter().filter_map(|n| n.url.as_deref()).collect();
+        if !urls.is_empty() {
+            md.push_str("## Links\n\n");
+            for url in urls {
+                md.push_str(&format!("- {}\n", url));
+            }
+            md.push('\n');
+        }
+
+        let names: HashMap<&str, String> = self
+            .nodes
+            .iter()
+            .map(|n| (n.id.as_str(), n.display_name()))
+            .collect();
+        let connections: Vec<String> = self
+            .edges
+            .iter()
+            .filter_map(|e| {
+                let from = names.get(e.from_node.as_str())?;
+                let to = names.get(e.to_node.as_str())?;
+                Some(match e.label.as_deref().filter(|l| !l.is_empty()) {
+                    Some(label) => format!("- {} → {} ({})\n", from, to, label),
+                    None => format!("- {} → {}\n", from, to),
+                })
+            })
+            .collect();
+        if !connections.is_empty() {
+            md.push_str("## Connections\n\n");
+            for line in connections {
+                md.push_str(&line);
+            }
+        }
+
+        md
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r##"{
+        "nodes": [
+            {"id": "a", "type": "text", "text": "# Pricing power\nMargins hold because of #moats", "x": 0, "y": 0, "width": 100, "height": 100},
+            {"id": "b", "type": "file", "file": "Notes/Moats.md", "x": 0, "y": 0, "width": 100, "height": 100},
+            {"id": "c", "type": "link", "url": "https://example.com/report", "x": 0, "y": 0, "width": 100, "height": 100},
+            {"id": "g", "type": "group", "label": "Evidence", "x": 0, "y": 0, "width": 100, "height": 100}
+        ],
+        "edges": [
+            {"id": "e1", "fromNode": "b", "toNode": "a", "label": "supports"},
+            {"id": "e2", "fromNode": "c", "toNode": "missing"}
+        ]
+    }"##;
+
+    #[test]
+    fn test_parse_canvas() {
+        let canvas = Canvas::parse(SAMPLE).unwrap();
+        assert_eq!(canvas.nodes.len(), 4);
+        assert_eq!(canvas.edges.len(), 2);
+        assert_eq!(canvas.file_links(), vec!["Notes/Moats.md"]);
+    }
+
+    #[test]
+    fn test_canvas_to_markdown() {
+        let md = Canvas::parse(SAMPLE).unwrap().to_markdown();
+        assert!(md.starts_with("# Pricing power"));
+        assert!(md.contains("- [[Notes/Moats.md]]"));
+        assert!(md.contains("- https://example.com/report"));
+        assert!(md.contains("- [[Notes/Moats.md]] → Pricing power (supports)"));
+        // Edges to unknown nodes are dropped
+        assert!(!md.contains("missing"));
+    }
+}
@@ -0,0 +1,173 @@
+//! Vault Indexing Exclusions
+//!
+//! Folder, glob and size exclusions applied when indexing a vault. Read from
+//! the `vaultIndexing` section of preferences.json and from a
+//! `.dialecticignore` file in the vault root; both sources are merged.
+//!
+//! `.dialecticignore` format (one rule per line, `#` for comments):
+//! - `Templates/` — a trailing slash excludes a folder and everything in it
+//! - `Daily/*.md` — anything else is a glob matched against the relative path
+//! - `max-size: 256` — skip notes larger than 256 KB
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tracing::warn;
+
+/// Ignore file read from the vault root
+pub const IGNORE_FILE: &str = ".dialecticignore";
+
+/// Exclusion settings as written in preferences or the ignore file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultIndexConfig {
+    /// Folders (relative to the vault root) skipped entirely
+    #[serde(default)]
+    pub excluded_folders: Vec<String>,
+    /// Glob patterns matched against note paths relative to the vault root
+    #[serde(default)]
+    pub exclude_patterns: Vec<String>,
+    /// Notes larger than this are not indexed
+    #[serde(default)]
+    pub max_note_bytes: Option<u64>,
+}
+
+impl VaultIndexConfig {
+    /// Parse a `.dialecticignore` file
+    pub fn from_ignore_file(content: &str) -> Self {
+        let mut config = Self::default();
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(size) = line.strip_prefix("max-size:") {
+                match size.trim().parse::<u64>() {
+                    Ok(kb) => config.max_note_bytes = Some(kb * 1024),
+                    Err(_) => warn!(line = %line, "Invalid max-size in {}", IGNORE_FILE),
+                }
+            } else if let Some(folder) = line.strip_suffix('/') {
+                config.excluded_folders.push(folder.to_string());
+            } else {
+                config.exclude_patterns.push(line.to_string());
+            }
+        }
+        config
+    }
+
+    /// Read the `vaultIndexing` section of preferences.json (if any)
+    pub fn from_preferences() -> Self {
+        crate::config::preferences::load_preferences().vault_indexing
+    }
+
+    /// Merge preferences with the vault's ignore file
+    pub fn load(vault_path: &Path) -> Self {
+        let mut config = Self::from_preferences();
+        if let Ok(content) = fs::read_to_string(vault_path.join(IGNORE_FILE)) {
+            config.merge(Self::from_ignore_file(&content));
+        }
+        config
+    }
+
+    /// Union of both rule sets; the smaller size limit wins
+    pub fn merge(&mut self, other: Self) {
+        self.excluded_folders.extend(other.excluded_folders);
+        self.exclude_patterns.extend(other.exclude_patterns);
+        self.max_note_bytes = match (self.max_note_bytes, other.max_note_bytes) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    /// Compile into a filter, dropping invalid glob patterns
+    pub fn compile(&self) -> VaultFilter {
+        let patterns = self
+            .exclude_patterns
+            .iter()
+            .filter_map(|p| match glob::Pattern::new(p) {
+                Ok(pattern) => Some(pattern),
+                Err(e) => {
+                    warn!(pattern = %p, error = %e, "Ignoring invalid exclude pattern");
+                    None
+                }
+            })
+            .collect();
+
+        VaultFilter {
+            folders: self
+                .excluded_folders
+                .iter()
+                .map(|f| f.trim_matches('/').to_string())
+                .filter(|f| !f.is_empty())
+                .collect(),
+            patterns,
+            max_note_bytes: self.max_note_bytes,
+        }
+    }
+}
+
+/// Compiled exclusion rules
+#[derive(Debug, Clone, Default)]
+pub struct VaultFilter {
+    folders: Vec<String>,
+    patterns: Vec<glob::Pattern>,
+    max_note_bytes: Option<u64>,
+}
+
+impl VaultFilter {
+    /// Whether a path (relative to the vault root) is excluded
+    pub fn is_excluded(&self, relative: &Path) -> bool {
+        let in_folder = self
+            .folders
+            .iter()
+            .any(|folder| relative.starts_with(folder));
+        in_folder || self.patterns.iter().any(|p| p.matches_path(relative))
+    }
+
+    /// Whether a note of `bytes` exceeds the configured size limit
+    pub fn exceeds_size(&self, bytes: u64) -> bool {
+        self.max_note_bytes.is_some_and(|max| bytes > max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ignore_file() {
+        let content = "# comment\nTemplates/\nDaily/*.md\n\nmax-size: 64\n";
+        let config = VaultIndexConfig::from_ignore_file(content);
+        assert_eq!(config.excluded_folders, vec!["Templates"]);
+        assert_eq!(config.exclude_patterns, vec!["Daily/*.md"]);
+        assert_eq!(config.max_note_bytes, Some(64 * 1024));
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let config = VaultIndexConfig {
+            excluded_folders: vec!["Templates/".to_string()],
+            exclude_patterns: vec!["Daily/*.md".to_string()],
+            max_note_bytes: Some(100),
+        };
+        let filter = config.compile();
+
+        assert!(filter.is_excluded(Path::new("Templates/meeting.md")));
+        assert!(filter.is_excluded(Path::new("Templates")));
+        assert!(filter.is_excluded(Path::new("Daily/2024-01-01.md")));
+        assert!(!filter.is_excluded(Path::new("TemplatesArchive/note.md")));
+        assert!(!filter.is_excluded(Path::new("Projects/plan.md")));
+        assert!(filter.exceeds_size(101));
+        assert!(!filter.exceeds_size(100));
+    }
+
+    #[test]
+    fn test_merge_takes_smaller_limit() {
+        let mut a = VaultIndexConfig {
+            max_note_bytes: Some(2048),
+            ..Default::default()
+        };
+        a.merge(VaultIndexConfig::from_ignore_file("max-size: 1\nArchive/"));
+        assert_eq!(a.max_note_bytes, Some(1024));
+        assert_eq!(a.excluded_folders, vec!["Archive"]);
+    }
+}
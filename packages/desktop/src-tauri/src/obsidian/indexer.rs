@@ -10,7 +10,18 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
+
+use crate::chroma::client::ChromaUpsertItem;
+use crate::chroma::collections::{add_entity_fields, collection_metadata};
+use crate::chroma::store::VectorStore;
+use crate::context::tokens::estimate_tokens_quick;
+use crate::documents::sentences::find_split_boundary;
+use crate::events::{IndexOperation, ProgressReporter};
+use crate::jobs::{self, JobHandle, JobKind};
+use super::cache::invalidate_query_cache;
+use super::canvas::{Canvas, CANVAS_EXTENSION};
+use super::exclusions::{VaultFilter, VaultIndexConfig};
 
 /// Global vault index
 static VAULT_INDEX: RwLock<Option<VaultIndex>> = RwLock::new(None);
@@ -25,6 +36,8 @@ pub enum ObsidianError {
     InvalidPath(String),
     #[error("Note not found: {0}")]
     NoteNotFound(String),
+    #[error("Search cancelled")]
+    Cancelled,
 }
 
 impl Serialize for ObsidianError {
@@ -73,10 +86,13 @@ pub struct VaultIndex {
     pub last_indexed: DateTime<Utc>,
     /// Last successful Chroma index timestamp (for incremental indexing)
     pub last_chroma_indexed: DateTime<Utc>,
+    /// Folder/glob/size exclusions
+    pub filter: VaultFilter,
 }
 
 impl VaultIndex {
     pub fn new(vault_path: PathBuf) -> Self {
+        let filter = VaultIndexConfig::load(&vault_path).compile();
         Self {
             vault_path,
             notes: HashMap::new(),
@@ -85,15 +101,14 @@ impl VaultIndex {
             last_indexed: Utc::now(),
             // Use epoch so first index_vault_to_chroma captures all notes
             last_chroma_indexed: DateTime::<Utc>::default(),
+            filter,
         }
     }
 
     /// Index a single note file
     fn index_note(&mut self, path: &Path) -> Result<(), ObsidianError> {
-        let content = fs::read_to_string(path)?;
-        let relative_path = path.strip_prefix(&self.vault_path)
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|_| path.to_string_lossy().to_string());
+        let content = read_note_text(path)?;
+        let relative_path = self.relative_path(path);
 
         let title = path.file_stem()
             .map(|s| s.to_string_lossy().to_string())
@@ -114,8 +129,8 @@ impl VaultIndex {
             .map(|t| t.into())
             .unwrap_or_else(|_| Utc::now());
 
-        // Estimate tokens (~4 chars per token)
-        let token_count = (content.len() as f64 / 4.0).ceil() as u32;
+        // Estimate tokens (ratio depends on prose/code/CJK content)
+        let token_count = estimate_tokens_quick(&content);
 
         let note = NoteIndex {
             path: relative_path.clone(),
@@ -165,6 +180,63 @@ impl VaultIndex {
         }
     }
 
+    /// Drop a note and every mapping that points at it.
+    /// Returns true if the note was indexed.
+    fn remove_note(&mut self, relative_path: &str) -> bool {
+        let note = match self.notes.remove(relative_path) {
+            Some(n) => n,
+            None => return false,
+        };
+
+        // Re-point the title at another note with the same name, if any
+        let title_key = note.title.to_lowercase();
+        if self.title_to_path.get(&title_key).is_some_and(|p| p == relative_path) {
+            match self.notes.values().find(|n| n.title.to_lowercase() == title_key) {
+                Some(other) => {
+                    self.title_to_path.insert(title_key, other.path.clone());
+                }
+                None => {
+                    self.title_to_path.remove(&title_key);
+                }
+            }
+        }
+
+        for tag in &note.tags {
+            if let Some(paths) = self.tag_to_paths.get_mut(tag) {
+                paths.retain(|p| p != relative_path);
+                if paths.is_empty() {
+                    self.tag_to_paths.remove(tag);
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Recompute backlinks from scratch (after notes were removed or changed)
+    fn rebuild_backlinks(&mut self) {
+        for note in self.notes.values_mut() {
+            note.backlinks.clear();
+        }
+        self.build_backlinks();
+    }
+
+    /// Whether a file should be kept out of the index (excluded or oversized)
+    fn is_excluded(&self, path: &Path) -> bool {
+        let relative = path.strip_prefix(&self.vault_path).unwrap_or(path);
+        if self.filter.is_excluded(relative) {
+            return true;
+        }
+        fs::metadata(path).is_ok_and(|m| m.is_file() && self.filter.exceeds_size(m.len()))
+    }
+
+    /// Path relative to the vault root, as used for index keys
+    fn relative_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.vault_path)
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|_| path.to_string_lossy().to_string())
+    }
+
     /// Resolve a [[link]] to a path
     fn resolve_link(&self, link: &str) -> Option<String> {
         // Remove alias if present: [[target|alias]] -> target
@@ -190,6 +262,24 @@ impl VaultIndex {
     }
 }
 
+/// Whether a file is a note the indexer understands (markdown or canvas)
+pub fn is_indexable(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|e| e == "md" || e == CANVAS_EXTENSION)
+}
+
+/// Read a note as markdown text. Canvas boards are rendered from their JSON;
+/// a canvas that fails to parse is reported as invalid data.
+pub fn read_note_text(path: &Path) -> Result<String, std::io::Error> {
+    let content = fs::read_to_string(path)?;
+    if path.extension().is_some_and(|e| e == CANVAS_EXTENSION) {
+        let canvas = Canvas::parse(&content)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        return Ok(canvas.to_markdown());
+    }
+    Ok(content)
+}
+
 /// Extract first paragraph as summary
 fn extract_summary(content: &str) -> String {
     // Skip YAML frontmatter if present
@@ -275,8 +365,9 @@ fn extract_tags(content: &str) -> Vec<String> {
     tags
 }
 
-/// Configure vault path (validation only, no indexing)
-pub fn configure_vault(vault_path: &str) -> Result<(), ObsidianError> {
+/// Check that `vault_path` is an Obsidian vault the app may read and
+/// resolve it
+fn validate_vault_path(vault_path: &str) -> Result<PathBuf, ObsidianError> {
     let path = PathBuf::from(vault_path);
 
     if !path.exists() {
@@ -304,13 +395,57 @@ pub fn configure_vault(vault_path: &str) -> Result<(), ObsidianError> {
         return Err(ObsidianError::InvalidPath("Not an Obsidian vault (no .obsidian folder)".to_string()));
     }
 
+    Ok(canonical_path)
+}
+
+/// Configure vault path (validation only, no indexing)
+pub fn configure_vault(vault_path: &str) -> Result<(), ObsidianError> {
+    let canonical_path = validate_vault_path(vault_path)?;
+
     // Initialize empty index
     let mut index = VAULT_INDEX.write();
     *index = Some(VaultIndex::new(canonical_path));
+    invalidate_query_cache();
 
     Ok(())
 }
 
+/// Installs a test index as the configured vault, without validating its
+/// path, and restores the previous one on drop. Overrides are serialized,
+/// since the index is process-wide.
+#[cfg(test)]
+pub(crate) struct VaultIndexOverride {
+    previous: Option<VaultIndex>,
+    _serial: parking_lot::MutexGuard<'static, ()>,
+}
+
+#[cfg(test)]
+impl VaultIndexOverride {
+    pub(crate) fn new(index: VaultIndex) -> Self {
+        static SERIAL: parking_lot::Mutex<()> = parking_lot::Mutex::new(());
+        let serial = SERIAL.lock();
+        let previous = VAULT_INDEX.write().replace(index);
+        invalidate_query_cache();
+        Self { previous, _serial: serial }
+    }
+}
+
+#[cfg(test)]
+impl Drop for VaultIndexOverride {
+    fn drop(&mut self) {
+        *VAULT_INDEX.write() = self.previous.take();
+        invalidate_query_cache();
+    }
+}
+
+/// Index a vault without making it the configured one, for callers that
+/// need another vault for a single operation
+pub fn build_vault_index(vault_path: &str) -> Result<VaultIndex, ObsidianError> {
+    let mut vault = VaultIndex::new(validate_vault_path(vault_path)?);
+    fill_index(&mut vault)?;
+    Ok(vault)
+}
+
 /// Threshold (in tokens) above which a note is chunked into multiple vectors.
 /// Notes below this are stored as a single vector.
 const NOTE_CHUNK_THRESHOLD: u32 = 1_000;
@@ -337,10 +472,8 @@ fn chunk_note_content(content: &str) -> Vec<(String, u32)> {
 
         // Force split if chunk gets too large
         if current_chunk.len() >= NOTE_CHUNK_TARGET {
-            // Try to find a paragraph boundary in the latter half
-            let mid = current_chunk.len() / 2;
-            if let Some(pos) = current_chunk[mid..].find("\n\n") {
-                let split_at = mid + pos + 2;
+            // Split at a paragraph or sentence boundary in the latter half
+            if let Some(split_at) = find_split_boundary(&current_chunk) {
                 let first = current_chunk[..split_at].to_string();
                 let rest = current_chunk[split_at..].to_string();
                 chunks.push((first, chunk_index));
@@ -357,11 +490,68 @@ fn chunk_note_content(content: &str) -> Vec<(String, u32)> {
     chunks
 }
 
-/// A single piece to upsert to Chroma (note or note chunk)
-struct ChromaUpsertItem {
-    id: String,
-    document: String,
-    metadata: serde_json::Value,
+/// Chroma vector ID prefix for a note (relative path with `/` flattened)
+fn note_vector_id(path: &str) -> String {
+    format!("obsidian_{}", path.replace('/', "_"))
+}
+
+/// Build the upsert items for one note, chunking large notes
+fn note_upsert_items(
+    path: &str,
+    title: &str,
+    content: &str,
+    tags: &[String],
+    token_count: u32,
+    modified: &str,
+) -> Vec<ChromaUpsertItem> {
+    if token_count > NOTE_CHUNK_THRESHOLD {
+        // Chunk large notes into multiple vectors
+        let chunks = chunk_note_content(content);
+        let total_chunks = chunks.len() as u32;
+        chunks.into_iter().map(|(chunk_content, chunk_index)| {
+            let chunk_tokens = estimate_tokens_quick(&chunk_content);
+            let mut metadata = crate::chroma::collections::obsidian_chunk_metadata_indexed(
+                path, title, tags, chunk_tokens, modified, chunk_index, total_chunks,
+            );
+            add_entity_fields(&mut metadata, &format!("{}\n{}", title, chunk_content));
+            ChromaUpsertItem {
+                id: format!("{}_chunk{}", note_vector_id(path), chunk_index),
+                document: chunk_content,
+                metadata,
+            }
+        }).collect()
+    } else {
+        // Small notes: single vector
+        let mut metadata = crate::chroma::collections::obsidian_chunk_metadata(
+            path, title, tags, token_count, modified,
+        );
+        add_entity_fields(&mut metadata, &format!("{}\n{}", title, content));
+        vec![ChromaUpsertItem {
+            id: note_vector_id(path),
+            document: content.to_string(),
+            metadata,
+        }]
+    }
+}
+
+/// Upsert items in concurrent batches, reporting items processed so far.
+/// Returns the number of items written.
+async fn upsert_items(
+    client: &impl VectorStore,
+    collection_id: &str,
+    items: &[ChromaUpsertItem],
+    on_progress: impl FnMut(u32) + Send,
+) -> u32 {
+    let outcome = client.upsert_batched(collection_id, items, on_progress).await;
+    if let Some(first) = outcome.errors.first() {
+        warn!(
+            failed_batches = outcome.errors.len(),
+            failed_items = outcome.failed,
+            error = %first,
+            "Chroma obsidian indexing batches failed"
+        );
+    }
+    outcome.upserted
 }
 
 /// Index the vault into Chroma for semantic search (best-effort, non-blocking).
@@ -377,7 +567,7 @@ pub async fn index_vault_to_chroma() -> u32 {
                     .map(|note| {
                         // Read the full content for Chroma indexing
                         let full_path = vault.vault_path.join(&note.path);
-                        let content = fs::read_to_string(&full_path).unwrap_or_else(|_| note.summary.clone());
+                        let content = read_note_text(&full_path).unwrap_or_else(|_| note.summary.clone());
                         (
                             note.path.clone(),
                             note.title.clone(),
@@ -398,66 +588,30 @@ pub async fn index_vault_to_chroma() -> u32 {
 
     let client = crate::chroma::client::get_client();
     let collection = match client.get_or_create_collection(
-        crate::chroma::collections::COLLECTION_OBSIDIAN, None
+        crate::chroma::collections::COLLECTION_OBSIDIAN,
+        Some(collection_metadata(crate::chroma::collections::COLLECTION_OBSIDIAN)),
     ).await {
         Ok(c) => c,
         Err(_) => return 0,
     };
 
     // Build upsert items, chunking large notes
-    let mut items: Vec<ChromaUpsertItem> = Vec::new();
-    for (path, title, content, tags, token_count, modified) in &notes_data {
-        if *token_count > NOTE_CHUNK_THRESHOLD {
-            // Chunk large notes into multiple vectors
-            let chunks = chunk_note_content(content);
-            let total_chunks = chunks.len() as u32;
-            for (chunk_content, chunk_index) in chunks {
-                let chunk_tokens = (chunk_content.len() as f64 / 4.0).ceil() as u32;
-                items.push(ChromaUpsertItem {
-                    id: format!("obsidian_{}_chunk{}", path.replace('/', "_"), chunk_index),
-                    document: chunk_content,
-                    metadata: crate::chroma::collections::obsidian_chunk_metadata_indexed(
-                        path, title, tags, chunk_tokens, modified, chunk_index, total_chunks,
-                    ),
-                });
-            }
-        } else {
-            // Small notes: single vector
-            items.push(ChromaUpsertItem {
-                id: format!("obsidian_{}", path.replace('/', "_")),
-                document: content.clone(),
-                metadata: crate::chroma::collections::obsidian_chunk_metadata(
-                    path, title, tags, *token_count, modified,
-                ),
-            });
-        }
-    }
-
-    // Batch upsert in groups of 50
-    let mut indexed = 0u32;
-    for batch in items.chunks(50) {
-        let ids: Vec<String> = batch.iter().map(|item| item.id.clone()).collect();
-        let documents: Vec<String> = batch.iter().map(|item| item.document.clone()).collect();
-        let metadatas: Vec<serde_json::Value> = batch.iter().map(|item| item.metadata.clone()).collect();
-
-        let embeddings = crate::chroma::client::embed_documents(&documents);
+    let items: Vec<ChromaUpsertItem> = notes_data.iter()
+        .flat_map(|(path, title, content, tags, token_count, modified)| {
+            note_upsert_items(path, title, content, tags, *token_count, modified)
+        })
+        .collect();
 
-        match client.upsert(
-            &collection.id,
-            ids,
-            Some(documents),
-            Some(embeddings),
-            Some(metadatas),
-        ).await {
-            Ok(_) => indexed += batch.len() as u32,
-            Err(e) => {
-                warn!(error = %e, "Chroma obsidian indexing batch failed");
-            }
-        }
-    }
+    let vault_path = VAULT_INDEX.read().as_ref().map(|v| v.vault_path.display().to_string()).unwrap_or_default();
+    let mut progress = ProgressReporter::start(IndexOperation::VaultChroma, vault_path, items.len());
+    let start = std::time::Instant::now();
+    let indexed = upsert_items(&client, &collection.id, &items, |processed| progress.set(processed)).await;
+    crate::metrics::record_duration(crate::metrics::VAULT_CHROMA_INDEX, start.elapsed());
+    progress.finish();
 
     // Update Chroma index timestamp so next call only processes new changes
     if indexed > 0 {
+        invalidate_query_cache();
         let mut index = VAULT_INDEX.write();
         if let Some(vault) = index.as_mut() {
             vault.last_chroma_indexed = Utc::now();
@@ -467,31 +621,125 @@ pub async fn index_vault_to_chroma() -> u32 {
     indexed
 }
 
+/// Incrementally sync specific notes to Chroma (best-effort).
+///
+/// `changed` notes have their existing vectors replaced (chunk counts may
+/// differ between versions); `removed` notes — deleted or renamed away —
+/// have their vectors dropped. Paths are relative to the vault root.
+/// Returns the number of vectors upserted.
+pub async fn sync_notes_to_chroma(changed: &[String], removed: &[String]) -> u32 {
+    if changed.is_empty() && removed.is_empty() {
+        return 0;
+    }
+
+    let notes_data: Vec<(String, String, String, Vec<String>, u32, String)> = {
+        let index = VAULT_INDEX.read();
+        match index.as_ref() {
+            Some(vault) => changed.iter()
+                .filter_map(|path| vault.notes.get(path))
+                .filter_map(|note| {
+                    let content = read_note_text(&vault.vault_path.join(&note.path)).ok()?;
+                    Some((
+                        note.path.clone(),
+                        note.title.clone(),
+                        content,
+                        note.tags.clone(),
+                        note.token_count,
+                        note.modified.to_rfc3339(),
+                    ))
+                })
+                .collect(),
+            None => return 0,
+        }
+    };
+
+    let client = crate::chroma::client::get_client();
+    let collection = match client.get_or_create_collection(
+        crate::chroma::collections::COLLECTION_OBSIDIAN,
+        Some(collection_metadata(crate::chroma::collections::COLLECTION_OBSIDIAN)),
+    ).await {
+        Ok(c) => c,
+        Err(_) => return 0,
+    };
+
+    // Drop stale vectors for every touched path before re-upserting
+    for path in changed.iter().chain(removed.iter()) {
+        let filter = serde_json::json!({ "path": { "$eq": path } });
+        if let Err(e) = client.delete(&collection.id, None, Some(filter)).await {
+            warn!(path = %path, error = %e, "Failed to delete stale obsidian vectors");
+        }
+    }
+
+    let items: Vec<ChromaUpsertItem> = notes_data.iter()
+        .flat_map(|(path, title, content, tags, token_count, modified)| {
+            note_upsert_items(path, title, content, tags, *token_count, modified)
+        })
+        .collect();
+
+    let indexed = upsert_items(&client, &collection.id, &items, |_| {}).await;
+    // Semantic results may have been cached while the sync was in flight
+    invalidate_query_cache();
+    debug!(changed = changed.len(), removed = removed.len(), vectors = indexed, "Synced obsidian notes to Chroma");
+    indexed
+}
+
 /// Index the entire vault
 pub fn index_vault() -> Result<IndexStats, ObsidianError> {
+    let start = std::time::Instant::now();
     let mut index = VAULT_INDEX.write();
     let vault = index.as_mut().ok_or(ObsidianError::NotConfigured)?;
+    let stats = fill_index(vault)?;
+    invalidate_query_cache();
+    crate::metrics::record_duration(crate::metrics::VAULT_INDEX, start.elapsed());
+
+    Ok(stats)
+}
+
+/// Re-index every note of `vault` from disk
+pub(crate) fn fill_index(vault: &mut VaultIndex) -> Result<IndexStats, ObsidianError> {
+    // Remember what was indexed so vanished notes can be reported as tombstones
+    let previous: Vec<String> = vault.notes.keys().cloned().collect();
+
+    // Pick up edits to preferences or .dialecticignore
+    vault.filter = VaultIndexConfig::load(&vault.vault_path).compile();
 
     // Clear existing index
     vault.notes.clear();
     vault.title_to_path.clear();
     vault.tag_to_paths.clear();
 
-    // Walk the vault directory
+    // Walk the vault directory, then index what was found
     let mut stats = IndexStats::default();
-    index_directory(&vault.vault_path.clone(), vault, &mut stats)?;
+    let mut notes = Vec::new();
+    collect_notes(&vault.vault_path, vault, &mut stats, &mut notes)?;
+
+    let mut progress = ProgressReporter::start(IndexOperation::Vault, vault.vault_path.display().to_string(), notes.len());
+    for path in &notes {
+        match vault.index_note(path) {
+            Ok(()) => stats.notes_indexed += 1,
+            Err(e) => {
+                stats.errors.push(format!("{}: {}", path.display(), e));
+            }
+        }
+        progress.advance(1);
+    }
+    progress.finish();
+
+    stats.removed = previous.into_iter()
+        .filter(|path| !vault.notes.contains_key(path))
+        .collect();
+    stats.removed.sort();
 
     // Build backlinks
     vault.build_backlinks();
     vault.last_indexed = Utc::now();
-
     stats.last_indexed = vault.last_indexed;
 
     Ok(stats)
 }
 
-/// Recursively index a directory
-fn index_directory(dir: &Path, index: &mut VaultIndex, stats: &mut IndexStats) -> Result<(), ObsidianError> {
+/// Recursively collect the notes to index under a directory
+fn collect_notes(dir: &Path, index: &VaultIndex, stats: &mut IndexStats, notes: &mut Vec<PathBuf>) -> Result<(), ObsidianError> {
     for entry in fs::read_dir(dir)? {
         let entry = entry?;
         let path = entry.path();
@@ -504,21 +752,26 @@ fn index_directory(dir: &Path, index: &mut VaultIndex, stats: &mut IndexStats) -
             continue;
         }
 
+        if index.is_excluded(&path) {
+            stats.skipped += 1;
+            continue;
+        }
+
         if path.is_dir() {
-            index_directory(&path, index, stats)?;
-        } else if path.extension().map(|e| e == "md").unwrap_or(false) {
-            match index.index_note(&path) {
-                Ok(()) => stats.notes_indexed += 1,
-                Err(e) => {
-                    stats.errors.push(format!("{}: {}", path.display(), e));
-                }
-            }
+            collect_notes(&path, index, stats, notes)?;
+        } else if is_indexable(&path) {
+            notes.push(path);
         }
     }
 
     Ok(())
 }
 
+/// Run `f` against the current vault index without cloning it
+pub fn with_vault_index<R>(f: impl FnOnce(&VaultIndex) -> R) -> Option<R> {
+    VAULT_INDEX.read().as_ref().map(f)
+}
+
 /// Get the current vault index
 pub fn get_vault_index() -> Result<VaultIndex, ObsidianError> {
     let index = VAULT_INDEX.read();
@@ -532,6 +785,56 @@ pub struct IndexStats {
     pub notes_indexed: u32,
     pub errors: Vec<String>,
     pub last_indexed: DateTime<Utc>,
+    /// Notes present in the previous index but no longer on disk
+    #[serde(default)]
+    pub removed: Vec<String>,
+    /// Files and folders skipped by exclusion rules
+    #[serde(default)]
+    pub skipped: u32,
+}
+
+/// Notes touched by an incremental update (paths relative to the vault root)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteChanges {
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Incrementally apply file-system changes to the in-memory index.
+///
+/// Paths that still exist are (re-)indexed; missing paths are treated as
+/// deletions. A rename arrives as the old path (missing) plus the new one.
+/// Paths that are not notes or canvases are ignored; excluded paths are dropped from the index.
+pub fn update_notes(paths: &[PathBuf]) -> Result<NoteChanges, ObsidianError> {
+    let mut index = VAULT_INDEX.write();
+    let vault = index.as_mut().ok_or(ObsidianError::NotConfigured)?;
+
+    let mut changes = NoteChanges::default();
+    for path in paths.iter().filter(|p| is_indexable(p)) {
+        let relative = vault.relative_path(path);
+        let was_indexed = vault.remove_note(&relative);
+
+        if path.exists() && !vault.is_excluded(path) {
+            match vault.index_note(path) {
+                Ok(()) => changes.updated.push(relative),
+                Err(e) => warn!(path = %path.display(), error = %e, "Failed to re-index note"),
+            }
+        } else if was_indexed {
+            changes.removed.push(relative);
+        }
+    }
+
+    changes.updated.sort();
+    changes.updated.dedup();
+    changes.removed.sort();
+    changes.removed.dedup();
+
+    vault.rebuild_backlinks();
+    vault.last_indexed = Utc::now();
+    invalidate_query_cache();
+
+    Ok(changes)
 }
 
 // ============ TAURI COMMANDS ============
@@ -541,19 +844,50 @@ pub fn obsidian_configure_vault(vault_path: String) -> Result<(), ObsidianError>
     configure_vault(&vault_path)
 }
 
-#[tauri::command]
-pub async fn obsidian_index_vault() -> Result<IndexStats, ObsidianError> {
+/// Re-index the vault, then bring Chroma up to date (best-effort)
+async fn reindex_vault(job: Option<&JobHandle>) -> Result<IndexStats, ObsidianError> {
+    if let Some(job) = job {
+        job.progress(0.0, "Scanning vault");
+    }
     let stats = index_vault()?;
 
     // Best-effort Chroma indexing (don't fail if Chroma is offline)
+    if let Some(job) = job {
+        job.progress(0.5, format!("Indexing {} notes to Chroma", stats.notes_indexed));
+    }
     let chroma_indexed = index_vault_to_chroma().await;
     if chroma_indexed > 0 {
         info!(count = chroma_indexed, "Indexed notes to Chroma");
     }
+    if !stats.removed.is_empty() {
+        if let Some(job) = job {
+            job.progress(0.9, "Removing deleted notes from Chroma");
+        }
+        sync_notes_to_chroma(&[], &stats.removed).await;
+        info!(count = stats.removed.len(), "Removed deleted notes from Chroma");
+    }
 
     Ok(stats)
 }
 
+#[tauri::command]
+pub async fn obsidian_index_vault() -> Result<IndexStats, ObsidianError> {
+    reindex_vault(None).await
+}
+
+/// Re-index the vault as a background job; returns the job id
+#[tauri::command]
+pub fn obsidian_index_vault_job() -> Result<String, ObsidianError> {
+    let vault_path = {
+        let index = VAULT_INDEX.read();
+        index.as_ref().ok_or(ObsidianError::NotConfigured)?.vault_path.display().to_string()
+    };
+    Ok(jobs::submit_unique(JobKind::VaultIndex, vault_path, |job| async move {
+        let stats = reindex_vault(Some(&job)).await.map_err(|e| e.to_string())?;
+        Ok(serde_json::to_value(stats).ok())
+    }))
+}
+
 #[tauri::command]
 pub fn obsidian_get_stats() -> Result<IndexStats, ObsidianError> {
     let index = VAULT_INDEX.read();
@@ -563,6 +897,8 @@ pub fn obsidian_get_stats() -> Result<IndexStats, ObsidianError> {
         notes_indexed: vault.notes.len() as u32,
         errors: Vec::new(),
         last_indexed: vault.last_indexed,
+        removed: Vec::new(),
+        skipped: 0,
     })
 }
 
@@ -597,4 +933,32 @@ mod tests {
         assert!(tags.contains(&"#tag-2".to_string()));
         assert!(tags.contains(&"#tag_3".to_string()));
     }
+
+    #[test]
+    fn test_remove_note_cleans_mappings() {
+        let dir = std::env::temp_dir().join(format!("dialectic_idx_{}", ulid::Ulid::new()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.md"), "Links to [[b]] #shared").unwrap();
+        fs::write(dir.join("b.md"), "Target note #shared #only-b").unwrap();
+
+        let mut index = VaultIndex::new(dir.clone());
+        index.index_note(&dir.join("a.md")).unwrap();
+        index.index_note(&dir.join("b.md")).unwrap();
+        index.build_backlinks();
+        assert_eq!(index.notes["b.md"].backlinks, vec!["a.md".to_string()]);
+
+        assert!(index.remove_note("a.md"));
+        index.rebuild_backlinks();
+
+        assert!(!index.notes.contains_key("a.md"));
+        assert!(!index.title_to_path.contains_key("a"));
+        assert_eq!(index.tag_to_paths["#shared"], vec!["b.md".to_string()]);
+        assert!(index.notes["b.md"].backlinks.is_empty());
+
+        assert!(index.remove_note("b.md"));
+        assert!(!index.tag_to_paths.contains_key("#only-b"));
+        assert!(!index.remove_note("b.md"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -2,6 +2,9 @@
 //!
 //! Read-only integration with user's Obsidian vault for semantic note retrieval.
 
+pub mod cache;
+pub mod canvas;
+pub mod exclusions;
 pub mod indexer;
 pub mod query;
 pub mod watcher;
@@ -2,15 +2,94 @@
 //!
 //! Handles @ mention resolution and semantic search over the vault index.
 
+use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use super::indexer::{get_vault_index, NoteIndex, ObsidianError};
-use std::fs;
+use super::cache::{cache_query, get_cached_query, QueryCacheKey, QueryKind};
+use crate::context::tokens::{count_tokens, estimate_tokens_quick};
+use crate::chroma::store::VectorStore;
+use crate::documents::snippets::{extract_snippet, Snippet};
+use super::indexer::{get_vault_index, read_note_text, NoteIndex, ObsidianError, VaultIndex};
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
 use tracing::{debug, warn};
 
 /// L2 distance threshold for semantic search.
 /// Relevance = 1/(1+distance); threshold 0.25 ≈ distance 3.0.
 const SEMANTIC_RELEVANCE_THRESHOLD: f32 = 0.25;
 
+/// Notes already retrieved per session, used for link-proximity boosting
+static SESSION_RETRIEVALS: LazyLock<RwLock<HashMap<String, HashSet<String>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Graph-structure relevance boost applied on top of text matching.
+///
+/// Only notes that already match the query are boosted; the boost never
+/// pulls in unrelated notes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GraphBoost {
+    /// Maximum boost for structurally central notes (scaled by backlink count)
+    pub backlink_weight: f32,
+    /// Backlink count at which the backlink boost saturates
+    pub backlink_saturation: usize,
+    /// Boost for notes linked directly to a previously retrieved note
+    /// (half this for two hops away)
+    pub proximity_weight: f32,
+}
+
+impl Default for GraphBoost {
+    fn default() -> Self {
+        Self {
+            backlink_weight: 0.15,
+            backlink_saturation: 20,
+            proximity_weight: 0.2,
+        }
+    }
+}
+
+impl GraphBoost {
+    /// Boost for `note` given the notes retrieved earlier in the session
+    pub fn score(&self, index: &VaultIndex, note: &NoteIndex, retrieved: &HashSet<String>) -> f32 {
+        let saturation = self.backlink_saturation.max(1) as f32;
+        let centrality = ((1.0 + note.backlinks.len() as f32).ln() / (1.0 + saturation).ln()).min(1.0);
+        let mut boost = self.backlink_weight * centrality;
+
+        if !retrieved.is_empty() && !retrieved.contains(&note.path) {
+            let neighbours = note_neighbours(index, note);
+            if neighbours.iter().any(|p| retrieved.contains(p)) {
+                boost += self.proximity_weight;
+            } else if neighbours.iter()
+                .filter_map(|p| index.notes.get(p))
+                .any(|n| note_neighbours(index, n).iter().any(|p| retrieved.contains(p)))
+            {
+                boost += self.proximity_weight / 2.0;
+            }
+        }
+
+        boost
+    }
+}
+
+/// Paths one link away from a note (outgoing links and backlinks)
+fn note_neighbours(index: &VaultIndex, note: &NoteIndex) -> Vec<String> {
+    note.links.iter()
+        .filter_map(|link| resolve_link_to_path(index, link))
+        .chain(note.backlinks.iter().cloned())
+        .collect()
+}
+
+/// Remember which notes a session has retrieved
+pub fn record_session_retrievals(session_id: &str, results: &[QueryResult]) {
+    let mut retrievals = SESSION_RETRIEVALS.write();
+    let seen = retrievals.entry(session_id.to_string()).or_default();
+    seen.extend(results.iter().map(|r| r.note.path.clone()));
+}
+
+/// Notes a session has retrieved so far
+pub fn session_retrievals(session_id: &str) -> HashSet<String> {
+    SESSION_RETRIEVALS.read().get(session_id).cloned().unwrap_or_default()
+}
+
 /// Query result with relevance score
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -18,6 +97,9 @@ pub struct QueryResult {
     pub note: NoteIndex,
     pub relevance: f32,
     pub match_type: MatchType,
+    /// Best-matching excerpt (matched chunk for semantic hits, summary otherwise)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<Snippet>,
 }
 
 /// How the note matched the query
@@ -114,11 +196,17 @@ pub fn resolve_mention(mention: &str) -> Result<Vec<NoteIndex>, ObsidianError> {
     Ok(matches)
 }
 
-/// Semantic search over Obsidian notes via Chroma
+/// Semantic search over Obsidian notes via Chroma (cached; empty results
+/// are not cached since they usually mean Chroma is unavailable)
 pub async fn query_notes_semantic(
     query: &str,
     n_results: u32,
 ) -> Vec<QueryResult> {
+    let cache_key = QueryCacheKey::new(QueryKind::Semantic, query, n_results);
+    if let Some(cached) = get_cached_query(&cache_key) {
+        return cached;
+    }
+
     let client = crate::chroma::client::get_client();
     let collection = match client.get_collection(
         crate::chroma::collections::COLLECTION_OBSIDIAN,
@@ -197,27 +285,76 @@ pub async fn query_notes_semantic(
             }
 
             if let Some(note) = index.notes.get(path) {
+                let chunk = result.documents.as_ref()
+                    .and_then(|d| d.get(query_idx))
+                    .and_then(|d| d.get(result_idx))
+                    .and_then(|d| d.as_deref())
+                    .unwrap_or(&note.summary);
+
                 results.push(QueryResult {
                     note: note.clone(),
                     relevance,
                     match_type: MatchType::Content,
+                    snippet: extract_snippet(chunk, query),
                 });
             }
         }
     }
 
     debug!(query = %query, n_results = n_results, hits = results.len(), "Obsidian semantic search");
+    if !results.is_empty() {
+        cache_query(cache_key, results.clone());
+    }
     results
 }
 
-/// Query notes with fuzzy matching and relevance scoring
+/// Query notes with fuzzy matching and relevance scoring (cached)
 pub fn query_notes(query: &str, budget: u32) -> Result<Vec<QueryResult>, ObsidianError> {
-    let index = get_vault_index()?;
+    query_notes_cached(query, budget, &GraphBoost::default(), &HashSet::new())
+}
+
+/// `query_notes_boosted`, cached on the boost and retrieved set as well
+pub fn query_notes_cached(
+    query: &str,
+    budget: u32,
+    boost: &GraphBoost,
+    retrieved: &HashSet<String>,
+) -> Result<Vec<QueryResult>, ObsidianError> {
+    let mut retrieved_paths: Vec<&String> = retrieved.iter().collect();
+    retrieved_paths.sort();
+    let boost_key = serde_json::to_string(boost).unwrap_or_default();
+    let cache_key = QueryCacheKey::new(QueryKind::Keyword, query, budget).with_context((boost_key, retrieved_paths));
+    if let Some(cached) = get_cached_query(&cache_key) {
+        return Ok(cached);
+    }
+    let results = query_notes_boosted(query, budget, boost, retrieved)?;
+    cache_query(cache_key, results.clone());
+    Ok(results)
+}
+
+/// Query notes, boosting matches by backlink centrality and by link
+/// proximity to `retrieved` (notes already pulled into the session)
+pub fn query_notes_boosted(
+    query: &str,
+    budget: u32,
+    boost: &GraphBoost,
+    retrieved: &HashSet<String>,
+) -> Result<Vec<QueryResult>, ObsidianError> {
+    Ok(score_notes(&get_vault_index()?, query, budget, boost, retrieved))
+}
+
+/// `query_notes_boosted` over a given index
+pub fn score_notes(
+    index: &VaultIndex,
+    query: &str,
+    budget: u32,
+    boost: &GraphBoost,
+    retrieved: &HashSet<String>,
+) -> Vec<QueryResult> {
     let query_lower = query.to_lowercase();
     let query_terms: Vec<&str> = query_lower.split_whitespace().collect();
 
     let mut results: Vec<QueryResult> = Vec::new();
-    let mut total_tokens = 0u32;
 
     // Score each note
     for note in index.notes.values() {
@@ -258,25 +395,33 @@ pub fn query_notes(query: &str, budget: u32) -> Result<Vec<QueryResult>, Obsidia
         }
 
         if relevance > 0.0 {
-            // Check budget
-            if total_tokens + note.token_count > budget {
-                continue;
-            }
-
-            total_tokens += note.token_count;
-
+            relevance += boost.score(index, note, retrieved);
             results.push(QueryResult {
                 note: note.clone(),
                 relevance,
                 match_type,
+                snippet: extract_snippet(&note.summary, query),
             });
         }
     }
 
-    // Sort by relevance
-    results.sort_by(|a, b| b.relevance.partial_cmp(&a.relevance).unwrap_or(std::cmp::Ordering::Equal));
+    // Sort by boosted relevance (ties by path, so equal scores fill the
+    // budget the same way every time), then fill the budget in that order
+    results.sort_by(|a, b| {
+        b.relevance.partial_cmp(&a.relevance)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.note.path.cmp(&b.note.path))
+    });
+    let mut total_tokens = 0u32;
+    results.retain(|r| {
+        if total_tokens + r.note.token_count > budget {
+            return false;
+        }
+        total_tokens += r.note.token_count;
+        true
+    });
 
-    Ok(results)
+    results
 }
 
 /// Get note content with optional truncation to budget
@@ -295,14 +440,15 @@ pub fn get_note_content(path: &str, max_tokens: u32) -> Result<NoteContent, Obsi
     if !canonical_path.starts_with(&canonical_vault) {
         return Err(ObsidianError::InvalidPath("Path escapes vault directory".to_string()));
     }
-    let content = fs::read_to_string(&canonical_path)?;
+    let content = read_note_text(&canonical_path)?;
 
     // Estimate tokens
-    let token_count = (content.len() as f64 / 4.0).ceil() as u32;
+    let token_count = estimate_tokens_quick(&content);
 
     let (final_content, truncated) = if token_count > max_tokens {
-        // Truncate to approximately max_tokens
-        let char_limit = (max_tokens as usize) * 4;
+        // Truncate to approximately max_tokens, scaling by this note's
+        // bytes-per-token ratio (CJK and code differ from prose)
+        let char_limit = (content.len() as u64 * max_tokens as u64 / token_count as u64) as usize;
         let truncated_content = if content.len() > char_limit {
             // Find a safe UTF-8 boundary for truncation
             let mut safe_limit = char_limit.min(content.len());
@@ -320,7 +466,8 @@ pub fn get_note_content(path: &str, max_tokens: u32) -> Result<NoteContent, Obsi
         (content, false)
     };
 
-    let final_token_count = (final_content.len() as f64 / 4.0).ceil() as u32;
+    // Exact count: this is what lands in the context window
+    let final_token_count = count_tokens(&final_content);
 
     Ok(NoteContent {
         path: path.to_string(),
@@ -402,9 +549,22 @@ pub fn obsidian_resolve_mention(mention: String) -> Result<Vec<NoteIndex>, Obsid
     resolve_mention(&mention)
 }
 
+/// Keyword search. With a `session_id`, notes near ones the session already
+/// retrieved rank higher and this query's results are remembered.
 #[tauri::command]
-pub fn obsidian_query_notes(query: String, budget: u32) -> Result<Vec<QueryResult>, ObsidianError> {
-    query_notes(&query, budget)
+pub fn obsidian_query_notes(
+    query: String,
+    budget: u32,
+    session_id: Option<String>,
+    boost: Option<GraphBoost>,
+) -> Result<Vec<QueryResult>, ObsidianError> {
+    let boost = boost.unwrap_or_default();
+    let retrieved = session_id.as_deref().map(session_retrievals).unwrap_or_default();
+    let results = query_notes_cached(&query, budget, &boost, &retrieved)?;
+    if let Some(sid) = session_id.as_deref() {
+        record_session_retrievals(sid, &results);
+    }
+    Ok(results)
 }
 
 /// Hybrid search: keyword + semantic via Chroma, deduped by path
@@ -413,13 +573,16 @@ pub async fn obsidian_query_notes_semantic(
     query: String,
     budget: u32,
     n_results: u32,
+    request_id: Option<String>,
 ) -> Result<Vec<QueryResult>, ObsidianError> {
     // Get keyword results
     let mut keyword_results = query_notes(&query, budget)?;
     let keyword_count = keyword_results.len();
 
     // Get semantic results from Chroma
-    let semantic_results = query_notes_semantic(&query, n_results).await;
+    let semantic_results = crate::cancellation::cancellable(request_id.as_deref(), query_notes_semantic(&query, n_results))
+        .await
+        .map_err(|_| ObsidianError::Cancelled)?;
     let semantic_count = semantic_results.len();
 
     // Merge: dedup by path, keep highest relevance
@@ -468,4 +631,93 @@ mod tests {
         let query = tag_mention.trim_start_matches('@');
         assert!(query.starts_with('#'));
     }
+
+    fn make_note(path: &str, links: &[&str], backlinks: &[&str]) -> NoteIndex {
+        NoteIndex {
+            path: path.to_string(),
+            title: path.trim_end_matches(".md").to_string(),
+            summary: String::new(),
+            links: links.iter().map(|s| s.to_string()).collect(),
+            backlinks: backlinks.iter().map(|s| s.to_string()).collect(),
+            tags: Vec::new(),
+            modified: chrono::Utc::now(),
+            token_count: 10,
+        }
+    }
+
+    #[test]
+    fn test_graph_boost() {
+        let mut index = VaultIndex::default();
+        for note in [
+            make_note("hub.md", &[], &["a.md", "b.md", "c.md"]),
+            make_note("a.md", &["hub"], &[]),
+            make_note("b.md", &["hub"], &[]),
+            make_note("c.md", &["hub"], &[]),
+            make_note("far.md", &[], &[]),
+        ] {
+            index.title_to_path.insert(note.title.clone(), note.path.clone());
+            index.notes.insert(note.path.clone(), note);
+        }
+        let boost = GraphBoost::default();
+        let none = HashSet::new();
+
+        // Central notes outrank leaves
+        let hub = boost.score(&index, &index.notes["hub.md"], &none);
+        let leaf = boost.score(&index, &index.notes["a.md"], &none);
+        assert!(hub > leaf);
+        let disabled = GraphBoost { backlink_weight: 0.0, proximity_weight: 0.0, ..Default::default() };
+        assert_eq!(disabled.score(&index, &index.notes["hub.md"], &none), 0.0);
+
+        // Proximity: one hop gets the full boost, two hops half, unrelated none
+        let retrieved: HashSet<String> = ["a.md".to_string()].into_iter().collect();
+        let one_hop = boost.score(&index, &index.notes["hub.md"], &retrieved) - hub;
+        let two_hop = boost.score(&index, &index.notes["b.md"], &retrieved) - leaf;
+        assert!((one_hop - boost.proximity_weight).abs() < 1e-6);
+        assert!((two_hop - boost.proximity_weight / 2.0).abs() < 1e-6);
+        assert_eq!(boost.score(&index, &index.notes["far.md"], &retrieved), 0.0);
+    }
+
+    #[test]
+    fn test_budget_filled_by_boosted_relevance() {
+        let mut index = VaultIndex::default();
+        for note in [
+            make_note("rates a.md", &[], &[]),
+            make_note("rates b.md", &["seen"], &[]),
+            make_note("seen.md", &[], &["rates b.md"]),
+        ] {
+            index.title_to_path.insert(note.title.clone(), note.path.clone());
+            index.notes.insert(note.path.clone(), note);
+        }
+        let boost = GraphBoost { backlink_weight: 0.0, ..Default::default() };
+        let paths = |results: Vec<QueryResult>| results.into_iter().map(|r| r.note.path).collect::<Vec<_>>();
+
+        // Equal relevance and room for one note: the tie goes by path
+        assert_eq!(paths(score_notes(&index, "rates", 10, &boost, &HashSet::new())), vec!["rates a.md"]);
+
+        // Linked to a retrieved note, b outranks a and takes the budget
+        let retrieved: HashSet<String> = ["seen.md".to_string()].into_iter().collect();
+        assert_eq!(paths(score_notes(&index, "rates", 10, &boost, &retrieved)), vec!["rates b.md"]);
+        assert_eq!(score_notes(&index, "rates", 20, &boost, &retrieved).len(), 2);
+    }
+
+    #[test]
+    fn test_query_cache_follows_configured_index() {
+        use crate::obsidian::indexer::VaultIndexOverride;
+
+        let index_of = |path: &str| {
+            let mut index = VaultIndex::default();
+            let note = make_note(path, &[], &[]);
+            index.title_to_path.insert(note.title.clone(), note.path.clone());
+            index.notes.insert(note.path.clone(), note);
+            index
+        };
+        let paths = || query_notes("rates", 100).unwrap().into_iter().map(|r| r.note.path).collect::<Vec<_>>();
+
+        let first = VaultIndexOverride::new(index_of("rates old.md"));
+        assert_eq!(paths(), vec!["rates old.md"]);
+        drop(first);
+        // A new index isn't answered from the previous one's cache
+        let _second = VaultIndexOverride::new(index_of("rates new.md"));
+        assert_eq!(paths(), vec!["rates new.md"]);
+    }
 }
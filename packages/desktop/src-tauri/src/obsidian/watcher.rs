@@ -1,6 +1,6 @@
 //! Obsidian Vault File Watcher
 //!
-//! Monitors vault for changes and triggers re-indexing.
+//! Monitors vault for note and canvas changes and triggers re-indexing.
 
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use notify_debouncer_mini::{new_debouncer, DebouncedEvent, Debouncer};
@@ -10,7 +10,7 @@ use std::path::PathBuf;
 use std::time::Duration;
 use tauri::{AppHandle, Emitter};
 
-use super::indexer::{index_vault, ObsidianError};
+use super::indexer::{is_indexable, sync_notes_to_chroma, update_notes, ObsidianError};
 
 /// Global vault watcher
 static VAULT_WATCHER: RwLock<Option<VaultWatcher>> = RwLock::new(None);
@@ -42,13 +42,23 @@ pub fn start_watching(app: AppHandle, vault_path: PathBuf) -> Result<(), Obsidia
         move |result: Result<Vec<DebouncedEvent>, notify::Error>| {
             if let Ok(events) = result {
                 let paths: Vec<String> = events.iter()
+                    .filter(|e| is_indexable(&e.path))
                     .filter_map(|e| e.path.to_str().map(|s| s.to_string()))
-                    .filter(|p| p.ends_with(".md"))
                     .collect();
 
                 if !paths.is_empty() {
-                    // Re-index vault
-                    let reindexed = index_vault().is_ok();
+                    // Apply only the touched notes: deleted or renamed-away
+                    // paths are dropped, existing ones re-indexed
+                    let changed: Vec<PathBuf> = events.iter().map(|e| e.path.clone()).collect();
+                    let changes = update_notes(&changed);
+                    let reindexed = changes.is_ok();
+
+                    // Push the same delta to Chroma
+                    if let Ok(changes) = changes {
+                        tauri::async_runtime::spawn(async move {
+                            sync_notes_to_chroma(&changes.updated, &changes.removed).await;
+                        });
+                    }
 
                     // Emit event to frontend
                     let event = VaultChangeEvent { paths, reindexed };
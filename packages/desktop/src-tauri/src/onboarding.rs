@@ -0,0 +1,342 @@
+//! First-Run Onboarding
+//!
+//! Tracks the setup a working install needs: a usable Claude CLI, the
+//! bundled skills installed, Chroma available and (optionally) an Obsidian
+//! vault. `get_onboarding_status` re-checks every step and reports what is
+//! missing; `run_setup_step` performs the steps the app can do itself and
+//! re-checks. Skipped steps and the last failure of each step are kept in
+//! `config/onboarding.json` so the guide resumes where the user left it.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use thiserror::Error;
+use tracing::info;
+
+use crate::cli_tool::detect;
+use crate::chroma::client::get_client;
+use crate::chroma::store::VectorStore;
+use crate::chroma::sidecar::{chroma_start_sidecar, is_sidecar_running, resolve_binary_path, SidecarError};
+use crate::config::preferences::{load_preferences, update_preferences, ChromaMode, Preferences, VectorBackend};
+use crate::config::ConfigError;
+use crate::obsidian::indexer::{configure_vault, ObsidianError};
+use crate::session::{get_app_data_dir_cli, SessionError};
+use crate::skills::{skills_dir_cli, sync_skills_in, SkillError, SkillFileState};
+
+#[derive(Error, Debug)]
+pub enum OnboardingError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Obsidian(#[from] ObsidianError),
+    #[error(transparent)]
+    Sidecar(#[from] SidecarError),
+    #[error(transparent)]
+    Skill(#[from] SkillError),
+    #[error("Missing input for {0:?}: {1}")]
+    MissingInput(OnboardingStep, String),
+    #[error("{0}")]
+    StepFailed(String),
+}
+
+impl Serialize for OnboardingError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Setup steps, in the order the guide walks them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    CliTool,
+    Skills,
+    Chroma,
+    Vault,
+}
+
+const STEPS: [OnboardingStep; 4] =
+    [OnboardingStep::CliTool, OnboardingStep::Skills, OnboardingStep::Chroma, OnboardingStep::Vault];
+
+impl OnboardingStep {
+    /// Optional steps can be skipped without losing core features
+    pub fn optional(&self) -> bool {
+        matches!(self, OnboardingStep::Vault)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepState {
+    Pending,
+    Done,
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepStatus {
+    pub step: OnboardingStep,
+    pub state: StepState,
+    pub optional: bool,
+    /// What was found, or what is missing and how to fix it
+    pub detail: String,
+    /// `run_setup_step` can do this step itself
+    pub automatic: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingStatus {
+    pub steps: Vec<StepStatus>,
+    /// First step still pending or failed
+    pub next_step: Option<OnboardingStep>,
+    pub complete: bool,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// What the guide remembers between launches
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OnboardingState {
+    #[serde(default)]
+    skipped: BTreeSet<OnboardingStep>,
+    /// Last failure per step, cleared when the step succeeds
+    #[serde(default)]
+    errors: BTreeMap<OnboardingStep, String>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+fn state_path() -> Result<PathBuf, OnboardingError> {
+    Ok(get_app_data_dir_cli()?.join("config/onboarding.json"))
+}
+
+fn load_state(path: &Path) -> OnboardingState {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(path: &Path, state: &OnboardingState) -> Result<(), OnboardingError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(state)?)?;
+    Ok(())
+}
+
+/// Whether a step is already satisfied, with a description either way
+async fn check_step(app: &AppHandle, step: OnboardingStep, prefs: &Preferences) -> (bool, String) {
+    match step {
+        OnboardingStep::CliTool => {
+            let tool = prefs.cli_tool.clone();
+            let diagnostics = tokio::task::spawn_blocking(move || detect(&tool)).await.unwrap_or_default();
+            match (&diagnostics.path, diagnostics.usable) {
+                (Some(path), true) => (true, format!("Found {} at {}", diagnostics.tool, path)),
+                _ => (false, diagnostics.problems.join("; ")),
+            }
+        }
+        OnboardingStep::Skills => match skills_dir_cli().map(|dir| sync_skills_in(&dir, false, false)) {
+            Ok(Ok(sync)) => {
+                let missing: Vec<&str> = sync
+                    .files
+                    .iter()
+                    .filter(|f| matches!(f.state, SkillFileState::Missing | SkillFileState::Outdated))
+                    .map(|f| f.path.as_str())
+                    .collect();
+                if missing.is_empty() {
+                    (true, "Bundled skills installed".to_string())
+                } else {
+                    (false, format!("Skills to install: {}", missing.join(", ")))
+                }
+            }
+            Ok(Err(e)) => (false, e.to_string()),
+            Err(e) => (false, e.to_string()),
+        },
+        OnboardingStep::Chroma if prefs.vector_backend == VectorBackend::Embedded => {
+            (true, "Using the embedded vector store".to_string())
+        }
+        OnboardingStep::Chroma if prefs.vector_backend == VectorBackend::Qdrant => match get_client().heartbeat().await {
+            Ok(_) => (true, "Qdrant reachable".to_string()),
+            Err(e) => (false, format!("Qdrant unreachable: {}", e)),
+        },
+        OnboardingStep::Chroma => match prefs.chroma_mode {
+            ChromaMode::Disabled => (true, "Chroma disabled; using local search".to_string()),
+            ChromaMode::External => match get_client().heartbeat().await {
+                Ok(_) => (true, "External Chroma reachable".to_string()),
+                Err(e) => (false, format!("External Chroma unreachable: {}", e)),
+            },
+            ChromaMode::Sidecar => match resolve_binary_path(Some(app)) {
+                Ok(path) if is_sidecar_running() => (true, format!("Chroma running from {}", path.display())),
+                Ok(path) => (false, format!("Chroma installed at {} but not running", path.display())),
+                Err(e) => (false, e.to_string()),
+            },
+        },
+        OnboardingStep::Vault => match prefs.vault_path.as_deref() {
+            Some(vault) if Path::new(vault).join(".obsidian").is_dir() => (true, format!("Vault: {}", vault)),
+            Some(vault) => (false, format!("Configured vault is not an Obsidian vault: {}", vault)),
+            None => (false, "No Obsidian vault configured".to_string()),
+        },
+    }
+}
+
+/// Combine live checks with the remembered skips and failures
+fn step_state(done: bool, step: OnboardingStep, state: &OnboardingState) -> StepState {
+    if done {
+        StepState::Done
+    } else if state.skipped.contains(&step) {
+        StepState::Skipped
+    } else if state.errors.contains_key(&step) {
+        StepState::Failed
+    } else {
+        StepState::Pending
+    }
+}
+
+async fn status(app: &AppHandle, path: &Path) -> Result<OnboardingStatus, OnboardingError> {
+    let prefs = load_preferences();
+    let mut state = load_state(path);
+    let mut steps = Vec::new();
+    for step in STEPS {
+        let (done, detail) = check_step(app, step, &prefs).await;
+        let step_state = step_state(done, step, &state);
+        let detail = match state.errors.get(&step) {
+            Some(error) if step_state == StepState::Failed => format!("{} (last attempt: {})", detail, error),
+            _ => detail,
+        };
+        let automatic = !matches!(step, OnboardingStep::CliTool);
+        steps.push(StepStatus { step, state: step_state, optional: step.optional(), detail, automatic });
+    }
+
+    let next_step = steps
+        .iter()
+        .find(|s| matches!(s.state, StepState::Pending | StepState::Failed))
+        .map(|s| s.step);
+    let complete = next_step.is_none();
+    if complete && state.completed_at.is_none() {
+        state.completed_at = Some(Utc::now());
+        save_state(path, &state)?;
+        info!("Onboarding complete");
+    }
+    Ok(OnboardingStatus { steps, next_step, complete, completed_at: state.completed_at })
+}
+
+async fn perform(app: &AppHandle, step: OnboardingStep, vault_path: Option<String>) -> Result<(), OnboardingError> {
+    match step {
+        OnboardingStep::CliTool => {
+            // Installing the CLI is up to the user; re-check only
+            let prefs = load_preferences();
+            match check_step(app, step, &prefs).await {
+                (true, _) => Ok(()),
+                (false, detail) => Err(OnboardingError::StepFailed(detail)),
+            }
+        }
+        OnboardingStep::Skills => {
+            sync_skills_in(&skills_dir_cli()?, true, false)?;
+            Ok(())
+        }
+        OnboardingStep::Chroma if load_preferences().vector_backend == VectorBackend::Embedded => Ok(()),
+        OnboardingStep::Chroma if load_preferences().vector_backend == VectorBackend::Qdrant => get_client()
+            .heartbeat()
+            .await
+            .map(|_| ())
+            .map_err(|e| OnboardingError::StepFailed(format!("Qdrant unreachable: {}", e))),
+        OnboardingStep::Chroma => match load_preferences().chroma_mode {
+            ChromaMode::Disabled => Ok(()),
+            ChromaMode::External => get_client()
+                .heartbeat()
+                .await
+                .map(|_| ())
+                .map_err(|e| OnboardingError::StepFailed(format!("External Chroma unreachable: {}", e))),
+            ChromaMode::Sidecar => chroma_start_sidecar(app.clone()).await.map(|_| ()).map_err(Into::into),
+        },
+        OnboardingStep::Vault => {
+            let vault_path = vault_path
+                .ok_or_else(|| OnboardingError::MissingInput(step, "vaultPath is required".to_string()))?;
+            configure_vault(&vault_path)?;
+            update_preferences(app.clone(), json!({ "vaultPath": vault_path }))?;
+            Ok(())
+        }
+    }
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub async fn get_onboarding_status(app: AppHandle) -> Result<OnboardingStatus, OnboardingError> {
+    status(&app, &state_path()?).await
+}
+
+/// Perform a setup step (the vault step needs `vault_path`) and return the
+/// updated status. A failure is recorded against the step and returned.
+#[tauri::command]
+pub async fn run_setup_step(
+    app: AppHandle,
+    step: OnboardingStep,
+    vault_path: Option<String>,
+) -> Result<OnboardingStatus, OnboardingError> {
+    let path = state_path()?;
+    let result = perform(&app, step, vault_path).await;
+    let mut state = load_state(&path);
+    match &result {
+        Ok(()) => {
+            state.errors.remove(&step);
+            state.skipped.remove(&step);
+            info!(step = ?step, "Setup step completed");
+        }
+        Err(e) => {
+            state.errors.insert(step, e.to_string());
+        }
+    }
+    save_state(&path, &state)?;
+    result?;
+    status(&app, &path).await
+}
+
+/// Skip an optional step (or un-skip it with `skip: false`)
+#[tauri::command]
+pub async fn skip_setup_step(app: AppHandle, step: OnboardingStep, skip: Option<bool>) -> Result<OnboardingStatus, OnboardingError> {
+    if !step.optional() {
+        return Err(OnboardingError::StepFailed(format!("{:?} is required and cannot be skipped", step)));
+    }
+    let path = state_path()?;
+    let mut state = load_state(&path);
+    if skip.unwrap_or(true) {
+        state.skipped.insert(step);
+    } else {
+        state.skipped.remove(&step);
+    }
+    save_state(&path, &state)?;
+    status(&app, &path).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_state() {
+        let mut state = OnboardingState::default();
+        state.skipped.insert(OnboardingStep::Vault);
+        state.errors.insert(OnboardingStep::Chroma, "binary missing".to_string());
+        assert_eq!(step_state(false, OnboardingStep::Vault, &state), StepState::Skipped);
+        assert_eq!(step_state(false, OnboardingStep::Chroma, &state), StepState::Failed);
+        assert_eq!(step_state(true, OnboardingStep::Chroma, &state), StepState::Done);
+        assert_eq!(step_state(false, OnboardingStep::CliTool, &state), StepState::Pending);
+    }
+}
@@ -0,0 +1,261 @@
+//! Publish
+//!
+//! Exports every Formed session as a static HTML site a team can browse
+//! without the app: `index.html` lists theses by category and by tag, and
+//! `theses/<id>.html` holds each session's report page (thesis, confidence
+//! history, claim graph, tensions and evidence). Pages are self-contained,
+//! so the directory can be opened locally or served by any static host.
+//! Pages for sessions that are no longer formed are removed on republish.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tracing::{debug, info};
+
+use crate::config::workspace::effective_preferences;
+use crate::report::{escape_html, load_confidence_history, render_report_html, STYLE};
+use crate::session::citations::collect_citations;
+use crate::session::entities::ENTITY_TAG_PREFIX;
+use crate::session::{list_sessions_cli, Session, SessionError, SessionStatus};
+
+pub const INDEX_FILE: &str = "index.html";
+pub const THESES_DIR: &str = "theses";
+/// Thesis text shown per entry on the index
+const EXCERPT_CHARS: usize = 220;
+
+#[derive(Error, Debug)]
+pub enum PublishError {
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Output directory must be an absolute path: {0}")]
+    InvalidPath(String),
+}
+
+impl Serialize for PublishError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishedSite {
+    pub out_dir: String,
+    pub index_path: String,
+    pub pages: usize,
+    /// Stale thesis pages deleted from an earlier publish
+    pub removed: usize,
+}
+
+/// Path of a session's page, relative to the site root
+pub fn page_path(session: &Session) -> String {
+    format!("{}/{}.html", THESES_DIR, session.id)
+}
+
+fn category_label(category: Option<&str>) -> String {
+    match category.map(str::trim).filter(|c| !c.is_empty()) {
+        Some(category) => category
+            .split(['-', '_'])
+            .map(|word| {
+                let mut chars = word.chars();
+                chars.next().map(|c| c.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        None => "Uncategorized".to_string(),
+    }
+}
+
+fn tag_anchor(tag: &str) -> String {
+    let slug: String = tag.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '-' }).collect();
+    format!("tag-{}", slug)
+}
+
+fn entry_html(session: &Session) -> String {
+    let (excerpt, confidence) = match &session.thesis {
+        Some(thesis) => {
+            let text = thesis.content.trim();
+            let mut excerpt: String = text.chars().take(EXCERPT_CHARS).collect();
+            if text.chars().count() > EXCERPT_CHARS {
+                excerpt.push('…');
+            }
+            (excerpt, format!("{:.0}%", thesis.confidence * 100.0))
+        }
+        None => (String::new(), "—".to_string()),
+    };
+    let formed = session.calibration.as_ref().map(|c| c.formed_at).unwrap_or(session.updated);
+    format!(
+        "<li><a href=\"{}\">{}</a> <span class=\"meta\">{} confidence · formed {}</span><br>{}</li>",
+        escape_html(&page_path(session)),
+        escape_html(&session.title),
+        confidence,
+        formed.format("%Y-%m-%d"),
+        escape_html(&excerpt)
+    )
+}
+
+/// The site index: theses grouped by category, then by tag
+pub fn render_index(sessions: &[Session], generated_at: DateTime<Utc>) -> String {
+    let mut sorted: Vec<&Session> = sessions.iter().collect();
+    sorted.sort_by(|a, b| a.title.to_lowercase().cmp(&b.title.to_lowercase()).then_with(|| a.id.cmp(&b.id)));
+
+    let mut by_category: BTreeMap<String, Vec<&Session>> = BTreeMap::new();
+    let mut by_tag: BTreeMap<&str, Vec<&Session>> = BTreeMap::new();
+    for session in &sorted {
+        by_category.entry(category_label(session.category.as_deref())).or_default().push(session);
+        for tag in session.tags.iter().filter(|t| !t.starts_with(ENTITY_TAG_PREFIX)) {
+            by_tag.entry(tag.as_str()).or_default().push(session);
+        }
+    }
+
+    let mut html = String::with_capacity(8 * 1024);
+    let _ = writeln!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>Thesis Library</title>\n<style>{}</style>\n</head>\n<body>",
+        STYLE
+    );
+    let _ = writeln!(html, "<header>\n<h1>Thesis Library</h1>\n<p class=\"meta\">{} formed theses</p>\n</header>", sorted.len());
+    if sorted.is_empty() {
+        html.push_str("<p class=\"empty\">No formed theses yet.</p>\n");
+    }
+
+    for (category, entries) in &by_category {
+        let _ = writeln!(html, "<h2>{}</h2>\n<ul>", escape_html(category));
+        for session in entries {
+            let _ = writeln!(html, "{}", entry_html(session));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    if !by_tag.is_empty() {
+        html.push_str("<h2>By Tag</h2>\n<p>");
+        let links: Vec<String> = by_tag
+            .iter()
+            .map(|(tag, entries)| format!("<a href=\"#{}\">{}</a> ({})", tag_anchor(tag), escape_html(tag), entries.len()))
+            .collect();
+        html.push_str(&links.join(" · "));
+        html.push_str("</p>\n");
+        for (tag, entries) in &by_tag {
+            let _ = writeln!(html, "<h3 id=\"{}\">{}</h3>\n<ul>", tag_anchor(tag), escape_html(tag));
+            for session in entries {
+                let _ = writeln!(html, "<li><a href=\"{}\">{}</a></li>", escape_html(&page_path(session)), escape_html(&session.title));
+            }
+            html.push_str("</ul>\n");
+        }
+    }
+
+    let _ = writeln!(html, "<footer>Published {} from Dialectic.</footer>\n</body>\n</html>", generated_at.format("%Y-%m-%d %H:%M UTC"));
+    html
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), std::io::Error> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)
+}
+
+/// Publish every formed session in `sessions` into `out_dir`
+pub async fn publish_sessions(sessions: &[Session], out_dir: &Path) -> Result<PublishedSite, PublishError> {
+    let formed: Vec<Session> = sessions.iter().filter(|s| s.status == SessionStatus::Formed).cloned().collect();
+    let theses_dir = out_dir.join(THESES_DIR);
+    fs::create_dir_all(&theses_dir)?;
+    let now = Utc::now();
+
+    let mut written: Vec<PathBuf> = Vec::with_capacity(formed.len());
+    for session in &formed {
+        let prefs = effective_preferences(Path::new(&session.working_dir)).0;
+        let citations = collect_citations(session, prefs.vault_path.as_deref().map(Path::new)).await;
+        let page = render_report_html(session, &load_confidence_history(session), &citations, now, Some("../index.html"));
+        let path = out_dir.join(page_path(session));
+        write_file(&path, &page)?;
+        written.push(path);
+    }
+
+    // Sessions reopened or deleted since the last publish
+    let mut removed = 0;
+    for entry in fs::read_dir(&theses_dir)?.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.extension().is_some_and(|e| e == "html") && !written.contains(&path) {
+            fs::remove_file(&path)?;
+            debug!(path = %path.display(), "Removed stale thesis page");
+            removed += 1;
+        }
+    }
+
+    let index_path = out_dir.join(INDEX_FILE);
+    write_file(&index_path, &render_index(&formed, now))?;
+    info!(out_dir = %out_dir.display(), pages = written.len(), removed, "Published thesis site");
+    Ok(PublishedSite {
+        out_dir: out_dir.to_string_lossy().to_string(),
+        index_path: index_path.to_string_lossy().to_string(),
+        pages: written.len(),
+        removed,
+    })
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub async fn publish_site(out_dir: String) -> Result<PublishedSite, PublishError> {
+    let path = PathBuf::from(&out_dir);
+    if !path.is_absolute() {
+        return Err(PublishError::InvalidPath(out_dir));
+    }
+    publish_sessions(&list_sessions_cli()?, &path).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use serde_json::json;
+
+    fn session(id: &str, title: &str, status: &str, category: Option<&str>, tags: &[&str]) -> Session {
+        test_session(json!({
+            "id": id, "title": title, "status": status, "category": category, "tags": tags,
+            "updated": "2026-01-03T00:00:00Z",
+            "thesis": { "content": "Rates & margins", "confidence": 0.8, "updatedAt": "2026-01-03T00:00:00Z" },
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_publish_sessions() {
+        let dir = std::env::temp_dir().join(format!("dialectic_publish_{}", ulid::Ulid::new()));
+        fs::create_dir_all(dir.join(THESES_DIR)).unwrap();
+        fs::write(dir.join(THESES_DIR).join("reopened.html"), "old").unwrap();
+        let sessions = vec![
+            session("b", "Bank margins", "formed", Some("market-structure"), &["rates", "entity/fed"]),
+            session("a", "AI capex cycle", "formed", None, &["rates"]),
+            session("x", "Still exploring", "exploring", None, &[]),
+        ];
+
+        let site = publish_sessions(&sessions, &dir).await.unwrap();
+        assert_eq!((site.pages, site.removed), (2, 1));
+        assert!(dir.join("theses/a.html").is_file());
+        assert!(!dir.join("theses/x.html").exists());
+        assert!(!dir.join("theses/reopened.html").exists());
+
+        let page = fs::read_to_string(dir.join("theses/b.html")).unwrap();
+        assert!(page.contains("<a href=\"../index.html\">← All theses</a>"));
+        assert!(page.contains("Claim Graph"));
+
+        let index = fs::read_to_string(dir.join(INDEX_FILE)).unwrap();
+        assert!(index.contains("<h2>Market Structure</h2>"));
+        assert!(index.contains("<a href=\"theses/b.html\">Bank margins</a> <span class=\"meta\">80% confidence"));
+        assert!(index.contains("Rates &amp; margins"));
+        assert!(index.contains("<a href=\"#tag-rates\">rates</a> (2)"));
+        assert!(!index.contains("entity/fed"));
+        assert!(!index.contains("Still exploring"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,215 @@
+//! Quick Search
+//!
+//! Search-as-you-type over in-memory indexes only: vault note titles and
+//! tags, session titles and reference document filenames. No Chroma and no
+//! disk reads after the session title cache is warm, so results come back
+//! in a few milliseconds for autocomplete.
+//!
+//! Session titles are cached here and kept current by the session load,
+//! save, list and delete paths.
+
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use tracing::{debug, warn};
+
+use crate::session::Session;
+
+/// Default number of hits returned
+const DEFAULT_LIMIT: usize = 20;
+
+/// Session id -> title entry; `None` until first warmed
+static SESSION_TITLES: LazyLock<RwLock<Option<HashMap<String, SessionTitle>>>> =
+    LazyLock::new(|| RwLock::new(None));
+
+#[derive(Debug, Clone)]
+struct SessionTitle {
+    title: String,
+    status: String,
+    updated: DateTime<Utc>,
+}
+
+impl SessionTitle {
+    fn from_session(session: &Session) -> Self {
+        Self {
+            title: session.title.clone(),
+            status: format!("{:?}", session.status).to_lowercase(),
+            updated: session.updated,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuickHitKind {
+    Note,
+    Tag,
+    Session,
+    Document,
+}
+
+/// One autocomplete suggestion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickHit {
+    pub kind: QuickHitKind,
+    /// Text shown to the user
+    pub title: String,
+    /// Note path, tag, session id or document id
+    pub id: String,
+    /// Secondary text (session status, owning session of a document)
+    pub detail: Option<String>,
+    /// 0.0–1.0, higher is better
+    pub score: f32,
+}
+
+/// Record a session's current title
+pub fn note_session(session: &Session) {
+    if let Some(titles) = SESSION_TITLES.write().as_mut() {
+        titles.insert(session.id.clone(), SessionTitle::from_session(session));
+    }
+}
+
+/// Replace the cache with a full session listing
+pub fn replace_sessions(sessions: &[Session]) {
+    let titles = sessions
+        .iter()
+        .map(|s| (s.id.clone(), SessionTitle::from_session(s)))
+        .collect();
+    *SESSION_TITLES.write() = Some(titles);
+}
+
+/// Drop a deleted session
+pub fn forget_session(session_id: &str) {
+    if let Some(titles) = SESSION_TITLES.write().as_mut() {
+        titles.remove(session_id);
+    }
+}
+
+/// Load session titles from disk if not yet cached
+pub fn warm_session_titles() {
+    if SESSION_TITLES.read().is_some() {
+        return;
+    }
+    // Listing sessions fills the cache via `replace_sessions`
+    match crate::session::list_sessions_cli() {
+        Ok(sessions) => debug!(count = sessions.len(), "Warmed session title cache"),
+        Err(e) => warn!(error = %e, "Failed to warm session title cache"),
+    }
+}
+
+/// Score how well `candidate` matches an already-lowercased `query`:
+/// exact > prefix > word prefix > substring > in-order fuzzy
+pub fn match_score(candidate: &str, query: &str) -> Option<f32> {
+    if query.is_empty() {
+        return None;
+    }
+    let candidate = candidate.to_lowercase();
+    if candidate == query {
+        return Some(1.0);
+    }
+    if candidate.starts_with(query) {
+        return Some(0.9);
+    }
+    let word_prefix = candidate
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|word| word.starts_with(query));
+    if word_prefix {
+        return Some(0.8);
+    }
+    if candidate.contains(query) {
+        return Some(0.6);
+    }
+
+    // Fuzzy: every query char appears in order; tighter spans score higher
+    let chars: Vec<char> = candidate.chars().collect();
+    let mut pos = 0;
+    let mut first = None;
+    for q in query.chars() {
+        let offset = chars[pos..].iter().position(|c| *c == q)?;
+        first.get_or_insert(pos + offset);
+        pos += offset + 1;
+    }
+    let span = (pos - first.unwrap_or(0)) as f32;
+    Some(0.5 * query.chars().count() as f32 / span)
+}
+
+fn push_hit(hits: &mut Vec<QuickHit>, kind: QuickHitKind, title: &str, id: &str, detail: Option<String>, query: &str) {
+    if let Some(score) = match_score(title, query) {
+        hits.push(QuickHit {
+            kind,
+            title: title.to_string(),
+            id: id.to_string(),
+            detail,
+            score,
+        });
+    }
+}
+
+/// Match `query` against every in-memory index, best first
+pub fn search(query: &str, limit: usize) -> Vec<QuickHit> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut hits = Vec::new();
+
+    crate::obsidian::indexer::with_vault_index(|vault| {
+        for note in vault.notes.values() {
+            push_hit(&mut hits, QuickHitKind::Note, &note.title, &note.path, None, &query);
+        }
+        for (tag, paths) in &vault.tag_to_paths {
+            let detail = Some(format!("{} notes", paths.len()));
+            push_hit(&mut hits, QuickHitKind::Tag, tag, tag, detail, &query);
+        }
+    });
+
+    let mut recency: HashMap<String, DateTime<Utc>> = HashMap::new();
+    if let Some(titles) = SESSION_TITLES.read().as_ref() {
+        for (id, entry) in titles {
+            push_hit(&mut hits, QuickHitKind::Session, &entry.title, id, Some(entry.status.clone()), &query);
+            recency.insert(id.clone(), entry.updated);
+        }
+    }
+
+    for doc in crate::documents::retriever::document_filenames() {
+        push_hit(&mut hits, QuickHitKind::Document, &doc.filename, &doc.doc_id, Some(doc.session_id), &query);
+    }
+
+    // Best score first; ties go to recently updated sessions, then shorter titles
+    hits.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| recency.get(&b.id).cmp(&recency.get(&a.id)))
+            .then_with(|| a.title.len().cmp(&b.title.len()))
+    });
+    hits.truncate(limit);
+    hits
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn quick_search(query: String, limit: Option<usize>) -> Vec<QuickHit> {
+    search(&query, limit.unwrap_or(DEFAULT_LIMIT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_score_ordering() {
+        assert_eq!(match_score("Pricing", "pricing"), Some(1.0));
+        assert_eq!(match_score("Pricing strategy", "pric"), Some(0.9));
+        assert_eq!(match_score("SaaS pricing", "pric"), Some(0.8));
+        assert_eq!(match_score("repricing", "pric"), Some(0.6));
+        let fuzzy = match_score("product market", "pdmk").unwrap();
+        assert!(fuzzy > 0.0 && fuzzy < 0.6);
+        assert!(match_score("pricing", "xyz").is_none());
+        assert!(match_score("pricing", "").is_none());
+    }
+}
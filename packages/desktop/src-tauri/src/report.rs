@@ -0,0 +1,634 @@
+//! Session Reports
+//!
+//! Renders a session as a self-contained HTML report for readers who don't
+//! use the app: thesis, confidence history, the claim graph as inline SVG,
+//! open tensions and an evidence table with its bibliography. The PDF
+//! format prints that HTML with a headless Chromium-family browser.
+//! Reports are written to `<session dir>/reports/`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::cdg::evidence::score_evidence;
+use crate::cdg::{ClaimStratum, EdgeType, ResolutionStatus};
+use crate::cli_tool::locate;
+use crate::config::workspace::effective_preferences;
+use crate::deep_link::session_link;
+use crate::session::audit::{read_audit_log, AuditAction, AuditEntry};
+use crate::session::citations::{collect_citations, CitationEntry};
+use crate::session::{get_session_dir_cli, load_session_cli, Session, SessionError};
+
+pub const REPORTS_DIR: &str = "reports";
+
+/// Browsers tried, in order, for PDF printing
+const PDF_RENDERERS: [&str; 8] = [
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "microsoft-edge",
+    "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+    "/Applications/Chromium.app/Contents/MacOS/Chromium",
+    "/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+];
+/// How long the headless browser may take to print
+const PDF_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Claim graph layout, in SVG user units
+const GRAPH_COLUMN_WIDTH: f32 = 180.0;
+const GRAPH_ROW_HEIGHT: f32 = 44.0;
+const GRAPH_TOP: f32 = 64.0;
+const NODE_RADIUS: f32 = 14.0;
+
+/// Confidence chart size, in SVG user units
+const CHART_WIDTH: f32 = 560.0;
+const CHART_HEIGHT: f32 = 160.0;
+const CHART_PADDING: f32 = 32.0;
+
+/// Stylesheet shared by reports and published pages
+pub const STYLE: &str = r#"
+:root { --ink: #1f2328; --muted: #59636e; --rule: #d1d9e0; --accent: #0969da; }
+* { box-sizing: border-box; }
+body { font: 15px/1.55 -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif; color: var(--ink); max-width: 880px; margin: 40px auto; padding: 0 24px; }
+h1 { font-size: 28px; margin: 0 0 4px; }
+h2 { font-size: 19px; border-bottom: 1px solid var(--rule); padding-bottom: 4px; margin-top: 36px; }
+a { color: var(--accent); }
+.meta { color: var(--muted); font-size: 13px; }
+.thesis { font-size: 17px; border-left: 4px solid var(--accent); padding: 8px 16px; background: #f6f8fa; }
+.confidence { font-weight: 600; }
+.empty { color: var(--muted); font-style: italic; }
+table { border-collapse: collapse; width: 100%; font-size: 13px; }
+th, td { border-bottom: 1px solid var(--rule); padding: 6px 8px; text-align: left; vertical-align: top; }
+th { background: #f6f8fa; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+tr.weak td { background: #fff8c5; }
+svg { max-width: 100%; height: auto; }
+svg text { font: 11px -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif; fill: var(--ink); }
+.legend span { display: inline-block; margin-right: 14px; font-size: 12px; color: var(--muted); }
+.legend i { display: inline-block; width: 18px; height: 3px; vertical-align: middle; margin-right: 4px; }
+nav { font-size: 13px; margin-bottom: 16px; }
+footer { margin-top: 48px; color: var(--muted); font-size: 12px; }
+@page { size: A4; margin: 18mm; }
+@media print { body { margin: 0; max-width: none; } h2 { break-after: avoid; } tr, svg { break-inside: avoid; } }
+"#;
+
+#[derive(Error, Debug)]
+pub enum ReportError {
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("No Chromium-based browser found to print the PDF; open the HTML report and print it instead")]
+    PdfRendererUnavailable,
+    #[error("PDF rendering failed: {0}")]
+    PdfFailed(String),
+}
+
+impl Serialize for ReportError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Html,
+    Pdf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedReport {
+    pub session_id: String,
+    pub format: ReportFormat,
+    pub path: String,
+}
+
+/// Thesis confidence at a point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfidencePoint {
+    pub at: DateTime<Utc>,
+    pub confidence: f32,
+    pub label: String,
+}
+
+/// Escape text for HTML content and attribute values
+pub fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// HTML for the inline Markdown used in citation references:
+/// `[text](url)`, `<url>`, `*emphasis*` and `` `code` ``
+pub fn inline_markdown(text: &str) -> String {
+    let mut html = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let link = (c == '[').then(|| rest.split_once("](")).flatten().and_then(|(label, tail)| {
+            let (url, after) = tail.split_once(')')?;
+            (!label[1..].contains(']')).then_some((&label[1..], url, after))
+        });
+        if let Some((label, url, after)) = link {
+            let _ = write!(html, "<a href=\"{}\">{}</a>", escape_html(url), escape_html(label));
+            rest = after;
+            continue;
+        }
+        let delimited = match c {
+            '<' | '*' | '`' => {
+                let close = if c == '<' { '>' } else { c };
+                rest[1..].split_once(close).filter(|(inner, _)| !inner.is_empty())
+            }
+            _ => None,
+        };
+        if let Some((inner, after)) = delimited {
+            let inner = escape_html(inner);
+            let _ = match c {
+                '<' => write!(html, "<a href=\"{0}\">{0}</a>", inner),
+                '*' => write!(html, "<em>{}</em>", inner),
+                _ => write!(html, "<code>{}</code>", inner),
+            };
+            rest = after;
+            continue;
+        }
+        html.push_str(&escape_html(&c.to_string()));
+        rest = &rest[c.len_utf8()..];
+    }
+    html
+}
+
+fn lower_debug<T: std::fmt::Debug>(value: &T) -> String {
+    format!("{:?}", value).to_lowercase()
+}
+
+/// Confidence over time from the audit log's thesis updates, the
+/// confidence recorded when the thesis was formed, and the current thesis
+pub fn confidence_history(session: &Session, audit: &[AuditEntry]) -> Vec<ConfidencePoint> {
+    let mut points: Vec<ConfidencePoint> = audit
+        .iter()
+        .filter(|e| e.action == AuditAction::ThesisUpdated)
+        .filter_map(|e| {
+            let confidence = e.detail.get("confidence")?.as_f64()? as f32;
+            Some(ConfidencePoint { at: e.timestamp, confidence, label: "Thesis updated".to_string() })
+        })
+        .collect();
+    if let Some(calibration) = &session.calibration {
+        points.push(ConfidencePoint { at: calibration.formed_at, confidence: calibration.confidence, label: "Formed".to_string() });
+    }
+    if let Some(thesis) = &session.thesis {
+        points.push(ConfidencePoint { at: thesis.updated_at, confidence: thesis.confidence, label: "Current".to_string() });
+    }
+    points.sort_by_key(|p| p.at);
+    // The same change can be recorded more than once
+    points.dedup_by(|b, a| (b.confidence - a.confidence).abs() < 0.005 && (b.at - a.at).num_seconds().abs() <= 60);
+    points
+}
+
+/// Line chart of confidence over time, or a note when there's nothing to plot
+pub fn render_confidence_svg(points: &[ConfidencePoint]) -> String {
+    if points.is_empty() {
+        return "<p class=\"empty\">No thesis confidence recorded yet.</p>".to_string();
+    }
+    let (first, last) = (points[0].at, points[points.len() - 1].at);
+    let span = (last - first).num_seconds().max(1) as f32;
+    let plot_width = CHART_WIDTH - 2.0 * CHART_PADDING;
+    let plot_height = CHART_HEIGHT - 2.0 * CHART_PADDING;
+    let x = |at: DateTime<Utc>| match points.len() {
+        1 => CHART_WIDTH / 2.0,
+        _ => CHART_PADDING + plot_width * (at - first).num_seconds() as f32 / span,
+    };
+    let y = |confidence: f32| CHART_PADDING + plot_height * (1.0 - confidence.clamp(0.0, 1.0));
+
+    let mut svg = format!(
+        "<svg viewBox=\"0 0 {0} {1}\" width=\"{0}\" height=\"{1}\" role=\"img\" aria-label=\"Thesis confidence over time\">\n",
+        CHART_WIDTH, CHART_HEIGHT
+    );
+    for level in [0.0, 0.5, 1.0] {
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{0}\" y1=\"{1:.1}\" x2=\"{2}\" y2=\"{1:.1}\" stroke=\"#d1d9e0\"/><text x=\"4\" y=\"{3:.1}\">{4:.0}%</text>",
+            CHART_PADDING,
+            y(level),
+            CHART_WIDTH - CHART_PADDING,
+            y(level) + 4.0,
+            level * 100.0
+        );
+    }
+    let line: Vec<String> = points.iter().map(|p| format!("{:.1},{:.1}", x(p.at), y(p.confidence))).collect();
+    let _ = writeln!(svg, "<polyline points=\"{}\" fill=\"none\" stroke=\"#0969da\" stroke-width=\"2\"/>", line.join(" "));
+    for point in points {
+        let _ = writeln!(
+            svg,
+            "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"4\" fill=\"#0969da\"><title>{} — {:.0}% ({})</title></circle>",
+            x(point.at),
+            y(point.confidence),
+            point.at.format("%Y-%m-%d"),
+            point.confidence * 100.0,
+            escape_html(&point.label)
+        );
+    }
+    let _ = writeln!(
+        svg,
+        "<text x=\"{}\" y=\"{}\">{}</text><text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>",
+        CHART_PADDING,
+        CHART_HEIGHT - 8.0,
+        first.format("%Y-%m-%d"),
+        CHART_WIDTH - CHART_PADDING,
+        CHART_HEIGHT - 8.0,
+        last.format("%Y-%m-%d")
+    );
+    svg.push_str("</svg>");
+    svg
+}
+
+fn edge_color(edge_type: &EdgeType) -> &'static str {
+    match edge_type {
+        EdgeType::Support => "#1a7f37",
+        EdgeType::Require => "#0969da",
+        EdgeType::Tension => "#cf222e",
+        EdgeType::Derive => "#8250df",
+        EdgeType::Qualify => "#9a6700",
+    }
+}
+
+/// Legend for the edge colors of `render_claim_graph_svg`
+pub fn render_graph_legend() -> String {
+    let mut html = String::from("<p class=\"legend\">");
+    for edge_type in [EdgeType::Support, EdgeType::Require, EdgeType::Tension, EdgeType::Derive, EdgeType::Qualify] {
+        let _ = write!(html, "<span><i style=\"background:{}\"></i>{}</span>", edge_color(&edge_type), lower_debug(&edge_type));
+    }
+    html.push_str("</p>");
+    html
+}
+
+/// The claim dependency graph as SVG: one column per stratum (core first),
+/// nodes numbered in claim order, edges colored by type
+pub fn render_claim_graph_svg(session: &Session) -> String {
+    if session.claims.is_empty() {
+        return "<p class=\"empty\">No claims yet.</p>".to_string();
+    }
+    let strata = [ClaimStratum::Core, ClaimStratum::Structural, ClaimStratum::Evidential, ClaimStratum::Peripheral];
+    let evidence = score_evidence(&session.claims, &session.cdg_edges);
+    let mut rows = [0usize; 4];
+    let mut positions: HashMap<&str, (f32, f32)> = HashMap::new();
+    let mut nodes = String::new();
+    for (index, (claim, scored)) in session.claims.iter().zip(&evidence.claims).enumerate() {
+        let column = strata.iter().position(|s| *s == scored.stratum).unwrap_or(3);
+        let (cx, cy) = (
+            GRAPH_COLUMN_WIDTH * (column as f32 + 0.5),
+            GRAPH_TOP + GRAPH_ROW_HEIGHT * rows[column] as f32,
+        );
+        rows[column] += 1;
+        positions.insert(claim.id.as_str(), (cx, cy));
+        let fill = if scored.under_supported { "#fff8c5" } else { "#ffffff" };
+        let _ = writeln!(
+            nodes,
+            "<g><title>{}</title><circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{}\" fill=\"{}\" stroke=\"#1f2328\"/><text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text></g>",
+            escape_html(&claim.content),
+            cx,
+            cy,
+            NODE_RADIUS,
+            fill,
+            cx,
+            cy + 4.0,
+            index + 1
+        );
+    }
+
+    let mut edges = String::new();
+    for edge in &session.cdg_edges {
+        let (Some(&(x1, y1)), Some(&(x2, y2))) =
+            (positions.get(edge.source_claim_id.as_str()), positions.get(edge.target_claim_id.as_str()))
+        else {
+            continue;
+        };
+        // Stop short of the node circles so arrowheads stay visible
+        let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt().max(1.0);
+        let (dx, dy) = ((x2 - x1) / length * NODE_RADIUS, (y2 - y1) / length * NODE_RADIUS);
+        let color = edge_color(&edge.edge_type);
+        let dashed = match (&edge.edge_type, &edge.resolution) {
+            (EdgeType::Tension, Some(ResolutionStatus::Resolved | ResolutionStatus::Accepted)) => " stroke-dasharray=\"2 3\"",
+            (EdgeType::Tension, _) => " stroke-dasharray=\"6 3\"",
+            _ => "",
+        };
+        let _ = writeln!(
+            edges,
+            "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" stroke-width=\"{:.1}\"{} marker-end=\"url(#arrow-{})\"/>",
+            x1 + dx,
+            y1 + dy,
+            x2 - dx,
+            y2 - dy,
+            color,
+            1.0 + edge.weight.clamp(0.0, 1.0) * 1.5,
+            dashed,
+            lower_debug(&edge.edge_type)
+        );
+    }
+
+    let height = GRAPH_TOP + GRAPH_ROW_HEIGHT * rows.iter().copied().max().unwrap_or(1) as f32;
+    let width = GRAPH_COLUMN_WIDTH * strata.len() as f32;
+    let mut svg = format!(
+        "<svg viewBox=\"0 0 {0} {1}\" width=\"{0}\" height=\"{1}\" role=\"img\" aria-label=\"Claim dependency graph\">\n<defs>",
+        width, height
+    );
+    for edge_type in [EdgeType::Support, EdgeType::Require, EdgeType::Tension, EdgeType::Derive, EdgeType::Qualify] {
+        let _ = write!(
+            svg,
+            "<marker id=\"arrow-{}\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"6\" markerHeight=\"6\" orient=\"auto\"><path d=\"M0,0 L10,5 L0,10 z\" fill=\"{}\"/></marker>",
+            lower_debug(&edge_type),
+            edge_color(&edge_type)
+        );
+    }
+    svg.push_str("</defs>\n");
+    for (column, stratum) in strata.iter().enumerate() {
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"24\" text-anchor=\"middle\" font-weight=\"600\">{}</text>",
+            GRAPH_COLUMN_WIDTH * (column as f32 + 0.5),
+            lower_debug(stratum)
+        );
+    }
+    svg.push_str(&edges);
+    svg.push_str(&nodes);
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Claims with their stratum, evidential support and cited sources
+fn render_evidence_table(session: &Session, citations: &[CitationEntry]) -> String {
+    if session.claims.is_empty() {
+        return "<p class=\"empty\">No claims yet.</p>".to_string();
+    }
+    let evidence = score_evidence(&session.claims, &session.cdg_edges);
+    let mut html = String::from(
+        "<table>\n<thead><tr><th>#</th><th>Claim</th><th>Stratum</th><th>Support</th><th>Score</th><th>Sources</th></tr></thead>\n<tbody>\n",
+    );
+    for (index, (claim, scored)) in session.claims.iter().zip(&evidence.claims).enumerate() {
+        let sources: Vec<String> = citations
+            .iter()
+            .filter(|c| c.claim_ids.contains(&claim.id))
+            .map(|c| format!("<a href=\"#ref-{0}\">[{0}]</a>", c.number))
+            .collect();
+        let marker = claim.marker.as_deref().map(|m| format!("<strong>{}</strong> ", escape_html(m))).unwrap_or_default();
+        let _ = writeln!(
+            html,
+            "<tr{}><td class=\"num\">{}</td><td>{}{}</td><td>{}</td><td class=\"num\">{:.2}</td><td class=\"num\">{:.2}</td><td>{}</td></tr>",
+            if scored.under_supported { " class=\"weak\"" } else { "" },
+            index + 1,
+            marker,
+            escape_html(&claim.content),
+            lower_debug(&scored.stratum),
+            scored.support,
+            scored.score,
+            sources.join(" ")
+        );
+    }
+    html.push_str("</tbody>\n</table>\n");
+    if !evidence.under_supported.is_empty() {
+        let _ = writeln!(
+            html,
+            "<p class=\"meta\">Highlighted rows are load-bearing claims with little evidential support ({}).</p>",
+            evidence.under_supported.len()
+        );
+    }
+    html
+}
+
+/// The report as a standalone HTML document; `index_href` adds a link
+/// back to the page listing it (for published sites)
+pub fn render_report_html(
+    session: &Session,
+    history: &[ConfidencePoint],
+    citations: &[CitationEntry],
+    generated_at: DateTime<Utc>,
+    index_href: Option<&str>,
+) -> String {
+    let mut html = String::with_capacity(16 * 1024);
+    let _ = writeln!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>",
+        escape_html(&session.title),
+        STYLE
+    );
+    if let Some(href) = index_href {
+        let _ = writeln!(html, "<nav><a href=\"{}\">← All theses</a></nav>", escape_html(href));
+    }
+    let _ = writeln!(html, "<header>\n<h1>{}</h1>", escape_html(&session.title));
+    let _ = writeln!(
+        html,
+        "<p class=\"meta\">{} · {} · {} claims · {} tensions · updated {}</p>\n</header>",
+        lower_debug(&session.status),
+        lower_debug(&session.mode),
+        session.claims.len(),
+        session.tensions.len(),
+        session.updated.format("%Y-%m-%d")
+    );
+
+    html.push_str("<h2>Thesis</h2>\n");
+    match &session.thesis {
+        Some(thesis) => {
+            let _ = writeln!(
+                html,
+                "<div class=\"thesis\">{}</div>\n<p>Confidence: <span class=\"confidence\">{:.0}%</span></p>",
+                escape_html(thesis.content.trim()),
+                thesis.confidence * 100.0
+            );
+        }
+        None => html.push_str("<p class=\"empty\">No thesis formed yet.</p>\n"),
+    }
+    if let Some(summary) = &session.summary {
+        let _ = writeln!(html, "<h2>Context</h2>\n<p>{}</p>", escape_html(summary.trim()));
+    }
+
+    let _ = writeln!(html, "<h2>Confidence History</h2>\n{}", render_confidence_svg(history));
+    let _ = writeln!(html, "<h2>Claim Graph</h2>\n{}\n{}", render_claim_graph_svg(session), render_graph_legend());
+
+    html.push_str("<h2>Open Tensions</h2>\n");
+    let claim_number: HashMap<&str, usize> = session.claims.iter().enumerate().map(|(i, c)| (c.id.as_str(), i + 1)).collect();
+    let open: Vec<_> = session.tensions.iter().filter(|t| t.resolution.is_none()).collect();
+    if open.is_empty() {
+        html.push_str("<p class=\"empty\">No open tensions.</p>\n");
+    } else {
+        html.push_str("<ul>\n");
+        for tension in open {
+            let between = |id: &str| claim_number.get(id).map(|n| format!("#{}", n)).unwrap_or_else(|| "?".to_string());
+            let _ = writeln!(
+                html,
+                "<li>{} <span class=\"meta\">(claims {} and {})</span></li>",
+                escape_html(&tension.description),
+                between(&tension.claim_a_id),
+                between(&tension.claim_b_id)
+            );
+        }
+        html.push_str("</ul>\n");
+    }
+
+    let _ = writeln!(html, "<h2>Evidence</h2>\n{}", render_evidence_table(session, citations));
+    if !citations.is_empty() {
+        html.push_str("<h2>References</h2>\n<ol>\n");
+        for entry in citations {
+            let _ = writeln!(html, "<li id=\"ref-{}\">{}</li>", entry.number, inline_markdown(&entry.reference));
+        }
+        html.push_str("</ol>\n");
+    }
+
+    let _ = writeln!(
+        html,
+        "<footer>Generated {} from Dialectic session <a href=\"{}\">{}</a>.</footer>\n</body>\n</html>",
+        generated_at.format("%Y-%m-%d %H:%M UTC"),
+        escape_html(&session_link(&session.id)),
+        escape_html(&session.id)
+    );
+    html
+}
+
+/// Print `html_path` to `pdf_path` with the first headless browser found
+async fn print_pdf(html_path: &Path, pdf_path: &Path) -> Result<(), ReportError> {
+    let browser = PDF_RENDERERS
+        .iter()
+        .find_map(|name| locate(name))
+        .map(|(path, _)| path)
+        .ok_or(ReportError::PdfRendererUnavailable)?;
+    let child = tokio::process::Command::new(&browser)
+        .arg("--headless")
+        .arg("--disable-gpu")
+        .arg("--no-pdf-header-footer")
+        .arg(format!("--print-to-pdf={}", pdf_path.display()))
+        .arg(format!("file://{}", html_path.display()))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| ReportError::PdfFailed(format!("{}: {}", browser.display(), e)))?;
+    let output = tokio::time::timeout(PDF_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| ReportError::PdfFailed(format!("timed out after {}s", PDF_TIMEOUT.as_secs())))??;
+    if !output.status.success() || !pdf_path.is_file() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(ReportError::PdfFailed(stderr.lines().last().unwrap_or("no output").to_string()));
+    }
+    Ok(())
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), std::io::Error> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)
+}
+
+/// Confidence history of `session` from its audit log (best-effort)
+pub fn load_confidence_history(session: &Session) -> Vec<ConfidencePoint> {
+    let audit = get_session_dir_cli(&session.id).and_then(|dir| read_audit_log(&dir)).unwrap_or_else(|e| {
+        warn!(session_id = %session.id, error = %e, "Audit log unavailable for confidence history");
+        Vec::new()
+    });
+    confidence_history(session, &audit)
+}
+
+/// Render a report for `session` into `reports_dir`
+pub async fn render_report(session: &Session, format: ReportFormat, reports_dir: &Path) -> Result<PathBuf, ReportError> {
+    fs::create_dir_all(reports_dir)?;
+    let prefs = effective_preferences(Path::new(&session.working_dir)).0;
+    let citations = collect_citations(session, prefs.vault_path.as_deref().map(Path::new)).await;
+    let now = Utc::now();
+    let html = render_report_html(session, &load_confidence_history(session), &citations, now, None);
+
+    let html_path = reports_dir.join(format!("report-{}.html", now.format("%Y%m%d-%H%M%S")));
+    write_file(&html_path, &html)?;
+    let path = match format {
+        ReportFormat::Html => html_path,
+        ReportFormat::Pdf => {
+            let pdf_path = html_path.with_extension("pdf");
+            print_pdf(&html_path, &pdf_path).await?;
+            pdf_path
+        }
+    };
+    info!(session_id = %session.id, path = %path.display(), "Rendered session report");
+    Ok(path)
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub async fn render_session_report(session_id: String, format: Option<ReportFormat>) -> Result<RenderedReport, ReportError> {
+    let session = load_session_cli(&session_id)?;
+    let format = format.unwrap_or_default();
+    let reports_dir = get_session_dir_cli(&session_id)?.join(REPORTS_DIR);
+    let path = render_report(&session, format, &reports_dir).await?;
+    Ok(RenderedReport {
+        session_id: session.id,
+        format,
+        path: path.to_string_lossy().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_report_html() {
+        let session: Session = test_session(json!({
+            "id": "report-test", "title": "Pricing <power>", "status": "synthesizing",
+            "updated": "2026-01-03T00:00:00Z",
+            "claims": [
+                { "id": "c1", "content": "Brands can raise prices", "sourceId": "s", "marker": "[CORE]", "createdAt": "2026-01-01T00:00:00Z" },
+                { "id": "c2", "content": "Churn stayed flat after the 2025 increase", "sourceId": "s", "marker": null, "createdAt": "2026-01-01T00:00:00Z" },
+            ],
+            "cdgEdges": [
+                { "sourceClaimId": "c2", "targetClaimId": "c1", "edgeType": "SUPPORT", "weight": 0.8, "createdAt": "2026-01-01T00:00:00Z" },
+            ],
+            "tensions": [
+                { "id": "t1", "claimAId": "c1", "claimBId": "c2", "description": "Cost & brand", "resolution": null, "createdAt": "2026-01-01T00:00:00Z" },
+            ],
+            "thesis": { "content": "Raise prices", "confidence": 0.7, "updatedAt": "2026-01-03T00:00:00Z" },
+        }));
+        let mut updated = AuditEntry::new(AuditAction::ThesisUpdated, None, json!({ "previousConfidence": null, "confidence": 0.55 }));
+        updated.timestamp = "2026-01-02T00:00:00Z".parse().unwrap();
+        let history = confidence_history(&session, &[updated]);
+        assert_eq!(history.iter().map(|p| p.confidence).collect::<Vec<_>>(), vec![0.55, 0.7]);
+
+        let citations = vec![CitationEntry {
+            number: 1,
+            kind: crate::session::citations::CitationKind::WebSource,
+            reference: "[Q3 letter](https://example.com/q3?a=1&b=2)".to_string(),
+            url: Some("https://example.com/q3?a=1&b=2".to_string()),
+            claim_ids: vec!["c2".to_string()],
+        }];
+        let html = render_report_html(&session, &history, &citations, Utc::now(), None);
+        assert!(html.contains("<h1>Pricing &lt;power&gt;</h1>"));
+        assert!(html.contains("<span class=\"confidence\">70%</span>"));
+        assert!(html.contains("<polyline points="));
+        assert!(html.contains("marker-end=\"url(#arrow-support)\""));
+        assert!(html.contains("<li>Cost &amp; brand <span class=\"meta\">(claims #1 and #2)</span></li>"));
+        assert!(html.contains("<a href=\"#ref-1\">[1]</a>"));
+        assert!(html.contains("<li id=\"ref-1\"><a href=\"https://example.com/q3?a=1&amp;b=2\">Q3 letter</a></li>"));
+        assert!(html.contains("dialectic://session/report-test"));
+    }
+}
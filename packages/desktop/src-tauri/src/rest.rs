@@ -0,0 +1,376 @@
+//! Local REST API
+//!
+//! `dialectic serve --port <port>` (built with the `rest-api` feature)
+//! exposes the session, search, memory and budget commands over HTTP on
+//! 127.0.0.1 for scripts, browser extensions or a web UI. Everything under
+//! `/api` requires `Authorization: Bearer <token>`; `/health` does not.
+//! Errors use the CLI's `{"error": "..."}` shape.
+//!
+//! `/api/events` is a Server-Sent Events stream of everything emitted in
+//! this process (job progress, index progress) plus session changes picked
+//! up by watching the sessions directory — so changes made by the app, the
+//! CLI or hooks all show up, including budget threshold crossings.
+
+use axum::{
+    extract::{Path, Query, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::sse::{Event, KeepAlive, Sse},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use futures::Stream;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use notify_debouncer_mini::{new_debouncer, DebouncedEvent, Debouncer};
+use parking_lot::Mutex;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeSet, HashMap};
+use std::convert::Infallible;
+use std::net::Ipv4Addr;
+use std::path::Path as FsPath;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+use ulid::Ulid;
+
+use crate::chroma::memory::{read_memories, write_memory, MemoryError, MemoryRecord, MemoryType};
+use crate::context::budget::{BudgetStatus, ThresholdStatus, WORKING_BUDGET};
+use crate::context::unified_search::{unified_search, UnifiedSearchError, UnifiedSearchResults};
+use crate::events;
+use crate::session::similar::find_similar_sessions;
+use crate::session::{
+    create_session_in, get_app_data_dir_cli, list_sessions_cli, load_session_cli, CreateSessionInput,
+    CreateSessionResponse, Session, SessionError,
+};
+
+/// Environment variable holding the API token when `--token` isn't given
+pub const TOKEN_ENV: &str = "DIALECTIC_API_TOKEN";
+/// Shortest token `serve` accepts
+pub const MIN_TOKEN_LEN: usize = 16;
+
+/// A session's `session.json` changed
+pub const SESSION_UPDATED_EVENT: &str = "session-updated";
+/// Another file in a session directory changed
+pub const SESSION_FILE_EVENT: &str = "session-file-changed";
+/// A session's context budget moved to a different threshold
+pub const BUDGET_THRESHOLD_EVENT: &str = "budget-threshold";
+/// Sent to a subscriber that fell behind and missed events
+const LAGGED_EVENT: &str = "lagged";
+
+#[derive(Error, Debug)]
+pub enum ApiError {
+    #[error("Missing or invalid API token")]
+    Unauthorized,
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error(transparent)]
+    Memory(#[from] MemoryError),
+    #[error(transparent)]
+    Search(#[from] UnifiedSearchError),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::Session(SessionError::NotFound(_)) | ApiError::Memory(MemoryError::NotFound(_)) => {
+                StatusCode::NOT_FOUND
+            }
+            ApiError::Session(SessionError::InvalidSessionId | SessionError::InvalidPath(_))
+            | ApiError::Memory(MemoryError::InvalidType(_))
+            | ApiError::Search(UnifiedSearchError::InvalidSessionId) => StatusCode::BAD_REQUEST,
+            ApiError::Session(SessionError::Locked(_)) => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+#[derive(Clone)]
+struct ApiState {
+    token: Arc<String>,
+}
+
+/// A random 256-bit token, hex-encoded, for when none is configured
+pub fn generate_token() -> std::io::Result<String> {
+    let mut bytes = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| std::io::Error::other("no randomness for the API token"))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Reject tokens too short to resist guessing
+pub fn check_token(token: &str) -> std::io::Result<()> {
+    if token.trim().len() < MIN_TOKEN_LEN {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("API token must be at least {} characters", MIN_TOKEN_LEN),
+        ));
+    }
+    Ok(())
+}
+
+/// Compare without short-circuiting on the first differing byte
+fn tokens_match(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided.bytes().zip(expected.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+async fn require_token(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if provided.is_some_and(|token| tokens_match(token.trim(), &state.token)) {
+        next.run(request).await
+    } else {
+        ApiError::Unauthorized.into_response()
+    }
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+    budget: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct MemoryQuery {
+    q: String,
+    n: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct MemoryInput {
+    id: Option<String>,
+    content: String,
+    metadata: Option<Value>,
+}
+
+async fn health() -> Json<Value> {
+    Json(json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") }))
+}
+
+async fn list_sessions() -> Result<Json<Vec<Session>>, ApiError> {
+    Ok(Json(list_sessions_cli()?))
+}
+
+async fn create_session(Json(input): Json<CreateSessionInput>) -> Result<(StatusCode, Json<CreateSessionResponse>), ApiError> {
+    let (title, summary) = (input.title.clone(), input.summary.clone());
+    let session = create_session_in(&get_app_data_dir_cli()?, input)?;
+    let similar_sessions = find_similar_sessions(&title, summary.as_deref(), &session.id).await;
+    Ok((StatusCode::CREATED, Json(CreateSessionResponse { session, similar_sessions })))
+}
+
+async fn get_session(Path(session_id): Path<String>) -> Result<Json<Session>, ApiError> {
+    Ok(Json(load_session_cli(&session_id)?))
+}
+
+async fn get_budget(Path(session_id): Path<String>) -> Result<Json<BudgetStatus>, ApiError> {
+    let session = load_session_cli(&session_id)?;
+    let budget = session.context_budget.unwrap_or_default();
+    Ok(Json(BudgetStatus::from(&budget)))
+}
+
+async fn search(
+    Path(session_id): Path<String>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<UnifiedSearchResults>, ApiError> {
+    let results = unified_search(&session_id, &query.q, query.budget.unwrap_or(WORKING_BUDGET)).await?;
+    Ok(Json(results))
+}
+
+async fn read_memory(
+    Path(memory_type): Path<String>,
+    Query(query): Query<MemoryQuery>,
+) -> Result<Json<Vec<MemoryRecord>>, ApiError> {
+    let memory_type = MemoryType::from_str(&memory_type)?;
+    Ok(Json(read_memories(memory_type, &query.q, query.n.unwrap_or(5), false).await?))
+}
+
+async fn write_memory_handler(
+    Path(memory_type): Path<String>,
+    Json(input): Json<MemoryInput>,
+) -> Result<Json<Value>, ApiError> {
+    let memory_type = MemoryType::from_str(&memory_type)?;
+    let id = input.id.unwrap_or_else(|| Ulid::new().to_string());
+    write_memory(memory_type, &id, &input.content, input.metadata).await?;
+    Ok(Json(json!({ "id": id })))
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionUpdated {
+    session_id: String,
+    status: String,
+    updated: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionFileChanged {
+    session_id: String,
+    path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BudgetThresholdCrossed {
+    session_id: String,
+    from: ThresholdStatus,
+    to: ThresholdStatus,
+    usage_percentage: u8,
+}
+
+/// Session ID of a path inside `sessions_dir/sess_<id>/`
+fn session_id_of(sessions_dir: &FsPath, path: &FsPath) -> Option<String> {
+    let dir = path.strip_prefix(sessions_dir).ok()?.components().next()?;
+    dir.as_os_str().to_str()?.strip_prefix("sess_").map(str::to_string)
+}
+
+/// Emit events for one debounced batch of changes under the sessions directory
+fn emit_session_changes(
+    sessions_dir: &FsPath,
+    batch: &[DebouncedEvent],
+    thresholds: &Mutex<HashMap<String, ThresholdStatus>>,
+) {
+    let mut updated = BTreeSet::new();
+    for event in batch {
+        let Some(session_id) = session_id_of(sessions_dir, &event.path) else { continue };
+        let name = event.path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        // Atomic writes land as session.json.tmp then rename
+        if name.starts_with("session.json") {
+            updated.insert(session_id);
+        } else {
+            events::emit(SESSION_FILE_EVENT, SessionFileChanged {
+                session_id,
+                path: event.path.to_string_lossy().to_string(),
+            });
+        }
+    }
+
+    for session_id in updated {
+        let Ok(session) = load_session_cli(&session_id) else { continue };
+        let status = BudgetStatus::from(&session.context_budget.clone().unwrap_or_default());
+        let previous = thresholds.lock().insert(session_id.clone(), status.threshold_status);
+        // The first sighting of a session only records its baseline
+        if let Some(from) = previous.filter(|p| *p != status.threshold_status) {
+            events::emit(BUDGET_THRESHOLD_EVENT, BudgetThresholdCrossed {
+                session_id: session_id.clone(),
+                from,
+                to: status.threshold_status,
+                usage_percentage: status.usage_percentage,
+            });
+        }
+        events::emit(SESSION_UPDATED_EVENT, SessionUpdated {
+            session_id,
+            status: format!("{:?}", session.status).to_lowercase(),
+            updated: session.updated,
+        });
+    }
+}
+
+/// Watch the sessions directory, emitting session events while the
+/// returned debouncer is alive
+fn watch_sessions() -> Result<Debouncer<RecommendedWatcher>, ApiError> {
+    let sessions_dir = get_app_data_dir_cli()?.join("sessions");
+    std::fs::create_dir_all(&sessions_dir).map_err(SessionError::from)?;
+    let thresholds = Mutex::new(HashMap::new());
+    let dir = sessions_dir.clone();
+    let mut debouncer = new_debouncer(
+        Duration::from_millis(500),
+        move |result: Result<Vec<DebouncedEvent>, notify::Error>| match result {
+            Ok(batch) => emit_session_changes(&dir, &batch, &thresholds),
+            Err(e) => warn!(error = %e, "Session watcher error"),
+        },
+    )
+    .map_err(|e| SessionError::Io(std::io::Error::other(e.to_string())))?;
+    debouncer
+        .watcher()
+        .watch(&sessions_dir, RecursiveMode::Recursive)
+        .map_err(|e| SessionError::Io(std::io::Error::other(e.to_string())))?;
+    Ok(debouncer)
+}
+
+async fn event_stream() -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = futures::stream::unfold(events::subscribe(), |mut rx| async move {
+        let event = match rx.recv().await {
+            Ok(bus_event) => Event::default()
+                .event(bus_event.event)
+                .json_data(bus_event.payload)
+                .unwrap_or_default(),
+            Err(RecvError::Lagged(missed)) => Event::default().event(LAGGED_EVENT).data(missed.to_string()),
+            Err(RecvError::Closed) => return None,
+        };
+        Some((Ok(event), rx))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+pub fn router(token: String) -> Router {
+    let state = ApiState { token: Arc::new(token) };
+    let api = Router::new()
+        .route("/sessions", get(list_sessions).post(create_session))
+        .route("/sessions/{id}", get(get_session))
+        .route("/sessions/{id}/budget", get(get_budget))
+        .route("/sessions/{id}/search", get(search))
+        .route("/memories/{memory_type}", get(read_memory).post(write_memory_handler))
+        .route("/events", get(event_stream))
+        .route_layer(middleware::from_fn_with_state(state, require_token));
+    Router::new().route("/health", get(health)).nest("/api", api)
+}
+
+/// Serve the API on 127.0.0.1:`port` until the process exits
+pub async fn serve(port: u16, token: String) -> std::io::Result<()> {
+    check_token(&token)?;
+    let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await?;
+    // Events still stream without the watcher, just not session changes
+    let _watcher = watch_sessions().map_err(|e| warn!(error = %e, "Session watcher unavailable")).ok();
+    info!(port = port, "REST API listening");
+    axum::serve(listener, router(token)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("abc123", "abc123"));
+        assert!(!tokens_match("abc124", "abc123"));
+        assert!(!tokens_match("abc", "abc123"));
+    }
+
+    #[test]
+    fn test_token_strength() {
+        assert!(check_token("").is_err());
+        assert!(check_token("  short token  ").is_err());
+        let token = generate_token().unwrap();
+        assert_eq!(token.len(), 64);
+        assert!(check_token(&token).is_ok());
+        assert_ne!(token, generate_token().unwrap());
+    }
+
+    #[test]
+    fn test_session_id_of() {
+        let dir = FsPath::new("/data/sessions");
+        assert_eq!(session_id_of(dir, FsPath::new("/data/sessions/sess_abc/session.json")).as_deref(), Some("abc"));
+        assert!(session_id_of(dir, FsPath::new("/data/sessions/index.json")).is_none());
+        assert!(session_id_of(dir, FsPath::new("/elsewhere/sess_abc/session.json")).is_none());
+    }
+
+    #[test]
+    fn test_error_status() {
+        let status = |e: ApiError| e.into_response().status();
+        assert_eq!(status(ApiError::Unauthorized), StatusCode::UNAUTHORIZED);
+        assert_eq!(status(SessionError::NotFound("x".into()).into()), StatusCode::NOT_FOUND);
+        assert_eq!(status(MemoryError::InvalidType("x".into()).into()), StatusCode::BAD_REQUEST);
+    }
+}
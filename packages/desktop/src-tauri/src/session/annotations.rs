@@ -0,0 +1,187 @@
+//! Review Annotations
+//!
+//! Structured critique a collaborator leaves on a session: a comment by an
+//! author on a claim, a tension or the thesis. Open annotations are listed
+//! in the session's CLAUDE.md so the next pass addresses them, and travel
+//! with shared bundles and decision records. Resolving one keeps it on the
+//! session as a record of the review.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::info;
+use ulid::Ulid;
+
+use super::{modify_session, Session, SessionError};
+
+/// What an annotation comments on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AnnotationTarget {
+    Claim { id: String },
+    Tension { id: String },
+    Thesis,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Annotation {
+    pub id: String,
+    pub author: String,
+    pub target: AnnotationTarget,
+    pub comment: String,
+    #[serde(default)]
+    pub resolved: bool,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// Input for adding an annotation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddAnnotationInput {
+    pub session_id: String,
+    pub author: String,
+    pub target: AnnotationTarget,
+    pub comment: String,
+}
+
+impl AnnotationTarget {
+    /// Whether the claim, tension or thesis exists in `session`
+    pub fn exists_in(&self, session: &Session) -> bool {
+        match self {
+            AnnotationTarget::Claim { id } => session.claims.iter().any(|c| &c.id == id),
+            AnnotationTarget::Tension { id } => session.tensions.iter().any(|t| &t.id == id),
+            AnnotationTarget::Thesis => session.thesis.is_some(),
+        }
+    }
+
+    /// Short description of the target, quoting the claim or tension
+    pub fn describe(&self, session: &Session) -> String {
+        match self {
+            AnnotationTarget::Claim { id } => match session.claims.iter().find(|c| &c.id == id) {
+                Some(claim) => format!("claim `{}` \"{}\"", id, claim.content),
+                None => format!("claim `{}`", id),
+            },
+            AnnotationTarget::Tension { id } => match session.tensions.iter().find(|t| &t.id == id) {
+                Some(tension) => format!("tension `{}` \"{}\"", id, tension.description),
+                None => format!("tension `{}`", id),
+            },
+            AnnotationTarget::Thesis => "thesis".to_string(),
+        }
+    }
+}
+
+/// Unresolved annotations, oldest first
+pub fn open_annotations(session: &Session) -> Vec<&Annotation> {
+    session.annotations.iter().filter(|a| !a.resolved).collect()
+}
+
+/// One line per open annotation, e.g. `**ana** on thesis: Too confident`
+pub fn annotation_lines(session: &Session) -> Vec<String> {
+    open_annotations(session)
+        .into_iter()
+        .map(|a| format!("**{}** on {}: {}", a.author, a.target.describe(session), a.comment))
+        .collect()
+}
+
+/// Attach a new annotation to `session`
+pub fn add(session: &mut Session, author: &str, target: AnnotationTarget, comment: &str) -> Result<Annotation, SessionError> {
+    let (author, comment) = (author.trim(), comment.trim());
+    if author.is_empty() || comment.is_empty() {
+        return Err(SessionError::InvalidAnnotation("an author and a comment are required".to_string()));
+    }
+    if !target.exists_in(session) {
+        return Err(SessionError::NotFound(target.describe(session)));
+    }
+    let annotation = Annotation {
+        id: Ulid::new().to_string(),
+        author: author.to_string(),
+        target,
+        comment: comment.to_string(),
+        resolved: false,
+        created_at: Utc::now(),
+        resolved_at: None,
+    };
+    session.annotations.push(annotation.clone());
+    Ok(annotation)
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn add_annotation(app: AppHandle, input: AddAnnotationInput) -> Result<Annotation, SessionError> {
+    let mut added = None;
+    modify_session(&app, &input.session_id, |s| {
+        added = Some(add(s, &input.author, input.target, &input.comment)?);
+        Ok(())
+    })?;
+    let annotation = added.ok_or_else(|| SessionError::NotFound(input.session_id.clone()))?;
+    info!(session_id = %input.session_id, annotation_id = %annotation.id, "Added annotation");
+    Ok(annotation)
+}
+
+#[tauri::command]
+pub fn resolve_annotation(app: AppHandle, session_id: String, annotation_id: String) -> Result<Session, SessionError> {
+    modify_session(&app, &session_id, |s| {
+        let annotation = s
+            .annotations
+            .iter_mut()
+            .find(|a| a.id == annotation_id)
+            .ok_or_else(|| SessionError::NotFound(annotation_id.clone()))?;
+        annotation.resolved = true;
+        annotation.resolved_at = Some(Utc::now());
+        Ok(())
+    })
+}
+
+/// Annotations on a session, open ones only unless `include_resolved`
+#[tauri::command]
+pub fn list_annotations(
+    app: AppHandle,
+    session_id: String,
+    include_resolved: Option<bool>,
+) -> Result<Vec<Annotation>, SessionError> {
+    let session = super::load_session(app, session_id)?;
+    Ok(session
+        .annotations
+        .into_iter()
+        .filter(|a| include_resolved.unwrap_or(false) || !a.resolved)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use serde_json::json;
+
+    #[test]
+    fn test_add_and_render_annotations() {
+        let mut session: Session = test_session(json!({
+            "id": "ann", "title": "Annotate", "status": "tensions",
+            "claims": [
+                { "id": "c1", "content": "Demand is inelastic", "sourceId": "s", "marker": null, "createdAt": "2026-01-01T00:00:00Z" },
+            ],
+        }));
+
+        let claim = AnnotationTarget::Claim { id: "c1".to_string() };
+        let first = add(&mut session, "ana", claim, "Cite the 2024 price test").unwrap();
+        assert!(add(&mut session, "ana", AnnotationTarget::Thesis, "No thesis yet").is_err());
+        assert!(add(&mut session, "ana", AnnotationTarget::Tension { id: "t9".to_string() }, "Missing").is_err());
+        assert!(add(&mut session, " ", AnnotationTarget::Claim { id: "c1".to_string() }, "Anonymous").is_err());
+        add(&mut session, "bo", AnnotationTarget::Claim { id: "c1".to_string() }, "Agree").unwrap();
+
+        assert_eq!(
+            annotation_lines(&session)[0],
+            "**ana** on claim `c1` \"Demand is inelastic\": Cite the 2024 price test"
+        );
+        session.annotations.iter_mut().find(|a| a.id == first.id).unwrap().resolved = true;
+        assert_eq!(open_annotations(&session).len(), 1);
+
+        // Round-trips with its tagged target
+        let value = serde_json::to_value(&session.annotations[0]).unwrap();
+        assert_eq!(value["target"], json!({ "kind": "claim", "id": "c1" }));
+    }
+}
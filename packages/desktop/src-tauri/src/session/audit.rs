@@ -0,0 +1,364 @@
+//! Session Audit Log
+//!
+//! Append-only `audit.log` (JSONL) in each session directory recording
+//! status changes, claim/tension/edge mutations, paper trail compression
+//! and launches. Entries are derived by diffing the session before and
+//! after a write, so edits made by the agent directly to `session.json`
+//! (picked up by the watcher) are logged the same way as app and CLI writes.
+//! The same diffs write episodic memories of completed passes and status
+//! changes (`chroma::memory::episodes`).
+
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::LazyLock;
+use tracing::warn;
+
+use super::{calendar, Session, SessionError};
+use crate::cdg::CdgEdge;
+use crate::chroma::memory::episodes;
+
+/// Audit log filename within a session directory
+pub const AUDIT_LOG: &str = "audit.log";
+
+/// Recent entries checked for duplicates before appending
+const DEDUP_WINDOW_ENTRIES: usize = 50;
+/// Identical entries within this many seconds are treated as one change
+/// seen twice (e.g. a CLI write also observed by the app's watcher)
+const DEDUP_WINDOW_SECS: i64 = 10;
+
+/// Who performed the write in this process
+static ACTOR: RwLock<AuditActor> = RwLock::new(AuditActor::App);
+
+/// Last audited state per session, used to diff watcher events
+static SNAPSHOTS: LazyLock<Mutex<HashMap<String, Session>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Origin of a change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditActor {
+    /// The desktop app
+    App,
+    /// The `dialectic` CLI
+    Cli,
+    /// An external edit to session.json (usually the agent)
+    Agent,
+}
+
+/// Kind of change recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Created,
+    StatusChanged,
+    /// Thesis text or confidence changed; detail carries both confidences
+    ThesisUpdated,
+    ClaimAdded,
+    ClaimUpdated,
+    ClaimRemoved,
+    TensionAdded,
+    TensionUpdated,
+    TensionRemoved,
+    EdgeAdded,
+    EdgeUpdated,
+    EdgeRemoved,
+    /// Tags added or removed; detail lists both
+    TagsChanged,
+    Compressed,
+    Reclassified,
+    Launched,
+}
+
+/// A single audit log line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub action: AuditAction,
+    pub actor: AuditActor,
+    /// Id of the affected claim, tension or edge
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(default)]
+    pub detail: Value,
+}
+
+impl AuditEntry {
+    pub fn new(action: AuditAction, target: Option<String>, detail: Value) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            action,
+            actor: current_actor(),
+            target,
+            detail,
+        }
+    }
+
+    fn same_change(&self, other: &AuditEntry) -> bool {
+        self.action == other.action
+            && self.target == other.target
+            && self.detail == other.detail
+            && (self.timestamp - other.timestamp).abs() <= Duration::seconds(DEDUP_WINDOW_SECS)
+    }
+}
+
+/// Set the actor recorded for writes from this process
+pub fn set_actor(actor: AuditActor) {
+    *ACTOR.write() = actor;
+}
+
+fn current_actor() -> AuditActor {
+    *ACTOR.read()
+}
+
+fn edge_key(edge: &CdgEdge) -> String {
+    format!("{}->{}:{:?}", edge.source_claim_id, edge.target_claim_id, edge.edge_type).to_lowercase()
+}
+
+/// Added/updated/removed entries for a keyed collection
+fn diff_keyed<T: Serialize>(
+    before: &[T],
+    after: &[T],
+    key: impl Fn(&T) -> String,
+    actions: (AuditAction, AuditAction, AuditAction),
+    entries: &mut Vec<AuditEntry>,
+) {
+    let (added, updated, removed) = actions;
+    let to_value = |item: &T| serde_json::to_value(item).unwrap_or(Value::Null);
+    let old: HashMap<String, &T> = before.iter().map(|item| (key(item), item)).collect();
+    let new: HashMap<String, &T> = after.iter().map(|item| (key(item), item)).collect();
+
+    for item in after {
+        let id = key(item);
+        match old.get(&id) {
+            None => entries.push(AuditEntry::new(added, Some(id), to_value(item))),
+            Some(prev) => {
+                let (prev, next) = (to_value(prev), to_value(item));
+                if prev != next {
+                    entries.push(AuditEntry::new(updated, Some(id), json!({ "before": prev, "after": next })));
+                }
+            }
+        }
+    }
+    for item in before {
+        let id = key(item);
+        if !new.contains_key(&id) {
+            entries.push(AuditEntry::new(removed, Some(id), to_value(item)));
+        }
+    }
+}
+
+/// Audit entries describing the changes from `before` to `after`
+pub fn diff_sessions(before: &Session, after: &Session) -> Vec<AuditEntry> {
+    let mut entries = Vec::new();
+
+    if before.status != after.status {
+        entries.push(AuditEntry::new(
+            AuditAction::StatusChanged,
+            None,
+            json!({ "from": before.status, "to": after.status }),
+        ));
+    }
+
+    let thesis = |s: &Session| s.thesis.as_ref().map(|t| (t.content.clone(), t.confidence));
+    if thesis(before) != thesis(after) {
+        entries.push(AuditEntry::new(
+            AuditAction::ThesisUpdated,
+            None,
+            json!({
+                "previousConfidence": before.thesis.as_ref().map(|t| t.confidence),
+                "confidence": after.thesis.as_ref().map(|t| t.confidence),
+            }),
+        ));
+    }
+
+    let added: Vec<&String> = after.tags.iter().filter(|t| !before.tags.contains(t)).collect();
+    let removed: Vec<&String> = before.tags.iter().filter(|t| !after.tags.contains(t)).collect();
+    if !added.is_empty() || !removed.is_empty() {
+        entries.push(AuditEntry::new(AuditAction::TagsChanged, None, json!({ "added": added, "removed": removed })));
+    }
+
+    diff_keyed(
+        &before.claims,
+        &after.claims,
+        |c| c.id.clone(),
+        (AuditAction::ClaimAdded, AuditAction::ClaimUpdated, AuditAction::ClaimRemoved),
+        &mut entries,
+    );
+    diff_keyed(
+        &before.tensions,
+        &after.tensions,
+        |t| t.id.clone(),
+        (AuditAction::TensionAdded, AuditAction::TensionUpdated, AuditAction::TensionRemoved),
+        &mut entries,
+    );
+    diff_keyed(
+        &before.cdg_edges,
+        &after.cdg_edges,
+        edge_key,
+        (AuditAction::EdgeAdded, AuditAction::EdgeUpdated, AuditAction::EdgeRemoved),
+        &mut entries,
+    );
+
+    // Compression moves material down the paper trail tiers
+    let tiers = |s: &Session| {
+        s.paper_trail.as_ref().map(|pt| {
+            (pt.historical_summaries.len(), pt.archive_paths.len(), pt.total_tokens())
+        })
+    };
+    if let (Some((hist_a, arch_a, tokens_a)), Some((hist_b, arch_b, tokens_b))) = (tiers(before), tiers(after)) {
+        if hist_b > hist_a || arch_b > arch_a {
+            entries.push(AuditEntry::new(
+                AuditAction::Compressed,
+                None,
+                json!({
+                    "historicalSummaries": hist_b,
+                    "archivePaths": arch_b,
+                    "tokensBefore": tokens_a,
+                    "tokensAfter": tokens_b,
+                }),
+            ));
+        }
+    }
+
+    entries
+}
+
+/// Read every entry in a session's audit log (oldest first).
+/// Malformed lines are skipped.
+pub fn read_audit_log(session_dir: &Path) -> Result<Vec<AuditEntry>, SessionError> {
+    let path = session_dir.join(AUDIT_LOG);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(fs::read_to_string(&path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Append entries, skipping any already logged moments ago
+pub fn append_entries(session_dir: &Path, entries: &[AuditEntry]) -> Result<(), SessionError> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let existing = read_audit_log(session_dir)?;
+    let recent = &existing[existing.len().saturating_sub(DEDUP_WINDOW_ENTRIES)..];
+
+    let mut lines = String::new();
+    for entry in entries {
+        if recent.iter().any(|e| e.same_change(entry)) {
+            continue;
+        }
+        lines.push_str(&serde_json::to_string(entry)?);
+        lines.push('\n');
+    }
+    if lines.is_empty() {
+        return Ok(());
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(session_dir.join(AUDIT_LOG))?;
+    file.write_all(lines.as_bytes())?;
+    Ok(())
+}
+
+/// Best-effort append; failures are logged, never returned
+pub fn record(session_dir: &Path, entries: &[AuditEntry]) {
+    let compressions = entries.iter().filter(|e| e.action == AuditAction::Compressed).count();
+    crate::metrics::increment(crate::metrics::COMPRESSION_EVENTS, compressions as u64);
+    if let Err(e) = append_entries(session_dir, entries) {
+        warn!(error = %e, dir = %session_dir.display(), "Failed to write audit log");
+    }
+}
+
+/// Record the changes made by a write and remember the new state so the
+/// watcher doesn't attribute the same write to the agent
+pub fn record_changes(session_dir: &Path, before: Option<&Session>, after: &Session) {
+    if let Some(before) = before {
+        record(session_dir, &diff_sessions(before, after));
+        episodes::record_outcomes(before, after);
+        calendar::refresh_if_changed(before, after);
+    }
+    SNAPSHOTS.lock().insert(after.id.clone(), after.clone());
+}
+
+/// Diff an externally written session against the last audited state.
+/// The first observation of a session only establishes the baseline.
+pub fn observe_external(session_dir: &Path, session: &Session) {
+    let previous = SNAPSHOTS.lock().insert(session.id.clone(), session.clone());
+    if let Some(previous) = previous {
+        let mut entries = diff_sessions(&previous, session);
+        for entry in entries.iter_mut() {
+            entry.actor = AuditActor::Agent;
+        }
+        record(session_dir, &entries);
+        episodes::record_outcomes(&previous, session);
+        calendar::refresh_if_changed(&previous, session);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+
+    fn session(claims: Value, status: &str) -> Session {
+        test_session(json!({
+            "id": "audit",
+            "title": "Audit",
+            "status": status,
+            "claims": claims,
+        }))
+    }
+
+    fn claim(id: &str, content: &str) -> Value {
+        json!({"id": id, "content": content, "sourceId": "s", "marker": null, "createdAt": "2026-01-01T00:00:00Z"})
+    }
+
+    #[test]
+    fn test_diff_sessions() {
+        let before = session(json!([claim("c1", "rates fall"), claim("c2", "old")]), "backlog");
+        let after = session(json!([claim("c1", "rates fall slowly"), claim("c3", "new")]), "exploring");
+
+        let actions: Vec<(AuditAction, Option<String>)> = diff_sessions(&before, &after)
+            .into_iter()
+            .map(|e| (e.action, e.target))
+            .collect();
+        assert_eq!(actions, vec![
+            (AuditAction::StatusChanged, None),
+            (AuditAction::ClaimUpdated, Some("c1".to_string())),
+            (AuditAction::ClaimAdded, Some("c3".to_string())),
+            (AuditAction::ClaimRemoved, Some("c2".to_string())),
+        ]);
+        assert!(diff_sessions(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_append_dedups_and_reads_back() {
+        let dir = std::env::temp_dir().join(format!("dialectic_audit_{}", ulid::Ulid::new()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let entry = AuditEntry::new(AuditAction::Launched, None, json!({"cliTool": "claude"}));
+        append_entries(&dir, std::slice::from_ref(&entry)).unwrap();
+        // Same change observed again (e.g. by the watcher) is not duplicated
+        let mut seen_again = entry.clone();
+        seen_again.actor = AuditActor::Agent;
+        append_entries(&dir, &[seen_again]).unwrap();
+        fs::write(dir.join(AUDIT_LOG), format!("{}not json\n", fs::read_to_string(dir.join(AUDIT_LOG)).unwrap())).unwrap();
+
+        let log = read_audit_log(&dir).unwrap();
+        assert_eq!(log, vec![entry]);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,209 @@
+//! Review Calendar
+//!
+//! Exports thesis review dates and stale-session reminders as an iCalendar
+//! file (`<app data>/reviews.ics`) that a calendar app can import or
+//! subscribe to. Once exported, the file is regenerated whenever a session
+//! change moves one of its events, so rescheduled or removed triggers don't
+//! linger in the calendar.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+use super::{get_app_data_dir_cli, list_sessions_from_dir, Session, SessionError, SessionStatus};
+use crate::deep_link::session_link;
+use crate::jobs::{self, JobKind};
+
+pub const CALENDAR_FILE: &str = "reviews.ics";
+/// Days without an update before an in-progress session gets a reminder
+const STALE_AFTER_DAYS: i64 = 14;
+/// Reminder time on the day of an all-day event
+const ALARM_OFFSET: &str = "PT9H";
+/// RFC 5545 content lines are folded at 75 octets
+const MAX_LINE_OCTETS: usize = 75;
+
+/// An all-day calendar entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub date: NaiveDate,
+    pub summary: String,
+    pub description: String,
+    pub url: String,
+}
+
+/// Review-by dates of a session's triggers, plus a reminder for an
+/// in-progress session that hasn't been touched in a while (dated
+/// `STALE_AFTER_DAYS` after its last update, or today once that's passed)
+pub fn session_events(session: &Session, now: DateTime<Utc>) -> Vec<CalendarEvent> {
+    let url = session_link(&session.id);
+    let thesis = session.thesis.as_ref().map(|t| format!("\n\nThesis ({:.0}%): {}", t.confidence * 100.0, t.content));
+    let mut events: Vec<CalendarEvent> = session
+        .review_triggers
+        .iter()
+        .filter_map(|trigger| {
+            Some(CalendarEvent {
+                uid: format!("{}-{}@dialectic", session.id, trigger.id),
+                date: trigger.review_by?.date_naive(),
+                summary: format!("Review thesis: {}", session.title),
+                description: format!("{}{}", trigger.description, thesis.clone().unwrap_or_default()),
+                url: url.clone(),
+            })
+        })
+        .collect();
+
+    let in_progress = matches!(session.status, SessionStatus::Exploring | SessionStatus::Tensions | SessionStatus::Synthesizing);
+    if in_progress {
+        let due = (session.updated + Duration::days(STALE_AFTER_DAYS)).date_naive().max(now.date_naive());
+        events.push(CalendarEvent {
+            uid: format!("{}-stale@dialectic", session.id),
+            date: due,
+            summary: format!("Pick up session: {}", session.title),
+            description: format!(
+                "No changes since {}. {} claims, {} open tensions.{}",
+                session.updated.format("%Y-%m-%d"),
+                session.claims.len(),
+                session.tensions.iter().filter(|t| t.resolution.is_none()).count(),
+                thesis.unwrap_or_default(),
+            ),
+            url,
+        });
+    }
+    events
+}
+
+/// Escape a TEXT value (RFC 5545 §3.3.11)
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line at 75 octets without splitting a character
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+/// Render the review calendar for `sessions`
+pub fn build_calendar(sessions: &[Session], now: DateTime<Utc>) -> String {
+    let stamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut out = String::new();
+    for line in ["BEGIN:VCALENDAR", "VERSION:2.0", "PRODID:-//Dialectic//Review Calendar//EN", "CALSCALE:GREGORIAN", "X-WR-CALNAME:Dialectic Reviews"] {
+        push_line(&mut out, line);
+    }
+    for event in sessions.iter().flat_map(|s| session_events(s, now)) {
+        let summary = escape(&event.summary);
+        let lines = [
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}", event.uid),
+            format!("DTSTAMP:{}", stamp),
+            format!("DTSTART;VALUE=DATE:{}", event.date.format("%Y%m%d")),
+            format!("DTEND;VALUE=DATE:{}", (event.date + Duration::days(1)).format("%Y%m%d")),
+            format!("SUMMARY:{}", summary),
+            format!("DESCRIPTION:{}", escape(&event.description)),
+            format!("URL:{}", event.url),
+            "BEGIN:VALARM".to_string(),
+            "ACTION:DISPLAY".to_string(),
+            format!("DESCRIPTION:{}", summary),
+            format!("TRIGGER:{}", ALARM_OFFSET),
+            "END:VALARM".to_string(),
+            "END:VEVENT".to_string(),
+        ];
+        for line in &lines {
+            push_line(&mut out, line);
+        }
+    }
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+/// Write the calendar for every session under `app_data`
+pub fn write_calendar_in(app_data: &Path) -> Result<PathBuf, SessionError> {
+    let sessions = list_sessions_from_dir(&app_data.join("sessions"))?;
+    let path = app_data.join(CALENDAR_FILE);
+    let tmp = path.with_extension("ics.tmp");
+    std::fs::write(&tmp, build_calendar(&sessions, Utc::now()))?;
+    std::fs::rename(&tmp, &path)?;
+    Ok(path)
+}
+
+/// Regenerate an exported calendar in the background when a session change
+/// moved, added or removed one of its events. No-op until the calendar has
+/// been exported once.
+pub fn refresh_if_changed(before: &Session, after: &Session) {
+    let now = Utc::now();
+    if session_events(before, now) == session_events(after, now) {
+        return;
+    }
+    let Ok(app_data) = get_app_data_dir_cli() else { return };
+    if !app_data.join(CALENDAR_FILE).exists() {
+        return;
+    }
+    jobs::submit_unique(JobKind::CalendarExport, "reviews.ics", |_job| async move {
+        match write_calendar_in(&app_data) {
+            Ok(path) => debug!(path = %path.display(), "Regenerated review calendar"),
+            Err(e) => warn!(error = %e, "Failed to regenerate review calendar"),
+        }
+        Ok(None)
+    });
+}
+
+// ============ TAURI COMMANDS ============
+
+/// Export review dates and stale-session reminders; returns the file path
+#[tauri::command]
+pub fn export_review_calendar() -> Result<String, SessionError> {
+    let path = write_calendar_in(&get_app_data_dir_cli()?)?;
+    info!(path = %path.display(), "Exported review calendar");
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use serde_json::json;
+
+    #[test]
+    fn test_build_calendar() {
+        let now: DateTime<Utc> = "2026-03-01T12:00:00Z".parse().unwrap();
+        let session: Session = test_session(json!({
+            "id": "cal", "title": "Rates, cuts; and moats",
+            "created": now, "updated": now - Duration::days(30),
+            "reviewTriggers": [
+                { "id": "t1", "description": "Revisit after the March FOMC meeting, which may change the path of policy rates materially", "reviewBy": "2026-03-20T00:00:00Z", "createdAt": now },
+                { "id": "t2", "description": "Keyword only", "keywords": ["fomc"], "createdAt": now },
+            ],
+        }));
+
+        let events = session_events(&session, now);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].date, NaiveDate::from_ymd_opt(2026, 3, 20).unwrap());
+        // Overdue reminders land on today rather than in the past
+        assert_eq!(events[1].uid, "cal-stale@dialectic");
+        assert_eq!(events[1].date, now.date_naive());
+
+        let ics = build_calendar(std::slice::from_ref(&session), now);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20260320\r\n"));
+        assert!(ics.contains("SUMMARY:Review thesis: Rates\\, cuts\\; and moats\r\n"));
+        assert!(ics.lines().all(|l| l.len() <= MAX_LINE_OCTETS));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+
+        // A formed session keeps its review dates but gets no stale reminder
+        let mut formed = session;
+        formed.status = SessionStatus::Formed;
+        assert_eq!(session_events(&formed, now).len(), 1);
+    }
+}
@@ -0,0 +1,253 @@
+//! Thesis Calibration
+//!
+//! When a session reaches Formed, the thesis confidence is recorded. Once
+//! the user knows how things turned out they record an outcome (correct,
+//! incorrect or partial), and the calibration report compares stated
+//! confidence with what happened: a Brier score across sessions plus a
+//! per-decile breakdown, so "80% sure" can be checked against reality.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::{debug, info};
+
+use super::lock::update_session_file;
+use super::{get_app_data_path, get_session_json_path, list_sessions_from_dir, Session, SessionError, SessionStatus};
+
+/// Confidence buckets in the report (deciles)
+const BUCKETS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThesisOutcome {
+    Correct,
+    Incorrect,
+    Partial,
+}
+
+impl ThesisOutcome {
+    /// Realized probability the thesis held
+    pub fn value(self) -> f32 {
+        match self {
+            ThesisOutcome::Correct => 1.0,
+            ThesisOutcome::Incorrect => 0.0,
+            ThesisOutcome::Partial => 0.5,
+        }
+    }
+}
+
+/// Confidence stated when the thesis was formed, and how it turned out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThesisCalibration {
+    pub confidence: f32,
+    pub formed_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<ThesisOutcome>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outcome_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outcome_note: Option<String>,
+}
+
+/// Squared error between stated confidence and outcome (0 = perfect)
+pub fn brier(confidence: f32, outcome: ThesisOutcome) -> f32 {
+    (confidence - outcome.value()).powi(2)
+}
+
+/// Record the thesis confidence of a session that just became Formed.
+/// A thesis re-formed before its outcome is known replaces the earlier
+/// record; once an outcome exists the record is kept as is.
+pub fn record_formed(session: &mut Session) {
+    if session.status != SessionStatus::Formed {
+        return;
+    }
+    let Some(thesis) = &session.thesis else { return };
+    if session.calibration.as_ref().is_some_and(|c| c.outcome.is_some()) {
+        return;
+    }
+    session.calibration = Some(ThesisCalibration {
+        confidence: thesis.confidence.clamp(0.0, 1.0),
+        formed_at: Utc::now(),
+        outcome: None,
+        outcome_at: None,
+        outcome_note: None,
+    });
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationEntry {
+    pub session_id: String,
+    pub title: String,
+    pub confidence: f32,
+    pub outcome: ThesisOutcome,
+    pub brier: f32,
+}
+
+/// Resolved theses whose confidence fell in `[lower, upper)`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationBucket {
+    pub lower: f32,
+    pub upper: f32,
+    pub count: usize,
+    pub mean_confidence: f32,
+    /// Mean outcome value; matches `mean_confidence` when well calibrated
+    pub observed_rate: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationReport {
+    /// Mean Brier score over resolved theses; `None` until one is resolved
+    pub brier_score: Option<f32>,
+    pub resolved: usize,
+    /// Formed theses still waiting for an outcome
+    pub pending: usize,
+    pub buckets: Vec<CalibrationBucket>,
+    pub entries: Vec<CalibrationEntry>,
+}
+
+pub fn build_report(sessions: &[Session]) -> CalibrationReport {
+    let mut entries = Vec::new();
+    let mut pending = 0;
+    for session in sessions {
+        let Some(calibration) = &session.calibration else { continue };
+        match calibration.outcome {
+            Some(outcome) => entries.push(CalibrationEntry {
+                session_id: session.id.clone(),
+                title: session.title.clone(),
+                confidence: calibration.confidence,
+                outcome,
+                brier: brier(calibration.confidence, outcome),
+            }),
+            None => pending += 1,
+        }
+    }
+
+    let mut sums = vec![(0usize, 0.0f32, 0.0f32); BUCKETS];
+    for entry in &entries {
+        // Nudge so 0.8 lands in [0.8, 0.9) despite float error; 1.0 joins the top bucket
+        let index = ((entry.confidence * BUCKETS as f32 + 1e-4) as usize).min(BUCKETS - 1);
+        let (count, confidence, observed) = &mut sums[index];
+        *count += 1;
+        *confidence += entry.confidence;
+        *observed += entry.outcome.value();
+    }
+    let buckets = sums
+        .into_iter()
+        .enumerate()
+        .filter(|(_, (count, _, _))| *count > 0)
+        .map(|(i, (count, confidence, observed))| CalibrationBucket {
+            lower: i as f32 / BUCKETS as f32,
+            upper: (i + 1) as f32 / BUCKETS as f32,
+            count,
+            mean_confidence: confidence / count as f32,
+            observed_rate: observed / count as f32,
+        })
+        .collect();
+
+    let brier_score = (!entries.is_empty())
+        .then(|| entries.iter().map(|e| e.brier).sum::<f32>() / entries.len() as f32);
+    CalibrationReport {
+        brier_score,
+        resolved: entries.len(),
+        pending,
+        buckets,
+        entries,
+    }
+}
+
+// ============ TAURI COMMANDS ============
+
+/// Record how a formed thesis turned out
+#[tauri::command]
+pub fn record_thesis_outcome(
+    app: AppHandle,
+    session_id: String,
+    outcome: ThesisOutcome,
+    note: Option<String>,
+) -> Result<Session, SessionError> {
+    let session_path = get_session_json_path(&app, &session_id)?;
+    if !session_path.exists() {
+        return Err(SessionError::NotFound(session_id));
+    }
+    let session = update_session_file(&session_path, |session| {
+        // Sessions formed before calibration tracking: use the current thesis
+        if session.calibration.is_none() {
+            record_formed(session);
+        }
+        let calibration = session
+            .calibration
+            .as_mut()
+            .ok_or_else(|| SessionError::NoThesis(session.id.clone()))?;
+        calibration.outcome = Some(outcome);
+        calibration.outcome_at = Some(Utc::now());
+        calibration.outcome_note = note;
+        Ok(())
+    })?;
+    info!(session_id = %session_id, outcome = ?outcome, "Recorded thesis outcome");
+    Ok(session)
+}
+
+#[tauri::command]
+pub fn get_calibration_report(app: AppHandle) -> Result<CalibrationReport, SessionError> {
+    let sessions = list_sessions_from_dir(&get_app_data_path(&app)?.join("sessions"))?;
+    let report = build_report(&sessions);
+    debug!(resolved = report.resolved, pending = report.pending, "Built calibration report");
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+
+    fn formed(id: &str, confidence: f32, outcome: Option<ThesisOutcome>) -> Session {
+        let mut session: Session = test_session(serde_json::json!({
+            "id": id,
+            "title": format!("Session {}", id),
+            "status": "formed",
+            "created": Utc::now(),
+            "updated": Utc::now(),
+            "thesis": { "content": "Thesis", "confidence": confidence, "updatedAt": Utc::now() },
+        }));
+        record_formed(&mut session);
+        session.calibration.as_mut().unwrap().outcome = outcome;
+        session
+    }
+
+    #[test]
+    fn test_brier_and_buckets() {
+        let sessions = vec![
+            formed("a", 0.8, Some(ThesisOutcome::Correct)),
+            formed("b", 0.85, Some(ThesisOutcome::Incorrect)),
+            formed("c", 1.0, Some(ThesisOutcome::Partial)),
+            formed("d", 0.6, None),
+        ];
+        let report = build_report(&sessions);
+        assert_eq!(report.resolved, 3);
+        assert_eq!(report.pending, 1);
+
+        let expected = (0.04 + 0.7225 + 0.25) / 3.0;
+        assert!((report.brier_score.unwrap() - expected).abs() < 1e-5);
+
+        assert_eq!(report.buckets.len(), 2);
+        let eighties = &report.buckets[0];
+        assert_eq!(eighties.count, 2);
+        assert!((eighties.lower - 0.8).abs() < 1e-6);
+        assert!((eighties.observed_rate - 0.5).abs() < 1e-6);
+        assert_eq!(report.buckets[1].count, 1);
+
+        assert!(build_report(&[]).brier_score.is_none());
+    }
+
+    #[test]
+    fn test_record_formed_keeps_resolved_record() {
+        let mut session = formed("e", 0.7, Some(ThesisOutcome::Correct));
+        session.thesis.as_mut().unwrap().confidence = 0.3;
+        record_formed(&mut session);
+        assert!((session.calibration.unwrap().confidence - 0.7).abs() < 1e-6);
+    }
+}
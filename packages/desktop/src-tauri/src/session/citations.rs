@@ -0,0 +1,353 @@
+//! Claim Citations
+//!
+//! Resolves where each claim came from into a numbered bibliography for
+//! thesis exports. Sources come from stored provenance: a claim's
+//! `SourceSpan` (reference document, vault note or file) and, for claims
+//! drawn from web research, a `source_id` that is a web_sources record or
+//! a URL. Reference documents use their citation metadata when imported
+//! from a bibliography. Transcript turns aren't publishable and are left out.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::debug;
+
+use super::claim_source::SourceSpan;
+use super::{load_session_cli, Session, SessionError, SessionReferenceDoc};
+use crate::chroma::client::get_client;
+use crate::chroma::store::VectorStore;
+use crate::chroma::collections::COLLECTION_WEB_SOURCES;
+use crate::config::workspace::effective_preferences;
+use crate::deep_link::obsidian_link;
+use crate::documents::bibtex::Citation;
+
+/// Marker of web_sources record IDs (`{session}::web::{hash}::chunk_{n}`)
+const WEB_SOURCE_ID_MARKER: &str = "::web::";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CitationKind {
+    Document,
+    WebSource,
+    Note,
+    File,
+}
+
+/// One bibliography entry and the claims that cite it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CitationEntry {
+    /// 1-based, in order of first citation
+    pub number: u32,
+    pub kind: CitationKind,
+    /// Formatted reference (Markdown)
+    pub reference: String,
+    pub url: Option<String>,
+    pub claim_ids: Vec<String>,
+}
+
+/// Title and URL of a web_sources record
+#[derive(Debug, Clone, Default)]
+pub struct WebSourceRef {
+    pub url: Option<String>,
+    pub title: Option<String>,
+}
+
+/// Where one claim came from. `key` is stable across sessions:
+/// `doc:{id}`, `note:{path}`, `file:{path}` or `web:{url}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimSource {
+    pub claim_id: String,
+    pub key: String,
+    pub kind: CitationKind,
+    pub reference: String,
+    pub url: Option<String>,
+}
+
+/// A session's bibliography, structured and rendered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormattedCitations {
+    pub session_id: String,
+    pub entries: Vec<CitationEntry>,
+    pub markdown: String,
+}
+
+fn family_name(author: &str) -> &str {
+    match author.split_once(',') {
+        Some((family, _)) => family.trim(),
+        None => author.split_whitespace().last().unwrap_or(author),
+    }
+}
+
+/// "Porter", "Porter & Smith" or "Porter et al."
+fn author_list(authors: &[String]) -> Option<String> {
+    match authors {
+        [] => None,
+        [one] => Some(family_name(one).to_string()),
+        [a, b] => Some(format!("{} & {}", family_name(a), family_name(b))),
+        [first, ..] => Some(format!("{} et al.", family_name(first))),
+    }
+}
+
+/// `Authors (Year). Title. *Container*. <https://doi.org/...>`
+pub fn format_reference(citation: &Citation) -> String {
+    let mut parts = Vec::new();
+    match (author_list(&citation.authors), &citation.year) {
+        (Some(authors), Some(year)) => parts.push(format!("{} ({})", authors, year)),
+        (Some(authors), None) => parts.push(authors),
+        (None, Some(year)) => parts.push(format!("({})", year)),
+        (None, None) => {}
+    }
+    parts.push(citation.title.clone().unwrap_or_else(|| citation.key.clone()));
+    if let Some(container) = &citation.container {
+        parts.push(format!("*{}*", container));
+    }
+    if let Some(doi) = &citation.doi {
+        parts.push(format!("<https://doi.org/{}>", doi));
+    } else if let Some(url) = &citation.url {
+        parts.push(format!("<{}>", url));
+    }
+    parts.join(". ")
+}
+
+fn document_reference(doc_id: &str, docs: &[SessionReferenceDoc]) -> (String, Option<String>) {
+    let Some(doc) = docs.iter().find(|d| d.id == doc_id) else {
+        return (format!("Reference document {}", doc_id), None);
+    };
+    match (&doc.citation, &doc.url) {
+        (Some(citation), _) => {
+            let url = citation.doi.as_ref().map(|doi| format!("https://doi.org/{}", doi)).or(citation.url.clone());
+            (format_reference(citation), url)
+        }
+        (None, Some(url)) => (format!("[{}]({})", doc.filename, url), Some(url.clone())),
+        (None, None) => (doc.filename.clone(), None),
+    }
+}
+
+fn is_url(value: &str) -> bool {
+    value.starts_with("http://") || value.starts_with("https://")
+}
+
+/// Source of a claim as (dedup key, kind, reference, url)
+fn claim_source_ref(
+    session: &Session,
+    claim: &super::Claim,
+    web_sources: &HashMap<String, WebSourceRef>,
+    vault: Option<&Path>,
+) -> Option<(String, CitationKind, String, Option<String>)> {
+    match &claim.source_span {
+        Some(SourceSpan::Document { doc_id, .. }) => {
+            let (reference, url) = document_reference(doc_id, &session.reference_docs);
+            return Some((format!("doc:{}", doc_id), CitationKind::Document, reference, url));
+        }
+        Some(SourceSpan::Note { path, .. }) => {
+            let link = vault.and_then(|vault| obsidian_link(vault, path));
+            let reference = match &link {
+                Some(link) => format!("[{}]({})", path, link),
+                None => path.clone(),
+            };
+            return Some((format!("note:{}", path), CitationKind::Note, reference, link));
+        }
+        Some(SourceSpan::File { path, .. }) => {
+            return Some((format!("file:{}", path), CitationKind::File, format!("`{}`", path), None));
+        }
+        Some(SourceSpan::Transcript { .. }) | None => {}
+    }
+
+    let source_id = claim.source_id.as_str();
+    if is_url(source_id) {
+        return Some((format!("web:{}", source_id), CitationKind::WebSource, format!("<{}>", source_id), Some(source_id.to_string())));
+    }
+    if !source_id.contains(WEB_SOURCE_ID_MARKER) {
+        return None;
+    }
+    let web = web_sources.get(source_id).cloned().unwrap_or_default();
+    let key = format!("web:{}", web.url.as_deref().unwrap_or(source_id));
+    let reference = match (&web.title, &web.url) {
+        (Some(title), Some(url)) => format!("[{}]({})", title, url),
+        (None, Some(url)) => format!("<{}>", url),
+        (Some(title), None) => title.clone(),
+        (None, None) => format!("Web source {}", source_id),
+    };
+    Some((key, CitationKind::WebSource, reference, web.url))
+}
+
+/// Bibliography of a session's claims, numbered by first citation
+pub fn resolve_citations(
+    session: &Session,
+    web_sources: &HashMap<String, WebSourceRef>,
+    vault: Option<&Path>,
+) -> Vec<CitationEntry> {
+    let mut entries: Vec<CitationEntry> = Vec::new();
+    let mut by_key: HashMap<String, usize> = HashMap::new();
+    for claim in &session.claims {
+        let Some((key, kind, reference, url)) = claim_source_ref(session, claim, web_sources, vault) else {
+            continue;
+        };
+        let index = *by_key.entry(key).or_insert_with(|| {
+            entries.push(CitationEntry {
+                number: entries.len() as u32 + 1,
+                kind,
+                reference,
+                url,
+                claim_ids: Vec::new(),
+            });
+            entries.len() - 1
+        });
+        entries[index].claim_ids.push(claim.id.clone());
+    }
+    entries
+}
+
+/// `## Bibliography` section, or an empty string with no entries
+pub fn render_bibliography(entries: &[CitationEntry]) -> String {
+    if entries.is_empty() {
+        return String::new();
+    }
+    let mut md = String::from("## Bibliography\n\n");
+    for entry in entries {
+        md.push_str(&format!("{}. {}\n", entry.number, entry.reference));
+    }
+    md
+}
+
+/// Titles and URLs of the web_sources records claims cite (best-effort)
+async fn lookup_web_sources(session: &Session) -> HashMap<String, WebSourceRef> {
+    let mut ids: Vec<String> = session
+        .claims
+        .iter()
+        .map(|c| c.source_id.clone())
+        .filter(|id| id.contains(WEB_SOURCE_ID_MARKER))
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    if ids.is_empty() {
+        return HashMap::new();
+    }
+
+    let client = get_client();
+    let result = match client.get_collection(COLLECTION_WEB_SOURCES).await {
+        Ok(collection) => {
+            client
+                .get(&collection.id, Some(ids), None, None, None, None, Some(vec!["metadatas".to_string()]))
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
+            debug!(session_id = %session.id, error = %e, "Web sources unavailable for citations");
+            return HashMap::new();
+        }
+    };
+    let metadatas = result.metadatas.unwrap_or_default();
+    result
+        .ids
+        .into_iter()
+        .zip(metadatas)
+        .map(|(id, meta)| {
+            let field = |key: &str| {
+                meta.as_ref().and_then(|m| m.get(key)).and_then(|v| v.as_str()).map(str::to_string)
+            };
+            (id, WebSourceRef { url: field("url"), title: field("title") })
+        })
+        .collect()
+}
+
+/// Bibliography with web source titles and Obsidian links resolved
+pub async fn collect_citations(session: &Session, vault: Option<&Path>) -> Vec<CitationEntry> {
+    let web_sources = lookup_web_sources(session).await;
+    resolve_citations(session, &web_sources, vault)
+}
+
+/// Source of each cited claim, with web source titles resolved
+pub async fn claim_sources(session: &Session, vault: Option<&Path>) -> Vec<ClaimSource> {
+    let web_sources = lookup_web_sources(session).await;
+    session
+        .claims
+        .iter()
+        .filter_map(|claim| {
+            let (key, kind, reference, url) = claim_source_ref(session, claim, &web_sources, vault)?;
+            Some(ClaimSource { claim_id: claim.id.clone(), key, kind, reference, url })
+        })
+        .collect()
+}
+
+/// Bibliography of a session, using its effective vault path
+pub async fn citations_for_session(session_id: &str) -> Result<FormattedCitations, SessionError> {
+    let session = load_session_cli(session_id)?;
+    let prefs = effective_preferences(Path::new(&session.working_dir)).0;
+    let entries = collect_citations(&session, prefs.vault_path.as_deref().map(Path::new)).await;
+    Ok(FormattedCitations {
+        session_id: session.id,
+        markdown: render_bibliography(&entries),
+        entries,
+    })
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub async fn format_citations(session_id: String) -> Result<FormattedCitations, SessionError> {
+    citations_for_session(&session_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use serde_json::json;
+
+    #[test]
+    fn test_resolve_citations() {
+        let now = chrono::Utc::now();
+        let session: Session = test_session(json!({
+            "id": "cite-test",
+            "title": "Moats",
+            "status": "formed",
+            "created": now,
+            "updated": now,
+            "referenceDocs": [
+                { "id": "d1", "filename": "porter.md", "path": "/x/porter.md", "tokenCount": 10, "handling": "full", "persistence": "cached",
+                  "citation": { "key": "porter1979", "entryType": "article", "title": "Competitive Forces",
+                                "authors": ["Porter, Michael", "Smith, A", "Lee, B"], "year": "1979", "doi": "10.1/hbr", "container": "HBR" } },
+                { "id": "d2", "filename": "page.md", "path": "/x/page.md", "tokenCount": 10, "handling": "full", "persistence": "cached",
+                  "url": "https://example.com/page" },
+            ],
+            "claims": [
+                { "id": "c1", "content": "a", "sourceId": "s", "marker": null, "createdAt": now,
+                  "sourceSpan": { "kind": "document", "docId": "d1", "chunkIndex": 0 } },
+                { "id": "c2", "content": "b", "sourceId": "s1::web::abc::chunk_0", "marker": null, "createdAt": now },
+                { "id": "c3", "content": "c", "sourceId": "s", "marker": null, "createdAt": now,
+                  "sourceSpan": { "kind": "document", "docId": "d1", "chunkIndex": 3 } },
+                { "id": "c4", "content": "d", "sourceId": "s", "marker": null, "createdAt": now,
+                  "sourceSpan": { "kind": "note", "path": "Markets/Pricing.md" } },
+                { "id": "c5", "content": "e", "sourceId": "s", "marker": null, "createdAt": now,
+                  "sourceSpan": { "kind": "transcript", "turn": 2 } },
+                { "id": "c6", "content": "f", "sourceId": "s", "marker": null, "createdAt": now,
+                  "sourceSpan": { "kind": "document", "docId": "d2", "chunkIndex": 0 } },
+            ],
+        }));
+        let web = HashMap::from([(
+            "s1::web::abc::chunk_0".to_string(),
+            WebSourceRef { url: Some("https://news.example/rates".into()), title: Some("Rates".into()) },
+        )]);
+
+        let entries = resolve_citations(&session, &web, Some(Path::new("/vaults/Notes")));
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].reference, "Porter et al. (1979). Competitive Forces. *HBR*. <https://doi.org/10.1/hbr>");
+        assert_eq!(entries[0].claim_ids, vec!["c1", "c3"]);
+        assert_eq!(entries[1].kind, CitationKind::WebSource);
+        assert_eq!(entries[1].reference, "[Rates](https://news.example/rates)");
+        assert_eq!(entries[2].reference, "[Markets/Pricing.md](obsidian://open?vault=Notes&file=Markets%2FPricing.md)");
+        assert_eq!(entries[3].reference, "[page.md](https://example.com/page)");
+
+        let md = render_bibliography(&entries);
+        assert!(md.starts_with("## Bibliography\n\n1. Porter et al. (1979)"));
+        assert!(md.contains("\n4. [page.md]"));
+        assert!(render_bibliography(&[]).is_empty());
+    }
+}
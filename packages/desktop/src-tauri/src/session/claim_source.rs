@@ -0,0 +1,301 @@
+//! Claim Provenance
+//!
+//! A claim can carry a `SourceSpan` pointing at the exact text it was drawn
+//! from: a reference document chunk (optionally a char range within it), a
+//! vault note (optionally one heading's section), a transcript turn, or a
+//! line range in a file. `get_claim_source` reads that text back so the
+//! evidence behind a claim can be re-verified against the original.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use thiserror::Error;
+use tracing::debug;
+
+use super::{ConversationRef, Session, SessionError};
+
+#[derive(Error, Debug)]
+pub enum ClaimSourceError {
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error("Claim not found: {0}")]
+    ClaimNotFound(String),
+    #[error("Claim has no source span: {0}")]
+    NoSpan(String),
+    #[error("Source unavailable: {0}")]
+    Unavailable(String),
+}
+
+impl Serialize for ClaimSourceError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Where in its source a claim was found
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", rename_all_fields = "camelCase")]
+pub enum SourceSpan {
+    /// A chunk of a session reference document; the char range, if given,
+    /// is relative to the chunk
+    Document {
+        doc_id: String,
+        chunk_index: u32,
+        #[serde(default)]
+        char_start: Option<u32>,
+        #[serde(default)]
+        char_end: Option<u32>,
+    },
+    /// A vault note (path relative to the vault), optionally one section
+    Note {
+        path: String,
+        #[serde(default)]
+        heading: Option<String>,
+    },
+    /// A user or assistant message in the Claude Code transcript, counted
+    /// from 0. Defaults to the session's own conversation.
+    Transcript {
+        #[serde(default)]
+        conversation_id: Option<String>,
+        turn: u32,
+    },
+    /// 1-based inclusive line range in a file; relative paths resolve
+    /// against the session working directory
+    File {
+        path: String,
+        start_line: u32,
+        #[serde(default)]
+        end_line: Option<u32>,
+    },
+}
+
+/// A claim together with the original text it cites
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimSource {
+    pub claim_id: String,
+    pub claim: String,
+    pub span: SourceSpan,
+    pub text: String,
+}
+
+/// Slice `text` by char offsets, clamped to its length
+fn char_range(text: &str, start: Option<u32>, end: Option<u32>) -> String {
+    let start = start.unwrap_or(0) as usize;
+    let len = end.map(|e| (e as usize).saturating_sub(start)).unwrap_or(usize::MAX);
+    text.chars().skip(start).take(len).collect()
+}
+
+/// Markdown heading level and text, if `line` is a heading
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    line[level..].strip_prefix(' ').map(|rest| (level, rest.trim()))
+}
+
+/// The section under `heading` (matched case-insensitively, heading line
+/// included), up to the next heading of the same or a higher level
+fn heading_section(markdown: &str, heading: &str) -> Option<String> {
+    let wanted = heading.trim().trim_start_matches('#').trim();
+    let mut lines = markdown.lines();
+    let level = lines.by_ref().find_map(|line| {
+        parse_heading(line)
+            .filter(|(_, text)| text.eq_ignore_ascii_case(wanted))
+            .map(|(level, _)| (level, line))
+    });
+    let (level, first) = level?;
+
+    let mut section = vec![first];
+    section.extend(lines.take_while(|line| parse_heading(line).is_none_or(|(l, _)| l > level)));
+    Some(section.join("\n").trim_end().to_string())
+}
+
+/// 1-based inclusive line range of `text`
+fn line_range(text: &str, start_line: u32, end_line: Option<u32>) -> Option<String> {
+    let start = start_line.max(1) as usize;
+    let end = end_line.map(|e| e as usize).unwrap_or(start).max(start);
+    let lines: Vec<&str> = text.lines().skip(start - 1).take(end - start + 1).collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Text of a JSONL message: a plain string, or its text blocks joined
+pub(crate) fn message_text(entry: &serde_json::Value) -> Option<String> {
+    let content = entry.get("message")?.get("content")?;
+    if let Some(text) = content.as_str() {
+        return Some(text.to_string());
+    }
+    let parts: Vec<&str> = content
+        .as_array()?
+        .iter()
+        .filter(|block| block.get("type").and_then(|t| t.as_str()) == Some("text"))
+        .filter_map(|block| block.get("text").and_then(|t| t.as_str()))
+        .collect();
+    Some(parts.join("\n"))
+}
+
+/// (role, text) of each user/assistant message of a transcript, in order;
+/// the index is the message's `turn`
+pub fn transcript_messages(jsonl: &str) -> Vec<(String, String)> {
+    jsonl
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter_map(|entry| {
+            let role = entry.get("type").and_then(|t| t.as_str())?;
+            matches!(role, "user" | "assistant")
+                .then(|| (role.to_string(), message_text(&entry).unwrap_or_default()))
+        })
+        .collect()
+}
+
+/// The `turn`th user/assistant message of a transcript
+fn transcript_turn(jsonl: &str, turn: u32) -> Option<String> {
+    transcript_messages(jsonl).into_iter().nth(turn as usize).map(|(_, text)| text)
+}
+
+fn read_document_chunk(session: &Session, doc_id: &str, chunk_index: u32) -> Result<String, ClaimSourceError> {
+    use crate::documents::{chunker, retriever};
+
+    if let Ok(chunk) = retriever::get_chunk(&session.id, doc_id, chunk_index) {
+        return Ok(chunk.content);
+    }
+    // Not loaded in memory: re-chunk the original file (chunking is deterministic)
+    let doc = session
+        .reference_docs
+        .iter()
+        .find(|d| d.id == doc_id)
+        .ok_or_else(|| ClaimSourceError::Unavailable(format!("reference document {}", doc_id)))?;
+    let chunked = chunker::chunk_document(Path::new(&doc.path), doc_id)
+        .map_err(|e| ClaimSourceError::Unavailable(format!("{}: {}", doc.filename, e)))?;
+    chunked
+        .chunks
+        .into_iter()
+        .nth(chunk_index as usize)
+        .map(|c| c.content)
+        .ok_or_else(|| ClaimSourceError::Unavailable(format!("chunk {} of {}", chunk_index, doc.filename)))
+}
+
+/// Read the original text a span points at
+pub fn resolve_span(session: &Session, span: &SourceSpan) -> Result<String, ClaimSourceError> {
+    match span {
+        SourceSpan::Document { doc_id, chunk_index, char_start, char_end } => {
+            let chunk = read_document_chunk(session, doc_id, *chunk_index)?;
+            Ok(char_range(&chunk, *char_start, *char_end))
+        }
+        SourceSpan::Note { path, heading } => {
+            let note = crate::obsidian::query::get_note_content(path, u32::MAX)
+                .map_err(|e| ClaimSourceError::Unavailable(e.to_string()))?;
+            match heading {
+                Some(heading) => heading_section(&note.content, heading)
+                    .ok_or_else(|| ClaimSourceError::Unavailable(format!("heading '{}' in {}", heading, path))),
+                None => Ok(note.content),
+            }
+        }
+        SourceSpan::Transcript { conversation_id, turn } => {
+            let conversation_id = conversation_id
+                .as_deref()
+                .or(session.latest_conversation_id())
+                .ok_or_else(|| ClaimSourceError::Unavailable("session has no conversation".to_string()))?;
+            let conversation = session
+                .conversations()
+                .into_iter()
+                .find(|c| c.id == conversation_id)
+                .unwrap_or_else(|| ConversationRef { id: conversation_id.to_string(), started_at: session.created, jsonl_path: None });
+            let jsonl_path = crate::chroma::jsonl_miner::locate_transcript(&conversation, &session.working_dir)
+                .ok_or_else(|| ClaimSourceError::Unavailable(format!("transcript {}", conversation_id)))?;
+            let jsonl = fs::read_to_string(&jsonl_path).map_err(SessionError::from)?;
+            transcript_turn(&jsonl, *turn)
+                .ok_or_else(|| ClaimSourceError::Unavailable(format!("turn {} of transcript {}", turn, conversation_id)))
+        }
+        SourceSpan::File { path, start_line, end_line } => {
+            let file = PathBuf::from(path);
+            let file = if file.is_absolute() { file } else { Path::new(&session.working_dir).join(file) };
+            let text = fs::read_to_string(&file)
+                .map_err(|e| ClaimSourceError::Unavailable(format!("{}: {}", file.display(), e)))?;
+            line_range(&text, *start_line, *end_line)
+                .ok_or_else(|| ClaimSourceError::Unavailable(format!("line {} of {}", start_line, path)))
+        }
+    }
+}
+
+/// Look up a claim and read back its cited source text
+pub fn claim_source(session: &Session, claim_id: &str) -> Result<ClaimSource, ClaimSourceError> {
+    let claim = session
+        .claims
+        .iter()
+        .find(|c| c.id == claim_id)
+        .ok_or_else(|| ClaimSourceError::ClaimNotFound(claim_id.to_string()))?;
+    let span = claim
+        .source_span
+        .clone()
+        .ok_or_else(|| ClaimSourceError::NoSpan(claim_id.to_string()))?;
+    let text = resolve_span(session, &span)?;
+    debug!(session_id = %session.id, claim_id = %claim_id, chars = text.len(), "Resolved claim source");
+    Ok(ClaimSource {
+        claim_id: claim.id.clone(),
+        claim: claim.content.clone(),
+        span,
+        text,
+    })
+}
+
+// ============ TAURI COMMANDS ============
+
+/// Original text behind a claim, for re-verifying its evidence
+#[tauri::command]
+pub fn get_claim_source(app: AppHandle, session_id: String, claim_id: String) -> Result<ClaimSource, ClaimSourceError> {
+    let session = super::load_session(app, session_id)?;
+    claim_source(&session, &claim_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_serde_shape() {
+        let span: SourceSpan = serde_json::from_str(
+            r#"{"kind":"document","docId":"doc1","chunkIndex":2,"charStart":5}"#,
+        ).unwrap();
+        assert_eq!(span, SourceSpan::Document {
+            doc_id: "doc1".to_string(),
+            chunk_index: 2,
+            char_start: Some(5),
+            char_end: None,
+        });
+        let json = serde_json::to_value(SourceSpan::Transcript { conversation_id: None, turn: 3 }).unwrap();
+        assert_eq!(json["kind"], "transcript");
+        assert_eq!(json["turn"], 3);
+    }
+
+    #[test]
+    fn test_heading_section_stops_at_sibling() {
+        let md = "# Title\nintro\n## Pricing\nline a\n### Detail\nline b\n## Risks\nline c";
+        assert_eq!(heading_section(md, "pricing").unwrap(), "## Pricing\nline a\n### Detail\nline b");
+        assert_eq!(heading_section(md, "## Risks").unwrap(), "## Risks\nline c");
+        assert!(heading_section(md, "Missing").is_none());
+    }
+
+    #[test]
+    fn test_ranges_and_transcript_turns() {
+        assert_eq!(char_range("héllo world", Some(1), Some(5)), "éllo");
+        assert_eq!(char_range("short", Some(2), None), "ort");
+        assert_eq!(line_range("a\nb\nc\nd", 2, Some(3)).unwrap(), "b\nc");
+        assert_eq!(line_range("a\nb", 2, None).unwrap(), "b");
+        assert!(line_range("a", 5, None).is_none());
+
+        let jsonl = [
+            r#"{"type":"summary","summary":"x"}"#,
+            r#"{"type":"user","message":{"role":"user","content":"What about pricing?"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Margins are thin."},{"type":"tool_use","name":"Read"}]}}"#,
+        ].join("\n");
+        assert_eq!(transcript_turn(&jsonl, 0).unwrap(), "What about pricing?");
+        assert_eq!(transcript_turn(&jsonl, 1).unwrap(), "Margins are thin.");
+        assert!(transcript_turn(&jsonl, 2).is_none());
+    }
+}
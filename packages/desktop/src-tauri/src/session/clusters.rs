@@ -0,0 +1,311 @@
+//! Session Clusters
+//!
+//! Groups sessions by topic for a portfolio overview. Each session is
+//! embedded from the content words of its title, summary and thesis, the
+//! embeddings are clustered with spherical k-means (k picked by silhouette
+//! unless given), and each cluster is named after the terms its members
+//! share more than the rest of the portfolio does. Pairs of sessions that
+//! are nearly identical are reported as possible duplicate lines of inquiry.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use tracing::info;
+
+use super::{list_sessions_cli, Session, SessionError};
+use crate::documents::embeddings::{dot, generate_embedding, Embedding, EMBEDDING_DIM};
+use crate::knowledge::content_words;
+
+/// Largest k tried when picking it automatically
+const MAX_AUTO_K: usize = 12;
+const MAX_ITERATIONS: usize = 50;
+/// Cosine similarity at which two sessions are flagged as duplicates
+const DUPLICATE_THRESHOLD: f32 = 0.85;
+/// Terms in a cluster name
+const NAME_TERMS: usize = 3;
+
+/// A session in a cluster
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterMember {
+    pub session_id: String,
+    pub title: String,
+    pub status: String,
+    /// Cosine similarity to the cluster centroid
+    pub similarity: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionCluster {
+    pub name: String,
+    pub terms: Vec<String>,
+    /// Most central first
+    pub members: Vec<ClusterMember>,
+    /// Mean member similarity to the centroid
+    pub cohesion: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicatePair {
+    pub session_a: String,
+    pub session_b: String,
+    pub similarity: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionClusters {
+    /// Largest first
+    pub clusters: Vec<SessionCluster>,
+    pub duplicates: Vec<DuplicatePair>,
+}
+
+/// Session content that is embedded and named on
+struct Item<'a> {
+    session: &'a Session,
+    terms: BTreeSet<String>,
+    embedding: Embedding,
+}
+
+fn session_terms(session: &Session) -> Vec<String> {
+    let text = [Some(session.title.as_str()), session.summary.as_deref(), session.thesis.as_ref().map(|t| t.content.as_str())]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("\n");
+    content_words(&text).into_iter().flatten().collect()
+}
+
+fn normalize(mut v: Vec<f32>) -> Vec<f32> {
+    let norm = dot(&v, &v).sqrt();
+    if norm > 0.0 {
+        v.iter_mut().for_each(|x| *x /= norm);
+    }
+    v
+}
+
+fn centroid(points: &[&Embedding]) -> Embedding {
+    let mut sum = vec![0.0; EMBEDDING_DIM];
+    for point in points {
+        for (s, x) in sum.iter_mut().zip(point.iter()) {
+            *s += x;
+        }
+    }
+    normalize(sum)
+}
+
+/// Spherical k-means over unit vectors; returns each point's cluster.
+/// Seeded by farthest-first traversal from the first point, so results
+/// are deterministic.
+fn kmeans(points: &[Embedding], k: usize) -> Vec<usize> {
+    let mut centroids: Vec<Embedding> = vec![points[0].clone()];
+    while centroids.len() < k {
+        let farthest = (0..points.len())
+            .min_by(|&a, &b| {
+                let nearest = |i: usize| centroids.iter().map(|c| dot(&points[i], c)).fold(f32::MIN, f32::max);
+                nearest(a).total_cmp(&nearest(b))
+            })
+            .unwrap_or(0);
+        centroids.push(points[farthest].clone());
+    }
+
+    let mut assignment = vec![usize::MAX; points.len()];
+    for _ in 0..MAX_ITERATIONS {
+        let next: Vec<usize> = points
+            .iter()
+            .map(|p| (0..k).max_by(|&a, &b| dot(p, &centroids[a]).total_cmp(&dot(p, &centroids[b]))).unwrap_or(0))
+            .collect();
+        if next == assignment {
+            break;
+        }
+        assignment = next;
+        for (c, centroid_slot) in centroids.iter_mut().enumerate() {
+            let members: Vec<&Embedding> = points.iter().zip(&assignment).filter(|(_, a)| **a == c).map(|(p, _)| p).collect();
+            if !members.is_empty() {
+                *centroid_slot = centroid(&members);
+            }
+        }
+    }
+    assignment
+}
+
+/// Mean silhouette under cosine distance; 0 for a single cluster
+fn silhouette(points: &[Embedding], assignment: &[usize], k: usize) -> f32 {
+    if k < 2 {
+        return 0.0;
+    }
+    let scores: Vec<f32> = (0..points.len())
+        .map(|i| {
+            let mut distance = vec![(0.0f32, 0usize); k];
+            for j in (0..points.len()).filter(|&j| j != i) {
+                let entry = &mut distance[assignment[j]];
+                entry.0 += 1.0 - dot(&points[i], &points[j]);
+                entry.1 += 1;
+            }
+            let mean = |c: usize| (distance[c].1 > 0).then(|| distance[c].0 / distance[c].1 as f32);
+            let Some(a) = mean(assignment[i]) else { return 0.0 };
+            let b = (0..k).filter(|&c| c != assignment[i]).filter_map(mean).fold(f32::MAX, f32::min);
+            if b == f32::MAX {
+                return 0.0;
+            }
+            (b - a) / a.max(b).max(f32::EPSILON)
+        })
+        .collect();
+    scores.iter().sum::<f32>() / scores.len() as f32
+}
+
+/// Terms members share more than the portfolio as a whole, best first
+fn cluster_terms(members: &[&Item], all: &[Item]) -> Vec<String> {
+    let document_frequency = |items: &mut dyn Iterator<Item = &BTreeSet<String>>| {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for terms in items {
+            for term in terms {
+                *counts.entry(term.clone()).or_default() += 1;
+            }
+        }
+        counts
+    };
+    let inside = document_frequency(&mut members.iter().map(|m| &m.terms));
+    let overall = document_frequency(&mut all.iter().map(|i| &i.terms));
+    let mut scored: Vec<(f32, String)> = inside
+        .into_iter()
+        .filter(|(_, count)| members.len() == 1 || *count >= 2)
+        .map(|(term, count)| {
+            let share = count as f32 / members.len() as f32;
+            let elsewhere = overall[&term] as f32 / all.len() as f32;
+            (share - elsewhere + share * 0.01, term)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    scored.into_iter().map(|(_, term)| term).take(NAME_TERMS).collect()
+}
+
+fn title_case(term: &str) -> String {
+    let mut chars = term.chars();
+    chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+}
+
+/// Cluster sessions into `k` groups, or a k picked by silhouette
+pub fn cluster(sessions: &[Session], k: Option<usize>) -> SessionClusters {
+    let items: Vec<Item> = sessions
+        .iter()
+        .filter_map(|session| {
+            let terms = session_terms(session);
+            if terms.is_empty() {
+                return None;
+            }
+            let embedding = generate_embedding(&terms.join(" ")).ok()?;
+            Some(Item { session, terms: terms.into_iter().collect(), embedding })
+        })
+        .collect();
+    if items.is_empty() {
+        return SessionClusters { clusters: Vec::new(), duplicates: Vec::new() };
+    }
+    let points: Vec<Embedding> = items.iter().map(|i| i.embedding.clone()).collect();
+
+    let assignment = match k {
+        Some(k) => kmeans(&points, k.clamp(1, points.len())),
+        None if points.len() < 3 => vec![0; points.len()],
+        None => (2..=MAX_AUTO_K.min(points.len() - 1))
+            .map(|k| {
+                let assignment = kmeans(&points, k);
+                (silhouette(&points, &assignment, k), assignment)
+            })
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, assignment)| assignment)
+            .unwrap_or_else(|| vec![0; points.len()]),
+    };
+
+    let groups = assignment.iter().copied().max().map_or(0, |max| max + 1);
+    let mut clusters: Vec<SessionCluster> = (0..groups)
+        .filter_map(|c| {
+            let members: Vec<&Item> = items.iter().zip(&assignment).filter(|(_, a)| **a == c).map(|(i, _)| i).collect();
+            if members.is_empty() {
+                return None;
+            }
+            let center = centroid(&members.iter().map(|m| &m.embedding).collect::<Vec<_>>());
+            let mut cluster_members: Vec<ClusterMember> = members
+                .iter()
+                .map(|m| ClusterMember {
+                    session_id: m.session.id.clone(),
+                    title: m.session.title.clone(),
+                    status: format!("{:?}", m.session.status).to_lowercase(),
+                    similarity: dot(&m.embedding, &center),
+                })
+                .collect();
+            cluster_members.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+            let cohesion = cluster_members.iter().map(|m| m.similarity).sum::<f32>() / cluster_members.len() as f32;
+            let terms = cluster_terms(&members, &items);
+            let name = match terms.is_empty() {
+                true => cluster_members[0].title.clone(),
+                false => terms.iter().map(|t| title_case(t)).collect::<Vec<_>>().join(" / "),
+            };
+            Some(SessionCluster { name, terms, members: cluster_members, cohesion })
+        })
+        .collect();
+    clusters.sort_by(|a, b| b.members.len().cmp(&a.members.len()).then(b.cohesion.total_cmp(&a.cohesion)));
+
+    let mut duplicates = Vec::new();
+    for (i, a) in items.iter().enumerate() {
+        for b in &items[i + 1..] {
+            let similarity = dot(&a.embedding, &b.embedding);
+            if similarity >= DUPLICATE_THRESHOLD {
+                duplicates.push(DuplicatePair { session_a: a.session.id.clone(), session_b: b.session.id.clone(), similarity });
+            }
+        }
+    }
+    duplicates.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    SessionClusters { clusters, duplicates }
+}
+
+// ============ TAURI COMMANDS ============
+
+/// Topic clusters of all sessions; `k` fixes the number of clusters
+#[tauri::command]
+pub fn cluster_sessions(k: Option<usize>) -> Result<SessionClusters, SessionError> {
+    let mut sessions = list_sessions_cli()?;
+    sessions.sort_by(|a, b| a.created.cmp(&b.created).then_with(|| a.id.cmp(&b.id)));
+    let clusters = cluster(&sessions, k);
+    info!(sessions = sessions.len(), clusters = clusters.clusters.len(), duplicates = clusters.duplicates.len(), "Clustered sessions");
+    Ok(clusters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use serde_json::json;
+
+    fn session(id: &str, title: &str, thesis: &str) -> Session {
+        let now = chrono::Utc::now();
+        test_session(json!({
+            "id": id, "title": title,
+            "created": now, "updated": now,
+            "thesis": { "content": thesis, "confidence": 0.5, "updatedAt": now },
+        }))
+    }
+
+    #[test]
+    fn test_clusters_by_topic_and_flags_duplicates() {
+        let sessions = vec![
+            session("p1", "Pricing power", "Subscription pricing power holds through inflation"),
+            session("r1", "Rate path", "Central bank rate cuts arrive before inflation cools"),
+            session("p2", "Pricing tiers", "Tiered subscription pricing power protects margins"),
+            session("r2", "Rate cuts", "Central bank rate cuts lag labour market softening"),
+            session("p3", "Pricing power again", "Subscription pricing power holds through inflation"),
+        ];
+        let result = cluster(&sessions, None);
+        assert_eq!(result.clusters.len(), 2);
+        let ids = |c: &SessionCluster| c.members.iter().map(|m| m.session_id.clone()).collect::<BTreeSet<_>>();
+        assert_eq!(ids(&result.clusters[0]), BTreeSet::from(["p1", "p2", "p3"].map(String::from)));
+        assert_eq!(ids(&result.clusters[1]), BTreeSet::from(["r1", "r2"].map(String::from)));
+        assert!(result.clusters[0].terms.contains(&"pricing".to_string()));
+        assert_eq!(result.clusters[1].name, "Bank / Central / Cuts");
+
+        assert_eq!(result.duplicates.len(), 1);
+        assert_eq!((result.duplicates[0].session_a.as_str(), result.duplicates[0].session_b.as_str()), ("p1", "p3"));
+
+        assert_eq!(cluster(&sessions, Some(1)).clusters[0].members.len(), 5);
+    }
+}
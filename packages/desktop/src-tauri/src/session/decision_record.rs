@@ -0,0 +1,342 @@
+//! Decision Journal Export
+//!
+//! Builds a standardized decision record from a Decision-mode session:
+//! the decision (thesis), options considered ([DECISION] claims), key
+//! claims, tensions accepted, expected outcomes and the next review date.
+//! Everything comes from existing session fields; the record can be
+//! rendered as Markdown for a journal or JSON for other tools.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tracing::info;
+
+use super::annotations::annotation_lines;
+use super::calibration::ThesisOutcome;
+use super::citations::{collect_citations, render_bibliography, resolve_citations, CitationEntry};
+use super::{Claim, Session, SessionError, SessionMode};
+use crate::cdg::{compute_strata, ClaimStratum};
+use crate::config::workspace::effective_preferences;
+use crate::deep_link::session_link;
+
+/// Written to the session dir when a Decision-mode session is formed
+pub const RECORD_FILE: &str = "decision-record.md";
+/// Key claims listed when the CDG doesn't single out load-bearing ones
+const MAX_KEY_CLAIMS: usize = 10;
+/// Markers treated as key claims without a CDG
+const KEY_CLAIM_MARKERS: [&str; 3] = ["[INSIGHT]", "[EVIDENCE]", "[ASSUMPTION]"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DecisionRecordFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordClaim {
+    pub id: String,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evidence_score: Option<f32>,
+}
+
+impl RecordClaim {
+    fn from_claim(claim: &Claim) -> Self {
+        Self {
+            id: claim.id.clone(),
+            content: claim.content.clone(),
+            evidence_score: claim.evidence_score,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptedTension {
+    pub description: String,
+    pub resolution: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpectedOutcomes {
+    /// Thesis confidence, 0.0–1.0
+    pub confidence: Option<f32>,
+    /// [RISK] claims: ways the decision could go wrong
+    pub risks: Vec<String>,
+    /// Conditions under which the decision should be revisited
+    pub revision_triggers: Vec<String>,
+    /// Recorded outcome, once known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual: Option<ThesisOutcome>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecisionRecord {
+    pub session_id: String,
+    pub question: String,
+    /// The thesis; `None` while the session hasn't formed one
+    pub decision: Option<String>,
+    pub options_considered: Vec<RecordClaim>,
+    pub key_claims: Vec<RecordClaim>,
+    pub tensions_accepted: Vec<AcceptedTension>,
+    /// Tensions still open when the record was exported
+    pub open_tensions: Vec<String>,
+    /// Unresolved review annotations
+    #[serde(default)]
+    pub open_annotations: Vec<String>,
+    pub expected_outcomes: ExpectedOutcomes,
+    /// Earliest review-by date among revision triggers
+    pub review_date: Option<DateTime<Utc>>,
+    pub status: String,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub exported_at: DateTime<Utc>,
+    /// Bibliography of the sources claims were drawn from
+    #[serde(default)]
+    pub sources: Vec<CitationEntry>,
+}
+
+fn has_marker(claim: &Claim, marker: &str) -> bool {
+    claim.marker.as_deref().is_some_and(|m| m.eq_ignore_ascii_case(marker))
+}
+
+/// Load-bearing claims from the CDG, else marker-tagged claims
+fn key_claims(session: &Session) -> Vec<RecordClaim> {
+    let strata = compute_strata(&session.claims, &session.cdg_edges);
+    let load_bearing: Vec<RecordClaim> = session
+        .claims
+        .iter()
+        .filter(|c| matches!(strata.get(&c.id), Some(ClaimStratum::Core | ClaimStratum::Structural)))
+        .map(RecordClaim::from_claim)
+        .collect();
+    if !load_bearing.is_empty() {
+        return load_bearing;
+    }
+    session
+        .claims
+        .iter()
+        .filter(|c| KEY_CLAIM_MARKERS.iter().any(|m| has_marker(c, m)))
+        .take(MAX_KEY_CLAIMS)
+        .map(RecordClaim::from_claim)
+        .collect()
+}
+
+/// Build the decision record for a Decision-mode session
+pub fn build_record(session: &Session) -> Result<DecisionRecord, SessionError> {
+    if session.mode != SessionMode::Decision {
+        return Err(SessionError::NotDecisionMode(session.id.clone()));
+    }
+
+    let (tensions_accepted, open_tensions) = session.tensions.iter().fold(
+        (Vec::new(), Vec::new()),
+        |(mut accepted, mut open), t| {
+            match &t.resolution {
+                Some(resolution) => accepted.push(AcceptedTension {
+                    description: t.description.clone(),
+                    resolution: resolution.clone(),
+                }),
+                None => open.push(t.description.clone()),
+            }
+            (accepted, open)
+        },
+    );
+
+    Ok(DecisionRecord {
+        session_id: session.id.clone(),
+        question: session.title.clone(),
+        decision: session.thesis.as_ref().map(|t| t.content.clone()),
+        options_considered: session
+            .claims
+            .iter()
+            .filter(|c| has_marker(c, "[DECISION]"))
+            .map(RecordClaim::from_claim)
+            .collect(),
+        key_claims: key_claims(session),
+        tensions_accepted,
+        open_tensions,
+        open_annotations: annotation_lines(session),
+        expected_outcomes: ExpectedOutcomes {
+            confidence: session.thesis.as_ref().map(|t| t.confidence),
+            risks: session
+                .claims
+                .iter()
+                .filter(|c| has_marker(c, "[RISK]"))
+                .map(|c| c.content.clone())
+                .collect(),
+            revision_triggers: session.review_triggers.iter().map(|t| t.description.clone()).collect(),
+            actual: session.calibration.as_ref().and_then(|c| c.outcome),
+        },
+        review_date: session.review_triggers.iter().filter_map(|t| t.review_by).min(),
+        status: format!("{:?}", session.status).to_lowercase(),
+        decided_at: session
+            .calibration
+            .as_ref()
+            .map(|c| c.formed_at)
+            .or(session.thesis.as_ref().map(|t| t.updated_at)),
+        exported_at: Utc::now(),
+        sources: resolve_citations(session, &Default::default(), None),
+    })
+}
+
+fn push_list(md: &mut String, heading: &str, items: &[String]) {
+    md.push_str(&format!("## {}\n\n", heading));
+    if items.is_empty() {
+        md.push_str("_None recorded._\n");
+    }
+    for item in items {
+        md.push_str(&format!("- {}\n", item));
+    }
+    md.push('\n');
+}
+
+fn claim_line(claim: &RecordClaim) -> String {
+    match claim.evidence_score {
+        Some(score) => format!("{} (evidence {:.2})", claim.content, score),
+        None => claim.content.clone(),
+    }
+}
+
+/// Render a decision record as a Markdown journal entry
+pub fn render_markdown(record: &DecisionRecord) -> String {
+    let mut md = String::with_capacity(2048);
+    md.push_str(&format!("# Decision: {}\n\n", record.question));
+    md.push_str(&format!("**Session:** [{}]({})\n", record.session_id, session_link(&record.session_id)));
+    md.push_str(&format!("**Status:** {}\n", record.status));
+    if let Some(decided) = record.decided_at {
+        md.push_str(&format!("**Decided:** {}\n", decided.format("%Y-%m-%d")));
+    }
+    if let Some(review) = record.review_date {
+        md.push_str(&format!("**Review by:** {}\n", review.format("%Y-%m-%d")));
+    }
+    md.push('\n');
+
+    md.push_str("## Decision\n\n");
+    md.push_str(record.decision.as_deref().unwrap_or("_Not yet decided._"));
+    md.push_str("\n\n");
+
+    let options: Vec<String> = record.options_considered.iter().map(claim_line).collect();
+    push_list(&mut md, "Options Considered", &options);
+    let claims: Vec<String> = record.key_claims.iter().map(claim_line).collect();
+    push_list(&mut md, "Key Claims", &claims);
+    let accepted: Vec<String> = record
+        .tensions_accepted
+        .iter()
+        .map(|t| format!("{} — {}", t.description, t.resolution))
+        .collect();
+    push_list(&mut md, "Tensions Accepted", &accepted);
+    if !record.open_tensions.is_empty() {
+        push_list(&mut md, "Open Tensions", &record.open_tensions);
+    }
+    if !record.open_annotations.is_empty() {
+        push_list(&mut md, "Open Review Notes", &record.open_annotations);
+    }
+
+    let outcomes = &record.expected_outcomes;
+    md.push_str("## Expected Outcomes\n\n");
+    if let Some(confidence) = outcomes.confidence {
+        md.push_str(&format!("**Confidence:** {:.0}%\n\n", confidence * 100.0));
+    }
+    if let Some(actual) = outcomes.actual {
+        let actual = format!("{:?}", actual).to_lowercase();
+        md.push_str(&format!("**Actual outcome:** {}\n\n", actual));
+    }
+    push_list(&mut md, "Risks", &outcomes.risks);
+    push_list(&mut md, "Revision Triggers", &outcomes.revision_triggers);
+    md.push_str(&render_bibliography(&record.sources));
+    md
+}
+
+/// Build and render the decision record for a session, with web source
+/// titles and Obsidian links resolved in its bibliography
+pub async fn export(session: &Session, format: DecisionRecordFormat) -> Result<String, SessionError> {
+    let mut record = build_record(session)?;
+    let prefs = effective_preferences(Path::new(&session.working_dir)).0;
+    record.sources = collect_citations(session, prefs.vault_path.as_deref().map(Path::new)).await;
+    Ok(match format {
+        DecisionRecordFormat::Markdown => render_markdown(&record),
+        DecisionRecordFormat::Json => serde_json::to_string_pretty(&record)?,
+    })
+}
+
+/// Write the Markdown decision record into the session dir
+pub async fn write_record(session: &Session, session_dir: &Path) -> Result<PathBuf, SessionError> {
+    let markdown = export(session, DecisionRecordFormat::Markdown).await?;
+    let path = session_dir.join(RECORD_FILE);
+    std::fs::write(&path, markdown)?;
+    info!(session_id = %session.id, path = %path.display(), "Wrote decision record");
+    Ok(path)
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub async fn export_decision_record(
+    app: AppHandle,
+    session_id: String,
+    format: Option<DecisionRecordFormat>,
+) -> Result<String, SessionError> {
+    let session = super::load_session(app, session_id)?;
+    export(&session, format.unwrap_or_default()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+
+    fn decision_session() -> Session {
+        test_session(serde_json::json!({
+            "id": "decision-test",
+            "title": "Expand into the EU?",
+            "status": "formed",
+            "mode": "decision",
+            "created": Utc::now(),
+            "updated": Utc::now(),
+            "claims": [
+                { "id": "c1", "content": "Launch in Germany first", "sourceId": "s", "marker": "[DECISION]", "createdAt": Utc::now() },
+                { "id": "c2", "content": "Delay until 2027", "sourceId": "s", "marker": "[DECISION]", "createdAt": Utc::now() },
+                { "id": "c3", "content": "GDPR tooling is mature", "sourceId": "s", "marker": "[EVIDENCE]", "createdAt": Utc::now() },
+                { "id": "c4", "content": "Local hiring is slow", "sourceId": "s", "marker": "[RISK]", "createdAt": Utc::now() },
+            ],
+            "tensions": [
+                { "id": "t1", "claimAId": "c1", "claimBId": "c2", "description": "Speed vs readiness", "resolution": "Pilot with one customer", "createdAt": Utc::now() },
+                { "id": "t2", "claimAId": "c3", "claimBId": "c4", "description": "Cost of compliance", "resolution": null, "createdAt": Utc::now() },
+            ],
+            "thesis": { "content": "Launch in Germany with a pilot", "confidence": 0.7, "updatedAt": Utc::now() },
+            "reviewTriggers": [
+                { "id": "r1", "description": "Pilot churns", "reviewBy": "2027-03-01T00:00:00Z", "createdAt": Utc::now() },
+                { "id": "r2", "description": "Regulation changes", "reviewBy": "2026-12-01T00:00:00Z", "createdAt": Utc::now() },
+            ],
+        }))
+    }
+
+    #[test]
+    fn test_build_record() {
+        let record = build_record(&decision_session()).unwrap();
+        assert_eq!(record.decision.as_deref(), Some("Launch in Germany with a pilot"));
+        assert_eq!(record.options_considered.len(), 2);
+        assert_eq!(record.key_claims.len(), 1);
+        assert_eq!(record.tensions_accepted.len(), 1);
+        assert_eq!(record.open_tensions, vec!["Cost of compliance".to_string()]);
+        assert_eq!(record.expected_outcomes.risks, vec!["Local hiring is slow".to_string()]);
+        assert_eq!(record.review_date.unwrap().format("%Y-%m-%d").to_string(), "2026-12-01");
+
+        let md = render_markdown(&record);
+        assert!(md.contains("# Decision: Expand into the EU?"));
+        assert!(md.contains("**Session:** [decision-test](dialectic://session/decision-test)"));
+        assert!(md.contains("- Speed vs readiness — Pilot with one customer"));
+        assert!(md.contains("**Confidence:** 70%"));
+    }
+
+    #[test]
+    fn test_idea_sessions_rejected() {
+        let mut session = decision_session();
+        session.mode = SessionMode::Idea;
+        assert!(matches!(build_record(&session), Err(SessionError::NotDecisionMode(_))));
+    }
+}
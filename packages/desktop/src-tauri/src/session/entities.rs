@@ -0,0 +1,299 @@
+//! Session Entities
+//!
+//! Named entities (companies, people, products, places) pulled out of a
+//! session's claims, thesis and cited sources. They are kept as
+//! `entity/<slug>` session tags, so they filter sessions and are stamped
+//! onto the session's Chroma records like any other tag, and vault notes,
+//! web sources and claim memories carry `entity:<slug>` keys of their own.
+//!
+//! Extraction is heuristic by default: capitalized phrases, acronyms,
+//! tickers and `[[wikilinks]]`. With `entityExtraction` set to `model` the
+//! configured CLI tool lists the entities instead, falling back to the
+//! heuristic if it fails.
+
+use parking_lot::Mutex;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, info, warn};
+
+use super::citations::{claim_sources, CitationKind};
+use super::lock::update_session_file;
+use super::tags::{normalize_tag, propagate_tags};
+use super::{get_session_dir_cli, load_session_cli, validate_session_id, Session, SessionError};
+use crate::config::preferences::EntityExtraction;
+use crate::config::workspace::effective_preferences;
+
+/// Prefix of entity session tags
+pub const ENTITY_TAG_PREFIX: &str = "entity/";
+/// Entities kept per session, most mentioned first
+const MAX_SESSION_ENTITIES: usize = 20;
+const MODEL_TIMEOUT: Duration = Duration::from_secs(120);
+const MODEL_PROMPT: &str = "List the named entities (companies, people, products, organizations, places) \
+mentioned in the text on stdin, one per line, most important first. Output only the names.";
+
+/// Capitalized words that start sentences without naming anything
+const COMMON_CAPITALIZED: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "for", "from", "he", "her", "his", "how", "i", "if", "in",
+    "it", "its", "no", "not", "of", "on", "or", "our", "she", "so", "that", "the", "their", "then",
+    "there", "these", "they", "this", "those", "to", "we", "what", "when", "where", "which", "while",
+    "who", "why", "with", "yes", "you", "your",
+];
+
+/// Fingerprint of the input last extracted per session, so unchanged
+/// sessions aren't re-extracted on every write
+static LAST_INPUT: LazyLock<Mutex<HashMap<String, u64>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Lowercase dash-joined form used in tags and Chroma keys: "AT&T Inc." → "at-t-inc"
+pub fn entity_slug(name: &str) -> Option<String> {
+    let mapped: String = name.chars().map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '-' }).collect();
+    let slug = mapped.split('-').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("-");
+    normalize_tag(&slug).ok()
+}
+
+/// Session tag for an entity
+pub fn entity_tag(name: &str) -> Option<String> {
+    normalize_tag(&format!("{}{}", ENTITY_TAG_PREFIX, entity_slug(name)?)).ok()
+}
+
+fn is_capitalized(word: &str) -> bool {
+    word.chars().next().is_some_and(|c| c.is_uppercase())
+}
+
+fn is_acronym(word: &str) -> bool {
+    let letters = word.chars().filter(|c| c.is_alphabetic()).count();
+    (2..=6).contains(&letters) && word.chars().all(|c| c.is_uppercase() || c.is_ascii_digit() || c == '&')
+}
+
+/// Entities in `text`, in order of first mention
+pub fn extract_entities(text: &str) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    let mut push = |name: &str| {
+        let name = name.trim();
+        if !name.is_empty() && !found.iter().any(|f| f.eq_ignore_ascii_case(name)) {
+            found.push(name.to_string());
+        }
+    };
+
+    // [[wikilinks]] name their target (up to an alias or heading); the rest
+    // of the text is scanned with them cut out
+    let mut plain = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("[[") {
+        let Some(end) = rest[start..].find("]]") else { break };
+        push(rest[start + 2..start + end].split(['|', '#']).next().unwrap_or_default());
+        plain.push_str(&rest[..start]);
+        plain.push('.');
+        rest = &rest[start + end + 2..];
+    }
+    plain.push_str(rest);
+
+    for sentence in plain.split(['.', '!', '?', '\n', ';', ':', '(', ')']) {
+        let mut phrase: Vec<&str> = Vec::new();
+        let mut phrase_starts_sentence = false;
+        let words: Vec<&str> = sentence.split_whitespace().collect();
+        let mut flush = |phrase: &mut Vec<&str>, mut starts_sentence: bool| {
+            // Drop leading articles ("The Fed") and trailing connectors ("Bank of")
+            while phrase.len() > 1 && COMMON_CAPITALIZED.contains(&phrase[0].to_lowercase().as_str()) {
+                phrase.remove(0);
+                starts_sentence = false;
+            }
+            while phrase.last().is_some_and(|w| matches!(*w, "of" | "&" | "and")) {
+                phrase.pop();
+            }
+            let lone_common = phrase.len() == 1 && COMMON_CAPITALIZED.contains(&phrase[0].to_lowercase().as_str());
+            let lone_opener = phrase.len() == 1 && starts_sentence && !is_acronym(phrase[0]);
+            if !phrase.is_empty() && !lone_common && !lone_opener {
+                push(&phrase.join(" "));
+            }
+            phrase.clear();
+        };
+        for (i, raw) in words.iter().enumerate() {
+            // Markers such as [INSIGHT] aren't entities
+            if raw.starts_with('[') && raw.ends_with(']') && !raw.starts_with("[[") {
+                flush(&mut phrase, phrase_starts_sentence);
+                continue;
+            }
+            if let Some(ticker) = raw.strip_prefix('$').filter(|t| is_acronym(t.trim_end_matches(','))) {
+                flush(&mut phrase, phrase_starts_sentence);
+                phrase.push(ticker.trim_end_matches(','));
+                flush(&mut phrase, false);
+                continue;
+            }
+            let word = raw.trim_matches(|c: char| !c.is_alphanumeric() && c != '&');
+            let breaks = raw.ends_with(',') || raw.ends_with('"');
+            let connector = !phrase.is_empty() && matches!(word, "of" | "&" | "and");
+            if !word.is_empty() && (is_capitalized(word) || connector) {
+                if phrase.is_empty() {
+                    phrase_starts_sentence = i == 0;
+                }
+                phrase.push(word);
+            } else {
+                flush(&mut phrase, phrase_starts_sentence);
+            }
+            if breaks {
+                flush(&mut phrase, phrase_starts_sentence);
+            }
+        }
+        flush(&mut phrase, phrase_starts_sentence);
+    }
+    found
+}
+
+/// Entities listed by `<tool> -p`, one per line; `None` on any failure
+async fn extract_with_cli(tool: &str, input: &str) -> Option<Vec<String>> {
+    let mut child = tokio::process::Command::new(tool)
+        .arg("-p")
+        .arg(MODEL_PROMPT)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| warn!(tool = %tool, error = %e, "Failed to start CLI tool for entity extraction"))
+        .ok()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes()).await.ok()?;
+    }
+    let output = match tokio::time::timeout(MODEL_TIMEOUT, child.wait_with_output()).await {
+        Ok(Ok(output)) if output.status.success() => output,
+        Ok(Ok(output)) => {
+            warn!(tool = %tool, status = %output.status, "CLI tool exited with an error during entity extraction");
+            return None;
+        }
+        Ok(Err(e)) => {
+            warn!(tool = %tool, error = %e, "CLI tool failed during entity extraction");
+            return None;
+        }
+        Err(_) => {
+            warn!(tool = %tool, timeout_secs = MODEL_TIMEOUT.as_secs(), "CLI tool timed out during entity extraction");
+            return None;
+        }
+    };
+    let entities: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().trim_start_matches(|c: char| c.is_ascii_digit() || matches!(c, '-' | '*' | '.' | ')')).trim())
+        .filter(|line| !line.is_empty() && line.len() <= 80)
+        .map(str::to_string)
+        .collect();
+    (!entities.is_empty()).then_some(entities)
+}
+
+/// Visible text of a Markdown link or autolink
+fn link_text(reference: &str) -> &str {
+    let reference = reference.trim_matches(['<', '>', '`']);
+    match reference.strip_prefix('[').and_then(|r| r.split_once("](")) {
+        Some((text, _)) => text,
+        None => reference,
+    }
+}
+
+/// Text entities are extracted from: claims, thesis and cited source titles
+async fn session_text(session: &Session, vault: Option<&Path>) -> String {
+    let mut parts: Vec<String> = session.claims.iter().map(|c| c.content.clone()).collect();
+    parts.extend(session.thesis.as_ref().map(|t| t.content.clone()));
+    let mut sources = BTreeSet::new();
+    for source in claim_sources(session, vault).await {
+        if matches!(source.kind, CitationKind::WebSource | CitationKind::Note | CitationKind::Document) {
+            sources.insert(link_text(&source.reference).to_string());
+        }
+    }
+    parts.extend(sources);
+    parts.join("\n")
+}
+
+/// Most mentioned entities first, capped
+fn rank_entities(text: &str, entities: Vec<String>) -> Vec<String> {
+    let lower = text.to_lowercase();
+    let mut counted: Vec<(usize, usize, String)> = entities
+        .into_iter()
+        .enumerate()
+        .map(|(order, name)| (lower.matches(&name.to_lowercase()).count(), order, name))
+        .collect();
+    counted.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    counted.into_iter().map(|(_, _, name)| name).take(MAX_SESSION_ENTITIES).collect()
+}
+
+/// Extract a session's entities and replace its `entity/` tags with them.
+/// Unless `force`, sessions whose text hasn't changed since the last run
+/// are skipped and `None` is returned.
+pub async fn tag_session_entities(session_id: &str, force: bool) -> Result<Option<Vec<String>>, SessionError> {
+    let session = load_session_cli(session_id)?;
+    let prefs = effective_preferences(Path::new(&session.working_dir)).0;
+    let text = session_text(&session, prefs.vault_path.as_deref().map(Path::new)).await;
+
+    let mut hasher = DefaultHasher::new();
+    (&text, prefs.entity_extraction).hash(&mut hasher);
+    let fingerprint = hasher.finish();
+    if !force && LAST_INPUT.lock().get(session_id) == Some(&fingerprint) {
+        return Ok(None);
+    }
+
+    let entities = match prefs.entity_extraction {
+        EntityExtraction::Model if !text.trim().is_empty() => match extract_with_cli(&prefs.cli_tool, &text).await {
+            Some(entities) => entities,
+            None => extract_entities(&text),
+        },
+        _ => extract_entities(&text),
+    };
+    let entities = rank_entities(&text, entities);
+    let wanted: BTreeSet<String> = entities.iter().filter_map(|e| entity_tag(e)).collect();
+
+    let mut removed = Vec::new();
+    let path = get_session_dir_cli(session_id)?.join("session.json");
+    let updated = update_session_file(&path, |session| {
+        removed = session
+            .tags
+            .iter()
+            .filter(|t| t.starts_with(ENTITY_TAG_PREFIX) && !wanted.contains(*t))
+            .cloned()
+            .collect();
+        session.tags.retain(|t| !t.starts_with(ENTITY_TAG_PREFIX));
+        session.tags.extend(wanted.iter().cloned());
+        session.tags.sort();
+        Ok(())
+    })?;
+    LAST_INPUT.lock().insert(session_id.to_string(), fingerprint);
+    if updated.tags != session.tags {
+        propagate_tags(session_id, &updated.tags, &removed).await;
+    }
+    debug!(session_id = %session_id, count = entities.len(), "Tagged session entities");
+    Ok(Some(entities))
+}
+
+// ============ TAURI COMMANDS ============
+
+/// Re-extract a session's entities now; returns them most mentioned first
+#[tauri::command]
+pub async fn extract_session_entities(session_id: String) -> Result<Vec<String>, SessionError> {
+    validate_session_id(&session_id)?;
+    let entities = tag_session_entities(&session_id, true).await?.unwrap_or_default();
+    info!(session_id = %session_id, count = entities.len(), "Extracted session entities");
+    Ok(entities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heuristic_extraction() {
+        let text = "[COUNTER] Competitor X undercuts us in the EU. The Bank of England held rates, \
+                    while $AAPL rallied.\nSee [[Porter Five Forces|forces]] and ask whether AT&T follows.";
+        let entities = extract_entities(text);
+        assert_eq!(entities, vec!["Porter Five Forces", "Competitor X", "EU", "Bank of England", "AAPL", "AT&T"]);
+
+        assert_eq!(entity_tag("Competitor X").as_deref(), Some("entity/competitor-x"));
+        assert_eq!(entity_slug("AT&T Inc."), Some("at-t-inc".to_string()));
+        assert_eq!(entity_slug("&&"), None);
+        assert_eq!(link_text("[Pricing study](https://example.com)"), "Pricing study");
+
+        let ranked = rank_entities("EU rules. EU fines. Competitor X", vec!["Competitor X".into(), "EU".into()]);
+        assert_eq!(ranked, vec!["EU", "Competitor X"]);
+    }
+}
@@ -0,0 +1,91 @@
+//! Session Environment File
+//!
+//! Values that change while the agent works — context budget usage and
+//! session status — exported from `<session dir>/dialectic.env`. Launch
+//! passes their initial values as environment variables, plus the file's
+//! path as `DIALECTIC_ENV_FILE`; every session write refreshes the file, so
+//! a skill can `source "$DIALECTIC_ENV_FILE"` for current values without a
+//! CLI round-trip.
+
+use std::fs;
+use std::path::Path;
+use tracing::debug;
+
+use super::{Session, SessionError};
+
+pub const ENV_FILE: &str = "dialectic.env";
+pub const ENV_FILE_VAR: &str = "DIALECTIC_ENV_FILE";
+pub const BUDGET_PCT_VAR: &str = "DIALECTIC_BUDGET_PCT";
+pub const STATUS_VAR: &str = "DIALECTIC_STATUS";
+
+/// The live values for `session`, as (variable, value)
+pub fn live_vars(session: &Session) -> Vec<(&'static str, String)> {
+    let budget_pct = session.context_budget.as_ref().map(|b| b.usage_percentage()).unwrap_or(0);
+    vec![
+        (BUDGET_PCT_VAR, budget_pct.to_string()),
+        (STATUS_VAR, format!("{:?}", session.status).to_lowercase()),
+    ]
+}
+
+/// POSIX shell `export` lines for `vars`, single-quoted
+pub fn render(vars: &[(&str, String)]) -> String {
+    let mut content = String::from("# Written by Dialectic on every session change; source to refresh\n");
+    for (name, value) in vars {
+        content.push_str(&format!("export {}='{}'\n", name, value.replace('\'', "'\\''")));
+    }
+    content
+}
+
+/// Write the env file into `session_dir` if its content changed
+pub fn write(session_dir: &Path, session: &Session) -> Result<(), SessionError> {
+    let path = session_dir.join(ENV_FILE);
+    let content = render(&live_vars(session));
+    if fs::read_to_string(&path).is_ok_and(|current| current == content) {
+        return Ok(());
+    }
+    let tmp = session_dir.join(format!("{}.tmp", ENV_FILE));
+    fs::write(&tmp, &content)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// Refresh the env file after a session write. Only sessions that have
+/// been launched have one; best-effort.
+pub fn refresh(session_dir: &Path, session: &Session) {
+    if !session_dir.join(ENV_FILE).exists() {
+        return;
+    }
+    if let Err(e) = write(session_dir, session) {
+        debug!(session_id = %session.id, error = %e, "Failed to refresh session env file");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use serde_json::json;
+
+    #[test]
+    fn test_env_file_refresh() {
+        let dir = std::env::temp_dir().join(format!("dialectic_env_{}", ulid::Ulid::new()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut session: Session = test_session(json!({
+            "id": "env", "title": "Env",
+        }));
+
+        // Not launched yet: nothing to refresh
+        refresh(&dir, &session);
+        assert!(!dir.join(ENV_FILE).exists());
+
+        write(&dir, &session).unwrap();
+        session.status = crate::session::SessionStatus::Tensions;
+        refresh(&dir, &session);
+        let content = fs::read_to_string(dir.join(ENV_FILE)).unwrap();
+        assert!(content.contains("export DIALECTIC_BUDGET_PCT='0'\n"));
+        assert!(content.contains("export DIALECTIC_STATUS='tensions'\n"));
+        assert_eq!(render(&[("X", "it's".to_string())]).lines().last(), Some("export X='it'\\''s'"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
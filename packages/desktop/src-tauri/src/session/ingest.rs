@@ -0,0 +1,132 @@
+//! Turn Ingestion
+//!
+//! Applies a chunk of assistant output handed over by a hook
+//! (`dialectic ingest-turn`): marker lines become claims, `@@tension` and
+//! `@@resolve` directives update tensions, and what was added is counted
+//! against the session's Paper Trail budget. The result lists exactly what
+//! was recorded, so the agent sees its markers land.
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::lock::update_session_file;
+use super::markers::{apply_directive, parse_line, Applied};
+use super::{get_session_dir_cli, Claim, Session, SessionError, Tension};
+use crate::context::tokens::count_tokens;
+
+/// Source ID of claims ingested from hook output
+pub const SOURCE_ID: &str = "hook";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedTension {
+    pub tension_id: String,
+    pub resolution: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestResult {
+    pub claims: Vec<Claim>,
+    pub tensions: Vec<Tension>,
+    pub resolved: Vec<ResolvedTension>,
+    /// Marker and directive lines that changed nothing (repeats, unknown references)
+    pub skipped: usize,
+    /// Tokens the new claims and tensions add to the Paper Trail
+    pub paper_trail_tokens: u32,
+    pub paper_trail_used: u32,
+    pub budget_pct: u8,
+}
+
+/// Apply the markers and directives in `text` to `session`
+pub fn ingest_text(session: &mut Session, text: &str) -> IngestResult {
+    let mut result = IngestResult::default();
+    for directive in text.lines().filter_map(parse_line) {
+        match apply_directive(session, directive, SOURCE_ID) {
+            Some(Applied::Claim(claim)) => {
+                result.paper_trail_tokens += count_tokens(&claim.content);
+                result.claims.push(claim);
+            }
+            Some(Applied::Tension(tension)) => {
+                result.paper_trail_tokens += count_tokens(&tension.description);
+                result.tensions.push(tension);
+            }
+            Some(Applied::Resolved { tension_id, resolution }) => {
+                result.resolved.push(ResolvedTension { tension_id, resolution });
+            }
+            None => result.skipped += 1,
+        }
+    }
+
+    let budget = session.context_budget.get_or_insert_with(Default::default);
+    budget.paper_trail_used = budget.paper_trail_used.saturating_add(result.paper_trail_tokens);
+    result.paper_trail_used = budget.paper_trail_used;
+    result.budget_pct = budget.usage_percentage();
+    result
+}
+
+/// Ingest `text` into the session's `session.json`
+pub fn ingest_turn(session_id: &str, text: &str) -> Result<IngestResult, SessionError> {
+    let path = get_session_dir_cli(session_id)?.join("session.json");
+    if !path.exists() {
+        return Err(SessionError::NotFound(session_id.to_string()));
+    }
+    let mut result = IngestResult::default();
+    update_session_file(&path, |session| {
+        result = ingest_text(session, text);
+        Ok(())
+    })?;
+    debug!(
+        session_id = %session_id,
+        claims = result.claims.len(),
+        tensions = result.tensions.len(),
+        resolved = result.resolved.len(),
+        "Ingested agent output"
+    );
+    Ok(result)
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn ingest_agent_output(session_id: String, text: String) -> Result<IngestResult, SessionError> {
+    ingest_turn(&session_id, &text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use serde_json::json;
+
+    #[test]
+    fn test_ingest_text() {
+        let mut session: Session = test_session(json!({
+            "id": "ing", "title": "Ingest",
+            "claims": [
+                { "id": "c1", "content": "Pricing power is the moat", "sourceId": "s", "marker": "[INSIGHT]", "createdAt": "2026-01-01T00:00:00Z" },
+            ],
+        }));
+
+        let text = "Looking at the numbers:\n\
+            [RISK] Churn rises with price\n\
+            - [insight] Pricing power is the moat\n\
+            @@tension: c1 | Churn rises | Price vs retention\n\
+            @@resolve: missing | nothing to resolve";
+        let result = ingest_text(&mut session, text);
+        assert_eq!(result.claims.len(), 1);
+        assert_eq!(result.claims[0].marker.as_deref(), Some("[RISK]"));
+        assert_eq!(result.claims[0].source_id, SOURCE_ID);
+        assert_eq!(result.tensions.len(), 1);
+        assert_eq!(result.tensions[0].claim_b_id, result.claims[0].id);
+        assert_eq!(result.skipped, 2);
+        assert!(result.paper_trail_tokens > 0);
+        assert_eq!(session.context_budget.as_ref().unwrap().paper_trail_used, result.paper_trail_tokens);
+
+        // Resolving the new tension round-trips through the same path
+        let resolve = format!("@@resolve: {} | Segment by plan", result.tensions[0].id);
+        let result = ingest_text(&mut session, &resolve);
+        assert_eq!(result.resolved.len(), 1);
+        assert_eq!(session.tensions[0].resolution.as_deref(), Some("Segment by plan"));
+    }
+}
@@ -0,0 +1,233 @@
+//! Write-Ahead Journal for session.json
+//!
+//! The app, the CLI and hooks all write `session.json`. A plain
+//! write-then-rename loses the update if a process dies between the two
+//! steps, and concurrent writers sharing one temp file can clobber each
+//! other. Each write here:
+//!
+//! 1. records the intended contents in a uniquely named journal file (synced),
+//! 2. writes a uniquely named temp file (synced) and renames it over the target,
+//! 3. removes the journal.
+//!
+//! On load, `recover` replays a journal whose write never landed (it is
+//! newer than the target) and discards superseded, corrupt or partial ones.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+use ulid::Ulid;
+
+use super::SessionError;
+
+/// Journals and temp files younger than this may belong to a writer that
+/// is still running, so recovery leaves them alone
+const WRITER_GRACE_MS: i64 = 2_000;
+
+/// Intended write, persisted before the target is touched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JournalRecord {
+    written_at: DateTime<Utc>,
+    pid: u32,
+    contents: String,
+}
+
+/// What recovery did for a target file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryOutcome {
+    /// No journals pending
+    Clean,
+    /// A pending write was applied
+    Replayed,
+    /// Journals existed but none needed applying
+    Discarded,
+}
+
+fn target_name(target: &Path) -> String {
+    target.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+fn sibling(target: &Path, suffix: &str) -> PathBuf {
+    target.with_file_name(format!("{}.{}.{}", target_name(target), Ulid::new(), suffix))
+}
+
+fn write_synced(path: &Path, contents: &[u8]) -> Result<(), SessionError> {
+    let mut file = File::create(path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Write `contents` to `target` through the journal
+pub fn journaled_write(target: &Path, contents: &str) -> Result<(), SessionError> {
+    let journal = sibling(target, "journal");
+    let record = JournalRecord {
+        written_at: Utc::now(),
+        pid: std::process::id(),
+        contents: contents.to_string(),
+    };
+    write_synced(&journal, serde_json::to_string(&record)?.as_bytes())?;
+
+    let tmp = sibling(target, "tmp");
+    let applied = write_synced(&tmp, contents.as_bytes())
+        .and_then(|_| fs::rename(&tmp, target).map_err(SessionError::from));
+    if applied.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    applied?;
+
+    // Recovery may already have consumed it
+    if let Err(e) = fs::remove_file(&journal) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            warn!(error = %e, journal = %journal.display(), "Failed to clear journal");
+        }
+    }
+    Ok(())
+}
+
+/// Journal and temp files belonging to `target`
+fn pending_files(target: &Path) -> Vec<(PathBuf, bool)> {
+    let Some(dir) = target.parent() else { return Vec::new() };
+    let prefix = format!("{}.", target_name(target));
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let rest = name.strip_prefix(&prefix)?;
+            if rest.ends_with(".journal") {
+                Some((entry.path(), true))
+            } else if rest.ends_with(".tmp") || rest == "tmp" {
+                Some((entry.path(), false))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn modified_at(path: &Path) -> Option<DateTime<Utc>> {
+    fs::metadata(path).and_then(|m| m.modified()).ok().map(DateTime::<Utc>::from)
+}
+
+fn recover_with_grace(target: &Path, grace: Duration) -> Result<RecoveryOutcome, SessionError> {
+    let pending = pending_files(target);
+    if pending.is_empty() {
+        return Ok(RecoveryOutcome::Clean);
+    }
+
+    let now = Utc::now();
+    let settled = |path: &Path| modified_at(path).is_none_or(|t| now - t >= grace);
+    let target_modified = modified_at(target);
+
+    let mut newest: Option<JournalRecord> = None;
+    let mut stale = Vec::new();
+    for (path, is_journal) in pending {
+        if !settled(&path) {
+            continue;
+        }
+        if is_journal {
+            let record = fs::read_to_string(&path)
+                .ok()
+                .and_then(|c| serde_json::from_str::<JournalRecord>(&c).ok());
+            // Only complete records for writes that never landed are replayed
+            if let Some(record) = record {
+                let unapplied = target_modified.is_none_or(|t| record.written_at > t)
+                    && serde_json::from_str::<serde_json::Value>(&record.contents).is_ok();
+                if unapplied && newest.as_ref().is_none_or(|n| record.written_at > n.written_at) {
+                    newest = Some(record);
+                }
+            }
+        }
+        stale.push(path);
+    }
+
+    let outcome = match newest {
+        Some(record) => {
+            let tmp = sibling(target, "tmp");
+            write_synced(&tmp, record.contents.as_bytes())?;
+            fs::rename(&tmp, target)?;
+            info!(target = %target.display(), pid = record.pid, written_at = %record.written_at, "Replayed journaled write");
+            RecoveryOutcome::Replayed
+        }
+        None if stale.is_empty() => return Ok(RecoveryOutcome::Clean),
+        None => RecoveryOutcome::Discarded,
+    };
+
+    for path in stale {
+        if let Err(e) = fs::remove_file(&path) {
+            debug!(error = %e, path = %path.display(), "Failed to remove stale journal file");
+        }
+    }
+    Ok(outcome)
+}
+
+/// Apply or discard any journals left behind for `target`
+pub fn recover(target: &Path) -> Result<RecoveryOutcome, SessionError> {
+    recover_with_grace(target, Duration::milliseconds(WRITER_GRACE_MS))
+}
+
+/// Read `target` after recovering any interrupted write (best-effort)
+pub fn read_recovered(target: &Path) -> Result<String, SessionError> {
+    if let Err(e) = recover(target) {
+        warn!(error = %e, target = %target.display(), "Journal recovery failed");
+    }
+    Ok(fs::read_to_string(target)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_target() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("dialectic_journal_{}", Ulid::new()));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("session.json")
+    }
+
+    fn leave_journal(target: &Path, contents: &str, written_at: DateTime<Utc>) {
+        let record = JournalRecord { written_at, pid: 1, contents: contents.to_string() };
+        fs::write(sibling(target, "journal"), serde_json::to_string(&record).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_write_leaves_no_journal() {
+        let target = temp_target();
+        journaled_write(&target, r#"{"v":1}"#).unwrap();
+        journaled_write(&target, r#"{"v":2}"#).unwrap();
+        assert_eq!(fs::read_to_string(&target).unwrap(), r#"{"v":2}"#);
+        assert!(pending_files(&target).is_empty());
+        assert_eq!(recover(&target).unwrap(), RecoveryOutcome::Clean);
+        fs::remove_dir_all(target.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_replays_interrupted_write() {
+        let target = temp_target();
+        fs::write(&target, r#"{"v":1}"#).unwrap();
+        leave_journal(&target, r#"{"v":2}"#, Utc::now() + Duration::seconds(5));
+        // Partial temp file from the crashed writer
+        fs::write(sibling(&target, "tmp"), r#"{"v":"#).unwrap();
+
+        assert_eq!(recover_with_grace(&target, Duration::zero()).unwrap(), RecoveryOutcome::Replayed);
+        assert_eq!(fs::read_to_string(&target).unwrap(), r#"{"v":2}"#);
+        assert!(pending_files(&target).is_empty());
+        fs::remove_dir_all(target.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_discards_superseded_and_corrupt_journals() {
+        let target = temp_target();
+        leave_journal(&target, r#"{"v":0}"#, Utc::now() - Duration::hours(1));
+        fs::write(sibling(&target, "journal"), r#"{"writtenAt":"#).unwrap();
+        fs::write(&target, r#"{"v":1}"#).unwrap();
+
+        assert_eq!(recover_with_grace(&target, Duration::zero()).unwrap(), RecoveryOutcome::Discarded);
+        assert_eq!(fs::read_to_string(&target).unwrap(), r#"{"v":1}"#);
+        assert!(pending_files(&target).is_empty());
+        fs::remove_dir_all(target.parent().unwrap()).ok();
+    }
+}
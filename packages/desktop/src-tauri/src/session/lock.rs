@@ -0,0 +1,310 @@
+//! Session File Locking
+//!
+//! Advisory lock around session.json read/modify/write cycles so the app,
+//! the CLI and hooks don't clobber each other. The lock is held on a
+//! sibling `session.json.lock` (session.json itself is replaced by rename,
+//! so locking it would lock a stale inode).
+//!
+//! Callers that read a session, do slow work, then save the whole struct
+//! (the CLI, launch) can't hold the lock throughout. `save_merged` re-reads
+//! under the lock and, if someone else wrote in between, carries over
+//! their newly appended items before writing.
+
+use chrono::Utc;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+use super::journal::read_recovered;
+use super::{atomic_write, audit, env_file, Session, SessionError};
+use crate::context::budget_history;
+
+/// How long to wait for another writer before giving up
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Delay between lock attempts
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Session as last loaded by this process, the base for merges
+static LOADED: LazyLock<Mutex<HashMap<String, Session>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Held lock on a session file; released on drop
+pub struct SessionLock {
+    _file: File,
+}
+
+fn lock_path(session_path: &Path) -> PathBuf {
+    let name = session_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    session_path.with_file_name(format!("{}.lock", name))
+}
+
+fn lock_with_timeout(session_path: &Path, timeout: Duration) -> Result<SessionLock, SessionError> {
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_path(session_path))?;
+    let start = Instant::now();
+    loop {
+        match file.try_lock() {
+            Ok(()) => return Ok(SessionLock { _file: file }),
+            Err(TryLockError::WouldBlock) if start.elapsed() < timeout => {
+                std::thread::sleep(LOCK_RETRY_INTERVAL);
+            }
+            Err(TryLockError::WouldBlock) => {
+                return Err(SessionError::Locked(session_path.display().to_string()));
+            }
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+    }
+}
+
+/// Take the exclusive lock for `session_path`, waiting up to `LOCK_TIMEOUT`
+pub fn lock_session_file(session_path: &Path) -> Result<SessionLock, SessionError> {
+    lock_with_timeout(session_path, LOCK_TIMEOUT)
+}
+
+/// Remember a loaded session as the base for a later `save_merged`
+pub fn remember_loaded(session: &Session) {
+    crate::quick_search::note_session(session);
+    LOADED.lock().insert(session.id.clone(), session.clone());
+}
+
+fn loaded_base(session_id: &str) -> Option<Session> {
+    LOADED.lock().get(session_id).cloned()
+}
+
+/// Append items that `theirs` added since `base` and `ours` doesn't have.
+/// Items `ours` removed (present in `base`) stay removed.
+fn merge_appended<T: Clone>(base: &[T], ours: &mut Vec<T>, theirs: &[T], key: impl Fn(&T) -> String) -> usize {
+    let known: HashSet<String> = base.iter().chain(ours.iter()).map(&key).collect();
+    let added: Vec<T> = theirs.iter().filter(|item| !known.contains(&key(item))).cloned().collect();
+    let count = added.len();
+    ours.extend(added);
+    count
+}
+
+fn json_key<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// Fold a concurrent writer's appended items into `ours`.
+/// Returns the number of items carried over.
+pub fn merge_concurrent(base: &Session, ours: &mut Session, theirs: &Session) -> usize {
+    let mut merged = 0;
+    merged += merge_appended(&base.claims, &mut ours.claims, &theirs.claims, |c| c.id.clone());
+    merged += merge_appended(&base.tensions, &mut ours.tensions, &theirs.tensions, |t| t.id.clone());
+    merged += merge_appended(&base.passes, &mut ours.passes, &theirs.passes, |p| p.id.clone());
+    merged += merge_appended(&base.context_files, &mut ours.context_files, &theirs.context_files, |f| f.id.clone());
+    merged += merge_appended(&base.reference_docs, &mut ours.reference_docs, &theirs.reference_docs, |d| d.id.clone());
+    merged += merge_appended(&base.review_triggers, &mut ours.review_triggers, &theirs.review_triggers, |t| t.id.clone());
+    merged += merge_appended(&base.trigger_alerts, &mut ours.trigger_alerts, &theirs.trigger_alerts, |a| a.id.clone());
+    merged += merge_appended(&base.annotations, &mut ours.annotations, &theirs.annotations, |a| a.id.clone());
+    merged += merge_appended(&base.source_changes, &mut ours.source_changes, &theirs.source_changes, |c| c.id.clone());
+    merged += merge_appended(&base.cdg_edges, &mut ours.cdg_edges, &theirs.cdg_edges, |e| {
+        format!("{}|{}|{}|{}", e.source_claim_id, e.target_claim_id, json_key(&e.edge_type), e.created_at)
+    });
+    merged += merge_appended(&base.cdg_snapshots, &mut ours.cdg_snapshots, &theirs.cdg_snapshots, |s| {
+        format!("{}|{}", s.pass_id, s.timestamp)
+    });
+    merged += merge_appended(&base.conversation_ids, &mut ours.conversation_ids, &theirs.conversation_ids, |c| c.id.clone());
+    merged += merge_appended(&base.tags, &mut ours.tags, &theirs.tags, |t| t.clone());
+    if ours.conversation_id.is_none() && ours.conversation_ids.is_empty() {
+        ours.conversation_id = theirs.conversation_id.clone();
+    }
+    ours.updated = ours.updated.max(theirs.updated);
+    merged
+}
+
+/// Lock, re-read, apply `f`, bump `updated` and write back, auditing the
+/// changes
+pub fn update_session_file<F>(session_path: &Path, f: F) -> Result<Session, SessionError>
+where
+    F: FnOnce(&mut Session) -> Result<(), SessionError>,
+{
+    let _lock = lock_session_file(session_path)?;
+    let mut session: Session = serde_json::from_str(&read_recovered(session_path)?)?;
+    let before = session.clone();
+    f(&mut session)?;
+    crate::git::stamp_commits(&mut session);
+    session.updated = Utc::now();
+    atomic_write(session_path, &serde_json::to_string_pretty(&session)?)?;
+    remember_loaded(&session);
+    if let Some(dir) = session_path.parent() {
+        env_file::refresh(dir, &session);
+        audit::record_changes(dir, Some(&before), &session);
+        budget_history::record_if_changed(dir, before.context_budget.as_ref(), session.context_budget.as_ref());
+    }
+    Ok(session)
+}
+
+/// Write a whole session under the lock, merging in appends made by other
+/// writers since `base` was read (defaults to what this process last loaded).
+/// Changes against the state it replaced are audited. Returns the session
+/// as written and that state.
+pub fn save_merged(
+    session_path: &Path,
+    session: &Session,
+    base: Option<&Session>,
+) -> Result<(Session, Option<Session>), SessionError> {
+    let _lock = lock_session_file(session_path)?;
+    let on_disk: Option<Session> = read_recovered(session_path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok());
+
+    let mut ours = session.clone();
+    let base = base.cloned().or_else(|| loaded_base(&session.id));
+    if let (Some(base), Some(theirs)) = (&base, &on_disk) {
+        // Compare contents, not `updated`: retention tags sessions without
+        // touching it
+        if json_key(theirs) != json_key(base) {
+            let merged = merge_concurrent(base, &mut ours, theirs);
+            if merged > 0 {
+                info!(session_id = %session.id, merged = merged, "Merged concurrent session changes");
+            } else {
+                debug!(session_id = %session.id, "Concurrent session write had nothing to merge");
+            }
+        }
+    }
+
+    crate::git::stamp_commits(&mut ours);
+    atomic_write(session_path, &serde_json::to_string_pretty(&ours)?)?;
+    remember_loaded(&ours);
+    if let Some(dir) = session_path.parent() {
+        env_file::refresh(dir, &ours);
+        audit::record_changes(dir, on_disk.as_ref(), &ours);
+        let budget_before = on_disk.as_ref().and_then(|s| s.context_budget.as_ref());
+        budget_history::record_if_changed(dir, budget_before, ours.context_budget.as_ref());
+    }
+    Ok((ours, on_disk))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use crate::session::annotations;
+    use serde_json::json;
+
+    fn session(claim_ids: &[&str], updated: &str) -> Session {
+        let claims: Vec<_> = claim_ids
+            .iter()
+            .map(|id| json!({"id": id, "content": id, "sourceId": "s", "marker": null, "createdAt": "2026-01-01T00:00:00Z"}))
+            .collect();
+        test_session(json!({
+            "id": "lock",
+            "title": "Lock",
+            "updated": updated,
+            "claims": claims,
+        }))
+    }
+
+    fn ids(session: &Session) -> Vec<&str> {
+        session.claims.iter().map(|c| c.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_merge_keeps_concurrent_appends() {
+        let base = session(&["a", "b"], "2026-01-01T00:00:00Z");
+        // We removed b and added c; they added d
+        let mut ours = session(&["a", "c"], "2026-01-02T00:00:00Z");
+        let theirs = session(&["a", "b", "d"], "2026-01-03T00:00:00Z");
+
+        assert_eq!(merge_concurrent(&base, &mut ours, &theirs), 1);
+        assert_eq!(ids(&ours), vec!["a", "c", "d"]);
+        assert_eq!(ours.updated, theirs.updated);
+    }
+
+    #[test]
+    fn test_conversations_fold_legacy_id_and_merge() {
+        let mut base = session(&[], "2026-01-01T00:00:00Z");
+        base.conversation_id = Some("conv-1".to_string());
+        assert_eq!(base.latest_conversation_id(), Some("conv-1"));
+
+        let mut ours = base.clone();
+        assert!(ours.add_conversation("conv-2", Some("/tmp/conv-2.jsonl".to_string())));
+        assert!(!ours.add_conversation("conv-2", None));
+        assert!(ours.conversation_id.is_none());
+        assert_eq!(ours.latest_conversation_id(), Some("conv-2"));
+
+        let mut theirs = base.clone();
+        theirs.add_conversation("conv-3", None);
+        merge_concurrent(&base, &mut ours, &theirs);
+        let merged: Vec<&str> = ours.conversation_ids.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(merged, vec!["conv-1", "conv-2", "conv-3"]);
+        assert!(ours.conversation_id.is_none());
+    }
+
+    #[test]
+    fn test_merge_keeps_concurrent_annotations_and_source_changes() {
+        let annotation = |id: &str| -> annotations::Annotation {
+            serde_json::from_value(json!({
+                "id": id, "author": "reviewer", "target": {"kind": "thesis"},
+                "comment": id, "createdAt": "2026-01-02T00:00:00Z",
+            }))
+            .unwrap()
+        };
+        let base = session(&[], "2026-01-01T00:00:00Z");
+        let mut ours = base.clone();
+        ours.annotations.push(annotation("mine"));
+        let mut theirs = base.clone();
+        theirs.updated = "2026-01-03T00:00:00Z".parse().unwrap();
+        theirs.annotations.push(annotation("theirs"));
+        // A freshness check ran while we were working
+        theirs.source_changes.push(
+            serde_json::from_value(json!({
+                "id": "change-1", "url": "https://example.com", "title": null, "changeRatio": 0.4,
+                "claimIds": ["a"], "detectedAt": "2026-01-03T00:00:00Z",
+            }))
+            .unwrap(),
+        );
+
+        assert_eq!(merge_concurrent(&base, &mut ours, &theirs), 2);
+        let merged: Vec<&str> = ours.annotations.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(merged, vec!["mine", "theirs"]);
+        assert_eq!(ours.source_changes[0].id, "change-1");
+    }
+
+    #[test]
+    fn test_save_merged_and_lock_contention() {
+        let dir = std::env::temp_dir().join(format!("dialectic_lock_{}", ulid::Ulid::new()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session.json");
+
+        let base = session(&["a"], "2026-01-01T00:00:00Z");
+        std::fs::write(&path, serde_json::to_string(&session(&["a", "x"], "2026-01-05T00:00:00Z")).unwrap()).unwrap();
+        let ours = session(&["a", "y"], "2026-01-06T00:00:00Z");
+        let (written, _) = save_merged(&path, &ours, Some(&base)).unwrap();
+        assert_eq!(ids(&written), vec!["a", "y", "x"]);
+
+        // A write that left `updated` alone is still merged
+        let mut tagged = written.clone();
+        tagged.tags.push("icebox".to_string());
+        std::fs::write(&path, serde_json::to_string(&tagged).unwrap()).unwrap();
+        let (written, _) = save_merged(&path, &written, None).unwrap();
+        assert_eq!(written.tags, vec!["icebox"]);
+        let updated = update_session_file(&path, |s| {
+            s.claims.retain(|c| c.id != "x");
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(ids(&updated), vec!["a", "y"]);
+        // Both writes are audited: y added, then x removed
+        assert_eq!(audit::read_audit_log(&dir).unwrap().len(), 2);
+
+        let held = lock_session_file(&path).unwrap();
+        assert!(matches!(
+            lock_with_timeout(&path, Duration::from_millis(50)),
+            Err(SessionError::Locked(_))
+        ));
+        drop(held);
+        assert!(lock_with_timeout(&path, Duration::from_millis(50)).is_ok());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
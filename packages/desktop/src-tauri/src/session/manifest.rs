@@ -0,0 +1,188 @@
+//! Context Manifests
+//!
+//! What each launch put in front of the model. While `prepare_launch`
+//! renders CLAUDE.md it notes every claim, tension, annotation, skill,
+//! artifact excerpt and related-work hit it includes, with the section it
+//! went under and its token cost, and saves the list as
+//! `manifests/<launch id>.json` in the session dir. The launch id is
+//! returned with the launch context and logged on the `launched` audit
+//! entry. Only the newest `MAX_MANIFESTS` are kept.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tracing::warn;
+
+use super::{atomic_write, get_session_dir, validate_session_id, SessionError};
+use crate::context::tokens::count_tokens;
+
+pub const MANIFEST_DIR: &str = "manifests";
+/// Manifests kept per session
+pub const MAX_MANIFESTS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ManifestItemKind {
+    Workflow,
+    Skill,
+    ContextFile,
+    Claim,
+    Tension,
+    Annotation,
+    Thesis,
+    Summary,
+    /// A distill run's memo, spine or thesis history
+    DistillArtifact,
+    IterationState,
+    Scratchpad,
+    /// A memory or document chunk from another session
+    RelatedWork,
+}
+
+/// One thing included in CLAUDE.md
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestItem {
+    /// Heading it was rendered under
+    pub section: String,
+    pub kind: ManifestItemKind,
+    /// Claim, tension, annotation or skill id, file path, or the
+    /// `<session>/<collection>` of a related hit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub tokens: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextManifest {
+    pub launch_id: String,
+    pub session_id: String,
+    pub created_at: DateTime<Utc>,
+    /// Token count of the whole CLAUDE.md
+    pub total_tokens: u32,
+    /// Headings, session metadata and other text not tied to an item
+    pub overhead_tokens: u32,
+    pub items: Vec<ManifestItem>,
+}
+
+/// Collects items while CLAUDE.md is rendered
+#[derive(Debug, Default)]
+pub struct ManifestRecorder {
+    items: Vec<ManifestItem>,
+}
+
+impl ManifestRecorder {
+    /// Note `text`, as rendered, as one item of `section`
+    pub fn record(&mut self, section: &str, kind: ManifestItemKind, id: Option<&str>, text: &str) {
+        self.items.push(ManifestItem {
+            section: section.to_string(),
+            kind,
+            id: id.map(str::to_string),
+            tokens: count_tokens(text),
+        });
+    }
+
+    pub fn finish(self, launch_id: &str, session_id: &str, markdown: &str) -> ContextManifest {
+        let total_tokens = count_tokens(markdown);
+        let items_tokens: u32 = self.items.iter().map(|i| i.tokens).sum();
+        ContextManifest {
+            launch_id: launch_id.to_string(),
+            session_id: session_id.to_string(),
+            created_at: Utc::now(),
+            total_tokens,
+            overhead_tokens: total_tokens.saturating_sub(items_tokens),
+            items: self.items,
+        }
+    }
+}
+
+fn manifest_path(session_dir: &Path, launch_id: &str) -> Result<PathBuf, SessionError> {
+    validate_session_id(launch_id)?;
+    Ok(session_dir.join(MANIFEST_DIR).join(format!("{}.json", launch_id)))
+}
+
+/// Launch ids with a manifest, oldest first (ids are ULIDs)
+pub fn list_launches(session_dir: &Path) -> Vec<String> {
+    let mut ids: Vec<String> = fs::read_dir(session_dir.join(MANIFEST_DIR))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| e.file_name().to_string_lossy().strip_suffix(".json").map(str::to_string))
+        .collect();
+    ids.sort();
+    ids
+}
+
+/// Save a manifest and drop the oldest beyond `MAX_MANIFESTS`
+pub fn write_manifest(session_dir: &Path, manifest: &ContextManifest) -> Result<(), SessionError> {
+    let path = manifest_path(session_dir, &manifest.launch_id)?;
+    fs::create_dir_all(session_dir.join(MANIFEST_DIR))?;
+    atomic_write(&path, &serde_json::to_string_pretty(manifest)?)?;
+
+    let launches = list_launches(session_dir);
+    for old in &launches[..launches.len().saturating_sub(MAX_MANIFESTS)] {
+        if let Err(e) = manifest_path(session_dir, old).and_then(|p| Ok(fs::remove_file(p)?)) {
+            warn!(launch_id = %old, error = %e, "Failed to prune context manifest");
+        }
+    }
+    Ok(())
+}
+
+/// The manifest of `launch_id`, or of the latest launch
+pub fn read_manifest(session_dir: &Path, launch_id: Option<&str>) -> Result<ContextManifest, SessionError> {
+    let launch_id = match launch_id {
+        Some(id) => id.to_string(),
+        None => list_launches(session_dir)
+            .pop()
+            .ok_or_else(|| SessionError::NotFound(format!("context manifest in {}", session_dir.display())))?,
+    };
+    let path = manifest_path(session_dir, &launch_id)?;
+    if !path.exists() {
+        return Err(SessionError::NotFound(format!("context manifest {}", launch_id)));
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+// ============ TAURI COMMANDS ============
+
+/// What a launch's CLAUDE.md contained; the latest launch without `launch_id`
+#[tauri::command]
+pub fn get_context_manifest(
+    app: AppHandle,
+    session_id: String,
+    launch_id: Option<String>,
+) -> Result<ContextManifest, SessionError> {
+    read_manifest(&get_session_dir(&app, &session_id)?, launch_id.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifests_round_trip_and_prune() {
+        let dir = std::env::temp_dir().join(format!("dialectic_manifest_{}", ulid::Ulid::new()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let markdown = "# Context\n\n## Claims\n\n- Rates stay high\n";
+        let mut recorder = ManifestRecorder::default();
+        recorder.record("Claims", ManifestItemKind::Claim, Some("c1"), "- Rates stay high\n");
+        let manifest = recorder.finish("01J0000000000000000000000A", "s1", markdown);
+        assert_eq!(manifest.total_tokens, count_tokens(markdown));
+        assert_eq!(manifest.overhead_tokens + manifest.items[0].tokens, manifest.total_tokens);
+        write_manifest(&dir, &manifest).unwrap();
+
+        for i in 0..MAX_MANIFESTS {
+            let later = ManifestRecorder::default().finish(&format!("01J1{:022}", i), "s1", "");
+            write_manifest(&dir, &later).unwrap();
+        }
+        assert_eq!(list_launches(&dir).len(), MAX_MANIFESTS);
+        assert!(read_manifest(&dir, Some("01J0000000000000000000000A")).is_err());
+        assert_eq!(read_manifest(&dir, None).unwrap().launch_id, format!("01J1{:022}", MAX_MANIFESTS - 1));
+        assert!(read_manifest(&dir, Some("../session")).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,233 @@
+//! Semantic Markers
+//!
+//! Claims are tagged in agent output with a bracketed marker at the start of
+//! a line (`[INSIGHT] ...`). Headless runs, the transcript tailer and the MCP
+//! server all share this vocabulary and parser.
+//!
+//! Live output (the terminal, `dialectic ingest-turn`) also accepts
+//! directives:
+//!
+//! - `@@claim: [MARKER] text` (marker optional)
+//! - `@@tension: <claim a> | <claim b> | description`, claims by ID or
+//!   the start of their content
+//! - `@@resolve: <tension id> | resolution`
+
+use chrono::Utc;
+use tracing::warn;
+use ulid::Ulid;
+
+use super::{Claim, Session, Tension};
+
+/// Markers recognized as claims
+pub const MARKERS: [&str; 8] = [
+    "INSIGHT", "EVIDENCE", "RISK", "COUNTER", "PATTERN", "DECISION", "TENSION", "ASSUMPTION",
+];
+
+/// Shortest text treated as the truncated start of a longer claim
+const MIN_PREFIX_CHARS: usize = 24;
+
+/// A marker line: `[INSIGHT] text`, optionally bulleted or bolded
+/// (`- [RISK] text`, `**[EVIDENCE]** text`)
+fn parse_marker_line(line: &str) -> Option<(&'static str, String)> {
+    let line = line.trim_start().trim_start_matches(['-', '*', '>', ' ']);
+    let rest = line.strip_prefix('[')?;
+    let (marker, rest) = rest.split_once(']')?;
+    let marker = MARKERS.into_iter().find(|m| m.eq_ignore_ascii_case(marker.trim()))?;
+    let content = rest.trim_start_matches(['*', ':']).trim();
+    (!content.is_empty()).then(|| (marker, content.to_string()))
+}
+
+/// Marker-tagged lines in `text`, in order, as (marker, content)
+pub fn extract_markers(text: &str) -> Vec<(&'static str, String)> {
+    text.lines().filter_map(parse_marker_line).collect()
+}
+
+/// Whether two claim texts are the same claim: equal ignoring case and
+/// spacing, or one is the start of the other (a wrapped terminal line
+/// against the full transcript text)
+pub fn same_claim(a: &str, b: &str) -> bool {
+    let normalize = |s: &str| s.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    let (a, b) = (normalize(a), normalize(b));
+    let (short, long) = if a.len() <= b.len() { (&a, &b) } else { (&b, &a) };
+    short == long || (short.chars().count() >= MIN_PREFIX_CHARS && long.starts_with(short.as_str()))
+}
+
+/// A session mutation requested in agent output
+#[derive(Debug, Clone, PartialEq)]
+pub enum Directive {
+    Claim { marker: Option<String>, content: String },
+    Tension { claim_a: String, claim_b: String, description: String },
+    Resolve { tension_id: String, resolution: String },
+}
+
+/// Parse one line of agent output: a directive or a marker line
+pub fn parse_line(line: &str) -> Option<Directive> {
+    // Drop TUI decoration (bullets, box drawing) around the text
+    let line = line
+        .trim_matches(|c: char| c.is_whitespace() || (!c.is_ascii() && !c.is_alphanumeric()))
+        .trim();
+
+    if let Some(rest) = line.strip_prefix("@@") {
+        let (name, args) = rest.split_once(':')?;
+        let args = args.trim();
+        let parts: Vec<&str> = args.split('|').map(str::trim).collect();
+        return match name.trim().to_lowercase().as_str() {
+            "claim" => match extract_markers(args).into_iter().next() {
+                Some((marker, content)) => Some(Directive::Claim { marker: Some(format!("[{}]", marker)), content }),
+                None => (!args.is_empty()).then(|| Directive::Claim { marker: None, content: args.to_string() }),
+            },
+            "tension" => match parts.as_slice() {
+                [a, b, description] if !a.is_empty() && !b.is_empty() => Some(Directive::Tension {
+                    claim_a: a.to_string(),
+                    claim_b: b.to_string(),
+                    description: description.to_string(),
+                }),
+                _ => None,
+            },
+            "resolve" => match parts.as_slice() {
+                [id, resolution] if !id.is_empty() && !resolution.is_empty() => Some(Directive::Resolve {
+                    tension_id: id.to_string(),
+                    resolution: resolution.to_string(),
+                }),
+                _ => None,
+            },
+            _ => None,
+        };
+    }
+
+    let (marker, content) = extract_markers(line).into_iter().next()?;
+    Some(Directive::Claim { marker: Some(format!("[{}]", marker)), content })
+}
+
+/// A claim by ID, or by the start of its content
+fn find_claim<'a>(session: &'a Session, reference: &str) -> Option<&'a Claim> {
+    let reference_lower = reference.to_lowercase();
+    session
+        .claims
+        .iter()
+        .find(|c| c.id == reference)
+        .or_else(|| session.claims.iter().find(|c| c.content.to_lowercase().starts_with(&reference_lower)))
+}
+
+/// What applying a directive changed
+#[derive(Debug, Clone)]
+pub enum Applied {
+    Claim(Claim),
+    Tension(Tension),
+    Resolved { tension_id: String, resolution: String },
+}
+
+/// Apply one directive to `session`; `None` when it changes nothing
+/// (a repeated claim or tension, an unknown claim or tension).
+/// New claims get `source_id`.
+pub fn apply_directive(session: &mut Session, directive: Directive, source_id: &str) -> Option<Applied> {
+    match directive {
+        Directive::Claim { marker, content } => {
+            if session.claims.iter().any(|c| same_claim(&c.content, &content)) {
+                return None;
+            }
+            let claim = Claim {
+                id: Ulid::new().to_string(),
+                content,
+                source_id: source_id.to_string(),
+                marker,
+                created_at: Utc::now(),
+                source_span: None,
+                evidence_score: None,
+                commit: None,
+            };
+            session.claims.push(claim.clone());
+            Some(Applied::Claim(claim))
+        }
+        Directive::Tension { claim_a, claim_b, description } => {
+            let (Some(a), Some(b)) = (find_claim(session, &claim_a), find_claim(session, &claim_b)) else {
+                warn!(session_id = %session.id, claim_a = %claim_a, claim_b = %claim_b, "Tension names an unknown claim");
+                return None;
+            };
+            let (a, b) = (a.id.clone(), b.id.clone());
+            let exists = session.tensions.iter().any(|t| {
+                (t.claim_a_id == a && t.claim_b_id == b) || (t.claim_a_id == b && t.claim_b_id == a)
+            });
+            if exists || a == b {
+                return None;
+            }
+            let tension = Tension {
+                id: Ulid::new().to_string(),
+                claim_a_id: a,
+                claim_b_id: b,
+                description,
+                resolution: None,
+                created_at: Utc::now(),
+            };
+            session.tensions.push(tension.clone());
+            Some(Applied::Tension(tension))
+        }
+        Directive::Resolve { tension_id, resolution } => {
+            let Some(tension) = session.tensions.iter_mut().find(|t| t.id == tension_id) else {
+                warn!(session_id = %session.id, tension_id = %tension_id, "Resolve names an unknown tension");
+                return None;
+            };
+            if tension.resolution.as_deref() == Some(resolution.as_str()) {
+                return None;
+            }
+            tension.resolution = Some(resolution.clone());
+            Some(Applied::Resolved { tension_id, resolution })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_markers() {
+        let text = "Intro line\n\
+            [INSIGHT] Pricing power is the moat\n\
+            - [risk] Churn rises with price\n\
+            **[EVIDENCE]** Q3 retention was 94%\n\
+            [TODO] not a marker\n\
+            [COUNTER]\n\
+            > [ASSUMPTION]: Costs stay flat";
+        assert_eq!(extract_markers(text), vec![
+            ("INSIGHT", "Pricing power is the moat".to_string()),
+            ("RISK", "Churn rises with price".to_string()),
+            ("EVIDENCE", "Q3 retention was 94%".to_string()),
+            ("ASSUMPTION", "Costs stay flat".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_same_claim() {
+        assert!(same_claim("Pricing power  is the moat", "pricing power is the moat"));
+        assert!(same_claim("Pricing power is the moat for", "Pricing power is the moat for incumbents"));
+        assert!(!same_claim("Pricing", "Pricing power is the moat"));
+        assert!(!same_claim("Churn rises", "Churn falls"));
+    }
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line("⏺ @@claim: [risk] Churn rises with price"),
+            Some(Directive::Claim { marker: Some("[RISK]".to_string()), content: "Churn rises with price".to_string() })
+        );
+        assert_eq!(
+            parse_line("@@claim: Prices are sticky"),
+            Some(Directive::Claim { marker: None, content: "Prices are sticky".to_string() })
+        );
+        assert_eq!(
+            parse_line("│ @@tension: 01ABC | Churn rises | Price vs retention │"),
+            Some(Directive::Tension {
+                claim_a: "01ABC".to_string(),
+                claim_b: "Churn rises".to_string(),
+                description: "Price vs retention".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_line("@@resolve: 01T | Segment by plan"),
+            Some(Directive::Resolve { tension_id: "01T".to_string(), resolution: "Segment by plan".to_string() })
+        );
+        assert_eq!(parse_line("@@tension: only one"), None);
+        assert_eq!(parse_line("[TODO] not a marker"), None);
+    }
+}
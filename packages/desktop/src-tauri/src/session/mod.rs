@@ -0,0 +1,1754 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use thiserror::Error;
+use tracing::{info, warn, debug};
+use ulid::Ulid;
+
+use crate::cdg::{CdgEdge, CdgSnapshot};
+use crate::cdg::evidence::EvidenceReport;
+use crate::chroma::search::RelatedSessionResults;
+use crate::config::preferences::{load_preferences, Preferences};
+use crate::config::profile;
+use crate::config::workspace::{effective_preferences, WorkspaceConfig, VAULT_PATH_VAR};
+use crate::context::{ContextBudget, PaperTrail};
+use crate::obsidian::indexer::VaultIndex;
+
+pub mod annotations;
+pub mod audit;
+pub mod calendar;
+pub mod calibration;
+pub mod citations;
+pub mod claim_source;
+pub mod clusters;
+pub mod decision_record;
+pub mod entities;
+pub mod env_file;
+pub mod ingest;
+pub mod journal;
+pub mod lock;
+pub mod manifest;
+pub mod markers;
+pub mod mode_policy;
+pub mod repair;
+pub mod retention;
+pub mod review;
+pub mod scratchpad;
+pub mod share;
+pub mod statusline;
+pub mod similar;
+pub mod tags;
+pub mod tailer;
+pub mod time_tracking;
+pub mod timeline;
+pub mod trash;
+pub mod trigger_alerts;
+
+use calibration::ThesisCalibration;
+use manifest::{ManifestItemKind, ManifestRecorder};
+use review::ReviewTrigger;
+use trigger_alerts::TriggerAlert;
+use crate::sources::freshness::SourceChange;
+
+#[derive(Error, Debug)]
+pub enum SessionError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Session not found: {0}")]
+    NotFound(String),
+    #[error("Invalid path: {0}")]
+    InvalidPath(String),
+    #[error("Invalid session ID")]
+    InvalidSessionId,
+    #[error("Path escapes allowed directory")]
+    PathEscape,
+    #[error("App data directory not found")]
+    NoAppDataDir,
+    #[error("Session is locked by another process: {0}")]
+    Locked(String),
+    #[error("Session has no thesis: {0}")]
+    NoThesis(String),
+    #[error("Not a decision-mode session: {0}")]
+    NotDecisionMode(String),
+    #[error("Decision-mode session {0} has {1} unresolved tension(s)")]
+    UnresolvedTensions(String, usize),
+    #[error("CLI tool unavailable: {0}")]
+    CliTool(String),
+    #[error("Invalid tag: {0}")]
+    InvalidTag(String),
+    #[error("Invalid annotation: {0}")]
+    InvalidAnnotation(String),
+}
+
+/// Validate that a session ID contains only safe characters (alphanumeric, dash, underscore).
+/// Rejects any path traversal attempts (/, \, ..).
+pub fn validate_session_id(session_id: &str) -> Result<(), SessionError> {
+    if session_id.is_empty() {
+        return Err(SessionError::InvalidSessionId);
+    }
+    if session_id.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+        Ok(())
+    } else {
+        Err(SessionError::InvalidSessionId)
+    }
+}
+
+/// Validate that a resolved path is contained within the expected base directory.
+/// Canonicalizes both paths and checks that the candidate starts with the base.
+pub fn validate_path_containment(base: &std::path::Path, candidate: &std::path::Path) -> Result<PathBuf, SessionError> {
+    let canonical_base = base.canonicalize().map_err(|_| SessionError::InvalidPath(base.display().to_string()))?;
+    let canonical_candidate = candidate.canonicalize().map_err(|_| SessionError::InvalidPath(candidate.display().to_string()))?;
+    if canonical_candidate.starts_with(&canonical_base) {
+        Ok(canonical_candidate)
+    } else {
+        Err(SessionError::PathEscape)
+    }
+}
+
+impl Serialize for SessionError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Session status corresponding to Kanban columns
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionStatus {
+    Backlog,     // Spark
+    Exploring,   // Shape
+    Tensions,    // Stress-Test
+    Synthesizing,// Sharpen
+    Formed,      // Ship
+}
+
+impl Default for SessionStatus {
+    fn default() -> Self {
+        SessionStatus::Backlog
+    }
+}
+
+/// Session mode
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionMode {
+    Idea,
+    Decision,
+}
+
+impl Default for SessionMode {
+    fn default() -> Self {
+        SessionMode::Idea
+    }
+}
+
+/// Context file reference
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextFile {
+    pub id: String,
+    pub filename: String,
+    pub path: String,
+    pub added_at: DateTime<Utc>,
+}
+
+/// Claim extracted from sources
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Claim {
+    pub id: String,
+    pub content: String,
+    pub source_id: String,
+    pub marker: Option<String>, // [INSIGHT], [EVIDENCE], [RISK], [COUNTER]
+    pub created_at: DateTime<Utc>,
+    /// Exact span of the source the claim was drawn from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_span: Option<claim_source::SourceSpan>,
+    /// Evidential support from the CDG, set by `cdg::evidence`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evidence_score: Option<f32>,
+    /// Repo HEAD the claim was formed against (project-local sessions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+/// Tension between claims
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tension {
+    pub id: String,
+    pub claim_a_id: String,
+    pub claim_b_id: String,
+    pub description: String,
+    pub resolution: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Thesis output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Thesis {
+    pub content: String,
+    pub confidence: f32,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Pass tracking for audit trail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pass {
+    pub id: String,
+    pub pass_type: String, // "expansion", "compression", "critique"
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub token_count: Option<u32>,
+    /// Repo HEAD when the pass was recorded (project-local sessions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+/// Terminal state within session
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalState {
+    pub pid: Option<u32>,
+    pub running: bool,
+    pub last_command: Option<String>,
+}
+
+/// Reference document attached to session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionReferenceDoc {
+    pub id: String,
+    pub filename: String,
+    pub path: String,
+    pub token_count: u32,
+    pub handling: String,  // "full", "summarized", "chunked"
+    pub persistence: String, // "ephemeral", "cached", "permanent"
+    /// Page a fetched web reference came from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Bibliographic metadata of an imported reference
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub citation: Option<crate::documents::bibtex::Citation>,
+}
+
+/// A Claude conversation that was part of a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationRef {
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    /// Transcript location, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jsonl_path: Option<String>,
+}
+
+/// Main session structure persisted to session.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub id: String,
+    pub title: String,
+    pub status: SessionStatus,
+    pub mode: SessionMode,
+
+    // Paths
+    pub working_dir: String,
+    pub is_project_local: bool,
+
+    // Timestamps
+    pub created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_resumed: Option<DateTime<Utc>>,
+    /// Single conversation recorded by older builds; folded into
+    /// `conversation_ids` the next time a conversation is added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conversation_id: Option<String>,
+    /// Claude conversations the session has spanned, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conversation_ids: Vec<ConversationRef>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub parent_session_id: Option<String>,
+    /// Installed skills the session's CLAUDE.md points at; empty for the default
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<String>,
+
+    // Content
+    #[serde(default)]
+    pub context_files: Vec<ContextFile>,
+    #[serde(default)]
+    pub claims: Vec<Claim>,
+    #[serde(default)]
+    pub tensions: Vec<Tension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thesis: Option<Thesis>,
+    #[serde(default)]
+    pub passes: Vec<Pass>,
+
+    // Terminal state
+    #[serde(default)]
+    pub terminal: TerminalState,
+
+    // Context management (Sprint 2)
+    #[serde(default)]
+    pub context_budget: Option<ContextBudget>,
+    #[serde(default)]
+    pub paper_trail: Option<PaperTrail>,
+    #[serde(default)]
+    pub reference_docs: Vec<SessionReferenceDoc>,
+
+    // Claim Dependency Graph
+    #[serde(default)]
+    pub cdg_edges: Vec<CdgEdge>,
+    #[serde(default)]
+    pub cdg_snapshots: Vec<CdgSnapshot>,
+
+    // Thesis review scheduling
+    #[serde(default)]
+    pub review_triggers: Vec<ReviewTrigger>,
+    #[serde(default)]
+    pub trigger_alerts: Vec<TriggerAlert>,
+    /// Cited web sources that changed after they were indexed
+    #[serde(default)]
+    pub source_changes: Vec<SourceChange>,
+
+    // Thesis calibration
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calibration: Option<ThesisCalibration>,
+
+    /// Collaborator review comments on claims, tensions and the thesis
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<annotations::Annotation>,
+
+    // Optional metadata
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Normalized (lowercase) labels for filtering sessions and search
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl Session {
+    /// All conversations, oldest first, including a legacy `conversationId`
+    pub fn conversations(&self) -> Vec<ConversationRef> {
+        let mut conversations = Vec::new();
+        if let Some(legacy) = &self.conversation_id {
+            if !self.conversation_ids.iter().any(|c| &c.id == legacy) {
+                conversations.push(ConversationRef { id: legacy.clone(), started_at: self.created, jsonl_path: None });
+            }
+        }
+        conversations.extend(self.conversation_ids.iter().cloned());
+        conversations
+    }
+
+    /// The conversation a launch resumes
+    pub fn latest_conversation_id(&self) -> Option<&str> {
+        self.conversation_ids.last().map(|c| c.id.as_str()).or(self.conversation_id.as_deref())
+    }
+
+    /// Record a conversation, filling in its transcript path if it is
+    /// already known. Returns whether it was new.
+    pub fn add_conversation(&mut self, id: &str, jsonl_path: Option<String>) -> bool {
+        let conversations = self.conversations();
+        self.conversation_ids = conversations;
+        self.conversation_id = None;
+
+        if let Some(existing) = self.conversation_ids.iter_mut().find(|c| c.id == id) {
+            if existing.jsonl_path.is_none() {
+                existing.jsonl_path = jsonl_path;
+            }
+            return false;
+        }
+        self.conversation_ids.push(ConversationRef { id: id.to_string(), started_at: Utc::now(), jsonl_path });
+        true
+    }
+}
+
+/// A minimal exploring idea session, with the top-level fields of
+/// `overrides` (camelCase, as in session.json) replacing the defaults
+#[cfg(test)]
+pub(crate) fn test_session(overrides: serde_json::Value) -> Session {
+    let mut session = serde_json::json!({
+        "id": "test", "title": "Test", "status": "exploring", "mode": "idea",
+        "workingDir": "/tmp", "isProjectLocal": false,
+        "created": "2026-01-01T00:00:00Z", "updated": "2026-01-01T00:00:00Z",
+    });
+    if let (Some(fields), serde_json::Value::Object(overrides)) = (session.as_object_mut(), overrides) {
+        fields.extend(overrides);
+    }
+    serde_json::from_value(session).expect("test session")
+}
+
+/// Input for creating a new session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSessionInput {
+    pub title: String,
+    /// Falls back to the workspace, then global, default mode
+    #[serde(default)]
+    pub mode: Option<SessionMode>,
+    pub working_dir: Option<String>,
+    pub category: Option<String>,
+    pub summary: Option<String>,
+}
+
+/// A new session and the existing sessions it resembles, so the UI can
+/// offer to continue one of those instead
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSessionResponse {
+    #[serde(flatten)]
+    pub session: Session,
+    pub similar_sessions: Vec<similar::SimilarSession>,
+}
+
+/// Input for forking an existing session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkSessionInput {
+    pub source_session_id: String,
+    pub title: Option<String>,
+}
+
+/// Get the app data directory path from AppHandle (Tauri), for the
+/// active profile
+fn get_app_data_path(app: &AppHandle) -> Result<PathBuf, SessionError> {
+    let root = app.path()
+        .app_data_dir()
+        .map_err(|_| SessionError::NoAppDataDir)?;
+    Ok(profile::profile_dir(&root, profile::current_profile()))
+}
+
+/// Journaled write: record the contents in a journal, write a temp file
+/// and rename it into place, then drop the journal. If the process dies
+/// before the rename, `journal::recover` replays the write on next load.
+fn atomic_write(path: &std::path::Path, contents: &str) -> Result<(), SessionError> {
+    journal::journaled_write(path, contents)
+}
+
+/// Get app data directory for CLI use (no AppHandle)
+/// Uses the standard Tauri app data location, for the active profile
+pub fn get_app_data_dir_cli() -> Result<PathBuf, SessionError> {
+    let root = profile::app_data_root().ok_or(SessionError::NoAppDataDir)?;
+    Ok(profile::profile_dir(&root, profile::current_profile()))
+}
+
+/// Get session directory for CLI use
+/// Accepts session_id with or without "sess_" prefix
+pub fn get_session_dir_cli(session_id: &str) -> Result<PathBuf, SessionError> {
+    let base = get_app_data_dir_cli()?;
+    // Strip prefix if present to normalize
+    let normalized_id = session_id.trim_start_matches("sess_");
+    validate_session_id(normalized_id)?;
+    Ok(base.join("sessions").join(format!("sess_{}", normalized_id)))
+}
+
+/// Load session from disk for CLI use
+pub fn load_session_cli(session_id: &str) -> Result<Session, SessionError> {
+    let session_dir = get_session_dir_cli(session_id)?;
+    let session_path = session_dir.join("session.json");
+
+    if !session_path.exists() {
+        return Err(SessionError::NotFound(session_id.to_string()));
+    }
+
+    let content = journal::read_recovered(&session_path)?;
+    let session: Session = serde_json::from_str(&content)?;
+    lock::remember_loaded(&session);
+
+    Ok(session)
+}
+
+/// Save session to disk for CLI use
+pub fn save_session_cli(session: &Session) -> Result<(), SessionError> {
+    let session_dir = get_session_dir_cli(&session.id)?;
+    let session_path = session_dir.join("session.json");
+    lock::save_merged(&session_path, session, None)?;
+    debug!(session_id = %session.id, "Saved session");
+    Ok(())
+}
+
+/// Shared helper: list sessions from a directory
+fn list_sessions_from_dir(sessions_dir: &PathBuf) -> Result<Vec<Session>, SessionError> {
+    if !sessions_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut sessions = Vec::new();
+
+    for entry in fs::read_dir(sessions_dir)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        if path.is_dir() {
+            let session_json = path.join("session.json");
+            if session_json.exists() {
+                match journal::read_recovered(&session_json) {
+                    Ok(content) => {
+                        match serde_json::from_str::<Session>(&content) {
+                            Ok(session) => sessions.push(session),
+                            Err(e) => {
+                                tracing::warn!(path = ?session_json, error = %e, "Failed to parse session (try `dialectic session repair`)");
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!(path = ?session_json, error = %e, "Failed to read session");
+                    }
+                }
+            }
+        }
+    }
+
+    // Sort by updated timestamp, most recent first
+    sessions.sort_by(|a, b| b.updated.cmp(&a.updated));
+    crate::quick_search::replace_sessions(&sessions);
+
+    Ok(sessions)
+}
+
+/// List all sessions for CLI use
+pub fn list_sessions_cli() -> Result<Vec<Session>, SessionError> {
+    let base = get_app_data_dir_cli()?;
+    let sessions_dir = base.join("sessions");
+    list_sessions_from_dir(&sessions_dir)
+}
+
+/// Initialize app data directory structure
+pub fn init_app_data_dir(app: &AppHandle) -> Result<(), SessionError> {
+    let base = get_app_data_path(app)?;
+
+    // Create directory structure
+    let dirs = [
+        base.join("sessions"),
+        base.join("skills"),
+        base.join("skills/dialectic"),
+        base.join("config"),
+    ];
+
+    for dir in dirs {
+        if !dir.exists() {
+            fs::create_dir_all(&dir)?;
+        }
+    }
+
+    crate::skills::install_skills(&base.join("skills"));
+    trash::purge_expired(&base);
+    retention::apply_on_startup(&base);
+
+    // Create default preferences if not exists
+    let prefs_path = base.join("config/preferences.json");
+    if !prefs_path.exists() {
+        let default_prefs = Preferences::default();
+        fs::write(&prefs_path, serde_json::to_string_pretty(&default_prefs)?)?;
+    }
+
+    Ok(())
+}
+
+/// Get session directory path
+pub(crate) fn get_session_dir(app: &AppHandle, session_id: &str) -> Result<PathBuf, SessionError> {
+    session_dir_in(&get_app_data_path(app)?, session_id)
+}
+
+/// Session directory under an app data directory
+fn session_dir_in(app_data: &Path, session_id: &str) -> Result<PathBuf, SessionError> {
+    validate_session_id(session_id)?;
+    Ok(app_data.join("sessions").join(format!("sess_{}", session_id)))
+}
+
+/// Get session.json path for a session
+fn get_session_json_path(app: &AppHandle, session_id: &str) -> Result<PathBuf, SessionError> {
+    let session_dir = get_session_dir(app, session_id)?;
+    Ok(session_dir.join("session.json"))
+}
+
+/// Load a session, apply `f`, bump `updated`, and write it back atomically
+pub(crate) fn modify_session<F>(app: &AppHandle, session_id: &str, f: F) -> Result<Session, SessionError>
+where
+    F: FnOnce(&mut Session) -> Result<(), SessionError>,
+{
+    let session_path = get_session_json_path(app, session_id)?;
+    if !session_path.exists() {
+        return Err(SessionError::NotFound(session_id.to_string()));
+    }
+    lock::update_session_file(&session_path, f)
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn get_app_data_dir(app: AppHandle) -> Result<String, SessionError> {
+    let path = get_app_data_path(&app)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub fn get_skills_dir(app: AppHandle) -> Result<String, SessionError> {
+    let base = get_app_data_path(&app)?;
+    let skills_dir = base.join("skills");
+    Ok(skills_dir.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub async fn create_session(app: AppHandle, input: CreateSessionInput) -> Result<CreateSessionResponse, SessionError> {
+    let (title, summary) = (input.title.clone(), input.summary.clone());
+    let session = create_session_in(&get_app_data_path(&app)?, input)?;
+    let similar_sessions = similar::find_similar_sessions(&title, summary.as_deref(), &session.id).await;
+    Ok(CreateSessionResponse { session, similar_sessions })
+}
+
+/// Create a session under `app_data` (the app's or, from the CLI, `get_app_data_dir_cli`)
+pub fn create_session_in(app_data: &Path, input: CreateSessionInput) -> Result<Session, SessionError> {
+    let session_id = Ulid::new().to_string();
+    let now = Utc::now();
+
+    // Determine working directory
+    let is_project_local = input.working_dir.is_some();
+    let working_dir = match input.working_dir {
+        Some(dir) => {
+            // Validate directory exists and canonicalize
+            let path = PathBuf::from(&dir).canonicalize()
+                .map_err(|_| SessionError::InvalidPath(dir.clone()))?;
+            if !path.is_dir() {
+                return Err(SessionError::InvalidPath(dir));
+            }
+            path.to_string_lossy().to_string()
+        }
+        None => {
+            // Use app data directory
+            let session_dir = session_dir_in(app_data, &session_id)?;
+            session_dir.to_string_lossy().to_string()
+        }
+    };
+
+    // Project-local sessions pick up the project's .dialectic/config.toml
+    let prefs = if is_project_local {
+        effective_preferences(Path::new(&working_dir)).0
+    } else {
+        load_preferences()
+    };
+
+    let session = Session {
+        id: session_id.clone(),
+        title: input.title,
+        status: SessionStatus::Backlog,
+        mode: input.mode.unwrap_or(prefs.default_mode),
+        working_dir: working_dir.clone(),
+        is_project_local,
+        created: now,
+        updated: now,
+        last_resumed: None,
+        conversation_id: None,
+        conversation_ids: Vec::new(),
+        skills: Vec::new(),
+        parent_session_id: None,
+        context_files: Vec::new(),
+        claims: Vec::new(),
+        tensions: Vec::new(),
+        thesis: None,
+        passes: Vec::new(),
+        terminal: TerminalState::default(),
+        context_budget: Some(ContextBudget::new(prefs.budget_profile)),
+        paper_trail: Some(PaperTrail::default()),
+        reference_docs: Vec::new(),
+        cdg_edges: Vec::new(),
+        cdg_snapshots: Vec::new(),
+        review_triggers: Vec::new(),
+        trigger_alerts: Vec::new(),
+        source_changes: Vec::new(),
+        calibration: None,
+        annotations: Vec::new(),
+        category: input.category,
+        summary: input.summary,
+        tags: Vec::new(),
+    };
+
+    // Create session directory structure
+    let session_dir = session_dir_in(app_data, &session_id)?;
+    fs::create_dir_all(&session_dir)?;
+    fs::create_dir_all(session_dir.join("context"))?;
+    fs::create_dir_all(session_dir.join("claims"))?;
+    fs::create_dir_all(session_dir.join("tensions"))?;
+    fs::create_dir_all(session_dir.join("thesis"))?;
+
+    // Write session.json atomically
+    let session_json = serde_json::to_string_pretty(&session)?;
+    atomic_write(&session_dir.join("session.json"), &session_json)?;
+    crate::quick_search::note_session(&session);
+    audit::record_changes(&session_dir, None, &session);
+    audit::record(&session_dir, &[audit::AuditEntry::new(
+        audit::AuditAction::Created,
+        None,
+        serde_json::json!({ "mode": session.mode, "workingDir": session.working_dir }),
+    )]);
+
+    info!(session_id = %session.id, title = %session.title, mode = ?session.mode, "Created session");
+    Ok(session)
+}
+
+#[tauri::command]
+pub fn load_session(app: AppHandle, session_id: String) -> Result<Session, SessionError> {
+    let session_path = get_session_json_path(&app, &session_id)?;
+
+    if !session_path.exists() {
+        return Err(SessionError::NotFound(session_id));
+    }
+
+    let content = journal::read_recovered(&session_path)?;
+    let session: Session = serde_json::from_str(&content)?;
+
+    debug!(session_id = %session_id, "Loaded session");
+    Ok(session)
+}
+
+#[tauri::command]
+pub fn list_sessions(app: AppHandle, tag: Option<String>) -> Result<Vec<Session>, SessionError> {
+    let base = get_app_data_path(&app)?;
+    let sessions_dir = base.join("sessions");
+    let mut sessions = list_sessions_from_dir(&sessions_dir)?;
+    if let Some(tag) = tag {
+        tags::retain_tagged(&mut sessions, &tag);
+    }
+    debug!(count = sessions.len(), "Listed sessions");
+    Ok(sessions)
+}
+
+#[tauri::command]
+pub fn update_session_status(
+    app: AppHandle,
+    session_id: String,
+    status: SessionStatus,
+) -> Result<Session, SessionError> {
+    let session_path = get_session_json_path(&app, &session_id)?;
+    if !session_path.exists() {
+        return Err(SessionError::NotFound(session_id));
+    }
+    let new_status = format!("{:?}", status);
+    let mut before = None;
+    let session = lock::update_session_file(&session_path, |session| {
+        mode_policy::ModePolicy::for_mode(&session.mode).check_transition(session, &status)?;
+        before = Some(session.clone());
+        session.status = status;
+        calibration::record_formed(session);
+        Ok(())
+    })?;
+    let old_status = before.as_ref().map(|b| format!("{:?}", b.status)).unwrap_or_default();
+    if let Some(dir) = session_path.parent() {
+        let newly_formed = session.status == SessionStatus::Formed
+            && before.as_ref().is_some_and(|b| b.status != SessionStatus::Formed);
+        if newly_formed && mode_policy::ModePolicy::for_mode(&session.mode).decision_record {
+            let (formed, dir) = (session.clone(), dir.to_path_buf());
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = decision_record::write_record(&formed, &dir).await {
+                    warn!(session_id = %formed.id, error = %e, "Failed to write decision record");
+                }
+            });
+        }
+    }
+    info!(session_id = %session_id, old_status = %old_status, new_status = %new_status, "Session status transition");
+    Ok(session)
+}
+
+#[tauri::command]
+pub fn delete_session(app: AppHandle, session_id: String) -> Result<(), SessionError> {
+    let session_dir = get_session_dir(&app, &session_id)?;
+
+    if !session_dir.exists() {
+        warn!(session_id = %session_id, "Session not found for deletion");
+        return Err(SessionError::NotFound(session_id));
+    }
+
+    if let Err(e) = crate::documents::permanent::preserve_session(&session_id) {
+        warn!(session_id = %session_id, error = %e, "Failed to preserve permanent documents");
+    }
+    trash::trash_session_in(&get_app_data_path(&app)?, &session_id)?;
+    crate::quick_search::forget_session(&session_id);
+    info!(session_id = %session_id, "Deleted session");
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn fork_session(app: AppHandle, input: ForkSessionInput) -> Result<Session, SessionError> {
+    validate_session_id(&input.source_session_id)?;
+
+    // Load source session
+    let source_path = get_session_json_path(&app, &input.source_session_id)?;
+    if !source_path.exists() {
+        return Err(SessionError::NotFound(input.source_session_id));
+    }
+    let source_content = journal::read_recovered(&source_path)?;
+    let source: Session = serde_json::from_str(&source_content)?;
+
+    let new_id = Ulid::new().to_string();
+    let now = Utc::now();
+    let title = input.title.unwrap_or_else(|| format!("{} (fork)", source.title));
+
+    let forked = Session {
+        id: new_id.clone(),
+        title,
+        status: SessionStatus::Backlog,
+        mode: source.mode.clone(),
+        working_dir: source.working_dir.clone(),
+        is_project_local: source.is_project_local,
+        created: now,
+        updated: now,
+        last_resumed: None,
+        conversation_id: None,
+        conversation_ids: Vec::new(),
+        skills: source.skills.clone(),
+        parent_session_id: Some(source.id.clone()),
+        // Deep-clone structured state
+        context_files: source.context_files.clone(),
+        claims: source.claims.clone(),
+        tensions: source.tensions.clone(),
+        thesis: source.thesis.clone(),
+        reference_docs: source.reference_docs.clone(),
+        cdg_edges: source.cdg_edges.clone(),
+        category: source.category.clone(),
+        summary: source.summary.clone(),
+        tags: source.tags.clone(),
+        // Reset transient state
+        passes: Vec::new(),
+        terminal: TerminalState::default(),
+        context_budget: Some(ContextBudget::new(load_preferences().budget_profile)),
+        paper_trail: Some(PaperTrail::default()),
+        cdg_snapshots: Vec::new(),
+        review_triggers: Vec::new(),
+        trigger_alerts: Vec::new(),
+        source_changes: Vec::new(),
+        calibration: None,
+        // Open critique still applies to the carried-forward claims
+        annotations: source.annotations.iter().filter(|a| !a.resolved).cloned().collect(),
+    };
+
+    // Create session directory structure
+    let session_dir = get_session_dir(&app, &new_id)?;
+    fs::create_dir_all(&session_dir)?;
+    fs::create_dir_all(session_dir.join("context"))?;
+    fs::create_dir_all(session_dir.join("claims"))?;
+    fs::create_dir_all(session_dir.join("tensions"))?;
+    fs::create_dir_all(session_dir.join("thesis"))?;
+
+    // Write session.json atomically
+    let session_json = serde_json::to_string_pretty(&forked)?;
+    atomic_write(&session_dir.join("session.json"), &session_json)?;
+    crate::quick_search::note_session(&forked);
+    audit::record_changes(&session_dir, None, &forked);
+    audit::record(&session_dir, &[audit::AuditEntry::new(
+        audit::AuditAction::Created,
+        None,
+        serde_json::json!({ "forkedFrom": source.id, "claims": forked.claims.len() }),
+    )]);
+
+    info!(
+        new_id = %new_id,
+        parent_id = %source.id,
+        claims_carried = forked.claims.len(),
+        tensions_carried = forked.tensions.len(),
+        "Forked session"
+    );
+
+    Ok(forked)
+}
+
+// ============ LAUNCH PIPELINE ============
+
+/// Response from prepare_launch — everything the frontend needs to spawn a terminal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchContext {
+    pub working_dir: String,
+    pub session_dir: String,
+    pub conversation_id: Option<String>,
+    pub claude_command: Vec<String>,
+    pub env_vars: HashMap<String, String>,
+    /// Flags left out because the CLI tool doesn't support them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Id of this launch's context manifest
+    pub launch_id: String,
+}
+
+impl LaunchContext {
+    /// The audit log entry recording this launch
+    pub fn audit_entry(&self) -> audit::AuditEntry {
+        audit::AuditEntry::new(
+            audit::AuditAction::Launched,
+            None,
+            serde_json::json!({
+                "command": self.claude_command,
+                "workingDir": self.working_dir,
+                "conversationId": self.conversation_id,
+                "launchId": self.launch_id,
+            }),
+        )
+    }
+}
+
+/// Map session status to the skill name used in CLAUDE.md
+fn get_skill_description(status: &SessionStatus) -> &'static str {
+    match status {
+        SessionStatus::Backlog => "Spark — brainstorm, gather sources, initial framing",
+        SessionStatus::Exploring => "Shape — form positions on claims through probing",
+        SessionStatus::Tensions => "Stress-Test — challenge assumptions, surface contradictions",
+        SessionStatus::Synthesizing => "Sharpen — crystallize thesis with calibrated confidence",
+        SessionStatus::Formed => "Ship — session is complete",
+    }
+}
+
+/// Get the skill instruction text to embed in CLAUDE.md
+fn get_skill_instruction(status: &SessionStatus) -> Option<&'static str> {
+    match status {
+        SessionStatus::Backlog => Some(
+            "You are in SPARK mode. Your job is to:\n\
+             - Help the user brainstorm and explore the question space broadly\n\
+             - Gather source material and extract initial claims\n\
+             - Surface assumptions and identify what's actually being asked\n\
+             - Use semantic markers: [INSIGHT], [EVIDENCE], [RISK], [COUNTER]\n\
+             - When the user has enough material, suggest moving to Shape (exploring)"
+        ),
+        SessionStatus::Exploring => Some(
+            "You are in SHAPE mode. Your job is to:\n\
+             - Interview the user about their emerging positions\n\
+             - Ask probing questions to clarify what they actually believe\n\
+             - Help form distinct claims from fuzzy intuitions\n\
+             - Identify which claims are load-bearing vs peripheral\n\
+             - When positions are clear, suggest moving to Stress-Test (tensions)"
+        ),
+        SessionStatus::Tensions => Some(
+            "You are in STRESS-TEST mode. Your job is to:\n\
+             - Apply structured critique: inversion, second-order effects, falsification, base rates, incentive audit, adversary simulation\n\
+             - Surface genuine tensions between claims\n\
+             - Challenge the strongest-seeming conclusions hardest\n\
+             - Don't paper over contradictions — preserve them as signal\n\
+             - When key tensions are surfaced, suggest moving to Sharpen (synthesizing)"
+        ),
+        SessionStatus::Synthesizing => Some(
+            "You are in SHARPEN mode. Your job is to:\n\
+             - Resolve or acknowledge open tensions\n\
+             - Produce a thesis with a calibrated confidence score (0.0-1.0)\n\
+             - Define concrete revision triggers\n\
+             - Compress insights into a defensible position\n\
+             - When thesis is solid, suggest moving to Ship (formed)"
+        ),
+        SessionStatus::Formed => None,
+    }
+}
+
+/// Generate CLAUDE.md content for a session
+fn generate_claude_md(
+    session: &Session,
+    session_dir: &str,
+    related_context: Option<&RelatedSessionResults>,
+    vault_path: Option<&Path>,
+    evidence: &EvidenceReport,
+    manifest: &mut ManifestRecorder,
+) -> String {
+    let mut md = String::with_capacity(2048);
+
+    md.push_str("# Dialectic Session Context\n\n");
+    md.push_str(&format!("**Session:** {}\n", session.title));
+    md.push_str(&format!("**ID:** {}\n", session.id));
+    md.push_str(&format!("**Status:** {} ({})\n",
+        format!("{:?}", session.status).to_lowercase(),
+        get_skill_description(&session.status)
+    ));
+    md.push_str(&format!("**Mode:** {}\n", format!("{:?}", session.mode).to_lowercase()));
+    md.push_str(&format!("**Session dir:** {}\n", session_dir));
+    md.push_str(&format!("**Session data:** {}/session.json\n", session_dir));
+    if let Some(vault_path) = vault_path {
+        md.push_str(&format!("**Vault:** {} (this project's vault)\n", vault_path.display()));
+    }
+    md.push_str(&format!(
+        "**Live status:** `source \"${}\"` for current `${}` and `${}`\n\n",
+        env_file::ENV_FILE_VAR, env_file::BUDGET_PCT_VAR, env_file::STATUS_VAR
+    ));
+
+    // Active skill instruction
+    if let Some(instruction) = get_skill_instruction(&session.status) {
+        md.push_str("## Active Workflow\n\n");
+        md.push_str(instruction);
+        manifest.record("Active Workflow", ManifestItemKind::Workflow, None, instruction);
+        md.push_str("\n\n");
+    }
+
+    // Skill files selected for the session
+    if let Some(app_data) = Path::new(session_dir).parent().and_then(Path::parent) {
+        let skills = crate::skills::session_skill_paths(session, &app_data.join("skills"));
+        if !skills.is_empty() {
+            md.push_str("## Skills\n\n");
+            for (name, path) in skills {
+                let line = format!("- **{}**: {}\n", name, path.display());
+                manifest.record("Skills", ManifestItemKind::Skill, Some(&name), &line);
+                md.push_str(&line);
+            }
+            md.push('\n');
+        }
+    }
+
+    // Context files
+    if !session.context_files.is_empty() {
+        md.push_str("## Context Files\n\n");
+        for cf in &session.context_files {
+            let line = format!("- `{}` ({})\n", cf.filename, cf.path);
+            manifest.record("Context Files", ManifestItemKind::ContextFile, Some(&cf.path), &line);
+            md.push_str(&line);
+        }
+        md.push_str("\n");
+    }
+
+    // Claims summary
+    if !session.claims.is_empty() {
+        md.push_str(&format!("## Claims ({} total)\n\n", session.claims.len()));
+        for claim in session.claims.iter().take(10) {
+            let marker = claim.marker.as_deref().unwrap_or("");
+            let line = format!("- {} {}\n", marker, claim.content);
+            manifest.record("Claims", ManifestItemKind::Claim, Some(&claim.id), &line);
+            md.push_str(&line);
+        }
+        if session.claims.len() > 10 {
+            md.push_str(&format!("- ... and {} more\n", session.claims.len() - 10));
+        }
+        md.push_str("\n");
+    }
+
+    // Load-bearing claims the evidence doesn't yet carry
+    if !evidence.under_supported.is_empty() {
+        md.push_str(&format!("## Under-supported Claims ({})\n\n", evidence.under_supported.len()));
+        md.push_str("Load-bearing claims with little evidential support. Gather evidence or weaken them.\n\n");
+        for id in &evidence.under_supported {
+            if let Some(claim) = session.claims.iter().find(|c| &c.id == id) {
+                let line = format!("- `{}` {}\n", claim.id, claim.content);
+                manifest.record("Under-supported Claims", ManifestItemKind::Claim, Some(&claim.id), &line);
+                md.push_str(&line);
+            }
+        }
+        md.push('\n');
+    }
+
+    // Open tensions
+    let open_tensions: Vec<_> = session.tensions.iter()
+        .filter(|t| t.resolution.is_none())
+        .collect();
+    if !open_tensions.is_empty() {
+        md.push_str(&format!("## Open Tensions ({} unresolved)\n\n", open_tensions.len()));
+        for tension in &open_tensions {
+            let line = format!("- {}\n", tension.description);
+            manifest.record("Open Tensions", ManifestItemKind::Tension, Some(&tension.id), &line);
+            md.push_str(&line);
+        }
+        md.push_str("\n");
+    }
+
+    // Collaborator critique for this pass to address
+    let review_notes = annotations::annotation_lines(session);
+    if !review_notes.is_empty() {
+        md.push_str(&format!("## Review Annotations ({} open)\n\n", review_notes.len()));
+        md.push_str("Address each in this pass; the reviewer resolves them.\n\n");
+        for (line, annotation) in review_notes.iter().zip(annotations::open_annotations(session)) {
+            let line = format!("- {}\n", line);
+            manifest.record("Review Annotations", ManifestItemKind::Annotation, Some(&annotation.id), &line);
+            md.push_str(&line);
+        }
+        md.push('\n');
+    }
+
+    // Thesis preview
+    if let Some(thesis) = &session.thesis {
+        md.push_str(&format!("## Current Thesis (confidence: {:.0}%)\n\n", thesis.confidence * 100.0));
+        md.push_str(&thesis.content);
+        md.push_str("\n\n");
+        manifest.record("Current Thesis", ManifestItemKind::Thesis, None, &thesis.content);
+    }
+
+    // Summary
+    if let Some(summary) = &session.summary {
+        md.push_str(&format!("## Summary\n\n{}\n\n", summary));
+        manifest.record("Summary", ManifestItemKind::Summary, None, summary);
+    }
+
+    // Lineage (when session was forked)
+    if let Some(parent_id) = &session.parent_session_id {
+        md.push_str("## Lineage\n\n");
+        md.push_str(&format!("This session was forked from parent session `{}`.\n", parent_id));
+        md.push_str("Claims, tensions, and thesis were carried forward. The conversation is fresh.\n\n");
+    }
+
+    // Session artifacts: distill output takes priority over in-session artifacts
+    let working_dir = PathBuf::from(&session.working_dir);
+    let distill_dir = working_dir.join(crate::distill::DISTILL_DIR);
+    let mut has_distill = false;
+
+    if distill_dir.exists() {
+        // Find the most recent run directory
+        if let Ok(entries) = fs::read_dir(&distill_dir) {
+            let mut dirs: Vec<_> = entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .collect();
+            dirs.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
+
+            if let Some(latest) = dirs.first() {
+                let run_dir = latest.path();
+
+                // memo-final.md → Prior Conviction Memo (up to 4000 chars)
+                let memo_path = run_dir.join(crate::distill::MEMO_FILE);
+                if memo_path.exists() {
+                    if let Ok(content) = fs::read_to_string(&memo_path) {
+                        let truncated: String = content.chars().take(4000).collect();
+                        md.push_str("## Prior Conviction Memo\n\n");
+                        md.push_str(&truncated);
+                        manifest.record("Prior Conviction Memo", ManifestItemKind::DistillArtifact, memo_path.to_str(), &truncated);
+                        if content.chars().count() > 4000 { md.push_str("\n\n[TRUNCATED]"); }
+                        md.push_str("\n\n");
+                        has_distill = true;
+                    }
+                }
+
+                // spine.yaml → Reasoning Spine (up to 2000 chars)
+                let spine_path = run_dir.join(crate::distill::SPINE_FILE);
+                if spine_path.exists() {
+                    if let Ok(content) = fs::read_to_string(&spine_path) {
+                        let truncated: String = content.chars().take(2000).collect();
+                        md.push_str("## Reasoning Spine\n\n```yaml\n");
+                        md.push_str(&truncated);
+                        manifest.record("Reasoning Spine", ManifestItemKind::DistillArtifact, spine_path.to_str(), &truncated);
+                        if content.chars().count() > 2000 { md.push_str("\n# [TRUNCATED]"); }
+                        md.push_str("\n```\n\n");
+                        has_distill = true;
+                    }
+                }
+
+                // thesis-history.md → Thesis Evolution (up to 2000 chars)
+                let thesis_path = run_dir.join(crate::distill::THESIS_HISTORY_FILE);
+                if thesis_path.exists() {
+                    if let Ok(content) = fs::read_to_string(&thesis_path) {
+                        let truncated: String = content.chars().take(2000).collect();
+                        md.push_str("## Thesis Evolution\n\n");
+                        md.push_str(&truncated);
+                        manifest.record("Thesis Evolution", ManifestItemKind::DistillArtifact, thesis_path.to_str(), &truncated);
+                        if content.chars().count() > 2000 { md.push_str("\n\n[TRUNCATED]"); }
+                        md.push_str("\n\n");
+                        has_distill = true;
+                    }
+                }
+            }
+        }
+    }
+
+    // In-session artifacts (lower priority, only if no distill output)
+    if !has_distill {
+        let state_path = working_dir.join("state.json");
+        if state_path.exists() {
+            if let Ok(content) = fs::read_to_string(&state_path) {
+                let truncated: String = content.chars().take(2000).collect();
+                md.push_str("## Previous Iteration State\n\n```json\n");
+                md.push_str(&truncated);
+                manifest.record("Previous Iteration State", ManifestItemKind::IterationState, state_path.to_str(), &truncated);
+                if content.chars().count() > 2000 { md.push_str("\n// [TRUNCATED]"); }
+                md.push_str("\n```\n\n");
+            }
+        }
+
+        let scratchpad_path = working_dir.join(scratchpad::SCRATCHPAD_FILE);
+        if scratchpad_path.exists() {
+            if let Ok(content) = fs::read_to_string(&scratchpad_path) {
+                let truncated: String = content.chars().take(3000).collect();
+                md.push_str("## Working Notes (Scratchpad)\n\n");
+                md.push_str(&truncated);
+                manifest.record("Working Notes (Scratchpad)", ManifestItemKind::Scratchpad, scratchpad_path.to_str(), &truncated);
+                if content.chars().count() > 3000 { md.push_str("\n\n[TRUNCATED]"); }
+                md.push_str("\n\n");
+            }
+        }
+    }
+
+    // Related Prior Work (from Chroma cross-session search)
+    if let Some(related) = related_context {
+        if !related.hits.is_empty() {
+            md.push_str("## Related Prior Work\n\n");
+            md.push_str("The following sessions contain related material:\n\n");
+            for hit in related.hits.iter().take(5) {
+                let line = format!("- **{}** ({}): {}\n", hit.session_id, hit.collection, hit.snippet);
+                let id = format!("{}/{}", hit.session_id, hit.collection);
+                manifest.record("Related Prior Work", ManifestItemKind::RelatedWork, Some(&id), &line);
+                md.push_str(&line);
+            }
+            md.push_str("\n");
+        }
+    }
+
+    md.push_str("---\n");
+    md.push_str("*This file is auto-generated by Dialectic. Do not edit manually.*\n");
+
+    md
+}
+
+/// Index a workspace's vault for this launch alone, if it isn't the one
+/// already configured. The configured vault is left as it is; failures
+/// are logged.
+async fn load_workspace_vault(vault_path: Option<&str>) -> Option<VaultIndex> {
+    let vault_path = vault_path?.to_string();
+    let current = crate::obsidian::indexer::with_vault_index(|v| v.vault_path.clone());
+    let wanted = Path::new(&vault_path).canonicalize().ok();
+    if wanted.is_none() || current == wanted {
+        return None;
+    }
+    tokio::task::spawn_blocking(move || match crate::obsidian::indexer::build_vault_index(&vault_path) {
+        Ok(vault) => Some(vault),
+        Err(e) => {
+            warn!(error = %e, vault = %vault_path, "Failed to load workspace vault");
+            None
+        }
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Attach files from the workspace's reference folders that the session
+/// doesn't already have. Returns the number attached.
+async fn attach_workspace_references(session: &mut Session, workspace: &WorkspaceConfig) -> usize {
+    let root = PathBuf::from(&session.working_dir);
+    let files = {
+        let workspace = workspace.clone();
+        tokio::task::spawn_blocking(move || workspace.reference_files(&root))
+            .await
+            .unwrap_or_default()
+    };
+
+    let mut attached = 0;
+    for file in files {
+        let path = file.to_string_lossy().to_string();
+        if session.reference_docs.iter().any(|d| d.path == path) {
+            continue;
+        }
+        match crate::documents::retriever::add_reference(
+            &session.id,
+            &path,
+            crate::documents::chunker::DocumentPersistence::Cached,
+        ).await {
+            Ok(doc) => {
+                session.reference_docs.push(SessionReferenceDoc {
+                    id: doc.id,
+                    filename: doc.filename,
+                    path: doc.path,
+                    token_count: doc.loaded_tokens,
+                    handling: doc.handling.as_str().to_string(),
+                    persistence: doc.persistence.as_str().to_string(),
+                    url: None,
+                    citation: doc.citation,
+                });
+                attached += 1;
+            }
+            Err(e) => warn!(error = %e, path = %path, "Failed to attach workspace reference"),
+        }
+    }
+    if attached > 0 {
+        info!(session_id = %session.id, attached = attached, "Attached workspace reference documents");
+    }
+    attached
+}
+
+#[tauri::command]
+pub async fn prepare_launch(app: AppHandle, session_id: String) -> Result<LaunchContext, SessionError> {
+    prepare_launch_in(get_app_data_path(&app)?, session_id).await
+}
+
+/// `prepare_launch` for a session under `app_data`
+pub async fn prepare_launch_in(app_data: PathBuf, session_id: String) -> Result<LaunchContext, SessionError> {
+    // Path computation (no I/O)
+    let session_dir = session_dir_in(&app_data, &session_id)?;
+    let session_path = session_dir.join("session.json");
+    let session_dir_str = session_dir.to_string_lossy().to_string();
+
+    // Phase 1: Read and update session (blocking file I/O on dedicated threadpool)
+    let mut session = {
+        let path = session_path.clone();
+        let dir = session_dir.clone();
+        let sid = session_id.clone();
+        tokio::task::spawn_blocking(move || -> Result<Session, SessionError> {
+            if !path.exists() {
+                return Err(SessionError::NotFound(sid));
+            }
+            let session = lock::update_session_file(&path, |session| {
+                session.last_resumed = Some(Utc::now());
+                Ok(())
+            })?;
+            // Ensure session directory exists (defensive against external deletion)
+            fs::create_dir_all(&dir)?;
+            Ok(session)
+        })
+        .await
+        .map_err(|e| SessionError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))??
+    };
+
+    // Phase 2: Best-effort Chroma search for related sessions (async, non-blocking)
+    let related_context = match crate::chroma::search::search_related_sessions(
+        &session.title,
+        session.summary.as_deref(),
+        &session.id,
+        5,
+    ).await {
+        Ok(results) if !results.hits.is_empty() => {
+            debug!(hits = results.hits.len(), "Found related sessions for launch context");
+            Some(results)
+        }
+        Ok(_) => None,
+        Err(e) => {
+            warn!(error = %e, "Related session search failed (Chroma may be offline), proceeding without");
+            None
+        }
+    };
+
+    // Phase 2a: Apply the project's .dialectic/config.toml (vault, reference folders)
+    let (prefs, workspace) = if session.is_project_local {
+        effective_preferences(Path::new(&session.working_dir))
+    } else {
+        (load_preferences(), None)
+    };
+    let mut new_docs = Vec::new();
+    let mut workspace_vault = None;
+    if let Some(workspace) = &workspace {
+        workspace_vault = load_workspace_vault(prefs.vault_path.as_deref()).await;
+        let attached = attach_workspace_references(&mut session, workspace).await;
+        new_docs = session.reference_docs[session.reference_docs.len() - attached..].to_vec();
+    }
+
+    // Phase 2b: Re-run classification against live signals and rebalance budgets
+    let mut audit_entries = Vec::new();
+    let reclassified = crate::context::reclassify::reclassify_for_launch(
+        &mut session,
+        related_context.as_ref(),
+        workspace_vault.as_ref(),
+    );
+    if let Some(change) = &reclassified {
+        info!(session_id = %session_id, from = ?change.from, to = ?change.to, "Session reclassified at launch");
+        audit_entries.push(audit::AuditEntry::new(
+            audit::AuditAction::Reclassified,
+            None,
+            serde_json::json!({ "from": change.from, "to": change.to }),
+        ));
+    }
+
+    // Phase 2c: Refresh per-claim evidence scores from the CDG
+    let mut evidence = crate::cdg::evidence::score_evidence(&session.claims, &session.cdg_edges);
+    let rescored = !session.cdg_edges.is_empty() && crate::cdg::evidence::apply_scores(&mut session.claims, &evidence);
+
+    // Apply just the launch's changes to the session as it is now: another
+    // writer may have saved while we were searching and attaching
+    if !new_docs.is_empty() || reclassified.is_some() || rescored {
+        let path = session_path;
+        let budget = session.context_budget.clone();
+        let reclassified = reclassified.is_some();
+        session = tokio::task::spawn_blocking(move || {
+            lock::update_session_file(&path, |live| {
+                for doc in new_docs {
+                    if !live.reference_docs.iter().any(|d| d.path == doc.path) {
+                        live.reference_docs.push(doc);
+                    }
+                }
+                if reclassified {
+                    live.context_budget = budget;
+                }
+                if !live.cdg_edges.is_empty() {
+                    let evidence = crate::cdg::evidence::score_evidence(&live.claims, &live.cdg_edges);
+                    crate::cdg::evidence::apply_scores(&mut live.claims, &evidence);
+                }
+                Ok(())
+            })
+        })
+        .await
+        .map_err(|e| SessionError::Io(std::io::Error::other(e)))??;
+        evidence = crate::cdg::evidence::score_evidence(&session.claims, &session.cdg_edges);
+    }
+
+    // Phase 3: Generate CLAUDE.md and the hook settings (pure) and write atomically (blocking I/O)
+    let launch_id = Ulid::new().to_string();
+    let mut recorder = ManifestRecorder::default();
+    let vault_path = workspace_vault.as_ref().map(|v| v.vault_path.clone());
+    let claude_md = generate_claude_md(
+        &session,
+        &session_dir_str,
+        related_context.as_ref(),
+        vault_path.as_deref(),
+        &evidence,
+        &mut recorder,
+    );
+    let context_manifest = recorder.finish(&launch_id, &session.id, &claude_md);
+    let hook_settings = serde_json::to_string_pretty(&hook_settings())?;
+    {
+        let dir = session_dir;
+        let content = claude_md;
+        let live = session.clone();
+        tokio::task::spawn_blocking(move || -> Result<(), SessionError> {
+            let tmp = dir.join("CLAUDE.md.tmp");
+            let target = dir.join("CLAUDE.md");
+            fs::write(&tmp, &content)?;
+            fs::rename(&tmp, &target)?;
+            if let Err(e) = manifest::write_manifest(&dir, &context_manifest) {
+                warn!(error = %e, "Failed to write context manifest");
+            }
+            atomic_write(&dir.join(HOOK_SETTINGS_FILE), &hook_settings)?;
+            env_file::write(&dir, &live)?;
+            Ok(())
+        })
+        .await
+        .map_err(|e| SessionError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))??;
+    }
+
+    // Check the CLI tool before handing the frontend a command that would fail in the PTY
+    let tool = prefs.cli_tool.clone();
+    let cli = tokio::task::spawn_blocking(move || crate::cli_tool::detect(&tool))
+        .await
+        .map_err(|e| SessionError::CliTool(e.to_string()))?;
+    let Some(cli_path) = cli.path.clone().filter(|_| cli.usable) else {
+        return Err(SessionError::CliTool(cli.problems.join("; ")));
+    };
+
+    // Phase 4: Build response (pure computation, no I/O)
+    let mut warnings = Vec::new();
+    let mut claude_command = vec![if cli.outside_path { cli_path } else { prefs.cli_tool }];
+    if let Some(conv_id) = session.latest_conversation_id() {
+        // Validate conversation_id contains only safe characters (alphanumeric, dash, underscore)
+        // to prevent shell metacharacter injection when the command is written to the PTY
+        if !conv_id.is_empty() && conv_id.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+            if cli.supports_resume {
+                claude_command.push("--resume".to_string());
+                claude_command.push(conv_id.to_string());
+            } else {
+                warnings.push(format!("{} does not support --resume; starting a new conversation", cli.tool));
+            }
+        }
+    }
+    // For project-local sessions, add --add-dir so Claude discovers session CLAUDE.md
+    if session.is_project_local {
+        if cli.supports_add_dir {
+            claude_command.push("--add-dir".to_string());
+            claude_command.push(session_dir_str.clone());
+        } else {
+            warnings.push(format!("{} does not support --add-dir; session CLAUDE.md won't be loaded", cli.tool));
+        }
+    }
+    // The SessionStart hook binds the conversation to this session
+    if cli.supports_settings && Path::new(&claude_command[0]).file_stem().is_some_and(|name| name == "claude") {
+        claude_command.push("--settings".to_string());
+        claude_command.push(Path::new(&session_dir_str).join(HOOK_SETTINGS_FILE).to_string_lossy().to_string());
+    }
+
+    let mut env_vars = HashMap::new();
+    env_vars.insert("DIALECTIC_SESSION_ID".to_string(), session.id.clone());
+    env_vars.insert("DIALECTIC_SESSION_DIR".to_string(), session_dir_str.clone());
+    // Hooks and the CLI they run stay on the profile the session lives in
+    if profile::current_profile() != profile::DEFAULT_PROFILE {
+        env_vars.insert(profile::PROFILE_ENV.to_string(), profile::current_profile().to_string());
+    }
+    // The MCP server and hooks search the project's vault, not the global one
+    if let Some(vault_path) = &vault_path {
+        env_vars.insert(VAULT_PATH_VAR.to_string(), vault_path.to_string_lossy().to_string());
+    }
+    env_vars.insert(
+        env_file::ENV_FILE_VAR.to_string(),
+        Path::new(&session_dir_str).join(env_file::ENV_FILE).to_string_lossy().to_string(),
+    );
+    for (name, value) in env_file::live_vars(&session) {
+        env_vars.insert(name.to_string(), value);
+    }
+
+    let working_dir = if session.is_project_local {
+        session.working_dir.clone()
+    } else {
+        session_dir_str.clone()
+    };
+
+    if let Some(budget) = session.context_budget.as_ref() {
+        crate::metrics::record_session_context(&session.id, budget.total_used());
+    }
+
+    let has_conversation = session.latest_conversation_id().is_some();
+    info!(session_id = %session_id, working_dir = %working_dir, has_conversation = has_conversation, "Prepared launch context");
+    let context = LaunchContext {
+        working_dir,
+        session_dir: session_dir_str,
+        conversation_id: session.latest_conversation_id().map(str::to_string),
+        claude_command,
+        env_vars,
+        warnings,
+        launch_id,
+    };
+    audit_entries.push(context.audit_entry());
+    let audit_dir = PathBuf::from(&context.session_dir);
+    tokio::task::spawn_blocking(move || audit::record(&audit_dir, &audit_entries));
+
+    Ok(context)
+}
+
+/// Claude Code settings written to each session dir and passed with `--settings`
+pub const HOOK_SETTINGS_FILE: &str = "claude-settings.json";
+
+/// SessionStart hook that records the conversation ID on the session named
+/// by `DIALECTIC_SESSION_ID` (see `dialectic session bind`). Binding this
+/// way is exact even when several sessions run at once; the JSONL scan in
+/// `capture_conversation_id` is the fallback. After a compaction the
+/// SessionStart hook also re-injects the session's HEAD, key evidence, open
+/// tensions and pinned chunks (see `dialectic compact-context`). The status
+/// line shows the same session's status and budget (see `dialectic statusline`).
+fn hook_settings() -> serde_json::Value {
+    serde_json::json!({
+        "hooks": {
+            "SessionStart": [
+                { "hooks": [{ "type": "command", "command": "dialectic session bind" }] },
+                { "matcher": "compact", "hooks": [{ "type": "command", "command": "dialectic compact-context" }] }
+            ]
+        },
+        "statusLine": { "type": "command", "command": "dialectic statusline" }
+    })
+}
+
+#[tauri::command]
+pub async fn capture_conversation_id(
+    app: AppHandle,
+    session_id: String,
+) -> Result<Option<String>, SessionError> {
+    let app_data = get_app_data_path(&app)?;
+    let conversation_id = capture_conversation_id_in(app_data.clone(), session_id.clone()).await?;
+
+    // Follow the transcript while the session runs
+    if let Some(conv_id) = conversation_id.clone() {
+        let session_dir = session_dir_in(&app_data, &session_id)?;
+        let session: Session = serde_json::from_str(&journal::read_recovered(&session_dir.join("session.json"))?)?;
+        let effective_dir = if session.is_project_local {
+            session.working_dir.clone()
+        } else {
+            session_dir.to_string_lossy().to_string()
+        };
+        let conversation = session
+            .conversations()
+            .into_iter()
+            .find(|c| c.id == conv_id)
+            .unwrap_or_else(|| ConversationRef { id: conv_id.clone(), started_at: Utc::now(), jsonl_path: None });
+        let jsonl_path = tokio::task::spawn_blocking(move || crate::chroma::jsonl_miner::locate_transcript(&conversation, &effective_dir))
+            .await
+            .map_err(|e| SessionError::Io(std::io::Error::other(e)))?;
+        match jsonl_path {
+            Some(path) => tailer::start(&session_id, &conv_id, path),
+            None => debug!(session_id = %session_id, conversation_id = %conv_id, "Transcript not found, not tailing"),
+        }
+    }
+
+    Ok(conversation_id)
+}
+
+/// `capture_conversation_id` for a session under `app_data`
+pub async fn capture_conversation_id_in(app_data: PathBuf, session_id: String) -> Result<Option<String>, SessionError> {
+    let session_dir = session_dir_in(&app_data, &session_id)?;
+    let session_path = session_dir.join("session.json");
+
+    // Read session to get working_dir
+    let session: Session = {
+        let content = journal::read_recovered(&session_path)
+            .map_err(|_| SessionError::NotFound(session_id.clone()))?;
+        serde_json::from_str(&content)?
+    };
+
+    // Already has a conversation (later ones are bound by the SessionStart hook)
+    if let Some(conv_id) = session.latest_conversation_id() {
+        return Ok(Some(conv_id.to_string()));
+    }
+
+    // Determine the effective working dir (same logic as prepare_launch)
+    let effective_dir = if session.is_project_local {
+        session.working_dir.clone()
+    } else {
+        session_dir.to_string_lossy().to_string()
+    };
+
+    // Use session.updated as the floor timestamp — only consider JSONL files modified after this
+    let session_updated = session.updated;
+
+    // Conversations already bound to other sessions can't be this one's
+    let sessions_dir = app_data.join("sessions");
+    let sid = session_id.clone();
+    let bound = tokio::task::spawn_blocking(move || bound_elsewhere(&sessions_dir, &sid))
+    .await
+    .map_err(|e| SessionError::Io(std::io::Error::other(e)))?;
+
+    let project_dir = crate::chroma::jsonl_miner::claude_code_project_dir(&effective_dir)
+        .ok_or_else(|| SessionError::InvalidPath("Cannot determine home directory".to_string()))?;
+
+    // Find the most recently modified .jsonl file, trying exact dir first then scanning all
+    let newest = tokio::task::spawn_blocking(move || -> Option<(String, PathBuf)> {
+        // Fast path: check the exact encoded working-dir project dir
+        if project_dir.exists() {
+            if let Some(result) = find_newest_jsonl(&project_dir, &session_updated, &bound) {
+                return Some(result);
+            }
+        }
+
+        // Broad scan: check ALL dirs under ~/.claude/projects/ with a 2-second timeout
+        let projects_base = crate::chroma::jsonl_miner::claude_projects_dir()?;
+        if !projects_base.exists() {
+            return None;
+        }
+
+        let start = std::time::Instant::now();
+        let timeout = std::time::Duration::from_secs(2);
+        let mut best_time = std::time::SystemTime::UNIX_EPOCH;
+        let mut best: Option<(String, PathBuf)> = None;
+
+        let entries = fs::read_dir(&projects_base).ok()?;
+        for entry in entries.flatten() {
+            if start.elapsed() > timeout {
+                debug!("Conversation ID scan timed out after 2s");
+                break;
+            }
+            let dir = entry.path();
+            if !dir.is_dir() { continue; }
+            if let Some((id, path)) = find_newest_jsonl(&dir, &session_updated, &bound) {
+                if let Ok(meta) = path.metadata() {
+                    if let Ok(modified) = meta.modified() {
+                        if modified > best_time {
+                            best_time = modified;
+                            best = Some((id, path));
+                        }
+                    }
+                }
+            }
+        }
+
+        best
+    })
+    .await
+    .map_err(|e| SessionError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+
+    let (conv_id, jsonl_path) = match newest {
+        Some((id, path)) => (id, path),
+        None => {
+            debug!(session_id = %session_id, "No Claude Code conversation files found");
+            return Ok(None);
+        }
+    };
+
+    // Validate the conversation ID looks like a UUID (safe chars only)
+    if !conv_id.chars().all(|c| c.is_alphanumeric() || c == '-') {
+        warn!(session_id = %session_id, conv_id = %conv_id, "Conversation ID contains invalid characters");
+        return Ok(None);
+    }
+
+    // Write it back to session.json
+    {
+        let path = session_path.clone();
+        let sid = session_id.clone();
+        let cid = conv_id.clone();
+        let jsonl = jsonl_path.to_string_lossy().to_string();
+        tokio::task::spawn_blocking(move || -> Result<(), SessionError> {
+            lock::update_session_file(&path, |session| {
+                session.add_conversation(&cid, Some(jsonl));
+                Ok(())
+            })?;
+            info!(session_id = %sid, conversation_id = %cid, "Captured conversation ID");
+            Ok(())
+        })
+        .await
+        .map_err(|e| SessionError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))??;
+    }
+
+    Ok(Some(conv_id))
+}
+
+/// Conversations recorded on sessions other than `session_id`
+fn bound_elsewhere(sessions_dir: &PathBuf, session_id: &str) -> HashSet<String> {
+    list_sessions_from_dir(sessions_dir)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|s| s.id != session_id)
+        .flat_map(|s| s.conversations())
+        .map(|c| c.id)
+        .collect()
+}
+
+/// Find the newest .jsonl file in a directory modified after the given timestamp,
+/// skipping conversation IDs in `exclude`. Returns (file_stem, full_path) if found.
+fn find_newest_jsonl(dir: &std::path::Path, after: &DateTime<Utc>, exclude: &HashSet<String>) -> Option<(String, PathBuf)> {
+    let after_system: std::time::SystemTime = (*after).into();
+    let mut newest_time = std::time::SystemTime::UNIX_EPOCH;
+    let mut newest: Option<(String, PathBuf)> = None;
+
+    let entries = fs::read_dir(dir).ok()?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().map(|e| e == "jsonl").unwrap_or(false) {
+            if let Ok(meta) = entry.metadata() {
+                if let Ok(modified) = meta.modified() {
+                    if modified > after_system && modified > newest_time {
+                        let Some(stem) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else { continue };
+                        if exclude.contains(&stem) {
+                            continue;
+                        }
+                        newest_time = modified;
+                        newest = Some((stem, path.clone()));
+                    }
+                }
+            }
+        }
+    }
+
+    newest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_capture_skips_conversations_bound_elsewhere() {
+        let root = std::env::temp_dir().join(format!("dialectic_capture_{}", ulid::Ulid::new()));
+        let sessions_dir = root.join("sessions");
+        for (id, conversations) in [("mine", vec!["conv-own"]), ("other", vec!["conv-bound"])] {
+            let mut session = test_session(serde_json::json!({ "id": id }));
+            for conversation in conversations {
+                session.add_conversation(conversation, None);
+            }
+            let dir = sessions_dir.join(id);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("session.json"), serde_json::to_string(&session).unwrap()).unwrap();
+        }
+
+        // Only the other session's conversations are off limits
+        let bound = bound_elsewhere(&sessions_dir, "mine");
+        assert_eq!(bound, HashSet::from(["conv-bound".to_string()]));
+
+        // The newest transcript belongs to the other session, so the older one is captured
+        let project_dir = root.join("project");
+        fs::create_dir_all(&project_dir).unwrap();
+        let now = SystemTime::now();
+        for (stem, age) in [("conv-new", 20), ("conv-bound", 10)] {
+            let file = fs::File::create(project_dir.join(format!("{}.jsonl", stem))).unwrap();
+            file.set_modified(now - Duration::from_secs(age)).unwrap();
+        }
+        let floor = Utc::now() - chrono::Duration::minutes(1);
+        let (id, path) = find_newest_jsonl(&project_dir, &floor, &bound).unwrap();
+        assert_eq!(id, "conv-new");
+        assert_eq!(path, project_dir.join("conv-new.jsonl"));
+        assert_eq!(find_newest_jsonl(&project_dir, &floor, &HashSet::new()).unwrap().0, "conv-bound");
+
+        fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,97 @@
+//! Mode Policy
+//!
+//! What a session's mode changes in the backend. Idea mode is exploratory:
+//! documents default to ephemeral and a session can be formed with
+//! tensions still open. Decision mode keeps documents cached for the
+//! session, only forms once every tension is resolved, and writes a
+//! decision record to the session dir when it does.
+
+use serde::{Deserialize, Serialize};
+
+use super::{load_session_cli, Session, SessionError, SessionMode, SessionStatus};
+use crate::documents::chunker::DocumentPersistence;
+
+/// Behaviors that depend on the session mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModePolicy {
+    /// Persistence of documents added without an explicit choice
+    pub default_persistence: DocumentPersistence,
+    /// Refuse Formed while any tension is unresolved
+    pub require_resolved_tensions: bool,
+    /// Write a decision record when the session is formed
+    pub decision_record: bool,
+}
+
+impl ModePolicy {
+    pub fn for_mode(mode: &SessionMode) -> Self {
+        match mode {
+            SessionMode::Idea => ModePolicy {
+                default_persistence: DocumentPersistence::Ephemeral,
+                require_resolved_tensions: false,
+                decision_record: false,
+            },
+            SessionMode::Decision => ModePolicy {
+                default_persistence: DocumentPersistence::Cached,
+                require_resolved_tensions: true,
+                decision_record: true,
+            },
+        }
+    }
+
+    /// Check that a session may move to `status`
+    pub fn check_transition(&self, session: &Session, status: &SessionStatus) -> Result<(), SessionError> {
+        if *status != SessionStatus::Formed || !self.require_resolved_tensions {
+            return Ok(());
+        }
+        let open = session.tensions.iter().filter(|t| t.resolution.is_none()).count();
+        if open > 0 {
+            return Err(SessionError::UnresolvedTensions(session.id.clone(), open));
+        }
+        Ok(())
+    }
+}
+
+/// Persistence for a document added to a session: the caller's choice,
+/// else the session mode's default
+pub fn document_persistence(session_id: &str, requested: Option<DocumentPersistence>) -> DocumentPersistence {
+    requested.unwrap_or_else(|| {
+        let mode = load_session_cli(session_id).map(|s| s.mode).unwrap_or_default();
+        ModePolicy::for_mode(&mode).default_persistence
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use chrono::Utc;
+
+    fn session(mode: &str, resolution: Option<&str>) -> Session {
+        test_session(serde_json::json!({
+            "id": "policy-test",
+            "title": "Expand into the EU?",
+            "mode": mode,
+            "created": Utc::now(),
+            "updated": Utc::now(),
+            "tensions": [
+                { "id": "t1", "claimAId": "c1", "claimBId": "c2", "description": "Speed vs readiness", "resolution": resolution, "createdAt": Utc::now() },
+            ],
+        }))
+    }
+
+    #[test]
+    fn test_decision_mode_requires_resolved_tensions() {
+        let open = session("decision", None);
+        let policy = ModePolicy::for_mode(&open.mode);
+        assert!(matches!(
+            policy.check_transition(&open, &SessionStatus::Formed),
+            Err(SessionError::UnresolvedTensions(_, 1))
+        ));
+        assert!(policy.check_transition(&open, &SessionStatus::Tensions).is_ok());
+        assert!(policy.check_transition(&session("decision", Some("Pilot first")), &SessionStatus::Formed).is_ok());
+
+        let idea = session("idea", None);
+        assert!(ModePolicy::for_mode(&idea.mode).check_transition(&idea, &SessionStatus::Formed).is_ok());
+    }
+}
@@ -0,0 +1,448 @@
+//! Corrupted Session Repair
+//!
+//! `session.json` files that fail to parse are skipped by listings. Repair
+//! tries, in order:
+//!
+//! 1. journal recovery (an interrupted write that never landed),
+//! 2. the newest temp/journal remnant that parses in full,
+//! 3. a lenient parse that closes truncated JSON and drops fields that no
+//!    longer deserialize, if the file still has every required field,
+//! 4. a rebuild from the session's CLAUDE.md (title, status, mode, claims,
+//!    thesis),
+//! 5. the lenient parse with missing required fields filled from defaults.
+//!
+//! The original file is always moved to `<app data>/quarantine/` before a
+//! repaired copy is written; unrecoverable files are quarantined as well so
+//! they stop failing every listing.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use super::journal::{self, RecoveryOutcome};
+use super::lock::lock_session_file;
+use super::{atomic_write, get_app_data_dir_cli, session_dir_in, Session, SessionError};
+
+/// Quarantine directory under the app data dir
+pub const QUARANTINE_DIR: &str = "quarantine";
+
+/// How a session was recovered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairStrategy {
+    /// session.json already parsed; nothing to do
+    Intact,
+    /// A journaled write was replayed
+    Journal,
+    /// Truncated or partially invalid JSON was salvaged
+    PartialParse,
+    /// Restored from a temp or journal remnant
+    Remnant,
+    /// Rebuilt from CLAUDE.md
+    ClaudeMd,
+    /// Nothing usable was found
+    Unrecoverable,
+}
+
+/// Result of a repair attempt
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairReport {
+    pub session_id: String,
+    pub strategy: RepairStrategy,
+    /// Top-level fields dropped or reset during a partial parse
+    pub dropped_fields: Vec<String>,
+    /// Where the original file was moved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quarantined: Option<String>,
+    pub dry_run: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<Session>,
+}
+
+/// Close strings, objects and arrays left open by a truncated write.
+/// Trailing partial tokens (a dangling key, comma or number) are dropped.
+pub fn close_truncated_json(text: &str) -> String {
+    let mut stack: Vec<char> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    // Byte offset just past the last complete value and the stack at that point
+    let mut last_good = 0;
+    let mut good_stack: Vec<char> = Vec::new();
+
+    for (i, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => {
+                    in_string = false;
+                    // A string is a complete value unless it's an object key
+                    if !text[i + 1..].trim_start().starts_with(':') {
+                        last_good = i + 1;
+                        good_stack = stack.clone();
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => {
+                stack.push(if c == '{' { '}' } else { ']' });
+                last_good = i + 1;
+                good_stack = stack.clone();
+            }
+            '}' | ']' => {
+                stack.pop();
+                last_good = i + 1;
+                good_stack = stack.clone();
+            }
+            c if c.is_ascii_alphanumeric() => {
+                // Literals and numbers end where the next delimiter starts
+                let next = text[i + 1..].chars().next();
+                if next.is_some_and(|n| matches!(n, ',' | '}' | ']' | ' ' | '\n' | '\r' | '\t')) {
+                    last_good = i + 1;
+                    good_stack = stack.clone();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut repaired = text[..last_good].trim_end().trim_end_matches(',').to_string();
+    while let Some(close) = good_stack.pop() {
+        repaired.push(close);
+    }
+    repaired
+}
+
+/// Minimal valid session fields for `session_id`
+fn skeleton(session_id: &str, session_dir: &Path, timestamp: DateTime<Utc>) -> Map<String, Value> {
+    match json!({
+        "id": session_id,
+        "title": format!("Recovered session {}", session_id),
+        "status": "backlog",
+        "mode": "idea",
+        "workingDir": session_dir.to_string_lossy(),
+        "isProjectLocal": false,
+        "created": timestamp,
+        "updated": timestamp,
+    }) {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Deserialize `raw` over `base`, dropping fields that don't fit the schema.
+/// Returns the session and the dropped field names.
+fn salvage(raw: Map<String, Value>, base: Map<String, Value>) -> Option<(Session, Vec<String>)> {
+    let mut accepted = base;
+    let mut dropped = Vec::new();
+    for (key, value) in raw {
+        let previous = accepted.insert(key.clone(), value);
+        if serde_json::from_value::<Session>(Value::Object(accepted.clone())).is_err() {
+            match previous {
+                Some(prev) => accepted.insert(key.clone(), prev),
+                None => accepted.remove(&key),
+            };
+            dropped.push(key);
+        }
+    }
+    let session = serde_json::from_value(Value::Object(accepted)).ok()?;
+    Some((session, dropped))
+}
+
+/// A lenient parse of `text` over `base`. Returns the session, the dropped
+/// fields, and the required fields that had to be taken from `base`.
+fn parse_lenient(text: &str, base: Map<String, Value>) -> Option<(Session, Vec<String>, Vec<String>)> {
+    let value = serde_json::from_str::<Value>(text)
+        .or_else(|_| serde_json::from_str::<Value>(&close_truncated_json(text)))
+        .ok()?;
+    let Value::Object(raw) = value else { return None };
+    let required: Vec<String> = base.keys().cloned().collect();
+    let supplied: Vec<String> = raw.keys().cloned().collect();
+    let (session, dropped) = salvage(raw, base)?;
+    let defaulted = required
+        .into_iter()
+        .filter(|key| !supplied.contains(key) || dropped.contains(key))
+        .collect();
+    Some((session, dropped, defaulted))
+}
+
+/// Newest temp or journal remnant that parses as a session
+fn newest_remnant(session_path: &Path) -> Option<Session> {
+    let dir = session_path.parent()?;
+    let mut candidates: Vec<(std::time::SystemTime, PathBuf)> = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            name.starts_with("session.json.") && (name.ends_with(".tmp") || name.ends_with(".journal"))
+        })
+        .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+        .collect();
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.0));
+
+    candidates.into_iter().find_map(|(_, path)| {
+        let content = fs::read_to_string(&path).ok()?;
+        // Journal records wrap the intended contents
+        let text = serde_json::from_str::<Value>(&content)
+            .ok()
+            .and_then(|v| v.get("contents").and_then(|c| c.as_str()).map(|s| s.to_string()))
+            .unwrap_or(content);
+        serde_json::from_str::<Session>(&text).ok()
+    })
+}
+
+/// Rebuild what CLAUDE.md still describes
+pub fn rebuild_from_claude_md(markdown: &str, base: Map<String, Value>) -> Option<Session> {
+    let field = |label: &str| {
+        markdown.lines().find_map(|line| {
+            line.strip_prefix(&format!("**{}:** ", label)).map(|rest| rest.trim().to_string())
+        })
+    };
+    let title = field("Session")?;
+
+    let mut raw = Map::new();
+    raw.insert("title".to_string(), json!(title));
+    if let Some(status) = field("Status").and_then(|s| s.split_whitespace().next().map(|w| w.to_string())) {
+        raw.insert("status".to_string(), json!(status));
+    }
+    if let Some(mode) = field("Mode") {
+        raw.insert("mode".to_string(), json!(mode));
+    }
+
+    let created = base.get("created").cloned().unwrap_or(Value::Null);
+    let mut section = "";
+    let mut claims = Vec::new();
+    let mut thesis: Option<(String, f32)> = None;
+    for line in markdown.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            section = heading;
+            if let Some(pct) = heading
+                .strip_prefix("Current Thesis (confidence: ")
+                .and_then(|r| r.strip_suffix("%)"))
+                .and_then(|p| p.parse::<f32>().ok())
+            {
+                thesis = Some((String::new(), pct / 100.0));
+            }
+            continue;
+        }
+        if section.starts_with("Claims") {
+            if let Some(item) = line.strip_prefix("- ").map(str::trim) {
+                if item.starts_with("... and ") {
+                    continue;
+                }
+                let (marker, content) = match item.split_once(' ') {
+                    Some((m, rest)) if m.starts_with('[') && m.ends_with(']') => (Some(m.to_string()), rest.trim()),
+                    _ => (None, item),
+                };
+                claims.push(json!({
+                    "id": format!("recovered-{}", claims.len() + 1),
+                    "content": content,
+                    "sourceId": "claude_md",
+                    "marker": marker,
+                    "createdAt": created,
+                }));
+            }
+        } else if section.starts_with("Current Thesis") {
+            if let Some((text, _)) = thesis.as_mut() {
+                if !line.trim().is_empty() {
+                    if !text.is_empty() {
+                        text.push('\n');
+                    }
+                    text.push_str(line);
+                }
+            }
+        }
+    }
+    raw.insert("claims".to_string(), Value::Array(claims));
+    if let Some((content, confidence)) = thesis.filter(|(t, _)| !t.is_empty()) {
+        raw.insert("thesis".to_string(), json!({"content": content, "confidence": confidence, "updatedAt": created}));
+    }
+
+    salvage(raw, base).map(|(session, _)| session)
+}
+
+fn quarantine(app_data: &Path, session_id: &str, session_path: &Path) -> Result<String, SessionError> {
+    let dir = app_data.join(QUARANTINE_DIR);
+    fs::create_dir_all(&dir)?;
+    let target = dir.join(format!("{}-{}-session.json", session_id, Utc::now().format("%Y%m%dT%H%M%S")));
+    fs::rename(session_path, &target).or_else(|_| {
+        // Cross-device: copy then remove
+        fs::copy(session_path, &target).and_then(|_| fs::remove_file(session_path))
+    })?;
+    warn!(session_id = %session_id, quarantined = %target.display(), "Quarantined corrupted session file");
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// Try to repair a session's session.json. With `dry_run` nothing is
+/// written or moved; the report shows what would be recovered.
+pub fn repair_session(session_id: &str, dry_run: bool) -> Result<RepairReport, SessionError> {
+    repair_session_in(&get_app_data_dir_cli()?, session_id, dry_run)
+}
+
+/// `repair_session` under an app data directory
+pub fn repair_session_in(app_data: &Path, session_id: &str, dry_run: bool) -> Result<RepairReport, SessionError> {
+    let id = session_id.strip_prefix("sess_").unwrap_or(session_id);
+    let session_dir = session_dir_in(app_data, id)?;
+    if !session_dir.is_dir() {
+        return Err(SessionError::NotFound(session_id.to_string()));
+    }
+    let session_path = session_dir.join("session.json");
+
+    let report = |strategy, session, dropped_fields, quarantined| RepairReport {
+        session_id: id.to_string(),
+        strategy,
+        dropped_fields,
+        quarantined,
+        dry_run,
+        session,
+    };
+
+    // Held from the read through the quarantine and write, so a save in
+    // between is neither quarantined nor overwritten
+    let _lock = if dry_run { None } else { Some(lock_session_file(&session_path)?) };
+    let original = fs::read_to_string(&session_path).ok();
+    if let Some(session) = original.as_deref().and_then(|c| serde_json::from_str::<Session>(c).ok()) {
+        return Ok(report(RepairStrategy::Intact, Some(session), Vec::new(), None));
+    }
+
+    // Read remnants before journal recovery cleans them up
+    let remnant = newest_remnant(&session_path);
+    if !dry_run && journal::recover(&session_path)? == RecoveryOutcome::Replayed {
+        if let Some(session) = fs::read_to_string(&session_path).ok().and_then(|c| serde_json::from_str(&c).ok()) {
+            return Ok(report(RepairStrategy::Journal, Some(session), Vec::new(), None));
+        }
+    }
+
+    let modified = fs::metadata(&session_path)
+        .and_then(|m| m.modified())
+        .map(DateTime::<Utc>::from)
+        .unwrap_or_else(|_| Utc::now());
+    let base = skeleton(id, &session_dir, modified);
+
+    // A salvage that had to default required fields (any truncated object
+    // prefix parses) only wins when nothing better is left
+    let (complete, partial) = match original.as_deref().and_then(|text| parse_lenient(text, base.clone())) {
+        Some((session, dropped, defaulted)) if defaulted.is_empty() => (Some((session, dropped)), None),
+        Some((session, mut dropped, defaulted)) => {
+            dropped.extend(defaulted.into_iter().filter(|key| !dropped.contains(key)).collect::<Vec<_>>());
+            (None, Some((session, dropped)))
+        }
+        None => (None, None),
+    };
+    let (strategy, recovered, dropped) = if let Some(session) = remnant {
+        (RepairStrategy::Remnant, Some(session), Vec::new())
+    } else if let Some((session, dropped)) = complete {
+        (RepairStrategy::PartialParse, Some(session), dropped)
+    } else if let Some(session) = fs::read_to_string(session_dir.join("CLAUDE.md"))
+        .ok()
+        .and_then(|md| rebuild_from_claude_md(&md, base))
+    {
+        (RepairStrategy::ClaudeMd, Some(session), Vec::new())
+    } else if let Some((session, dropped)) = partial {
+        (RepairStrategy::PartialParse, Some(session), dropped)
+    } else {
+        (RepairStrategy::Unrecoverable, None, Vec::new())
+    };
+
+    if dry_run {
+        return Ok(report(strategy, recovered, dropped, None));
+    }
+
+    let quarantined = if session_path.exists() {
+        Some(quarantine(app_data, id, &session_path)?)
+    } else {
+        None
+    };
+    if let Some(session) = &recovered {
+        atomic_write(&session_path, &serde_json::to_string_pretty(session)?)?;
+        info!(session_id = %id, strategy = ?strategy, dropped = ?dropped, "Repaired session");
+    }
+    Ok(report(strategy, recovered, dropped, quarantined))
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn repair_corrupted_session(session_id: String, dry_run: Option<bool>) -> Result<RepairReport, SessionError> {
+    repair_session(&session_id, dry_run.unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_close_truncated_json() {
+        let truncated = r#"{"id": "x", "claims": [{"id": "c1", "content": "ok"}, {"id": "c2", "cont"#;
+        let closed = close_truncated_json(truncated);
+        let value: Value = serde_json::from_str(&closed).unwrap();
+        assert_eq!(value["claims"].as_array().unwrap().len(), 2);
+        assert_eq!(value["claims"][1]["id"], "c2");
+
+        let dangling_key = r#"{"id": "x", "title":"#;
+        let value: Value = serde_json::from_str(&close_truncated_json(dangling_key)).unwrap();
+        assert_eq!(value, json!({"id": "x"}));
+    }
+
+    #[test]
+    fn test_lenient_parse_drops_bad_fields() {
+        let base = skeleton("abc", Path::new("/tmp/sess_abc"), Utc::now());
+        let text = r#"{"id": "abc", "title": "Rates", "status": "not-a-status", "mode": "decision", "claims": [{"id": "c1""#;
+        let (session, dropped, defaulted) = parse_lenient(text, base).unwrap();
+        assert_eq!(session.title, "Rates");
+        assert_eq!(session.mode, crate::session::SessionMode::Decision);
+        assert!(dropped.contains(&"status".to_string()));
+        assert!(dropped.contains(&"claims".to_string()));
+        assert!(defaulted.contains(&"status".to_string()) && defaulted.contains(&"workingDir".to_string()));
+    }
+
+    #[test]
+    fn test_repair_prefers_remnant_over_defaulted_salvage() {
+        let app_data = std::env::temp_dir().join(format!("dialectic_repair_{}", ulid::Ulid::new()));
+        let session_dir = app_data.join("sessions").join("sess_rep");
+        fs::create_dir_all(&session_dir).unwrap();
+        let good = crate::session::test_session(json!({ "id": "rep", "title": "Rates", "status": "tensions" }));
+        let text = serde_json::to_string_pretty(&good).unwrap();
+        // Cut off after the id: a lenient parse would default everything else
+        fs::write(session_dir.join("session.json"), &text[..text.find("\"title\"").unwrap()]).unwrap();
+        fs::write(session_dir.join("session.json.1.tmp"), &text).unwrap();
+
+        let dry = repair_session_in(&app_data, "rep", true).unwrap();
+        assert_eq!(dry.strategy, RepairStrategy::Remnant);
+        assert_eq!(dry.session.unwrap().title, "Rates");
+
+        // Without the remnant the defaulted salvage is the last resort
+        fs::remove_file(session_dir.join("session.json.1.tmp")).unwrap();
+        let report = repair_session_in(&app_data, "sess_rep", false).unwrap();
+        assert_eq!(report.strategy, RepairStrategy::PartialParse);
+        assert!(report.dropped_fields.contains(&"title".to_string()));
+        assert!(report.quarantined.is_some_and(|q| Path::new(&q).starts_with(app_data.join(QUARANTINE_DIR))));
+        let repaired: Session = serde_json::from_str(&fs::read_to_string(session_dir.join("session.json")).unwrap()).unwrap();
+        assert_eq!(repaired.id, "rep");
+        fs::remove_dir_all(&app_data).unwrap();
+    }
+
+    #[test]
+    fn test_rebuild_from_claude_md() {
+        let md = "# Dialectic Session Context\n\n**Session:** Fed cuts\n**ID:** abc\n**Status:** tensions (Stress-Test)\n**Mode:** idea\n\n\
+                  ## Claims (2 total)\n\n- [EVIDENCE] CPI is falling\n- Labour market cooling\n\n\
+                  ## Current Thesis (confidence: 70%)\n\nCuts by Q3.\n";
+        let base = skeleton("abc", Path::new("/tmp/sess_abc"), Utc::now());
+        let session = rebuild_from_claude_md(md, base).unwrap();
+        assert_eq!(session.title, "Fed cuts");
+        assert_eq!(session.status, crate::session::SessionStatus::Tensions);
+        assert_eq!(session.claims.len(), 2);
+        assert_eq!(session.claims[0].marker.as_deref(), Some("[EVIDENCE]"));
+        let thesis = session.thesis.unwrap();
+        assert_eq!(thesis.content, "Cuts by Q3.");
+        assert!((thesis.confidence - 0.7).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,204 @@
+//! Retention Policies
+//!
+//! Keeps the board from silting up. Sessions untouched for
+//! `staleAfterDays` are flagged as stale; optionally, stale Backlog sessions
+//! are tagged `icebox` and Formed sessions untouched for
+//! `archiveFormedAfterDays` are tagged `archived`, so the board can filter
+//! them out while they stay one tag removal away. Runs at startup and on
+//! `apply_retention_policies`. Tagging by policy doesn't count as touching
+//! the session: its `updated` time is left alone.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::{info, warn};
+
+use super::audit;
+use super::journal::read_recovered;
+use super::lock::{lock_session_file, remember_loaded};
+use super::{atomic_write, get_app_data_dir_cli, list_sessions_from_dir, session_dir_in, Session, SessionError, SessionStatus};
+use crate::config::preferences::load_preferences;
+
+pub const ICEBOX_TAG: &str = "icebox";
+pub const ARCHIVED_TAG: &str = "archived";
+
+/// The `retention` preference
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetentionPolicy {
+    /// Days without changes before a session is flagged stale; 0 disables
+    pub stale_after_days: u32,
+    /// Tag stale Backlog sessions `icebox`
+    pub icebox_backlog: bool,
+    /// Days without changes before a Formed session is tagged `archived`;
+    /// 0 disables
+    pub archive_formed_after_days: u32,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self { stale_after_days: 30, icebox_backlog: false, archive_formed_after_days: 0 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StaleSession {
+    pub session_id: String,
+    pub title: String,
+    pub status: SessionStatus,
+    pub days_idle: i64,
+}
+
+/// What a policy run found and did
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionReport {
+    /// Stale sessions still on the board, longest idle first
+    pub stale: Vec<StaleSession>,
+    /// Sessions newly tagged `icebox`
+    pub iceboxed: Vec<String>,
+    /// Sessions newly tagged `archived`
+    pub archived: Vec<String>,
+}
+
+fn idle_for(session: &Session, days: u32, now: DateTime<Utc>) -> bool {
+    days > 0 && now - session.updated >= Duration::days(days as i64)
+}
+
+/// Decide what `policy` does to `sessions`, without writing anything
+pub fn evaluate(sessions: &[Session], policy: &RetentionPolicy, now: DateTime<Utc>) -> RetentionReport {
+    let mut report = RetentionReport::default();
+    for session in sessions {
+        if session.tags.iter().any(|t| t == ICEBOX_TAG || t == ARCHIVED_TAG) {
+            continue;
+        }
+        if session.status == SessionStatus::Formed && idle_for(session, policy.archive_formed_after_days, now) {
+            report.archived.push(session.id.clone());
+        } else if session.status == SessionStatus::Backlog && policy.icebox_backlog && idle_for(session, policy.stale_after_days, now) {
+            report.iceboxed.push(session.id.clone());
+        } else if idle_for(session, policy.stale_after_days, now) {
+            report.stale.push(StaleSession {
+                session_id: session.id.clone(),
+                title: session.title.clone(),
+                status: session.status.clone(),
+                days_idle: (now - session.updated).num_days(),
+            });
+        }
+    }
+    report.stale.sort_by_key(|s| std::cmp::Reverse(s.days_idle));
+    report
+}
+
+/// Add `tag` under the session lock, keeping the session's `updated` time
+fn tag_quietly(session_dir: &Path, tag: &str) -> Result<(), SessionError> {
+    let session_path = session_dir.join("session.json");
+    let _lock = lock_session_file(&session_path)?;
+    let before: Session = serde_json::from_str(&read_recovered(&session_path)?)?;
+    if before.tags.iter().any(|t| t == tag) {
+        return Ok(());
+    }
+    let mut session = before.clone();
+    session.tags.push(tag.to_string());
+    session.tags.sort();
+    atomic_write(&session_path, &serde_json::to_string_pretty(&session)?)?;
+    audit::record_changes(session_dir, Some(&before), &session);
+    remember_loaded(&session);
+    Ok(())
+}
+
+/// Run `policy` over the sessions in `app_data`
+pub fn apply_in(app_data: &Path, policy: &RetentionPolicy, now: DateTime<Utc>) -> Result<RetentionReport, SessionError> {
+    let sessions = list_sessions_from_dir(&app_data.join("sessions"))?;
+    let mut report = evaluate(&sessions, policy, now);
+
+    let tag_all = |ids: &mut Vec<String>, tag: &str| {
+        ids.retain(|id| {
+            let result = session_dir_in(app_data, id).and_then(|dir| tag_quietly(&dir, tag));
+            if let Err(e) = &result {
+                warn!(session_id = %id, tag, error = %e, "Failed to apply retention tag");
+            }
+            result.is_ok()
+        });
+    };
+    tag_all(&mut report.iceboxed, ICEBOX_TAG);
+    tag_all(&mut report.archived, ARCHIVED_TAG);
+
+    info!(
+        stale = report.stale.len(),
+        iceboxed = report.iceboxed.len(),
+        archived = report.archived.len(),
+        "Applied retention policies"
+    );
+    Ok(report)
+}
+
+/// Startup run using the `retention` preference
+pub fn apply_on_startup(app_data: &Path) {
+    if let Err(e) = apply_in(app_data, &load_preferences().retention, Utc::now()) {
+        warn!(error = %e, "Failed to apply retention policies");
+    }
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn apply_retention_policies() -> Result<RetentionReport, SessionError> {
+    apply_in(&get_app_data_dir_cli()?, &load_preferences().retention, Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::audit::AuditAction;
+    use crate::session::test_session;
+    use serde_json::json;
+    use std::fs;
+
+    #[test]
+    fn test_apply_tags_without_touching_sessions() {
+        let app_data = std::env::temp_dir().join(format!("dialectic_retention_{}", ulid::Ulid::new()));
+        let now = Utc::now();
+        let write = |id: &str, status: &str, days_idle: i64, tags: &[&str]| {
+            let updated = now - Duration::days(days_idle);
+            let session = test_session(json!({
+                "id": id, "title": id, "status": status,
+                "created": updated, "updated": updated, "tags": tags,
+            }));
+            let session_dir = app_data.join("sessions").join(format!("sess_{}", id));
+            fs::create_dir_all(&session_dir).unwrap();
+            fs::write(session_dir.join("session.json"), serde_json::to_string(&session).unwrap()).unwrap();
+        };
+        write("fresh", "exploring", 2, &[]);
+        write("idle", "tensions", 45, &[]);
+        write("older", "exploring", 90, &[]);
+        write("spark", "backlog", 40, &[]);
+        write("shipped", "formed", 20, &[]);
+        write("parked", "backlog", 100, &[ICEBOX_TAG]);
+
+        let policy = RetentionPolicy { stale_after_days: 30, icebox_backlog: true, archive_formed_after_days: 14 };
+        let report = apply_in(&app_data, &policy, now).unwrap();
+        let stale: Vec<&str> = report.stale.iter().map(|s| s.session_id.as_str()).collect();
+        assert_eq!(stale, vec!["older", "idle"]);
+        assert_eq!(report.iceboxed, vec!["spark"]);
+        assert_eq!(report.archived, vec!["shipped"]);
+
+        let read = |id: &str| -> Session {
+            let path = app_data.join("sessions").join(format!("sess_{}", id)).join("session.json");
+            serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+        };
+        let spark = read("spark");
+        assert_eq!(spark.tags, vec![ICEBOX_TAG]);
+        assert_eq!((now - spark.updated).num_days(), 40);
+        assert_eq!(read("shipped").tags, vec![ARCHIVED_TAG]);
+        let log = audit::read_audit_log(&app_data.join("sessions").join("sess_spark")).unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].action, AuditAction::TagsChanged);
+        assert_eq!(log[0].detail["added"], json!([ICEBOX_TAG]));
+
+        // Tagged sessions are left alone on the next run
+        let again = apply_in(&app_data, &policy, now).unwrap();
+        assert!(again.iceboxed.is_empty() && again.archived.is_empty());
+        fs::remove_dir_all(&app_data).unwrap();
+    }
+}
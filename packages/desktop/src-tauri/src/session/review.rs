@@ -0,0 +1,404 @@
+//! Thesis Review Scheduling
+//!
+//! Revision triggers attached to a session's thesis, each with optional
+//! keywords and an optional review-by date. A formed thesis is due for
+//! review when a trigger's date has passed, or when one of its keywords
+//! shows up in vault notes or web sources indexed since the last review.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::AppHandle;
+use tracing::{debug, info, warn};
+use ulid::Ulid;
+
+use super::{
+    get_app_data_path, list_sessions_from_dir, modify_session, Session, SessionError,
+    SessionStatus,
+};
+use crate::chroma::client::get_client;
+use crate::chroma::store::VectorStore;
+use crate::chroma::collections::COLLECTION_WEB_SOURCES;
+use crate::obsidian::get_vault_index;
+
+/// Maximum keyword matches reported per trigger
+const MAX_MATCHES_PER_TRIGGER: usize = 5;
+/// Maximum web source chunks scanned per due-review check
+const MAX_WEB_SOURCES_SCANNED: u32 = 500;
+
+/// A condition under which a formed thesis should be revisited
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewTrigger {
+    pub id: String,
+    pub description: String,
+    /// Case-insensitive keywords matched against newly indexed content
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review_by: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_reviewed_at: Option<DateTime<Utc>>,
+}
+
+impl ReviewTrigger {
+    /// Content indexed after this instant counts as "new" for keyword matching
+    pub fn baseline(&self) -> DateTime<Utc> {
+        self.last_reviewed_at.unwrap_or(self.created_at)
+    }
+
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.review_by.is_some_and(|d| d <= now)
+    }
+
+    /// First keyword (lowercased) found in `text`, if any
+    pub fn match_keyword(&self, text: &str) -> Option<String> {
+        let haystack = text.to_lowercase();
+        self.keywords
+            .iter()
+            .map(|k| k.trim().to_lowercase())
+            .find(|k| !k.is_empty() && haystack.contains(k.as_str()))
+    }
+}
+
+/// Where a freshly indexed source came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FreshSourceKind {
+    VaultNote,
+    WebSource,
+}
+
+/// Recently indexed content that review triggers are matched against
+#[derive(Debug, Clone)]
+pub struct FreshSource {
+    pub kind: FreshSourceKind,
+    pub id: String,
+    pub title: String,
+    pub text: String,
+    pub indexed_at: DateTime<Utc>,
+}
+
+/// Why a trigger is flagged as due
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DueReason {
+    #[serde(rename_all = "camelCase")]
+    Overdue { review_by: DateTime<Utc> },
+    #[serde(rename_all = "camelCase")]
+    KeywordMatch {
+        keyword: String,
+        source_kind: FreshSourceKind,
+        source_id: String,
+        source_title: String,
+    },
+}
+
+/// A trigger on a formed session that needs attention
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DueReview {
+    pub session_id: String,
+    pub session_title: String,
+    pub trigger_id: String,
+    pub trigger_description: String,
+    pub reasons: Vec<DueReason>,
+}
+
+/// Input for adding a review trigger
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddReviewTriggerInput {
+    pub session_id: String,
+    pub description: String,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    pub review_by: Option<DateTime<Utc>>,
+}
+
+/// Flag triggers on formed sessions that are overdue or matched by fresh sources.
+///
+/// Pure function: callers gather `sources` (see `list_due_reviews`).
+pub fn find_due_reviews(
+    sessions: &[Session],
+    sources: &[FreshSource],
+    now: DateTime<Utc>,
+) -> Vec<DueReview> {
+    let mut due = Vec::new();
+
+    for session in sessions.iter().filter(|s| s.status == SessionStatus::Formed) {
+        for trigger in &session.review_triggers {
+            let mut reasons = Vec::new();
+
+            if let Some(review_by) = trigger.review_by.filter(|_| trigger.is_overdue(now)) {
+                reasons.push(DueReason::Overdue { review_by });
+            }
+
+            let baseline = trigger.baseline();
+            let keyword_matches = sources
+                .iter()
+                .filter(|s| s.indexed_at > baseline)
+                .filter_map(|source| {
+                    trigger.match_keyword(&source.text).map(|keyword| DueReason::KeywordMatch {
+                        keyword,
+                        source_kind: source.kind,
+                        source_id: source.id.clone(),
+                        source_title: source.title.clone(),
+                    })
+                })
+                .take(MAX_MATCHES_PER_TRIGGER);
+            reasons.extend(keyword_matches);
+
+            if !reasons.is_empty() {
+                due.push(DueReview {
+                    session_id: session.id.clone(),
+                    session_title: session.title.clone(),
+                    trigger_id: trigger.id.clone(),
+                    trigger_description: trigger.description.clone(),
+                    reasons,
+                });
+            }
+        }
+    }
+
+    due
+}
+
+/// Vault notes modified after `since` (empty if no vault is configured)
+fn fresh_vault_notes(since: DateTime<Utc>) -> Vec<FreshSource> {
+    let index = match get_vault_index() {
+        Ok(index) => index,
+        Err(_) => return Vec::new(),
+    };
+
+    index
+        .notes
+        .values()
+        .filter(|note| note.modified > since)
+        .map(|note| FreshSource {
+            kind: FreshSourceKind::VaultNote,
+            id: note.path.clone(),
+            title: note.title.clone(),
+            text: format!("{}\n{}\n{}", note.title, note.summary, note.tags.join(" ")),
+            indexed_at: note.modified,
+        })
+        .collect()
+}
+
+/// Web source chunks indexed after `since` (best-effort; empty if Chroma is down)
+async fn fresh_web_sources(since: DateTime<Utc>) -> Vec<FreshSource> {
+    let client = get_client();
+    let collection = match client.get_collection(COLLECTION_WEB_SOURCES).await {
+        Ok(c) => c,
+        Err(e) => {
+            debug!(error = %e, "web_sources collection unavailable for review check");
+            return Vec::new();
+        }
+    };
+
+    let result = match client
+        .get(
+            &collection.id,
+            None,
+            Some(json!({ "indexed_at": { "$gt": since.timestamp() } })),
+            None,
+            Some(MAX_WEB_SOURCES_SCANNED),
+            None,
+            Some(vec!["documents".to_string(), "metadatas".to_string()]),
+        )
+        .await
+    {
+        Ok(r) => r,
+        Err(e) => {
+            warn!(error = %e, "Failed to fetch recent web sources for review check");
+            return Vec::new();
+        }
+    };
+
+    let documents = result.documents.unwrap_or_default();
+    let metadatas = result.metadatas.unwrap_or_default();
+
+    result
+        .ids
+        .into_iter()
+        .enumerate()
+        .map(|(i, id)| {
+            let meta = metadatas.get(i).cloned().flatten().unwrap_or_default();
+            let title = meta
+                .get("title")
+                .or_else(|| meta.get("url"))
+                .and_then(|v| v.as_str())
+                .unwrap_or(&id)
+                .to_string();
+            let indexed_at = meta
+                .get("indexed_at")
+                .and_then(|v| v.as_i64())
+                .and_then(|ts| DateTime::from_timestamp(ts, 0))
+                .unwrap_or(since);
+            FreshSource {
+                kind: FreshSourceKind::WebSource,
+                id,
+                title,
+                text: documents.get(i).cloned().flatten().unwrap_or_default(),
+                indexed_at,
+            }
+        })
+        .collect()
+}
+
+/// Gather fresh vault notes and web sources, then flag due reviews.
+pub async fn list_due_reviews(sessions: &[Session]) -> Vec<DueReview> {
+    let earliest_baseline = sessions
+        .iter()
+        .filter(|s| s.status == SessionStatus::Formed)
+        .flat_map(|s| s.review_triggers.iter())
+        .filter(|t| !t.keywords.is_empty())
+        .map(|t| t.baseline())
+        .min();
+
+    let mut sources = Vec::new();
+    if let Some(since) = earliest_baseline {
+        sources.extend(fresh_vault_notes(since));
+        sources.extend(fresh_web_sources(since).await);
+    }
+
+    find_due_reviews(sessions, &sources, Utc::now())
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn add_review_trigger(app: AppHandle, input: AddReviewTriggerInput) -> Result<Session, SessionError> {
+    let trigger = ReviewTrigger {
+        id: Ulid::new().to_string(),
+        description: input.description,
+        keywords: input.keywords,
+        review_by: input.review_by,
+        created_at: Utc::now(),
+        last_reviewed_at: None,
+    };
+    let trigger_id = trigger.id.clone();
+    let session = modify_session(&app, &input.session_id, |s| {
+        s.review_triggers.push(trigger);
+        Ok(())
+    })?;
+    info!(session_id = %session.id, trigger_id = %trigger_id, "Added review trigger");
+    Ok(session)
+}
+
+#[tauri::command]
+pub fn remove_review_trigger(app: AppHandle, session_id: String, trigger_id: String) -> Result<Session, SessionError> {
+    modify_session(&app, &session_id, |s| {
+        let before = s.review_triggers.len();
+        s.review_triggers.retain(|t| t.id != trigger_id);
+        if s.review_triggers.len() == before {
+            return Err(SessionError::NotFound(trigger_id.clone()));
+        }
+        Ok(())
+    })
+}
+
+/// Mark a trigger as reviewed, optionally rescheduling its review-by date.
+#[tauri::command]
+pub fn mark_trigger_reviewed(
+    app: AppHandle,
+    session_id: String,
+    trigger_id: String,
+    next_review_by: Option<DateTime<Utc>>,
+) -> Result<Session, SessionError> {
+    modify_session(&app, &session_id, |s| {
+        let trigger = s
+            .review_triggers
+            .iter_mut()
+            .find(|t| t.id == trigger_id)
+            .ok_or_else(|| SessionError::NotFound(trigger_id.clone()))?;
+        trigger.last_reviewed_at = Some(Utc::now());
+        trigger.review_by = next_review_by;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub async fn get_due_reviews(app: AppHandle) -> Result<Vec<DueReview>, SessionError> {
+    let sessions_dir = get_app_data_path(&app)?.join("sessions");
+    let sessions = list_sessions_from_dir(&sessions_dir)?;
+    let due = list_due_reviews(&sessions).await;
+    debug!(count = due.len(), "Checked due reviews");
+    Ok(due)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use chrono::Duration;
+
+    fn make_session(status: SessionStatus, triggers: Vec<ReviewTrigger>) -> Session {
+        let mut session = test_session(json!({ "id": "s1", "title": "Test thesis" }));
+        session.status = status;
+        session.review_triggers = triggers;
+        session
+    }
+
+    fn make_trigger(keywords: &[&str], review_by: Option<DateTime<Utc>>, created_at: DateTime<Utc>) -> ReviewTrigger {
+        ReviewTrigger {
+            id: "t1".to_string(),
+            description: "Revisit if rates move".to_string(),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            review_by,
+            created_at,
+            last_reviewed_at: None,
+        }
+    }
+
+    fn make_source(text: &str, indexed_at: DateTime<Utc>) -> FreshSource {
+        FreshSource {
+            kind: FreshSourceKind::VaultNote,
+            id: "notes/rates.md".to_string(),
+            title: "rates".to_string(),
+            text: text.to_string(),
+            indexed_at,
+        }
+    }
+
+    #[test]
+    fn test_overdue_trigger_flagged() {
+        let now = Utc::now();
+        let trigger = make_trigger(&[], Some(now - Duration::days(1)), now - Duration::days(30));
+        let sessions = vec![make_session(SessionStatus::Formed, vec![trigger])];
+
+        let due = find_due_reviews(&sessions, &[], now);
+        assert_eq!(due.len(), 1);
+        assert!(matches!(due[0].reasons[0], DueReason::Overdue { .. }));
+    }
+
+    #[test]
+    fn test_keyword_match_only_after_baseline() {
+        let now = Utc::now();
+        let created = now - Duration::days(10);
+        let trigger = make_trigger(&["Interest Rates"], None, created);
+        let sessions = vec![make_session(SessionStatus::Formed, vec![trigger])];
+
+        let stale = [make_source("interest rates rose", created - Duration::days(1))];
+        assert!(find_due_reviews(&sessions, &stale, now).is_empty());
+
+        let fresh = [make_source("Central bank: interest rates rose", now)];
+        let due = find_due_reviews(&sessions, &fresh, now);
+        assert_eq!(due.len(), 1);
+        match &due[0].reasons[0] {
+            DueReason::KeywordMatch { keyword, .. } => assert_eq!(keyword, "interest rates"),
+            other => panic!("unexpected reason: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_non_formed_sessions_ignored() {
+        let now = Utc::now();
+        let trigger = make_trigger(&["rates"], Some(now - Duration::days(1)), now - Duration::days(30));
+        let sessions = vec![make_session(SessionStatus::Exploring, vec![trigger])];
+        let sources = [make_source("rates", now)];
+
+        assert!(find_due_reviews(&sessions, &sources, now).is_empty());
+    }
+}
@@ -0,0 +1,260 @@
+//! Session Scratchpad
+//!
+//! `scratchpad.md` in the session working directory holds running notes in
+//! `## ` sections. Three sections are structured — Core Claim, Open
+//! Questions and Next Actions — and always written in that order; any other
+//! sections (added by hand or by the agent) are kept after them untouched.
+//! The UI, the CLI (and through it hooks) all go through here, so edits
+//! from different writers merge instead of clobbering each other.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use tauri::AppHandle;
+use tracing::debug;
+
+use super::{atomic_write, Session, SessionError};
+
+pub const SCRATCHPAD_FILE: &str = "scratchpad.md";
+const TITLE: &str = "# Scratchpad";
+
+/// Serializes read-modify-write cycles within this process
+static WRITE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScratchpadSection {
+    CoreClaim,
+    OpenQuestions,
+    NextActions,
+}
+
+impl ScratchpadSection {
+    pub const ALL: [ScratchpadSection; 3] = [
+        ScratchpadSection::CoreClaim,
+        ScratchpadSection::OpenQuestions,
+        ScratchpadSection::NextActions,
+    ];
+
+    pub fn heading(self) -> &'static str {
+        match self {
+            ScratchpadSection::CoreClaim => "Core Claim",
+            ScratchpadSection::OpenQuestions => "Open Questions",
+            ScratchpadSection::NextActions => "Next Actions",
+        }
+    }
+
+    fn from_heading(heading: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.heading().eq_ignore_ascii_case(heading.trim()))
+    }
+
+    /// List sections get one bullet per appended entry
+    fn is_list(self) -> bool {
+        !matches!(self, ScratchpadSection::CoreClaim)
+    }
+}
+
+/// A section that isn't one of the structured ones
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtraSection {
+    pub heading: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scratchpad {
+    pub core_claim: String,
+    pub open_questions: String,
+    pub next_actions: String,
+    /// Text before the first section, other than the title
+    pub preamble: String,
+    pub extra_sections: Vec<ExtraSection>,
+}
+
+impl Scratchpad {
+    pub fn parse(markdown: &str) -> Self {
+        let mut pad = Scratchpad::default();
+        let mut heading: Option<String> = None;
+        let mut body: Vec<&str> = Vec::new();
+
+        let flush = |pad: &mut Scratchpad, heading: Option<String>, body: &mut Vec<&str>| {
+            let content = body.join("\n").trim().to_string();
+            body.clear();
+            match heading {
+                None => pad.preamble = content,
+                Some(h) => match ScratchpadSection::from_heading(&h) {
+                    Some(section) => *pad.section_mut(section) = content,
+                    None => pad.extra_sections.push(ExtraSection { heading: h, content }),
+                },
+            }
+        };
+
+        for line in markdown.lines() {
+            if let Some(h) = line.strip_prefix("## ") {
+                flush(&mut pad, heading.take(), &mut body);
+                heading = Some(h.trim().to_string());
+            } else if heading.is_some() || line.trim() != TITLE {
+                body.push(line);
+            }
+        }
+        flush(&mut pad, heading, &mut body);
+        pad
+    }
+
+    pub fn section(&self, section: ScratchpadSection) -> &str {
+        match section {
+            ScratchpadSection::CoreClaim => &self.core_claim,
+            ScratchpadSection::OpenQuestions => &self.open_questions,
+            ScratchpadSection::NextActions => &self.next_actions,
+        }
+    }
+
+    fn section_mut(&mut self, section: ScratchpadSection) -> &mut String {
+        match section {
+            ScratchpadSection::CoreClaim => &mut self.core_claim,
+            ScratchpadSection::OpenQuestions => &mut self.open_questions,
+            ScratchpadSection::NextActions => &mut self.next_actions,
+        }
+    }
+
+    /// Add `text` to the end of a section
+    pub fn append(&mut self, section: ScratchpadSection, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        let entry = if section.is_list() && !text.starts_with("- ") {
+            format!("- {}", text)
+        } else {
+            text.to_string()
+        };
+        let current = self.section_mut(section);
+        if !current.is_empty() {
+            current.push_str(if section.is_list() { "\n" } else { "\n\n" });
+        }
+        current.push_str(&entry);
+    }
+
+    pub fn replace(&mut self, section: ScratchpadSection, content: &str) {
+        *self.section_mut(section) = content.trim().to_string();
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("{}\n\n", TITLE);
+        if !self.preamble.is_empty() {
+            md.push_str(&self.preamble);
+            md.push_str("\n\n");
+        }
+        for section in ScratchpadSection::ALL {
+            md.push_str(&format!("## {}\n\n", section.heading()));
+            let content = self.section(section);
+            if !content.is_empty() {
+                md.push_str(content);
+                md.push_str("\n\n");
+            }
+        }
+        for extra in &self.extra_sections {
+            md.push_str(&format!("## {}\n\n", extra.heading));
+            if !extra.content.is_empty() {
+                md.push_str(&extra.content);
+                md.push_str("\n\n");
+            }
+        }
+        md.truncate(md.trim_end().len());
+        md.push('\n');
+        md
+    }
+}
+
+pub fn scratchpad_path(session: &Session) -> PathBuf {
+    Path::new(&session.working_dir).join(SCRATCHPAD_FILE)
+}
+
+/// Read a session's scratchpad (empty if it doesn't exist yet)
+pub fn read(session: &Session) -> Result<Scratchpad, SessionError> {
+    match fs::read_to_string(scratchpad_path(session)) {
+        Ok(content) => Ok(Scratchpad::parse(&content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Scratchpad::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Apply `f` to the scratchpad and write it back atomically
+pub fn modify(session: &Session, f: impl FnOnce(&mut Scratchpad)) -> Result<Scratchpad, SessionError> {
+    let _guard = WRITE_LOCK.lock();
+    let mut pad = read(session)?;
+    f(&mut pad);
+    atomic_write(&scratchpad_path(session), &pad.to_markdown())?;
+    debug!(session_id = %session.id, "Updated scratchpad");
+    Ok(pad)
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn scratchpad_get(app: AppHandle, session_id: String) -> Result<Scratchpad, SessionError> {
+    read(&super::load_session(app, session_id)?)
+}
+
+#[tauri::command]
+pub fn scratchpad_append(
+    app: AppHandle,
+    session_id: String,
+    section: ScratchpadSection,
+    text: String,
+) -> Result<Scratchpad, SessionError> {
+    let session = super::load_session(app, session_id)?;
+    modify(&session, |pad| pad.append(section, &text))
+}
+
+#[tauri::command]
+pub fn scratchpad_replace_section(
+    app: AppHandle,
+    session_id: String,
+    section: ScratchpadSection,
+    content: String,
+) -> Result<Scratchpad, SessionError> {
+    let session = super::load_session(app, session_id)?;
+    modify(&session, |pad| pad.replace(section, &content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keeps_unknown_sections() {
+        let md = "# Scratchpad\n\nintro\n\n## Next Actions\n\n- ship\n\n## Ideas\n\nfree text\n\n## core claim\n\nPricing wins";
+        let pad = Scratchpad::parse(md);
+        assert_eq!(pad.preamble, "intro");
+        assert_eq!(pad.core_claim, "Pricing wins");
+        assert_eq!(pad.next_actions, "- ship");
+        assert_eq!(pad.extra_sections, vec![ExtraSection { heading: "Ideas".into(), content: "free text".into() }]);
+
+        // Structured sections are written first, in canonical order
+        let rendered = pad.to_markdown();
+        assert!(rendered.starts_with("# Scratchpad\n\nintro\n\n## Core Claim\n\nPricing wins\n\n## Open Questions\n\n## Next Actions"));
+        assert!(rendered.ends_with("## Ideas\n\nfree text\n"));
+        assert_eq!(Scratchpad::parse(&rendered), pad);
+    }
+
+    #[test]
+    fn test_append_and_replace() {
+        let mut pad = Scratchpad::default();
+        pad.append(ScratchpadSection::OpenQuestions, "Who pays?");
+        pad.append(ScratchpadSection::OpenQuestions, "- When?");
+        pad.append(ScratchpadSection::CoreClaim, "First");
+        pad.append(ScratchpadSection::CoreClaim, "Second");
+        pad.append(ScratchpadSection::NextActions, "   ");
+        assert_eq!(pad.open_questions, "- Who pays?\n- When?");
+        assert_eq!(pad.core_claim, "First\n\nSecond");
+        assert!(pad.next_actions.is_empty());
+
+        pad.replace(ScratchpadSection::CoreClaim, " Revised \n");
+        assert_eq!(pad.core_claim, "Revised");
+    }
+}
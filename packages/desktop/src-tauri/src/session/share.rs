@@ -0,0 +1,229 @@
+//! Shareable Sessions
+//!
+//! A session bundle to hand to a collaborator, with what shouldn't leave
+//! the machine taken out: local paths (the working directory and the home
+//! directory, wherever they appear) are masked, Claude conversation IDs and
+//! transcript paths dropped, terminal state cleared, and chosen claims
+//! removed together with the tensions, CDG edges, key evidence and
+//! annotations that refer to them.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use tauri::AppHandle;
+use tracing::info;
+
+use super::annotations::AnnotationTarget;
+use super::{Session, SessionError, TerminalState};
+
+pub const BUNDLE_FORMAT: &str = "dialectic-share";
+pub const BUNDLE_VERSION: u32 = 1;
+/// Stands in for the session's working directory
+pub const WORKDIR_PLACEHOLDER: &str = "<workdir>";
+/// Stands in for the user's home directory
+pub const HOME_PLACEHOLDER: &str = "~";
+
+/// What to take out of a shared session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Redactions {
+    /// Mask the working directory and home directory in every field
+    pub paths: bool,
+    /// Drop Claude conversation IDs and transcript paths
+    pub conversation_ids: bool,
+    /// Claims to remove
+    pub claim_ids: Vec<String>,
+    /// Remove every claim with one of these markers, e.g. `[PRIVATE]`
+    pub claim_markers: Vec<String>,
+}
+
+impl Default for Redactions {
+    fn default() -> Self {
+        Self { paths: true, conversation_ids: true, claim_ids: Vec::new(), claim_markers: Vec::new() }
+    }
+}
+
+/// What was taken out
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionSummary {
+    pub paths_masked: usize,
+    pub conversations_removed: usize,
+    pub claims_removed: usize,
+    pub tensions_removed: usize,
+    pub edges_removed: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareBundle {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub session: Session,
+    pub redacted: RedactionSummary,
+}
+
+/// Replace `from` in every string of `value`, counting the strings changed
+fn mask_strings(value: &mut Value, from: &str, to: &str) -> usize {
+    match value {
+        Value::String(s) if s.contains(from) => {
+            *s = s.replace(from, to);
+            1
+        }
+        Value::Array(items) => items.iter_mut().map(|v| mask_strings(v, from, to)).sum(),
+        Value::Object(map) => map.values_mut().map(|v| mask_strings(v, from, to)).sum(),
+        _ => 0,
+    }
+}
+
+fn remove_claims(session: &mut Session, redactions: &Redactions, summary: &mut RedactionSummary) {
+    let removed: HashSet<String> = session
+        .claims
+        .iter()
+        .filter(|c| {
+            redactions.claim_ids.contains(&c.id)
+                || c.marker.as_ref().is_some_and(|m| redactions.claim_markers.iter().any(|r| r.eq_ignore_ascii_case(m)))
+        })
+        .map(|c| c.id.clone())
+        .collect();
+    if removed.is_empty() {
+        return;
+    }
+
+    session.claims.retain(|c| !removed.contains(&c.id));
+    let tensions = session.tensions.len();
+    session.tensions.retain(|t| !removed.contains(&t.claim_a_id) && !removed.contains(&t.claim_b_id));
+    let edges = session.cdg_edges.len();
+    session.cdg_edges.retain(|e| !removed.contains(&e.source_claim_id) && !removed.contains(&e.target_claim_id));
+    if let Some(trail) = session.paper_trail.as_mut() {
+        trail.key_evidence.retain(|k| !removed.contains(&k.id));
+    }
+    for change in &mut session.source_changes {
+        change.claim_ids.retain(|id| !removed.contains(id));
+    }
+    // Annotations on what's gone would point nowhere
+    session.annotations.retain(|a| match &a.target {
+        AnnotationTarget::Claim { id } => !removed.contains(id),
+        AnnotationTarget::Tension { id } => session.tensions.iter().any(|t| &t.id == id),
+        AnnotationTarget::Thesis => true,
+    });
+
+    summary.claims_removed = removed.len();
+    summary.tensions_removed = tensions - session.tensions.len();
+    summary.edges_removed = edges - session.cdg_edges.len();
+}
+
+/// A redacted copy of `session`; `home` is the home directory to mask
+pub fn redact(session: &Session, redactions: &Redactions, home: Option<&str>) -> Result<(Session, RedactionSummary), SessionError> {
+    let mut summary = RedactionSummary::default();
+    let mut shared = session.clone();
+    shared.terminal = TerminalState::default();
+
+    if redactions.conversation_ids {
+        summary.conversations_removed = shared.conversations().len();
+        shared.conversation_id = None;
+        shared.conversation_ids.clear();
+    }
+    remove_claims(&mut shared, redactions, &mut summary);
+
+    if redactions.paths {
+        let mut value = serde_json::to_value(&shared)?;
+        // Working dir first: it usually sits under home
+        let working_dir = shared.working_dir.trim_end_matches('/');
+        if !working_dir.is_empty() {
+            summary.paths_masked += mask_strings(&mut value, working_dir, WORKDIR_PLACEHOLDER);
+        }
+        if let Some(home) = home.map(|h| h.trim_end_matches('/')).filter(|h| !h.is_empty()) {
+            summary.paths_masked += mask_strings(&mut value, home, HOME_PLACEHOLDER);
+        }
+        shared = serde_json::from_value(value)?;
+    }
+
+    Ok((shared, summary))
+}
+
+/// Build the share bundle for `session`
+pub fn build_bundle(session: &Session, redactions: &Redactions) -> Result<ShareBundle, SessionError> {
+    let home = dirs::home_dir().map(|h| h.to_string_lossy().to_string());
+    let (shared, redacted) = redact(session, redactions, home.as_deref())?;
+    info!(
+        session_id = %session.id,
+        paths = redacted.paths_masked,
+        claims = redacted.claims_removed,
+        "Built shareable session bundle"
+    );
+    Ok(ShareBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at: Utc::now(),
+        session: shared,
+        redacted,
+    })
+}
+
+// ============ TAURI COMMANDS ============
+
+/// Export a session for sharing; without `redactions`, paths and
+/// conversation IDs are masked and every claim is kept
+#[tauri::command]
+pub fn export_shareable(
+    app: AppHandle,
+    session_id: String,
+    redactions: Option<Redactions>,
+) -> Result<ShareBundle, SessionError> {
+    let session = super::load_session(app, session_id)?;
+    build_bundle(&session, &redactions.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_masks_paths_and_removes_claims() {
+        let session: Session = test_session(json!({
+            "id": "share", "title": "Share", "status": "tensions",
+            "workingDir": "/home/ana/projects/acme", "isProjectLocal": true,
+            "conversationIds": [{ "id": "conv-1", "startedAt": "2026-01-01T00:00:00Z", "jsonlPath": "/home/ana/.claude/conv-1.jsonl" }],
+            "terminal": { "pid": 42, "running": true, "lastCommand": "claude" },
+            "contextFiles": [
+                { "id": "f1", "filename": "plan.md", "path": "/home/ana/projects/acme/plan.md", "addedAt": "2026-01-01T00:00:00Z" },
+                { "id": "f2", "filename": "notes.md", "path": "/home/ana/notes.md", "addedAt": "2026-01-01T00:00:00Z" },
+            ],
+            "claims": [
+                { "id": "c1", "content": "Margins expand", "sourceId": "s", "marker": "[INSIGHT]", "createdAt": "2026-01-01T00:00:00Z" },
+                { "id": "c2", "content": "Salary data", "sourceId": "s", "marker": "[PRIVATE]", "createdAt": "2026-01-01T00:00:00Z" },
+                { "id": "c3", "content": "Costs rise", "sourceId": "s", "marker": "[RISK]", "createdAt": "2026-01-01T00:00:00Z" },
+            ],
+            "tensions": [
+                { "id": "t1", "claimAId": "c1", "claimBId": "c2", "description": "d", "resolution": null, "createdAt": "2026-01-01T00:00:00Z" },
+                { "id": "t2", "claimAId": "c1", "claimBId": "c3", "description": "d", "resolution": null, "createdAt": "2026-01-01T00:00:00Z" },
+            ],
+        }));
+
+        let redactions = Redactions { claim_markers: vec!["[private]".to_string()], ..Default::default() };
+        let (shared, summary) = redact(&session, &redactions, Some("/home/ana")).unwrap();
+        assert_eq!(shared.working_dir, WORKDIR_PLACEHOLDER);
+        assert_eq!(shared.context_files[0].path, "<workdir>/plan.md");
+        assert_eq!(shared.context_files[1].path, "~/notes.md");
+        assert!(shared.conversations().is_empty());
+        assert!(shared.terminal.pid.is_none());
+        assert_eq!(shared.claims.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(), vec!["c1", "c3"]);
+        assert_eq!(shared.tensions.len(), 1);
+        assert_eq!(summary.claims_removed, 1);
+        assert_eq!(summary.tensions_removed, 1);
+        assert_eq!(summary.conversations_removed, 1);
+        assert_eq!(summary.paths_masked, 3);
+
+        // Nothing redacted but terminal state
+        let keep = Redactions { paths: false, conversation_ids: false, ..Default::default() };
+        let (shared, summary) = redact(&session, &keep, Some("/home/ana")).unwrap();
+        assert_eq!(shared.working_dir, session.working_dir);
+        assert_eq!(shared.conversations().len(), 1);
+        assert_eq!(summary.claims_removed, 0);
+    }
+}
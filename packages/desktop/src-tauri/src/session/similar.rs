@@ -0,0 +1,146 @@
+//! Similar Sessions
+//!
+//! Checks a new session against existing ones so the UI can offer to
+//! continue an earlier line of inquiry instead. Titles, summaries and theses
+//! are compared locally by embedding their content words; the
+//! related-sessions search adds sessions whose memories or documents match.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::debug;
+
+use super::{list_sessions_cli, Session};
+use crate::chroma::search::search_related_sessions;
+use crate::documents::embeddings::{dot, generate_embedding};
+use crate::knowledge::content_words;
+
+/// Local similarity at which a session is reported
+const LOCAL_THRESHOLD: f32 = 0.6;
+/// Related-search relevance at which a session is reported
+const RELATED_THRESHOLD: f32 = 0.7;
+const RELATED_RESULTS: u32 = 10;
+const MAX_SIMILAR: usize = 5;
+
+/// An existing session resembling a new one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimilarSession {
+    pub session_id: String,
+    pub title: String,
+    pub status: String,
+    pub similarity: f32,
+    /// What matched: `title`, `summary`, `thesis` or the collection searched
+    pub matched: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+}
+
+fn embed(text: &str) -> Option<Vec<f32>> {
+    let words: Vec<String> = content_words(text).into_iter().flatten().collect();
+    if words.is_empty() {
+        return None;
+    }
+    generate_embedding(&words.join(" ")).ok()
+}
+
+fn similar(session: &Session, similarity: f32, matched: &str, snippet: Option<String>) -> SimilarSession {
+    SimilarSession {
+        session_id: session.id.clone(),
+        title: session.title.clone(),
+        status: format!("{:?}", session.status).to_lowercase(),
+        similarity,
+        matched: matched.to_string(),
+        snippet,
+    }
+}
+
+/// Sessions whose title, summary or thesis resemble `title` and `summary`
+pub fn local_matches(title: &str, summary: Option<&str>, sessions: &[Session]) -> Vec<SimilarSession> {
+    let Some(query) = embed(&format!("{}\n{}", title, summary.unwrap_or_default())) else { return Vec::new() };
+    sessions
+        .iter()
+        .filter_map(|session| {
+            let fields = [
+                ("title", Some(session.title.as_str())),
+                ("summary", session.summary.as_deref()),
+                ("thesis", session.thesis.as_ref().map(|t| t.content.as_str())),
+            ];
+            fields
+                .into_iter()
+                .filter_map(|(name, text)| Some((name, text?, dot(&query, &embed(text?)?))))
+                .filter(|(_, _, score)| *score >= LOCAL_THRESHOLD)
+                .max_by(|a, b| a.2.total_cmp(&b.2))
+                .map(|(name, text, score)| {
+                    let snippet = (name != "title").then(|| text.chars().take(120).collect());
+                    similar(session, score, name, snippet)
+                })
+        })
+        .collect()
+}
+
+/// Existing sessions most like a new one, best first. Best-effort: the
+/// related-sessions search is skipped when Chroma is unavailable.
+pub async fn find_similar_sessions(title: &str, summary: Option<&str>, exclude_session_id: &str) -> Vec<SimilarSession> {
+    let sessions: Vec<Session> = list_sessions_cli()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|s| s.id != exclude_session_id)
+        .collect();
+    let mut best: HashMap<String, SimilarSession> = HashMap::new();
+    let mut keep = |found: SimilarSession| {
+        if best.get(&found.session_id).is_none_or(|b| b.similarity < found.similarity) {
+            best.insert(found.session_id.clone(), found);
+        }
+    };
+    for found in local_matches(title, summary, &sessions) {
+        keep(found);
+    }
+
+    match search_related_sessions(title, summary, exclude_session_id, RELATED_RESULTS).await {
+        Ok(related) => {
+            for hit in related.hits.into_iter().filter(|h| h.relevance >= RELATED_THRESHOLD) {
+                if let Some(session) = sessions.iter().find(|s| s.id == hit.session_id) {
+                    keep(similar(session, hit.relevance, &hit.collection, Some(hit.snippet)));
+                }
+            }
+        }
+        Err(e) => debug!(error = %e, "Related-session search unavailable for similarity check"),
+    }
+
+    let mut found: Vec<SimilarSession> = best.into_values().collect();
+    found.sort_by(|a, b| b.similarity.total_cmp(&a.similarity).then_with(|| a.session_id.cmp(&b.session_id)));
+    found.truncate(MAX_SIMILAR);
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use serde_json::json;
+
+    #[test]
+    fn test_local_matches() {
+        let now = chrono::Utc::now();
+        let sessions: Vec<Session> = [
+            ("a", "Subscription pricing power", None, None),
+            ("b", "Rate cuts", Some("Central bank easing path"), None),
+            ("c", "Market entry", None, Some("Subscription pricing power erodes in Europe")),
+        ]
+        .into_iter()
+        .map(|(id, title, summary, thesis)| {
+            test_session(json!({
+                "id": id, "title": title, "summary": summary,
+                "created": now, "updated": now,
+                "thesis": thesis.map(|t| json!({ "content": t, "confidence": 0.5, "updatedAt": now })),
+            }))
+        })
+        .collect();
+
+        let found = local_matches("Pricing power of subscriptions", None, &sessions);
+        let matched: Vec<(&str, &str)> = found.iter().map(|f| (f.session_id.as_str(), f.matched.as_str())).collect();
+        assert_eq!(matched, vec![("a", "title"), ("c", "thesis")]);
+        assert!(found[1].snippet.as_deref().is_some_and(|s| s.contains("Europe")));
+        assert!(local_matches("the and of", None, &sessions).is_empty());
+    }
+}
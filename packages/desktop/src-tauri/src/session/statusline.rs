@@ -0,0 +1,155 @@
+//! Status Line
+//!
+//! A compact one-line session summary for Claude Code's statusline hook
+//! (`dialectic statusline`): status, context budget, open tensions and CDG
+//! coherence. The hook runs on every prompt refresh, so the line is cached
+//! in the session directory against session.json's modification time and
+//! recomputed only after a write. A recomputation that overruns the latency
+//! budget falls back to the last cached line.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::{Duration, UNIX_EPOCH};
+
+use super::{get_session_dir_cli, Session, SessionError};
+use crate::cdg::compute_metrics;
+
+pub const CACHE_FILE: &str = ".statusline.json";
+/// Longest the hook waits for a fresh line
+pub const LATENCY_BUDGET: Duration = Duration::from_millis(250);
+/// Shown when there's neither a fresh nor a cached line
+const FALLBACK_LINE: &str = "dialectic";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusLine {
+    pub status: String,
+    pub budget_pct: u8,
+    pub open_tensions: usize,
+    /// `None` before the session has claims
+    pub coherence: Option<f32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Cached {
+    modified_ms: u128,
+    line: String,
+}
+
+impl StatusLine {
+    pub fn from_session(session: &Session) -> Self {
+        Self {
+            status: format!("{:?}", session.status).to_lowercase(),
+            budget_pct: session.context_budget.as_ref().map(|b| b.usage_percentage()).unwrap_or(0),
+            open_tensions: session.tensions.iter().filter(|t| t.resolution.is_none()).count(),
+            coherence: (!session.claims.is_empty())
+                .then(|| compute_metrics(&session.claims, &session.cdg_edges).coherence),
+        }
+    }
+
+    /// e.g. `◆ tensions · ctx 42% · 3 open · coh 0.71`
+    pub fn render(&self) -> String {
+        let mut parts = vec![
+            format!("◆ {}", self.status),
+            format!("ctx {}%", self.budget_pct),
+            format!("{} open", self.open_tensions),
+        ];
+        if let Some(coherence) = self.coherence {
+            parts.push(format!("coh {:.2}", coherence));
+        }
+        parts.join(" · ")
+    }
+}
+
+fn modified_ms(session_dir: &Path) -> Option<u128> {
+    let modified = fs::metadata(session_dir.join("session.json")).and_then(|m| m.modified()).ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_millis())
+}
+
+/// Load the session, render its line and cache it
+fn refresh(session_dir: &Path, modified_ms: u128) -> Option<String> {
+    let content = fs::read_to_string(session_dir.join("session.json")).ok()?;
+    let session: Session = serde_json::from_str(&content).ok()?;
+    let line = StatusLine::from_session(&session).render();
+    let cached = Cached { modified_ms, line: line.clone() };
+    if let Ok(json) = serde_json::to_string(&cached) {
+        let tmp = session_dir.join(format!("{}.tmp", CACHE_FILE));
+        if fs::write(&tmp, json).is_ok() {
+            let _ = fs::rename(&tmp, session_dir.join(CACHE_FILE));
+        }
+    }
+    Some(line)
+}
+
+/// The status line for the session in `session_dir`, from the cache when
+/// session.json hasn't changed; never waits longer than `budget`
+pub fn status_line(session_dir: &Path, budget: Duration) -> String {
+    let cached: Option<Cached> = fs::read_to_string(session_dir.join(CACHE_FILE))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok());
+    let Some(modified) = modified_ms(session_dir) else {
+        return cached.map(|c| c.line).unwrap_or_else(|| FALLBACK_LINE.to_string());
+    };
+    if let Some(cached) = cached.as_ref().filter(|c| c.modified_ms == modified) {
+        return cached.line.clone();
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let dir = session_dir.to_path_buf();
+    std::thread::spawn(move || {
+        let _ = tx.send(refresh(&dir, modified));
+    });
+    match rx.recv_timeout(budget) {
+        Ok(Some(line)) => line,
+        _ => cached.map(|c| c.line).unwrap_or_else(|| FALLBACK_LINE.to_string()),
+    }
+}
+
+// ============ TAURI COMMANDS ============
+
+/// The same line the statusline hook shows, e.g. for the terminal header
+#[tauri::command]
+pub fn get_status_line(session_id: String) -> Result<String, SessionError> {
+    Ok(status_line(&get_session_dir_cli(&session_id)?, LATENCY_BUDGET))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use serde_json::json;
+
+    #[test]
+    fn test_status_line_cached_until_session_changes() {
+        let dir = std::env::temp_dir().join(format!("dialectic_statusline_{}", ulid::Ulid::new()));
+        fs::create_dir_all(&dir).unwrap();
+        let session = test_session(json!({
+            "id": "sl", "title": "Status", "status": "tensions",
+            "tensions": [
+                { "id": "t1", "claimAId": "a", "claimBId": "b", "description": "d", "resolution": null, "createdAt": "2026-01-01T00:00:00Z" },
+                { "id": "t2", "claimAId": "a", "claimBId": "b", "description": "d", "resolution": "done", "createdAt": "2026-01-01T00:00:00Z" },
+            ],
+        }));
+        fs::write(dir.join("session.json"), serde_json::to_string(&session).unwrap()).unwrap();
+
+        let line = status_line(&dir, Duration::from_secs(5));
+        assert_eq!(line, "◆ tensions · ctx 0% · 1 open");
+        assert!(dir.join(CACHE_FILE).is_file());
+
+        // Served from the cache while session.json is unchanged
+        let mut cached: Cached = serde_json::from_str(&fs::read_to_string(dir.join(CACHE_FILE)).unwrap()).unwrap();
+        cached.line = "from cache".to_string();
+        fs::write(dir.join(CACHE_FILE), serde_json::to_string(&cached).unwrap()).unwrap();
+        assert_eq!(status_line(&dir, Duration::from_secs(5)), "from cache");
+
+        // Unreadable session with no usable cache
+        fs::remove_file(dir.join(CACHE_FILE)).unwrap();
+        fs::write(dir.join("session.json"), "{").unwrap();
+        assert_eq!(status_line(&dir, Duration::from_secs(5)), FALLBACK_LINE);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
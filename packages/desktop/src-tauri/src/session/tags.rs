@@ -0,0 +1,142 @@
+//! Session Tags
+//!
+//! Free-form labels on sessions. Tags are normalized to lowercase with
+//! dashes for spaces, filter `list_sessions` and the CLI session list, and
+//! are copied onto every Chroma record of the session as `tag:<name>`
+//! boolean keys so cross-session search can be narrowed to a tag.
+
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+use super::lock::update_session_file;
+use super::{get_session_dir_cli, load_session_cli, validate_session_id, Session, SessionError};
+use crate::chroma::client::get_client;
+use crate::chroma::store::VectorStore;
+use crate::chroma::collections::{session_filter, tag_key, SESSION_SCOPED_COLLECTIONS};
+
+/// Longest tag accepted
+const MAX_TAG_LEN: usize = 64;
+
+/// Lowercase, trim and dash-join a tag. Letters, digits, `-`, `_` and `/`
+/// are allowed.
+pub fn normalize_tag(tag: &str) -> Result<String, SessionError> {
+    let normalized = tag.split_whitespace().collect::<Vec<_>>().join("-").to_lowercase();
+    let valid = !normalized.is_empty()
+        && normalized.len() <= MAX_TAG_LEN
+        && normalized.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '/'));
+    if valid {
+        Ok(normalized)
+    } else {
+        Err(SessionError::InvalidTag(tag.to_string()))
+    }
+}
+
+pub fn has_tag(session: &Session, tag: &str) -> bool {
+    normalize_tag(tag).is_ok_and(|tag| session.tags.contains(&tag))
+}
+
+/// Keep only sessions tagged `tag`
+pub fn retain_tagged(sessions: &mut Vec<Session>, tag: &str) {
+    sessions.retain(|s| has_tag(s, tag));
+}
+
+/// Tags of a session, for stamping onto records as they are indexed
+pub fn session_tags(session_id: &str) -> Vec<String> {
+    load_session_cli(session_id).map(|s| s.tags).unwrap_or_default()
+}
+
+/// Rewrite the tag keys on a session's existing Chroma records: `tags`
+/// set, `removed` cleared. Best-effort.
+pub async fn propagate_tags(session_id: &str, tags: &[String], removed: &[String]) {
+    let client = get_client();
+    let mut patch = serde_json::Map::new();
+    for tag in removed {
+        patch.insert(tag_key(tag), json!(false));
+    }
+    for tag in tags {
+        patch.insert(tag_key(tag), json!(true));
+    }
+    if patch.is_empty() {
+        return;
+    }
+
+    let mut updated = 0;
+    for name in SESSION_SCOPED_COLLECTIONS {
+        let Ok(collection) = client.get_collection(name).await else { continue };
+        let ids = match client.get(&collection.id, None, Some(session_filter(session_id)), None, None, None, Some(Vec::new())).await {
+            Ok(records) => records.ids,
+            Err(e) => {
+                warn!(collection = %name, error = %e, "Failed to list session records for tagging");
+                continue;
+            }
+        };
+        let count = ids.len();
+        let metadatas = vec![Value::Object(patch.clone()); count];
+        match client.update_metadata(&collection.id, ids, metadatas).await {
+            Ok(()) => updated += count,
+            Err(e) => warn!(collection = %name, error = %e, "Failed to update session record tags"),
+        }
+    }
+    info!(session_id = %session_id, records = updated, "Propagated session tags to Chroma");
+}
+
+/// Apply a tag change to session.json, then to the session's Chroma records
+fn edit_tags(session_id: &str, tag: &str, add: bool) -> Result<Session, SessionError> {
+    validate_session_id(session_id)?;
+    let tag = normalize_tag(tag)?;
+    let path = get_session_dir_cli(session_id)?.join("session.json");
+    let session = update_session_file(&path, |session| {
+        session.tags.retain(|t| *t != tag);
+        if add {
+            session.tags.push(tag.clone());
+            session.tags.sort();
+        }
+        Ok(())
+    })?;
+
+    let (id, tags) = (session.id.clone(), session.tags.clone());
+    let removed = if add { Vec::new() } else { vec![tag] };
+    tauri::async_runtime::spawn(async move {
+        propagate_tags(&id, &tags, &removed).await;
+    });
+    Ok(session)
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn add_session_tag(session_id: String, tag: String) -> Result<Session, SessionError> {
+    edit_tags(&session_id, &tag, true)
+}
+
+#[tauri::command]
+pub fn remove_session_tag(session_id: String, tag: String) -> Result<Session, SessionError> {
+    edit_tags(&session_id, &tag, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+
+    #[test]
+    fn test_normalize_and_filter_tags() {
+        assert_eq!(normalize_tag("  Monetary Policy ").unwrap(), "monetary-policy");
+        assert_eq!(normalize_tag("q3/eu_launch").unwrap(), "q3/eu_launch");
+        assert!(matches!(normalize_tag("   "), Err(SessionError::InvalidTag(_))));
+        assert!(normalize_tag("rates!").is_err());
+
+        let session = |id: &str, tags: &[&str]| -> Session {
+            test_session(json!({
+                "id": id,
+                "title": id,
+                "created": chrono::Utc::now(),
+                "updated": chrono::Utc::now(),
+                "tags": tags,
+            }))
+        };
+        let mut sessions = vec![session("a", &["rates", "macro"]), session("b", &["macro"]), session("c", &[])];
+        retain_tagged(&mut sessions, "Rates");
+        assert_eq!(sessions.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(), vec!["a"]);
+    }
+}
@@ -0,0 +1,287 @@
+//! Transcript Tailer
+//!
+//! While a session runs, follows its Claude Code conversation JSONL and
+//! processes lines as they are appended: web (and, if enabled, code) tool
+//! results are mined into Chroma, marker-tagged assistant lines become
+//! claims, and assistant output sizes feed the context budget. The one-shot
+//! mining pass when a session is formed still runs as a catch-up; upserts
+//! and claim dedup make the overlap harmless.
+
+use chrono::Utc;
+use futures::future::{AbortHandle, Abortable};
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+use ulid::Ulid;
+
+use super::claim_source::{message_text, SourceSpan};
+use super::lock::update_session_file;
+use super::markers::{extract_markers, same_claim};
+use super::{get_session_dir_cli, Claim, SessionError};
+use crate::chroma::jsonl_miner::{index_code_sources, index_sources, CodeSource, JsonlMiner, WebSource};
+use crate::config::preferences::load_preferences;
+
+/// How often the transcript is checked for new lines
+const TAIL_INTERVAL: Duration = Duration::from_secs(3);
+/// Stop tailing after the transcript has not grown for this long
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// Source ID of claims extracted from the live transcript
+const SOURCE_ID: &str = "transcript";
+
+/// Running tailers by session ID
+static TAILERS: LazyLock<Mutex<HashMap<String, AbortHandle>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// What the lines appended since the last read produced
+#[derive(Debug, Default)]
+pub struct TailBatch {
+    pub sources: Vec<WebSource>,
+    pub code_sources: Vec<CodeSource>,
+    pub claims: Vec<Claim>,
+    /// Output tokens of each new assistant message
+    pub output_tokens: Vec<u32>,
+}
+
+impl TailBatch {
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty() && self.code_sources.is_empty() && self.claims.is_empty() && self.output_tokens.is_empty()
+    }
+}
+
+/// Read position and parse state for one transcript
+pub struct TailState {
+    conversation_id: String,
+    offset: u64,
+    /// Index of the next user/assistant message, matching `transcript_messages`
+    turn: u32,
+    miner: JsonlMiner,
+    /// Assistant message IDs whose usage was already counted; the CLI
+    /// writes one line per content block, each repeating the usage
+    counted_messages: HashSet<String>,
+}
+
+impl TailState {
+    pub fn new(conversation_id: &str, jsonl_path: &Path) -> Self {
+        Self {
+            conversation_id: conversation_id.to_string(),
+            offset: 0,
+            turn: 0,
+            miner: JsonlMiner::new(jsonl_path.parent()),
+            counted_messages: HashSet::new(),
+        }
+    }
+
+    /// Process complete lines appended since the last read. A trailing
+    /// partial line is left for the next read.
+    pub fn read_new(&mut self, jsonl_path: &Path) -> std::io::Result<TailBatch> {
+        let mut file = File::open(jsonl_path)?;
+        let len = file.metadata()?.len();
+        if len < self.offset {
+            // Rewritten from scratch: start over
+            *self = Self::new(&self.conversation_id, jsonl_path);
+        }
+        if len == self.offset {
+            return Ok(TailBatch::default());
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+        let Some(end) = buf.iter().rposition(|b| *b == b'\n') else {
+            return Ok(TailBatch::default());
+        };
+        self.offset += end as u64 + 1;
+
+        let mut batch = TailBatch::default();
+        for line in String::from_utf8_lossy(&buf[..end]).lines() {
+            let Ok(entry) = serde_json::from_str::<Value>(line) else { continue };
+            self.miner.feed(&entry);
+            self.read_message(&entry, &mut batch);
+        }
+        let mined = self.miner.take();
+        batch.sources = mined.sources;
+        batch.code_sources = mined.code_sources;
+        Ok(batch)
+    }
+
+    fn read_message(&mut self, entry: &Value, batch: &mut TailBatch) {
+        let role = entry.get("type").and_then(|t| t.as_str());
+        if !matches!(role, Some("user" | "assistant")) {
+            return;
+        }
+        let turn = self.turn;
+        self.turn += 1;
+        if role != Some("assistant") {
+            return;
+        }
+
+        let text = message_text(entry).unwrap_or_default();
+        for (marker, content) in extract_markers(&text) {
+            batch.claims.push(Claim {
+                id: Ulid::new().to_string(),
+                content,
+                source_id: SOURCE_ID.to_string(),
+                marker: Some(format!("[{}]", marker)),
+                created_at: Utc::now(),
+                source_span: Some(SourceSpan::Transcript {
+                    conversation_id: Some(self.conversation_id.clone()),
+                    turn,
+                }),
+                evidence_score: None,
+                commit: None,
+            });
+        }
+
+        let message = entry.get("message");
+        let output_tokens = message
+            .and_then(|m| m.get("usage"))
+            .and_then(|u| u.get("output_tokens"))
+            .and_then(|t| t.as_u64());
+        let message_id = message.and_then(|m| m.get("id")).and_then(|id| id.as_str());
+        if let (Some(tokens), Some(id)) = (output_tokens, message_id) {
+            if self.counted_messages.insert(id.to_string()) {
+                batch.output_tokens.push(tokens as u32);
+            }
+        }
+    }
+}
+
+/// Index a batch and write its claims and output sizes to the session
+async fn apply(session_id: &str, batch: TailBatch) -> Result<(), SessionError> {
+    index_sources(session_id, &batch.sources).await;
+    if !batch.code_sources.is_empty() && load_preferences().mine_code_context {
+        index_code_sources(session_id, &batch.code_sources).await;
+    }
+    if batch.claims.is_empty() && batch.output_tokens.is_empty() {
+        return Ok(());
+    }
+
+    let path = get_session_dir_cli(session_id)?.join("session.json");
+    let mut claims_added = 0;
+    update_session_file(&path, |session| {
+        for claim in &batch.claims {
+            if !session.claims.iter().any(|c| same_claim(&c.content, &claim.content)) {
+                session.claims.push(claim.clone());
+                claims_added += 1;
+            }
+        }
+        if !batch.output_tokens.is_empty() {
+            let budget = session.context_budget.get_or_insert_with(Default::default);
+            for tokens in &batch.output_tokens {
+                budget.record_pass_output(*tokens);
+            }
+        }
+        Ok(())
+    })?;
+    debug!(session_id = %session_id, claims_added, messages = batch.output_tokens.len(), "Applied transcript updates");
+    Ok(())
+}
+
+async fn tail(session_id: String, conversation_id: String, jsonl_path: PathBuf) {
+    info!(session_id = %session_id, path = %jsonl_path.display(), "Tailing conversation transcript");
+    let mut state = TailState::new(&conversation_id, &jsonl_path);
+    let mut last_growth = Instant::now();
+    let mut interval = tokio::time::interval(TAIL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        let batch = match state.read_new(&jsonl_path) {
+            Ok(batch) => batch,
+            Err(e) => {
+                warn!(session_id = %session_id, error = %e, "Failed to read conversation transcript");
+                break;
+            }
+        };
+        if batch.is_empty() {
+            if last_growth.elapsed() >= IDLE_TIMEOUT {
+                info!(session_id = %session_id, "Transcript idle, stopping tailer");
+                break;
+            }
+            continue;
+        }
+        last_growth = Instant::now();
+        if let Err(e) = apply(&session_id, batch).await {
+            warn!(session_id = %session_id, error = %e, "Failed to apply transcript updates");
+        }
+    }
+
+    TAILERS.lock().remove(&session_id);
+}
+
+/// Start tailing a session's transcript. No-op if one is already running.
+pub fn start(session_id: &str, conversation_id: &str, jsonl_path: PathBuf) {
+    let (handle, registration) = AbortHandle::new_pair();
+    {
+        let mut tailers = TAILERS.lock();
+        if tailers.contains_key(session_id) {
+            return;
+        }
+        tailers.insert(session_id.to_string(), handle);
+    }
+    let task = tail(session_id.to_string(), conversation_id.to_string(), jsonl_path);
+    tauri::async_runtime::spawn(Abortable::new(task, registration));
+}
+
+/// Stop tailing a session's transcript, if running
+pub fn stop(session_id: &str) {
+    if let Some(handle) = TAILERS.lock().remove(session_id) {
+        handle.abort();
+        debug!(session_id = %session_id, "Stopped transcript tailer");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Write;
+
+    #[test]
+    fn test_reads_appended_lines_incrementally() {
+        let dir = std::env::temp_dir().join(format!("dialectic_tailer_{}", Ulid::new()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("conversation.jsonl");
+        let mut state = TailState::new("conv-1", &path);
+
+        let user = json!({"type": "user", "message": {"content": "Is pricing power durable?"}});
+        let assistant = |text: &str| json!({"type": "assistant", "message": {
+            "id": "msg_1",
+            "content": [
+                {"type": "text", "text": text},
+                {"type": "tool_use", "id": "t1", "name": "WebFetch", "input": {"url": "https://example.com"}}
+            ],
+            "usage": {"output_tokens": 120}
+        }});
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "{}", user).unwrap();
+        writeln!(file, "{}", assistant("[INSIGHT] Pricing power is the moat")).unwrap();
+        // Partial line: not processed until it is terminated
+        write!(file, "{{\"type\": \"user\", \"message\": {{\"content\": [").unwrap();
+        file.flush().unwrap();
+
+        let first = state.read_new(&path).unwrap();
+        assert_eq!(first.claims.len(), 1);
+        assert_eq!(first.claims[0].marker.as_deref(), Some("[INSIGHT]"));
+        assert!(matches!(first.claims[0].source_span, Some(SourceSpan::Transcript { turn: 1, .. })));
+        assert_eq!(first.output_tokens, vec![120]);
+        assert!(first.sources.is_empty());
+
+        // The tool result arrives later; a repeated line of the same message isn't recounted
+        writeln!(file, "{{\"type\": \"tool_result\", \"tool_use_id\": \"t1\", \"content\": \"Example page\"}}]}}}}").unwrap();
+        writeln!(file, "{}", assistant("Done.")).unwrap();
+        file.flush().unwrap();
+
+        let second = state.read_new(&path).unwrap();
+        assert_eq!(second.sources.len(), 1);
+        assert_eq!(second.sources[0].url.as_deref(), Some("https://example.com"));
+        assert!(second.claims.is_empty());
+        assert!(second.output_tokens.is_empty());
+        assert!(state.read_new(&path).unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
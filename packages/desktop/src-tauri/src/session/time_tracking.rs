@@ -0,0 +1,327 @@
+//! Time Tracking
+//!
+//! Active time per session, from start/stop signals appended to the
+//! session's `activity.log`: the backend records when the session's terminal
+//! spawns and exits, the frontend when the session's window gains and loses
+//! focus. A session is active while any signal is on; a signal left on (the
+//! app quit mid-session, or a stop was lost) counts for at most
+//! `MAX_OPEN_INTERVAL_HOURS`.
+
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use tauri::AppHandle;
+use tracing::{debug, warn};
+
+use super::{get_session_dir, Session, SessionError};
+
+pub const ACTIVITY_LOG: &str = "activity.log";
+/// Longest a start without a matching stop is counted for
+pub const MAX_OPEN_INTERVAL_HOURS: i64 = 8;
+const UNCATEGORIZED: &str = "uncategorized";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivitySignal {
+    /// The session's terminal is running
+    Terminal,
+    /// The session's window has focus
+    Focus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityState {
+    Start,
+    Stop,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityEvent {
+    pub timestamp: DateTime<Utc>,
+    pub signal: ActivitySignal,
+    pub state: ActivityState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimePeriod {
+    /// Since local midnight
+    Today,
+    /// The last 7 days
+    Week,
+    /// The last 30 days
+    Month,
+    All,
+}
+
+impl TimePeriod {
+    /// Start of the period ending at `now`; `None` for all time
+    pub fn since(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            TimePeriod::Today => {
+                let midnight = now.with_timezone(&Local).date_naive().and_hms_opt(0, 0, 0)?;
+                Local.from_local_datetime(&midnight).earliest().map(|t| t.with_timezone(&Utc))
+            }
+            TimePeriod::Week => Some(now - Duration::days(7)),
+            TimePeriod::Month => Some(now - Duration::days(30)),
+            TimePeriod::All => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTime {
+    pub session_id: String,
+    pub title: String,
+    pub category: String,
+    pub hours: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryTime {
+    pub category: String,
+    pub hours: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeReport {
+    pub period: TimePeriod,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+    pub until: DateTime<Utc>,
+    pub total_hours: f64,
+    /// Sessions with any active time, most first
+    pub sessions: Vec<SessionTime>,
+    /// Most first
+    pub categories: Vec<CategoryTime>,
+}
+
+/// Append a start or stop to the session's activity log
+pub fn append_event(session_dir: &Path, signal: ActivitySignal, state: ActivityState) -> Result<(), SessionError> {
+    let event = ActivityEvent { timestamp: Utc::now(), signal, state };
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(session_dir.join(ACTIVITY_LOG))?;
+    file.write_all(format!("{}\n", serde_json::to_string(&event)?).as_bytes())?;
+    Ok(())
+}
+
+/// Record a signal, logging rather than failing: time tracking must never
+/// get in the way of the terminal or the window
+pub fn record(session_dir: &Path, signal: ActivitySignal, state: ActivityState) {
+    if !session_dir.join("session.json").exists() {
+        return;
+    }
+    match append_event(session_dir, signal, state) {
+        Ok(()) => debug!(dir = %session_dir.display(), ?signal, ?state, "Recorded activity"),
+        Err(e) => warn!(error = %e, dir = %session_dir.display(), "Failed to record activity"),
+    }
+}
+
+pub fn read_activity_log(session_dir: &Path) -> Result<Vec<ActivityEvent>, SessionError> {
+    let path = session_dir.join(ACTIVITY_LOG);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(fs::read_to_string(&path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Intervals during which any signal was on, merged and oldest first
+pub fn active_intervals(events: &[ActivityEvent], now: DateTime<Utc>) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let cap = Duration::hours(MAX_OPEN_INTERVAL_HOURS);
+    let mut events: Vec<&ActivityEvent> = events.iter().collect();
+    events.sort_by_key(|e| e.timestamp);
+
+    let mut open: HashMap<ActivitySignal, DateTime<Utc>> = HashMap::new();
+    let mut intervals = Vec::new();
+    for event in events {
+        match event.state {
+            ActivityState::Start => {
+                // A second start means the stop went missing; the earlier
+                // one ended no later than this
+                if let Some(start) = open.insert(event.signal, event.timestamp) {
+                    intervals.push((start, event.timestamp.min(start + cap)));
+                }
+            }
+            ActivityState::Stop => {
+                if let Some(start) = open.remove(&event.signal) {
+                    intervals.push((start, event.timestamp));
+                }
+            }
+        }
+    }
+    intervals.extend(open.into_values().map(|start| (start, now.min(start + cap))));
+
+    intervals.retain(|(start, end)| end > start);
+    intervals.sort();
+    let mut merged: Vec<(DateTime<Utc>, DateTime<Utc>)> = Vec::new();
+    for (start, end) in intervals {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Active hours within `since..until`
+pub fn active_hours(events: &[ActivityEvent], since: Option<DateTime<Utc>>, until: DateTime<Utc>) -> f64 {
+    let seconds: i64 = active_intervals(events, until)
+        .into_iter()
+        .map(|(start, end)| {
+            let start = since.map_or(start, |s| start.max(s));
+            (end.min(until) - start).num_seconds().max(0)
+        })
+        .sum();
+    seconds as f64 / 3600.0
+}
+
+fn round_hours(hours: f64) -> f64 {
+    (hours * 100.0).round() / 100.0
+}
+
+/// Aggregate each session's activity over `period`
+pub fn build_report(sessions: &[(Session, Vec<ActivityEvent>)], period: TimePeriod, now: DateTime<Utc>) -> TimeReport {
+    let since = period.since(now);
+    let mut by_session: Vec<SessionTime> = sessions
+        .iter()
+        .map(|(session, events)| SessionTime {
+            session_id: session.id.clone(),
+            title: session.title.clone(),
+            category: session
+                .category
+                .clone()
+                .filter(|c| !c.trim().is_empty())
+                .unwrap_or_else(|| UNCATEGORIZED.to_string()),
+            hours: active_hours(events, since, now),
+        })
+        .filter(|s| s.hours > 0.0)
+        .collect();
+
+    let mut by_category: HashMap<String, f64> = HashMap::new();
+    for entry in &by_session {
+        *by_category.entry(entry.category.clone()).or_default() += entry.hours;
+    }
+    let total: f64 = by_session.iter().map(|s| s.hours).sum();
+
+    by_session.sort_by(|a, b| b.hours.total_cmp(&a.hours));
+    for entry in by_session.iter_mut() {
+        entry.hours = round_hours(entry.hours);
+    }
+    let mut categories: Vec<CategoryTime> = by_category
+        .into_iter()
+        .map(|(category, hours)| CategoryTime { category, hours: round_hours(hours) })
+        .collect();
+    categories.sort_by(|a, b| b.hours.total_cmp(&a.hours).then_with(|| a.category.cmp(&b.category)));
+
+    TimeReport {
+        period,
+        since,
+        until: now,
+        total_hours: round_hours(total),
+        sessions: by_session,
+        categories,
+    }
+}
+
+// ============ TAURI COMMANDS ============
+
+/// Record a focus (or terminal) signal from the frontend
+#[tauri::command]
+pub fn record_activity(
+    app: AppHandle,
+    session_id: String,
+    signal: ActivitySignal,
+    active: bool,
+) -> Result<(), SessionError> {
+    let session_dir = get_session_dir(&app, &session_id)?;
+    if !session_dir.join("session.json").exists() {
+        return Err(SessionError::NotFound(session_id));
+    }
+    let state = if active { ActivityState::Start } else { ActivityState::Stop };
+    append_event(&session_dir, signal, state)
+}
+
+/// Active hours per session and per category over `period`
+#[tauri::command]
+pub fn get_time_report(app: AppHandle, period: TimePeriod) -> Result<TimeReport, SessionError> {
+    let sessions = super::list_sessions(app.clone(), None)?
+        .into_iter()
+        .map(|session| {
+            let events = get_session_dir(&app, &session.id)
+                .and_then(|dir| read_activity_log(&dir))
+                .unwrap_or_default();
+            (session, events)
+        })
+        .collect::<Vec<_>>();
+    Ok(build_report(&sessions, period, Utc::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use serde_json::json;
+
+    #[test]
+    fn test_build_report_merges_signals_and_clips() {
+        let at = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        let event = |t: &str, signal, state| ActivityEvent { timestamp: at(t), signal, state };
+        let session = |id: &str, category: Option<&str>| -> Session {
+            test_session(json!({
+                "id": id, "title": id,
+                "category": category,
+            }))
+        };
+        use ActivitySignal::*;
+        use ActivityState::*;
+
+        // Terminal 9-11 overlaps focus 10-12: 3h, not 4h. A stray stop is
+        // ignored and a repeated start closes the earlier one.
+        let a = vec![
+            event("2026-01-10T09:00:00Z", Terminal, Start),
+            event("2026-01-10T10:00:00Z", Focus, Start),
+            event("2026-01-10T11:00:00Z", Terminal, Stop),
+            event("2026-01-10T12:00:00Z", Focus, Stop),
+            event("2026-01-10T12:30:00Z", Terminal, Stop),
+            event("2026-01-10T13:00:00Z", Focus, Start),
+            event("2026-01-10T13:30:00Z", Focus, Start),
+            event("2026-01-10T14:00:00Z", Focus, Stop),
+        ];
+        // Never stopped: capped at 8h
+        let b = vec![event("2026-01-01T00:00:00Z", Terminal, Start)];
+        let c = vec![event("2026-01-10T09:00:00Z", Focus, Start), event("2026-01-10T10:00:00Z", Focus, Stop)];
+        let sessions = vec![
+            (session("a", Some("research")), a),
+            (session("b", None), b),
+            (session("c", Some("research")), c),
+            (session("idle", None), Vec::new()),
+        ];
+
+        let now = at("2026-01-12T00:00:00Z");
+        let report = build_report(&sessions, TimePeriod::All, now);
+        assert_eq!(report.total_hours, 13.0);
+        let hours: Vec<(&str, f64)> = report.sessions.iter().map(|s| (s.session_id.as_str(), s.hours)).collect();
+        assert_eq!(hours, vec![("b", 8.0), ("a", 4.0), ("c", 1.0)]);
+        assert_eq!(report.categories[0].category, "uncategorized");
+        assert_eq!(report.categories[1].hours, 5.0);
+
+        // The week before `now` starts mid-way through session a's terminal run
+        let report = build_report(&sessions, TimePeriod::Week, at("2026-01-17T10:30:00Z"));
+        assert_eq!(report.sessions.len(), 1);
+        assert_eq!(report.sessions[0].hours, 2.5);
+    }
+}
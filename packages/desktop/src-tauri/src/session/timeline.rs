@@ -0,0 +1,273 @@
+//! Session Timeline
+//!
+//! One chronological list of what happened in a session, for the timeline
+//! view and `dialectic session timeline`. Claims, tensions and passes carry
+//! their own timestamps in session.json; status changes, thesis revisions,
+//! compressions, launches and tension resolutions come from the audit log;
+//! artifacts the session wrote (scratchpad, decision record, distill output)
+//! are dated by their last modification.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+use super::audit::{read_audit_log, AuditAction, AuditEntry, AUDIT_LOG};
+use super::{get_session_dir, journal, Session, SessionError, HOOK_SETTINGS_FILE};
+
+/// Session-dir files that are bookkeeping rather than artifacts
+const NOT_ARTIFACTS: &[&str] = &[
+    "session.json",
+    AUDIT_LOG,
+    super::time_tracking::ACTIVITY_LOG,
+    crate::context::budget_history::BUDGET_LOG,
+    "CLAUDE.md",
+    HOOK_SETTINGS_FILE,
+    super::env_file::ENV_FILE,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineKind {
+    Created,
+    StatusChanged,
+    PassStarted,
+    PassCompleted,
+    ClaimAdded,
+    TensionAdded,
+    TensionResolved,
+    ThesisUpdated,
+    Compressed,
+    Launched,
+    ArtifactWritten,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEvent {
+    pub timestamp: DateTime<Utc>,
+    pub kind: TimelineKind,
+    pub summary: String,
+    /// Id of the claim, tension or pass, or the artifact's file name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub detail: Value,
+}
+
+impl TimelineEvent {
+    fn new(timestamp: DateTime<Utc>, kind: TimelineKind, summary: String, target: Option<String>) -> Self {
+        Self { timestamp, kind, summary, target, detail: Value::Null }
+    }
+}
+
+fn excerpt(text: &str) -> String {
+    const MAX_CHARS: usize = 80;
+    match text.char_indices().nth(MAX_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+fn from_audit(entry: &AuditEntry) -> Option<TimelineEvent> {
+    let value = |key: &str| entry.detail.get(key).and_then(Value::as_str).map(str::to_string);
+    let (kind, summary) = match entry.action {
+        AuditAction::StatusChanged => (
+            TimelineKind::StatusChanged,
+            format!("Status {} → {}", value("from").unwrap_or_default(), value("to").unwrap_or_default()),
+        ),
+        AuditAction::ThesisUpdated => {
+            let confidence = entry.detail.get("confidence").and_then(Value::as_f64);
+            (
+                TimelineKind::ThesisUpdated,
+                match confidence {
+                    Some(c) => format!("Thesis revised ({:.0}% confidence)", c * 100.0),
+                    None => "Thesis revised".to_string(),
+                },
+            )
+        }
+        AuditAction::Compressed => (TimelineKind::Compressed, "Paper trail compressed".to_string()),
+        AuditAction::Launched => {
+            // The command's first element is the CLI tool, possibly a full path
+            let tool = entry.detail.pointer("/command/0").and_then(Value::as_str);
+            let tool = tool.and_then(|t| Path::new(t).file_name()).map(|t| t.to_string_lossy().to_string());
+            (TimelineKind::Launched, format!("Launched {}", tool.unwrap_or_else(|| "session".to_string())))
+        }
+        AuditAction::TensionUpdated => {
+            // Only the update that first set a resolution, not later edits
+            if !entry.detail.pointer("/before/resolution").is_none_or(Value::is_null) {
+                return None;
+            }
+            let resolution = entry.detail.pointer("/after/resolution").and_then(Value::as_str)?;
+            (TimelineKind::TensionResolved, format!("Tension resolved: {}", excerpt(resolution)))
+        }
+        // Claims and tensions are dated from session.json, which also has
+        // those added before the audit log existed
+        _ => return None,
+    };
+    Some(TimelineEvent {
+        timestamp: entry.timestamp,
+        kind,
+        summary,
+        target: entry.target.clone(),
+        detail: json!({ "actor": entry.actor }),
+    })
+}
+
+/// Merge the session's own timestamps, its audit log and its artifacts
+/// (file name, last written) into one list, oldest first
+pub fn build_timeline(session: &Session, audit: &[AuditEntry], artifacts: &[(String, DateTime<Utc>)]) -> Vec<TimelineEvent> {
+    let mut events = vec![TimelineEvent::new(session.created, TimelineKind::Created, format!("Created \"{}\"", session.title), None)];
+
+    for pass in &session.passes {
+        events.push(TimelineEvent::new(
+            pass.started_at,
+            TimelineKind::PassStarted,
+            format!("{} pass started", pass.pass_type),
+            Some(pass.id.clone()),
+        ));
+        if let Some(completed) = pass.completed_at {
+            let mut event = TimelineEvent::new(
+                completed,
+                TimelineKind::PassCompleted,
+                format!("{} pass completed", pass.pass_type),
+                Some(pass.id.clone()),
+            );
+            event.detail = json!({ "tokenCount": pass.token_count });
+            events.push(event);
+        }
+    }
+    for claim in &session.claims {
+        let marker = claim.marker.as_deref().map(|m| format!("{} ", m)).unwrap_or_default();
+        events.push(TimelineEvent::new(
+            claim.created_at,
+            TimelineKind::ClaimAdded,
+            format!("{}{}", marker, excerpt(&claim.content)),
+            Some(claim.id.clone()),
+        ));
+    }
+    for tension in &session.tensions {
+        events.push(TimelineEvent::new(
+            tension.created_at,
+            TimelineKind::TensionAdded,
+            format!("Tension: {}", excerpt(&tension.description)),
+            Some(tension.id.clone()),
+        ));
+    }
+    events.extend(audit.iter().filter_map(from_audit));
+    for (name, written) in artifacts {
+        events.push(TimelineEvent::new(*written, TimelineKind::ArtifactWritten, format!("Wrote {}", name), Some(name.clone())));
+    }
+
+    // Stable, so same-instant events keep the order above
+    events.sort_by_key(|e| e.timestamp);
+    events
+}
+
+/// Files in the session dir other than its bookkeeping, with their last write
+fn artifacts(session_dir: &Path) -> Vec<(String, DateTime<Utc>)> {
+    let Ok(entries) = fs::read_dir(session_dir) else { return Vec::new() };
+    entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let internal = name.starts_with('.')
+                || NOT_ARTIFACTS.contains(&name.as_str())
+                || [".tmp", ".lock", ".journal"].iter().any(|s| name.ends_with(s));
+            if internal {
+                return None;
+            }
+            let modified = e.metadata().and_then(|m| m.modified()).ok()?;
+            Some((name, DateTime::<Utc>::from(modified)))
+        })
+        .collect()
+}
+
+/// The timeline of the session in `session_dir`
+pub fn session_timeline(session_dir: &Path) -> Result<Vec<TimelineEvent>, SessionError> {
+    let path = session_dir.join("session.json");
+    if !path.exists() {
+        return Err(SessionError::NotFound(session_dir.display().to_string()));
+    }
+    let session: Session = serde_json::from_str(&journal::read_recovered(&path)?)?;
+    Ok(build_timeline(&session, &read_audit_log(session_dir)?, &artifacts(session_dir)))
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn get_session_timeline(app: AppHandle, session_id: String) -> Result<Vec<TimelineEvent>, SessionError> {
+    session_timeline(&get_session_dir(&app, &session_id)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::audit::diff_sessions;
+    use crate::session::{test_session, LaunchContext};
+
+    fn session(status: &str, resolution: Option<&str>) -> Session {
+        test_session(json!({
+            "id": "tl", "title": "Timeline", "status": status,
+            "updated": "2026-01-03T00:00:00Z",
+            "passes": [{ "id": "p1", "passType": "expansion", "startedAt": "2026-01-01T01:00:00Z", "completedAt": "2026-01-01T02:00:00Z", "tokenCount": 900 }],
+            "claims": [{ "id": "c1", "content": "Rates stay high", "sourceId": "s", "marker": "[INSIGHT]", "createdAt": "2026-01-01T01:30:00Z" }],
+            "tensions": [{ "id": "t1", "claimAId": "c1", "claimBId": "c1", "description": "Growth vs rates", "resolution": resolution, "createdAt": "2026-01-02T00:00:00Z" }],
+        }))
+    }
+
+    #[test]
+    fn test_build_timeline_merges_sources() {
+        let at = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        let exploring = session("exploring", None);
+        let resolved = session("exploring", Some("Both"));
+        let tensions = session("tensions", Some("Both"));
+        let reworded = session("tensions", Some("Both, for now"));
+
+        let launch = LaunchContext {
+            working_dir: "/tmp/tl".to_string(),
+            session_dir: "/tmp/tl".to_string(),
+            conversation_id: None,
+            claude_command: vec!["/usr/local/bin/claude".to_string(), "--add-dir".to_string(), "/tmp/tl".to_string()],
+            env_vars: Default::default(),
+            warnings: Vec::new(),
+            launch_id: "l1".to_string(),
+        };
+        let mut audit = vec![launch.audit_entry()];
+        audit.extend(diff_sessions(&exploring, &resolved));
+        audit.extend(diff_sessions(&resolved, &tensions));
+        // Rewording an existing resolution is not another resolution
+        audit.extend(diff_sessions(&tensions, &reworded));
+        let times = ["2026-01-01T00:30:00Z", "2026-01-02T12:00:00Z", "2026-01-02T13:00:00Z", "2026-01-02T14:00:00Z"];
+        assert_eq!(audit.len(), times.len());
+        for (entry, time) in audit.iter_mut().zip(times) {
+            entry.timestamp = at(time);
+        }
+        let artifacts = vec![("scratchpad.md".to_string(), at("2026-01-01T03:00:00Z"))];
+
+        let timeline = build_timeline(&reworded, &audit, &artifacts);
+        let kinds: Vec<TimelineKind> = timeline.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TimelineKind::Created,
+                TimelineKind::Launched,
+                TimelineKind::PassStarted,
+                TimelineKind::ClaimAdded,
+                TimelineKind::PassCompleted,
+                TimelineKind::ArtifactWritten,
+                TimelineKind::TensionAdded,
+                TimelineKind::TensionResolved,
+                TimelineKind::StatusChanged,
+            ]
+        );
+        assert_eq!(timeline[1].summary, "Launched claude");
+        assert_eq!(timeline[3].summary, "[INSIGHT] Rates stay high");
+        assert_eq!(timeline[7].summary, "Tension resolved: Both");
+        assert_eq!(timeline[7].target.as_deref(), Some("t1"));
+        assert_eq!(timeline[8].summary, "Status exploring → tensions");
+    }
+}
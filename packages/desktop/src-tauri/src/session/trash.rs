@@ -0,0 +1,174 @@
+//! Session Trash
+//!
+//! Deleting a session moves its directory to `<app data>/.trash/<id>/`
+//! with a `trashed.json` recording when and what was deleted, so a
+//! mistaken delete can be undone with `restore_from_trash`. Entries older
+//! than the `trashRetentionDays` preference are purged at startup.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use super::{get_app_data_dir_cli, journal, session_dir_in, validate_session_id, Session, SessionError};
+use crate::config::preferences::load_preferences;
+
+pub const TRASH_DIR: &str = ".trash";
+/// Written next to the session's files inside its trash entry
+const TRASH_INFO_FILE: &str = "trashed.json";
+
+/// A deleted session waiting in the trash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashEntry {
+    pub session_id: String,
+    pub title: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+fn move_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(from, to)
+}
+
+/// Move a session dir into the trash
+pub fn trash_session_in(app_data: &Path, session_id: &str) -> Result<TrashEntry, SessionError> {
+    let session_dir = session_dir_in(app_data, session_id)?;
+    if !session_dir.exists() {
+        return Err(SessionError::NotFound(session_id.to_string()));
+    }
+    let title = journal::read_recovered(&session_dir.join("session.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Session>(&content).ok())
+        .map(|s| s.title)
+        .unwrap_or_default();
+
+    let target = app_data.join(TRASH_DIR).join(session_id);
+    if target.exists() {
+        // Left from an earlier delete of a since-restored session
+        fs::remove_dir_all(&target)?;
+    }
+    move_dir(&session_dir, &target)?;
+
+    let entry = TrashEntry { session_id: session_id.to_string(), title, deleted_at: Utc::now() };
+    fs::write(target.join(TRASH_INFO_FILE), serde_json::to_string_pretty(&entry)?)?;
+    info!(session_id = %session_id, "Moved session to trash");
+    Ok(entry)
+}
+
+/// Trashed sessions, most recently deleted first
+pub fn list_trash_in(app_data: &Path) -> Vec<TrashEntry> {
+    let mut entries: Vec<TrashEntry> = fs::read_dir(app_data.join(TRASH_DIR))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| fs::read_to_string(entry.path().join(TRASH_INFO_FILE)).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.deleted_at));
+    entries
+}
+
+/// Move a trashed session back to the sessions dir
+pub fn restore_in(app_data: &Path, session_id: &str) -> Result<PathBuf, SessionError> {
+    validate_session_id(session_id)?;
+    let entry = app_data.join(TRASH_DIR).join(session_id);
+    if !entry.join(TRASH_INFO_FILE).exists() {
+        return Err(SessionError::NotFound(session_id.to_string()));
+    }
+    let target = session_dir_in(app_data, session_id)?;
+    if target.exists() {
+        return Err(SessionError::InvalidPath(format!("Session already exists: {}", session_id)));
+    }
+    fs::remove_file(entry.join(TRASH_INFO_FILE))?;
+    move_dir(&entry, &target)?;
+    info!(session_id = %session_id, "Restored session from trash");
+    Ok(target)
+}
+
+/// Delete trash entries older than `retention_days`; returns how many
+pub fn purge_expired_in(app_data: &Path, retention_days: u32, now: DateTime<Utc>) -> usize {
+    if retention_days == 0 {
+        return 0;
+    }
+    let cutoff = now - Duration::days(retention_days as i64);
+    let mut purged = 0;
+    for entry in list_trash_in(app_data).into_iter().filter(|e| e.deleted_at < cutoff) {
+        match fs::remove_dir_all(app_data.join(TRASH_DIR).join(&entry.session_id)) {
+            Ok(()) => {
+                purged += 1;
+                if let Err(e) = crate::documents::registry::release_session_in(app_data, &entry.session_id) {
+                    warn!(session_id = %entry.session_id, error = %e, "Failed to release registered documents");
+                }
+            }
+            Err(e) => warn!(session_id = %entry.session_id, error = %e, "Failed to purge trashed session"),
+        }
+    }
+    purged
+}
+
+/// Startup purge using the retention preference
+pub fn purge_expired(app_data: &Path) {
+    let purged = purge_expired_in(app_data, load_preferences().trash_retention_days, Utc::now());
+    if purged > 0 {
+        info!(purged, "Purged expired sessions from trash");
+    }
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn list_trash() -> Result<Vec<TrashEntry>, SessionError> {
+    Ok(list_trash_in(&get_app_data_dir_cli()?))
+}
+
+#[tauri::command]
+pub fn restore_from_trash(session_id: String) -> Result<Session, SessionError> {
+    let dir = restore_in(&get_app_data_dir_cli()?, &session_id)?;
+    if let Err(e) = crate::documents::permanent::restore_session(&session_id) {
+        warn!(session_id = %session_id, error = %e, "Failed to take back preserved permanent documents");
+    }
+    let session: Session = serde_json::from_str(&journal::read_recovered(&dir.join("session.json"))?)?;
+    crate::quick_search::note_session(&session);
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ulid::Ulid;
+
+    #[test]
+    fn test_trash_restore_and_purge() {
+        let app_data = std::env::temp_dir().join(format!("dialectic_trash_{}", Ulid::new()));
+        let make = |id: &str| {
+            let dir = session_dir_in(&app_data, id).unwrap();
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("session.json"), "{}").unwrap();
+        };
+        let (a, b) = (Ulid::new().to_string(), Ulid::new().to_string());
+        make(&a);
+        make(&b);
+
+        trash_session_in(&app_data, &a).unwrap();
+        trash_session_in(&app_data, &b).unwrap();
+        assert!(!session_dir_in(&app_data, &a).unwrap().exists());
+        assert_eq!(list_trash_in(&app_data).len(), 2);
+
+        restore_in(&app_data, &a).unwrap();
+        let restored = session_dir_in(&app_data, &a).unwrap();
+        assert!(restored.join("session.json").exists());
+        assert!(!restored.join(TRASH_INFO_FILE).exists());
+        assert!(matches!(restore_in(&app_data, &a), Err(SessionError::NotFound(_))));
+
+        assert_eq!(purge_expired_in(&app_data, 30, Utc::now()), 0);
+        assert_eq!(purge_expired_in(&app_data, 0, Utc::now() + Duration::days(365)), 0);
+        assert_eq!(purge_expired_in(&app_data, 30, Utc::now() + Duration::days(31)), 1);
+        assert!(list_trash_in(&app_data).is_empty());
+
+        let _ = fs::remove_dir_all(app_data);
+    }
+}
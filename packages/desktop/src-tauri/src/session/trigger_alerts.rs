@@ -0,0 +1,328 @@
+//! Revision Trigger Matching
+//!
+//! Background job that embeds each session's thesis triggers (from the
+//! paper trail HEAD) and queries Chroma for content added after the thesis
+//! was last updated. Strong matches are recorded as `TriggerAlert`s on the
+//! session and announced via a `trigger-alert-{session_id}` event.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use std::path::Path;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tracing::{debug, info, warn};
+use ulid::Ulid;
+
+use super::journal::read_recovered;
+use super::lock::update_session_file;
+use super::{get_app_data_path, get_session_json_path, list_sessions_from_dir, Session, SessionError};
+use crate::chroma::collections::{
+    COLLECTION_MEMORY_EPISODIC, COLLECTION_MEMORY_PROCEDURAL, COLLECTION_MEMORY_SEMANTIC,
+    COLLECTION_OBSIDIAN, COLLECTION_WEB_SOURCES,
+};
+use crate::chroma::search::{search_all, SearchHit};
+
+/// Minimum relevance (1 / (1 + distance)) for a hit to raise an alert
+pub const TRIGGER_MATCH_THRESHOLD: f32 = 0.65;
+/// Results fetched per trigger query
+const RESULTS_PER_TRIGGER: u32 = 10;
+/// Interval between background sweeps
+const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+/// Delay before the first sweep so the sidecar can come up
+const INITIAL_DELAY: Duration = Duration::from_secs(60);
+/// Snippet length stored on an alert (chars)
+const SNIPPET_CHARS: usize = 280;
+
+/// Collections scanned for new evidence
+const TRIGGER_COLLECTIONS: &[&str] = &[
+    COLLECTION_WEB_SOURCES,
+    COLLECTION_OBSIDIAN,
+    COLLECTION_MEMORY_SEMANTIC,
+    COLLECTION_MEMORY_PROCEDURAL,
+    COLLECTION_MEMORY_EPISODIC,
+];
+
+/// New content that matched one of a thesis's revision triggers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerAlert {
+    pub id: String,
+    pub trigger: String,
+    pub collection: String,
+    pub source_id: String,
+    pub snippet: String,
+    pub relevance: f32,
+    pub detected_at: DateTime<Utc>,
+    #[serde(default)]
+    pub acknowledged: bool,
+}
+
+/// Event payload emitted when new alerts are recorded
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerAlertEvent {
+    pub session_id: String,
+    pub alerts: Vec<TriggerAlert>,
+}
+
+/// When the thesis was last revised; only content newer than this counts
+fn thesis_date(session: &Session) -> Option<DateTime<Utc>> {
+    session
+        .thesis
+        .as_ref()
+        .map(|t| t.updated_at)
+        .or_else(|| session.paper_trail.as_ref().map(|pt| pt.head.updated_at))
+}
+
+/// Non-empty triggers from the paper trail HEAD
+fn thesis_triggers(session: &Session) -> Vec<String> {
+    session
+        .paper_trail
+        .as_ref()
+        .map(|pt| {
+            pt.head
+                .triggers
+                .iter()
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Best-effort timestamp of when a Chroma record was added.
+///
+/// Web sources carry `indexed_at` (unix seconds), memories `created_at`
+/// and Obsidian chunks `modified` (both RFC 3339).
+pub fn content_timestamp(metadata: &Value) -> Option<DateTime<Utc>> {
+    if let Some(ts) = metadata.get("indexed_at").and_then(|v| v.as_i64()) {
+        return DateTime::from_timestamp(ts, 0);
+    }
+    ["created_at", "modified"]
+        .iter()
+        .filter_map(|key| metadata.get(*key).and_then(|v| v.as_str()))
+        .find_map(|s| DateTime::parse_from_rfc3339(s).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Turn search hits into new alerts, skipping stale, weak, self-referential
+/// and already-alerted hits.
+pub fn select_alerts(
+    session: &Session,
+    trigger: &str,
+    hits: &[SearchHit],
+    since: DateTime<Utc>,
+) -> Vec<TriggerAlert> {
+    let now = Utc::now();
+    hits.iter()
+        .filter(|hit| hit.relevance >= TRIGGER_MATCH_THRESHOLD)
+        .filter(|hit| hit.metadata.get("session_id").and_then(|v| v.as_str()) != Some(session.id.as_str()))
+        .filter(|hit| content_timestamp(&hit.metadata).is_some_and(|ts| ts > since))
+        .filter(|hit| {
+            !session
+                .trigger_alerts
+                .iter()
+                .any(|a| a.trigger == trigger && a.source_id == hit.id)
+        })
+        .map(|hit| TriggerAlert {
+            id: Ulid::new().to_string(),
+            trigger: trigger.to_string(),
+            collection: hit.collection.clone(),
+            source_id: hit.id.clone(),
+            snippet: hit.document.chars().take(SNIPPET_CHARS).collect(),
+            relevance: hit.relevance,
+            detected_at: now,
+            acknowledged: false,
+        })
+        .collect()
+}
+
+/// Query Chroma for each of a session's triggers and collect new alerts.
+async fn find_new_alerts(session: &Session) -> Vec<TriggerAlert> {
+    let since = match thesis_date(session) {
+        Some(d) => d,
+        None => return Vec::new(),
+    };
+    let collections: Vec<String> = TRIGGER_COLLECTIONS.iter().map(|c| c.to_string()).collect();
+
+    let mut alerts: Vec<TriggerAlert> = Vec::new();
+    for trigger in thesis_triggers(session) {
+        match search_all(&trigger, RESULTS_PER_TRIGGER, None, Some(collections.clone()), None, None).await {
+            Ok(results) => {
+                for alert in select_alerts(session, &trigger, &results.hits, since) {
+                    if !alerts.iter().any(|a| a.trigger == alert.trigger && a.source_id == alert.source_id) {
+                        alerts.push(alert);
+                    }
+                }
+            }
+            Err(e) => {
+                debug!(session_id = %session.id, error = %e, "Trigger query failed");
+            }
+        }
+    }
+    alerts
+}
+
+/// Append alerts to session.json (re-read to avoid clobbering concurrent edits).
+fn record_alerts(session_path: &Path, alerts: &[TriggerAlert]) -> Result<Session, SessionError> {
+    update_session_file(session_path, |session| {
+        session.trigger_alerts.extend(alerts.iter().cloned());
+        Ok(())
+    })
+}
+
+/// Check one session's triggers, persist any new alerts and emit an event.
+pub async fn check_session_triggers(app: &AppHandle, session: &Session) -> Result<Vec<TriggerAlert>, SessionError> {
+    let alerts = find_new_alerts(session).await;
+    if alerts.is_empty() {
+        return Ok(alerts);
+    }
+
+    let session_path = get_session_json_path(app, &session.id)?;
+    record_alerts(&session_path, &alerts)?;
+    info!(session_id = %session.id, count = alerts.len(), "Recorded trigger alerts");
+
+    let event_name = format!("trigger-alert-{}", session.id);
+    let payload = TriggerAlertEvent {
+        session_id: session.id.clone(),
+        alerts: alerts.clone(),
+    };
+    if let Err(e) = app.emit(&event_name, payload) {
+        warn!(session_id = %session.id, error = %e, "Failed to emit trigger alert");
+    }
+    Ok(alerts)
+}
+
+/// Run one sweep over all sessions that have thesis triggers.
+async fn sweep(app: &AppHandle) {
+    let sessions_dir = match get_app_data_path(app) {
+        Ok(base) => base.join("sessions"),
+        Err(e) => {
+            warn!(error = %e, "Trigger sweep: no app data dir");
+            return;
+        }
+    };
+    let sessions = match list_sessions_from_dir(&sessions_dir) {
+        Ok(s) => s,
+        Err(e) => {
+            warn!(error = %e, "Trigger sweep: failed to list sessions");
+            return;
+        }
+    };
+
+    for session in sessions.iter().filter(|s| !thesis_triggers(s).is_empty()) {
+        if let Err(e) = check_session_triggers(app, session).await {
+            warn!(session_id = %session.id, error = %e, "Trigger check failed");
+        }
+    }
+}
+
+/// Spawn the periodic trigger-matching job.
+pub fn start_trigger_matcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(INITIAL_DELAY).await;
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            debug!("Running revision trigger sweep");
+            sweep(&app).await;
+        }
+    });
+}
+
+// ============ TAURI COMMANDS ============
+
+/// Check a session's triggers now instead of waiting for the next sweep.
+#[tauri::command]
+pub async fn check_trigger_alerts(app: AppHandle, session_id: String) -> Result<Vec<TriggerAlert>, SessionError> {
+    let session_path = get_session_json_path(&app, &session_id)?;
+    if !session_path.exists() {
+        return Err(SessionError::NotFound(session_id));
+    }
+    let content = read_recovered(&session_path)?;
+    let session: Session = serde_json::from_str(&content)?;
+    check_session_triggers(&app, &session).await
+}
+
+#[tauri::command]
+pub fn acknowledge_trigger_alert(app: AppHandle, session_id: String, alert_id: String) -> Result<Session, SessionError> {
+    let session_path = get_session_json_path(&app, &session_id)?;
+    if !session_path.exists() {
+        return Err(SessionError::NotFound(session_id));
+    }
+    update_session_file(&session_path, |session| {
+        let alert = session
+            .trigger_alerts
+            .iter_mut()
+            .find(|a| a.id == alert_id)
+            .ok_or_else(|| SessionError::NotFound(alert_id.clone()))?;
+        alert.acknowledged = true;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use serde_json::json;
+
+    fn make_session() -> Session {
+        let now = Utc::now();
+        test_session(json!({
+            "id": "s1",
+            "title": "Test thesis",
+            "status": "formed",
+            "created": now,
+            "updated": now,
+        }))
+    }
+
+    fn make_hit(id: &str, relevance: f32, metadata: Value) -> SearchHit {
+        SearchHit {
+            id: id.to_string(),
+            collection: COLLECTION_WEB_SOURCES.to_string(),
+            document: "Rates were cut by 50bp".to_string(),
+            metadata,
+            distance: 1.0 / relevance - 1.0,
+            relevance,
+            snippet: None,
+        }
+    }
+
+    #[test]
+    fn test_content_timestamp_formats() {
+        let ts = content_timestamp(&json!({ "indexed_at": 1_700_000_000_i64 })).unwrap();
+        assert_eq!(ts.timestamp(), 1_700_000_000);
+
+        let ts = content_timestamp(&json!({ "modified": "2024-01-02T03:04:05Z" })).unwrap();
+        assert_eq!(ts.to_rfc3339(), "2024-01-02T03:04:05+00:00");
+
+        assert!(content_timestamp(&json!({})).is_none());
+    }
+
+    #[test]
+    fn test_select_alerts_filters() {
+        let mut session = make_session();
+        let since = Utc::now() - chrono::Duration::days(1);
+        let fresh = Utc::now().timestamp();
+        let stale = (since - chrono::Duration::days(1)).timestamp();
+
+        let hits = vec![
+            make_hit("fresh", 0.9, json!({ "indexed_at": fresh })),
+            make_hit("stale", 0.9, json!({ "indexed_at": stale })),
+            make_hit("weak", 0.2, json!({ "indexed_at": fresh })),
+            make_hit("own", 0.9, json!({ "indexed_at": fresh, "session_id": "s1" })),
+        ];
+
+        let alerts = select_alerts(&session, "rate cuts", &hits, since);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].source_id, "fresh");
+
+        // Already-alerted hits are not repeated
+        session.trigger_alerts = alerts;
+        assert!(select_alerts(&session, "rate cuts", &hits, since).is_empty());
+    }
+}
@@ -0,0 +1,277 @@
+//! Skill Files
+//!
+//! The app ships Claude Code skill files and installs them under
+//! `skills/` in the app data dir. A manifest records the app version and
+//! the hash of each file as installed, which tells three kinds of drift
+//! apart: files missing from disk, files an older app installed that the
+//! bundle has since changed, and files edited locally. Updates never
+//! overwrite local edits unless forced.
+//!
+//! Sessions choose which installed skills their CLAUDE.md points the agent at.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::chroma::jsonl_miner::hash_url as content_hash;
+use crate::session::lock::update_session_file;
+use crate::session::{get_app_data_dir_cli, get_session_dir_cli, Session, SessionError};
+
+/// Skill files bundled with this build, relative to the skills dir
+const BUNDLED: &[(&str, &str)] = &[
+    ("dialectic/SKILL.md", include_str!("../../skills/dialectic/SKILL.md")),
+];
+
+/// Skill selected for sessions that haven't chosen any
+pub const DEFAULT_SKILL: &str = "dialectic";
+
+/// Install manifest, kept in the skills dir
+const MANIFEST_FILE: &str = ".manifest.json";
+
+#[derive(Error, Debug)]
+pub enum SkillError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error("Skill not installed: {0}")]
+    NotInstalled(String),
+}
+
+impl Serialize for SkillError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// What was installed, by which app version
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SkillManifest {
+    app_version: Option<String>,
+    /// Relative path → hash of the content as installed
+    files: BTreeMap<String, String>,
+}
+
+/// How an installed skill file compares with the bundled one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillFileState {
+    Current,
+    Missing,
+    /// Installed by an earlier version and not edited since; safe to update
+    Outdated,
+    /// Edited locally since it was installed
+    Modified,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillFileStatus {
+    pub path: String,
+    pub state: SkillFileState,
+}
+
+/// Drift report, and what a sync changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillSync {
+    pub app_version: String,
+    pub installed_version: Option<String>,
+    pub files: Vec<SkillFileStatus>,
+    /// Files written by this sync
+    pub updated: Vec<String>,
+}
+
+fn read_manifest(skills_dir: &Path) -> SkillManifest {
+    fs::read_to_string(skills_dir.join(MANIFEST_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn file_state(skills_dir: &Path, manifest: &SkillManifest, path: &str, bundled: &str) -> SkillFileState {
+    let Ok(installed) = fs::read_to_string(skills_dir.join(path)) else {
+        return SkillFileState::Missing;
+    };
+    let installed_hash = content_hash(&installed);
+    if installed_hash == content_hash(bundled) {
+        SkillFileState::Current
+    } else if manifest.files.get(path) == Some(&installed_hash) {
+        SkillFileState::Outdated
+    } else {
+        SkillFileState::Modified
+    }
+}
+
+/// Compare installed skill files with the bundle; with `apply`, install
+/// missing and outdated files (and modified ones too with `force`)
+pub fn sync_skills_in(skills_dir: &Path, apply: bool, force: bool) -> Result<SkillSync, SkillError> {
+    let mut manifest = read_manifest(skills_dir);
+    let installed_version = manifest.app_version.clone();
+    let mut files = Vec::new();
+    let mut updated = Vec::new();
+
+    for (path, content) in BUNDLED {
+        let mut state = file_state(skills_dir, &manifest, path, content);
+        let replace = match state {
+            SkillFileState::Missing | SkillFileState::Outdated => true,
+            SkillFileState::Modified => force,
+            SkillFileState::Current => false,
+        };
+        if apply && replace {
+            let target = skills_dir.join(path);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&target, content)?;
+            updated.push(path.to_string());
+            state = SkillFileState::Current;
+        }
+        if apply && state == SkillFileState::Current {
+            manifest.files.insert(path.to_string(), content_hash(content));
+        }
+        files.push(SkillFileStatus { path: path.to_string(), state });
+    }
+
+    if apply {
+        manifest.app_version = Some(env!("CARGO_PKG_VERSION").to_string());
+        fs::create_dir_all(skills_dir)?;
+        fs::write(skills_dir.join(MANIFEST_FILE), serde_json::to_string_pretty(&manifest)?)?;
+        if !updated.is_empty() {
+            info!(files = updated.len(), "Installed bundled skill files");
+        }
+    }
+
+    Ok(SkillSync {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        installed_version,
+        files,
+        updated,
+    })
+}
+
+/// Install missing and outdated skill files at startup, leaving local edits alone
+pub fn install_skills(skills_dir: &Path) {
+    match sync_skills_in(skills_dir, true, false) {
+        Ok(sync) => {
+            for file in sync.files.iter().filter(|f| f.state == SkillFileState::Modified) {
+                warn!(path = %file.path, "Skill file edited locally; not updating it");
+            }
+        }
+        Err(e) => warn!(error = %e, "Failed to install skill files"),
+    }
+}
+
+/// Installed skills: directories of the skills dir that have a SKILL.md
+pub fn installed_skills(skills_dir: &Path) -> Vec<String> {
+    let mut skills: Vec<String> = fs::read_dir(skills_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().join("SKILL.md").is_file())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    skills.sort();
+    skills
+}
+
+/// Skills dir under the app data dir
+pub fn skills_dir_cli() -> Result<PathBuf, SessionError> {
+    Ok(get_app_data_dir_cli()?.join("skills"))
+}
+
+/// SKILL.md paths of the skills a session uses
+pub fn session_skill_paths(session: &Session, skills_dir: &Path) -> Vec<(String, PathBuf)> {
+    let selected = if session.skills.is_empty() {
+        vec![DEFAULT_SKILL.to_string()]
+    } else {
+        session.skills.clone()
+    };
+    selected
+        .into_iter()
+        .map(|name| {
+            let path = skills_dir.join(&name).join("SKILL.md");
+            (name, path)
+        })
+        .filter(|(_, path)| path.is_file())
+        .collect()
+}
+
+// ============ TAURI COMMANDS ============
+
+/// Report drift between installed and bundled skill files; `apply` installs
+/// missing and outdated ones, `force` also overwrites local edits
+#[tauri::command]
+pub fn sync_skills(apply: Option<bool>, force: Option<bool>) -> Result<SkillSync, SkillError> {
+    sync_skills_in(&skills_dir_cli()?, apply.unwrap_or(false), force.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn list_skills() -> Result<Vec<String>, SkillError> {
+    Ok(installed_skills(&skills_dir_cli()?))
+}
+
+/// Choose the skills a session's CLAUDE.md points at (empty for the default)
+#[tauri::command]
+pub fn set_session_skills(session_id: String, skills: Vec<String>) -> Result<Session, SkillError> {
+    let installed = installed_skills(&skills_dir_cli()?);
+    if let Some(missing) = skills.iter().find(|s| !installed.contains(s)) {
+        return Err(SkillError::NotInstalled(missing.clone()));
+    }
+    let path = get_session_dir_cli(&session_id)?.join("session.json");
+    Ok(update_session_file(&path, |session| {
+        session.skills = skills.clone();
+        Ok(())
+    })?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_of(sync: &SkillSync) -> SkillFileState {
+        sync.files.iter().find(|f| f.path == "dialectic/SKILL.md").unwrap().state
+    }
+
+    #[test]
+    fn test_sync_detects_drift_and_keeps_local_edits() {
+        let dir = std::env::temp_dir().join(format!("dialectic_skills_{}", ulid::Ulid::new()));
+        let skill = dir.join("dialectic/SKILL.md");
+
+        let report = sync_skills_in(&dir, false, false).unwrap();
+        assert_eq!(state_of(&report), SkillFileState::Missing);
+        assert!(report.updated.is_empty() && !skill.exists());
+
+        let installed = sync_skills_in(&dir, true, false).unwrap();
+        assert_eq!(installed.updated, vec!["dialectic/SKILL.md"]);
+        assert_eq!(state_of(&installed), SkillFileState::Current);
+        assert_eq!(installed_skills(&dir), vec!["dialectic"]);
+
+        // Installed by an older bundle and untouched since: outdated
+        let mut manifest = read_manifest(&dir);
+        fs::write(&skill, "old bundle").unwrap();
+        manifest.files.insert("dialectic/SKILL.md".to_string(), content_hash("old bundle"));
+        fs::write(dir.join(MANIFEST_FILE), serde_json::to_string(&manifest).unwrap()).unwrap();
+        assert_eq!(state_of(&sync_skills_in(&dir, false, false).unwrap()), SkillFileState::Outdated);
+        assert_eq!(state_of(&sync_skills_in(&dir, true, false).unwrap()), SkillFileState::Current);
+
+        // Edited locally: reported, left alone unless forced
+        fs::write(&skill, "my notes").unwrap();
+        let kept = sync_skills_in(&dir, true, false).unwrap();
+        assert_eq!(state_of(&kept), SkillFileState::Modified);
+        assert_eq!(fs::read_to_string(&skill).unwrap(), "my notes");
+        let forced = sync_skills_in(&dir, true, true).unwrap();
+        assert_eq!(state_of(&forced), SkillFileState::Current);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
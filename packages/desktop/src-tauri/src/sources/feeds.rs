@@ -0,0 +1,480 @@
+//! RSS/Atom Feeds
+//!
+//! Feeds are registered globally or for one session and polled in the
+//! background. Items not seen before are indexed into web_sources, then
+//! the revision triggers of the sessions a feed concerns are checked right
+//! away, so news bearing on a thesis surfaces as a trigger alert instead of
+//! waiting for the next trigger sweep. The web_sources collection is shared,
+//! so later sweeps can match a session feed's items for other sessions too.
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::AppHandle;
+use thiserror::Error;
+use tracing::{debug, info, warn};
+use ulid::Ulid;
+
+use crate::chroma::jsonl_miner::{index_sources, WebSource};
+use crate::documents::web::{attribute, decode_entities, fetch_text, html_to_markdown, validate_url, WebReferenceError};
+use crate::session::trigger_alerts::check_session_triggers;
+use crate::session::{get_app_data_dir_cli, list_sessions_cli, load_session_cli, validate_session_id, SessionError};
+
+/// Feed registry, relative to the app data dir
+const FEEDS_FILE: &str = "config/feeds.json";
+/// Interval between background polls
+const POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Delay before the first poll so the sidecar can come up
+const INITIAL_DELAY: Duration = Duration::from_secs(2 * 60);
+/// Item IDs remembered per feed; older ones may be re-indexed (upserts are idempotent)
+const MAX_SEEN_ITEMS: usize = 500;
+/// `source_type` of indexed feed items
+const SOURCE_TYPE: &str = "feed";
+
+/// Serializes read-modify-write cycles on feeds.json
+static FEEDS_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Error, Debug)]
+pub enum FeedError {
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error(transparent)]
+    Web(#[from] WebReferenceError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Feed not found: {0}")]
+    NotFound(String),
+    #[error("Feed already registered: {0}")]
+    Duplicate(String),
+    #[error("Not an RSS or Atom feed: {0}")]
+    NotAFeed(String),
+}
+
+impl Serialize for FeedError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// A registered feed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Feed {
+    pub id: String,
+    pub url: String,
+    pub title: Option<String>,
+    /// `None` for feeds checked against every session
+    pub session_id: Option<String>,
+    pub added_at: DateTime<Utc>,
+    pub last_fetched: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    /// IDs of items already indexed, oldest first
+    #[serde(default)]
+    pub seen: Vec<String>,
+}
+
+/// One entry of a parsed feed
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedItem {
+    pub id: String,
+    pub title: Option<String>,
+    pub link: Option<String>,
+    pub published: Option<String>,
+    /// Item body as Markdown
+    pub content: String,
+}
+
+/// Result of a poll
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedRefresh {
+    pub feeds_polled: usize,
+    pub new_items: usize,
+    pub alerts: usize,
+    pub errors: Vec<String>,
+}
+
+// ============ PARSING ============
+
+/// `<name ...>inner</name>` elements in document order as (attributes, inner);
+/// self-closing elements have an empty inner
+fn elements<'a>(xml: &'a str, name: &str) -> Vec<(&'a str, &'a str)> {
+    let open = format!("<{}", name);
+    let close = format!("</{}>", name);
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some(i) = rest.find(&open) {
+        let after = &rest[i + open.len()..];
+        if !after.starts_with([' ', '\t', '\r', '\n', '>', '/']) {
+            rest = after;
+            continue;
+        }
+        let Some(gt) = after.find('>') else { break };
+        let tag = &after[..gt];
+        if let Some(attrs) = tag.strip_suffix('/') {
+            found.push((attrs, ""));
+            rest = &after[gt + 1..];
+            continue;
+        }
+        let body = &after[gt + 1..];
+        let Some(end) = body.find(&close) else { break };
+        found.push((tag, &body[..end]));
+        rest = &body[end + close.len()..];
+    }
+    found
+}
+
+/// Element text with CDATA unwrapped; HTML bodies become Markdown
+fn text_of(inner: &str) -> String {
+    let inner = inner.trim();
+    let text = match inner.strip_prefix("<![CDATA[").and_then(|s| s.strip_suffix("]]>")) {
+        Some(cdata) => cdata.to_string(),
+        None => decode_entities(inner),
+    };
+    if text.contains('<') {
+        html_to_markdown(&text).1
+    } else {
+        text.trim().to_string()
+    }
+}
+
+/// Text of the first non-empty `name` child
+fn child_text(xml: &str, names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .flat_map(|name| elements(xml, name))
+        .map(|(_, inner)| text_of(inner))
+        .find(|t| !t.is_empty())
+}
+
+/// RSS `<link>url</link>` or Atom `<link rel="alternate" href="url"/>`
+fn item_link(xml: &str) -> Option<String> {
+    elements(xml, "link").into_iter().find_map(|(attrs, inner)| {
+        let text = text_of(inner);
+        if !text.is_empty() {
+            return Some(text);
+        }
+        let rel = attribute(attrs, "rel").unwrap_or("alternate");
+        (rel == "alternate").then(|| attribute(attrs, "href").map(decode_entities)).flatten()
+    })
+}
+
+/// Parse an RSS 2.0 or Atom document into its title and items
+pub fn parse_feed(xml: &str) -> Option<(Option<String>, Vec<FeedItem>)> {
+    let (tag, raw_items) = match elements(xml, "item") {
+        items if !items.is_empty() => ("<item", items),
+        _ => ("<entry", elements(xml, "entry")),
+    };
+    if raw_items.is_empty() && !xml.contains("<rss") && !xml.contains("<feed") {
+        return None;
+    }
+    let header = xml.split(tag).next().unwrap_or_default();
+    let title = child_text(header, &["title"]);
+
+    let items = raw_items
+        .into_iter()
+        .filter_map(|(_, item)| {
+            let title = child_text(item, &["title"]);
+            let link = item_link(item);
+            let content = child_text(item, &["content:encoded", "content", "description", "summary"])
+                .unwrap_or_default();
+            let id = child_text(item, &["guid", "id"])
+                .or_else(|| link.clone())
+                .or_else(|| title.clone())?;
+            Some(FeedItem {
+                id,
+                title,
+                link,
+                published: child_text(item, &["pubDate", "published", "updated"]),
+                content,
+            })
+        })
+        .collect();
+    Some((title, items))
+}
+
+// ============ REGISTRY ============
+
+fn feeds_path(app_data: &Path) -> PathBuf {
+    app_data.join(FEEDS_FILE)
+}
+
+fn read_feeds(app_data: &Path) -> Result<Vec<Feed>, FeedError> {
+    let path = feeds_path(app_data);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn write_feeds(app_data: &Path, feeds: &[Feed]) -> Result<(), FeedError> {
+    let path = feeds_path(app_data);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(feeds)?)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// Registered feeds; with a session ID, that session's feeds plus global ones
+pub fn list_feeds(app_data: &Path, session_id: Option<&str>) -> Result<Vec<Feed>, FeedError> {
+    let feeds = read_feeds(app_data)?;
+    Ok(match session_id {
+        Some(id) => feeds
+            .into_iter()
+            .filter(|f| f.session_id.as_deref().is_none_or(|s| s == id))
+            .collect(),
+        None => feeds,
+    })
+}
+
+pub fn add_feed(app_data: &Path, url: &str, session_id: Option<String>) -> Result<Feed, FeedError> {
+    let url = validate_url(url)?.to_string();
+    if let Some(id) = &session_id {
+        validate_session_id(id)?;
+    }
+    let _guard = FEEDS_LOCK.lock();
+    let mut feeds = read_feeds(app_data)?;
+    if feeds.iter().any(|f| f.url == url && f.session_id == session_id) {
+        return Err(FeedError::Duplicate(url));
+    }
+    let feed = Feed {
+        id: Ulid::new().to_string(),
+        url,
+        title: None,
+        session_id,
+        added_at: Utc::now(),
+        last_fetched: None,
+        last_error: None,
+        seen: Vec::new(),
+    };
+    feeds.push(feed.clone());
+    write_feeds(app_data, &feeds)?;
+    info!(feed_id = %feed.id, url = %feed.url, "Registered feed");
+    Ok(feed)
+}
+
+pub fn remove_feed(app_data: &Path, feed_id: &str) -> Result<(), FeedError> {
+    let _guard = FEEDS_LOCK.lock();
+    let mut feeds = read_feeds(app_data)?;
+    let before = feeds.len();
+    feeds.retain(|f| f.id != feed_id);
+    if feeds.len() == before {
+        return Err(FeedError::NotFound(feed_id.to_string()));
+    }
+    write_feeds(app_data, &feeds)
+}
+
+/// Apply a poll outcome to the stored feed, re-reading the registry so
+/// concurrent edits aren't lost
+fn record_poll(app_data: &Path, feed_id: &str, f: impl FnOnce(&mut Feed)) -> Result<(), FeedError> {
+    let _guard = FEEDS_LOCK.lock();
+    let mut feeds = read_feeds(app_data)?;
+    if let Some(feed) = feeds.iter_mut().find(|f| f.id == feed_id) {
+        f(feed);
+        feed.last_fetched = Some(Utc::now());
+        write_feeds(app_data, &feeds)?;
+    }
+    Ok(())
+}
+
+// ============ POLLING ============
+
+/// Fetch a feed and return its title and the items not seen before
+async fn fetch_new_items(feed: &Feed) -> Result<(Option<String>, Vec<FeedItem>), FeedError> {
+    let fetched = fetch_text(&feed.url).await?;
+    let (title, items) = parse_feed(&fetched.body).ok_or_else(|| FeedError::NotAFeed(feed.url.clone()))?;
+    let new = items.into_iter().filter(|item| !feed.seen.contains(&item.id)).collect();
+    Ok((title, new))
+}
+
+fn web_source(item: &FeedItem) -> WebSource {
+    let content = match &item.title {
+        Some(title) => format!("{}\n\n{}", title, item.content),
+        None => item.content.clone(),
+    };
+    WebSource {
+        url: item.link.clone(),
+        title: item.title.clone(),
+        query: None,
+        content,
+        source_type: SOURCE_TYPE.to_string(),
+    }
+}
+
+/// Poll feeds (all, or one session's plus global ones) and index unseen
+/// items. Returns the refresh summary and the sessions to check: `None`
+/// when a global feed had new items and every session is concerned.
+pub async fn poll_feeds(
+    app_data: &Path,
+    session_id: Option<&str>,
+) -> Result<(FeedRefresh, Option<BTreeSet<String>>), FeedError> {
+    let feeds = list_feeds(app_data, session_id)?;
+    let mut refresh = FeedRefresh::default();
+    let mut sessions = Some(BTreeSet::new());
+
+    for feed in feeds {
+        refresh.feeds_polled += 1;
+        match fetch_new_items(&feed).await {
+            Ok((title, items)) => {
+                if !items.is_empty() {
+                    let sources: Vec<WebSource> = items.iter().map(web_source).collect();
+                    index_sources(&format!("feed_{}", feed.id), &sources).await;
+                    refresh.new_items += items.len();
+                    match (&feed.session_id, sessions.as_mut()) {
+                        (Some(id), Some(set)) => {
+                            set.insert(id.clone());
+                        }
+                        (None, _) => sessions = None,
+                        _ => {}
+                    }
+                }
+                debug!(feed_id = %feed.id, new_items = items.len(), "Polled feed");
+                record_poll(app_data, &feed.id, |stored| {
+                    stored.title = title.or(stored.title.take());
+                    stored.last_error = None;
+                    stored.seen.extend(items.into_iter().map(|i| i.id));
+                    let excess = stored.seen.len().saturating_sub(MAX_SEEN_ITEMS);
+                    stored.seen.drain(..excess);
+                })?;
+            }
+            Err(e) => {
+                warn!(feed_id = %feed.id, url = %feed.url, error = %e, "Feed poll failed");
+                refresh.errors.push(format!("{}: {}", feed.url, e));
+                let message = e.to_string();
+                record_poll(app_data, &feed.id, |stored| stored.last_error = Some(message))?;
+            }
+        }
+    }
+    Ok((refresh, sessions))
+}
+
+/// Poll feeds, then check revision triggers of the sessions with new items
+pub async fn refresh_feeds(app: &AppHandle, session_id: Option<&str>) -> Result<FeedRefresh, FeedError> {
+    let app_data = get_app_data_dir_cli()?;
+    let (mut refresh, sessions) = poll_feeds(&app_data, session_id).await?;
+    if refresh.new_items == 0 {
+        return Ok(refresh);
+    }
+
+    let sessions = match sessions {
+        Some(ids) => ids.iter().filter_map(|id| load_session_cli(id).ok()).collect(),
+        None => list_sessions_cli()?,
+    };
+    for session in &sessions {
+        match check_session_triggers(app, session).await {
+            Ok(alerts) => refresh.alerts += alerts.len(),
+            Err(e) => warn!(session_id = %session.id, error = %e, "Trigger check after feed poll failed"),
+        }
+    }
+    info!(new_items = refresh.new_items, alerts = refresh.alerts, "Refreshed feeds");
+    Ok(refresh)
+}
+
+/// Spawn the periodic feed poller.
+pub fn start_feed_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(INITIAL_DELAY).await;
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            debug!("Polling feeds");
+            if let Err(e) = refresh_feeds(&app, None).await {
+                warn!(error = %e, "Feed poll failed");
+            }
+        }
+    });
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn feeds_list(session_id: Option<String>) -> Result<Vec<Feed>, FeedError> {
+    list_feeds(&get_app_data_dir_cli()?, session_id.as_deref())
+}
+
+/// Register a feed for one session, or for all sessions when `session_id` is omitted
+#[tauri::command]
+pub fn feeds_add(url: String, session_id: Option<String>) -> Result<Feed, FeedError> {
+    add_feed(&get_app_data_dir_cli()?, &url, session_id)
+}
+
+#[tauri::command]
+pub fn feeds_remove(feed_id: String) -> Result<(), FeedError> {
+    remove_feed(&get_app_data_dir_cli()?, &feed_id)
+}
+
+/// Poll now instead of waiting for the next background poll
+#[tauri::command]
+pub async fn feeds_refresh(app: AppHandle, session_id: Option<String>) -> Result<FeedRefresh, FeedError> {
+    refresh_feeds(&app, session_id.as_deref()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rss() {
+        let xml = r#"<?xml version="1.0"?><rss version="2.0"><channel>
+            <title>Rates &amp; Markets</title><link>https://example.com</link>
+            <item><title>Fed cuts rates</title><link>https://example.com/cut</link>
+              <guid isPermaLink="false">item-1</guid><pubDate>Tue, 01 Oct 2024 12:00:00 GMT</pubDate>
+              <description><![CDATA[<p>The Fed cut by <b>50bp</b>.</p>]]></description></item>
+            <item><title>No guid</title><link>https://example.com/2</link>
+              <description>Plain &lt;em&gt;escaped&lt;/em&gt; text</description></item>
+            </channel></rss>"#;
+        let (title, items) = parse_feed(xml).unwrap();
+        assert_eq!(title.as_deref(), Some("Rates & Markets"));
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].id, "item-1");
+        assert_eq!(items[0].link.as_deref(), Some("https://example.com/cut"));
+        assert_eq!(items[0].content, "The Fed cut by 50bp.");
+        assert_eq!(items[1].id, "https://example.com/2");
+        assert_eq!(items[1].content, "Plain escaped text");
+    }
+
+    #[test]
+    fn test_parse_atom() {
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>Blog</title>
+            <entry><title>Post</title><id>tag:blog,2024:1</id>
+              <link rel="self" href="https://blog.example/self"/>
+              <link href="https://blog.example/post"/>
+              <updated>2024-10-01T00:00:00Z</updated><summary>Short</summary></entry></feed>"#;
+        let (title, items) = parse_feed(xml).unwrap();
+        assert_eq!(title.as_deref(), Some("Blog"));
+        assert_eq!(items[0].id, "tag:blog,2024:1");
+        assert_eq!(items[0].link.as_deref(), Some("https://blog.example/post"));
+        assert_eq!(items[0].published.as_deref(), Some("2024-10-01T00:00:00Z"));
+        assert_eq!(items[0].content, "Short");
+        assert!(parse_feed("<html><body>not a feed</body></html>").is_none());
+    }
+
+    #[test]
+    fn test_registry() {
+        let dir = std::env::temp_dir().join(format!("dialectic-feeds-{}", Ulid::new()));
+        let global = add_feed(&dir, "https://example.com/rss", None).unwrap();
+        let scoped = add_feed(&dir, "https://example.com/rss", Some("s1".to_string())).unwrap();
+        assert!(matches!(add_feed(&dir, "https://example.com/rss", None), Err(FeedError::Duplicate(_))));
+        assert!(add_feed(&dir, "ftp://example.com/rss", None).is_err());
+
+        assert_eq!(list_feeds(&dir, Some("s1")).unwrap().len(), 2);
+        assert_eq!(list_feeds(&dir, Some("s2")).unwrap().len(), 1);
+
+        remove_feed(&dir, &scoped.id).unwrap();
+        assert!(matches!(remove_feed(&dir, &scoped.id), Err(FeedError::NotFound(_))));
+        assert_eq!(list_feeds(&dir, None).unwrap()[0].id, global.id);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
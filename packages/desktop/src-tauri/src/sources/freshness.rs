@@ -0,0 +1,302 @@
+//! Web Source Freshness
+//!
+//! Pages mined into web_sources are a snapshot of when a session fetched
+//! them. `refresh_web_source` re-fetches the URL, compares the page with
+//! the stored chunks, replaces every session's copy with the new content
+//! and, when the page changed materially, flags the sessions whose claims
+//! cite it with a `SourceChange`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashSet};
+use tauri::{AppHandle, Emitter};
+use thiserror::Error;
+use tracing::{info, warn};
+use ulid::Ulid;
+
+use crate::chroma::client::{get_client, ChromaError};
+use crate::chroma::store::VectorStore;
+use crate::chroma::collections::COLLECTION_WEB_SOURCES;
+use crate::chroma::jsonl_miner::{hash_url, web_source_items, WebSource};
+use crate::documents::web::{fetch_page, WebReferenceError};
+use crate::session::lock::update_session_file;
+use crate::session::{get_session_dir_cli, list_sessions_cli, Session, SessionError};
+
+/// Share of word trigrams added or removed for a change to count as material
+pub const MATERIAL_CHANGE_THRESHOLD: f32 = 0.2;
+/// Words per shingle when comparing content
+const SHINGLE_WORDS: usize = 3;
+
+#[derive(Error, Debug)]
+pub enum FreshnessError {
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error(transparent)]
+    Web(#[from] WebReferenceError),
+    #[error("Chroma error: {0}")]
+    Chroma(#[from] ChromaError),
+    #[error("Web source not found: {0}")]
+    NotFound(String),
+    #[error("Web source has no URL: {0}")]
+    NoUrl(String),
+}
+
+impl Serialize for FreshnessError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// A cited web source that changed materially since it was indexed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceChange {
+    pub id: String,
+    pub url: String,
+    pub title: Option<String>,
+    /// 0.0 (identical) – 1.0 (nothing in common)
+    pub change_ratio: f32,
+    /// Claims in the session that cite the source
+    pub claim_ids: Vec<String>,
+    pub detected_at: DateTime<Utc>,
+    #[serde(default)]
+    pub acknowledged: bool,
+}
+
+/// Outcome of a refresh
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebSourceRefresh {
+    pub url: String,
+    pub title: Option<String>,
+    pub change_ratio: f32,
+    pub material: bool,
+    /// Sessions whose copy of the page was re-indexed
+    pub sessions_reindexed: usize,
+    /// Sessions flagged with a `SourceChange`
+    pub flagged_sessions: Vec<String>,
+}
+
+fn shingles(text: &str) -> HashSet<String> {
+    let words: Vec<String> = text
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect();
+    if words.len() < SHINGLE_WORDS {
+        return HashSet::from([words.join(" ")]);
+    }
+    words.windows(SHINGLE_WORDS).map(|w| w.join(" ")).collect()
+}
+
+/// Share of word trigrams not common to both texts (Jaccard distance).
+/// Insensitive to whitespace, case and chunk boundaries.
+pub fn change_ratio(old: &str, new: &str) -> f32 {
+    let (old, new) = (shingles(old), shingles(new));
+    let union = old.union(&new).count();
+    if union == 0 {
+        return 0.0;
+    }
+    1.0 - old.intersection(&new).count() as f32 / union as f32
+}
+
+/// Stored copies of a page: session ID → chunks in order, plus the shared metadata
+struct StoredSource {
+    url: String,
+    title: Option<String>,
+    source_type: String,
+    copies: BTreeMap<String, Vec<(i64, String)>>,
+}
+
+/// Look up a web source by record ID (or URL) and load every session's copy
+async fn load_stored(id: &str) -> Result<StoredSource, FreshnessError> {
+    let client = get_client();
+    let collection = client.get_collection(COLLECTION_WEB_SOURCES).await?;
+    let include = || Some(vec!["documents".to_string(), "metadatas".to_string()]);
+    let str_of = |meta: &Value, key: &str| meta.get(key).and_then(|v| v.as_str()).map(str::to_string);
+
+    let url = if id.starts_with("http://") || id.starts_with("https://") {
+        id.to_string()
+    } else {
+        let record = client
+            .get(&collection.id, Some(vec![id.to_string()]), None, None, Some(1), None, include())
+            .await?;
+        let meta = record
+            .metadatas
+            .and_then(|m| m.into_iter().next().flatten())
+            .ok_or_else(|| FreshnessError::NotFound(id.to_string()))?;
+        str_of(&meta, "url").ok_or_else(|| FreshnessError::NoUrl(id.to_string()))?
+    };
+
+    let records = client
+        .get(&collection.id, None, Some(json!({ "url": { "$eq": url } })), None, None, None, include())
+        .await?;
+    if records.ids.is_empty() {
+        return Err(FreshnessError::NotFound(id.to_string()));
+    }
+    let documents = records.documents.unwrap_or_default();
+    let metadatas = records.metadatas.unwrap_or_default();
+    let mut stored = StoredSource { url, title: None, source_type: "web_fetch".to_string(), copies: BTreeMap::new() };
+    for (document, meta) in documents.into_iter().zip(metadatas) {
+        let meta = meta.unwrap_or_default();
+        let Some(session_id) = str_of(&meta, "session_id") else { continue };
+        stored.title = stored.title.or_else(|| str_of(&meta, "title"));
+        if let Some(source_type) = str_of(&meta, "source_type") {
+            stored.source_type = source_type;
+        }
+        let index = meta.get("chunk_index").and_then(|v| v.as_i64()).unwrap_or(0);
+        stored.copies.entry(session_id).or_default().push((index, document.unwrap_or_default()));
+    }
+    for chunks in stored.copies.values_mut() {
+        chunks.sort_by_key(|(index, _)| *index);
+    }
+    Ok(stored)
+}
+
+/// Record a `SourceChange` on every session with claims citing `url`
+fn flag_citing_sessions(change: &SourceChange) -> Result<Vec<(String, SourceChange)>, FreshnessError> {
+    let record_marker = format!("::web::{}::", hash_url(&change.url));
+    let mut flagged = Vec::new();
+    for session in list_sessions_cli()? {
+        let claim_ids: Vec<String> = session
+            .claims
+            .iter()
+            .filter(|c| c.source_id == change.url || c.source_id.contains(&record_marker))
+            .map(|c| c.id.clone())
+            .collect();
+        if claim_ids.is_empty() {
+            continue;
+        }
+        let change = SourceChange { id: Ulid::new().to_string(), claim_ids, ..change.clone() };
+        let path = get_session_dir_cli(&session.id)?.join("session.json");
+        update_session_file(&path, |s| {
+            s.source_changes.push(change.clone());
+            Ok(())
+        })?;
+        flagged.push((session.id, change));
+    }
+    Ok(flagged)
+}
+
+/// Re-fetch a web source, re-index it for every session that has it and
+/// flag citing sessions if the page changed materially. `id` is a
+/// web_sources record ID or the page URL.
+pub async fn refresh(id: &str) -> Result<(WebSourceRefresh, Vec<(String, SourceChange)>), FreshnessError> {
+    let stored = load_stored(id).await?;
+    let page = fetch_page(&stored.url).await?;
+
+    // The most complete stored copy is the baseline
+    let baseline = stored
+        .copies
+        .values()
+        .map(|chunks| chunks.iter().map(|(_, c)| c.as_str()).collect::<Vec<_>>().join("\n"))
+        .max_by_key(|text| text.len())
+        .unwrap_or_default();
+    let ratio = change_ratio(&baseline, &page.markdown);
+    let material = ratio >= MATERIAL_CHANGE_THRESHOLD;
+    let title = page.title.clone().or(stored.title.clone());
+
+    let source = WebSource {
+        url: Some(stored.url.clone()),
+        title: title.clone(),
+        query: None,
+        content: page.markdown,
+        source_type: stored.source_type.clone(),
+    };
+    // Chunk IDs are deterministic, so the upsert overwrites the old chunks
+    // in place and a failure leaves every session's old copy intact
+    let client = get_client();
+    let collection = client.get_collection(COLLECTION_WEB_SOURCES).await?;
+    let mut chunk_count = 0;
+    for session_id in stored.copies.keys() {
+        let items = web_source_items(session_id, std::slice::from_ref(&source));
+        chunk_count = items.len();
+        let outcome = client.upsert_batched(&collection.id, &items, |_| {}).await;
+        if let Some(error) = outcome.errors.into_iter().next() {
+            return Err(ChromaError::Http(error).into());
+        }
+    }
+    // Then drop the tail a shorter page no longer covers
+    let stale_tail = json!({ "$and": [
+        { "url": { "$eq": stored.url } },
+        { "chunk_index": { "$gte": chunk_count as i64 } },
+    ] });
+    client.delete(&collection.id, None, Some(stale_tail)).await?;
+
+    let flagged = if material {
+        flag_citing_sessions(&SourceChange {
+            id: String::new(),
+            url: stored.url.clone(),
+            title: title.clone(),
+            change_ratio: ratio,
+            claim_ids: Vec::new(),
+            detected_at: Utc::now(),
+            acknowledged: false,
+        })?
+    } else {
+        Vec::new()
+    };
+    info!(url = %stored.url, change_ratio = ratio, material, flagged = flagged.len(), "Refreshed web source");
+
+    let result = WebSourceRefresh {
+        url: stored.url,
+        title,
+        change_ratio: ratio,
+        material,
+        sessions_reindexed: stored.copies.len(),
+        flagged_sessions: flagged.iter().map(|(id, _)| id.clone()).collect(),
+    };
+    Ok((result, flagged))
+}
+
+// ============ TAURI COMMANDS ============
+
+/// Re-fetch a web source now; flagged sessions get a `source-changed-{session_id}` event
+#[tauri::command]
+pub async fn refresh_web_source(app: AppHandle, id: String) -> Result<WebSourceRefresh, FreshnessError> {
+    let (result, flagged) = refresh(&id).await?;
+    for (session_id, change) in flagged {
+        if let Err(e) = app.emit(&format!("source-changed-{}", session_id), &change) {
+            warn!(session_id = %session_id, error = %e, "Failed to emit source change");
+        }
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn acknowledge_source_change(session_id: String, change_id: String) -> Result<Session, FreshnessError> {
+    let path = get_session_dir_cli(&session_id)?.join("session.json");
+    Ok(update_session_file(&path, |session| {
+        let change = session
+            .source_changes
+            .iter_mut()
+            .find(|c| c.id == change_id)
+            .ok_or_else(|| SessionError::NotFound(change_id.clone()))?;
+        change.acknowledged = true;
+        Ok(())
+    })?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_ratio() {
+        let old = "The Fed held rates at 5.25%.\n\nMarkets expect cuts next year.";
+        assert_eq!(change_ratio(old, "the fed held   rates at 5.25%. Markets expect\ncuts next year."), 0.0);
+
+        let edited = "The Fed held rates at 5.25%.\n\nMarkets expect cuts next year. Inflation is rising again.";
+        let small = change_ratio(old, edited);
+        assert!(small > 0.0 && small < 1.0);
+
+        let rewritten = "Rates were cut by fifty basis points in a surprise move.";
+        assert!(change_ratio(old, rewritten) >= MATERIAL_CHANGE_THRESHOLD);
+        assert_eq!(change_ratio(old, rewritten), 1.0);
+        assert_eq!(change_ratio("", ""), 0.0);
+    }
+}
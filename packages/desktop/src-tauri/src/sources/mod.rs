@@ -0,0 +1,6 @@
+//! External Sources
+//!
+//! Content pulled into Dialectic from outside sessions and the vault.
+
+pub mod feeds;
+pub mod freshness;
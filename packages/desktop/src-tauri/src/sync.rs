@@ -0,0 +1,387 @@
+//! Folder Sync
+//!
+//! Mirrors `sessions/` into a user-chosen folder that Dropbox, iCloud Drive
+//! or a git checkout carries between machines, so no server is needed. Each
+//! run compares every file with the hash it had at the last sync (kept in
+//! `<app data>/sync-state.json`): a file changed on one side is copied to
+//! the other, a deletion on one side is applied to the other, and a file
+//! changed on both sides goes to the newer write, with the older one kept
+//! next to it as `<name>.conflict-<timestamp>.<ext>` on both sides.
+//!
+//! Files that only make sense on one machine (CLAUDE.md, hook settings and
+//! the env file, which hold absolute paths) and in-flight files (temp,
+//! lock and journal files, dotfiles) are not synced. A side that looks
+//! wiped while the last sync saw files, e.g. an unmounted folder, stops the
+//! run instead of deleting everything on the other side.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::config::preferences::load_preferences;
+use crate::documents::registry::content_hash;
+use crate::session::env_file::ENV_FILE;
+use crate::session::journal::journaled_write;
+use crate::session::lock::lock_session_file;
+use crate::session::{get_app_data_dir_cli, SessionError, HOOK_SETTINGS_FILE};
+
+pub const SYNC_STATE_FILE: &str = "sync-state.json";
+const SESSIONS_DIR: &str = "sessions";
+/// Regenerated per machine with absolute paths
+const MACHINE_LOCAL_FILES: &[&str] = &["CLAUDE.md", HOOK_SETTINGS_FILE, ENV_FILE];
+const IN_FLIGHT_SUFFIXES: &[&str] = &[".tmp", ".lock", ".journal"];
+
+#[derive(Error, Debug)]
+pub enum SyncError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error("No sync folder configured")]
+    NotConfigured,
+    #[error("Sync folder unavailable: {0}")]
+    Unavailable(String),
+}
+
+impl Serialize for SyncError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Hashes of every synced file as of the last run, by relative path
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncState {
+    /// The folder the hashes were taken against; a new folder starts over
+    pub folder: String,
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_synced: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncSide {
+    Local,
+    Remote,
+}
+
+/// A file changed on both sides since the last sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflict {
+    pub path: String,
+    /// Side whose (newer) version was kept
+    pub kept: SyncSide,
+    /// Where the other version was saved, relative like `path`
+    pub conflict_copy: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    pub folder: String,
+    pub pushed: Vec<String>,
+    pub pulled: Vec<String>,
+    pub deleted_local: Vec<String>,
+    pub deleted_remote: Vec<String>,
+    pub conflicts: Vec<SyncConflict>,
+    pub unchanged: usize,
+    pub synced_at: Option<DateTime<Utc>>,
+}
+
+fn is_synced(name: &str) -> bool {
+    !name.starts_with('.')
+        && !MACHINE_LOCAL_FILES.contains(&name)
+        && !IN_FLIGHT_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+}
+
+fn collect_files(root: &Path, dir: &Path, files: &mut BTreeMap<String, PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !is_synced(&name) {
+            continue;
+        }
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let key = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+            files.insert(key, path);
+        }
+    }
+    Ok(())
+}
+
+/// Hash of every synced file under `root`, by relative path
+fn snapshot(root: &Path) -> Result<BTreeMap<String, String>, SyncError> {
+    let mut files = BTreeMap::new();
+    if root.is_dir() {
+        collect_files(root, root, &mut files)?;
+    }
+    let mut hashes = BTreeMap::new();
+    for (key, path) in files {
+        hashes.insert(key, content_hash(&path)?);
+    }
+    Ok(hashes)
+}
+
+fn modified(path: &Path) -> Option<DateTime<Utc>> {
+    fs::metadata(path).and_then(|m| m.modified()).ok().map(DateTime::<Utc>::from)
+}
+
+/// `session.conflict-20261015T093000Z.json`
+fn conflict_name(key: &str, at: DateTime<Utc>) -> String {
+    let stamp = at.format("%Y%m%dT%H%M%SZ");
+    let (dir, name) = key.rsplit_once('/').map_or(("", key), |(d, n)| (d, n));
+    let renamed = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{}.conflict-{}.{}", stem, stamp, ext),
+        _ => format!("{}.conflict-{}", name, stamp),
+    };
+    if dir.is_empty() { renamed } else { format!("{}/{}", dir, renamed) }
+}
+
+/// Copy `from` over `to`; session.json goes through its lock and journal
+/// so a running writer never sees a torn file
+fn copy_file(from: &Path, to: &Path) -> Result<(), SyncError> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if to.file_name().is_some_and(|n| n == "session.json") {
+        let contents = fs::read_to_string(from)?;
+        let _lock = lock_session_file(to)?;
+        journaled_write(to, &contents)?;
+    } else {
+        let tmp = to.with_file_name(format!(".{}.sync.tmp", to.file_name().unwrap_or_default().to_string_lossy()));
+        fs::copy(from, &tmp)?;
+        fs::rename(&tmp, to)?;
+    }
+    Ok(())
+}
+
+fn remove_file(root: &Path, path: &Path) -> Result<(), SyncError> {
+    match fs::remove_file(path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    // Drop directories the delete left empty, up to the root
+    let mut dir = path.parent();
+    while let Some(d) = dir.filter(|d| *d != root && d.starts_with(root)) {
+        if fs::remove_dir(d).is_err() {
+            break;
+        }
+        dir = d.parent();
+    }
+    Ok(())
+}
+
+fn side_path(root: &Path, key: &str) -> PathBuf {
+    key.split('/').fold(root.to_path_buf(), |path, part| path.join(part))
+}
+
+/// Two-way sync of `local` and `remote` against `state`, which is updated
+/// to the synced hashes
+pub fn sync_dirs(local: &Path, remote: &Path, state: &mut SyncState) -> Result<SyncReport, SyncError> {
+    let local_files = snapshot(local)?;
+    let remote_files = snapshot(remote)?;
+    if !state.files.is_empty() {
+        for (side, files) in [("local sessions", &local_files), ("sync folder", &remote_files)] {
+            if files.is_empty() {
+                return Err(SyncError::Unavailable(format!(
+                    "{} has no files but {} were synced last time",
+                    side,
+                    state.files.len()
+                )));
+            }
+        }
+    }
+
+    let mut report = SyncReport::default();
+    let mut synced = BTreeMap::new();
+    let keys: BTreeSet<&String> = local_files.keys().chain(remote_files.keys()).chain(state.files.keys()).collect();
+    for key in keys {
+        let (l, r, base) = (local_files.get(key), remote_files.get(key), state.files.get(key));
+        let (local_path, remote_path) = (side_path(local, key), side_path(remote, key));
+
+        if l == r {
+            if let Some(hash) = l {
+                synced.insert(key.clone(), hash.clone());
+                report.unchanged += 1;
+            }
+            continue;
+        }
+
+        let local_changed = l != base;
+        let remote_changed = r != base;
+        match (l, r) {
+            // Changed on one side only (an edit also beats a delete)
+            (Some(hash), _) if !remote_changed || (r.is_none() && local_changed) => {
+                copy_file(&local_path, &remote_path)?;
+                synced.insert(key.clone(), hash.clone());
+                report.pushed.push(key.clone());
+            }
+            (_, Some(hash)) if !local_changed || (l.is_none() && remote_changed) => {
+                copy_file(&remote_path, &local_path)?;
+                synced.insert(key.clone(), hash.clone());
+                report.pulled.push(key.clone());
+            }
+            // Deleted on one side, untouched on the other
+            (None, Some(_)) => {
+                remove_file(remote, &remote_path)?;
+                report.deleted_remote.push(key.clone());
+            }
+            (Some(_), None) => {
+                remove_file(local, &local_path)?;
+                report.deleted_local.push(key.clone());
+            }
+            (Some(lh), Some(rh)) => {
+                let (local_at, remote_at) = (modified(&local_path), modified(&remote_path));
+                let kept = if remote_at > local_at { SyncSide::Remote } else { SyncSide::Local };
+                let (winner, winner_hash, loser, loser_at) = match kept {
+                    SyncSide::Local => (&local_path, lh, &remote_path, remote_at),
+                    SyncSide::Remote => (&remote_path, rh, &local_path, local_at),
+                };
+                let copy_key = conflict_name(key, loser_at.unwrap_or_else(Utc::now));
+                let copy_hash = content_hash(loser)?;
+                copy_file(loser, &side_path(local, &copy_key))?;
+                copy_file(loser, &side_path(remote, &copy_key))?;
+                match kept {
+                    SyncSide::Local => copy_file(winner, &remote_path)?,
+                    SyncSide::Remote => copy_file(winner, &local_path)?,
+                }
+                warn!(path = %key, kept = ?kept, conflict_copy = %copy_key, "Sync conflict");
+                synced.insert(key.clone(), winner_hash.clone());
+                synced.insert(copy_key.clone(), copy_hash);
+                report.conflicts.push(SyncConflict { path: key.clone(), kept, conflict_copy: copy_key });
+            }
+            (None, None) => {}
+        }
+    }
+
+    state.files = synced;
+    state.last_synced = Some(Utc::now());
+    report.synced_at = state.last_synced;
+    Ok(report)
+}
+
+fn load_state(path: &Path, folder: &str) -> SyncState {
+    let state: Option<SyncState> = fs::read_to_string(path).ok().and_then(|c| serde_json::from_str(&c).ok());
+    match state {
+        Some(state) if state.folder == folder => state,
+        _ => SyncState { folder: folder.to_string(), ..Default::default() },
+    }
+}
+
+fn save_state(path: &Path, state: &SyncState) -> Result<(), SyncError> {
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(state)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Sync this profile's sessions with the configured `syncFolder`
+pub fn sync_sessions() -> Result<SyncReport, SyncError> {
+    let folder = load_preferences().sync_folder.ok_or(SyncError::NotConfigured)?;
+    if !Path::new(&folder).is_dir() {
+        return Err(SyncError::Unavailable(folder));
+    }
+    let app_data = get_app_data_dir_cli()?;
+    let state_path = app_data.join(SYNC_STATE_FILE);
+    let mut state = load_state(&state_path, &folder);
+
+    let mut report = sync_dirs(&app_data.join(SESSIONS_DIR), &Path::new(&folder).join(SESSIONS_DIR), &mut state)?;
+    save_state(&state_path, &state)?;
+    report.folder = folder;
+    info!(
+        pushed = report.pushed.len(),
+        pulled = report.pulled.len(),
+        conflicts = report.conflicts.len(),
+        "Synced sessions"
+    );
+    Ok(report)
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub async fn sync_now() -> Result<SyncReport, SyncError> {
+    tauri::async_runtime::spawn_blocking(sync_sessions)
+        .await
+        .map_err(|e| SyncError::Io(std::io::Error::other(e.to_string())))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, key: &str, content: &str) {
+        let path = side_path(root, key);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn read(root: &Path, key: &str) -> Option<String> {
+        fs::read_to_string(side_path(root, key)).ok()
+    }
+
+    #[test]
+    fn test_sync_dirs_two_way_with_conflicts() {
+        let base = std::env::temp_dir().join(format!("dialectic_sync_{}", ulid::Ulid::new()));
+        let (local, remote) = (base.join("local"), base.join("remote"));
+        write(&local, "sess_a/session.json", "{\"v\":1}");
+        write(&local, "sess_a/scratchpad.md", "notes");
+        write(&local, "sess_a/CLAUDE.md", "machine-local");
+        write(&local, "sess_a/session.json.journal", "in flight");
+        write(&remote, "sess_b/session.json", "{\"b\":1}");
+
+        let mut state = SyncState::default();
+        let report = sync_dirs(&local, &remote, &mut state).unwrap();
+        assert_eq!(report.pushed, vec!["sess_a/scratchpad.md", "sess_a/session.json"]);
+        assert_eq!(report.pulled, vec!["sess_b/session.json"]);
+        assert!(read(&remote, "sess_a/CLAUDE.md").is_none());
+        assert!(read(&remote, "sess_a/session.json.journal").is_none());
+        assert_eq!(state.files.len(), 3);
+
+        // One-sided edit and delete propagate
+        write(&remote, "sess_a/scratchpad.md", "edited elsewhere");
+        fs::remove_file(side_path(&local, "sess_b/session.json")).unwrap();
+        let report = sync_dirs(&local, &remote, &mut state).unwrap();
+        assert_eq!(report.pulled, vec!["sess_a/scratchpad.md"]);
+        assert_eq!(report.deleted_remote, vec!["sess_b/session.json"]);
+        assert_eq!(read(&local, "sess_a/scratchpad.md").as_deref(), Some("edited elsewhere"));
+        assert!(!remote.join("sess_b").exists());
+
+        // Both sides edited: the newer write wins, the other is kept as a copy
+        write(&local, "sess_a/session.json", "{\"v\":\"local\"}");
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        write(&remote, "sess_a/session.json", "{\"v\":\"remote\"}");
+        let report = sync_dirs(&local, &remote, &mut state).unwrap();
+        assert_eq!(report.conflicts.len(), 1);
+        let conflict = &report.conflicts[0];
+        assert_eq!(conflict.kept, SyncSide::Remote);
+        assert!(conflict.conflict_copy.starts_with("sess_a/session.conflict-") && conflict.conflict_copy.ends_with(".json"));
+        assert_eq!(read(&local, "sess_a/session.json").as_deref(), Some("{\"v\":\"remote\"}"));
+        assert_eq!(read(&remote, &conflict.conflict_copy).as_deref(), Some("{\"v\":\"local\"}"));
+        assert_eq!(read(&local, &conflict.conflict_copy).as_deref(), Some("{\"v\":\"local\"}"));
+
+        // An emptied side stops the run rather than deleting the other
+        fs::remove_dir_all(&remote).unwrap();
+        assert!(matches!(sync_dirs(&local, &remote, &mut state), Err(SyncError::Unavailable(_))));
+        assert!(read(&local, "sess_a/session.json").is_some());
+
+        fs::remove_dir_all(&base).ok();
+    }
+}
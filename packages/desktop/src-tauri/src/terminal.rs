@@ -9,6 +9,10 @@ use tauri::{AppHandle, Emitter};
 use thiserror::Error;
 use tracing::{info, warn, debug, trace};
 
+use crate::session::time_tracking::{self, ActivitySignal, ActivityState};
+
+pub mod markers;
+
 #[derive(Error, Debug)]
 pub enum TerminalError {
     #[error("PTY error: {0}")]
@@ -131,6 +135,13 @@ fn find_utf8_boundary(data: &[u8]) -> (usize, usize) {
     }
 }
 
+/// Count the terminal's lifetime as active time on its session
+fn record_terminal_activity(session_id: &str, state: ActivityState) {
+    if let Ok(session_dir) = crate::session::get_session_dir_cli(session_id) {
+        time_tracking::record(&session_dir, ActivitySignal::Terminal, state);
+    }
+}
+
 #[tauri::command]
 pub fn spawn_terminal(app: AppHandle, config: TerminalConfig) -> Result<TerminalState, TerminalError> {
     let mut manager = TERMINAL_MANAGER.lock();
@@ -224,6 +235,7 @@ pub fn spawn_terminal(app: AppHandle, config: TerminalConfig) -> Result<Terminal
     }));
 
     manager.terminals.insert(session_id.clone(), handle.clone());
+    record_terminal_activity(&session_id, ActivityState::Start);
 
     // Spawn reader thread to emit output events
     let app_clone = app.clone();
@@ -241,6 +253,7 @@ pub fn spawn_terminal(app: AppHandle, config: TerminalConfig) -> Result<Terminal
 
         let mut buf = [0u8; 65536];
         let mut leftover: Vec<u8> = Vec::new();
+        let mut scanner = markers::OutputScanner::default();
         loop {
             // If there are leftover bytes from a previous read, place them at the
             // start of the buffer so they get prepended to the next chunk.
@@ -268,6 +281,14 @@ pub fn spawn_terminal(app: AppHandle, config: TerminalConfig) -> Result<Terminal
                         let data = unsafe { std::str::from_utf8_unchecked(&buf[..valid_end]) };
                         let event_name = format!("terminal-output-{}", session_id_clone);
                         let _ = app_clone.emit(&event_name, data);
+
+                        // Claims and directives written by the agent update the session live
+                        let directives = scanner.feed(data);
+                        if !directives.is_empty() {
+                            if let Err(e) = markers::apply_directives(&session_id_clone, directives) {
+                                warn!(session_id = %session_id_clone, error = %e, "Failed to apply terminal directives");
+                            }
+                        }
                     }
                     if carry > 0 {
                         leftover.extend_from_slice(&buf[valid_end..total]);
@@ -283,6 +304,8 @@ pub fn spawn_terminal(app: AppHandle, config: TerminalConfig) -> Result<Terminal
         let _ = app_clone.emit(&event_name, ());
 
         // Clean up
+        crate::session::tailer::stop(&session_id_clone);
+        record_terminal_activity(&session_id_clone, ActivityState::Stop);
         let mut manager = TERMINAL_MANAGER.lock();
         manager.terminals.remove(&session_id_clone);
     });
@@ -0,0 +1,133 @@
+//! Terminal Output Markers
+//!
+//! Scans PTY output for claims as the agent writes them, so the session
+//! updates in real time rather than after the transcript is mined.
+//! Recognized lines are semantic markers and `@@` directives (see
+//! `session::markers`).
+//!
+//! The terminal redraws lines freely, so each distinct line is acted on once.
+
+use std::collections::HashSet;
+use tracing::debug;
+
+use crate::session::lock::update_session_file;
+use crate::session::markers::{apply_directive, parse_line, Directive};
+use crate::session::{get_session_dir_cli, SessionError};
+
+/// Source ID of claims picked up from terminal output
+const SOURCE_ID: &str = "terminal";
+/// Longest partial line kept between reads
+const MAX_PENDING: usize = 16 * 1024;
+
+/// Remove ANSI escape sequences. Cursor-forward moves become a space,
+/// since TUIs use them in place of runs of spaces.
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters, then a final byte in @..~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        if c == 'C' {
+                            out.push(' ');
+                        }
+                        break;
+                    }
+                }
+            }
+            // OSC: terminated by BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Line assembler for one terminal's output stream
+#[derive(Default)]
+pub struct OutputScanner {
+    pending: String,
+    seen: HashSet<String>,
+}
+
+impl OutputScanner {
+    /// Directives on lines completed by `data`, each distinct line once
+    pub fn feed(&mut self, data: &str) -> Vec<Directive> {
+        self.pending.push_str(data);
+        let Some(end) = self.pending.rfind(['\n', '\r']) else {
+            if self.pending.len() > MAX_PENDING {
+                self.pending.clear();
+            }
+            return Vec::new();
+        };
+        let complete: String = self.pending.drain(..=end).collect();
+
+        let mut directives = Vec::new();
+        for line in strip_ansi(&complete).split(['\n', '\r']) {
+            let line = line.trim();
+            if line.is_empty() || !(line.contains("@@") || line.contains('[')) {
+                continue;
+            }
+            if let Some(directive) = parse_line(line) {
+                if self.seen.insert(line.to_string()) {
+                    directives.push(directive);
+                }
+            }
+        }
+        directives
+    }
+}
+
+/// Apply directives to the session's `session.json`. Returns how many changed it.
+pub fn apply_directives(session_id: &str, directives: Vec<Directive>) -> Result<usize, SessionError> {
+    let path = get_session_dir_cli(session_id)?.join("session.json");
+    if !path.exists() {
+        return Ok(0);
+    }
+
+    let mut applied = 0;
+    update_session_file(&path, |session| {
+        for directive in directives {
+            if apply_directive(session, directive, SOURCE_ID).is_some() {
+                applied += 1;
+            }
+        }
+        Ok(())
+    })?;
+    debug!(session_id = %session_id, applied, "Applied terminal directives");
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\x1b[1m[INSIGHT]\x1b[0m\x1b[1CMoat"), "[INSIGHT] Moat");
+        assert_eq!(strip_ansi("\x1b]0;title\x07text"), "text");
+    }
+
+    #[test]
+    fn test_scanner_joins_chunks_and_skips_redraws() {
+        let mut scanner = OutputScanner::default();
+        assert!(scanner.feed("\x1b[2K[INSIGHT] Pricing ").is_empty());
+        let first = scanner.feed("power is the moat\r\n");
+        assert_eq!(first.len(), 1);
+        // A redraw of the same line is not acted on again
+        assert!(scanner.feed("\x1b[2K[INSIGHT] Pricing power is the moat\r\n").is_empty());
+        assert_eq!(scanner.feed("@@claim: Second\n").len(), 1);
+    }
+}
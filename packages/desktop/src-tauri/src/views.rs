@@ -0,0 +1,292 @@
+//! Saved Views
+//!
+//! Named session filters for the board ("Stale decisions", "Needs
+//! resolution"). Views are stored in `config/views.json` and evaluated
+//! server-side by `list_sessions_filtered`, so a board is a single call.
+//! A fresh install starts with the built-in views below.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tracing::info;
+use ulid::Ulid;
+
+use crate::session::entities::entity_tag;
+use crate::session::tags::{has_tag, normalize_tag};
+use crate::session::{get_app_data_dir_cli, list_sessions_cli, Session, SessionError, SessionMode, SessionStatus};
+
+#[derive(Error, Debug)]
+pub enum ViewError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error("View not found: {0}")]
+    NotFound(String),
+    #[error("View name cannot be empty")]
+    EmptyName,
+}
+
+impl Serialize for ViewError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Conditions a session must meet; unset fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionFilter {
+    /// Any of these statuses
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub status: Vec<SessionStatus>,
+    /// All of these tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Mentions all of these entities (by name, e.g. "Competitor X")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entities: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<SessionMode>,
+    /// Last updated before this instant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_before: Option<DateTime<Utc>>,
+    /// Last updated more than this many days ago (relative to now, so
+    /// saved views don't go stale themselves)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_days: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_unresolved_tensions: Option<bool>,
+}
+
+impl SessionFilter {
+    pub fn matches(&self, session: &Session, now: DateTime<Utc>) -> bool {
+        if !self.status.is_empty() && !self.status.contains(&session.status) {
+            return false;
+        }
+        if !self.tags.iter().all(|tag| has_tag(session, tag)) {
+            return false;
+        }
+        if !self.entities.iter().all(|entity| entity_tag(entity).is_some_and(|tag| session.tags.contains(&tag))) {
+            return false;
+        }
+        if self.mode.as_ref().is_some_and(|mode| *mode != session.mode) {
+            return false;
+        }
+        if self.updated_before.is_some_and(|before| session.updated >= before) {
+            return false;
+        }
+        if self.idle_days.is_some_and(|days| session.updated > now - Duration::days(days as i64)) {
+            return false;
+        }
+        if let Some(wanted) = self.has_unresolved_tensions {
+            if session.tensions.iter().any(|t| t.resolution.is_none()) != wanted {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// A named filter shown as a board
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedView {
+    pub id: String,
+    pub name: String,
+    pub filter: SessionFilter,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Views a fresh install starts with
+fn builtin_views() -> Vec<SavedView> {
+    let view = |id: &str, name: &str, filter: SessionFilter| SavedView {
+        id: id.to_string(),
+        name: name.to_string(),
+        filter,
+        created_at: Utc::now(),
+    };
+    vec![
+        view("stale-decisions", "Stale decisions", SessionFilter {
+            status: vec![SessionStatus::Exploring, SessionStatus::Tensions, SessionStatus::Synthesizing],
+            mode: Some(SessionMode::Decision),
+            idle_days: Some(14),
+            ..Default::default()
+        }),
+        view("needs-resolution", "Needs resolution", SessionFilter {
+            has_unresolved_tensions: Some(true),
+            ..Default::default()
+        }),
+    ]
+}
+
+fn views_path() -> Result<PathBuf, ViewError> {
+    Ok(get_app_data_dir_cli()?.join("config/views.json"))
+}
+
+/// Saved views at `path`, or the built-ins if none were ever saved
+pub fn load_views_from(path: &Path) -> Result<Vec<SavedView>, ViewError> {
+    if !path.exists() {
+        return Ok(builtin_views());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn write_views(path: &Path, views: &[SavedView]) -> Result<(), ViewError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(views)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Save a view, replacing one with the same ID or name
+pub fn save_view_in(path: &Path, name: &str, filter: SessionFilter, id: Option<String>) -> Result<SavedView, ViewError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ViewError::EmptyName);
+    }
+    let mut filter = filter;
+    filter.tags = filter.tags.iter().map(|t| normalize_tag(t)).collect::<Result<_, _>>()?;
+
+    let mut views = load_views_from(path)?;
+    let existing = views
+        .iter()
+        .position(|v| id.as_deref() == Some(v.id.as_str()) || v.name.eq_ignore_ascii_case(name));
+    let view = SavedView {
+        id: existing.map(|i| views[i].id.clone()).or(id).unwrap_or_else(|| Ulid::new().to_string()),
+        name: name.to_string(),
+        filter,
+        created_at: existing.map(|i| views[i].created_at).unwrap_or_else(Utc::now),
+    };
+    match existing {
+        Some(i) => views[i] = view.clone(),
+        None => views.push(view.clone()),
+    }
+    write_views(path, &views)?;
+    info!(view = %view.name, "Saved session view");
+    Ok(view)
+}
+
+/// Find a view by ID, or by name ignoring case
+pub fn find_view<'a>(views: &'a [SavedView], id_or_name: &str) -> Option<&'a SavedView> {
+    views
+        .iter()
+        .find(|v| v.id == id_or_name)
+        .or_else(|| views.iter().find(|v| v.name.eq_ignore_ascii_case(id_or_name)))
+}
+
+/// Sessions matching a filter, most recently updated first
+pub fn filter_sessions(sessions: Vec<Session>, filter: &SessionFilter) -> Vec<Session> {
+    let now = Utc::now();
+    let mut matched: Vec<Session> = sessions.into_iter().filter(|s| filter.matches(s, now)).collect();
+    matched.sort_by_key(|s| std::cmp::Reverse(s.updated));
+    matched
+}
+
+// ============ TAURI COMMANDS ============
+
+#[tauri::command]
+pub fn list_views() -> Result<Vec<SavedView>, ViewError> {
+    load_views_from(&views_path()?)
+}
+
+/// Create a view, or update the one with the given ID (or same name)
+#[tauri::command]
+pub fn save_view(name: String, filter: SessionFilter, id: Option<String>) -> Result<SavedView, ViewError> {
+    save_view_in(&views_path()?, &name, filter, id)
+}
+
+#[tauri::command]
+pub fn delete_view(id: String) -> Result<(), ViewError> {
+    let path = views_path()?;
+    let mut views = load_views_from(&path)?;
+    let before = views.len();
+    views.retain(|v| v.id != id);
+    if views.len() == before {
+        return Err(ViewError::NotFound(id));
+    }
+    write_views(&path, &views)
+}
+
+/// Sessions shown by a saved view (ID or name), or by an ad-hoc filter
+#[tauri::command]
+pub fn list_sessions_filtered(view: Option<String>, filter: Option<SessionFilter>) -> Result<Vec<Session>, ViewError> {
+    let filter = match (view, filter) {
+        (Some(view), _) => {
+            let views = load_views_from(&views_path()?)?;
+            find_view(&views, &view).ok_or(ViewError::NotFound(view.clone()))?.filter.clone()
+        }
+        (None, filter) => filter.unwrap_or_default(),
+    };
+    Ok(filter_sessions(list_sessions_cli()?, &filter))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_session;
+    use serde_json::json;
+
+    fn session(id: &str, mode: &str, status: &str, days_idle: i64, open_tension: bool) -> Session {
+        let updated = Utc::now() - Duration::days(days_idle);
+        test_session(json!({
+            "id": id,
+            "title": id,
+            "status": status,
+            "mode": mode,
+            "created": updated,
+            "updated": updated,
+            "tags": ["macro"],
+            "tensions": [
+                { "id": "t1", "claimAId": "c1", "claimBId": "c2", "description": "d",
+                  "resolution": if open_tension { None } else { Some("ok") }, "createdAt": updated },
+            ],
+        }))
+    }
+
+    #[test]
+    fn test_builtin_views_filter_sessions() {
+        let sessions = vec![
+            session("stale", "decision", "tensions", 30, false),
+            session("fresh", "decision", "tensions", 1, true),
+            session("formed", "decision", "formed", 30, true),
+            session("idea", "idea", "exploring", 30, false),
+        ];
+        let views = builtin_views();
+        let ids = |view: &str| -> Vec<String> {
+            let filter = &find_view(&views, view).unwrap().filter;
+            filter_sessions(sessions.clone(), filter).into_iter().map(|s| s.id).collect()
+        };
+        assert_eq!(ids("stale-decisions"), vec!["stale"]);
+        assert_eq!(ids("Needs Resolution"), vec!["fresh", "formed"]);
+
+        let tagged = SessionFilter { tags: vec!["Macro".to_string()], ..Default::default() };
+        assert_eq!(filter_sessions(sessions.clone(), &tagged).len(), 4);
+    }
+
+    #[test]
+    fn test_save_view_replaces_by_name() {
+        let path = std::env::temp_dir().join(format!("dialectic_views_{}", Ulid::new())).join("views.json");
+        assert_eq!(load_views_from(&path).unwrap().len(), builtin_views().len());
+
+        let first = save_view_in(&path, "Rates", SessionFilter::default(), None).unwrap();
+        let filter = SessionFilter { tags: vec!["Monetary Policy".to_string()], ..Default::default() };
+        let second = save_view_in(&path, "rates", filter, None).unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(second.filter.tags, vec!["monetary-policy"]);
+        assert_eq!(load_views_from(&path).unwrap().len(), builtin_views().len() + 1);
+        assert!(matches!(save_view_in(&path, "  ", SessionFilter::default(), None), Err(ViewError::EmptyName)));
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -11,6 +11,7 @@ use thiserror::Error;
 use crate::session::Session;
 use crate::context::budget::ThresholdStatus;
 use crate::chroma::memory::{extract_session_markers, index_session_artifact, MemoryType};
+use crate::jobs::{self, JobKind};
 
 #[derive(Error, Debug)]
 pub enum WatcherError {
@@ -152,6 +153,14 @@ pub fn watch_session(app: AppHandle, session_id: String) -> Result<(), WatcherEr
 
     tracing::info!(session_id = %session_id, dir = %canonical_dir.display(), "Starting session watcher");
 
+    // Baseline for auditing external edits
+    if let Some(session) = fs::read_to_string(canonical_dir.join("session.json"))
+        .ok()
+        .and_then(|c| serde_json::from_str::<Session>(&c).ok())
+    {
+        crate::session::audit::observe_external(&canonical_dir, &session);
+    }
+
     let app_clone = app.clone();
     let session_id_clone = session_id.clone();
 
@@ -182,6 +191,13 @@ pub fn watch_session(app: AppHandle, session_id: String) -> Result<(), WatcherEr
 
                             if let Ok(content) = fs::read_to_string(path) {
                                 if let Ok(session) = serde_json::from_str::<Session>(&content) {
+                                    // Log changes made outside the app (usually the agent)
+                                    if let Some(dir) = path.parent() {
+                                        crate::session::audit::observe_external(dir, &session);
+                                        crate::session::env_file::refresh(dir, &session);
+                                        crate::quick_search::note_session(&session);
+                                    }
+
                                     // Check context budget and emit alert if threshold exceeded
                                     if let Some(ref budget) = session.context_budget {
                                         let status = budget.threshold_status();
@@ -213,8 +229,20 @@ pub fn watch_session(app: AppHandle, session_id: String) -> Result<(), WatcherEr
                                     let has_thesis = session.thesis.is_some();
                                     if has_markers || has_unresolved || has_thesis {
                                         let session_for_markers = session.clone();
-                                        tauri::async_runtime::spawn(async move {
+                                        jobs::submit(JobKind::MemoryConsolidation, format!("{}:markers", session.id), |_job| async move {
                                             extract_session_markers(&session_for_markers).await;
+                                            Ok(None)
+                                        });
+                                    }
+
+                                    // Re-tag entities; skipped inside when the claims haven't changed
+                                    if !session.claims.is_empty() || has_thesis {
+                                        let entity_session_id = session.id.clone();
+                                        jobs::submit_unique(JobKind::MemoryConsolidation, format!("{}:entities", session.id), |_job| async move {
+                                            crate::session::entities::tag_session_entities(&entity_session_id, false)
+                                                .await
+                                                .map_err(|e| e.to_string())?;
+                                            Ok(None)
                                         });
                                     }
 
@@ -227,13 +255,12 @@ pub fn watch_session(app: AppHandle, session_id: String) -> Result<(), WatcherEr
                                             &app_clone,
                                         );
 
-                                        // Trigger JSONL mining if conversation_id is set
-                                        if let Some(ref conv_id) = session.conversation_id {
-                                            let sid = session.id.clone();
-                                            let cid = conv_id.clone();
-                                            let working_dir_str = session.working_dir.clone();
-                                            tauri::async_runtime::spawn(async move {
-                                                crate::chroma::jsonl_miner::mine_session_if_possible(&sid, &cid, &working_dir_str).await;
+                                        // Mine every conversation the session has spanned
+                                        if !session.conversations().is_empty() {
+                                            let session_for_mining = session.clone();
+                                            jobs::submit_unique(JobKind::JsonlMining, session.id.clone(), |_job| async move {
+                                                crate::chroma::jsonl_miner::mine_session_conversations(&session_for_mining).await;
+                                                Ok(None)
                                             });
                                         }
                                     }
@@ -246,8 +273,9 @@ pub fn watch_session(app: AppHandle, session_id: String) -> Result<(), WatcherEr
                             // Index state.json as episodic memory
                             if let Ok(content) = fs::read_to_string(path) {
                                 let sid = session_id_clone.clone();
-                                tauri::async_runtime::spawn(async move {
+                                jobs::submit(JobKind::MemoryConsolidation, format!("{}:state.json", sid), |_job| async move {
                                     index_session_artifact(&sid, "state.json", &content, MemoryType::Episodic).await;
+                                    Ok(None)
                                 });
                             }
                         }
@@ -255,8 +283,9 @@ pub fn watch_session(app: AppHandle, session_id: String) -> Result<(), WatcherEr
                             // Index scratchpad.md as episodic memory
                             if let Ok(content) = fs::read_to_string(path) {
                                 let sid = session_id_clone.clone();
-                                tauri::async_runtime::spawn(async move {
+                                jobs::submit(JobKind::MemoryConsolidation, format!("{}:scratchpad.md", sid), |_job| async move {
                                     index_session_artifact(&sid, "scratchpad.md", &content, MemoryType::Episodic).await;
+                                    Ok(None)
                                 });
                             }
                         }
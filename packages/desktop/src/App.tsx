@@ -368,7 +368,6 @@ function App() {
         await invoke('documents_add_reference', {
           sessionId: activeWindowId,
           path: loadedDocument.path,
-          persistence: 'cached',
         })
       } catch (err) {
         console.warn('Failed to index document to Chroma:', err)
@@ -509,6 +508,7 @@ function App() {
     if (isTerminalSession) {
       try {
         const ctx = await prepareLaunch(sessionId)
+        ctx.warnings?.forEach((w) => console.warn('prepare_launch:', w))
         initialCommand = ctx.claudeCommand.join(' ')
         envVars = ctx.envVars
         launchWorkingDir = ctx.workingDir
@@ -678,6 +678,7 @@ function App() {
     let launchWorkingDir = path
     try {
       const ctx = await prepareLaunch(newSession.id)
+      ctx.warnings?.forEach((w) => console.warn('prepare_launch:', w))
       initialCommand = ctx.claudeCommand.join(' ')
       envVars = ctx.envVars
       launchWorkingDir = ctx.workingDir
@@ -257,6 +257,10 @@ export interface LaunchContext {
   conversationId: string | null
   claudeCommand: string[]
   envVars: Record<string, string>
+  /** Flags left out because the CLI tool doesn't support them */
+  warnings?: string[]
+  /** Id of this launch's context manifest */
+  launchId: string
 }
 
 /**